@@ -0,0 +1,139 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+// Caches the application shell so that it loads quickly on unreliable connections. Live data still comes over the
+// websocket, so this doesn't make the application usable offline; when the page can't be loaded at all, a fallback page
+// is shown instead.
+
+const CACHE_VERSION = 1;
+const SHELL_CACHE = `stream-log-shell-v${CACHE_VERSION}`;
+const ASSET_CACHE = `stream-log-assets-v${CACHE_VERSION}`;
+const CURRENT_CACHES = [SHELL_CACHE, ASSET_CACHE];
+
+const scopeUrl = new URL(self.registration.scope);
+const OFFLINE_PAGE = new URL("offline.html", scopeUrl).href;
+const CONFIG_FILE = new URL("config.json", scopeUrl).href;
+const ICON_FILE = new URL("icon.svg", scopeUrl).href;
+
+// Paths that always need a live connection to the server
+const UNCACHED_PATHS = ["ws", "api/"].map((path) => new URL(path, scopeUrl).pathname);
+
+// Trunk adds a content hash to the names of built files (e.g. index-0123456789abcdef.css). Removing the hash gives a
+// name that stays the same between builds, which lets old versions of a file be removed from the cache.
+const ASSET_HASH_PATTERN = /-[0-9a-f]{16,}(?=[._])/;
+
+self.addEventListener("install", (event) => {
+	event.waitUntil(
+		caches
+			.open(SHELL_CACHE)
+			.then((cache) => cache.addAll([OFFLINE_PAGE, ICON_FILE]))
+			.then(() => self.skipWaiting())
+	);
+});
+
+self.addEventListener("activate", (event) => {
+	event.waitUntil(
+		caches
+			.keys()
+			.then((cacheNames) =>
+				Promise.all(
+					cacheNames
+						.filter((cacheName) => !CURRENT_CACHES.includes(cacheName))
+						.map((cacheName) => caches.delete(cacheName))
+				)
+			)
+			.then(() => self.clients.claim())
+	);
+});
+
+self.addEventListener("fetch", (event) => {
+	const request = event.request;
+	if (request.method !== "GET") {
+		return;
+	}
+	const url = new URL(request.url);
+	if (url.origin !== scopeUrl.origin || UNCACHED_PATHS.some((path) => url.pathname.startsWith(path))) {
+		return;
+	}
+
+	if (request.mode === "navigate") {
+		event.respondWith(fetch(request).catch(() => caches.match(OFFLINE_PAGE)));
+	} else if (url.href === CONFIG_FILE) {
+		event.respondWith(networkFirst(request));
+	} else if (ASSET_HASH_PATTERN.test(url.pathname)) {
+		event.respondWith(cacheFirst(request));
+	} else {
+		event.respondWith(staleWhileRevalidate(request, event));
+	}
+});
+
+// Uses the network response when it's available, keeping a copy in case the network is unavailable later
+async function networkFirst(request) {
+	const cache = await caches.open(SHELL_CACHE);
+	try {
+		const response = await fetch(request);
+		if (response.ok) {
+			await cache.put(request, response.clone());
+		}
+		return response;
+	} catch (error) {
+		const cachedResponse = await cache.match(request);
+		if (cachedResponse) {
+			return cachedResponse;
+		}
+		throw error;
+	}
+}
+
+// Uses the cached copy of a built file when there is one, fetching and caching it otherwise. Since the file name changes
+// whenever the contents do, a cached copy is never out of date.
+async function cacheFirst(request) {
+	const cache = await caches.open(ASSET_CACHE);
+	const cachedResponse = await cache.match(request);
+	if (cachedResponse) {
+		return cachedResponse;
+	}
+
+	const response = await fetch(request);
+	if (response.ok) {
+		await removeOldAssetVersions(cache, request.url);
+		await cache.put(request, response.clone());
+	}
+	return response;
+}
+
+// Uses the cached copy of a file that may change between builds when there is one, updating the cache in the background
+// for the next load
+async function staleWhileRevalidate(request, event) {
+	const cache = await caches.open(ASSET_CACHE);
+	const cachedResponse = await cache.match(request);
+	const updatedResponse = fetch(request).then(async (response) => {
+		if (response.ok) {
+			await cache.put(request, response.clone());
+		}
+		return response;
+	});
+
+	if (cachedResponse) {
+		event.waitUntil(updatedResponse.catch(() => undefined));
+		return cachedResponse;
+	}
+	return updatedResponse;
+}
+
+async function removeOldAssetVersions(cache, assetUrl) {
+	const assetName = unhashedPath(assetUrl);
+	const cachedRequests = await cache.keys();
+	await Promise.all(
+		cachedRequests
+			.filter((cachedRequest) => unhashedPath(cachedRequest.url) === assetName)
+			.map((cachedRequest) => cache.delete(cachedRequest))
+	);
+}
+
+function unhashedPath(url) {
+	return new URL(url).pathname.replace(ASSET_HASH_PATTERN, "");
+}
@@ -0,0 +1,305 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use std::collections::BTreeMap;
+use stream_log_shared::messages::entry_types::EntryType;
+use sycamore::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Event as WebEvent, KeyboardEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+/// A set of entry types listed together under a heading
+#[derive(Clone, PartialEq)]
+struct EntryTypeGroup {
+	/// The heading shown above the group, if any
+	heading: Option<String>,
+	entry_types: Vec<EntryType>,
+}
+
+#[derive(Prop)]
+pub struct EntryTypeSelectProps<'a, G: Html> {
+	/// The entry types that can be chosen
+	entry_types: &'a ReadSignal<Vec<EntryType>>,
+	/// The entry type name entered in the field
+	name: &'a Signal<String>,
+	/// A problem with the entered name to show on the field
+	error: &'a ReadSignal<Option<String>>,
+	/// The ID of the text field
+	id: String,
+	input_ref: &'a NodeRef<G>,
+}
+
+/// A text field for entering an entry type that shows the matching entry types in a list below it, organized by group.
+/// The list can be navigated with the arrow keys, and Enter chooses the highlighted entry type.
+#[component]
+pub fn EntryTypeSelect<'a, G: Html>(ctx: Scope<'a>, props: EntryTypeSelectProps<'a, G>) -> View<G> {
+	let entry_types = props.entry_types;
+	let entered_name = props.name;
+	let id = create_ref(ctx, props.id);
+
+	let list_open = create_signal(ctx, false);
+	let highlighted_id: &Signal<Option<String>> = create_signal(ctx, None);
+
+	let groups = create_memo(ctx, move || {
+		let filter = entered_name.get().trim().to_lowercase();
+		let entry_types = entry_types.get();
+
+		// Once an entry type has been fully entered, all of them are listed so that it's easy to pick a different one
+		let filter_matches_type = entry_types
+			.iter()
+			.any(|entry_type| entry_type.name.to_lowercase() == filter);
+
+		let mut types_by_group: BTreeMap<Option<String>, Vec<EntryType>> = BTreeMap::new();
+		for entry_type in entry_types.iter() {
+			if !filter_matches_type && !entry_type.name.to_lowercase().contains(&filter) {
+				continue;
+			}
+			types_by_group
+				.entry(entry_type.group_name.clone())
+				.or_default()
+				.push(entry_type.clone());
+		}
+
+		let has_named_groups = types_by_group.keys().any(|group_name| group_name.is_some());
+		let ungrouped_types = types_by_group.remove(&None);
+		let mut groups: Vec<EntryTypeGroup> = types_by_group
+			.into_iter()
+			.map(|(group_name, entry_types)| EntryTypeGroup {
+				heading: group_name,
+				entry_types,
+			})
+			.collect();
+		if let Some(entry_types) = ungrouped_types {
+			let heading = if has_named_groups {
+				Some(String::from("Other"))
+			} else {
+				None
+			};
+			groups.push(EntryTypeGroup { heading, entry_types });
+		}
+		for group in groups.iter_mut() {
+			group
+				.entry_types
+				.sort_by_key(|entry_type| entry_type.name.to_lowercase());
+		}
+		groups
+	});
+	let listed_type_ids = create_memo(ctx, move || {
+		let ids: Vec<String> = groups
+			.get()
+			.iter()
+			.flat_map(|group| group.entry_types.iter().map(|entry_type| entry_type.id.clone()))
+			.collect();
+		ids
+	});
+
+	create_effect(ctx, move || {
+		let listed_type_ids = listed_type_ids.get();
+		let highlighted_is_listed = match highlighted_id.get_untracked().as_deref() {
+			Some(highlighted_id) => listed_type_ids.iter().any(|id| id == highlighted_id),
+			None => false,
+		};
+		if !highlighted_is_listed {
+			highlighted_id.set(listed_type_ids.first().cloned());
+		}
+	});
+
+	let choose_type = move |entry_type_id: &str| {
+		let entry_types = entry_types.get();
+		if let Some(entry_type) = entry_types.iter().find(|entry_type| entry_type.id == entry_type_id) {
+			entered_name.set(entry_type.name.clone());
+		}
+		list_open.set(false);
+	};
+
+	// When the field loses focus, we fill in the rest of the name if what was entered can only mean one entry type
+	let complete_entered_name = move || {
+		let name = entered_name.get();
+		let entry_types = entry_types.get();
+		if entry_types.iter().any(|entry_type| entry_type.name == *name) {
+			return;
+		}
+
+		let lower_name = name.to_lowercase();
+		if let Some(entry_type) = entry_types
+			.iter()
+			.find(|entry_type| entry_type.name.to_lowercase() == lower_name)
+		{
+			entered_name.set(entry_type.name.clone());
+			return;
+		}
+
+		let mut prefix_matches = entry_types
+			.iter()
+			.filter(|entry_type| entry_type.name.to_lowercase().starts_with(&lower_name));
+		if let (Some(entry_type), None) = (prefix_matches.next(), prefix_matches.next()) {
+			entered_name.set(entry_type.name.clone());
+		}
+	};
+
+	let key_handler = move |event: WebEvent| {
+		let key_event: KeyboardEvent = event.unchecked_into();
+		let key = key_event.key();
+		match key.as_str() {
+			"ArrowDown" | "ArrowUp" => {
+				key_event.prevent_default();
+				if !*list_open.get() {
+					list_open.set(true);
+					return;
+				}
+
+				let listed_type_ids = listed_type_ids.get();
+				if listed_type_ids.is_empty() {
+					return;
+				}
+				let highlighted_index = highlighted_id
+					.get()
+					.as_deref()
+					.and_then(|highlighted_id| listed_type_ids.iter().position(|id| id == highlighted_id));
+				let new_index = match (key.as_str(), highlighted_index) {
+					("ArrowDown", Some(index)) => (index + 1) % listed_type_ids.len(),
+					("ArrowDown", None) => 0,
+					(_, Some(index)) if index > 0 => index - 1,
+					_ => listed_type_ids.len() - 1,
+				};
+				let new_highlighted_id = listed_type_ids[new_index].clone();
+				scroll_option_into_view(&option_element_id(id, &new_highlighted_id));
+				highlighted_id.set(Some(new_highlighted_id));
+			}
+			"Enter" => {
+				if !*list_open.get() {
+					return;
+				}
+				let highlighted = (*highlighted_id.get()).clone();
+				if let Some(highlighted) = highlighted {
+					key_event.prevent_default();
+					key_event.stop_propagation();
+					choose_type(&highlighted);
+				}
+			}
+			"Escape" => {
+				if *list_open.get() {
+					key_event.prevent_default();
+					key_event.stop_propagation();
+					list_open.set(false);
+				}
+			}
+			_ => (),
+		}
+	};
+
+	let input_handler = |_event: WebEvent| {
+		list_open.set(true);
+	};
+
+	let click_handler = |_event: WebEvent| {
+		list_open.set(true);
+	};
+
+	let blur_handler = move |_event: WebEvent| {
+		list_open.set(false);
+		complete_entered_name();
+	};
+
+	let list_id = create_ref(ctx, format!("{}_list", id));
+
+	view! {
+		ctx,
+		div(class="entry_type_select") {
+			input(
+				placeholder="Type",
+				bind:value=entered_name,
+				id=id.clone(),
+				autocomplete="off",
+				class=if props.error.get().is_some() { "error" } else { "" },
+				title=(*props.error.get()).as_ref().unwrap_or(&String::new()),
+				on:input=input_handler,
+				on:click=click_handler,
+				on:keydown=key_handler,
+				on:blur=blur_handler,
+				ref=props.input_ref
+			)
+			(if *list_open.get() && !groups.get().is_empty() {
+				view! {
+					ctx,
+					div(id=list_id.clone(), class="entry_type_select_list") {
+						Indexed(
+							iterable=groups,
+							view=move |ctx, group| {
+								let options: Vec<View<G>> = group
+									.entry_types
+									.into_iter()
+									.map(|entry_type| {
+										let is_highlighted = create_memo(ctx, {
+											let entry_type_id = entry_type.id.clone();
+											move || highlighted_id.get().as_deref() == Some(entry_type_id.as_str())
+										});
+										let option_id = option_element_id(id, &entry_type.id);
+										let color_style = format!("background: {}", rgb_str_from_color(entry_type.color));
+										let option_click_handler = {
+											let entry_type_id = entry_type.id.clone();
+											move |_event: WebEvent| choose_type(&entry_type_id)
+										};
+										let hover_handler = {
+											let entry_type_id = entry_type.id.clone();
+											move |_event: WebEvent| highlighted_id.set(Some(entry_type_id.clone()))
+										};
+										// Choosing an option with the mouse shouldn't take focus from the text field
+										let mouse_down_handler = |event: WebEvent| event.prevent_default();
+										view! {
+											ctx,
+											div(
+												id=option_id,
+												class=if *is_highlighted.get() { "entry_type_select_option entry_type_select_option_highlighted" } else { "entry_type_select_option" },
+												title=entry_type.description,
+												on:mousedown=mouse_down_handler,
+												on:mouseenter=hover_handler,
+												on:click=option_click_handler
+											) {
+												span(class="entry_type_select_color", style=color_style)
+												(entry_type.name)
+											}
+										}
+									})
+									.collect();
+								let options = View::new_fragment(options);
+
+								match group.heading {
+									Some(heading) => view! {
+										ctx,
+										div(class="entry_type_select_group_heading") { (heading) }
+										(options)
+									},
+									None => options,
+								}
+							}
+						)
+					}
+				}
+			} else {
+				view! { ctx, }
+			})
+		}
+	}
+}
+
+/// Gets the ID of the list element for an entry type
+fn option_element_id(select_id: &str, entry_type_id: &str) -> String {
+	format!("{}_option_{}", select_id, entry_type_id)
+}
+
+/// Scrolls the list so that the option with the given element ID is visible
+fn scroll_option_into_view(option_id: &str) {
+	let Some(document) = window().and_then(|window| window.document()) else {
+		return;
+	};
+	let Some(option_element) = document.get_element_by_id(option_id) else {
+		return;
+	};
+	let scroll_into_view_options = ScrollIntoViewOptions::new();
+	scroll_into_view_options.set_block(ScrollLogicalPosition::Nearest);
+	option_element.scroll_into_view_with_scroll_into_view_options(&scroll_into_view_options);
+}
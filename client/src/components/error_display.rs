@@ -6,6 +6,7 @@
 
 use crate::subscriptions::connection::ConnectionState;
 use crate::subscriptions::DataSignals;
+use stream_log_shared::messages::initial::SyncCompatibility;
 use sycamore::prelude::*;
 use web_sys::Event as WebEvent;
 
@@ -14,12 +15,39 @@ pub fn ErrorDisplay<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let data: &DataSignals = use_context(ctx);
 	let errors = create_memo(ctx, || (*data.errors.get()).clone());
 	let connection_state = create_memo(ctx, || *data.connection_state.get());
+	let server_restarting = create_memo(ctx, || *data.server_restarting.get());
+	let sync_compatibility = create_memo(ctx, || *data.sync_compatibility.get());
+
+	let reload_handler = |_event: WebEvent| {
+		if let Some(window) = web_sys::window() {
+			let _ = window.location().reload();
+		}
+	};
 
 	view! {
 		ctx,
 		ul(id="page_errors") {
+			(match *sync_compatibility.get() {
+				SyncCompatibility::Current => view! { ctx, },
+				SyncCompatibility::UpdateAvailable => view! {
+					ctx,
+					li(class="page_error_entry_update_available") {
+						"A new version of this page is available."
+						button(class="page_error_entry_reload", on:click=reload_handler) { "Reload" }
+					}
+				},
+				SyncCompatibility::UpdateRequired => view! {
+					ctx,
+					li(class="page_error_entry_update_required") {
+						"The server was updated. Reload the page to keep working."
+						button(class="page_error_entry_reload", on:click=reload_handler) { "Reload" }
+					}
+				}
+			})
 			(match *connection_state.get() {
-				ConnectionState::Connected => view! { ctx, },
+				ConnectionState::Connected | ConnectionState::Stale if *server_restarting.get() => view! { ctx, li(class="page_error_entry_connection_reconnecting") { "The server is restarting. You'll be reconnected when it's back." } },
+				ConnectionState::Connected | ConnectionState::Stale => view! { ctx, },
+				ConnectionState::Reconnecting if *server_restarting.get() => view! { ctx, li(class="page_error_entry_connection_reconnecting") { "The server is restarting. Reconnecting..." } },
 				ConnectionState::Reconnecting => view! { ctx, li(class="page_error_entry_connection_reconnecting") { "Connection to server lost. Reconnecting..." } },
 				ConnectionState::Lost => view! { ctx, li(class="page_error_entry_connection_lost") { "Connection to server lost." } }
 			})
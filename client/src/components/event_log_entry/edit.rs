@@ -4,20 +4,29 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::utils::{format_duration, get_duration_from_formatted};
+use super::edit_panes::{edit_field_id, EditPane, EditPanes};
+use super::history::{entry_part_value, EventLogEntryHistory};
+use super::utils::time_display_for_user;
+use crate::components::entry_type_select::EntryTypeSelect;
+use crate::components::tag_autocomplete::{recent_tag_usage, TagAutocomplete};
+use crate::config::client_config;
+use crate::pending_edits::{copy_entry_part, entry_part_name};
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use chrono::Utc;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::custom_fields::{CustomField, CustomFieldType};
+use stream_log_shared::messages::entry_templates::EntryTemplate;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry, EventLogTab, VideoEditState};
 use stream_log_shared::messages::event_subscription::{
-	EventSubscriptionUpdate, ModifiedEventLogEntryParts, NewTypingData,
+	entry_parts_match, ChildEntryHandling, EventSubscriptionUpdate, ModifiedEventLogEntryParts, NewTypingData,
 };
-use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::events::{description_length, Event};
+use stream_log_shared::messages::hotkeys::{effective_hotkeys, HotkeyAction};
 use stream_log_shared::messages::permissions::PermissionLevel;
 use stream_log_shared::messages::subscriptions::SubscriptionTargetUpdate;
 use stream_log_shared::messages::tags::Tag;
@@ -26,7 +35,7 @@ use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{Event as WebEvent, HtmlElement, KeyboardEvent};
+use web_sys::{Event as WebEvent, HtmlElement, HtmlSelectElement, KeyboardEvent};
 
 #[derive(Prop)]
 pub struct EventLogEntryEditProps<'a> {
@@ -36,16 +45,25 @@ pub struct EventLogEntryEditProps<'a> {
 	event_tags: &'a ReadSignal<Vec<Tag>>,
 	event_editors: &'a ReadSignal<Vec<PublicUserData>>,
 	event_log_tabs: &'a ReadSignal<Vec<EventLogTab>>,
+	entry_templates: &'a ReadSignal<Vec<EntryTemplate>>,
+	custom_fields: &'a ReadSignal<Vec<CustomField>>,
 	current_tab: &'a ReadSignal<Option<EventLogTab>>,
 	event_log_entries: &'a ReadSignal<Vec<EventLogEntry>>,
-	editing_log_entry: &'a Signal<Option<EventLogEntry>>,
-	edit_parent_log_entry: &'a Signal<Option<EventLogEntry>>,
+	new_event_log_entries: &'a ReadSignal<Vec<EventLogEntry>>,
+	pane: EditPane,
+	edit_panes: &'a EditPanes,
 	save_message_queue: &'a Signal<Vec<FromClientMessage>>,
 }
 
 #[component]
 pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditProps<'a>) -> View<G> {
-	let editing_log_entry = create_memo(ctx, || (*props.editing_log_entry.get()).clone().unwrap_or_default());
+	let entry_id: &String = create_ref(ctx, props.pane.entry_id.clone());
+	let pane_entry = create_ref(ctx, props.pane.entry.clone());
+	let edit_parent_log_entry = create_ref(ctx, props.pane.parent.clone());
+	let editing_log_entry = create_memo(ctx, || (*pane_entry.get()).clone().unwrap_or_default());
+
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+	let time_display = create_memo(ctx, || time_display_for_user(&user.get(), &props.event.get()));
 
 	let event_entry_types_name_index = create_memo(ctx, {
 		let event_entry_types = (*props.event_entry_types.get()).clone();
@@ -57,13 +75,6 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			name_index
 		}
 	});
-	let event_entry_types_name_case_map = create_memo(ctx, || {
-		let mut case_map: BTreeMap<String, String> = BTreeMap::new();
-		for name in event_entry_types_name_index.get().keys() {
-			case_map.insert(name.to_lowercase(), name.clone());
-		}
-		case_map
-	});
 	let event_entry_types_id_index = create_memo(ctx, {
 		let event_entry_types = (*props.event_entry_types.get()).clone();
 		move || {
@@ -83,6 +94,19 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			.collect();
 		tag_index
 	});
+	// Tags outside their valid dates can still be entered by name, but they aren't suggested
+	let suggested_tags = create_memo(ctx, || {
+		let today = Utc::now().date_naive();
+		let tags: Vec<Tag> = props
+			.event_tags
+			.get()
+			.iter()
+			.filter(|tag| tag.is_valid_on(today))
+			.cloned()
+			.collect();
+		tags
+	});
+	let tag_usage = create_memo(ctx, || recent_tag_usage(&props.event_log_entries.get()));
 	let event_editors_name_index = create_memo(ctx, || {
 		let editor_index: HashMap<String, PublicUserData> = props
 			.event_editors
@@ -97,7 +121,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	let suppress_typing_notifications = create_signal(ctx, true);
 
 	create_effect(ctx, move || {
-		let parent_entry = props.edit_parent_log_entry.get();
+		let parent_entry = edit_parent_log_entry.get();
 		if *suppress_typing_notifications.get_untracked() {
 			return;
 		}
@@ -138,14 +162,11 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		});
 	});
 
-	let start_time_warning_base = (*props.editing_log_entry.get())
-		.as_ref()
-		.and_then(|entry| entry.start_time);
+	let start_time_warning_base = (*pane_entry.get()).as_ref().and_then(|entry| entry.start_time);
 	let start_time_warning_active = create_signal(ctx, false);
-	let start_time_input = if let Some(entry) = props.editing_log_entry.get().as_ref() {
+	let start_time_input = if let Some(entry) = pane_entry.get().as_ref() {
 		if let Some(start_time) = entry.start_time {
-			let initial_start_time_duration = start_time - props.event.get().start_time;
-			format_duration(&initial_start_time_duration)
+			time_display.get().format_time(start_time, props.event.get().start_time)
 		} else {
 			String::new()
 		}
@@ -155,7 +176,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	let start_time_input = create_signal(ctx, start_time_input);
 	let start_time_value = create_signal(
 		ctx,
-		if let Some(entry) = props.editing_log_entry.get().as_ref() {
+		if let Some(entry) = pane_entry.get().as_ref() {
 			entry.start_time
 		} else {
 			None
@@ -163,22 +184,17 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	);
 	let start_time_error: &Signal<Option<String>> = create_signal(ctx, None);
 
-	let initial_end_time = (*props.editing_log_entry.get())
+	let initial_end_time = (*pane_entry.get())
 		.as_ref()
 		.map(|entry| entry.end_time)
 		.unwrap_or(EndTimeData::NotEntered);
-	let initial_end_time_duration = match initial_end_time {
-		EndTimeData::Time(end_time) => Some(end_time - props.event.get().start_time),
-		_ => None,
-	};
-	let initial_end_time_input = if let Some(duration) = initial_end_time_duration.as_ref() {
-		format_duration(duration)
-	} else {
-		String::new()
+	let initial_end_time_input = match initial_end_time {
+		EndTimeData::Time(end_time) => time_display.get().format_time(end_time, props.event.get().start_time),
+		_ => String::new(),
 	};
 	let end_time_value = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.end_time)
 			.unwrap_or(EndTimeData::NotEntered),
@@ -186,9 +202,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	let end_time_input = create_signal(ctx, initial_end_time_input);
 	let end_time_error: &Signal<Option<String>> = create_signal(ctx, None);
 
-	let initial_entry_type_id = (*props.editing_log_entry.get())
-		.as_ref()
-		.map(|entry| entry.entry_type.clone());
+	let initial_entry_type_id = (*pane_entry.get()).as_ref().map(|entry| entry.entry_type.clone());
 	let initial_entry_type_name = if let Some(Some(entry_type_id)) = initial_entry_type_id.as_ref() {
 		if let Some(entry_type) = event_entry_types_id_index.get().get(entry_type_id) {
 			entry_type.name.clone()
@@ -204,15 +218,25 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let description = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.description.clone())
 			.unwrap_or_default(),
 	);
+	let description_error = create_memo(ctx, || {
+		props.event.get().check_description_length(&description.get()).err()
+	});
+	let description_counter = create_memo(ctx, || {
+		let length = description_length(&description.get());
+		match props.event.get().description_max_length {
+			Some(max_length) => format!("{}/{}", length, max_length),
+			None => length.to_string(),
+		}
+	});
 
 	let submitter_or_winner = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.submitter_or_winner.clone())
 			.unwrap_or_default(),
@@ -220,7 +244,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let media_links = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.media_links.clone())
 			.unwrap_or_default(),
@@ -230,7 +254,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let tags = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.tags.clone())
 			.unwrap_or_default(),
@@ -257,7 +281,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let video_edit_state = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.video_edit_state)
 			.unwrap_or_default(),
@@ -286,18 +310,13 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let notes = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.notes.clone())
 			.unwrap_or_default(),
 	);
 
-	let editor_value = create_signal(
-		ctx,
-		(*props.editing_log_entry.get())
-			.as_ref()
-			.and_then(|entry| entry.editor.clone()),
-	);
+	let editor_value = create_signal(ctx, (*pane_entry.get()).as_ref().and_then(|entry| entry.editor.clone()));
 	let editor_entry = if let Some(editor) = (*editor_value.get()).as_ref() {
 		editor.username.clone()
 	} else {
@@ -308,7 +327,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let poster_moment = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.poster_moment)
 			.unwrap_or_default(),
@@ -316,7 +335,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let missing_giveaway_information = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
 			.map(|entry| entry.missing_giveaway_information)
 			.unwrap_or_default(),
@@ -324,11 +343,19 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 	let disable_missing_giveaway_info = create_signal(ctx, false);
 
-	let manual_sort_key = create_signal(
+	// Values of the event's custom fields, keyed by field ID. Cleared fields are kept as empty values until the entry
+	// is saved.
+	let custom_field_values = create_signal(
 		ctx,
-		(*props.editing_log_entry.get())
+		(*pane_entry.get())
 			.as_ref()
-			.and_then(|entry| entry.manual_sort_key),
+			.map(|entry| entry.custom_fields.clone())
+			.unwrap_or_default(),
+	);
+
+	let manual_sort_key = create_signal(
+		ctx,
+		(*pane_entry.get()).as_ref().and_then(|entry| entry.manual_sort_key),
 	);
 	let sort_key_entry = create_signal(
 		ctx,
@@ -355,14 +382,15 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 				.modify()
 				.insert(ModifiedEventLogEntryParts::StartTime);
 		} else {
-			let start_time_result = get_duration_from_formatted(&start_time_input);
+			let warning_start_time = start_time_warning_base.unwrap_or_else(Utc::now);
+			let start_time_result = time_display
+				.get()
+				.parse_time(&start_time_input, event_start, warning_start_time);
 			match start_time_result {
-				Ok(duration) => {
+				Ok(new_start_time) => {
 					start_time_error.set(None);
-					let new_start_time = event_start + duration;
 					start_time_value.set(Some(new_start_time));
 
-					let warning_start_time = start_time_warning_base.unwrap_or_else(Utc::now);
 					start_time_warning_active.set((new_start_time - warning_start_time).num_minutes().abs() >= 60);
 
 					modified_entry_data
@@ -431,11 +459,13 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 				modified_entry_data.modify().insert(ModifiedEventLogEntryParts::EndTime);
 			}
 		} else {
-			let end_time_result = get_duration_from_formatted(end_time_input);
+			let reference_time = start_time_value.get().unwrap_or_else(Utc::now);
+			let end_time_result = time_display
+				.get()
+				.parse_time(end_time_input, event_start, reference_time);
 			match end_time_result {
-				Ok(duration) => {
+				Ok(new_end_time) => {
 					end_time_error.set(None);
-					let new_end_time = event_start + duration;
 					end_time_value.set(EndTimeData::Time(new_end_time));
 
 					modified_entry_data.modify().insert(ModifiedEventLogEntryParts::EndTime);
@@ -669,6 +699,47 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		tags.track();
 		modified_entry_data.modify().insert(ModifiedEventLogEntryParts::Tags);
 	});
+	create_effect(ctx, move || {
+		let typed_tags: Vec<String> = tag_names
+			.get()
+			.iter()
+			.filter(|name| !name.is_empty())
+			.cloned()
+			.collect();
+		let typed_tags = typed_tags.join(", ");
+		if *suppress_typing_notifications.get_untracked() {
+			return;
+		}
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+				(*props.event.get()).clone(),
+				Box::new(EventSubscriptionUpdate::Typing(NewTypingData::Tags(
+					(*editing_log_entry.get()).clone(),
+					typed_tags,
+				))),
+			)));
+			let message_json = match serde_json::to_string(&message) {
+				Ok(msg) => msg,
+				Err(error) => {
+					let data: &DataSignals = use_context(ctx);
+					data.errors.modify().push(ErrorData::new_with_error(
+						"Failed to serialize typing notification.",
+						error,
+					));
+					return;
+				}
+			};
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send typing notification.", error));
+			}
+		});
+	});
 
 	create_effect(ctx, || {
 		notes.track();
@@ -741,12 +812,19 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			.insert(ModifiedEventLogEntryParts::MissingGiveawayInfo);
 	});
 
+	create_effect(ctx, || {
+		custom_field_values.track();
+		modified_entry_data
+			.modify()
+			.insert(ModifiedEventLogEntryParts::CustomFields);
+	});
+
 	create_effect(ctx, || {
 		let entered_end_time = end_time_input.get();
 		let entered_submitter_or_winner = submitter_or_winner.get();
 		let permission_level = props.permission_level.get();
 		let entry_marked_missing_giveaway_info = missing_giveaway_information.get();
-		let editing_existing_entry = props.editing_log_entry.get().is_some();
+		let editing_existing_entry = pane_entry.get().is_some();
 
 		if !entered_end_time.is_empty()
 			&& !entered_end_time.chars().all(|c| c == '-')
@@ -769,19 +847,54 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		manual_sort_key.set(sort_key);
 		modified_entry_data.modify().insert(ModifiedEventLogEntryParts::SortKey);
 	});
+	create_effect(ctx, move || {
+		sort_key_entry.track();
+		if *suppress_typing_notifications.get_untracked() {
+			return;
+		}
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+				(*props.event.get()).clone(),
+				Box::new(EventSubscriptionUpdate::Typing(NewTypingData::SortKey(
+					(*editing_log_entry.get()).clone(),
+					(*sort_key_entry.get()).clone(),
+				))),
+			)));
+			let message_json = match serde_json::to_string(&message) {
+				Ok(msg) => msg,
+				Err(error) => {
+					let data: &DataSignals = use_context(ctx);
+					data.errors.modify().push(ErrorData::new_with_error(
+						"Failed to serialize typing notification.",
+						error,
+					));
+					return;
+				}
+			};
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send typing notification.", error));
+			}
+		});
+	});
 
 	create_effect(ctx, || {
-		props.edit_parent_log_entry.track();
+		edit_parent_log_entry.track();
 		modified_entry_data.modify().insert(ModifiedEventLogEntryParts::Parent);
 	});
 
 	// After setting up all the effects, initialize the modified data tracking to empty
 	modified_entry_data.modify().clear();
-	suppress_typing_notifications.set(false);
+	suppress_typing_notifications.set(!client_config().features.typing_notifications);
 
 	let insert_position_time = create_memo(ctx, || {
 		let log_entries = props.event_log_entries.get();
-		let editing_log_entry = props.editing_log_entry.get();
+		let editing_log_entry = pane_entry.get();
 		let entered_start_time = start_time_value.get();
 		let mut top_level_parent = if let Some(entry) = (*editing_log_entry).clone() {
 			entry
@@ -827,9 +940,8 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	let type_field_ref = create_node_ref(ctx);
 
 	let start_now = || {
-		let start_time_duration = Utc::now() - props.event.get().start_time;
-		let start_time_duration = format_duration(&start_time_duration);
-		start_time_input.set(start_time_duration);
+		let start_time = time_display.get().format_time(Utc::now(), props.event.get().start_time);
+		start_time_input.set(start_time);
 	};
 
 	let start_now_handler = move |_event: WebEvent| {
@@ -841,9 +953,8 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 	};
 
 	let end_now = || {
-		let end_time_duration = Utc::now() - props.event.get().start_time;
-		let end_time_duration = format_duration(&end_time_duration);
-		end_time_input.set(end_time_duration);
+		let end_time = time_display.get().format_time(Utc::now(), props.event.get().start_time);
+		end_time_input.set(end_time);
 	};
 
 	let end_now_handler = move |_event: WebEvent| {
@@ -858,39 +969,49 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		start_time_warning_active.set(false);
 	};
 
-	let entry_type_lost_focus = move |_event: WebEvent| {
-		let entered_name = entry_type_name.get();
-		let name_index = event_entry_types_name_index.get();
-		let entered_type = name_index.get(&*entered_name);
-		if entered_type.is_some() {
+	let add_media_link_handler = |_event: WebEvent| {
+		media_links.modify().push(String::new());
+	};
+
+	let template_change_handler = move |event: WebEvent| {
+		let Some(target) = event.target() else {
 			return;
-		}
-		let lower_name = entered_name.to_lowercase();
-		let case_map = event_entry_types_name_case_map.get();
-		let entered_type_name = case_map.get(&lower_name);
-		if let Some(name) = entered_type_name {
-			entry_type_name.set(name.clone());
+		};
+		let select_element: HtmlSelectElement = target.unchecked_into();
+		let template_id = select_element.value();
+		// The picker goes back to its placeholder so that the same template can be used again
+		select_element.set_value("");
+		let template = props
+			.entry_templates
+			.get()
+			.iter()
+			.find(|template| template.id == template_id)
+			.cloned();
+		let Some(template) = template else {
 			return;
-		}
+		};
 
-		let mut found_name: Option<&String> = None;
-		for (case_insensitive_name, case_sensitive_name) in case_map.range(lower_name.clone()..) {
-			if !case_insensitive_name.starts_with(&lower_name) {
-				break;
-			}
-			if found_name.is_some() {
-				found_name = None;
-				break;
-			}
-			found_name = Some(case_sensitive_name);
+		let template_entry_type_name = template.entry_type.as_ref().and_then(|entry_type_id| {
+			event_entry_types_id_index
+				.get()
+				.get(entry_type_id)
+				.map(|entry_type| entry_type.name.clone())
+		});
+		if let Some(name) = template_entry_type_name {
+			entry_type_name.set(name);
 		}
-		if let Some(name) = found_name {
-			entry_type_name.set(name.clone());
+		if !template.description.is_empty() {
+			description.set(template.description);
+		}
+		if !template.tags.is_empty() {
+			let mut entry_tags = tags.modify();
+			entry_tags.retain(|tag| !tag.name.is_empty());
+			for tag in template.tags {
+				if !entry_tags.iter().any(|entry_tag| entry_tag.id == tag.id) {
+					entry_tags.push(tag);
+				}
+			}
 		}
-	};
-
-	let add_media_link_handler = |_event: WebEvent| {
-		media_links.modify().push(String::new());
 	};
 
 	let add_tag_handler = |_event: WebEvent| {
@@ -899,26 +1020,25 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			name: String::new(),
 			description: String::new(),
 			playlist: None,
+			valid_from: None,
+			valid_to: None,
 		});
 	};
 
 	create_effect(ctx, move || {
-		let editing_log_entry = props.editing_log_entry.get();
+		let editing_log_entry = pane_entry.get();
 		suppress_typing_notifications.set(true);
 
 		if let Some(entry) = editing_log_entry.as_ref() {
 			let event_start_time = props.event.get_untracked().start_time;
+			let time_display = time_display.get_untracked();
 			let start_duration = if let Some(start_time) = entry.start_time {
-				let duration = start_time - event_start_time;
-				format_duration(&duration)
+				time_display.format_time(start_time, event_start_time)
 			} else {
 				String::new()
 			};
 			let end_duration = match entry.end_time {
-				EndTimeData::Time(time) => {
-					let duration = time - event_start_time;
-					format_duration(&duration)
-				}
+				EndTimeData::Time(time) => time_display.format_time(time, event_start_time),
 				EndTimeData::NotEntered => String::new(),
 				EndTimeData::NoTime => String::from("-"),
 			};
@@ -958,8 +1078,9 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 					.unwrap_or_default(),
 			);
 			missing_giveaway_information.set(entry.missing_giveaway_information);
+			custom_field_values.set(entry.custom_fields.clone());
 			sort_key_entry.set(entry.manual_sort_key.map(|key| key.to_string()).unwrap_or_default());
-			props.edit_parent_log_entry.set(parent_entry);
+			edit_parent_log_entry.set(parent_entry);
 		} else {
 			start_time_input.set(String::new());
 			end_time_input.set(String::new());
@@ -972,64 +1093,274 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			notes.set(String::new());
 			editor_entry.set(String::new());
 			missing_giveaway_information.set(false);
+			custom_field_values.set(HashMap::new());
 			sort_key_entry.set(String::new());
-			props.edit_parent_log_entry.set(None);
+			edit_parent_log_entry.set(None);
 		}
 
 		start_time_warning_active.set(false);
 		modified_entry_data.modify().clear();
-		suppress_typing_notifications.set(false);
+		suppress_typing_notifications.set(!client_config().features.typing_notifications);
 	});
 
+	// Repeated segments usually differ only by their times, so duplicates start with everything else that describes them
+	if let Some(source_entry) = props.pane.duplicate_of.as_ref() {
+		let source_entry_type_name = source_entry.entry_type.as_ref().and_then(|entry_type_id| {
+			event_entry_types_id_index
+				.get_untracked()
+				.get(entry_type_id)
+				.map(|entry_type| entry_type.name.clone())
+		});
+		if let Some(name) = source_entry_type_name {
+			entry_type_name.set(name);
+		}
+		description.set(source_entry.description.clone());
+		tags.set(source_entry.tags.clone());
+		let parent_entry = source_entry.parent.as_ref().and_then(|parent_id| {
+			props
+				.event_log_entries
+				.get_untracked()
+				.iter()
+				.find(|entry| entry.id == *parent_id)
+				.cloned()
+		});
+		edit_parent_log_entry.set(parent_entry);
+	}
+
+	let history_open = create_signal(ctx, false);
+
 	let reset_data = move || {
-		props.editing_log_entry.set(None);
+		history_open.set(false);
+		props.edit_panes.close(entry_id);
+	};
+
+	// Applies the value entered in the form for the given part to an entry
+	let apply_form_part = move |entry: &mut EventLogEntry, part: ModifiedEventLogEntryParts| match part {
+		ModifiedEventLogEntryParts::StartTime => entry.start_time = *start_time_value.get(),
+		ModifiedEventLogEntryParts::EndTime => entry.end_time = *end_time_value.get(),
+		ModifiedEventLogEntryParts::EntryType => entry.entry_type.clone_from(&(*entry_type_id.get())),
+		ModifiedEventLogEntryParts::Description => entry.description.clone_from(&(*description.get())),
+		ModifiedEventLogEntryParts::MediaLinks => {
+			entry.media_links = (*media_links.get())
+				.iter()
+				.filter(|link| !link.is_empty())
+				.cloned()
+				.collect()
+		}
+		ModifiedEventLogEntryParts::SubmitterOrWinner => {
+			entry.submitter_or_winner.clone_from(&(*submitter_or_winner.get()))
+		}
+		ModifiedEventLogEntryParts::Tags => {
+			entry.tags = tags.get().iter().filter(|tag| !tag.name.is_empty()).cloned().collect()
+		}
+		ModifiedEventLogEntryParts::VideoEditState => entry.video_edit_state = *video_edit_state.get(),
+		ModifiedEventLogEntryParts::PosterMoment => entry.poster_moment = *poster_moment.get(),
+		ModifiedEventLogEntryParts::Notes => entry.notes.clone_from(&(*notes.get())),
+		ModifiedEventLogEntryParts::Editor => entry.editor.clone_from(&(*editor_value.get())),
+		ModifiedEventLogEntryParts::MissingGiveawayInfo => {
+			entry.missing_giveaway_information = *missing_giveaway_information.get()
+		}
+		ModifiedEventLogEntryParts::SortKey => entry.manual_sort_key = *manual_sort_key.get(),
+		ModifiedEventLogEntryParts::Parent => {
+			entry.parent = (*edit_parent_log_entry.get())
+				.as_ref()
+				.map(|parent_entry| parent_entry.id.clone())
+		}
+		ModifiedEventLogEntryParts::CustomFields => {
+			entry.custom_fields = custom_field_values
+				.get()
+				.iter()
+				.filter(|(_, value)| !value.is_empty())
+				.map(|(field_id, value)| (field_id.clone(), value.clone()))
+				.collect()
+		}
+	};
+
+	// Someone else can update the entry while it's being edited. The form is compared against this version of the entry
+	// to find those changes, and each part is moved up to the incoming version once the user resolves it.
+	let conflict_base_entry = create_signal(ctx, (*pane_entry.get_untracked()).clone());
+	let incoming_entry = create_memo(ctx, || {
+		props
+			.event_log_entries
+			.get()
+			.iter()
+			.find(|entry| entry.id == *entry_id)
+			.cloned()
+	});
+	let draft_entry = create_memo(ctx, move || {
+		let mut entry = (*editing_log_entry.get()).clone();
+		for part in ModifiedEventLogEntryParts::all() {
+			apply_form_part(&mut entry, part);
+		}
+		entry
+	});
+	let conflicting_parts = create_memo(ctx, || {
+		let base_entry = conflict_base_entry.get();
+		let incoming_entry = incoming_entry.get();
+		let (Some(base_entry), Some(incoming_entry)) = ((*base_entry).as_ref(), (*incoming_entry).as_ref()) else {
+			return Vec::new();
+		};
+		let draft_entry = draft_entry.get();
+		let parts: Vec<ModifiedEventLogEntryParts> = ModifiedEventLogEntryParts::all()
+			.into_iter()
+			.filter(|part| {
+				!entry_parts_match(*part, base_entry, incoming_entry)
+					&& !entry_parts_match(*part, &draft_entry, incoming_entry)
+			})
+			.collect();
+		parts
+	});
+
+	let resolve_conflict = move |part: ModifiedEventLogEntryParts| {
+		let Some(incoming_entry) = (*incoming_entry.get_untracked()).clone() else {
+			return;
+		};
+		let mut base_entry = conflict_base_entry.modify();
+		if let Some(base_entry) = base_entry.as_mut() {
+			copy_entry_part(part, &incoming_entry, base_entry);
+		}
+	};
+	let take_incoming_part = move |part: ModifiedEventLogEntryParts| {
+		let Some(entry) = (*incoming_entry.get_untracked()).clone() else {
+			return;
+		};
+		let previous_modified_parts = (*modified_entry_data.get_untracked()).clone();
+		suppress_typing_notifications.set(true);
+
+		let event_start_time = props.event.get_untracked().start_time;
+		let time_display = time_display.get_untracked();
+		match part {
+			ModifiedEventLogEntryParts::StartTime => start_time_input.set(
+				entry
+					.start_time
+					.map(|start_time| time_display.format_time(start_time, event_start_time))
+					.unwrap_or_default(),
+			),
+			ModifiedEventLogEntryParts::EndTime => end_time_input.set(match entry.end_time {
+				EndTimeData::Time(time) => time_display.format_time(time, event_start_time),
+				EndTimeData::NotEntered => String::new(),
+				EndTimeData::NoTime => String::from("-"),
+			}),
+			ModifiedEventLogEntryParts::EntryType => entry_type_name.set(
+				entry
+					.entry_type
+					.as_ref()
+					.and_then(|entry_type| {
+						event_entry_types_id_index
+							.get_untracked()
+							.get(entry_type)
+							.map(|entry_type| entry_type.name.clone())
+					})
+					.unwrap_or_default(),
+			),
+			ModifiedEventLogEntryParts::Description => description.set(entry.description.clone()),
+			ModifiedEventLogEntryParts::MediaLinks => media_links.set(entry.media_links.clone()),
+			ModifiedEventLogEntryParts::SubmitterOrWinner => submitter_or_winner.set(entry.submitter_or_winner.clone()),
+			ModifiedEventLogEntryParts::Tags => tags.set(entry.tags.clone()),
+			ModifiedEventLogEntryParts::VideoEditState => video_edit_state.set(entry.video_edit_state),
+			ModifiedEventLogEntryParts::PosterMoment => poster_moment.set(entry.poster_moment),
+			ModifiedEventLogEntryParts::Notes => notes.set(entry.notes.clone()),
+			ModifiedEventLogEntryParts::Editor => editor_entry.set(
+				entry
+					.editor
+					.as_ref()
+					.map(|editor| editor.username.clone())
+					.unwrap_or_default(),
+			),
+			ModifiedEventLogEntryParts::MissingGiveawayInfo => {
+				missing_giveaway_information.set(entry.missing_giveaway_information)
+			}
+			ModifiedEventLogEntryParts::SortKey => {
+				sort_key_entry.set(entry.manual_sort_key.map(|key| key.to_string()).unwrap_or_default())
+			}
+			ModifiedEventLogEntryParts::Parent => {
+				let parent_entry = entry.parent.as_ref().and_then(|parent_id| {
+					props
+						.event_log_entries
+						.get_untracked()
+						.iter()
+						.find(|entry| entry.id == *parent_id)
+						.cloned()
+				});
+				edit_parent_log_entry.set(parent_entry);
+			}
+			ModifiedEventLogEntryParts::CustomFields => custom_field_values.set(entry.custom_fields.clone()),
+		}
+
+		// The form now matches the incoming entry for this part, so it no longer needs to be saved. Setting the field
+		// can mark other parts as modified through the effects, so the previous set of modified parts is restored.
+		let mut modified_parts = previous_modified_parts;
+		modified_parts.remove(&part);
+		modified_entry_data.set(modified_parts);
+		suppress_typing_notifications.set(!client_config().features.typing_notifications);
+
+		resolve_conflict(part);
+	};
+	let keep_draft_part = move |part: ModifiedEventLogEntryParts| {
+		// Saving the part makes sure the form's value replaces the incoming one even if it wasn't changed in the form
+		modified_entry_data.modify().insert(part);
+		resolve_conflict(part);
+	};
+	let conflict_part_value = move |part: ModifiedEventLogEntryParts, entry: &EventLogEntry| -> String {
+		if part == ModifiedEventLogEntryParts::Parent {
+			return entry
+				.parent
+				.as_ref()
+				.map(|parent_id| {
+					props
+						.event_log_entries
+						.get_untracked()
+						.iter()
+						.find(|entry| entry.id == *parent_id)
+						.map(|parent_entry| parent_entry.description.clone())
+						.unwrap_or_else(|| parent_id.clone())
+				})
+				.unwrap_or_default();
+		}
+		if part == ModifiedEventLogEntryParts::CustomFields {
+			let values: Vec<String> = props
+				.custom_fields
+				.get_untracked()
+				.iter()
+				.filter_map(|field| {
+					let value = entry.custom_fields.get(&field.id)?;
+					Some(format!("{}: {}", field.name, value))
+				})
+				.collect();
+			return values.join(", ");
+		}
+		entry_part_value(
+			part,
+			entry,
+			&time_display.get_untracked(),
+			props.event.get_untracked().start_time,
+			&props.event_entry_types.get_untracked(),
+		)
 	};
 
 	let save_handler = move |event: WebEvent| {
 		event.prevent_default();
 
-		if let Some(entry) = (*props.editing_log_entry.get()).as_ref() {
+		if let Some(entry) = (*pane_entry.get()).as_ref() {
 			let mut entry = entry.clone();
 			for modification in modified_entry_data.get().iter() {
-				match *modification {
-					ModifiedEventLogEntryParts::StartTime => entry.start_time = *start_time_value.get(),
-					ModifiedEventLogEntryParts::EndTime => entry.end_time = *end_time_value.get(),
-					ModifiedEventLogEntryParts::EntryType => entry.entry_type.clone_from(&(*entry_type_id.get())),
-					ModifiedEventLogEntryParts::Description => entry.description.clone_from(&(*description.get())),
-					ModifiedEventLogEntryParts::MediaLinks => {
-						entry.media_links = (*media_links.get())
-							.iter()
-							.filter(|link| !link.is_empty())
-							.cloned()
-							.collect()
-					}
-					ModifiedEventLogEntryParts::SubmitterOrWinner => {
-						entry.submitter_or_winner.clone_from(&(*submitter_or_winner.get()))
-					}
-					ModifiedEventLogEntryParts::Tags => {
-						entry.tags = tags.get().iter().filter(|tag| !tag.name.is_empty()).cloned().collect()
-					}
-					ModifiedEventLogEntryParts::VideoEditState => entry.video_edit_state = *video_edit_state.get(),
-					ModifiedEventLogEntryParts::PosterMoment => entry.poster_moment = *poster_moment.get(),
-					ModifiedEventLogEntryParts::Notes => entry.notes.clone_from(&(*notes.get())),
-					ModifiedEventLogEntryParts::Editor => entry.editor.clone_from(&(*editor_value.get())),
-					ModifiedEventLogEntryParts::MissingGiveawayInfo => {
-						entry.missing_giveaway_information = *missing_giveaway_information.get()
-					}
-					ModifiedEventLogEntryParts::SortKey => entry.manual_sort_key = *manual_sort_key.get(),
-					ModifiedEventLogEntryParts::Parent => {
-						entry.parent = (*props.edit_parent_log_entry.get())
-							.as_ref()
-							.map(|parent_entry| parent_entry.id.clone())
-					}
-				}
+				apply_form_part(&mut entry, *modification);
 			}
 
+			// Once every conflict is resolved, the user has seen everything in the incoming version, so the change is
+			// based on that
+			let base_entry = if conflicting_parts.get().is_empty() {
+				incoming_entry.get()
+			} else {
+				conflict_base_entry.get()
+			};
+			let base_history_id = (*base_entry).as_ref().and_then(|entry| entry.history_id.clone());
 			let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
 				(*props.event.get()).clone(),
 				Box::new(EventSubscriptionUpdate::UpdateLogEntry(
 					entry.clone(),
 					modified_entry_data.get().iter().copied().collect(),
+					base_history_id,
 				)),
 			)));
 
@@ -1076,14 +1407,33 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		reset_data();
 	};
 
+	let history_handler = move |_event: WebEvent| {
+		history_open.set(true);
+	};
+
+	let duplicate_handler = move |_event: WebEvent| {
+		let Some(source_entry) = (*pane_entry.get()).clone() else {
+			return;
+		};
+		let new_entry = props
+			.new_event_log_entries
+			.get()
+			.iter()
+			.find(|entry| entry.start_time.is_none() && !props.edit_panes.is_editing(&entry.id))
+			.cloned();
+		if let Some(new_entry) = new_entry {
+			props.edit_panes.open_duplicate(new_entry, source_entry);
+		}
+	};
+
 	let delete_confirm_signal = create_signal(ctx, false);
 
 	let delete_handler = move |_event: WebEvent| {
 		delete_confirm_signal.set(true);
 	};
 
-	let delete_confirm_handler = move |_event: WebEvent| {
-		let Some(log_entry) = (*props.editing_log_entry.get()).clone() else {
+	let send_delete = move |child_handling: ChildEntryHandling| {
+		let Some(log_entry) = (*pane_entry.get()).clone() else {
 			return;
 		};
 		delete_confirm_signal.set(false);
@@ -1093,7 +1443,7 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 			let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
 				(*props.event.get()).clone(),
-				Box::new(EventSubscriptionUpdate::DeleteLogEntry(log_entry)),
+				Box::new(EventSubscriptionUpdate::DeleteLogEntry(log_entry, child_handling)),
 			)));
 			let message_json = match serde_json::to_string(&message) {
 				Ok(msg) => msg,
@@ -1117,6 +1467,9 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 		});
 	};
 
+	let delete_confirm_handler = move |_event: WebEvent| send_delete(ChildEntryHandling::Promote);
+	let delete_with_children_handler = move |_event: WebEvent| send_delete(ChildEntryHandling::Delete);
+
 	let delete_cancel_handler = move |_event: WebEvent| {
 		delete_confirm_signal.set(false);
 	};
@@ -1161,69 +1514,68 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 			|| end_time_error.get().is_some()
 			|| entry_type_error.get().is_some()
 			|| editor_error.get().is_some()
+			|| description_error.get().is_some()
 			|| !new_tag_names.get().is_empty()
 			|| *start_time_warning_active.get()
 	});
 
 	let remove_parent_handler = |_event: WebEvent| {
-		props.edit_parent_log_entry.set(None);
+		edit_parent_log_entry.set(None);
 	};
 
+	let hotkeys = {
+		let data: &DataSignals = use_context(ctx);
+		create_memo(ctx, || effective_hotkeys(&data.hotkeys.get()))
+	};
 	let key_handler = move |event: WebEvent| {
 		let key_event: KeyboardEvent = event.unchecked_into();
 
-		if !key_event.alt_key() || key_event.shift_key() || key_event.ctrl_key() || key_event.meta_key() {
+		let code = key_event.code();
+		let action = hotkeys.get().iter().find_map(|(action, hotkey)| {
+			hotkey
+				.matches(
+					&code,
+					key_event.alt_key(),
+					key_event.ctrl_key(),
+					key_event.shift_key(),
+					key_event.meta_key(),
+				)
+				.then_some(*action)
+		});
+		let Some(action) = action else {
 			return;
-		}
+		};
+		key_event.prevent_default();
 
-		match key_event.key().as_str() {
-			"s" => {
-				if props.editing_log_entry.get().is_none() {
+		match action {
+			HotkeyAction::StartTimeNow => {
+				if pane_entry.get().is_none() {
 					start_now();
 				}
 			}
-			"e" => end_now(),
-			"i" => {
+			HotkeyAction::EndTimeNow => end_now(),
+			HotkeyAction::ToggleMissingGiveawayInfo => {
 				if !*disable_missing_giveaway_info.get() {
 					missing_giveaway_information.set(!*missing_giveaway_information.get());
 				}
 			}
-			_ => (),
 		}
 	};
 
-	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
 	let use_spell_check = create_memo(ctx, move || {
 		(*user.get()).as_ref().map(|user| user.use_spell_check).unwrap_or(false)
 	});
 
+	// Rows choose parents for and open entries in the pane the user last worked in
+	let focus_handler = |_event: WebEvent| {
+		props.edit_panes.set_active(entry_id);
+	};
+
+	let editors_list_id = create_ref(ctx, edit_field_id("event_log_entry_edit_editors_list", entry_id));
+
 	view! {
 		ctx,
-		datalist(id="event_log_entry_edit_type_list") {
-			Keyed(
-				iterable=props.event_entry_types,
-				key=|entry_type| entry_type.id.clone(),
-				view=|ctx, entry_type| {
-					view! {
-						ctx,
-						option(value=entry_type.name)
-					}
-				}
-			)
-		}
-		datalist(id="event_log_entry_edit_tags_list") {
-			Keyed(
-				iterable=props.event_tags,
-				key=|tag| tag.id.clone(),
-				view=|ctx, tag| {
-					view! {
-						ctx,
-						option(value=tag.name)
-					}
-				}
-			)
-		}
-		datalist(id="event_log_entry_edit_editors_list") {
+		datalist(id=editors_list_id.clone()) {
 			Keyed(
 				iterable=props.event_editors,
 				key=|editor| editor.id.clone(),
@@ -1235,20 +1587,16 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 				}
 			)
 		}
-		form(id="event_log_entry_edit", on:submit=save_handler, on:keydown=key_handler) {
-			(if let Some(entry) = (*props.editing_log_entry.get()).as_ref() {
+		form(class="event_log_entry_edit", on:submit=save_handler, on:keydown=key_handler, on:focusin=focus_handler) {
+			(if let Some(entry) = (*pane_entry.get()).as_ref() {
 				let event_start_time = props.event.get().start_time;
 				let start_duration = if let Some(start_time) = entry.start_time {
-					let duration = start_time - event_start_time;
-					format_duration(&duration)
+					time_display.get().format_time(start_time, event_start_time)
 				} else {
 					String::new()
 				};
 				let end_duration = match entry.end_time {
-					EndTimeData::Time(time) => {
-						let duration = time - props.event.get().start_time;
-						format_duration(&duration)
-					}
+					EndTimeData::Time(time) => time_display.get().format_time(time, event_start_time),
 					EndTimeData::NotEntered => String::new(),
 					EndTimeData::NoTime => String::from("—")
 				};
@@ -1259,20 +1607,66 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 
 				view! {
 					ctx,
-					div(id="event_log_entry_edit_editing_info", class="event_log_entry_edit_editing_info_existing") {
+					div(class="event_log_entry_edit_editing_info event_log_entry_edit_editing_info_existing") {
 						(header_text)
 					}
 				}
 			} else {
 				view! {
 					ctx,
-					div(id="event_log_entry_edit_editing_info", class="event_log_entry_edit_editing_info_new") {
+					div(class="event_log_entry_edit_editing_info event_log_entry_edit_editing_info_new") {
 						"Creating new entry"
 					}
 				}
 			})
-			div(id="event_log_entry_edit_parent_info") {
-				(if let Some(parent) = props.edit_parent_log_entry.get().as_ref() {
+			(if conflicting_parts.get().is_empty() {
+				view! { ctx, }
+			} else {
+				let draft_entry = draft_entry.get();
+				let incoming_entry = (*incoming_entry.get()).clone().unwrap_or_default();
+				let conflict_rows: Vec<View<G>> = conflicting_parts
+					.get()
+					.iter()
+					.map(|part| {
+						let part = *part;
+						let draft_value = conflict_part_value(part, &draft_entry);
+						let incoming_value = conflict_part_value(part, &incoming_entry);
+						view! {
+							ctx,
+							tr {
+								td(class="event_log_entry_edit_conflict_field") { (entry_part_name(part)) }
+								td(class="event_log_entry_edit_conflict_mine") { (draft_value) }
+								td(class="event_log_entry_edit_conflict_theirs") { (incoming_value) }
+								td(class="event_log_entry_edit_conflict_actions") {
+									button(type="button", on:click=move |_event: WebEvent| take_incoming_part(part)) { "Take theirs" }
+									button(type="button", on:click=move |_event: WebEvent| keep_draft_part(part)) { "Keep mine" }
+								}
+							}
+						}
+					})
+					.collect();
+				let conflict_rows = View::new_fragment(conflict_rows);
+
+				view! {
+					ctx,
+					div(class="event_log_entry_edit_conflict") {
+						div(class="event_log_entry_edit_conflict_header") {
+							"Someone else changed this entry while you were editing it."
+						}
+						table {
+							tr {
+								th { "Field" }
+								th { "Mine" }
+								th { "Theirs" }
+								th {}
+							}
+							(conflict_rows)
+						}
+					}
+				}
+			})
+			div(class="event_log_entry_edit_parent_info") {
+				(if let Some(parent) = edit_parent_log_entry.get().as_ref() {
 					let event_start_time = props.event.get().start_time;
 					let event_entry_types = props.event_entry_types.get();
 					let entry_type_name = parent.entry_type
@@ -1286,16 +1680,12 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 					let description = parent.description.clone();
 
 					let start_time = if let Some(start_time) = parent.start_time {
-						let start_time_duration = start_time - event_start_time;
-						format_duration(&start_time_duration)
+						time_display.get().format_time(start_time, event_start_time)
 					} else {
 						String::new()
 					};
 					let end_time = match parent.end_time {
-						EndTimeData::Time(time) => {
-							let duration = time - props.event.get().start_time;
-							format_duration(&duration)
-						}
+						EndTimeData::Time(time) => time_display.get().format_time(time, event_start_time),
 						EndTimeData::NotEntered => String::new(),
 						EndTimeData::NoTime => String::from("—")
 					};
@@ -1315,57 +1705,81 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 							(description)
 						}
 						div {
-							img(id="event_log_entry_edit_parent_remove", class="click", src="images/remove.png", on:click=remove_parent_handler)
+							img(class="event_log_entry_edit_parent_remove click", src="images/remove.png", on:click=remove_parent_handler)
 						}
 					}
 				} else {
 					view! { ctx, }
 				})
 			}
-			div(id="event_log_entry_edit_basic_info") {
-				div(id="event_log_entry_edit_start_time") {
+			div(class="event_log_entry_edit_basic_info") {
+				(if props.entry_templates.get().is_empty() {
+					view! { ctx, }
+				} else {
+					view! {
+						ctx,
+						div(class="event_log_entry_edit_template") {
+							select(on:change=template_change_handler, title="Fill in the entry from a template") {
+								option(value="") { "Template" }
+								Keyed(
+									iterable=props.entry_templates,
+									key=|template| template.id.clone(),
+									view=|ctx, template| view! { ctx, option(value=template.id) { (template.name) } }
+								)
+							}
+						}
+					}
+				})
+				div(class="event_log_entry_edit_start_time") {
 					input(
 						placeholder="Start",
 						bind:value=start_time_input,
-						id="event_log_entry_edit_start_time_field",
+						id=edit_field_id("event_log_entry_edit_start_time_field", entry_id),
 						class=if start_time_error.get().is_some() { "error" } else { "" },
 						title=(*start_time_error.get()).as_ref().unwrap_or(&String::new())
 					)
 					button(type="button", tabindex=-1, on:click=start_now_handler) { "Now" }
 				}
-				div(id="event_log_entry_edit_end_time") {
+				div(class="event_log_entry_edit_end_time") {
 					input(
 						placeholder="End",
 						bind:value=end_time_input,
-						id="event_log_entry_edit_end_time_field",
+						id=edit_field_id("event_log_entry_edit_end_time_field", entry_id),
 						class=if end_time_error.get().is_some() { "error" } else { "" },
 						title=(*end_time_error.get()).as_ref().unwrap_or(&String::new()),
 						ref=end_field_ref
 					)
 					button(type="button", tabindex=-1, on:click=end_now_handler) { "Now" }
 				}
-				div(id="event_log_entry_edit_type") {
-					input(
-						placeholder="Type",
-						bind:value=entry_type_name,
-						id="event_log_entry_edit_type_field",
-						class=if entry_type_error.get().is_some() { "error" } else { "" },
-						title=(*entry_type_error.get()).as_ref().unwrap_or(&String::new()),
-						list="event_log_entry_edit_type_list",
-						on:blur=entry_type_lost_focus,
-						ref=type_field_ref
+				div(class="event_log_entry_edit_type") {
+					EntryTypeSelect(
+						entry_types=props.event_entry_types,
+						name=entry_type_name,
+						error=entry_type_error,
+						id=edit_field_id("event_log_entry_edit_type_field", entry_id),
+						input_ref=type_field_ref
 					)
 				}
-				div(id="event_log_entry_edit_description") {
-					input(placeholder="Description", bind:value=description, id="event_log_entry_edit_description_field", spellcheck={use_spell_check.get()})
+				div(class="event_log_entry_edit_description") {
+					input(
+						placeholder="Description",
+						bind:value=description,
+						id=edit_field_id("event_log_entry_edit_description_field", entry_id),
+						class=if description_error.get().is_some() { "error" } else { "" },
+						title=(*description_error.get()).as_ref().unwrap_or(&String::new()),
+						spellcheck={use_spell_check.get()}
+					)
+					span(class=if description_error.get().is_some() { "event_log_entry_edit_description_counter error" } else { "event_log_entry_edit_description_counter" }) {
+						(description_counter.get())
+					}
 				}
-				div(id="event_log_entry_edit_submitter_or_winner") {
-					input(bind:value=submitter_or_winner, placeholder="Submitter/winner", id="event_log_entry_edit_submitter_or_winner_field")
+				div(class="event_log_entry_edit_submitter_or_winner") {
+					input(bind:value=submitter_or_winner, placeholder="Submitter/winner", id=edit_field_id("event_log_entry_edit_submitter_or_winner_field", entry_id))
 				}
 			}
-			div(id="event_log_entry_edit_media_links") {
+			div(class="event_log_entry_edit_media_links") {
 				label { "Media links:" }
-				div(id="event_log_entry_edit_media_links_fields") {
+				div(class="event_log_entry_edit_media_links_fields") {
 					Keyed(
 						iterable=media_links_with_index,
 						key=|(index, _)| *index,
@@ -1391,9 +1805,9 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 					}
 				}
 			}
-			div(id="event_log_entry_edit_tags") {
+			div(class="event_log_entry_edit_tags") {
 				label { "Tags:" }
-				div(id="event_log_entry_edit_tags_fields") {
+				div(class="event_log_entry_edit_tags_fields") {
 					Keyed(
 						iterable=tag_names_with_index,
 						key=|(index, _)| *index,
@@ -1417,26 +1831,32 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 							view! {
 								ctx,
 								div {
-									input(bind:value=tag_name_entry, list="event_log_entry_edit_tags_list", title=tag_description.get())
+									TagAutocomplete(
+										tags=suggested_tags,
+										tag_usage=tag_usage,
+										name=tag_name_entry,
+										title=tag_description,
+										id=edit_field_id(&format!("event_log_entry_edit_tag_{}", tag_index), entry_id)
+									)
 								}
 							}
 						}
 					)
 					div {
-						button(type="button", id="event_log_entry_edit_add_tag_button", on:click=add_tag_handler) {
+						button(type="button", id=edit_field_id("event_log_entry_edit_add_tag_button", entry_id), on:click=add_tag_handler) {
 							"Add Tag"
 						}
 					}
 				}
 			}
-			div(id="event_log_entry_edit_new_tags") {
+			div(class="event_log_entry_edit_new_tags") {
 				(if new_tag_names.get().is_empty() {
 					view! { ctx, }
 				} else {
 					view! {
 						ctx,
 						label { "New tags:" }
-						div(id="event_log_entry_edit_new_tags_fields") {
+						div(class="event_log_entry_edit_new_tags_fields") {
 							Indexed(
 								iterable=new_tag_names,
 								view=move |ctx, tag_name| {
@@ -1484,13 +1904,13 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 					}
 				})
 			}
-			div(id="event_log_entry_edit_misc_info") {
-				div(id="event_log_entry_edit_video_edit_state") {
+			div(class="event_log_entry_edit_misc_info") {
+				div(class="event_log_entry_edit_video_edit_state") {
 					button(
 						type="button",
 						class=if *video_edit_state_no_video.get() { "active_button_option" } else { "" },
 						on:click=video_edit_state_set_no_video,
-						id="event_log_entry_edit_video_edit_state_first_button"
+						id=edit_field_id("event_log_entry_edit_video_edit_state_first_button", entry_id)
 					) {
 						"No Video"
 					}
@@ -1509,32 +1929,32 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 						"Done Editing"
 					}
 				}
-				div(id="event_log_entry_edit_poster_moment") {
+				div(class="event_log_entry_edit_poster_moment") {
 					label {
-						input(type="checkbox", id="event_log_entry_edit_poster_moment_checkbox", bind:checked=poster_moment)
+						input(type="checkbox", id=edit_field_id("event_log_entry_edit_poster_moment_checkbox", entry_id), bind:checked=poster_moment)
 						"Poster moment"
 					}
 				}
-				div(id="event_log_entry_edit_notes") {
-					input(id="event_log_entry_edit_notes_field", bind:value=notes, placeholder="Notes", spellcheck={use_spell_check.get()})
+				div(class="event_log_entry_edit_notes") {
+					input(id=edit_field_id("event_log_entry_edit_notes_field", entry_id), bind:value=notes, placeholder="Notes", spellcheck={use_spell_check.get()})
 				}
-				div(id="event_log_entry_edit_editor") {
+				div(class="event_log_entry_edit_editor") {
 					input(
 						bind:value=editor_entry,
 						placeholder="Editor",
-						list="event_log_entry_edit_editors_list",
-						id="event_log_entry_edit_editor_field",
+						list=editors_list_id.clone(),
+						id=edit_field_id("event_log_entry_edit_editor_field", entry_id),
 						class=if editor_error.get().is_some() { "error" } else { "" },
 						title=(*editor_error.get()).as_ref().unwrap_or(&String::new())
 					)
 				}
-				div(id="event_log_entry_edit_incomplete") {
+				div(class="event_log_entry_edit_incomplete") {
 					label {
 						input(type="checkbox", bind:checked=missing_giveaway_information, disabled=*disable_missing_giveaway_info.get())
 						"Needs giveaway results"
 					}
 				}
-				div(id="event_log_entry_edit_sort_key") {
+				div(class="event_log_entry_edit_sort_key") {
 					input(
 						bind:value=sort_key_entry,
 						placeholder="Sort",
@@ -1545,7 +1965,131 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 					)
 				}
 			}
-			div(id="event_log_entry_edit_close") {
+			(if props.custom_fields.get().is_empty() {
+				view! { ctx, }
+			} else {
+				view! {
+					ctx,
+					div(class="event_log_entry_edit_custom_fields") {
+						Keyed(
+							iterable=props.custom_fields,
+							key=|field| {
+								let options = match &field.field_type {
+									CustomFieldType::Select(options) => options.clone(),
+									_ => Vec::new(),
+								};
+								(field.id.clone(), field.name.clone(), options)
+							},
+							view=move |ctx, field| {
+								let field_id = field.id.clone();
+								let value = create_signal(
+									ctx,
+									custom_field_values.get_untracked().get(&field_id).cloned().unwrap_or_default(),
+								);
+								// The field's input and the entry's values are kept in sync in both directions so that
+								// resetting the form or taking incoming changes updates the input.
+								create_effect(ctx, {
+									let field_id = field_id.clone();
+									move || {
+										let entry_value =
+											custom_field_values.get().get(&field_id).cloned().unwrap_or_default();
+										if *value.get_untracked() != entry_value {
+											value.set(entry_value);
+										}
+									}
+								});
+								create_effect(ctx, {
+									let field_id = field_id.clone();
+									move || {
+										let entered_value = (*value.get()).clone();
+										let entry_value =
+											custom_field_values.get_untracked().get(&field_id).cloned().unwrap_or_default();
+										if entered_value != entry_value {
+											custom_field_values.modify().insert(field_id.clone(), entered_value);
+										}
+									}
+								});
+								let value_error = create_memo(ctx, {
+									let field = field.clone();
+									move || field.check_value(&value.get()).err()
+								});
+								let error_class = create_memo(ctx, || if value_error.get().is_some() { "error" } else { "" });
+								let error_title = create_memo(ctx, || (*value_error.get()).clone().unwrap_or_default());
+
+								let field_name = field.name.clone();
+								let field_input = match field.field_type {
+									CustomFieldType::Text => view! {
+										ctx,
+										input(bind:value=value, placeholder=field_name.clone())
+									},
+									CustomFieldType::Number => view! {
+										ctx,
+										input(
+											bind:value=value,
+											placeholder=field_name.clone(),
+											inputmode="decimal",
+											class=error_class.get(),
+											title=error_title.get()
+										)
+									},
+									CustomFieldType::Checkbox => {
+										let checked = create_signal(ctx, *value.get_untracked() == "true");
+										create_effect(ctx, || {
+											let value_checked = *value.get() == "true";
+											if *checked.get_untracked() != value_checked {
+												checked.set(value_checked);
+											}
+										});
+										create_effect(ctx, || {
+											let new_value = if *checked.get() { "true" } else { "" };
+											if *value.get_untracked() != new_value {
+												value.set(String::from(new_value));
+											}
+										});
+										view! {
+											ctx,
+											label {
+												input(type="checkbox", bind:checked=checked)
+												(field_name.clone())
+											}
+										}
+									}
+									CustomFieldType::Select(options) => {
+										let options = View::new_fragment(
+											options
+												.into_iter()
+												.map(|option| {
+													let option_value = option.clone();
+													view! { ctx, option(value=option_value) { (option) } }
+												})
+												.collect(),
+										);
+										view! {
+											ctx,
+											label {
+												(field_name.clone())
+												": "
+												select(bind:value=value) {
+													option(value="")
+													(options)
+												}
+											}
+										}
+									}
+								};
+
+								view! {
+									ctx,
+									div(class="event_log_entry_edit_custom_field") {
+										(field_input)
+									}
+								}
+							}
+						)
+					}
+				}
+			})
+			div(class="event_log_entry_edit_close") {
 				(if *start_time_warning_active.get() {
 					view! {
 						ctx,
@@ -1575,12 +2119,26 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 						view! { ctx, }
 					}
 				})
-				(if let Some(entry) = (*props.editing_log_entry.get()).clone() {
+				(if let Some(entry) = (*pane_entry.get()).clone() {
+					let history_entry_id = entry.id.clone();
 					view! {
 						ctx,
-						div(id="event_log_entry_edit_delete") {
+						div(class="event_log_entry_edit_delete") {
 							(if entry.video_link.is_none() && *props.permission_level.get() == PermissionLevel::Supervisor {
-								if *delete_confirm_signal.get() {
+								let has_children = props
+									.event_log_entries
+									.get()
+									.iter()
+									.any(|check_entry| check_entry.parent.as_ref() == Some(&entry.id));
+								if *delete_confirm_signal.get() && has_children {
+									view! {
+										ctx,
+										"This will really delete this row. What should happen to the rows under it?"
+										button(type="button", on:click=delete_with_children_handler) { "Delete them too" }
+										button(type="button", on:click=delete_confirm_handler) { "Move them to the top level" }
+										button(type="button", on:click=delete_cancel_handler) { "No, keep it!" }
+									}
+								} else if *delete_confirm_signal.get() {
 									view! {
 										ctx,
 										"This will really delete this row. Are you sure?"
@@ -1597,35 +2155,54 @@ pub fn EventLogEntryEdit<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryEditPr
 								view! { ctx, }
 							})
 						}
-						div(id="event_log_entry_id_info") {
+						div(class="event_log_entry_id_info") {
 							"ID: "
 							(entry.id)
 							({
 								if entry.start_time.is_some() {
-									let visible_creation_time = {
-										let creation_duration = entry.created_at - props.event.get().start_time;
-										format_duration(&creation_duration)
-									};
+									let visible_creation_time = time_display
+										.get()
+										.format_time(entry.created_at, props.event.get().start_time);
 									view! {
 										ctx,
 										" Created: "
 										(visible_creation_time)
+										" "
+										button(type="button", class="event_log_entry_edit_history_button", on:click=history_handler) { "History" }
+										" "
+										button(
+											type="button",
+											class="event_log_entry_edit_duplicate_button",
+											title="Start a new entry with this entry's type, description, tags, and parent",
+											on:click=duplicate_handler
+										) {
+											"Duplicate"
+										}
 									}
 								} else {
 									view! { ctx, }
 								}
 							})
 						}
-						div(id="event_log_entry_edit_close_buttons") {
+						div(class="event_log_entry_edit_close_buttons") {
 							button(disabled=*disable_save.get()) { "Save" }
 							button(on:click=cancel_handler) { "Cancel" }
 						}
+						(if *history_open.get() {
+							let entry_id = history_entry_id.clone();
+							view! {
+								ctx,
+								EventLogEntryHistory(entry_id=entry_id, event=props.event, event_entry_types=props.event_entry_types, open=history_open)
+							}
+						} else {
+							view! { ctx, }
+						})
 					}
 				} else {
 					view! {
 						ctx,
-						div(id="event_log_entry_edit_delete")
-						div(id="event_log_entry_edit_close_buttons") {
+						div(class="event_log_entry_edit_delete")
+						div(class="event_log_entry_edit_close_buttons") {
 							button(disabled=*disable_save.get()) { "Add" }
 							button(type="reset", on:click=reset_handler) { "Reset" }
 						}
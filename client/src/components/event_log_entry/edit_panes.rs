@@ -0,0 +1,164 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use stream_log_shared::messages::event_log::EventLogEntry;
+use sycamore::prelude::*;
+
+/// The largest number of entries that can be edited at the same time
+pub const MAX_EDIT_PANES: usize = 3;
+
+/// The editing state of one entry being edited
+#[derive(Clone)]
+pub struct EditPane {
+	pub entry_id: String,
+	pub entry: RcSignal<Option<EventLogEntry>>,
+	pub parent: RcSignal<Option<EventLogEntry>>,
+	/// The entry whose values fill in this pane's new entry, if the pane was opened to duplicate an entry
+	pub duplicate_of: Option<EventLogEntry>,
+}
+
+/// The entries open for editing in the event log, each shown in its own pane
+#[derive(Clone)]
+pub struct EditPanes {
+	/// The open panes in the order they're shown
+	pub panes: RcSignal<Vec<EditPane>>,
+	/// The ID of the entry in the pane that was last used, which is where rows choose parents and where opened entries
+	/// go when no new pane is opened
+	pub active_entry_id: RcSignal<Option<String>>,
+}
+
+impl EditPanes {
+	pub fn new() -> Self {
+		Self {
+			panes: create_rc_signal(Vec::new()),
+			active_entry_id: create_rc_signal(None),
+		}
+	}
+
+	/// Opens an entry for editing. If the entry is already open, its pane becomes active. Otherwise, the entry is opened
+	/// in a new pane if one was requested and there's room for it, or it replaces the entry in the active pane.
+	pub fn open(&self, entry: EventLogEntry, new_pane: bool) {
+		if self.is_editing_untracked(&entry.id) {
+			self.active_entry_id.set(Some(entry.id));
+			return;
+		}
+
+		let pane = EditPane {
+			entry_id: entry.id.clone(),
+			entry: create_rc_signal(Some(entry)),
+			parent: create_rc_signal(None),
+			duplicate_of: None,
+		};
+		self.add_pane(pane, new_pane);
+	}
+
+	/// Opens a new entry in a new pane, filled in with the values of an existing entry
+	pub fn open_duplicate(&self, new_entry: EventLogEntry, source_entry: EventLogEntry) {
+		let pane = EditPane {
+			entry_id: new_entry.id.clone(),
+			entry: create_rc_signal(Some(new_entry)),
+			parent: create_rc_signal(None),
+			duplicate_of: Some(source_entry),
+		};
+		self.add_pane(pane, true);
+	}
+
+	fn add_pane(&self, pane: EditPane, new_pane: bool) {
+		let entry_id = pane.entry_id.clone();
+		let mut panes = self.panes.modify();
+		let active_index = self
+			.active_entry_id
+			.get_untracked()
+			.as_ref()
+			.and_then(|active_id| panes.iter().position(|pane| pane.entry_id == *active_id));
+		match active_index {
+			Some(index) if !new_pane || panes.len() >= MAX_EDIT_PANES => panes[index] = pane,
+			_ if panes.len() >= MAX_EDIT_PANES => {
+				if let Some(last_pane) = panes.last_mut() {
+					*last_pane = pane;
+				}
+			}
+			_ => panes.push(pane),
+		}
+		drop(panes);
+		self.active_entry_id.set(Some(entry_id));
+	}
+
+	/// Closes the pane editing the given entry
+	pub fn close(&self, entry_id: &str) {
+		self.panes.modify().retain(|pane| pane.entry_id != entry_id);
+		let active_entry_id = self.active_entry_id.get_untracked();
+		if (*active_entry_id).as_deref() == Some(entry_id) {
+			let last_entry_id = self.panes.get_untracked().last().map(|pane| pane.entry_id.clone());
+			self.active_entry_id.set(last_entry_id);
+		}
+	}
+
+	/// Closes the panes of any entries that aren't in the given list of entry IDs
+	pub fn close_missing(&self, entry_ids: &[&str]) {
+		let missing_entry_ids: Vec<String> = self
+			.panes
+			.get_untracked()
+			.iter()
+			.filter(|pane| !entry_ids.contains(&pane.entry_id.as_str()))
+			.map(|pane| pane.entry_id.clone())
+			.collect();
+		for entry_id in missing_entry_ids.iter() {
+			self.close(entry_id);
+		}
+	}
+
+	pub fn is_editing(&self, entry_id: &str) -> bool {
+		self.panes.get().iter().any(|pane| pane.entry_id == entry_id)
+	}
+
+	pub fn is_editing_untracked(&self, entry_id: &str) -> bool {
+		self.panes.get_untracked().iter().any(|pane| pane.entry_id == entry_id)
+	}
+
+	pub fn has_open_panes(&self) -> bool {
+		!self.panes.get().is_empty()
+	}
+
+	pub fn pane(&self, entry_id: &str) -> Option<EditPane> {
+		self.panes
+			.get_untracked()
+			.iter()
+			.find(|pane| pane.entry_id == entry_id)
+			.cloned()
+	}
+
+	pub fn set_active(&self, entry_id: &str) {
+		if (*self.active_entry_id.get_untracked()).as_deref() != Some(entry_id) {
+			self.active_entry_id.set(Some(entry_id.to_string()));
+		}
+	}
+
+	/// Sets the parent of the entry in the active pane. An entry can't be made its own parent.
+	pub fn choose_parent_for_active(&self, parent: Option<EventLogEntry>) {
+		let active_entry_id = self.active_entry_id.get_untracked();
+		let Some(active_entry_id) = (*active_entry_id).as_ref() else {
+			return;
+		};
+		if parent.as_ref().map(|parent| &parent.id) == Some(active_entry_id) {
+			return;
+		}
+		if let Some(pane) = self.pane(active_entry_id) {
+			pane.parent.set(parent);
+		}
+	}
+}
+
+impl Default for EditPanes {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Gets the element ID of a field in the edit pane for the given entry
+pub fn edit_field_id(field_id: &str, entry_id: &str) -> String {
+	format!("{}_{}", field_id, entry_id)
+}
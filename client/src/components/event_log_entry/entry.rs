@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::edit_panes::EditPanes;
 use super::row::EventLogEntryRow;
 use super::typing::EventLogEntryTyping;
 use super::UserTypingData;
@@ -11,6 +12,7 @@ use crate::subscriptions::event::EventSubscriptionSignals;
 use std::collections::HashMap;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::FromClientMessage;
 use sycamore::prelude::*;
 
 #[derive(Prop)]
@@ -19,13 +21,14 @@ pub struct EventLogEntryProps<'a> {
 	jump_highlight_row_id: &'a Signal<String>,
 	event_subscription_data: EventSubscriptionSignals,
 	can_edit: &'a ReadSignal<bool>,
-	editing_log_entry: &'a Signal<Option<EventLogEntry>>,
+	edit_panes: &'a EditPanes,
 	read_entry_types_signal: &'a ReadSignal<Vec<EntryType>>,
-	editing_entry_parent: &'a Signal<Option<EventLogEntry>>,
 	entries_by_parent: &'a ReadSignal<HashMap<String, Vec<EventLogEntry>>>,
 	child_depth: u32,
-	entry_numbers: &'a ReadSignal<HashMap<String, usize>>,
 	use_editor_view: &'a ReadSignal<bool>,
+	quick_edit: &'a ReadSignal<bool>,
+	save_message_queue: &'a Signal<Vec<FromClientMessage>>,
+	dragged_entry: &'a Signal<Option<EventLogEntry>>,
 }
 
 #[component]
@@ -109,11 +112,12 @@ pub fn EventLogEntry<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryProps<'a>)
 			can_edit=can_edit,
 			entry_type=entry_type,
 			jump_highlight_row_id=props.jump_highlight_row_id,
-			editing_log_entry=props.editing_log_entry,
-			editing_entry_parent=props.editing_entry_parent,
+			edit_panes=props.edit_panes,
 			child_depth=props.child_depth,
-			entry_numbers=props.entry_numbers,
-			use_editor_view=props.use_editor_view
+			use_editor_view=props.use_editor_view,
+			quick_edit=props.quick_edit,
+			save_message_queue=props.save_message_queue,
+			dragged_entry=props.dragged_entry
 		)
 		EventLogEntryTyping(
 			event=event_signal,
@@ -137,13 +141,14 @@ pub fn EventLogEntry<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryProps<'a>)
 								jump_highlight_row_id=props.jump_highlight_row_id,
 								event_subscription_data=event_subscription_data,
 								can_edit=can_edit,
-								editing_log_entry=props.editing_log_entry,
+								edit_panes=props.edit_panes,
 								read_entry_types_signal=props.read_entry_types_signal,
-								editing_entry_parent=props.editing_entry_parent,
 								entries_by_parent=props.entries_by_parent,
 								child_depth=props.child_depth + 1,
-								entry_numbers=props.entry_numbers,
-								use_editor_view=props.use_editor_view
+								use_editor_view=props.use_editor_view,
+								quick_edit=props.quick_edit,
+								save_message_queue=props.save_message_queue,
+								dragged_entry=props.dragged_entry
 							)
 						}
 					}
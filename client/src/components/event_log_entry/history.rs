@@ -0,0 +1,332 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::utils::time_display_for_user;
+use crate::subscriptions::errors::ErrorData;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use chrono::{DateTime, Utc};
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::event_log::{
+	EndTimeData, EntryChangeSource, EventLogEntry, EventLogEntryRevision, VideoEditState,
+};
+use stream_log_shared::messages::event_subscription::ModifiedEventLogEntryParts;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::time_display::TimeDisplay;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use web_sys::Event as WebEvent;
+
+#[derive(Prop)]
+pub struct EventLogEntryHistoryProps<'a> {
+	entry_id: String,
+	event: &'a ReadSignal<Event>,
+	event_entry_types: &'a ReadSignal<Vec<EntryType>>,
+	open: &'a Signal<bool>,
+}
+
+/// A single field changed in a revision
+#[derive(Clone, Eq, PartialEq)]
+struct FieldChange {
+	field: &'static str,
+	old_value: String,
+	new_value: String,
+}
+
+/// A revision as shown in the history timeline
+#[derive(Clone, Eq, PartialEq)]
+struct HistoryTimelineItem {
+	edit_time: DateTime<Utc>,
+	changed_by: String,
+	created: bool,
+	deleted: bool,
+	changes: Vec<FieldChange>,
+}
+
+fn end_time_value(end_time: &EndTimeData, time_display: &TimeDisplay, event_start: DateTime<Utc>) -> String {
+	match end_time {
+		EndTimeData::Time(time) => time_display.format_time(*time, event_start),
+		EndTimeData::NotEntered => String::new(),
+		EndTimeData::NoTime => String::from("—"),
+	}
+}
+
+fn video_edit_state_value(state: VideoEditState) -> String {
+	match state {
+		VideoEditState::NoVideo => String::from("No video"),
+		VideoEditState::MarkedForEditing => String::from("Marked for editing"),
+		VideoEditState::DoneEditing => String::from("Done editing"),
+	}
+}
+
+fn yes_no(value: bool) -> String {
+	String::from(if value { "Yes" } else { "No" })
+}
+
+/// Gets the displayed value of a part of an entry
+pub fn entry_part_value(
+	part: ModifiedEventLogEntryParts,
+	entry: &EventLogEntry,
+	time_display: &TimeDisplay,
+	event_start: DateTime<Utc>,
+	entry_types: &[EntryType],
+) -> String {
+	match part {
+		ModifiedEventLogEntryParts::StartTime => entry
+			.start_time
+			.map(|start_time| time_display.format_time(start_time, event_start))
+			.unwrap_or_default(),
+		ModifiedEventLogEntryParts::EndTime => end_time_value(&entry.end_time, time_display, event_start),
+		ModifiedEventLogEntryParts::EntryType => entry
+			.entry_type
+			.as_ref()
+			.map(|entry_type_id| {
+				entry_types
+					.iter()
+					.find(|entry_type| entry_type.id == *entry_type_id)
+					.map(|entry_type| entry_type.name.clone())
+					.unwrap_or_else(|| entry_type_id.clone())
+			})
+			.unwrap_or_default(),
+		ModifiedEventLogEntryParts::Description => entry.description.clone(),
+		ModifiedEventLogEntryParts::MediaLinks => entry.media_links.join("\n"),
+		ModifiedEventLogEntryParts::SubmitterOrWinner => entry.submitter_or_winner.clone(),
+		ModifiedEventLogEntryParts::Tags => {
+			let tags: Vec<&str> = entry.tags.iter().map(|tag| tag.name.as_str()).collect();
+			tags.join(", ")
+		}
+		ModifiedEventLogEntryParts::VideoEditState => video_edit_state_value(entry.video_edit_state),
+		ModifiedEventLogEntryParts::PosterMoment => yes_no(entry.poster_moment),
+		ModifiedEventLogEntryParts::Notes => entry.notes.clone(),
+		ModifiedEventLogEntryParts::Editor => entry
+			.editor
+			.as_ref()
+			.map(|editor| editor.username.clone())
+			.unwrap_or_default(),
+		ModifiedEventLogEntryParts::MissingGiveawayInfo => yes_no(entry.missing_giveaway_information),
+		ModifiedEventLogEntryParts::SortKey => entry.manual_sort_key.map(|key| key.to_string()).unwrap_or_default(),
+		ModifiedEventLogEntryParts::Parent => entry.parent.clone().unwrap_or_default(),
+		ModifiedEventLogEntryParts::CustomFields => {
+			let mut values: Vec<&str> = entry.custom_fields.values().map(|value| value.as_str()).collect();
+			values.sort_unstable();
+			values.join(", ")
+		}
+	}
+}
+
+/// Gets the displayed value of each field tracked in the history for an entry
+fn field_values(
+	entry: &EventLogEntry,
+	time_display: &TimeDisplay,
+	event_start: DateTime<Utc>,
+	entry_types: &[EntryType],
+) -> Vec<(&'static str, String)> {
+	let part_value =
+		|part: ModifiedEventLogEntryParts| entry_part_value(part, entry, time_display, event_start, entry_types);
+
+	vec![
+		("Start", part_value(ModifiedEventLogEntryParts::StartTime)),
+		("End", part_value(ModifiedEventLogEntryParts::EndTime)),
+		("Type", part_value(ModifiedEventLogEntryParts::EntryType)),
+		("Description", part_value(ModifiedEventLogEntryParts::Description)),
+		("Media links", part_value(ModifiedEventLogEntryParts::MediaLinks)),
+		(
+			"Submitter/winner",
+			part_value(ModifiedEventLogEntryParts::SubmitterOrWinner),
+		),
+		("Tags", part_value(ModifiedEventLogEntryParts::Tags)),
+		(
+			"Video edit state",
+			part_value(ModifiedEventLogEntryParts::VideoEditState),
+		),
+		("Poster moment", part_value(ModifiedEventLogEntryParts::PosterMoment)),
+		("Notes to editor", part_value(ModifiedEventLogEntryParts::Notes)),
+		("Editor", part_value(ModifiedEventLogEntryParts::Editor)),
+		(
+			"Missing giveaway info",
+			part_value(ModifiedEventLogEntryParts::MissingGiveawayInfo),
+		),
+		("Sort key", part_value(ModifiedEventLogEntryParts::SortKey)),
+		("Parent", part_value(ModifiedEventLogEntryParts::Parent)),
+		("Video link", entry.video_link.clone().unwrap_or_default()),
+		("Video processing state", entry.video_processing_state.to_string()),
+		("Video errors", entry.video_errors.clone()),
+	]
+}
+
+/// Builds the timeline of changes from the list of revisions, which is ordered oldest first
+fn build_timeline(
+	revisions: &[EventLogEntryRevision],
+	time_display: &TimeDisplay,
+	event_start: DateTime<Utc>,
+	entry_types: &[EntryType],
+) -> Vec<HistoryTimelineItem> {
+	let mut timeline = Vec::with_capacity(revisions.len());
+	let mut previous_values: Option<Vec<(&'static str, String)>> = None;
+	let mut previously_deleted = false;
+
+	for revision in revisions.iter() {
+		let values = field_values(&revision.entry, time_display, event_start, entry_types);
+		let changes: Vec<FieldChange> = match previous_values.as_ref() {
+			Some(previous_values) => previous_values
+				.iter()
+				.zip(values.iter())
+				.filter(|((_, old_value), (_, new_value))| old_value != new_value)
+				.map(|((field, old_value), (_, new_value))| FieldChange {
+					field: *field,
+					old_value: old_value.clone(),
+					new_value: new_value.clone(),
+				})
+				.collect(),
+			None => values
+				.iter()
+				.filter(|(_, value)| !value.is_empty())
+				.map(|(field, value)| FieldChange {
+					field: *field,
+					old_value: String::new(),
+					new_value: value.clone(),
+				})
+				.collect(),
+		};
+		let changed_by = match &revision.changed_by {
+			EntryChangeSource::User(user) => user.username.clone(),
+			EntryChangeSource::Application(name) => format!("{} (application)", name),
+			EntryChangeSource::Unknown => String::from("Unknown"),
+		};
+
+		timeline.push(HistoryTimelineItem {
+			edit_time: revision.edit_time,
+			changed_by,
+			created: previous_values.is_none(),
+			deleted: revision.deleted && !previously_deleted,
+			changes,
+		});
+		previous_values = Some(values);
+		previously_deleted = revision.deleted;
+	}
+
+	timeline.reverse();
+	timeline
+}
+
+#[component]
+pub fn EventLogEntryHistory<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryHistoryProps<'a>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+	let entry_id = create_ref(ctx, props.entry_id);
+
+	data.entry_history.set(None);
+	spawn_local_scoped(ctx, async move {
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+
+		let message = FromClientMessage::EventLogEntryHistory(entry_id.clone());
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize entry history request.",
+					error,
+				));
+				return;
+			}
+		};
+		if let Err(error) = ws.send(Message::Text(message_json)).await {
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to send entry history request.",
+				error,
+			));
+		}
+	});
+
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+	let timeline = create_memo(ctx, move || {
+		let entry_history = data.entry_history.get();
+		let event = props.event.get();
+		match entry_history.as_ref() {
+			Some((history_entry_id, revisions)) if history_entry_id == entry_id => Some(build_timeline(
+				revisions,
+				&time_display_for_user(&user.get(), &event),
+				event.start_time,
+				&props.event_entry_types.get(),
+			)),
+			_ => None,
+		}
+	});
+	let timeline_items = create_memo(ctx, || (*timeline.get()).clone().unwrap_or_default());
+
+	let close_handler = move |_event: WebEvent| {
+		props.open.set(false);
+	};
+
+	view! {
+		ctx,
+		div(class="event_log_entry_history_overlay") {
+			div(id="event_log_entry_history") {
+				div(id="event_log_entry_history_header") {
+					h2 { "Entry History" }
+					button(type="button", on:click=close_handler) { "Close" }
+				}
+				(if timeline.get().is_none() {
+					view! { ctx, "Loading history..." }
+				} else if timeline_items.get().is_empty() {
+					view! { ctx, "No history has been recorded for this entry." }
+				} else {
+					view! {
+						ctx,
+						ol(id="event_log_entry_history_timeline") {
+							Indexed(
+								iterable=timeline_items,
+								view=|ctx, item| {
+									let edit_time = format!("{}", item.edit_time.format("%Y-%m-%d %H:%M:%S UTC"));
+									let action = if item.created {
+										"Created by"
+									} else if item.deleted {
+										"Deleted by"
+									} else {
+										"Changed by"
+									};
+									let changes = create_signal(ctx, item.changes);
+
+									view! {
+										ctx,
+										li(class="event_log_entry_history_revision") {
+											div(class="event_log_entry_history_revision_info") {
+												span(class="event_log_entry_history_revision_time") { (edit_time) }
+												" "
+												(action)
+												" "
+												span(class="event_log_entry_history_revision_user") { (item.changed_by) }
+											}
+											table(class="event_log_entry_history_changes") {
+												Indexed(
+													iterable=changes,
+													view=|ctx, change| {
+														view! {
+															ctx,
+															tr {
+																td(class="event_log_entry_history_field") { (change.field) }
+																td(class="event_log_entry_history_old_value") { (change.old_value) }
+																td(class="event_log_entry_history_new_value") { (change.new_value) }
+															}
+														}
+													}
+												)
+											}
+										}
+									}
+								}
+							)
+						}
+					}
+				})
+			}
+		}
+	}
+}
@@ -9,9 +9,11 @@ use std::collections::HashMap;
 use stream_log_shared::messages::user::PublicUserData;
 
 pub mod edit;
+pub mod edit_panes;
 pub mod entry;
+pub mod history;
 pub mod row;
 pub mod typing;
-mod utils;
+pub mod utils;
 
 pub type UserTypingData = (PublicUserData, HashMap<TypingTarget, String>);
@@ -4,16 +4,46 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::utils::format_duration;
+use super::edit_panes::{edit_field_id, EditPanes};
+use super::utils::{entry_exceeds_expected_duration, entry_matches_filters, time_display_for_user};
 use crate::color_utils::rgb_str_from_color;
+use crate::components::user_avatar::UserAvatar;
 use crate::entry_type_colors::use_white_foreground;
 use crate::subscriptions::event::EventSubscriptionSignals;
-use std::collections::HashMap;
+use chrono::Utc;
+use stream_log_shared::messages::custom_fields::CustomFieldType;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry, VideoEditState};
+use stream_log_shared::messages::event_subscription::{EventSubscriptionUpdate, ModifiedEventLogEntryParts};
+use stream_log_shared::messages::subscriptions::SubscriptionTargetUpdate;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
 use sycamore::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{window, Event as WebEvent, HtmlElement};
+use web_sys::{window, DragEvent, Element, Event as WebEvent, HtmlElement, KeyboardEvent, MouseEvent, Node};
+
+/// Edit form fields focused by clicking the cells that quick edit mode edits in place instead
+const QUICK_EDIT_FOCUS_IDS: [&str; 3] = [
+	"event_log_entry_edit_end_time_field",
+	"event_log_entry_edit_description_field",
+	"event_log_entry_edit_video_edit_state_first_button",
+];
+
+/// Where a dragged entry is dropped relative to the row it's dropped on
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum DropPosition {
+	Before,
+	Child,
+	After,
+}
+
+/// The cells of a row that can be edited in place in quick edit mode
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum QuickEditCell {
+	EndTime,
+	Description,
+	VideoEditState,
+}
 
 #[derive(Prop)]
 pub struct EventLogEntryRowProps<'a> {
@@ -22,20 +52,19 @@ pub struct EventLogEntryRowProps<'a> {
 	can_edit: &'a ReadSignal<bool>,
 	entry_type: &'a ReadSignal<Option<EntryType>>,
 	jump_highlight_row_id: &'a Signal<String>,
-	editing_log_entry: &'a Signal<Option<EventLogEntry>>,
-	editing_entry_parent: &'a Signal<Option<EventLogEntry>>,
+	edit_panes: &'a EditPanes,
 	child_depth: u32,
-	entry_numbers: &'a ReadSignal<HashMap<String, usize>>,
 	use_editor_view: &'a ReadSignal<bool>,
+	quick_edit: &'a ReadSignal<bool>,
+	save_message_queue: &'a Signal<Vec<FromClientMessage>>,
+	dragged_entry: &'a Signal<Option<EventLogEntry>>,
 }
 
 #[component]
 pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProps<'a>) -> View<G> {
-	let row_is_being_edited = create_memo(ctx, || {
-		match ((*props.entry.get()).as_ref(), (*props.editing_log_entry.get()).as_ref()) {
-			(Some(row_entry), Some(edit_entry)) => row_entry.id == edit_entry.id,
-			_ => false,
-		}
+	let row_is_being_edited = create_memo(ctx, || match (*props.entry.get()).as_ref() {
+		Some(row_entry) => props.edit_panes.is_editing(&row_entry.id),
+		None => false,
 	});
 
 	let mut child_indicators = Vec::new();
@@ -48,6 +77,14 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 	}
 	let child_indicators = View::new_fragment(child_indicators);
 
+	let time_display = create_memo(ctx, {
+		let event_signal = props.event_subscription_data.event.clone();
+		move || {
+			let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+			time_display_for_user(&user.get(), &event_signal.get())
+		}
+	});
+
 	let start_time = create_memo(ctx, {
 		let event_start = props.event_subscription_data.event.get().start_time;
 		move || {
@@ -55,8 +92,7 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 				return String::new();
 			};
 			if let Some(start_time) = entry.start_time {
-				let start_time_duration = start_time - event_start;
-				format_duration(&start_time_duration)
+				time_display.get().format_time(start_time, event_start)
 			} else {
 				String::new()
 			}
@@ -70,10 +106,7 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 				return String::new();
 			};
 			match entry.end_time {
-				EndTimeData::Time(time) => {
-					let end_time_duration = time - event_start;
-					format_duration(&end_time_duration)
-				}
+				EndTimeData::Time(time) => time_display.get().format_time(time, event_start),
 				EndTimeData::NotEntered => String::new(),
 				EndTimeData::NoTime => String::from("—"),
 			}
@@ -111,10 +144,41 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 	});
 
 	let media_links = create_memo(ctx, || {
-		(*props.entry.get())
+		let links: Vec<(String, bool)> = (*props.entry.get())
 			.as_ref()
-			.map(|entry| entry.media_links.clone())
-			.unwrap_or_default()
+			.map(|entry| {
+				entry
+					.media_links
+					.iter()
+					.map(|link| (link.clone(), entry.dead_links.contains(link)))
+					.collect()
+			})
+			.unwrap_or_default();
+		links
+	});
+
+	// Custom field values are shown with the description so that events with different fields share the same columns
+	let custom_field_values = create_memo(ctx, {
+		let custom_fields = props.event_subscription_data.custom_fields.clone();
+		move || {
+			let entry = props.entry.get();
+			let Some(entry) = entry.as_ref() else {
+				return Vec::new();
+			};
+			let values: Vec<(String, String)> = custom_fields
+				.get()
+				.iter()
+				.filter_map(|field| {
+					let value = entry.custom_fields.get(&field.id)?;
+					let value = match field.field_type {
+						CustomFieldType::Checkbox => String::from("Yes"),
+						_ => value.clone(),
+					};
+					Some((field.name.clone(), value))
+				})
+				.collect();
+			values
+		}
 	});
 
 	let tags_signal = create_signal(
@@ -133,13 +197,13 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 
 	let is_secure_context = window().map(|window| window.is_secure_context()).unwrap_or(false);
 
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
 	let row_is_visible = create_memo(ctx, {
-		let video_edit_state_filters = props.event_subscription_data.video_edit_state_filters.clone();
-		let video_processing_state_filters = props.event_subscription_data.video_processing_state_filters.clone();
+		let event_subscription_data = props.event_subscription_data.clone();
 		move || {
 			let entry = props.entry.get();
-			let video_edit_state_filters = video_edit_state_filters.get();
-			let video_processing_state_filters = video_processing_state_filters.get();
+			let filters = event_subscription_data.filters();
+			let entry_types = event_subscription_data.entry_types.get();
 
 			let entry = if let Some(entry) = entry.as_ref() {
 				entry
@@ -147,10 +211,46 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 				return false;
 			};
 
-			(video_edit_state_filters.is_empty() || video_edit_state_filters.contains(&entry.video_edit_state))
-				&& (video_processing_state_filters.is_empty()
-					|| video_processing_state_filters.contains(&entry.video_processing_state))
+			entry_matches_filters(entry, &filters, &user.get(), &entry_types)
+		}
+	});
+
+	// Entries being edited by someone else are marked, and opening them takes a second click so users don't step on
+	// each other's changes by accident
+	let other_user_lock = create_memo(ctx, {
+		let entry_locks = props.event_subscription_data.entry_locks.clone();
+		move || {
+			let entry = props.entry.get();
+			let entry_locks = entry_locks.get();
+			let user = user.get();
+			let entry_id = (*entry).as_ref()?.id.as_str();
+			entry_locks
+				.get(entry_id)
+				.filter(|lock| (*user).as_ref().map(|user| user.id != lock.user.id).unwrap_or(true))
+				.cloned()
+		}
+	});
+	let locked_open_requested = create_signal(ctx, false);
+	create_effect(ctx, || {
+		other_user_lock.track();
+		locked_open_requested.set(false);
+	});
+
+	let exceeds_expected_duration = create_memo(ctx, || {
+		(*props.entry.get())
+			.as_ref()
+			.map(|entry| entry_exceeds_expected_duration(entry, (*props.entry_type.get()).as_ref()))
+			.unwrap_or(false)
+	});
+	let end_time_title = create_memo(ctx, || {
+		if !*exceeds_expected_duration.get() {
+			return String::new();
 		}
+		(*props.entry_type.get())
+			.as_ref()
+			.and_then(|entry_type| entry_type.expected_duration_minutes)
+			.map(|minutes| format!("Longer than the expected {} minutes", minutes))
+			.unwrap_or_default()
 	});
 
 	let prevent_row_click_handler = |event: WebEvent| {
@@ -159,7 +259,270 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 
 	let parent_select_handler = move |event: WebEvent| {
 		event.stop_propagation();
-		props.editing_entry_parent.set((*props.entry.get()).clone());
+		props.edit_panes.choose_parent_for_active((*props.entry.get()).clone());
+	};
+
+	let quick_edit_cell: &Signal<Option<QuickEditCell>> = create_signal(ctx, None);
+	let quick_edit_value = create_signal(ctx, String::new());
+	let quick_edit_error: &Signal<Option<String>> = create_signal(ctx, None);
+	// The entry as it was when the quick edit started, so the server can tell if someone else changed the same value in
+	// the meantime
+	let quick_edit_base_entry: &Signal<Option<EventLogEntry>> = create_signal(ctx, None);
+	let quick_edit_id = create_memo(ctx, || {
+		(*props.entry.get())
+			.as_ref()
+			.map(|entry| format!("event_log_entry_quick_edit_{}", entry.id))
+			.unwrap_or_default()
+	});
+
+	create_effect(ctx, || {
+		if !*props.quick_edit.get() || !*props.can_edit.get() {
+			quick_edit_cell.set(None);
+		}
+	});
+
+	let quick_edit_start_handler = move |cell: QuickEditCell| {
+		move |_event: WebEvent| {
+			if !*props.quick_edit.get_untracked() || !*props.can_edit.get_untracked() {
+				return;
+			}
+			let Some(entry) = (*props.entry.get_untracked()).clone() else {
+				return;
+			};
+			let value = match cell {
+				QuickEditCell::EndTime => (*end_time.get_untracked()).clone(),
+				QuickEditCell::Description => entry.description.clone(),
+				QuickEditCell::VideoEditState => String::from(video_edit_state_value(entry.video_edit_state)),
+			};
+			quick_edit_value.set(value);
+			quick_edit_error.set(None);
+			quick_edit_base_entry.set(Some(entry));
+			quick_edit_cell.set(Some(cell));
+
+			if let Some(window) = window() {
+				if let Some(document) = window.document() {
+					if let Some(element) = document.get_element_by_id(&quick_edit_id.get_untracked()) {
+						let html_element: HtmlElement = element.unchecked_into();
+						let _ = html_element.focus();
+					}
+				}
+			}
+		}
+	};
+
+	// Applies the value being edited to the entry and sends only the edited part. Returns false if the value couldn't
+	// be applied, in which case the error is shown on the cell.
+	let save_quick_edit = move || -> bool {
+		let Some(cell) = *quick_edit_cell.get_untracked() else {
+			return true;
+		};
+		let Some(mut entry) = (*props.entry.get_untracked()).clone() else {
+			quick_edit_cell.set(None);
+			return true;
+		};
+		let event = (*props.event_subscription_data.event.get_untracked()).clone();
+		let value = (*quick_edit_value.get_untracked()).clone();
+
+		let part = match cell {
+			QuickEditCell::EndTime => {
+				let end_time = if value.is_empty() {
+					EndTimeData::NotEntered
+				} else if value.chars().all(|c| c == '-') {
+					let requires_end_time = (*props.entry_type.get_untracked())
+						.as_ref()
+						.map(|entry_type| entry_type.require_end_time)
+						.unwrap_or(false);
+					if requires_end_time {
+						quick_edit_error.set(Some(String::from("The entry's type requires an end time")));
+						return false;
+					}
+					EndTimeData::NoTime
+				} else {
+					let reference_time = entry.start_time.unwrap_or_else(Utc::now);
+					match time_display
+						.get_untracked()
+						.parse_time(&value, event.start_time, reference_time)
+					{
+						Ok(time) => EndTimeData::Time(time),
+						Err(error) => {
+							quick_edit_error.set(Some(error));
+							return false;
+						}
+					}
+				};
+				if end_time == entry.end_time {
+					quick_edit_cell.set(None);
+					return true;
+				}
+				entry.end_time = end_time;
+				ModifiedEventLogEntryParts::EndTime
+			}
+			QuickEditCell::Description => {
+				if let Err(error) = event.check_description_length(&value) {
+					quick_edit_error.set(Some(error));
+					return false;
+				}
+				if value == entry.description {
+					quick_edit_cell.set(None);
+					return true;
+				}
+				entry.description = value;
+				ModifiedEventLogEntryParts::Description
+			}
+			QuickEditCell::VideoEditState => {
+				let video_edit_state = VideoEditState::all_states()
+					.into_iter()
+					.find(|state| video_edit_state_value(*state) == value)
+					.unwrap_or_default();
+				if video_edit_state == entry.video_edit_state {
+					quick_edit_cell.set(None);
+					return true;
+				}
+				entry.video_edit_state = video_edit_state;
+				ModifiedEventLogEntryParts::VideoEditState
+			}
+		};
+
+		quick_edit_cell.set(None);
+		let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+			event,
+			Box::new(EventSubscriptionUpdate::UpdateLogEntry(
+				entry,
+				vec![part],
+				(*quick_edit_base_entry.get_untracked())
+					.as_ref()
+					.and_then(|entry| entry.history_id.clone()),
+			)),
+		)));
+		props.save_message_queue.modify().push(message);
+		true
+	};
+
+	let quick_edit_keydown_handler = move |event: WebEvent| {
+		let key_event: KeyboardEvent = event.unchecked_into();
+		match key_event.key().as_str() {
+			"Enter" => {
+				key_event.prevent_default();
+				save_quick_edit();
+			}
+			"Escape" => {
+				key_event.prevent_default();
+				quick_edit_cell.set(None);
+			}
+			_ => (),
+		}
+	};
+
+	let quick_edit_change_handler = move |_event: WebEvent| {
+		save_quick_edit();
+	};
+
+	// Leaving the cell keeps a valid value and discards an invalid one
+	let quick_edit_blur_handler = move |_event: WebEvent| {
+		if !save_quick_edit() {
+			quick_edit_cell.set(None);
+		}
+	};
+
+	let quick_edit_input_class = create_memo(ctx, || {
+		if quick_edit_error.get().is_some() {
+			"log_entry_quick_edit log_entry_quick_edit_error"
+		} else {
+			"log_entry_quick_edit"
+		}
+	});
+	let quick_edit_title = create_memo(ctx, || (*quick_edit_error.get()).clone().unwrap_or_default());
+
+	let drop_position: &Signal<Option<DropPosition>> = create_signal(ctx, None);
+	create_effect(ctx, || {
+		if props.dragged_entry.get().is_none() {
+			drop_position.set(None);
+		}
+	});
+
+	let drag_start_handler = move |event: WebEvent| {
+		let entry = (*props.entry.get_untracked()).clone();
+		let Some(entry) = entry.filter(|entry| entry.start_time.is_some() && *props.can_edit.get_untracked()) else {
+			event.prevent_default();
+			return;
+		};
+		let drag_event: DragEvent = event.unchecked_into();
+		if let Some(data_transfer) = drag_event.data_transfer() {
+			data_transfer.set_effect_allowed("move");
+			// Some browsers don't start the drag unless it carries some data
+			let _ = data_transfer.set_data("text/plain", &entry.id);
+		}
+		props.dragged_entry.set(Some(entry));
+	};
+
+	let drag_end_handler = move |_event: WebEvent| {
+		props.dragged_entry.set(None);
+	};
+
+	let can_drop_dragged_entry = move || -> bool {
+		if !*props.can_edit.get_untracked() {
+			return false;
+		}
+		let dragged_entry = props.dragged_entry.get_untracked();
+		let Some(dragged_entry) = (*dragged_entry).as_ref() else {
+			return false;
+		};
+		let entry = props.entry.get_untracked();
+		let Some(entry) = (*entry).as_ref() else {
+			return false;
+		};
+		if entry.start_time.is_none() || entry.id == dragged_entry.id {
+			return false;
+		}
+		let log_entries = props.event_subscription_data.event_log_entries.get_untracked();
+		!entry_has_ancestor(&log_entries, entry, &dragged_entry.id)
+	};
+
+	let drag_over_handler = move |event: WebEvent| {
+		if !can_drop_dragged_entry() {
+			return;
+		}
+		let Some(position) = drop_position_for_event(&event) else {
+			return;
+		};
+		event.prevent_default();
+		if *drop_position.get_untracked() != Some(position) {
+			drop_position.set(Some(position));
+		}
+	};
+
+	let drag_leave_handler = move |_event: WebEvent| {
+		drop_position.set(None);
+	};
+
+	let drop_handler = move |event: WebEvent| {
+		event.prevent_default();
+		let position = *drop_position.get_untracked();
+		let dragged_entry = (*props.dragged_entry.get_untracked()).clone();
+		props.dragged_entry.set(None);
+
+		let (Some(position), Some(dragged_entry)) = (position, dragged_entry) else {
+			return;
+		};
+		let Some(entry) = (*props.entry.get_untracked()).clone() else {
+			return;
+		};
+
+		let event = (*props.event_subscription_data.event.get_untracked()).clone();
+		let log_entries = props.event_subscription_data.event_log_entries.get_untracked();
+		let mut save_message_queue = props.save_message_queue.modify();
+		for (updated_entry, modified_parts) in entry_move_updates(&log_entries, &dragged_entry, &entry, position) {
+			save_message_queue.push(FromClientMessage::SubscriptionMessage(Box::new(
+				SubscriptionTargetUpdate::EventUpdate(
+					event.clone(),
+					Box::new(EventSubscriptionUpdate::UpdateLogEntry(
+						updated_entry,
+						modified_parts,
+						None,
+					)),
+				),
+			)));
+		}
 	};
 
 	view! {
@@ -169,16 +532,35 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 
 			let row_click_handler_for_id = move |focus_element_id: &str| {
 				let focus_element_id = focus_element_id.to_string();
-				move |_event: WebEvent| {
+				move |event: WebEvent| {
 					if any_text_is_selected() {
 						return;
 					}
-					let entry = (*props.entry.get()).clone();
-					props.editing_log_entry.set(entry);
+					// In quick edit mode, cells that can be edited in place do so on double click instead
+					if *props.quick_edit.get_untracked() && QUICK_EDIT_FOCUS_IDS.contains(&focus_element_id.as_str()) {
+						return;
+					}
+					let Some(entry) = (*props.entry.get()).clone() else {
+						return;
+					};
+					if other_user_lock.get_untracked().is_some()
+						&& !props.edit_panes.is_editing_untracked(&entry.id)
+						&& !*locked_open_requested.get_untracked()
+					{
+						locked_open_requested.set(true);
+						return;
+					}
+					locked_open_requested.set(false);
+					// Holding Ctrl (or Cmd) while clicking opens the entry next to the ones already being edited
+					let mouse_event: MouseEvent = event.unchecked_into();
+					let new_pane = mouse_event.ctrl_key() || mouse_event.meta_key();
+					let entry_id = entry.id.clone();
+					props.edit_panes.open(entry, new_pane);
 					props.jump_highlight_row_id.set(String::new());
 					if !focus_element_id.is_empty() {
 						if let Some(window) = window() {
 							if let Some(document) = window.document() {
+								let focus_element_id = edit_field_id(&focus_element_id, &entry_id);
 								if let Some(element) = document.get_element_by_id(&focus_element_id) {
 									let html_element: HtmlElement = element.unchecked_into();
 									let _ = html_element.focus();
@@ -218,6 +600,10 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 							row_class = format!("{} log_entry_end_highlight", row_class);
 						}
 
+						if *exceeds_expected_duration.get() {
+							row_class = format!("{} log_entry_over_expected_duration", row_class);
+						}
+
 						if *props.can_edit.get() {
 							row_class = format!("{} click", row_class);
 						}
@@ -232,32 +618,44 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 
 						if *row_is_being_edited.get() {
 							row_class = format!("{} event_log_entry_edit_highlight", row_class);
+						} else if other_user_lock.get().is_some() {
+							row_class = format!("{} event_log_entry_locked", row_class);
+						}
+
+						match *drop_position.get() {
+							Some(DropPosition::Before) => row_class = format!("{} event_log_entry_drop_before", row_class),
+							Some(DropPosition::Child) => row_class = format!("{} event_log_entry_drop_child", row_class),
+							Some(DropPosition::After) => row_class = format!("{} event_log_entry_drop_after", row_class),
+							None => (),
 						}
 
 						row_class
-					}
+					},
+					on:dragover=drag_over_handler,
+					on:dragleave=drag_leave_handler,
+					on:drop=drop_handler
 				) {
-					div(class="log_entry_number") {
+					div(
+						class=if *props.can_edit.get() { "log_entry_number log_entry_drag_handle" } else { "log_entry_number" },
+						title=if *props.can_edit.get() { "Drag onto another entry to move this entry" } else { "" },
+						draggable=if *props.can_edit.get() { "true" } else { "false" },
+						on:dragstart=drag_start_handler,
+						on:dragend=drag_end_handler
+					) {
 						({
-							let entry_numbers = props.entry_numbers.get();
 							let entry = props.entry.get();
-							let entry = (*entry).as_ref();
-
-							match entry {
-								Some(entry) => match entry_numbers.get(&entry.id) {
-									Some(num) => num.to_string(),
-									None => String::new()
-								}
-								None => String::new()
-							}
+							(*entry)
+								.as_ref()
+								.and_then(|entry| entry.entry_number)
+								.map(|num| num.to_string())
+								.unwrap_or_default()
 						})
 					}
 					div(class="log_entry_select_parent", on:click=prevent_row_click_handler) {
 						(child_indicators)
 						({
 							let entry = props.entry.get();
-							let editing_log_entry = props.editing_log_entry.get();
-							if (*entry).as_ref().map(|entry| entry.start_time.is_some()).unwrap_or(false) && editing_log_entry.is_some() {
+							if (*entry).as_ref().map(|entry| entry.start_time.is_some()).unwrap_or(false) && props.edit_panes.has_open_panes() {
 								view! {
 									ctx,
 									img(src="images/add.png", class="click", alt="Add child entry", title="Add child entry", on:click=parent_select_handler)
@@ -268,7 +666,28 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 						})
 					}
 					div(class="log_entry_start_time", on:click=row_click_handler_for_id("event_log_entry_edit_start_time_field")) { (start_time.get()) }
-					div(class="log_entry_end_time", on:click=row_click_handler_for_id("event_log_entry_edit_end_time_field")) { (end_time.get()) }
+					div(
+						class="log_entry_end_time",
+						title=end_time_title.get(),
+						on:click=row_click_handler_for_id("event_log_entry_edit_end_time_field"),
+						on:dblclick=quick_edit_start_handler(QuickEditCell::EndTime)
+					) {
+						(if *quick_edit_cell.get() == Some(QuickEditCell::EndTime) {
+							view! {
+								ctx,
+								input(
+									id=quick_edit_id.get(),
+									class=quick_edit_input_class.get(),
+									title=quick_edit_title.get(),
+									bind:value=quick_edit_value,
+									on:keydown=quick_edit_keydown_handler,
+									on:blur=quick_edit_blur_handler
+								)
+							}
+						} else {
+							view! { ctx, (end_time.get()) }
+						})
+					}
 					div(
 						class="log_entry_type",
 						style=entry_type_style.get(),
@@ -277,8 +696,72 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 					) {
 						(entry_type_name.get())
 					}
-					div(class="log_entry_description", on:click=row_click_handler_for_id("event_log_entry_edit_description_field")) {
-						((*props.entry.get()).as_ref().map(|entry| entry.description.clone()).unwrap_or_default())
+					div(
+						class="log_entry_description",
+						on:click=row_click_handler_for_id("event_log_entry_edit_description_field"),
+						on:dblclick=quick_edit_start_handler(QuickEditCell::Description)
+					) {
+						(if *quick_edit_cell.get() == Some(QuickEditCell::Description) {
+							view! {
+								ctx,
+								input(
+									id=quick_edit_id.get(),
+									class=quick_edit_input_class.get(),
+									title=quick_edit_title.get(),
+									bind:value=quick_edit_value,
+									on:keydown=quick_edit_keydown_handler,
+									on:blur=quick_edit_blur_handler
+								)
+							}
+						} else {
+							let description = (*props.entry.get()).as_ref().map(|entry| entry.description.clone()).unwrap_or_default();
+							view! { ctx, (description) }
+						})
+						(if custom_field_values.get().is_empty() {
+							view! { ctx, }
+						} else {
+							view! {
+								ctx,
+								div(class="log_entry_custom_fields") {
+									Keyed(
+										iterable=custom_field_values,
+										key=|value| value.clone(),
+										view=|ctx, (name, value)| {
+											view! {
+												ctx,
+												span(class="log_entry_custom_field") {
+													span(class="log_entry_custom_field_name") { (name) ": " }
+													(value)
+												}
+											}
+										}
+									)
+								}
+							}
+						})
+						({
+							match (*other_user_lock.get()).as_ref() {
+								Some(lock) => {
+									let lock_user = lock.user.clone();
+									let name_style = format!("color: {}", rgb_str_from_color(lock_user.color));
+									let username = lock_user.username.clone();
+									view! {
+										ctx,
+										div(class="log_entry_edit_lock") {
+											UserAvatar(user=lock_user)
+											"Being edited by "
+											span(style=name_style) { (username) }
+											(if *locked_open_requested.get() {
+												" (click again to edit anyway)"
+											} else {
+												""
+											})
+										}
+									}
+								}
+								None => view! { ctx, },
+							}
+						})
 					}
 					div(class="log_entry_submitter_winner", on:click=row_click_handler_for_id("event_log_entry_edit_submitter_or_winner_field")) {
 						((*props.entry.get()).as_ref().map(|entry| entry.submitter_or_winner.clone()).unwrap_or_default())
@@ -287,11 +770,12 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 						Keyed(
 							iterable=media_links,
 							key=|link| link.clone(),
-							view=|ctx, link| {
+							view=|ctx, (link, is_dead)| {
 								let link_link = link.clone();
+								let link_class = if is_dead { "log_entry_dead_link" } else { "" };
 								view! {
 									ctx,
-									a(href=link_link, target="_blank", rel="noopener") {
+									a(href=link_link, target="_blank", rel="noopener", class=link_class) {
 										(link)
 									}
 								}
@@ -328,9 +812,35 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 							}
 							classes.join(" ")
 						},
-						on:click=row_click_handler_for_id("event_log_entry_edit_video_edit_state_first_button")
+						on:click=row_click_handler_for_id("event_log_entry_edit_video_edit_state_first_button"),
+						on:dblclick=quick_edit_start_handler(QuickEditCell::VideoEditState)
 					) {
-						({
+						(if *quick_edit_cell.get() == Some(QuickEditCell::VideoEditState) {
+							view! {
+								ctx,
+								select(
+									id=quick_edit_id.get(),
+									class="log_entry_quick_edit",
+									bind:value=quick_edit_value,
+									on:change=quick_edit_change_handler,
+									on:keydown=quick_edit_keydown_handler,
+									on:blur=quick_edit_blur_handler
+								) {
+									option(
+										value=video_edit_state_value(VideoEditState::NoVideo),
+										selected=*quick_edit_value.get() == video_edit_state_value(VideoEditState::NoVideo)
+									) { "No Video" }
+									option(
+										value=video_edit_state_value(VideoEditState::MarkedForEditing),
+										selected=*quick_edit_value.get() == video_edit_state_value(VideoEditState::MarkedForEditing)
+									) { "Marked" }
+									option(
+										value=video_edit_state_value(VideoEditState::DoneEditing),
+										selected=*quick_edit_value.get() == video_edit_state_value(VideoEditState::DoneEditing)
+									) { "Done Editing" }
+								}
+							}
+						} else {
 							let video_edit_state = (*props.entry.get()).as_ref().map(|entry| entry.video_edit_state).unwrap_or_default();
 							match video_edit_state {
 								VideoEditState::NoVideo => view! { ctx, },
@@ -386,10 +896,16 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 							if let Some(link) = video_link.as_ref() {
 								let link = link.clone();
 								let copy_link = link.clone();
+								let link_is_dead = (*props.entry.get()).as_ref().map(|entry| entry.dead_links.contains(&link)).unwrap_or(false);
+								let (link_class, link_title) = if link_is_dead {
+									("log_entry_dead_link", "Open video (this link appears to be dead)")
+								} else {
+									("", "Open video")
+								};
 								view! {
 									ctx,
-									a(href=link, target="_blank", rel="noopener") {
-										img(src="/images/youtube.png", alt="Video", title="Open video")
+									a(href=link, target="_blank", rel="noopener", class=link_class) {
+										img(src="/images/youtube.png", alt="Video", title=link_title)
 									}
 									(if is_secure_context {
 										let video_copy_click_handler = {
@@ -429,6 +945,31 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 										let name_color = rgb_str_from_color(editor.color);
 										let name_style = format!("color: {}", name_color);
 										let username = editor.username.clone();
+										let editor = editor.clone();
+										view! {
+											ctx,
+											UserAvatar(user=editor)
+											span(style=name_style) { (username) }
+										}
+									} else {
+										view! { ctx, }
+									}
+								})
+							}
+						}
+					} else {
+						view! { ctx, }
+					})
+					(if *props.use_editor_view.get() {
+						view! {
+							ctx,
+							div(class="log_entry_created_by") {
+								({
+									let created_by = (*props.entry.get()).as_ref().and_then(|entry| entry.created_by.clone());
+									if let Some(created_by) = created_by.as_ref() {
+										let name_color = rgb_str_from_color(created_by.color);
+										let name_style = format!("color: {}", name_color);
+										let username = created_by.username.clone();
 										view! {
 											ctx,
 											span(style=name_style) { (username) }
@@ -472,6 +1013,128 @@ pub fn EventLogEntryRow<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryRowProp
 	}
 }
 
+/// Gets where a dragged entry would be dropped for a drag event over a row. The top and bottom quarters of a row place
+/// the entry before or after that row, and the middle makes the entry a child of that row.
+fn drop_position_for_event(event: &WebEvent) -> Option<DropPosition> {
+	let drag_event: &DragEvent = event.unchecked_ref();
+	let target_node: Node = event.target()?.dyn_into().ok()?;
+	let target_element = match target_node.dyn_into::<Element>() {
+		Ok(element) => element,
+		Err(node) => node.parent_element()?,
+	};
+	// The row itself doesn't have a box in the grid, so we measure the cell being dragged over
+	let cell = target_element.closest(".event_log_entry > div").ok()??;
+	let cell_bounds = cell.get_bounding_client_rect();
+	let offset = f64::from(drag_event.client_y()) - cell_bounds.top();
+	let height = cell_bounds.height();
+
+	if offset < height / 4.0 {
+		Some(DropPosition::Before)
+	} else if offset > height * 3.0 / 4.0 {
+		Some(DropPosition::After)
+	} else {
+		Some(DropPosition::Child)
+	}
+}
+
+/// Checks whether the entry with the given ID is the parent of the entry or one of its parent's ancestors
+fn entry_has_ancestor(entries: &[EventLogEntry], entry: &EventLogEntry, ancestor_id: &str) -> bool {
+	let mut checked_entry_ids: Vec<&str> = Vec::new();
+	let mut next_parent_id = entry.parent.as_deref();
+	while let Some(parent_id) = next_parent_id {
+		if parent_id == ancestor_id {
+			return true;
+		}
+		if checked_entry_ids.contains(&parent_id) {
+			break;
+		}
+		checked_entry_ids.push(parent_id);
+		next_parent_id = entries
+			.iter()
+			.find(|entry| entry.id == parent_id)
+			.and_then(|entry| entry.parent.as_deref());
+	}
+	false
+}
+
+/// Gets the entry updates needed to drop an entry at the given position relative to the target entry. Entries are
+/// ordered by start time before sort key, so dropping an entry before or after another only reorders them when they
+/// start at the same time; otherwise, the dropped entry only takes the target's parent.
+fn entry_move_updates(
+	entries: &[EventLogEntry],
+	moved_entry: &EventLogEntry,
+	target_entry: &EventLogEntry,
+	position: DropPosition,
+) -> Vec<(EventLogEntry, Vec<ModifiedEventLogEntryParts>)> {
+	let mut moved_entry = moved_entry.clone();
+	let mut moved_entry_parts = Vec::new();
+	let mut updates = Vec::new();
+
+	let new_parent = if position == DropPosition::Child {
+		Some(target_entry.id.clone())
+	} else {
+		target_entry.parent.clone()
+	};
+	if moved_entry.parent != new_parent {
+		moved_entry.parent = new_parent;
+		moved_entry_parts.push(ModifiedEventLogEntryParts::Parent);
+	}
+
+	if position != DropPosition::Child && moved_entry.start_time == target_entry.start_time {
+		let moved_entry_id = moved_entry.id.clone();
+		let mut sibling_ids: Vec<&str> = entries
+			.iter()
+			.filter(|entry| {
+				entry.id != moved_entry_id
+					&& entry.parent == target_entry.parent
+					&& entry.start_time == target_entry.start_time
+			})
+			.map(|entry| entry.id.as_str())
+			.collect();
+		let target_index = sibling_ids
+			.iter()
+			.position(|sibling_id| *sibling_id == target_entry.id)
+			.unwrap_or_default();
+		let insert_index = if position == DropPosition::After {
+			target_index + 1
+		} else {
+			target_index
+		};
+		sibling_ids.insert(insert_index.min(sibling_ids.len()), &moved_entry_id);
+
+		for (sort_key, sibling_id) in (1..).zip(sibling_ids) {
+			let sort_key = Some(sort_key);
+			if sibling_id == moved_entry_id {
+				if moved_entry.manual_sort_key != sort_key {
+					moved_entry.manual_sort_key = sort_key;
+					moved_entry_parts.push(ModifiedEventLogEntryParts::SortKey);
+				}
+			} else if let Some(sibling) = entries.iter().find(|entry| entry.id == sibling_id) {
+				if sibling.manual_sort_key != sort_key {
+					let mut sibling = sibling.clone();
+					sibling.manual_sort_key = sort_key;
+					updates.push((sibling, vec![ModifiedEventLogEntryParts::SortKey]));
+				}
+			}
+		}
+	}
+
+	if !moved_entry_parts.is_empty() {
+		updates.insert(0, (moved_entry, moved_entry_parts));
+	}
+	updates
+}
+
+/// Gets the value used for a video edit state in the quick edit select box
+fn video_edit_state_value(state: VideoEditState) -> &'static str {
+	match state {
+		VideoEditState::NoVideo => "none",
+		VideoEditState::MarkedForEditing => "marked",
+		VideoEditState::DoneEditing => "done",
+	}
+}
+
+/// Checks whether the entry was created by the given user
 /// Checks whether any text in the DOM is selected
 fn any_text_is_selected() -> bool {
 	if let Some(window) = window() {
@@ -4,15 +4,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::utils::format_duration;
+use super::utils::time_display_for_user;
 use super::UserTypingData;
 use crate::color_utils::rgb_str_from_color;
+use crate::components::user_avatar::UserAvatar;
 use crate::subscriptions::event::TypingTarget;
 use std::collections::HashMap;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry};
 use stream_log_shared::messages::events::Event;
-use stream_log_shared::messages::user::PublicUserData;
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
 use sycamore::prelude::*;
 
 #[derive(Prop)]
@@ -24,7 +25,18 @@ pub struct EventLogEntryTypingProps<'a> {
 	use_editor_view: &'a ReadSignal<bool>,
 }
 
-type TypingStringData = (Option<String>, String, String, String, String, String, String, String);
+type TypingStringData = (
+	Option<String>,
+	String,
+	String,
+	String,
+	String,
+	String,
+	String,
+	String,
+	String,
+	String,
+);
 
 #[component]
 pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypingProps<'a>) -> View<G> {
@@ -43,6 +55,8 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 					String::new(),
 					String::new(),
 					String::new(),
+					String::new(),
+					String::new(),
 				);
 				for (target, value) in typing_data.iter() {
 					match *target {
@@ -54,6 +68,8 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 						TypingTarget::SubmitterWinner => typing_values.5.clone_from(value),
 						TypingTarget::MediaLink => typing_values.6.clone_from(value),
 						TypingTarget::Notes => typing_values.7.clone_from(value),
+						TypingTarget::Tags => typing_values.8.clone_from(value),
+						TypingTarget::SortKey => typing_values.9.clone_from(value),
 					};
 				}
 				(user.clone(), typing_values)
@@ -69,14 +85,16 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 			iterable=user_typing_data,
 			key=|data| data.clone(),
 			view=move |ctx, (user, typing_events)| {
-				let (parent_id, typed_start_time, typed_end_time, typed_entry_type, typed_description, typed_submitter_or_winner, typed_media_link, typed_notes) = typing_events;
+				let (parent_id, typed_start_time, typed_end_time, typed_entry_type, typed_description, typed_submitter_or_winner, typed_media_link, typed_notes, typed_tags, typed_sort_key) = typing_events;
 
 				let user_color = rgb_str_from_color(user.color);
 				let username_style = format!("color: {}", user_color);
 
-				let username = user.username;
+				let username = user.username.clone();
 
 				let event = (*props.event.get()).clone();
+				let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+				let time_display = time_display_for_user(&user.get(), &event);
 				let event_entry_types = (*props.event_entry_types.get()).clone();
 
 				let parent_entry_data = match parent_id {
@@ -102,16 +120,12 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 								let description = parent.description.clone();
 
 								let start_time = if let Some(parent_start_time) = parent.start_time {
-									let start_time_duration = parent_start_time - event.start_time;
-									format_duration(&start_time_duration)
+									time_display.format_time(parent_start_time, event.start_time)
 								} else {
 									String::new()
 								};
 								let end_time = match parent.end_time {
-									EndTimeData::Time(time) => {
-										let duration = time - event.start_time;
-										format_duration(&duration)
-									}
+									EndTimeData::Time(time) => time_display.format_time(time, event.start_time),
 									EndTimeData::NotEntered => String::new(),
 									EndTimeData::NoTime => String::from("—")
 								};
@@ -129,6 +143,7 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 					ctx,
 					div(class="event_log_entry_typing_header") {
 						div(class="event_log_entry_typing_username", style=username_style) {
+							UserAvatar(user=user)
 							(username)
 						}
 						div(class="event_log_entry_typing_parent") {
@@ -156,7 +171,7 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 						}
 					}
 					div(class="event_log_entry_typing_data") {
-						div {}
+						div { (typed_sort_key) }
 						div {}
 						div { (typed_start_time) }
 						div { (typed_end_time) }
@@ -164,7 +179,7 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 						div { (typed_description) }
 						div { (typed_submitter_or_winner) }
 						div { (typed_media_link) }
-						div {}
+						div { (typed_tags) }
 						div {}
 						div {}
 						(if *props.use_editor_view.get() {
@@ -180,6 +195,7 @@ pub fn EventLogEntryTyping<'a, G: Html>(ctx: Scope<'a>, props: EventLogEntryTypi
 							view ! {
 								ctx,
 								div {}
+								div {}
 							}
 						} else {
 							view! { ctx, }
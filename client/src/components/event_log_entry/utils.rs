@@ -5,53 +5,69 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use chrono::Duration;
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::time_display::TimeDisplay;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::view_preferences::EventLogFilters;
 
-/// Formats a [`Duration`] object as hours:minutes
-pub fn format_duration(duration: &Duration) -> String {
-	let mut hours = duration.num_hours();
-	let mut minutes = duration.num_minutes() % 60;
-	let sign = if hours < 0 || minutes < 0 {
-		hours = hours.abs();
-		minutes = minutes.abs();
-		"-"
-	} else {
-		""
-	};
-	format!("{}{}:{:02}", sign, hours, minutes)
-}
+pub use stream_log_shared::messages::time_display::{format_duration, get_duration_from_formatted};
 
-/// Parses a string formatted as hhh:mm into a [`Duration`] object. If parsing fails,
-/// returns a string suitable for display to the user who entered the value.
-pub fn get_duration_from_formatted(formatted_duration: &str) -> Result<Duration, String> {
-	let Some((hours, minutes)) = formatted_duration.split_once(':') else {
-		return Err(String::from("Invalid format"));
-	};
+/// Gets how times in the event's log should be shown to the user
+pub fn time_display_for_user(user: &Option<SelfUserData>, event: &Event) -> TimeDisplay {
+	match user {
+		Some(user) => user.time_display_for_event(event),
+		None => event.time_display.clone(),
+	}
+}
 
-	let is_negative = match hours.chars().next() {
-		Some(c) => c == '-',
-		None => false,
-	};
-	let hours: i64 = match hours.parse() {
-		Ok(hours) => hours,
-		Err(error) => return Err(format!("Couldn't parse hours: {}", error)),
-	};
+/// Checks whether an entry should be shown with the given log filters applied
+pub fn entry_matches_filters(
+	entry: &EventLogEntry,
+	filters: &EventLogFilters,
+	user: &Option<SelfUserData>,
+	entry_types: &[EntryType],
+) -> bool {
+	(filters.video_edit_states.is_empty() || filters.video_edit_states.contains(&entry.video_edit_state))
+		&& (filters.video_processing_states.is_empty()
+			|| filters.video_processing_states.contains(&entry.video_processing_state))
+		&& (!filters.created_by_me || entry_created_by_user(entry, user))
+		&& (!filters.assigned_to_me || entry_assigned_to_user(entry, user))
+		&& (!filters.missing_giveaway_information || entry.missing_giveaway_information)
+		&& (!filters.over_expected_duration || {
+			let entry_type = entry
+				.entry_type
+				.as_ref()
+				.and_then(|type_id| entry_types.iter().find(|entry_type| entry_type.id == *type_id));
+			entry_exceeds_expected_duration(entry, entry_type)
+		})
+}
 
-	let mut minutes: i64 = match minutes.parse() {
-		Ok(mins) => mins,
-		Err(error) => return Err(format!("Couldn't parse minutes: {}", error)),
+/// Checks whether an entry ran longer than the expected duration for its entry type. Entries without both a start and
+/// end time can't be checked and are never considered to exceed the expected duration.
+pub fn entry_exceeds_expected_duration(entry: &EventLogEntry, entry_type: Option<&EntryType>) -> bool {
+	let Some(expected_minutes) = entry_type.and_then(|entry_type| entry_type.expected_duration_minutes) else {
+		return false;
 	};
-
-	if is_negative {
-		if hours > 0 {
-			return Err(format!(
-				"Hour parsing went wrong: detected negative duration but parsed hours as {}",
-				hours
-			));
+	match (entry.start_time, &entry.end_time) {
+		(Some(start_time), EndTimeData::Time(end_time)) => {
+			*end_time - start_time > Duration::minutes(expected_minutes.into())
 		}
+		_ => false,
+	}
+}
 
-		minutes = -minutes;
+fn entry_created_by_user(entry: &EventLogEntry, user: &Option<SelfUserData>) -> bool {
+	match (entry.created_by.as_ref(), user.as_ref()) {
+		(Some(creator), Some(user)) => creator.id == user.id,
+		_ => false,
 	}
+}
 
-	let duration_minutes = hours * 60 + minutes;
-	Ok(Duration::minutes(duration_minutes))
+fn entry_assigned_to_user(entry: &EventLogEntry, user: &Option<SelfUserData>) -> bool {
+	match (entry.editor.as_ref(), user.as_ref()) {
+		(Some(editor), Some(user)) => editor.id == user.id,
+		_ => false,
+	}
 }
@@ -0,0 +1,206 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use gloo_timers::future::TimeoutFuture;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use web_sys::{window, Element, Event as WebEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+const HIGHLIGHT_CLASS: &str = "guided_tour_highlight";
+
+struct TourStep {
+	/// CSS selector for the element the step describes
+	selector: &'static str,
+	title: &'static str,
+	text: &'static str,
+}
+
+const TOUR_STEPS: &[TourStep] = &[
+	TourStep {
+		selector: "#event_log_header",
+		title: "Event details",
+		text: "The top of the page shows who else is viewing the event and lets you jump to a time or export the log.",
+	},
+	TourStep {
+		selector: "#event_log_tabs",
+		title: "Tabs",
+		text: "The log is split into tabs by time. Choose a tab to see only the entries in that part of the event.",
+	},
+	TourStep {
+		selector: ".event_log_column_filter_menu",
+		title: "Filters",
+		text: "Each column header has a filter menu. Use these to show only the entries you're interested in.",
+	},
+	TourStep {
+		selector: "#event_log_filter_presets",
+		title: "Filter presets",
+		text: "Save the filters you use often as a preset so you can apply them again later.",
+	},
+	TourStep {
+		selector: "#event_log_new_entry",
+		title: "Adding entries",
+		text: "Fill in this form to add a new entry to the log. Clicking an existing entry opens it here for editing.",
+	},
+	TourStep {
+		selector: "#user_event_menu",
+		title: "Event pages",
+		text: "This menu has the other pages for the event. You can show this tour again from your profile.",
+	},
+];
+
+/// Walks new users through the parts of the event log page. The tour is shown until the user finishes or skips it.
+#[component]
+pub fn GuidedTour<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+	let show_tour = user_signal
+		.get()
+		.as_ref()
+		.as_ref()
+		.map(|user| !user.completed_guided_tour)
+		.unwrap_or(false);
+
+	let tour_active = create_signal(ctx, show_tour);
+	let steps: &Signal<Vec<&'static TourStep>> = create_signal(ctx, Vec::new());
+	let current_step = create_signal(ctx, 0usize);
+	let highlighted_element: &Signal<Option<Element>> = create_signal(ctx, None);
+
+	if show_tour {
+		spawn_local_scoped(ctx, async move {
+			// Let the rest of the page render first so that we only include steps for elements the user can see
+			TimeoutFuture::new(0).await;
+			let Some(document) = window().and_then(|window| window.document()) else {
+				return;
+			};
+			let available_steps: Vec<&'static TourStep> = TOUR_STEPS
+				.iter()
+				.filter(|step| matches!(document.query_selector(step.selector), Ok(Some(_))))
+				.collect();
+			steps.set(available_steps);
+		});
+	}
+
+	let clear_highlight = move || {
+		if let Some(element) = highlighted_element.get_untracked().as_ref() {
+			let _ = element.class_list().remove_1(HIGHLIGHT_CLASS);
+		}
+		highlighted_element.set(None);
+	};
+
+	create_effect(ctx, move || {
+		clear_highlight();
+		if !*tour_active.get() {
+			return;
+		}
+		let steps = steps.get();
+		let Some(step) = steps.get(*current_step.get()) else {
+			return;
+		};
+		let Some(document) = window().and_then(|window| window.document()) else {
+			return;
+		};
+		if let Ok(Some(element)) = document.query_selector(step.selector) {
+			let _ = element.class_list().add_1(HIGHLIGHT_CLASS);
+			let scroll_into_view_options = ScrollIntoViewOptions::new();
+			scroll_into_view_options.set_block(ScrollLogicalPosition::Nearest);
+			element.scroll_into_view_with_scroll_into_view_options(&scroll_into_view_options);
+			highlighted_element.set(Some(element));
+		}
+	});
+
+	on_cleanup(ctx, clear_highlight);
+
+	let end_tour = move || {
+		tour_active.set(false);
+		send_guided_tour_completed(ctx, true);
+	};
+
+	let back_handler = move |_event: WebEvent| {
+		let step = *current_step.get();
+		current_step.set(step.saturating_sub(1));
+	};
+	let next_handler = move |_event: WebEvent| {
+		let step = *current_step.get() + 1;
+		if step < steps.get().len() {
+			current_step.set(step);
+		} else {
+			end_tour();
+		}
+	};
+	let skip_handler = move |_event: WebEvent| end_tour();
+
+	view! {
+		ctx,
+		(if *tour_active.get() {
+			let steps = steps.get();
+			match steps.get(*current_step.get()) {
+				Some(step) => {
+					let step_number = *current_step.get() + 1;
+					let step_count = steps.len();
+					let is_first_step = step_number == 1;
+					let is_last_step = step_number == step_count;
+					view! {
+						ctx,
+						div(id="guided_tour") {
+							div(id="guided_tour_progress") { "Step " (step_number) " of " (step_count) }
+							h3(id="guided_tour_title") { (step.title) }
+							p(id="guided_tour_text") { (step.text) }
+							div(id="guided_tour_buttons") {
+								button(type="button", disabled=is_first_step, on:click=back_handler) { "Back" }
+								button(type="button", on:click=next_handler) {
+									(if is_last_step { "Finish" } else { "Next" })
+								}
+								(if is_last_step {
+									view! { ctx, }
+								} else {
+									view! {
+										ctx,
+										button(type="button", on:click=skip_handler) { "Skip Tour" }
+									}
+								})
+							}
+						}
+					}
+				}
+				None => view! { ctx, },
+			}
+		} else {
+			view! { ctx, }
+		})
+	}
+}
+
+/// Sends whether the user has completed the guided tour to the server
+pub fn send_guided_tour_completed(ctx: Scope<'_>, completed: bool) {
+	spawn_local_scoped(ctx, async move {
+		let data: &DataSignals = use_context(ctx);
+		let message = FromClientMessage::SetGuidedTourCompleted(completed);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize guided tour update.",
+					error,
+				));
+				return;
+			}
+		};
+
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+		if let Err(error) = ws.send(Message::Text(message_json)).await {
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to send guided tour update.", error));
+		}
+	});
+}
@@ -0,0 +1,45 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::subscriptions::load_progress::LoadProgress;
+use crate::subscriptions::DataSignals;
+use sycamore::prelude::*;
+
+#[component]
+pub fn LoadProgressDisplay<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+	let load_progress = create_memo(ctx, || (*data.load_progress.get()).clone());
+
+	view! {
+		ctx,
+		(match (*load_progress.get()).clone() {
+			Some(LoadProgress::ReadingMessage(message_size)) => {
+				let message_size = format!("{:.1} MB", message_size as f64 / (1024.0 * 1024.0));
+				view! {
+					ctx,
+					div(id="load_progress") {
+						"Processing data from the server (" (message_size) ")..."
+						progress {}
+					}
+				}
+			}
+			Some(LoadProgress::EventEntries(event_name, loaded_entries, total_entries)) => {
+				let loaded_entries_value = loaded_entries.to_string();
+				let total_entries_value = total_entries.to_string();
+				let loaded_entries = loaded_entries_value.clone();
+				let total_entries = total_entries_value.clone();
+				view! {
+					ctx,
+					div(id="load_progress") {
+						"Loading " (event_name) ": " (loaded_entries_value) " of " (total_entries_value) " entries"
+						progress(value=loaded_entries, max=total_entries)
+					}
+				}
+			}
+			None => view! { ctx, }
+		})
+	}
+}
@@ -5,6 +5,12 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 pub mod color_input_with_contrast;
+pub mod entry_type_select;
 pub mod error_display;
 pub mod event_log_entry;
+pub mod guided_tour;
+pub mod load_progress_display;
+pub mod notification_toasts;
+pub mod tag_autocomplete;
+pub mod user_avatar;
 pub mod user_info_bar;
@@ -0,0 +1,71 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::subscriptions::DataSignals;
+use chrono::Local;
+use sycamore::prelude::*;
+use web_sys::Event as WebEvent;
+
+#[component]
+pub fn NotificationToasts<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+	let toasts = create_memo(ctx, || (*data.notification_toasts.get()).clone());
+	let broadcasts = create_memo(ctx, || (*data.broadcast_toasts.get()).clone());
+
+	view! {
+		ctx,
+		ul(id="notification_toasts") {
+			Keyed(
+				iterable=broadcasts,
+				key=|broadcast| broadcast.id.clone(),
+				view=|ctx, broadcast| {
+					let dismiss_handler = {
+						let broadcast_id = broadcast.id.clone();
+						move |_event: WebEvent| {
+							let data: &DataSignals = use_context(ctx);
+							data.broadcast_toasts.modify().retain(|toast| toast.id != broadcast_id);
+						}
+					};
+					let sent_at = broadcast.sent_at.with_timezone(&Local).format("%H:%M").to_string();
+					view! {
+						ctx,
+						li(class="notification_toast broadcast_toast") {
+							div(class="broadcast_toast_sender") {
+								(broadcast.sender.username) " (" (sent_at) ")"
+							}
+							span(class="notification_toast_text") { (broadcast.message) }
+							span(class="notification_toast_dismiss") {
+								a(class="click", on:click=dismiss_handler) { "[X]" }
+							}
+						}
+					}
+				}
+			)
+			Keyed(
+				iterable=toasts,
+				key=|notification| notification.id.clone(),
+				view=|ctx, notification| {
+					let dismiss_handler = {
+						let notification_id = notification.id.clone();
+						move |_event: WebEvent| {
+							let data: &DataSignals = use_context(ctx);
+							data.notification_toasts.modify().retain(|toast| toast.id != notification_id);
+						}
+					};
+					view! {
+						ctx,
+						li(class="notification_toast") {
+							span(class="notification_toast_text") { (notification.to_string()) }
+							span(class="notification_toast_dismiss") {
+								a(class="click", on:click=dismiss_handler) { "[X]" }
+							}
+						}
+					}
+				}
+			)
+		}
+	}
+}
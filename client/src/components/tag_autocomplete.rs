@@ -0,0 +1,292 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::tags::Tag;
+use sycamore::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Event as WebEvent, KeyboardEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+/// The number of the event's most recent entries whose tags count toward ranking suggestions
+const RECENT_USAGE_ENTRY_COUNT: usize = 200;
+/// The most suggestions shown at once
+const MAX_SUGGESTIONS: usize = 20;
+
+/// Counts how many times each tag is used in the most recent entries. Entries are expected to be in log order, so the
+/// most recent entries are the last ones. Maps tag IDs to counts.
+pub fn recent_tag_usage(entries: &[EventLogEntry]) -> HashMap<String, usize> {
+	let mut usage: HashMap<String, usize> = HashMap::new();
+	for entry in entries.iter().rev().take(RECENT_USAGE_ENTRY_COUNT) {
+		for tag in entry.tags.iter() {
+			*usage.entry(tag.id.clone()).or_default() += 1;
+		}
+	}
+	usage
+}
+
+/// Gets how closely a tag name matches what was entered, or None if it doesn't match. Lower values are closer
+/// matches: exact matches come first, then names starting with the entered text, then names containing it, and then
+/// names containing its characters in order.
+fn match_rank(filter: &str, name: &str) -> Option<(u8, usize)> {
+	if filter.is_empty() || name == filter {
+		return Some((0, 0));
+	}
+	if name.starts_with(filter) {
+		return Some((1, 0));
+	}
+	if let Some(position) = name.find(filter) {
+		return Some((2, position));
+	}
+
+	// Matches with the entered characters closer together rank higher
+	let mut name_chars = name.char_indices();
+	let mut first_match: Option<usize> = None;
+	let mut last_match = 0;
+	for filter_char in filter.chars() {
+		let (index, _) = name_chars.find(|(_, name_char)| *name_char == filter_char)?;
+		first_match.get_or_insert(index);
+		last_match = index;
+	}
+	let span = last_match - first_match.unwrap_or_default();
+	Some((3, span))
+}
+
+#[derive(Prop)]
+pub struct TagAutocompleteProps<'a> {
+	/// The tags that can be suggested
+	tags: &'a ReadSignal<Vec<Tag>>,
+	/// How often each tag was used recently, keyed by tag ID
+	tag_usage: &'a ReadSignal<HashMap<String, usize>>,
+	/// The tag name entered in the field
+	name: &'a Signal<String>,
+	/// The title of the text field
+	title: &'a ReadSignal<String>,
+	/// The ID of the text field
+	id: String,
+}
+
+/// A text field for entering a tag name that suggests matching tags in a list below it. Suggestions are fuzzy-matched
+/// against the entered text and ranked by how often they've been used recently in the event. The list can be navigated
+/// with the arrow keys, and Enter chooses the highlighted tag. Names that don't match a tag can still be entered to
+/// create new tags.
+#[component]
+pub fn TagAutocomplete<'a, G: Html>(ctx: Scope<'a>, props: TagAutocompleteProps<'a>) -> View<G> {
+	let tags = props.tags;
+	let tag_usage = props.tag_usage;
+	let entered_name = props.name;
+	let id = create_ref(ctx, props.id);
+
+	let list_open = create_signal(ctx, false);
+	let highlighted_id: &Signal<Option<String>> = create_signal(ctx, None);
+
+	let suggestions = create_memo(ctx, move || {
+		let filter = entered_name.get().trim().to_lowercase();
+		let tags = tags.get();
+		let tag_usage = tag_usage.get();
+
+		let mut matches: Vec<((u8, usize), Reverse<usize>, String, Tag)> = tags
+			.iter()
+			.filter_map(|tag| {
+				let lower_name = tag.name.to_lowercase();
+				let rank = match_rank(&filter, &lower_name)?;
+				let usage = tag_usage.get(&tag.id).copied().unwrap_or_default();
+				Some((rank, Reverse(usage), lower_name, tag.clone()))
+			})
+			.collect();
+		matches.sort_by(|a, b| (&a.0, &a.1, &a.2).cmp(&(&b.0, &b.1, &b.2)));
+
+		// Once a tag has been fully entered, there's nothing left to suggest
+		if matches.len() == 1 && matches[0].0 == (0, 0) && !filter.is_empty() {
+			return Vec::new();
+		}
+
+		let suggestions: Vec<Tag> = matches
+			.into_iter()
+			.take(MAX_SUGGESTIONS)
+			.map(|(_, _, _, tag)| tag)
+			.collect();
+		suggestions
+	});
+
+	create_effect(ctx, move || {
+		let suggestions = suggestions.get();
+		let highlighted_is_listed = match highlighted_id.get_untracked().as_deref() {
+			Some(highlighted_id) => suggestions.iter().any(|tag| tag.id == highlighted_id),
+			None => false,
+		};
+		if !highlighted_is_listed {
+			highlighted_id.set(suggestions.first().map(|tag| tag.id.clone()));
+		}
+	});
+
+	let choose_tag = move |tag_id: &str| {
+		let tags = tags.get();
+		if let Some(tag) = tags.iter().find(|tag| tag.id == tag_id) {
+			entered_name.set(tag.name.clone());
+		}
+		list_open.set(false);
+	};
+
+	// When the field loses focus, we correct the capitalization of names that match a tag. Other names are left as
+	// entered, since they may be new tags.
+	let complete_entered_name = move || {
+		let name = entered_name.get();
+		let tags = tags.get();
+		if tags.iter().any(|tag| tag.name == *name) {
+			return;
+		}
+
+		let lower_name = name.to_lowercase();
+		if let Some(tag) = tags.iter().find(|tag| tag.name.to_lowercase() == lower_name) {
+			entered_name.set(tag.name.clone());
+		}
+	};
+
+	let key_handler = move |event: WebEvent| {
+		let key_event: KeyboardEvent = event.unchecked_into();
+		let key = key_event.key();
+		match key.as_str() {
+			"ArrowDown" | "ArrowUp" => {
+				key_event.prevent_default();
+				if !*list_open.get() {
+					list_open.set(true);
+					return;
+				}
+
+				let suggestions = suggestions.get();
+				if suggestions.is_empty() {
+					return;
+				}
+				let highlighted_index = highlighted_id
+					.get()
+					.as_deref()
+					.and_then(|highlighted_id| suggestions.iter().position(|tag| tag.id == highlighted_id));
+				let new_index = match (key.as_str(), highlighted_index) {
+					("ArrowDown", Some(index)) => (index + 1) % suggestions.len(),
+					("ArrowDown", None) => 0,
+					(_, Some(index)) if index > 0 => index - 1,
+					_ => suggestions.len() - 1,
+				};
+				let new_highlighted_id = suggestions[new_index].id.clone();
+				scroll_option_into_view(&option_element_id(id, &new_highlighted_id));
+				highlighted_id.set(Some(new_highlighted_id));
+			}
+			"Enter" => {
+				if !*list_open.get() || suggestions.get().is_empty() {
+					return;
+				}
+				let highlighted = (*highlighted_id.get()).clone();
+				if let Some(highlighted) = highlighted {
+					key_event.prevent_default();
+					key_event.stop_propagation();
+					choose_tag(&highlighted);
+				}
+			}
+			"Escape" => {
+				if *list_open.get() {
+					key_event.prevent_default();
+					key_event.stop_propagation();
+					list_open.set(false);
+				}
+			}
+			_ => (),
+		}
+	};
+
+	let input_handler = |_event: WebEvent| {
+		list_open.set(true);
+	};
+
+	let click_handler = |_event: WebEvent| {
+		list_open.set(true);
+	};
+
+	let blur_handler = move |_event: WebEvent| {
+		list_open.set(false);
+		complete_entered_name();
+	};
+
+	let list_id = create_ref(ctx, format!("{}_list", id));
+
+	view! {
+		ctx,
+		div(class="tag_autocomplete") {
+			input(
+				bind:value=entered_name,
+				id=id.clone(),
+				autocomplete="off",
+				title=props.title.get(),
+				on:input=input_handler,
+				on:click=click_handler,
+				on:keydown=key_handler,
+				on:blur=blur_handler
+			)
+			(if *list_open.get() && !suggestions.get().is_empty() {
+				view! {
+					ctx,
+					div(id=list_id.clone(), class="tag_autocomplete_list") {
+						Keyed(
+							iterable=suggestions,
+							key=|tag| tag.id.clone(),
+							view=move |ctx, tag| {
+								let is_highlighted = create_memo(ctx, {
+									let tag_id = tag.id.clone();
+									move || highlighted_id.get().as_deref() == Some(tag_id.as_str())
+								});
+								let option_id = option_element_id(id, &tag.id);
+								let option_click_handler = {
+									let tag_id = tag.id.clone();
+									move |_event: WebEvent| choose_tag(&tag_id)
+								};
+								let hover_handler = {
+									let tag_id = tag.id.clone();
+									move |_event: WebEvent| highlighted_id.set(Some(tag_id.clone()))
+								};
+								// Choosing an option with the mouse shouldn't take focus from the text field
+								let mouse_down_handler = |event: WebEvent| event.prevent_default();
+								view! {
+									ctx,
+									div(
+										id=option_id,
+										class=if *is_highlighted.get() { "tag_autocomplete_option tag_autocomplete_option_highlighted" } else { "tag_autocomplete_option" },
+										on:mousedown=mouse_down_handler,
+										on:mouseenter=hover_handler,
+										on:click=option_click_handler
+									) {
+										div(class="tag_autocomplete_option_name") { (tag.name) }
+										div(class="tag_autocomplete_option_description") { (tag.description) }
+									}
+								}
+							}
+						)
+					}
+				}
+			} else {
+				view! { ctx, }
+			})
+		}
+	}
+}
+
+/// Gets the ID of the list element for a tag
+fn option_element_id(input_id: &str, tag_id: &str) -> String {
+	format!("{}_option_{}", input_id, tag_id)
+}
+
+/// Scrolls the list so that the option with the given element ID is visible
+fn scroll_option_into_view(option_id: &str) {
+	let Some(document) = window().and_then(|window| window.document()) else {
+		return;
+	};
+	let Some(option_element) = document.get_element_by_id(option_id) else {
+		return;
+	};
+	let scroll_into_view_options = ScrollIntoViewOptions::new();
+	scroll_into_view_options.set_block(ScrollLogicalPosition::Nearest);
+	option_element.scroll_into_view_with_scroll_into_view_options(&scroll_into_view_options);
+}
@@ -0,0 +1,80 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use stream_log_shared::messages::user::PublicUserData;
+use sycamore::prelude::*;
+
+/// The number of cells across (and down) an identicon
+const IDENTICON_SIZE: u64 = 5;
+
+/// Generates a hash of the user ID to use for an identicon. This needs to give the same result everywhere, so it uses
+/// FNV-1a instead of the standard library's hasher, which isn't guaranteed to be stable.
+fn identicon_hash(user_id: &str) -> u64 {
+	let mut hash: u64 = 0xcbf29ce484222325;
+	for byte in user_id.bytes() {
+		hash ^= u64::from(byte);
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+	hash
+}
+
+/// Generates a data URL for an identicon for the user. Identicons are a horizontally symmetrical grid of cells in the
+/// user's color, where which cells are filled is determined by the user's ID.
+fn identicon_url(user: &PublicUserData) -> String {
+	let hash = identicon_hash(&user.id);
+	let fill = rgb_str_from_color(user.color);
+	let half_width = IDENTICON_SIZE.div_ceil(2);
+
+	let mut cells = String::new();
+	for row in 0..IDENTICON_SIZE {
+		for column in 0..half_width {
+			if hash & (1 << (row * half_width + column)) == 0 {
+				continue;
+			}
+			cells.push_str(&format!("<rect x='{}' y='{}' width='1' height='1'/>", column, row));
+			let mirrored_column = IDENTICON_SIZE - 1 - column;
+			if mirrored_column != column {
+				cells.push_str(&format!(
+					"<rect x='{}' y='{}' width='1' height='1'/>",
+					mirrored_column, row
+				));
+			}
+		}
+	}
+
+	let svg = format!(
+		"<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 {} {}' shape-rendering='crispEdges' fill='{}'>{}</svg>",
+		IDENTICON_SIZE, IDENTICON_SIZE, fill, cells
+	);
+	let svg = svg.replace('#', "%23").replace('<', "%3C").replace('>', "%3E");
+	format!("data:image/svg+xml,{}", svg)
+}
+
+/// Gets the URL of the image to show as the user's avatar
+pub fn avatar_url(user: &PublicUserData) -> String {
+	match user.avatar_updated_at.as_ref() {
+		Some(updated_at) => format!("/avatars/{}?v={}", user.id, updated_at.timestamp()),
+		None => identicon_url(user),
+	}
+}
+
+#[derive(Prop)]
+pub struct UserAvatarProps {
+	user: PublicUserData,
+}
+
+/// Shows a user's avatar: the image they uploaded, or an identicon if they haven't uploaded one
+#[component]
+pub fn UserAvatar<G: Html>(ctx: Scope<'_>, props: UserAvatarProps) -> View<G> {
+	let url = avatar_url(&props.user);
+	let border_style = format!("border-color: {}", rgb_str_from_color(props.user.color));
+
+	view! {
+		ctx,
+		img(class="user_avatar", src=url, alt="", title=props.user.username, style=border_style)
+	}
+}
@@ -4,14 +4,33 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::subscriptions::connection::ConnectionState;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::event::EventSubscriptionSignals;
+use crate::subscriptions::manager::SubscriptionManager;
 use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use chrono::{Duration, Local, Utc};
 use futures::future::poll_fn;
+use futures::lock::Mutex;
 use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use gloo_timers::callback::Interval;
 use std::collections::HashMap;
 use std::fmt;
+use stream_log_shared::messages::event_log::VideoProcessingState;
+use stream_log_shared::messages::notifications::UserNotificationUpdate;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
 use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
+use web_sys::Event as WebEvent;
+
+/// How often the entry ticker updates its count of recent entries
+const TICKER_TIME_UPDATE_INTERVAL_MS: u32 = 30_000;
 
 pub struct EventId(String);
 
@@ -32,21 +51,44 @@ impl fmt::Display for EventId {
 pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
 	let event_id_signal: &Signal<Option<EventId>> = use_context(ctx);
+	let data: &DataSignals = use_context(ctx);
+	let impersonating = create_memo(ctx, || *data.impersonating.get());
+
+	// Reloading the page opens a new connection, which is always for the user's own account
+	let stop_impersonating_handler = |_event: WebEvent| {
+		if let Some(window) = web_sys::window() {
+			let _ = window.location().reload();
+		}
+	};
+
 	view! {
 		ctx,
 		(if let Some(user) = user_signal.get().as_ref().clone() {
 			view! {
 				ctx,
 				div(id="user") {
+					ConnectionStatusView
 					div(id="home_link") {
 						a(href="/") {
 							"Home"
 						}
 					}
-					div(id="user_greeting") {
-						"Hi, "
+					div(id="user_greeting", class=if *impersonating.get() { "user_impersonating" } else { "" }) {
+						(if *impersonating.get() { "Viewing as " } else { "Hi, " })
 						(user.username)
 						ul(id="user_menu", class="user_info_menu") {
+							(if *impersonating.get() {
+								view! {
+									ctx,
+									li {
+										button(type="button", on:click=stop_impersonating_handler) {
+											"Stop viewing as this user"
+										}
+									}
+								}
+							} else {
+								view! { ctx, }
+							})
 							li {
 								a(href="/user_profile") {
 									"Profile"
@@ -59,12 +101,21 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 							}
 						}
 					}
+					NotificationsMenuView
 					(if let Some(event_id) = event_id_signal.get().as_ref() {
 						let event_log_link = format!("/log/{}", event_id);
 						let tags_link = format!("/log/{}/tags", event_id);
 						let entry_types_link = format!("/log/{}/entry_types", event_id);
+						let editor_notes_link = format!("/log/{}/notes", event_id);
+						let editors_dashboard_link = format!("/log/{}/editors_dashboard", event_id);
+						let stats_link = format!("/log/{}/stats", event_id);
 						view! {
 							ctx,
+							div(id="user_event_ticker") {
+								Suspense(fallback=view! { ctx, }) {
+									EventEntryTickerView
+								}
+							}
 							div(id="user_event_menu") {
 								"Event Menu"
 								ul(id="user_event_menu_pages", class="user_info_menu") {
@@ -83,6 +134,21 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 											"Entry Types"
 										}
 									}
+									li {
+										a(href=editor_notes_link) {
+											"Notes to Editor"
+										}
+									}
+									li {
+										a(href=editors_dashboard_link) {
+											"Editors Dashboard"
+										}
+									}
+									li {
+										a(href=stats_link) {
+											"Statistics"
+										}
+									}
 									Suspense(fallback=view! { ctx, }) {
 										EventInfoPagesView
 									}
@@ -118,6 +184,11 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 											"Assign Users to Permission Groups"
 										}
 									}
+									li {
+										a(href="/admin/user_event_permissions") {
+											"Direct Event Permissions"
+										}
+									}
 									li {
 										a(href="/admin/event_types") {
 											"Manage Entry Types"
@@ -128,6 +199,11 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 											"Assign Entry Types to Events"
 										}
 									}
+									li {
+										a(href="/admin/entry_type_matrix") {
+											"Entry Types for All Events"
+										}
+									}
 									li {
 										a(href="/admin/editors") {
 											"Manage Event Editors"
@@ -148,6 +224,31 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 											"Manage Info Pages"
 										}
 									}
+									li {
+										a(href="/admin/access_requests") {
+											"Review Event Access Requests"
+										}
+									}
+									li {
+										a(href="/admin/webhooks") {
+											"Manage Webhooks"
+										}
+									}
+									li {
+										a(href="/admin/custom_fields") {
+											"Manage Custom Fields"
+										}
+									}
+									li {
+										a(href="/admin/audit") {
+											"Audit Log"
+										}
+									}
+									li {
+										a(href="/admin/jobs") {
+											"Background Jobs"
+										}
+									}
 								}
 							}
 						}
@@ -162,46 +263,347 @@ pub fn UserInfoBar<G: Html>(ctx: Scope) -> View<G> {
 	}
 }
 
+/// Shows the state of the connection to the server, along with a button to reload all subscription data
 #[component]
-async fn EventInfoPagesView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	let event_id_signal: &Signal<Option<EventId>> = use_context(ctx);
-	let event_id = (*event_id_signal.get())
-		.as_ref()
-		.map(|id| id.to_string())
-		.unwrap_or_default();
+fn ConnectionStatusView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+	let connection_state = create_memo(ctx, || *data.connection_state.get());
+	let resync_in_progress = create_signal(ctx, false);
+
+	let status_class = create_memo(ctx, || match *connection_state.get() {
+		ConnectionState::Connected => "connection_status_connected",
+		ConnectionState::Stale => "connection_status_stale",
+		ConnectionState::Reconnecting => "connection_status_reconnecting",
+		ConnectionState::Lost => "connection_status_lost",
+	});
+	let status_description = create_memo(ctx, || match *connection_state.get() {
+		ConnectionState::Connected => "Connected",
+		ConnectionState::Stale => "The server hasn't responded in a while",
+		ConnectionState::Reconnecting => "Reconnecting",
+		ConnectionState::Lost => "Disconnected",
+	});
+	let resync_disabled = create_memo(ctx, || {
+		*resync_in_progress.get()
+			|| !matches!(
+				*connection_state.get(),
+				ConnectionState::Connected | ConnectionState::Stale
+			)
+	});
+
+	let resync_handler = move |_event: WebEvent| {
+		resync_in_progress.set(true);
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+			let resync_result = {
+				let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+				let mut subscription_manager = subscription_manager.lock().await;
+				subscription_manager.resync_all_subscriptions(&mut ws).await
+			};
+			if let Err(error) = resync_result {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to request updated data from the server.",
+					error,
+				));
+			}
+			resync_in_progress.set(false);
+		});
+	};
+
+	view! {
+		ctx,
+		div(id="user_connection_status") {
+			span(id="user_connection_status_indicator", class=*status_class.get(), title=*status_description.get())
+			button(
+				id="user_connection_resync",
+				type="button",
+				title="Reload all data from the server",
+				disabled=*resync_disabled.get(),
+				on:click=resync_handler
+			) {
+				"Resync"
+			}
+		}
+	}
+}
+
+/// Shows the user's notifications, along with how many are unread
+#[component]
+fn NotificationsMenuView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+
+	spawn_local_scoped(ctx, async move {
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+		let add_subscription_result = {
+			let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+			let mut subscription_manager = subscription_manager.lock().await;
+			subscription_manager
+				.add_persistent_subscription(SubscriptionType::UserNotifications, &mut ws)
+				.await
+		};
+		if let Err(error) = add_subscription_result {
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Couldn't send notifications subscription message.",
+				error,
+			));
+		}
+	});
+
+	let notifications = create_memo(ctx, || (*data.notifications.get()).clone());
+	let unread_count = create_memo(ctx, || {
+		notifications
+			.get()
+			.iter()
+			.filter(|notification| !notification.read)
+			.count()
+	});
+
+	let mark_all_read_handler = move |_event: WebEvent| {
+		send_notification_update(ctx, UserNotificationUpdate::MarkAllRead);
+	};
+
+	view! {
+		ctx,
+		div(id="user_notifications") {
+			img(src="images/notifications.svg", alt="Notifications", title="Notifications")
+			(if *unread_count.get() > 0 {
+				view! {
+					ctx,
+					span(id="user_notifications_unread") { (unread_count.get()) }
+				}
+			} else {
+				view! { ctx, }
+			})
+			ul(id="user_notifications_list", class="user_info_menu") {
+				(if notifications.get().is_empty() {
+					view! {
+						ctx,
+						li(class="user_notifications_empty") { "No notifications" }
+					}
+				} else if *unread_count.get() > 0 {
+					view! {
+						ctx,
+						li {
+							button(type="button", on:click=mark_all_read_handler) { "Mark all read" }
+						}
+					}
+				} else {
+					view! { ctx, }
+				})
+				Keyed(
+					iterable=notifications,
+					key=|notification| (notification.id.clone(), notification.read),
+					view=move |ctx, notification| {
+						let event_log_link = format!("/log/{}", notification.event.id);
+						let time = notification.time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+						let read = notification.read;
+						let notification_id = notification.id.clone();
+						let open_handler = move |_event: WebEvent| {
+							if !read {
+								send_notification_update(ctx, UserNotificationUpdate::MarkRead(vec![notification_id.clone()]));
+							}
+						};
+						view! {
+							ctx,
+							li(class=if read { "user_notification_read" } else { "user_notification_unread" }) {
+								span(class="user_notification_time") { (time) }
+								a(href=event_log_link, on:click=open_handler) { (notification.to_string()) }
+							}
+						}
+					}
+				)
+			}
+		}
+	}
+}
 
-	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+/// Sends a change to the user's notifications to the server
+fn send_notification_update(ctx: Scope<'_>, update: UserNotificationUpdate) {
+	spawn_local_scoped(ctx, async move {
+		let data: &DataSignals = use_context(ctx);
+		let message =
+			FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::UserNotificationsUpdate(update)));
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize notification update.",
+					error,
+				));
+				return;
+			}
+		};
+
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+		if let Err(error) = ws.send(Message::Text(message_json)).await {
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to send notification update.", error));
+		}
+	});
+}
+
+/// Waits for the subscription data for the given event to be available
+async fn wait_for_event_subscription_data(ctx: Scope<'_>, event_id: &str) -> EventSubscriptionSignals {
+	poll_fn(|poll_context: &mut Context<'_>| {
 		log::debug!(
 			"[User Info Bar] Checking whether event {} is present yet in the subscription manager",
 			event_id
 		);
 
 		let data: &DataSignals = use_context(ctx);
-		match data.events.get().get(&event_id) {
+		match data.events.get().get(event_id) {
 			Some(event_data) => Poll::Ready(event_data.clone()),
 			None => {
 				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
 				event_wakers
 					.modify()
-					.entry(event_id.clone())
+					.entry(event_id.to_string())
 					.or_default()
 					.push(poll_context.waker().clone());
 				Poll::Pending
 			}
 		}
 	})
-	.await;
+	.await
+}
+
+/// Shows a summary of the event's entries that updates as the event log changes
+#[component]
+async fn EventEntryTickerView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let event_id_signal: &Signal<Option<EventId>> = use_context(ctx);
+	let event_id = (*event_id_signal.get())
+		.as_ref()
+		.map(|id| id.to_string())
+		.unwrap_or_default();
+
+	let event_subscription_data = wait_for_event_subscription_data(ctx, &event_id).await;
+
+	// The count of recent entries depends on the current time, so it needs to be recalculated periodically even when
+	// no entries change.
+	let current_time = create_rc_signal(Utc::now());
+	let _current_time_interval = create_ref(
+		ctx,
+		Interval::new(TICKER_TIME_UPDATE_INTERVAL_MS, {
+			let current_time = current_time.clone();
+			move || current_time.set(Utc::now())
+		}),
+	);
+
+	let entries_signal = event_subscription_data.event_log_entries.clone();
+	let entry_count = create_memo(ctx, {
+		let entries_signal = entries_signal.clone();
+		move || entries_signal.get().len()
+	});
+	let recent_entry_count = create_memo(ctx, {
+		let entries_signal = entries_signal.clone();
+		move || {
+			let recent_cutoff = *current_time.get() - Duration::hours(1);
+			entries_signal
+				.get()
+				.iter()
+				.filter(|entry| entry.created_at > recent_cutoff)
+				.count()
+		}
+	});
+	let videos_done_count = create_memo(ctx, move || {
+		entries_signal
+			.get()
+			.iter()
+			.filter(|entry| entry.video_processing_state == VideoProcessingState::Done)
+			.count()
+	});
+
+	view! {
+		ctx,
+		span(title="Entries in this event") { (entry_count.get()) " entries" }
+		" · "
+		span(title="Entries created in the last hour") { (recent_entry_count.get()) " in the last hour" }
+		" · "
+		span(title="Entries with finished videos") { (videos_done_count.get()) " videos done" }
+	}
+}
+
+#[component]
+async fn EventInfoPagesView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let event_id_signal: &Signal<Option<EventId>> = use_context(ctx);
+	let event_id = (*event_id_signal.get())
+		.as_ref()
+		.map(|id| id.to_string())
+		.unwrap_or_default();
+
+	let event_subscription_data = wait_for_event_subscription_data(ctx, &event_id).await;
 
 	log::info!("Found info pages: {:?}", event_subscription_data.info_pages.get());
 
-	let info_page_signal = create_memo(ctx, move || {
-		let mut pages = (*event_subscription_data.info_pages.get()).clone();
-		pages.sort_by(|a, b| a.title.cmp(&b.title));
-		pages
+	let info_page_signal = create_memo(ctx, {
+		let info_pages_signal = event_subscription_data.info_pages.clone();
+		move || {
+			let mut pages = (*info_pages_signal.get()).clone();
+			pages.sort_by(|a, b| a.title.cmp(&b.title));
+			pages
+		}
+	});
+	let feedback_count = create_memo(ctx, {
+		let feedback_signal = event_subscription_data.feedback.clone();
+		move || feedback_signal.get().len()
+	});
+	let is_supervisor = create_memo(ctx, move || {
+		*event_subscription_data.permission.get() == PermissionLevel::Supervisor
 	});
+	let dead_links_url = format!("/log/{}/dead_links", event_id);
+	let time_shift_url = format!("/log/{}/time_shift", event_id);
+	let editors_url = format!("/log/{}/editors", event_id);
+	let tabs_url = format!("/log/{}/tabs", event_id);
+	let templates_url = format!("/log/{}/templates", event_id);
+	let recurring_url = format!("/log/{}/recurring", event_id);
+	let import_url = format!("/log/{}/import", event_id);
+	let feedback_url = format!("/log/{}/feedback", event_id);
 
 	view! {
 		ctx,
+		(if *is_supervisor.get() {
+			let dead_links_url = dead_links_url.clone();
+			let time_shift_url = time_shift_url.clone();
+			let editors_url = editors_url.clone();
+			let tabs_url = tabs_url.clone();
+			let templates_url = templates_url.clone();
+			let recurring_url = recurring_url.clone();
+			let import_url = import_url.clone();
+			let feedback_url = feedback_url.clone();
+			view! {
+				ctx,
+				li {
+					a(href=dead_links_url) { "Dead Links" }
+				}
+				li {
+					a(href=time_shift_url) { "Shift Entry Times" }
+				}
+				li {
+					a(href=editors_url) { "Manage Editors" }
+				}
+				li {
+					a(href=tabs_url) { "Manage Tabs" }
+				}
+				li {
+					a(href=templates_url) { "Entry Templates" }
+				}
+				li {
+					a(href=recurring_url) { "Recurring Entries" }
+				}
+				li {
+					a(href=import_url) { "Import Entries" }
+				}
+				li {
+					a(href=feedback_url) { "Feedback (" (feedback_count.get()) ")" }
+				}
+			}
+		} else {
+			view! { ctx, }
+		})
 		Keyed(
 			iterable=info_page_signal,
 			key=|page| page.id.clone(),
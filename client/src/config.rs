@@ -0,0 +1,90 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use gloo_net::http::Request;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+static CLIENT_CONFIG: OnceLock<ClientConfig> = OnceLock::new();
+
+/// Deployment configuration for the client, loaded from config.json before the application starts. Every field is
+/// optional in the file; anything missing uses the default for a standard Stream Log setup.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ClientConfig {
+	/// Path of the websocket endpoint, relative to the base URL of the application
+	pub websocket_path: String,
+	/// Path of the REST API, relative to the base URL of the application
+	pub api_base_path: String,
+	pub features: FeatureFlags,
+	pub branding: BrandingConfig,
+}
+
+impl Default for ClientConfig {
+	fn default() -> Self {
+		Self {
+			websocket_path: String::from("ws"),
+			api_base_path: String::from("api/v1"),
+			features: FeatureFlags::default(),
+			branding: BrandingConfig::default(),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct FeatureFlags {
+	/// Whether to send typing notifications to other users while editing entries
+	pub typing_notifications: bool,
+}
+
+impl Default for FeatureFlags {
+	fn default() -> Self {
+		Self {
+			typing_notifications: true,
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BrandingConfig {
+	/// The name of the site, used in page titles
+	pub site_name: String,
+}
+
+impl Default for BrandingConfig {
+	fn default() -> Self {
+		Self {
+			site_name: String::from("Stream Log"),
+		}
+	}
+}
+
+/// Loads the client configuration from config.json. If the file doesn't exist or can't be read, the default
+/// configuration is used instead. This should be called once before anything reads the configuration.
+pub async fn load_client_config() {
+	let config = match Request::get("config.json").send().await {
+		Ok(response) if response.ok() => match response.json().await {
+			Ok(config) => config,
+			Err(error) => {
+				log::error!("Failed to parse client configuration; using defaults: {}", error);
+				ClientConfig::default()
+			}
+		},
+		Ok(_) => ClientConfig::default(),
+		Err(error) => {
+			log::warn!("Failed to retrieve client configuration; using defaults: {}", error);
+			ClientConfig::default()
+		}
+	};
+	let _ = CLIENT_CONFIG.set(config);
+}
+
+/// Gets the client configuration. If the configuration hasn't been loaded, the default configuration is used.
+pub fn client_config() -> &'static ClientConfig {
+	CLIENT_CONFIG.get_or_init(ClientConfig::default)
+}
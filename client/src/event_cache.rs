@@ -0,0 +1,204 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::subscriptions::event::{EventSubscriptionSignals, EventSubscriptionSignalsInitData};
+use chrono::{DateTime, Utc};
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use stream_log_shared::messages::subscriptions::InitialEventSubscriptionLoadData;
+use stream_log_shared::SYNC_VERSION;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, Event as WebEvent, IdbDatabase, IdbRequest, IdbTransactionMode};
+
+/// Name of the IndexedDB database in which event data is cached
+const DATABASE_NAME: &str = "stream_log_cache";
+/// Version of the database's layout. This only needs to change when object stores are added or changed; changes to
+/// the cached data itself are handled by the sync version stored with it.
+const DATABASE_VERSION: u32 = 1;
+/// Object store holding the cached data for each event, keyed by event ID
+const EVENT_STORE_NAME: &str = "events";
+
+/// An event's data as of the last time it was loaded. This is shown when the event is opened again while the current
+/// data is loaded from the server.
+#[derive(Deserialize, Serialize)]
+struct CachedEventData {
+	/// The [SYNC_VERSION] of the client that cached the data. Data cached by other versions may not match what the
+	/// server sends now.
+	sync_version: u32,
+	/// The user the data was loaded for, as what's sent depends on the user's permissions
+	user_id: String,
+	cached_at: DateTime<Utc>,
+	event_data: InitialEventSubscriptionLoadData,
+}
+
+/// Waits for an IndexedDB request to finish, returning its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+	let (sender, receiver) = oneshot::channel();
+	let sender = Rc::new(RefCell::new(Some(sender)));
+	let finish_handler = Closure::<dyn FnMut(WebEvent)>::new(move |_event: WebEvent| {
+		if let Some(sender) = sender.borrow_mut().take() {
+			let _ = sender.send(());
+		}
+	});
+	request.set_onsuccess(Some(finish_handler.as_ref().unchecked_ref()));
+	request.set_onerror(Some(finish_handler.as_ref().unchecked_ref()));
+	let _ = receiver.await;
+	request.set_onsuccess(None);
+	request.set_onerror(None);
+
+	if let Some(error) = request.error()? {
+		return Err(error.into());
+	}
+	request.result()
+}
+
+async fn open_database() -> Result<IdbDatabase, JsValue> {
+	let Some(factory) = window().and_then(|window| window.indexed_db().ok().flatten()) else {
+		return Err(JsValue::from_str("IndexedDB is unavailable"));
+	};
+	let open_request = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+	let upgrade_handler = Closure::<dyn FnMut(WebEvent)>::new({
+		let open_request = open_request.clone();
+		move |_event: WebEvent| {
+			let Ok(database) = open_request.result() else {
+				return;
+			};
+			let database: IdbDatabase = database.unchecked_into();
+			if let Err(error) = database.create_object_store(EVENT_STORE_NAME) {
+				log::error!("Failed to create the event cache: {:?}", error);
+			}
+		}
+	});
+	open_request.set_onupgradeneeded(Some(upgrade_handler.as_ref().unchecked_ref()));
+	let database = request_result(&open_request).await;
+	open_request.set_onupgradeneeded(None);
+	Ok(database?.unchecked_into())
+}
+
+async fn read_cached_event(event_id: &str) -> Result<Option<String>, JsValue> {
+	let database = open_database().await?;
+	let transaction = database.transaction_with_str_and_mode(EVENT_STORE_NAME, IdbTransactionMode::Readonly)?;
+	let request = transaction
+		.object_store(EVENT_STORE_NAME)?
+		.get(&JsValue::from_str(event_id))?;
+	let cached_data = request_result(&request).await;
+	database.close();
+	Ok(cached_data?.as_string())
+}
+
+async fn write_cached_event(event_id: &str, cached_data: Option<&str>) -> Result<(), JsValue> {
+	let database = open_database().await?;
+	let transaction = database.transaction_with_str_and_mode(EVENT_STORE_NAME, IdbTransactionMode::Readwrite)?;
+	let store = transaction.object_store(EVENT_STORE_NAME)?;
+	let key = JsValue::from_str(event_id);
+	let request = match cached_data {
+		Some(cached_data) => store.put_with_key(&JsValue::from_str(cached_data), &key)?,
+		None => store.delete(&key)?,
+	};
+	let result = request_result(&request).await;
+	database.close();
+	result.map(|_| ())
+}
+
+/// Gets the data cached for an event the last time the user loaded it, if there is any
+pub async fn load_cached_event(user_id: &str, event_id: &str) -> Option<EventSubscriptionSignalsInitData> {
+	let cached_data = match read_cached_event(event_id).await {
+		Ok(Some(cached_data)) => cached_data,
+		Ok(None) => return None,
+		Err(error) => {
+			log::warn!("Failed to read cached data for event {}: {:?}", event_id, error);
+			return None;
+		}
+	};
+	let cached_data: CachedEventData = match serde_json::from_str(&cached_data) {
+		Ok(data) => data,
+		Err(error) => {
+			log::debug!("Ignoring unreadable cached data for event {}: {}", event_id, error);
+			return None;
+		}
+	};
+	if cached_data.sync_version != SYNC_VERSION || cached_data.user_id != user_id {
+		return None;
+	}
+	log::debug!("Loaded data for event {} cached at {}", event_id, cached_data.cached_at);
+
+	let event_data = cached_data.event_data;
+	Some(EventSubscriptionSignalsInitData {
+		event: event_data.event,
+		permission: event_data.permission,
+		entry_types: event_data.entry_types,
+		tags: event_data.tags,
+		video_edit_state_rules: event_data.video_edit_state_rules,
+		pending_tag_actions: event_data.pending_tag_actions,
+		feedback: event_data.feedback,
+		editors: event_data.editors,
+		viewers: event_data.viewers,
+		entry_locks: event_data.entry_locks,
+		info_pages: event_data.info_pages,
+		event_log_tabs: event_data.tabs,
+		entry_templates: event_data.entry_templates,
+		recurring_entries: event_data.recurring_entries,
+		custom_fields: event_data.custom_fields,
+		event_log_entries: event_data.entries,
+		pending_entry_count: event_data.pending_entry_count,
+		unloaded_tab_ids: event_data.unloaded_tab_ids,
+		new_event_log_entries: event_data.new_entries,
+	})
+}
+
+/// Caches the current data for an event so that it can be shown right away the next time the user opens the event
+pub async fn cache_event(user_id: &str, event_signals: &EventSubscriptionSignals) {
+	let event = (*event_signals.event.get_untracked()).clone();
+	let event_id = event.id.clone();
+	let event_data = InitialEventSubscriptionLoadData {
+		event,
+		permission: *event_signals.permission.get_untracked(),
+		entry_types: (*event_signals.entry_types.get_untracked()).clone(),
+		tags: (*event_signals.tags.get_untracked()).clone(),
+		video_edit_state_rules: (*event_signals.video_edit_state_rules.get_untracked()).clone(),
+		pending_tag_actions: (*event_signals.pending_tag_actions.get_untracked()).clone(),
+		feedback: (*event_signals.feedback.get_untracked()).clone(),
+		editors: (*event_signals.editors.get_untracked()).clone(),
+		// Who's viewing the event and editing entries only matters while it's happening
+		viewers: Vec::new(),
+		entry_locks: Vec::new(),
+		info_pages: (*event_signals.info_pages.get_untracked()).clone(),
+		tabs: (*event_signals.event_log_tabs.get_untracked()).clone(),
+		entry_templates: (*event_signals.entry_templates.get_untracked()).clone(),
+		recurring_entries: (*event_signals.recurring_entries.get_untracked()).clone(),
+		custom_fields: (*event_signals.custom_fields.get_untracked()).clone(),
+		entries: (*event_signals.event_log_entries.get_untracked()).clone(),
+		pending_entry_count: 0,
+		unloaded_tab_ids: event_signals.unloaded_tab_ids.get_untracked().iter().cloned().collect(),
+		new_entries: Vec::new(),
+	};
+	let cached_data = CachedEventData {
+		sync_version: SYNC_VERSION,
+		user_id: user_id.to_string(),
+		cached_at: Utc::now(),
+		event_data,
+	};
+	let cached_data = match serde_json::to_string(&cached_data) {
+		Ok(data) => data,
+		Err(error) => {
+			log::error!("Failed to serialize data for event {} for caching: {}", event_id, error);
+			return;
+		}
+	};
+	if let Err(error) = write_cached_event(&event_id, Some(&cached_data)).await {
+		log::warn!("Failed to cache data for event {}: {:?}", event_id, error);
+	}
+}
+
+/// Removes the cached data for an event, such as when the user can no longer access it
+pub async fn remove_cached_event(event_id: &str) {
+	if let Err(error) = write_cached_event(event_id, None).await {
+		log::warn!("Failed to remove cached data for event {}: {:?}", event_id, error);
+	}
+}
@@ -9,27 +9,39 @@ use futures::task::Waker;
 use futures::StreamExt;
 use gloo_net::websocket::futures::WebSocket;
 use std::collections::HashMap;
-use stream_log_shared::messages::initial::{InitialMessage, UserDataLoad};
-use stream_log_shared::SYNC_VERSION;
+use stream_log_shared::messages::initial::{InitialMessage, SyncCompatibility, UserDataLoad};
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
 use sycamore_router::{HistoryIntegration, Route, Router};
+use web_sys::Event as WebEvent;
 
 mod color_utils;
 mod components;
+mod config;
 mod entry_type_colors;
 mod entry_utils;
+mod event_cache;
+mod notifications;
 mod page_utils;
 mod pages;
+mod pending_edits;
 mod subscriptions;
 mod websocket;
 use components::error_display::ErrorDisplay;
+use components::load_progress_display::LoadProgressDisplay;
+use components::notification_toasts::NotificationToasts;
 use components::user_info_bar::{EventId, UserInfoBar};
-use page_utils::set_page_title;
+use config::load_client_config;
+use page_utils::set_default_page_title;
 use pages::admin::assign_entry_types::AdminManageEntryTypesForEventsView;
 use pages::admin::assign_groups::AssignUsersToGroupsView;
+use pages::admin::audit_log::AdminAuditLogView;
+use pages::admin::entry_type_matrix::AdminEntryTypeMatrixView;
+use pages::admin::jobs::AdminJobsView;
+use pages::admin::manage_access_requests::AdminEventAccessRequestsView;
 use pages::admin::manage_applications::AdminApplicationsView;
+use pages::admin::manage_custom_fields::AdminEventCustomFieldsView;
 use pages::admin::manage_editors::AdminManageEditorsView;
 use pages::admin::manage_entry_types::AdminManageEntryTypesView;
 use pages::admin::manage_events::AdminManageEventsView;
@@ -37,17 +49,31 @@ use pages::admin::manage_groups::AdminManageGroupsView;
 use pages::admin::manage_info_pages::AdminInfoPagesView;
 use pages::admin::manage_tabs::AdminManageEventLogTabsView;
 use pages::admin::manage_users::AdminManageUsersView;
+use pages::admin::manage_webhooks::AdminEventWebhooksView;
+use pages::admin::user_event_permissions::AdminUserEventPermissionsView;
+use pages::event_log::dead_links::EventLogDeadLinksView;
+use pages::event_log::editor_notes::EventLogEditorNotesView;
+use pages::event_log::editors::EventLogEditorsView;
+use pages::event_log::editors_dashboard::EventLogEditorsDashboardView;
+use pages::event_log::entry_templates::EventLogEntryTemplatesView;
 use pages::event_log::entry_types::EventLogEntryTypesView;
+use pages::event_log::feedback::EventLogFeedbackView;
+use pages::event_log::import::EventLogImportView;
 use pages::event_log::info_page::EventLogInfoPageView;
 use pages::event_log::log::EventLogView;
+use pages::event_log::recurring_entries::EventLogRecurringEntriesView;
+use pages::event_log::stats::EventLogStatsView;
+use pages::event_log::tabs::EventLogTabsView;
 use pages::event_log::tags::EventLogTagsView;
+use pages::event_log::time_shift::EventLogTimeShiftView;
 use pages::event_selection::EventSelectionView;
 use pages::not_found::NotFoundView;
 use pages::register::RegistrationView;
 use pages::register_complete::RegistrationCompleteView;
 use pages::user_profile::UserProfileView;
+use subscriptions::keepalive::keep_connection_alive;
 use subscriptions::manager::SubscriptionManager;
-use subscriptions::{initial_events_sort, process_messages, DataSignals};
+use subscriptions::{initial_events_sort, process_messages, release_subscriptions_after_grace_period, DataSignals};
 use websocket::{read_websocket, websocket_endpoint, WebSocketSendStream};
 
 #[derive(Debug, Route)]
@@ -64,6 +90,28 @@ enum AppRoutes {
 	EventLogTags(String),
 	#[to("/log/<id>/entry_types")]
 	EventLogEntryTypes(String),
+	#[to("/log/<id>/dead_links")]
+	EventLogDeadLinks(String),
+	#[to("/log/<id>/notes")]
+	EventLogEditorNotes(String),
+	#[to("/log/<id>/time_shift")]
+	EventLogTimeShift(String),
+	#[to("/log/<id>/editors")]
+	EventLogEditors(String),
+	#[to("/log/<id>/editors_dashboard")]
+	EventLogEditorsDashboard(String),
+	#[to("/log/<id>/stats")]
+	EventLogStats(String),
+	#[to("/log/<id>/tabs")]
+	EventLogTabs(String),
+	#[to("/log/<id>/feedback")]
+	EventLogFeedback(String),
+	#[to("/log/<id>/templates")]
+	EventLogEntryTemplates(String),
+	#[to("/log/<id>/recurring")]
+	EventLogRecurringEntries(String),
+	#[to("/log/<id>/import")]
+	EventLogImport(String),
 	#[to("/log/<event_id>/page/<page_id>")]
 	EventLogInfoPage(String, String),
 	#[to("/admin/events")]
@@ -74,10 +122,14 @@ enum AppRoutes {
 	AdminPermissionGroupManager,
 	#[to("/admin/assign_groups")]
 	AdminUserGroupAssignmentManager,
+	#[to("/admin/user_event_permissions")]
+	AdminUserEventPermissionsManager,
 	#[to("/admin/event_types")]
 	AdminEntryTypeManager,
 	#[to("/admin/assign_event_types")]
 	AdminEntryTypesForEventManager,
+	#[to("/admin/entry_type_matrix")]
+	AdminEntryTypeMatrix,
 	#[to("/admin/editors")]
 	AdminEditorsManager,
 	#[to("/admin/tabs")]
@@ -86,6 +138,16 @@ enum AppRoutes {
 	AdminApplicationsManager,
 	#[to("/admin/info_pages")]
 	AdminInfoPagesManager,
+	#[to("/admin/access_requests")]
+	AdminEventAccessRequestsManager,
+	#[to("/admin/webhooks")]
+	AdminEventWebhooksManager,
+	#[to("/admin/custom_fields")]
+	AdminEventCustomFieldsManager,
+	#[to("/admin/audit")]
+	AdminAuditLog,
+	#[to("/admin/jobs")]
+	AdminJobs,
 	#[to("/user_profile")]
 	UserProfile,
 	#[not_found]
@@ -94,6 +156,8 @@ enum AppRoutes {
 
 #[component]
 async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
+	load_client_config().await;
+
 	let ws = WebSocket::open(websocket_endpoint().as_str());
 	let ws = match ws {
 		Ok(ws) => ws,
@@ -127,19 +191,28 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 		}
 	};
 
-	if initial_message.sync_version != SYNC_VERSION {
+	let sync_compatibility = initial_message.client_compatibility();
+	if sync_compatibility == SyncCompatibility::UpdateRequired {
+		let reload_handler = |_event: WebEvent| {
+			if let Some(window) = web_sys::window() {
+				let _ = window.location().reload();
+			}
+		};
 		return view! {
 			ctx,
 			div(id="fatal_startup_error") {
 				div(id="fatal_startup_error_description") {
-					"A mismatch in communication protocols occurred between the client and the server. Please refresh the page. If the problem persists, please contact an administrator."
+					"This page doesn't match the version of the server. Please reload the page to update it. If the problem persists after reloading, please contact an administrator."
 				}
+				button(id="fatal_startup_error_reload", on:click=reload_handler) { "Reload" }
 			}
 		};
 	}
 
 	let initial_data = match initial_message.user_data {
-		UserDataLoad::User(user_data, available_events) => Some((user_data, available_events)),
+		UserDataLoad::User(user_data, available_events, hotkeys, filter_presets) => {
+			Some((user_data, available_events, hotkeys, filter_presets))
+		}
 		UserDataLoad::NewUser => None,
 		UserDataLoad::MissingId => {
 			return view! {
@@ -151,6 +224,16 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 				}
 			};
 		}
+		UserDataLoad::Deactivated => {
+			return view! {
+				ctx,
+				div(id="fatal_startup_error") {
+					div(id="fatal_startup_error_description") {
+						"Your account has been deactivated. Please contact an administrator if you think this is a mistake."
+					}
+				}
+			};
+		}
 		UserDataLoad::Error => {
 			return view! {
 				ctx,
@@ -162,12 +245,13 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 			}
 		}
 	};
-	let (user_data, available_events) = if let Some((user, mut events)) = initial_data {
-		initial_events_sort(&mut events);
-		(Some(user), Some(events))
-	} else {
-		(None, None)
-	};
+	let (user_data, available_events, hotkeys, filter_presets) =
+		if let Some((user, mut events, hotkeys, filter_presets)) = initial_data {
+			initial_events_sort(&mut events);
+			(Some(user), Some(events), hotkeys, filter_presets)
+		} else {
+			(None, None, Vec::new(), Vec::new())
+		};
 	provide_context_ref(ctx, create_signal(ctx, user_data));
 
 	// Assuming the WASM client for this might multithread at any point in the future is probably way overkill.
@@ -181,13 +265,17 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 	if let Some(events) = available_events {
 		client_data.available_events = create_rc_signal(events);
 	}
+	client_data.hotkeys = create_rc_signal(hotkeys);
+	client_data.filter_presets = create_rc_signal(filter_presets);
+	client_data.sync_compatibility = create_rc_signal(sync_compatibility);
+	let subscription_manager = Mutex::new(SubscriptionManager::new(client_data.stores.clone()));
 	provide_context(ctx, client_data);
-	let subscription_manager = Mutex::new(SubscriptionManager::default());
 	provide_context(ctx, subscription_manager);
 	let event_wakers: HashMap<String, Vec<Waker>> = HashMap::new();
 	provide_context_ref(ctx, create_signal(ctx, event_wakers));
 
 	spawn_local_scoped(ctx, process_messages(ctx, ws_read));
+	spawn_local_scoped(ctx, keep_connection_alive(ctx));
 
 	let current_event_id: &Signal<Option<EventId>> = create_signal(ctx, None);
 	provide_context_ref(ctx, current_event_id);
@@ -195,6 +283,8 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 	view! {
 		ctx,
 		ErrorDisplay
+		LoadProgressDisplay
+		NotificationToasts
 		Router(
 			integration=HistoryIntegration::new(),
 			view=move |ctx, route: &ReadSignal<AppRoutes>| {
@@ -205,12 +295,17 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 						log::info!("Navigating to route: {:?}", route.get());
 
 						// Default the window title in case the page doesn't support/set it
-						set_page_title("Stream Log");
+						set_default_page_title();
 
 						match route.get().as_ref() {
-							AppRoutes::EventLog(id) | AppRoutes::EventLogTags(id) | AppRoutes::EventLogEntryTypes(id) | AppRoutes::EventLogInfoPage(id, _) => current_event_id.set(Some(EventId::new(id.clone()))),
+							AppRoutes::EventLog(id) | AppRoutes::EventLogTags(id) | AppRoutes::EventLogEntryTypes(id) | AppRoutes::EventLogDeadLinks(id) | AppRoutes::EventLogEditorNotes(id) | AppRoutes::EventLogTimeShift(id) | AppRoutes::EventLogEditors(id) | AppRoutes::EventLogEditorsDashboard(id) | AppRoutes::EventLogStats(id) | AppRoutes::EventLogTabs(id) | AppRoutes::EventLogFeedback(id) | AppRoutes::EventLogEntryTemplates(id) | AppRoutes::EventLogRecurringEntries(id) | AppRoutes::EventLogImport(id) | AppRoutes::EventLogInfoPage(id, _) => current_event_id.set(Some(EventId::new(id.clone()))),
 							_ => current_event_id.set(None)
 						}
+						// Pages that don't use subscriptions don't set them, so we need to clear out the subscriptions left over
+						// from the previous page.
+						if matches!(route.get().as_ref(), AppRoutes::EventSelection | AppRoutes::Register | AppRoutes::RegistrationComplete | AppRoutes::UserProfile | AppRoutes::NotFound) {
+							spawn_local_scoped(ctx, release_subscriptions_after_grace_period(ctx));
+						}
 						match route.get().as_ref() {
 							AppRoutes::EventSelection => view! { ctx, EventSelectionView },
 							AppRoutes::Register => view! { ctx, RegistrationView },
@@ -218,17 +313,35 @@ async fn App<G: Html>(ctx: Scope<'_>) -> View<G> {
 							AppRoutes::EventLog(id) => view! { ctx, EventLogView(id=id.clone()) },
 							AppRoutes::EventLogTags(id) => view! { ctx, EventLogTagsView(id=id.clone()) },
 							AppRoutes::EventLogEntryTypes(id) => view! { ctx, EventLogEntryTypesView(id=id.clone()) },
+							AppRoutes::EventLogDeadLinks(id) => view! { ctx, EventLogDeadLinksView(id=id.clone()) },
+							AppRoutes::EventLogEditorNotes(id) => view! { ctx, EventLogEditorNotesView(id=id.clone()) },
+							AppRoutes::EventLogTimeShift(id) => view! { ctx, EventLogTimeShiftView(id=id.clone()) },
+							AppRoutes::EventLogEditors(id) => view! { ctx, EventLogEditorsView(id=id.clone()) },
+							AppRoutes::EventLogEditorsDashboard(id) => view! { ctx, EventLogEditorsDashboardView(id=id.clone()) },
+							AppRoutes::EventLogStats(id) => view! { ctx, EventLogStatsView(id=id.clone()) },
+							AppRoutes::EventLogTabs(id) => view! { ctx, EventLogTabsView(id=id.clone()) },
+							AppRoutes::EventLogFeedback(id) => view! { ctx, EventLogFeedbackView(id=id.clone()) },
+							AppRoutes::EventLogEntryTemplates(id) => view! { ctx, EventLogEntryTemplatesView(id=id.clone()) },
+							AppRoutes::EventLogRecurringEntries(id) => view! { ctx, EventLogRecurringEntriesView(id=id.clone()) },
+							AppRoutes::EventLogImport(id) => view! { ctx, EventLogImportView(id=id.clone()) },
 							AppRoutes::EventLogInfoPage(event_id, page_id) => view! { ctx, EventLogInfoPageView(event_id=event_id.clone(),page_id=page_id.clone()) },
 							AppRoutes::AdminEventManager => view! { ctx, AdminManageEventsView },
 							AppRoutes::AdminUserManager => view! { ctx, AdminManageUsersView },
 							AppRoutes::AdminPermissionGroupManager => view! { ctx, AdminManageGroupsView },
 							AppRoutes::AdminUserGroupAssignmentManager => view! { ctx, AssignUsersToGroupsView },
+							AppRoutes::AdminUserEventPermissionsManager => view! { ctx, AdminUserEventPermissionsView },
 							AppRoutes::AdminEntryTypeManager => view! { ctx, AdminManageEntryTypesView },
 							AppRoutes::AdminEntryTypesForEventManager => view! { ctx, AdminManageEntryTypesForEventsView },
+							AppRoutes::AdminEntryTypeMatrix => view! { ctx, AdminEntryTypeMatrixView },
 							AppRoutes::AdminEditorsManager => view! { ctx, AdminManageEditorsView },
 							AppRoutes::AdminEventLogTabsManager => view! { ctx, AdminManageEventLogTabsView },
 							AppRoutes::AdminApplicationsManager => view! { ctx, AdminApplicationsView },
 							AppRoutes::AdminInfoPagesManager => view! { ctx, AdminInfoPagesView },
+							AppRoutes::AdminEventAccessRequestsManager => view! { ctx, AdminEventAccessRequestsView },
+							AppRoutes::AdminEventWebhooksManager => view! { ctx, AdminEventWebhooksView },
+							AppRoutes::AdminEventCustomFieldsManager => view! { ctx, AdminEventCustomFieldsView },
+							AppRoutes::AdminAuditLog => view! { ctx, AdminAuditLogView },
+							AppRoutes::AdminJobs => view! { ctx, AdminJobsView },
 							AppRoutes::UserProfile => view! { ctx, UserProfileView },
 							AppRoutes::NotFound => view! { ctx, NotFoundView }
 						}
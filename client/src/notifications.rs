@@ -0,0 +1,122 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::cell::RefCell;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::notifications::{NotificationPreferences, UserNotification, UserNotificationDetails};
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, Notification, NotificationOptions, NotificationPermission, OscillatorType};
+
+/// How long the alert sound plays, in seconds
+const ALERT_SOUND_DURATION: f64 = 0.3;
+
+/// The pitch of the alert sound, in Hz
+const ALERT_SOUND_FREQUENCY: f32 = 880.0;
+
+thread_local! {
+	/// Browsers limit the number of audio contexts a page can create, so the same one is used for every alert sound
+	static AUDIO_CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Asks the browser for permission to show desktop notifications if the user hasn't already allowed or blocked them.
+/// Browsers only show the prompt in response to something the user did, so this should be called from an event handler.
+pub fn request_desktop_notification_permission() {
+	if Notification::permission() == NotificationPermission::Default {
+		if let Err(error) = Notification::request_permission() {
+			log::warn!("Failed to request permission for desktop notifications: {:?}", error);
+		}
+	}
+}
+
+/// Whether the user blocked desktop notifications in their browser
+pub fn desktop_notifications_denied() -> bool {
+	Notification::permission() == NotificationPermission::Denied
+}
+
+fn show_desktop_notification(body: &str, tag: &str) {
+	if Notification::permission() != NotificationPermission::Granted {
+		return;
+	}
+	let options = NotificationOptions::new();
+	options.set_body(body);
+	// Notifications with the same tag replace each other instead of piling up
+	options.set_tag(tag);
+	if let Err(error) = Notification::new_with_options("Stream Log", &options) {
+		log::warn!("Failed to show a desktop notification: {:?}", error);
+	}
+}
+
+fn play_alert_sound() {
+	let result = AUDIO_CONTEXT.with(|audio_context| -> Result<(), JsValue> {
+		let mut audio_context = audio_context.borrow_mut();
+		let audio_context = match audio_context.as_ref() {
+			Some(audio_context) => audio_context.clone(),
+			None => {
+				let new_audio_context = AudioContext::new()?;
+				*audio_context = Some(new_audio_context.clone());
+				new_audio_context
+			}
+		};
+		// Audio contexts start out suspended if they're created before the user has interacted with the page
+		let _ = audio_context.resume()?;
+
+		let oscillator = audio_context.create_oscillator()?;
+		oscillator.set_type(OscillatorType::Sine);
+		oscillator.frequency().set_value(ALERT_SOUND_FREQUENCY);
+		let gain = audio_context.create_gain()?;
+		let start_time = audio_context.current_time();
+		gain.gain().set_value_at_time(0.2, start_time)?;
+		gain.gain()
+			.exponential_ramp_to_value_at_time(0.001, start_time + ALERT_SOUND_DURATION)?;
+		oscillator.connect_with_audio_node(&gain)?;
+		gain.connect_with_audio_node(&audio_context.destination())?;
+		oscillator.start_with_when(start_time)?;
+		oscillator.stop_with_when(start_time + ALERT_SOUND_DURATION)?;
+		Ok(())
+	});
+	if let Err(error) = result {
+		log::warn!("Failed to play the alert sound: {:?}", error);
+	}
+}
+
+/// Alerts the user to a notification they received in the ways they chose in their preferences
+pub fn alert_for_notification(preferences: &NotificationPreferences, notification: &UserNotification) {
+	let (sound, desktop) = match notification.details {
+		UserNotificationDetails::EditorAssigned { .. } => {
+			(preferences.editor_assigned_sound, preferences.editor_assigned_desktop)
+		}
+		_ => (
+			preferences.other_notifications_sound,
+			preferences.other_notifications_desktop,
+		),
+	};
+	if sound {
+		play_alert_sound();
+	}
+	if desktop {
+		show_desktop_notification(&notification.to_string(), &notification.id);
+	}
+}
+
+/// Alerts the user that someone else started typing in an log entry, if the user is the entry's editor and chose to be
+/// alerted in their preferences
+pub fn alert_for_typing(user: &SelfUserData, event_name: &str, entry: &EventLogEntry, typing_user: &PublicUserData) {
+	if !entry.editor.as_ref().is_some_and(|editor| editor.id == user.id) {
+		return;
+	}
+	let preferences = &user.notification_preferences;
+	if preferences.typing_in_edited_entry_sound {
+		play_alert_sound();
+	}
+	if preferences.typing_in_edited_entry_desktop {
+		let body = format!(
+			"{}: {} is making changes to \"{}\"",
+			event_name, typing_user.username, entry.description
+		);
+		show_desktop_notification(&body, &format!("typing-{}", entry.id));
+	}
+}
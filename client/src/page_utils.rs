@@ -1,9 +1,50 @@
-use web_sys::window;
+use crate::config::client_config;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Url};
 
+/// Sets the page title, adding the site name
 pub fn set_page_title(new_title: &str) {
+	let site_name = &client_config().branding.site_name;
+	set_document_title(&format!("{} | {}", new_title, site_name));
+}
+
+/// Sets the page title to only the site name
+pub fn set_default_page_title() {
+	set_document_title(&client_config().branding.site_name);
+}
+
+fn set_document_title(title: &str) {
 	if let Some(window) = window() {
 		if let Some(document) = window.document() {
-			document.set_title(new_title);
+			document.set_title(title);
 		}
 	}
 }
+
+/// Gets the URL of a path relative to the base URL at which the application is hosted.
+///
+/// # Panics
+///
+/// This function panics when the browser context (window, location, URL, etc.) is inaccessible.
+pub fn application_url(path: &str) -> Url {
+	let doc = window()
+		.expect("Failed to get browser window context")
+		.document()
+		.expect("Failed to get webpage document root");
+	let doc_node: web_sys::Node = doc.unchecked_into();
+	let web_endpoint = doc_node
+		.base_uri()
+		.expect("Failed to get base address")
+		.expect("Failed to get base address");
+	let url = Url::new(&web_endpoint).expect("Failed to generate URL instance");
+	url.set_search(""); // Query string is unnecessary and should be cleared
+	let url_path = url.pathname();
+	let path = path.trim_start_matches('/');
+	let full_path = if let Some(base_path) = url_path.strip_suffix('/') {
+		format!("{}/{}", base_path, path)
+	} else {
+		format!("{}/{}", url_path, path)
+	};
+	url.set_pathname(&full_path);
+	url
+}
@@ -9,6 +9,7 @@ use crate::entry_type_colors::use_white_foreground;
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEntryTypesEventsStore, AdminEntryTypesStore, AdminEventsStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
@@ -27,7 +28,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 async fn AdminManageEntryTypesForEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Assign Entry Types to Events | Stream Log");
+	set_page_title("Assign Entry Types to Events");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -50,8 +51,13 @@ async fn AdminManageEntryTypesForEventsLoadedView<G: Html>(ctx: Scope<'_>) -> Vi
 		));
 	}
 
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
-	let all_entry_types = create_memo(ctx, || (*data.all_entry_types.get()).clone());
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let entry_types_store: &AdminEntryTypesStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEntryTypes));
+	let entry_types_events_store: &AdminEntryTypesEventsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEntryTypesEvents));
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
+	let all_entry_types = create_memo(ctx, || (*entry_types_store.all_entry_types.get()).clone());
 
 	let selected_event_signal: &Signal<Option<Event>> = create_signal(ctx, None);
 
@@ -59,7 +65,7 @@ async fn AdminManageEntryTypesForEventsLoadedView<G: Html>(ctx: Scope<'_>) -> Vi
 	let entered_event_error_signal = create_signal(ctx, String::new());
 
 	let all_events_name_index = create_memo(ctx, || {
-		let name_index: HashMap<String, Event> = data
+		let name_index: HashMap<String, Event> = events_store
 			.all_events
 			.get()
 			.iter()
@@ -122,7 +128,7 @@ async fn AdminManageEntryTypesForEventsLoadedView<G: Html>(ctx: Scope<'_>) -> Vi
 							let event = event.clone();
 							move |ctx, entry_type| {
 								let is_checked = create_memo(ctx, {
-									let entry_type_event_associations = data.entry_type_event_associations.clone();
+									let entry_type_event_associations = entry_types_events_store.entry_type_event_associations.clone();
 									let entry_type = entry_type.clone();
 									let event = event.clone();
 									move || entry_type_event_associations.get().iter().any(|association| association.event.id == event.id && association.entry_type.id == entry_type.id)
@@ -8,6 +8,7 @@ use crate::color_utils::rgb_str_from_color;
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminPermissionGroupUsersStore, AdminPermissionGroupsStore, AdminUsersStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
@@ -27,7 +28,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 async fn AssignUsersToGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Assign Users to Permission Groups | Stream Log");
+	set_page_title("Assign Users to Permission Groups");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -50,11 +51,17 @@ async fn AssignUsersToGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_users = create_memo(ctx, || (*data.all_users.get()).clone());
-	let all_groups = create_memo(ctx, || (*data.all_permission_groups.get()).clone());
+	let users_store: &AdminUsersStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminUsers));
+	let groups_store: &AdminPermissionGroupsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminPermissionGroups));
+	let group_users_store: &AdminPermissionGroupUsersStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminPermissionGroupUsers));
+
+	let all_users = create_memo(ctx, || (*users_store.all_users.get()).clone());
+	let all_groups = create_memo(ctx, || (*groups_store.all_permission_groups.get()).clone());
 
 	let groups_name_index_signal = create_memo(ctx, || {
-		let name_indexed_groups: HashMap<String, PermissionGroup> = data
+		let name_indexed_groups: HashMap<String, PermissionGroup> = groups_store
 			.all_permission_groups
 			.get()
 			.iter()
@@ -68,10 +75,10 @@ async fn AssignUsersToGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let entered_group_error_signal = create_signal(ctx, String::new());
 
 	let group_users_signal = create_memo(ctx, || {
-		data.user_permission_groups.track();
+		group_users_store.user_permission_groups.track();
 		match selected_group_signal.get().as_ref() {
 			Some(group) => {
-				let user_ids: HashSet<String> = data
+				let user_ids: HashSet<String> = group_users_store
 					.user_permission_groups
 					.get()
 					.iter()
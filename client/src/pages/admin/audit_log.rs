@@ -0,0 +1,215 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminAuditLogStore, AdminRejectedUpdatesStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use stream_log_shared::messages::admin::{AdminActionType, AdminAuditLogEntry, RejectedEventUpdate};
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::user::SelfUserData;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+
+#[component]
+async fn AdminAuditLogLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Audit Log");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+	let add_subscription_result = {
+		let subscriptions = vec![SubscriptionType::AdminRejectedUpdates, SubscriptionType::AdminAuditLog];
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = add_subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send audit log subscription message.",
+			error,
+		));
+	}
+
+	let event_filter = create_signal(ctx, String::new());
+	let user_filter = create_signal(ctx, String::new());
+
+	let rejected_updates_store: &AdminRejectedUpdatesStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminRejectedUpdates));
+	let audit_log_store: &AdminAuditLogStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminAuditLog));
+
+	let rejected_updates = create_memo(ctx, || {
+		let event_filter = event_filter.get().to_lowercase();
+		let user_filter = user_filter.get().to_lowercase();
+		let rejected_updates: Vec<RejectedEventUpdate> = rejected_updates_store
+			.rejected_event_updates
+			.get()
+			.iter()
+			.filter(|update| event_filter.is_empty() || update.event.name.to_lowercase().contains(&event_filter))
+			.filter(|update| user_filter.is_empty() || update.user.username.to_lowercase().contains(&user_filter))
+			.cloned()
+			.collect();
+		rejected_updates
+	});
+
+	let actor_filter = create_signal(ctx, String::new());
+	let action_type_filter = create_signal(ctx, String::new());
+
+	let admin_actions = create_memo(ctx, || {
+		let actor_filter = actor_filter.get().to_lowercase();
+		let action_type_filter = action_type_filter.get();
+		let admin_actions: Vec<AdminAuditLogEntry> = audit_log_store
+			.admin_audit_log
+			.get()
+			.iter()
+			.filter(|entry| actor_filter.is_empty() || entry.actor.username.to_lowercase().contains(&actor_filter))
+			.filter(|entry| action_type_filter.is_empty() || entry.action_type.name() == *action_type_filter)
+			.cloned()
+			.collect();
+		admin_actions
+	});
+
+	let action_type_options = View::new_fragment(
+		AdminActionType::all()
+			.iter()
+			.map(|action_type| {
+				let name = action_type.name();
+				view! { ctx, option(value=name) { (name) } }
+			})
+			.collect(),
+	);
+
+	view! {
+		ctx,
+		h1 { "Audit Log" }
+		h2 { "Administrative Changes" }
+		p {
+			"Changes administrators and supervisors made to events, users, and other settings. The most recent changes are shown first."
+		}
+		div(id="admin_audit_log_action_filters") {
+			input(bind:value=actor_filter, placeholder="Filter by user")
+			select(bind:value=action_type_filter) {
+				option(value="") { "All changes" }
+				(action_type_options)
+			}
+		}
+		(if admin_actions.get().is_empty() {
+			view! {
+				ctx,
+				p { "There are no administrative changes to show." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		table(id="admin_audit_log_admin_actions") {
+			tr {
+				th { "Time" }
+				th { "User" }
+				th { "Type" }
+				th { "Change" }
+			}
+			Keyed(
+				iterable=admin_actions,
+				key=|entry| entry.id.clone(),
+				view=|ctx, entry| {
+					let performed_at = format!("{}", entry.performed_at.format("%Y-%m-%d %H:%M:%S UTC"));
+					let actor_color_style = format!("color: {}", rgb_str_from_color(entry.actor.color));
+					let action_type = entry.action_type.name();
+
+					view! {
+						ctx,
+						tr {
+							td { (performed_at) }
+							td(style=actor_color_style) { (entry.actor.username) }
+							td { (action_type) }
+							td { (entry.description) }
+						}
+					}
+				}
+			)
+		}
+		h2 { "Rejected Changes" }
+		p {
+			"Changes to events that users requested but the server refused to make, such as edits without permission or with invalid data. The most recent changes are shown first."
+		}
+		div(id="admin_audit_log_filters") {
+			input(bind:value=event_filter, placeholder="Filter by event")
+			input(bind:value=user_filter, placeholder="Filter by user")
+		}
+		(if rejected_updates.get().is_empty() {
+			view! {
+				ctx,
+				p { "There are no rejected changes to show." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		table(id="admin_audit_log_rejected_updates") {
+			tr {
+				th { "Time" }
+				th { "Event" }
+				th { "User" }
+				th { "Change" }
+				th { "Entry" }
+				th { "Reason" }
+			}
+			Keyed(
+				iterable=rejected_updates,
+				key=|update| update.id.clone(),
+				view=|ctx, update| {
+					let rejected_at = format!("{}", update.rejected_at.format("%Y-%m-%d %H:%M:%S UTC"));
+					let user_color_style = format!("color: {}", rgb_str_from_color(update.user.color));
+					let entry_id = update.entry_id.unwrap_or_default();
+
+					view! {
+						ctx,
+						tr {
+							td { (rejected_at) }
+							td { (update.event.name) }
+							td(style=user_color_style) { (update.user.username) }
+							td { (update.update_kind) }
+							td(class="admin_audit_log_entry_id") { (entry_id) }
+							td { (update.reason) }
+						}
+					}
+				}
+			)
+		}
+	}
+}
+
+#[component]
+pub fn AdminAuditLogView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+
+	if let Some(user_data) = user_signal.get().as_ref() {
+		if !user_data.is_admin {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	} else {
+		spawn_local_scoped(ctx, async {
+			navigate("/");
+		});
+		return view! { ctx, };
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading audit log..." }) {
+			AdminAuditLogLoadedView
+		}
+	}
+}
@@ -0,0 +1,265 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::entry_type_colors::use_white_foreground;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEntryTypesEventsStore, AdminEntryTypesStore, AdminEventsStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::admin::{AdminEntryTypeEventUpdate, EntryTypeEventAssociation};
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+use web_sys::Event as WebEvent;
+
+/// Identifies a cell in the matrix by event ID and entry type ID
+type MatrixCell = (String, String);
+
+#[component]
+async fn AdminEntryTypeMatrixLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Entry Types for All Events");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let add_subscriptions_result = {
+		let subscriptions = vec![
+			SubscriptionType::AdminEvents,
+			SubscriptionType::AdminEntryTypes,
+			SubscriptionType::AdminEntryTypesEvents,
+		];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = add_subscriptions_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send entry types and events subscription message.",
+			error,
+		));
+	}
+
+	let event_filter = create_signal(ctx, String::new());
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let entry_types_store: &AdminEntryTypesStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEntryTypes));
+	let entry_types_events_store: &AdminEntryTypesEventsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEntryTypesEvents));
+
+	let filtered_events = create_memo(ctx, || {
+		let filter = event_filter.get().to_lowercase();
+		let events: Vec<Event> = events_store
+			.all_events
+			.get()
+			.iter()
+			.filter(|event| filter.is_empty() || event.name.to_lowercase().contains(&filter))
+			.cloned()
+			.collect();
+		events
+	});
+	let all_entry_types = create_memo(ctx, || {
+		let mut entry_types = (*entry_types_store.all_entry_types.get()).clone();
+		entry_types.sort_by_key(|entry_type| entry_type.name.to_lowercase());
+		entry_types
+	});
+
+	let current_associations = create_memo(ctx, || {
+		let associations: HashSet<MatrixCell> = entry_types_events_store
+			.entry_type_event_associations
+			.get()
+			.iter()
+			.map(|association| (association.event.id.clone(), association.entry_type.id.clone()))
+			.collect();
+		associations
+	});
+
+	// Changes the user has made that haven't been saved yet, mapped to whether the entry type should be available
+	let pending_changes: &Signal<HashMap<MatrixCell, bool>> = create_signal(ctx, HashMap::new());
+	let has_pending_changes = create_memo(ctx, || !pending_changes.get().is_empty());
+
+	let save_handler = move |_event: WebEvent| {
+		let events_by_id: HashMap<String, Event> = events_store
+			.all_events
+			.get()
+			.iter()
+			.map(|event| (event.id.clone(), event.clone()))
+			.collect();
+		let entry_types_by_id: HashMap<String, EntryType> = entry_types_store
+			.all_entry_types
+			.get()
+			.iter()
+			.map(|entry_type| (entry_type.id.clone(), entry_type.clone()))
+			.collect();
+
+		let mut added_associations: Vec<EntryTypeEventAssociation> = Vec::new();
+		let mut removed_associations: Vec<EntryTypeEventAssociation> = Vec::new();
+		for ((event_id, entry_type_id), available) in pending_changes.get().iter() {
+			let (Some(event), Some(entry_type)) = (events_by_id.get(event_id), entry_types_by_id.get(entry_type_id))
+			else {
+				continue;
+			};
+			let association = EntryTypeEventAssociation {
+				entry_type: entry_type.clone(),
+				event: event.clone(),
+			};
+			if *available {
+				added_associations.push(association);
+			} else {
+				removed_associations.push(association);
+			}
+		}
+		pending_changes.modify().clear();
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			let message = FromClientMessage::SubscriptionMessage(Box::new(
+				SubscriptionTargetUpdate::AdminEntryTypesEventsUpdate(AdminEntryTypeEventUpdate::UpdateAssociations(
+					added_associations,
+					removed_associations,
+				)),
+			));
+			let message_json = match serde_json::to_string(&message) {
+				Ok(msg) => msg,
+				Err(error) => {
+					let data: &DataSignals = use_context(ctx);
+					data.errors.modify().push(ErrorData::new_with_error(
+						"Failed to serialize entry type/event association updates.",
+						error,
+					));
+					return;
+				}
+			};
+
+			let send_result = ws.send(Message::Text(message_json)).await;
+			if let Err(error) = send_result {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to send entry type/event association updates.",
+					error,
+				));
+			}
+		});
+	};
+
+	let discard_handler = |_event: WebEvent| {
+		pending_changes.modify().clear();
+	};
+
+	view! {
+		ctx,
+		h1 { "Entry Types for All Events" }
+		div(id="admin_entry_type_matrix_controls") {
+			input(bind:value=event_filter, placeholder="Filter events by name")
+			button(type="button", on:click=save_handler, disabled=!*has_pending_changes.get()) { "Save Changes" }
+			button(type="button", on:click=discard_handler, disabled=!*has_pending_changes.get()) { "Discard Changes" }
+		}
+		table(id="admin_entry_type_matrix") {
+			tr {
+				th {}
+				Keyed(
+					iterable=all_entry_types,
+					key=|entry_type| entry_type.id.clone(),
+					view=|ctx, entry_type| {
+						let background_color = rgb_str_from_color(entry_type.color);
+						let foreground_color = if use_white_foreground(&entry_type.color) { "#fff" } else { "#000" };
+						let name_style = format!("color: {}; background: {};", foreground_color, background_color);
+						view! {
+							ctx,
+							th(class="admin_entry_type_matrix_entry_type", style=name_style) { (entry_type.name) }
+						}
+					}
+				)
+			}
+			Keyed(
+				iterable=filtered_events,
+				key=|event| event.id.clone(),
+				view=move |ctx, event| {
+					let event_id = event.id.clone();
+					view! {
+						ctx,
+						tr {
+							th(class="admin_entry_type_matrix_event") { (event.name) }
+							Keyed(
+								iterable=all_entry_types,
+								key=|entry_type| entry_type.id.clone(),
+								view=move |ctx, entry_type| {
+									let cell: MatrixCell = (event_id.clone(), entry_type.id);
+									let is_associated = create_memo(ctx, {
+										let cell = cell.clone();
+										move || current_associations.get().contains(&cell)
+									});
+									let is_changed = create_memo(ctx, {
+										let cell = cell.clone();
+										move || pending_changes.get().contains_key(&cell)
+									});
+									let is_checked = create_memo(ctx, {
+										let cell = cell.clone();
+										move || pending_changes.get().get(&cell).copied().unwrap_or(*is_associated.get())
+									});
+
+									let toggle_handler = move |_event: WebEvent| {
+										let available = !*is_checked.get();
+										let mut pending_changes = pending_changes.modify();
+										if available == *is_associated.get() {
+											pending_changes.remove(&cell);
+										} else {
+											pending_changes.insert(cell.clone(), available);
+										}
+									};
+
+									view! {
+										ctx,
+										td(class=if *is_changed.get() { "admin_entry_type_matrix_changed" } else { "" }) {
+											input(type="checkbox", checked=*is_checked.get(), on:change=toggle_handler)
+										}
+									}
+								}
+							)
+						}
+					}
+				}
+			)
+		}
+	}
+}
+
+#[component]
+pub fn AdminEntryTypeMatrixView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+	if let Some(user) = user.get().as_ref() {
+		if !user.is_admin {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	} else {
+		spawn_local_scoped(ctx, async {
+			navigate("/");
+		});
+		return view! { ctx, };
+	}
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading event type data..." }) {
+			AdminEntryTypeMatrixLoadedView
+		}
+	}
+}
@@ -0,0 +1,148 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::AdminJobsStore;
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use stream_log_shared::messages::admin::{Job, JobStatus};
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::user::SelfUserData;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+#[component]
+async fn AdminJobsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Background Jobs");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+	let add_subscription_result = {
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscriptions(vec![SubscriptionType::AdminJobs], &mut ws)
+			.await
+	};
+	if let Err(error) = add_subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send background jobs subscription message.",
+			error,
+		));
+	}
+
+	let jobs_store: &AdminJobsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminJobs));
+	let show_finished = create_signal(ctx, true);
+
+	let jobs = create_memo(ctx, || {
+		let show_finished = *show_finished.get();
+		let jobs: Vec<Job> = jobs_store
+			.jobs
+			.get()
+			.iter()
+			.filter(|job| show_finished || !job.status.is_finished())
+			.cloned()
+			.collect();
+		jobs
+	});
+
+	view! {
+		ctx,
+		h1 { "Background Jobs" }
+		p {
+			"Work the server does in the background, like delivering webhooks and creating recurring entries. Jobs that fail are tried again a few times with increasing delays. Recently finished jobs are kept until the server restarts. The most recently queued jobs are shown first."
+		}
+		div(id="admin_jobs_filters") {
+			label {
+				input(type="checkbox", bind:checked=show_finished)
+				"Show finished jobs"
+			}
+		}
+		(if jobs.get().is_empty() {
+			view! {
+				ctx,
+				p { "There are no jobs to show." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		table(id="admin_jobs") {
+			tr {
+				th { "Queued" }
+				th { "Job" }
+				th { "Status" }
+				th { "Attempts" }
+				th { "Last Updated" }
+				th { "Last Error" }
+			}
+			Indexed(
+				iterable=jobs,
+				view=|ctx, job| {
+					let queued_at = format!("{}", job.queued_at.format(TIME_FORMAT));
+					let updated_at = format!("{}", job.updated_at.format(TIME_FORMAT));
+					let (status, status_class) = match &job.status {
+						JobStatus::Queued => (String::from("Queued"), "admin_jobs_status_queued"),
+						JobStatus::Running => (String::from("Running"), "admin_jobs_status_running"),
+						JobStatus::WaitingToRetry(retry_time) => (
+							format!("Retrying at {}", retry_time.format(TIME_FORMAT)),
+							"admin_jobs_status_retrying",
+						),
+						JobStatus::Succeeded => (String::from("Succeeded"), "admin_jobs_status_succeeded"),
+						JobStatus::Failed => (String::from("Failed"), "admin_jobs_status_failed"),
+					};
+					let last_error = job.last_error.unwrap_or_default();
+
+					view! {
+						ctx,
+						tr {
+							td { (queued_at) }
+							td { (job.description) }
+							td(class=status_class) { (status) }
+							td { (job.attempts) }
+							td { (updated_at) }
+							td { (last_error) }
+						}
+					}
+				}
+			)
+		}
+	}
+}
+
+#[component]
+pub fn AdminJobsView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+
+	if let Some(user_data) = user_signal.get().as_ref() {
+		if !user_data.is_admin {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	} else {
+		spawn_local_scoped(ctx, async {
+			navigate("/");
+		});
+		return view! { ctx, };
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading background jobs..." }) {
+			AdminJobsLoadedView
+		}
+	}
+}
@@ -0,0 +1,215 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventAccessRequestsStore, AdminPermissionGroupsStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::admin::{AdminEventAccessRequestUpdate, PermissionGroup};
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+use web_sys::Event as WebEvent;
+
+#[component]
+async fn AdminEventAccessRequestsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Review Event Access Requests");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let add_subscriptions_result = {
+		let subscriptions = vec![
+			SubscriptionType::AdminEventAccessRequests,
+			SubscriptionType::AdminPermissionGroups,
+		];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = add_subscriptions_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event access requests subscription message.",
+			error,
+		));
+	}
+
+	let access_requests_store: &AdminEventAccessRequestsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEventAccessRequests));
+	let groups_store: &AdminPermissionGroupsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminPermissionGroups));
+
+	let access_requests = create_memo(ctx, || (*access_requests_store.event_access_requests.get()).clone());
+
+	view! {
+		ctx,
+		(if access_requests.get().is_empty() {
+			view! {
+				ctx,
+				p(id="admin_event_access_requests_empty") { "There are no pending event access requests." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		table(id="admin_event_access_requests") {
+			Keyed(
+				iterable=access_requests,
+				key=|request| (request.user.id.clone(), request.event.id.clone()),
+				view=move |ctx, request| {
+					let available_groups = create_memo(ctx, {
+						let event_id = request.event.id.clone();
+						move || {
+							let group_event_associations = groups_store.permission_group_event_associations.get();
+							let groups: Vec<PermissionGroup> = groups_store
+								.all_permission_groups
+								.get()
+								.iter()
+								.filter(|group| {
+									group_event_associations
+										.iter()
+										.any(|association| association.group == group.id && association.event == event_id)
+								})
+								.cloned()
+								.collect();
+							groups
+						}
+					});
+					let groups_name_index = create_memo(ctx, || {
+						let name_index: HashMap<String, PermissionGroup> = available_groups
+							.get()
+							.iter()
+							.map(|group| (group.name.clone(), group.clone()))
+							.collect();
+						name_index
+					});
+
+					let entered_group_name = create_signal(ctx, String::new());
+					let entered_group_error = create_signal(ctx, String::new());
+
+					let send_update = move |update: AdminEventAccessRequestUpdate| {
+						let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventAccessRequestsUpdate(update)));
+
+						spawn_local_scoped(ctx, async move {
+							let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+							let mut ws = ws_context.lock().await;
+
+							let message_json = match serde_json::to_string(&message) {
+								Ok(msg) => msg,
+								Err(error) => {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to serialize event access request update.", error));
+									return;
+								}
+							};
+
+							let send_result = ws.send(Message::Text(message_json)).await;
+							if let Err(error) = send_result {
+								let data: &DataSignals = use_context(ctx);
+								data.errors.modify().push(ErrorData::new_with_error("Failed to send event access request update.", error));
+							}
+						});
+					};
+
+					let approve_handler = {
+						let request = request.clone();
+						move |event: WebEvent| {
+							event.prevent_default();
+
+							let group_name = entered_group_name.get();
+							let groups_name_index = groups_name_index.get();
+							let Some(group) = groups_name_index.get(&*group_name) else {
+								entered_group_error.set(String::from("Select a group with access to the event."));
+								return;
+							};
+							entered_group_error.set(String::new());
+
+							send_update(AdminEventAccessRequestUpdate::Approve(request.clone(), group.clone()));
+						}
+					};
+
+					let deny_handler = {
+						let request = request.clone();
+						move |_event: WebEvent| {
+							send_update(AdminEventAccessRequestUpdate::Deny(request.clone()));
+						}
+					};
+
+					let groups_list_id = format!("admin_event_access_request_groups_{}_{}", request.user.id, request.event.id);
+					let user_color_style = format!("color: {}", rgb_str_from_color(request.user.color));
+					let requested_at = format!("{}", request.requested_at.format("%Y-%m-%d %H:%M UTC"));
+
+					view! {
+						ctx,
+						tr {
+							td(style=user_color_style) { (request.user.username) }
+							td { (request.event.name) }
+							td { (requested_at) }
+							td {
+								datalist(id=groups_list_id) {
+									Keyed(
+										iterable=available_groups,
+										key=|group| group.id.clone(),
+										view=|ctx, group| view! { ctx, option(value=group.name) }
+									)
+								}
+								form(class="admin_event_access_request_approve", on:submit=approve_handler) {
+									input(
+										list=groups_list_id,
+										placeholder="Permission group",
+										bind:value=entered_group_name,
+										class=if entered_group_error.get().is_empty() { "" } else { "error" }
+									)
+									button(type="submit") { "Approve" }
+									span(class="input_error") { (entered_group_error.get()) }
+								}
+							}
+							td {
+								button(type="button", on:click=deny_handler) { "Deny" }
+							}
+						}
+					}
+				}
+			)
+		}
+	}
+}
+
+#[component]
+pub fn AdminEventAccessRequestsView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+
+	if let Some(user_data) = user_signal.get().as_ref() {
+		if !user_data.is_admin {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	} else {
+		spawn_local_scoped(ctx, async {
+			navigate("/");
+		});
+		return view! { ctx, };
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading event access requests..." }) {
+			AdminEventAccessRequestsLoadedView
+		}
+	}
+}
@@ -4,14 +4,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::page_utils::set_page_title;
+use crate::config::client_config;
+use crate::page_utils::{application_url, set_page_title};
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminApplicationsStore, AdminEventsStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
 use stream_log_shared::messages::admin::{AdminApplicationUpdate, Application};
+use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
 use stream_log_shared::messages::user::SelfUserData;
 use stream_log_shared::messages::FromClientMessage;
@@ -21,22 +24,78 @@ use sycamore::suspense::Suspense;
 use sycamore_router::navigate;
 use web_sys::Event as WebEvent;
 
+#[derive(Prop)]
+struct ApplicationEventScopeProps<'a> {
+	all_events: &'a ReadSignal<Vec<Event>>,
+	all_events_allowed: &'a Signal<bool>,
+	event_scope: &'a Signal<Vec<String>>,
+}
+
+/// Selection of the events an application may access
+#[component]
+fn ApplicationEventScope<'a, G: Html>(ctx: Scope<'a>, props: ApplicationEventScopeProps<'a>) -> View<G> {
+	let all_events_allowed = props.all_events_allowed;
+	let event_scope = props.event_scope;
+
+	view! {
+		ctx,
+		label {
+			"All Events"
+			input(type="checkbox", bind:checked=all_events_allowed)
+		}
+		(if *all_events_allowed.get() {
+			view! { ctx, }
+		} else {
+			view! {
+				ctx,
+				div(class="admin_manage_applications_event_scope") {
+					Keyed(
+						iterable=props.all_events,
+						key=|event| event.id.clone(),
+						view=move |ctx, event| {
+							let event_allowed = create_signal(ctx, event_scope.get_untracked().contains(&event.id));
+							create_effect(ctx, {
+								let event_id = event.id.clone();
+								move || {
+									let allowed = *event_allowed.get();
+									let in_scope = event_scope.get_untracked().contains(&event_id);
+									if allowed && !in_scope {
+										event_scope.modify().push(event_id.clone());
+									} else if !allowed && in_scope {
+										event_scope.modify().retain(|scope_event_id| *scope_event_id != event_id);
+									}
+								}
+							});
+
+							view! {
+								ctx,
+								label {
+									input(type="checkbox", bind:checked=event_allowed)
+									(event.name)
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
 #[component]
 async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Applications | Stream Log");
+	set_page_title("Manage Applications");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
 
 	let data: &DataSignals = use_context(ctx);
-	data.show_application_auth_keys.set(Vec::new());
 
 	let set_subscription_result = {
 		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
 		let mut subscription_manager = subscription_manager.lock().await;
-		subscription_manager
-			.set_subscription(SubscriptionType::AdminApplications, &mut ws)
-			.await
+		let subscriptions = vec![SubscriptionType::AdminApplications, SubscriptionType::AdminEvents];
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
 	};
 	if let Err(error) = set_subscription_result {
 		data.errors.modify().push(ErrorData::new_with_error(
@@ -45,12 +104,22 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let read_applications = create_memo(ctx, || (*data.all_applications.get()).clone());
-	let read_auth_keys = create_memo(ctx, || (*data.show_application_auth_keys.get()).clone());
+	let applications_store: &AdminApplicationsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminApplications));
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	applications_store.show_application_auth_keys.set(Vec::new());
+
+	let read_applications = create_memo(ctx, || (*applications_store.all_applications.get()).clone());
+	let read_auth_keys = create_memo(ctx, || (*applications_store.show_application_auth_keys.get()).clone());
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
 
 	let new_application_name = create_signal(ctx, String::new());
 	let new_application_read_log = create_signal(ctx, false);
 	let new_application_write_links = create_signal(ctx, false);
+	let new_application_write_processing_state = create_signal(ctx, false);
+	let new_application_write_entries = create_signal(ctx, false);
+	let new_application_all_events = create_signal(ctx, true);
+	let new_application_event_scope: &Signal<Vec<String>> = create_signal(ctx, Vec::new());
 	let submit_new_application = move |event: WebEvent| {
 		event.prevent_default();
 
@@ -61,12 +130,22 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 
 		let read_log = *new_application_read_log.get();
 		let write_links = *new_application_write_links.get();
+		let write_processing_state = *new_application_write_processing_state.get();
+		let write_entries = *new_application_write_entries.get();
+		let event_scope = if *new_application_all_events.get() {
+			None
+		} else {
+			Some((*new_application_event_scope.get()).clone())
+		};
 
 		let new_application = Application {
 			id: String::new(),
 			name,
 			read_log,
 			write_links,
+			write_processing_state,
+			write_entries,
+			event_scope,
 		};
 
 		spawn_local_scoped(ctx, async move {
@@ -101,19 +180,33 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			new_application_name.set(String::new());
 			new_application_read_log.set(false);
 			new_application_write_links.set(false);
+			new_application_write_processing_state.set(false);
+			new_application_write_entries.set(false);
+			new_application_all_events.set(true);
+			new_application_event_scope.set(Vec::new());
 		});
 	};
 
+	let api_base_url: String = application_url(&client_config().api_base_path).to_string().into();
+
 	view! {
 		ctx,
+		div(id="admin_manage_applications_api_base") {
+			"Applications access the API at: "
+			span(id="admin_manage_applications_api_base_url") { (api_base_url) }
+		}
 		div(id="admin_manage_applications") {
 			Keyed(
 				iterable=read_applications,
 				key=|app| app.id.clone(),
-				view=|ctx, application| {
+				view=move |ctx, application| {
 					let entered_name = create_signal(ctx, application.name.clone());
 					let entered_read_log = create_signal(ctx, application.read_log);
 					let entered_write_links = create_signal(ctx, application.write_links);
+					let entered_write_processing_state = create_signal(ctx, application.write_processing_state);
+					let entered_write_entries = create_signal(ctx, application.write_entries);
+					let entered_all_events = create_signal(ctx, application.event_scope.is_none());
+					let entered_event_scope = create_signal(ctx, application.event_scope.clone().unwrap_or_default());
 
 					let update_application = {
 						let application = application.clone();
@@ -126,8 +219,15 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 							}
 							let read_log = *entered_read_log.get();
 							let write_links = *entered_write_links.get();
+							let write_processing_state = *entered_write_processing_state.get();
+							let write_entries = *entered_write_entries.get();
+							let event_scope = if *entered_all_events.get() {
+								None
+							} else {
+								Some((*entered_event_scope.get()).clone())
+							};
 
-							let updated_application = Application { id: application.id.clone(), name, read_log, write_links };
+							let updated_application = Application { id: application.id.clone(), name, read_log, write_links, write_processing_state, write_entries, event_scope };
 							spawn_local_scoped(ctx, async move {
 								let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 								let mut ws = ws_context.lock().await;
@@ -247,6 +347,21 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 									input(type="checkbox", bind:checked=entered_write_links)
 								}
 							}
+							div(class="admin_manage_applications_application_write_processing_state") {
+								label {
+									"Write Processing State"
+									input(type="checkbox", bind:checked=entered_write_processing_state)
+								}
+							}
+							div(class="admin_manage_applications_application_write_entries") {
+								label {
+									"Write Entries"
+									input(type="checkbox", bind:checked=entered_write_entries)
+								}
+							}
+							div(class="admin_manage_applications_application_events") {
+								ApplicationEventScope(all_events=all_events, all_events_allowed=entered_all_events, event_scope=entered_event_scope)
+							}
 							div(class="admin_manage_applications_application_update") {
 								button(type="submit") { "Update" }
 							}
@@ -301,6 +416,21 @@ async fn AdminApplicationsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 					input(type="checkbox", bind:checked=new_application_write_links)
 				}
 			}
+			div {
+				label {
+					"Write Processing State"
+					input(type="checkbox", bind:checked=new_application_write_processing_state)
+				}
+			}
+			div {
+				label {
+					"Write Entries"
+					input(type="checkbox", bind:checked=new_application_write_entries)
+				}
+			}
+			div {
+				ApplicationEventScope(all_events=all_events, all_events_allowed=new_application_all_events, event_scope=new_application_event_scope)
+			}
 			button(type="submit") { "Add Application" }
 		}
 	}
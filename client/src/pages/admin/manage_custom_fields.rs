@@ -0,0 +1,370 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventCustomFieldsStore, AdminEventsStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::admin::{AdminEventCustomFieldUpdate, EventCustomField};
+use stream_log_shared::messages::custom_fields::{CustomField, CustomFieldType};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+use web_sys::Event as WebEvent;
+
+/// Gets the options entered for a select field, one per line
+fn select_options_from_entry(options_entry: &str) -> Vec<String> {
+	options_entry
+		.lines()
+		.map(|option| option.trim())
+		.filter(|option| !option.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/// Builds a field type from the type chosen in a type selection box and the entered select options
+fn field_type_from_entry(type_entry: &str, options_entry: &str) -> CustomFieldType {
+	match type_entry {
+		"number" => CustomFieldType::Number,
+		"checkbox" => CustomFieldType::Checkbox,
+		"select" => CustomFieldType::Select(select_options_from_entry(options_entry)),
+		_ => CustomFieldType::Text,
+	}
+}
+
+fn custom_field_error(name: &str, field_type: &CustomFieldType) -> String {
+	if name.trim().is_empty() {
+		String::from("Fields must have a name.")
+	} else if matches!(field_type, CustomFieldType::Select(options) if options.is_empty()) {
+		String::from("Select fields must have at least one option.")
+	} else {
+		String::new()
+	}
+}
+
+async fn send_custom_field_update(ctx: Scope<'_>, update: AdminEventCustomFieldUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(
+		SubscriptionTargetUpdate::AdminEventCustomFieldsUpdate(update),
+	));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize custom field update message.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send custom field update message.",
+			error,
+		));
+	}
+}
+
+#[component]
+async fn AdminEventCustomFieldsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Manage Custom Fields");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let set_subscription_result = {
+		let subscriptions = vec![SubscriptionType::AdminEvents, SubscriptionType::AdminEventCustomFields];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = set_subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to subscribe to admin custom fields.",
+			error,
+		));
+	}
+
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let custom_fields_store: &AdminEventCustomFieldsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEventCustomFields));
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
+	let selected_event: &Signal<Option<Event>> = create_signal(ctx, None);
+
+	let event_custom_fields = create_memo(ctx, {
+		let all_custom_fields = custom_fields_store.all_custom_fields.clone();
+		move || {
+			let all_custom_fields = all_custom_fields.get();
+			let Some(selected_event) = (*selected_event.get()).clone() else {
+				return Vec::new();
+			};
+			let mut event_custom_fields: Vec<CustomField> = all_custom_fields
+				.iter()
+				.filter(|field| field.event.id == selected_event.id)
+				.map(|field| field.field.clone())
+				.collect();
+			event_custom_fields.sort_by_key(|field| field.name.to_lowercase());
+			event_custom_fields
+		}
+	});
+
+	view! {
+		ctx,
+		(if let Some(event) = selected_event.get().as_ref() {
+			let event_name = event.name.clone();
+			let go_back_handler = |_event: WebEvent| {
+				selected_event.set(None);
+			};
+
+			let new_name_entry = create_signal(ctx, String::new());
+			let new_type_entry = create_signal(ctx, String::from("text"));
+			let new_options_entry = create_signal(ctx, String::new());
+			let new_field_type =
+				create_memo(ctx, || field_type_from_entry(&new_type_entry.get(), &new_options_entry.get()));
+			let new_field_error = create_memo(ctx, || {
+				let name = new_name_entry.get();
+				if name.is_empty() {
+					String::new()
+				} else {
+					custom_field_error(&name, &new_field_type.get())
+				}
+			});
+			let add_disabled =
+				create_memo(ctx, || !custom_field_error(&new_name_entry.get(), &new_field_type.get()).is_empty());
+
+			let add_field_handler = move |event: WebEvent| {
+				event.prevent_default();
+
+				let Some(selected_event) = (*selected_event.get()).clone() else {
+					return;
+				};
+				let name = new_name_entry.get().trim().to_string();
+				let field_type = (*new_field_type.get()).clone();
+				if !custom_field_error(&name, &field_type).is_empty() {
+					return;
+				}
+
+				let field = EventCustomField {
+					event: selected_event,
+					field: CustomField { id: String::new(), name, field_type },
+				};
+				spawn_local_scoped(ctx, async move {
+					send_custom_field_update(ctx, AdminEventCustomFieldUpdate::UpdateField(field)).await;
+					new_name_entry.set(String::new());
+					new_type_entry.set(String::from("text"));
+					new_options_entry.set(String::new());
+				});
+			};
+
+			view! {
+				ctx,
+				h1 {
+					"Custom Fields for "
+					(event_name)
+				}
+				a(class="click", on:click=go_back_handler) {
+					"Back to event selection"
+				}
+				p {
+					"Custom fields are added to every entry in the event. A field's type can't be changed after it's added, and select options can't be removed while entries use them."
+				}
+				div(id="admin_event_custom_fields_list") {
+					div(class="admin_event_custom_fields_header") { "Name" }
+					div(class="admin_event_custom_fields_header") { "Type" }
+					div(class="admin_event_custom_fields_header") { "Options (one per line)" }
+					div(class="admin_event_custom_fields_header") {}
+					Keyed(
+						iterable=event_custom_fields,
+						key=|field| {
+							let options = match &field.field_type {
+								CustomFieldType::Select(options) => options.clone(),
+								_ => Vec::new(),
+							};
+							(field.id.clone(), field.name.clone(), options)
+						},
+						view=move |ctx, field| {
+							let name_entry = create_signal(ctx, field.name.clone());
+							let options_entry = create_signal(ctx, match &field.field_type {
+								CustomFieldType::Select(options) => options.join("\n"),
+								_ => String::new(),
+							});
+							let is_select = matches!(field.field_type, CustomFieldType::Select(_));
+							let updated_field_type = create_memo(ctx, {
+								let field_type = field.field_type.clone();
+								move || match field_type {
+									CustomFieldType::Select(_) => {
+										CustomFieldType::Select(select_options_from_entry(&options_entry.get()))
+									}
+									_ => field_type.clone(),
+								}
+							});
+							let field_error =
+								create_memo(ctx, || custom_field_error(&name_entry.get(), &updated_field_type.get()));
+							let save_disabled = create_memo(ctx, {
+								let field = field.clone();
+								move || {
+									!field_error.get().is_empty()
+										|| (*name_entry.get().trim() == field.name
+											&& *updated_field_type.get() == field.field_type)
+								}
+							});
+							let type_name = field.field_type.name();
+
+							let save_handler = {
+								let field = field.clone();
+								move |event: WebEvent| {
+									event.prevent_default();
+									let Some(selected_event) = (*selected_event.get()).clone() else {
+										return;
+									};
+									let name = name_entry.get().trim().to_string();
+									let field_type = (*updated_field_type.get()).clone();
+									if !custom_field_error(&name, &field_type).is_empty() {
+										return;
+									}
+									let field = EventCustomField {
+										event: selected_event,
+										field: CustomField { name, field_type, ..field.clone() },
+									};
+									spawn_local_scoped(ctx, async move {
+										send_custom_field_update(ctx, AdminEventCustomFieldUpdate::UpdateField(field)).await;
+									});
+								}
+							};
+							let delete_handler = move |_event: WebEvent| {
+								let Some(selected_event) = (*selected_event.get()).clone() else {
+									return;
+								};
+								let field = EventCustomField { event: selected_event, field: field.clone() };
+								spawn_local_scoped(ctx, async move {
+									send_custom_field_update(ctx, AdminEventCustomFieldUpdate::DeleteField(field)).await;
+								});
+							};
+
+							view! {
+								ctx,
+								form(class="admin_event_custom_fields_field", on:submit=save_handler) {
+									div {
+										input(bind:value=name_entry, class=if field_error.get().is_empty() { "" } else { "error" })
+									}
+									div { (type_name) }
+									div {
+										(if is_select {
+											view! { ctx, textarea(bind:value=options_entry) }
+										} else {
+											view! { ctx, }
+										})
+									}
+									div {
+										button(type="submit", disabled=*save_disabled.get()) { "Save" }
+										button(type="button", on:click=delete_handler) { "Delete" }
+										span(class="input_error") { (field_error.get()) }
+									}
+								}
+							}
+						}
+					)
+					form(class="admin_event_custom_fields_field", on:submit=add_field_handler) {
+						div {
+							input(bind:value=new_name_entry, placeholder="Field name", class=if new_field_error.get().is_empty() { "" } else { "error" })
+						}
+						div {
+							select(bind:value=new_type_entry) {
+								option(value="text") { "Text" }
+								option(value="number") { "Number" }
+								option(value="checkbox") { "Checkbox" }
+								option(value="select") { "Select" }
+							}
+						}
+						div {
+							(if *new_type_entry.get() == "select" {
+								view! { ctx, textarea(bind:value=new_options_entry) }
+							} else {
+								view! { ctx, }
+							})
+						}
+						div {
+							button(type="submit", disabled=*add_disabled.get()) { "Add" }
+							span(class="input_error") { (new_field_error.get()) }
+						}
+					}
+				}
+			}
+		} else {
+			view! {
+				ctx,
+				div(id="admin_event_custom_fields_event_selection") {
+					Keyed(
+						iterable=all_events,
+						key=|event| event.id.clone(),
+						view=move |ctx, event| {
+							let event_name = event.name.clone();
+							let edit_button_handler = move |_event: WebEvent| {
+								selected_event.set(Some(event.clone()));
+							};
+							view! {
+								ctx,
+								div(class="admin_event_custom_fields_event_selection_event_name") {
+									(event_name)
+								}
+								div(class="admin_event_custom_fields_event_selection_edit") {
+									button(on:click=edit_button_handler) {
+										"Edit Custom Fields"
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn AdminEventCustomFieldsView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+	match user_signal.get().as_ref() {
+		Some(user) => {
+			if !user.is_admin {
+				spawn_local_scoped(ctx, async {
+					navigate("/");
+				});
+				return view! { ctx, };
+			}
+		}
+		None => {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading custom fields..." }) {
+			AdminEventCustomFieldsLoadedView
+		}
+	}
+}
@@ -8,6 +8,7 @@ use crate::color_utils::rgb_str_from_color;
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventEditorsStore, AdminEventsStore, AdminUsersStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
@@ -16,7 +17,7 @@ use std::collections::{HashMap, HashSet};
 use stream_log_shared::messages::admin::{AdminEventEditorUpdate, EditorEventAssociation};
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
-use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
 use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
@@ -26,7 +27,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 async fn AdminManageEditorsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Editors | Stream Log");
+	set_page_title("Manage Editors");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -49,15 +50,27 @@ async fn AdminManageEditorsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
-	let all_users = create_memo(ctx, || (*data.all_users.get()).clone());
+	let editors_store: &AdminEventEditorsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEventEditors));
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let users_store: &AdminUsersStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminUsers));
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
+	let all_users = create_memo(ctx, || {
+		let users: Vec<PublicUserData> = users_store
+			.all_users
+			.get()
+			.iter()
+			.map(|user| user.clone().into())
+			.collect();
+		users
+	});
 
 	let selected_event: &Signal<Option<Event>> = create_signal(ctx, None);
 	let event_input = create_signal(ctx, String::new());
 	let event_input_error = create_signal(ctx, String::new());
 
 	let event_name_index = create_memo(ctx, || {
-		let name_index: HashMap<String, Event> = data
+		let name_index: HashMap<String, Event> = events_store
 			.all_events
 			.get()
 			.iter()
@@ -66,23 +79,6 @@ async fn AdminManageEditorsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		name_index
 	});
 
-	let current_event_editor_ids = create_memo(ctx, || {
-		let event_editors = data.event_editors.get();
-		let current_event = match selected_event.get().as_ref() {
-			Some(event) => event.clone(),
-			None => return HashSet::new(),
-		};
-
-		let mut users: HashSet<String> = HashSet::new();
-		for event_editor in event_editors
-			.iter()
-			.filter(|association| association.event.id == current_event.id)
-		{
-			users.insert(event_editor.editor.id.clone());
-		}
-		users
-	});
-
 	let event_selection_handler = |event: WebEvent| {
 		event.prevent_default();
 
@@ -127,86 +123,132 @@ async fn AdminManageEditorsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			button { "Select Event" }
 			span(class="input_error") { (event_input_error.get()) }
 		}
+		(if let Some(event) = (*selected_event.get()).clone() {
+			view! { ctx, EventEditorsEditor(event=event, users=all_users, editors=editors_store) }
+		} else {
+			view! { ctx, }
+		})
+	}
+}
+
+#[derive(Prop)]
+pub struct EventEditorsEditorProps<'a> {
+	event: Event,
+	/// The users who can be shown as options to be editors
+	users: &'a ReadSignal<Vec<PublicUserData>>,
+	/// The editors data from the subscription used by the page
+	editors: &'a AdminEventEditorsStore,
+}
+
+/// Lists users along with whether they're editors for an event and allows changing which users are editors
+#[component]
+pub fn EventEditorsEditor<'a, G: Html>(ctx: Scope<'a>, props: EventEditorsEditorProps<'a>) -> View<G> {
+	let event: &Event = create_ref(ctx, props.event);
+
+	let current_event_editors = create_memo(ctx, || {
+		let editors: Vec<PublicUserData> = props
+			.editors
+			.event_editors
+			.get()
+			.iter()
+			.filter(|association| association.event.id == event.id)
+			.map(|association| association.editor.clone())
+			.collect();
+		editors
+	});
+	let current_event_editor_ids = create_memo(ctx, || {
+		let editor_ids: HashSet<String> = current_event_editors
+			.get()
+			.iter()
+			.map(|editor| editor.id.clone())
+			.collect();
+		editor_ids
+	});
+
+	// Editors who aren't in the provided list of users are still listed so that they can be removed
+	let listed_users = create_memo(ctx, || {
+		let mut users = (*props.users.get()).clone();
+		let user_ids: HashSet<String> = users.iter().map(|user| user.id.clone()).collect();
+		for editor in current_event_editors.get().iter() {
+			if !user_ids.contains(&editor.id) {
+				users.push(editor.clone());
+			}
+		}
+		users.sort_unstable_by_key(|user| user.username.to_lowercase());
+		users
+	});
+
+	view! {
+		ctx,
 		table(id="admin_event_editors_list") {
-			(if selected_event.get().is_some() {
-				view! {
-					ctx,
-					Keyed(
-						iterable=all_users,
-						key=|user| user.id.clone(),
-						view=move |ctx, user| {
-							let is_editor = create_memo(ctx, {
-								let user_id = user.id.clone();
-								move || current_event_editor_ids.get().contains(&user_id)
-							});
+			Keyed(
+				iterable=listed_users,
+				key=|user| user.id.clone(),
+				view=move |ctx, user| {
+					let is_editor = create_memo(ctx, {
+						let user_id = user.id.clone();
+						move || current_event_editor_ids.get().contains(&user_id)
+					});
 
-							let toggle_user_editor = {
-								let user = user.clone();
-								move |_event: WebEvent| {
-									let selected_event = match selected_event.get().as_ref() {
-										Some(event) => event.clone(),
-										None => return
-									};
-									let editor_event_association = EditorEventAssociation { event: selected_event, editor: user.clone().into() };
-									let editor_update_message = if *is_editor.get() {
-										AdminEventEditorUpdate::RemoveEditor(editor_event_association)
-									} else {
-										AdminEventEditorUpdate::AddEditor(editor_event_association)
-									};
-									let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventEditorsUpdate(editor_update_message)));
-
-									spawn_local_scoped(ctx, async move {
-										let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
-										let mut ws = ws_context.lock().await;
-
-										let message_json = match serde_json::to_string(&message) {
-											Ok(msg) => msg,
-											Err(error) => {
-												let data: &DataSignals = use_context(ctx);
-												data.errors.modify().push(ErrorData::new_with_error("Failed to serialize admin editor update.", error));
-												return;
-											}
-										};
-
-										let send_result = ws.send(Message::Text(message_json)).await;
-										if let Err(error) = send_result {
-											let data: &DataSignals = use_context(ctx);
-											data.errors.modify().push(ErrorData::new_with_error("Failed to send admin editor update.", error));
-										}
-									});
-								}
+					let toggle_user_editor = {
+						let user = user.clone();
+						move |_event: WebEvent| {
+							let editor_event_association = EditorEventAssociation { event: event.clone(), editor: user.clone() };
+							let editor_update_message = if *is_editor.get() {
+								AdminEventEditorUpdate::RemoveEditor(editor_event_association)
+							} else {
+								AdminEventEditorUpdate::AddEditor(editor_event_association)
 							};
+							let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventEditorsUpdate(editor_update_message)));
 
-							let user_color_style = format!("color: {}", rgb_str_from_color(user.color));
-
-							view! {
-								ctx,
-								tr {
-									td(style=user_color_style) { (user.username) }
-									td {
-										(if *is_editor.get() {
-											"✔️"
-										} else {
-											""
-										})
-									}
-									td {
-										button(type="button", on:click=toggle_user_editor) {
-											(if *is_editor.get() {
-												"Remove"
-											} else {
-												"Add"
-											})
-										}
+							spawn_local_scoped(ctx, async move {
+								let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+								let mut ws = ws_context.lock().await;
+
+								let message_json = match serde_json::to_string(&message) {
+									Ok(msg) => msg,
+									Err(error) => {
+										let data: &DataSignals = use_context(ctx);
+										data.errors.modify().push(ErrorData::new_with_error("Failed to serialize admin editor update.", error));
+										return;
 									}
+								};
+
+								let send_result = ws.send(Message::Text(message_json)).await;
+								if let Err(error) = send_result {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to send admin editor update.", error));
+								}
+							});
+						}
+					};
+
+					let user_color_style = format!("color: {}", rgb_str_from_color(user.color));
+
+					view! {
+						ctx,
+						tr {
+							td(style=user_color_style) { (user.username) }
+							td {
+								(if *is_editor.get() {
+									"✔️"
+								} else {
+									""
+								})
+							}
+							td {
+								button(type="button", on:click=toggle_user_editor) {
+									(if *is_editor.get() {
+										"Remove"
+									} else {
+										"Add"
+									})
 								}
 							}
 						}
-					)
+					}
 				}
-			} else {
-				view! { ctx, }
-			})
+			)
 		}
 	}
 }
@@ -9,13 +9,14 @@ use crate::entry_type_colors::{use_white_foreground, BLACK, WHITE};
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::AdminEntryTypesStore;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use stream_log_shared::messages::admin::AdminEntryTypeUpdate;
-use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::entry_types::{normalize_group_name, EntryType};
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
 use stream_log_shared::messages::user::SelfUserData;
 use stream_log_shared::messages::FromClientMessage;
@@ -29,7 +30,7 @@ const DEFAULT_COLOR: &str = "#ffffff";
 
 #[component]
 async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Entry Types | Stream Log");
+	set_page_title("Manage Entry Types");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -49,10 +50,12 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_entry_types = create_memo(ctx, || (*data.all_entry_types.get()).clone());
+	let entry_types_store: &AdminEntryTypesStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEntryTypes));
+
+	let all_entry_types = create_memo(ctx, || (*entry_types_store.all_entry_types.get()).clone());
 
 	let used_names_signal = create_memo(ctx, || {
-		let names: HashMap<String, String> = data
+		let names: HashMap<String, String> = entry_types_store
 			.all_entry_types
 			.get()
 			.iter()
@@ -60,6 +63,15 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			.collect();
 		names
 	});
+	let group_names = create_memo(ctx, || {
+		let group_names: BTreeSet<String> = entry_types_store
+			.all_entry_types
+			.get()
+			.iter()
+			.filter_map(|entry_type| entry_type.group_name.clone())
+			.collect();
+		group_names.into_iter().collect::<Vec<String>>()
+	});
 
 	let new_type_name_signal = create_signal(ctx, String::new());
 	let new_type_name_error_signal = create_signal(ctx, String::new());
@@ -86,6 +98,9 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		let require_end_time = !*new_type_require_end_time.get();
 		new_type_require_end_time.set(require_end_time);
 	};
+	let new_type_expected_duration_signal = create_signal(ctx, String::new());
+	let new_type_expected_duration_error_signal = create_signal(ctx, String::new());
+	let new_type_group_signal = create_signal(ctx, String::new());
 
 	let new_type_submit_handler = move |event: WebEvent| {
 		event.prevent_default();
@@ -114,8 +129,21 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 
 		let require_end_time = *new_type_require_end_time.get();
 
+		let expected_duration_minutes = match expected_duration_from_input(&new_type_expected_duration_signal.get()) {
+			Ok(duration) => duration,
+			Err(error) => {
+				new_type_expected_duration_error_signal.set(error);
+				return;
+			}
+		};
+		new_type_expected_duration_error_signal.modify().clear();
+
+		let group_name = normalize_group_name(Some((*new_type_group_signal.get()).clone()));
+
 		new_type_name_signal.modify().clear();
 		new_type_color_signal.set(String::from(DEFAULT_COLOR));
+		new_type_expected_duration_signal.modify().clear();
+		new_type_group_signal.modify().clear();
 
 		let new_type = EntryType {
 			id: String::new(),
@@ -123,6 +151,8 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			description,
 			color,
 			require_end_time,
+			expected_duration_minutes,
+			group_name,
 		};
 		let message = FromClientMessage::SubscriptionMessage(Box::new(
 			SubscriptionTargetUpdate::AdminEntryTypesUpdate(AdminEntryTypeUpdate::UpdateEntryType(new_type)),
@@ -155,6 +185,13 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 
 	view! {
 		ctx,
+		datalist(id="admin_entry_type_groups") {
+			Keyed(
+				iterable=group_names,
+				key=|name| name.clone(),
+				view=|ctx, name| view! { ctx, option(value=name) }
+			)
+		}
 		div(id="admin_manage_entry_types") {
 			Keyed(
 				iterable=all_entry_types,
@@ -166,6 +203,9 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 					let color_signal = create_signal(ctx, rgb_str_from_color(entry_type.color));
 					let color_error_signal = create_signal(ctx, String::new());
 					let require_end_time_signal = create_signal(ctx, entry_type.require_end_time);
+					let expected_duration_signal = create_signal(ctx, entry_type.expected_duration_minutes.map(|minutes| minutes.to_string()).unwrap_or_default());
+					let expected_duration_error_signal = create_signal(ctx, String::new());
+					let group_signal = create_signal(ctx, entry_type.group_name.clone().unwrap_or_default());
 
 					let display_style_signal = create_memo(ctx, || {
 						let background = color_signal.get();
@@ -221,7 +261,18 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 
 						let require_end_time = *require_end_time_signal.get();
 
-						let updated_type = EntryType { id: entry_type.id.clone(), name, description, color, require_end_time };
+						let expected_duration_minutes = match expected_duration_from_input(&expected_duration_signal.get()) {
+							Ok(duration) => duration,
+							Err(error) => {
+								expected_duration_error_signal.set(error);
+								return;
+							}
+						};
+						expected_duration_error_signal.modify().clear();
+
+						let group_name = normalize_group_name(Some((*group_signal.get()).clone()));
+
+						let updated_type = EntryType { id: entry_type.id.clone(), name, description, color, require_end_time, expected_duration_minutes, group_name };
 						let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEntryTypesUpdate(AdminEntryTypeUpdate::UpdateEntryType(updated_type))));
 						let message_json = match serde_json::to_string(&message) {
 							Ok(msg) => msg,
@@ -267,6 +318,12 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 									})
 								}
 							}
+							div {
+								input(type="number", min="1", bind:value=expected_duration_signal, placeholder="Expected minutes", class=if expected_duration_error_signal.get().is_empty() { "admin_entry_type_expected_duration_field" } else { "admin_entry_type_expected_duration_field error" }, title=*expected_duration_error_signal.get())
+							}
+							div {
+								input(bind:value=group_signal, placeholder="Group", list="admin_entry_type_groups", class="admin_entry_type_group_field")
+							}
 							div {
 								button(type="submit") { "Update" }
 							}
@@ -296,6 +353,12 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 						})
 					}
 				}
+				div {
+					input(type="number", min="1", bind:value=new_type_expected_duration_signal, placeholder="Expected minutes", class=if new_type_expected_duration_error_signal.get().is_empty() { "admin_entry_type_expected_duration_field" } else { "admin_entry_type_expected_duration_field error" }, title=*new_type_expected_duration_error_signal.get())
+				}
+				div {
+					input(bind:value=new_type_group_signal, placeholder="Group", list="admin_entry_type_groups", class="admin_entry_type_group_field")
+				}
 				div {
 					button(type="submit") { "Add New" }
 				}
@@ -304,6 +367,18 @@ async fn AdminManageEntryTypesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	}
 }
 
+/// Parses the expected duration entered for an entry type. An empty field means there's no expected duration.
+fn expected_duration_from_input(input: &str) -> Result<Option<i32>, String> {
+	let input = input.trim();
+	if input.is_empty() {
+		return Ok(None);
+	}
+	match input.parse::<i32>() {
+		Ok(minutes) if minutes > 0 => Ok(Some(minutes)),
+		_ => Err(String::from("Expected duration must be a whole number of minutes")),
+	}
+}
+
 #[component]
 pub fn AdminManageEntryTypesView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
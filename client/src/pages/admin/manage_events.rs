@@ -5,40 +5,104 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::entry_utils::{parse_time_field_value, ISO_DATETIME_FORMAT_STRING};
-use crate::page_utils::set_page_title;
+use crate::page_utils::{application_url, set_page_title};
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventShareLinksStore, AdminEventsStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use chrono::prelude::*;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
 use std::collections::HashSet;
-use stream_log_shared::messages::admin::AdminEventUpdate;
+use stream_log_shared::messages::admin::{AdminEventShareLinkUpdate, AdminEventUpdate, EventTemplate};
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::time_display::{time_zone_names, TimeDisplay};
 use stream_log_shared::messages::user::SelfUserData;
 use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
 use sycamore_router::navigate;
-use web_sys::Event as WebEvent;
+use web_sys::{window, Event as WebEvent};
+
+fn description_max_length_from_input(input: &str) -> Result<Option<i32>, String> {
+	let input = input.trim();
+	if input.is_empty() {
+		return Ok(None);
+	}
+	match input.parse::<i32>() {
+		Ok(max_length) if max_length > 0 => Ok(Some(max_length)),
+		_ => Err(String::from(
+			"Maximum description length must be a positive whole number",
+		)),
+	}
+}
+
+fn time_display_from_input(input: &str) -> Result<TimeDisplay, String> {
+	let input = input.trim();
+	let time_display = if input.is_empty() {
+		TimeDisplay::EventRelative
+	} else {
+		TimeDisplay::WallClock(input.to_string())
+	};
+	time_display.validate()?;
+	Ok(time_display)
+}
+
+fn end_time_from_input(input: &str, start_time: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+	if input.is_empty() {
+		return Ok(None);
+	}
+	let end_time = parse_time_field_value(input).map_err(|error| format!("Invalid time: {}", error))?;
+	if end_time <= start_time {
+		return Err(String::from("End time must be after the start time"));
+	}
+	Ok(Some(end_time))
+}
+
+async fn send_share_link_update(ctx: Scope<'_>, update: AdminEventShareLinkUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message =
+		FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventShareLinksUpdate(update)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize share link update message.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send share link update message.",
+			error,
+		));
+	}
+}
 
 #[component]
 async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Events | Stream Log");
+	set_page_title("Manage Events");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
 	let data: &DataSignals = use_context(ctx);
 
 	let add_subscription_result = {
+		let subscriptions = vec![SubscriptionType::AdminEvents, SubscriptionType::AdminEventShareLinks];
 		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
 		let mut subscription_manager = subscription_manager.lock().await;
-		subscription_manager
-			.set_subscription(SubscriptionType::AdminEvents, &mut ws)
-			.await
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
 	};
 	if let Err(error) = add_subscription_result {
 		data.errors.modify().push(ErrorData::new_with_error(
@@ -47,10 +111,22 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let share_links_store: &AdminEventShareLinksStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEventShareLinks));
+
+	events_store.exported_event_template.set(None);
+	events_store.archived_event.set(None);
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
 
 	let used_names_signal = create_memo(ctx, || {
-		let names: HashSet<String> = data.all_events.get().iter().map(|event| event.name.clone()).collect();
+		let names: HashSet<String> = events_store
+			.all_events
+			.get()
+			.iter()
+			.map(|event| event.name.clone())
+			.collect();
 		names
 	});
 
@@ -58,8 +134,15 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let new_event_name_error_signal = create_signal(ctx, String::new());
 	let new_event_time_signal = create_signal(ctx, format!("{}", Utc::now().format(ISO_DATETIME_FORMAT_STRING)));
 	let new_event_time_error_signal = create_signal(ctx, String::new());
+	let new_event_end_time_signal = create_signal(ctx, String::new());
+	let new_event_end_time_error_signal = create_signal(ctx, String::new());
 	let new_event_editor_link_format_signal = create_signal(ctx, String::new());
 	let new_event_first_tab_name_signal = create_signal(ctx, String::new());
+	let new_event_description_max_length_signal = create_signal(ctx, String::new());
+	let new_event_description_max_length_error_signal = create_signal(ctx, String::new());
+	let new_event_require_tag_deletion_approval_signal = create_signal(ctx, false);
+	let new_event_time_zone_signal = create_signal(ctx, String::new());
+	let new_event_time_zone_error_signal = create_signal(ctx, String::new());
 
 	let new_event_submit_handler = move |event: WebEvent| {
 		event.prevent_default();
@@ -85,17 +168,53 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		};
 		new_event_time_error_signal.modify().clear();
 
+		let end_time = match end_time_from_input(&new_event_end_time_signal.get(), start_time) {
+			Ok(end_time) => end_time,
+			Err(error) => {
+				new_event_end_time_error_signal.set(error);
+				return;
+			}
+		};
+		new_event_end_time_error_signal.modify().clear();
+
+		let description_max_length =
+			match description_max_length_from_input(&new_event_description_max_length_signal.get()) {
+				Ok(max_length) => max_length,
+				Err(error) => {
+					new_event_description_max_length_error_signal.set(error);
+					return;
+				}
+			};
+		new_event_description_max_length_error_signal.modify().clear();
+
+		let time_display = match time_display_from_input(&new_event_time_zone_signal.get()) {
+			Ok(time_display) => time_display,
+			Err(error) => {
+				new_event_time_zone_error_signal.set(error);
+				return;
+			}
+		};
+		new_event_time_zone_error_signal.modify().clear();
+
 		let editor_link_format = (*new_event_editor_link_format_signal.get()).clone();
 		let first_tab_name = (*new_event_first_tab_name_signal.get()).clone();
+		let require_tag_deletion_approval = *new_event_require_tag_deletion_approval_signal.get();
 
 		new_event_name_signal.modify().clear();
 		new_event_time_signal.set(format!("{}", Utc::now().format(ISO_DATETIME_FORMAT_STRING)));
+		new_event_end_time_signal.modify().clear();
+		new_event_time_zone_signal.modify().clear();
 		let new_event = Event {
 			id: String::new(),
 			name,
 			start_time,
+			end_time,
 			editor_link_format,
 			first_tab_name,
+			description_max_length,
+			require_tag_deletion_approval,
+			time_display,
+			archived: false,
 		};
 
 		let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(
@@ -126,15 +245,122 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		});
 	};
 
+	let exported_template = create_memo(ctx, || {
+		let exported_template = events_store.exported_event_template.get();
+		(*exported_template).as_ref().map(|(event, template)| {
+			let template_json = serde_json::to_string_pretty(template).unwrap_or_default();
+			(event.name.clone(), template_json)
+		})
+	});
+
+	let import_name_signal = create_signal(ctx, String::new());
+	let import_name_error_signal = create_signal(ctx, String::new());
+	let import_time_signal = create_signal(ctx, format!("{}", Utc::now().format(ISO_DATETIME_FORMAT_STRING)));
+	let import_time_error_signal = create_signal(ctx, String::new());
+	let import_template_signal = create_signal(ctx, String::new());
+	let import_template_error_signal = create_signal(ctx, String::new());
+
+	let import_submit_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let name = (*import_name_signal.get()).clone();
+		if name.is_empty() {
+			import_name_error_signal.set(String::from("Event must have a name"));
+			return;
+		}
+		if used_names_signal.get().contains(&name) {
+			import_name_error_signal.set(String::from("This name is already in use."));
+			return;
+		}
+		import_name_error_signal.modify().clear();
+
+		let formatted_time = import_time_signal.get();
+		let start_time = match parse_time_field_value(&formatted_time) {
+			Ok(time) => time,
+			Err(error) => {
+				import_time_error_signal.set(format!("Invalid time: {}", error));
+				return;
+			}
+		};
+		import_time_error_signal.modify().clear();
+
+		let template: EventTemplate = match serde_json::from_str(&import_template_signal.get()) {
+			Ok(template) => template,
+			Err(error) => {
+				import_template_error_signal.set(format!("Invalid template: {}", error));
+				return;
+			}
+		};
+		import_template_error_signal.modify().clear();
+
+		import_name_signal.modify().clear();
+		import_time_signal.set(format!("{}", Utc::now().format(ISO_DATETIME_FORMAT_STRING)));
+		import_template_signal.modify().clear();
+		let new_event = Event {
+			id: String::new(),
+			name,
+			start_time,
+			end_time: None,
+			editor_link_format: template.editor_link_format.clone(),
+			first_tab_name: template.first_tab_name.clone(),
+			description_max_length: template.description_max_length,
+			require_tag_deletion_approval: template.require_tag_deletion_approval,
+			time_display: template.time_display.clone(),
+			archived: false,
+		};
+
+		let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(
+			AdminEventUpdate::ImportTemplate(new_event, template),
+		)));
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize event template import message.",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to send event template import message.",
+					error,
+				));
+			}
+		});
+	};
+
+	let time_zone_options = View::new_fragment(
+		time_zone_names()
+			.map(|time_zone| view! { ctx, option(value=time_zone) })
+			.collect(),
+	);
+
 	view! {
 		ctx,
 		h1 { "Manage Events" }
+		datalist(id="admin_manage_events_time_zones") {
+			(time_zone_options)
+		}
 		div(id="admin_manage_events") {
 			div(class="admin_manage_events_row admin_manage_events_headers") {
 				div { "Name" }
 				div { "Start Time (UTC)" }
+				div { "End Time (UTC)" }
 				div { "Editor Link Format" }
 				div { "First Tab Name" }
+				div { "Max Description Length" }
+				div(title="Removing or replacing tags requires approval from a second supervisor") { "Tag Removal Approval" }
+				div(title="Log times are shown as the time of day in this time zone unless users choose otherwise") { "Log Time Zone" }
+				div(title="Archived events are kept, but their logs can't be changed") { "Archived" }
 				div { }
 			}
 			Keyed(
@@ -145,8 +371,16 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 					let name_error_signal = create_signal(ctx, String::new());
 					let time_signal = create_signal(ctx, format!("{}", event.start_time.format(ISO_DATETIME_FORMAT_STRING)));
 					let time_error_signal = create_signal(ctx, String::new());
+					let end_time_signal = create_signal(ctx, event.end_time.map(|end_time| format!("{}", end_time.format(ISO_DATETIME_FORMAT_STRING))).unwrap_or_default());
+					let end_time_error_signal = create_signal(ctx, String::new());
 					let editor_link_format_signal = create_signal(ctx, event.editor_link_format.clone());
 					let first_tab_name_signal = create_signal(ctx, event.first_tab_name.clone());
+					let description_max_length_signal = create_signal(ctx, event.description_max_length.map(|max_length| max_length.to_string()).unwrap_or_default());
+					let description_max_length_error_signal = create_signal(ctx, String::new());
+					let require_tag_deletion_approval_signal = create_signal(ctx, event.require_tag_deletion_approval);
+					let time_zone_signal = create_signal(ctx, event.time_display.time_zone_name().unwrap_or_default().to_string());
+					let time_zone_error_signal = create_signal(ctx, String::new());
+					let archived_signal = create_signal(ctx, event.archived);
 
 					let submit_handler = move |web_event: WebEvent| {
 						web_event.prevent_default();
@@ -156,7 +390,7 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 							name_error_signal.set(String::from("Event must have a name"));
 							return;
 						}
-						for list_event in data.all_events.get().iter() {
+						for list_event in events_store.all_events.get().iter() {
 							if event.id != list_event.id && event.name == list_event.name {
 								name_error_signal.set(String::from("This name is already in use."));
 								return;
@@ -174,10 +408,39 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 						};
 						time_error_signal.modify().clear();
 
+						let end_time = match end_time_from_input(&end_time_signal.get(), start_time) {
+							Ok(end_time) => end_time,
+							Err(error) => {
+								end_time_error_signal.set(error);
+								return;
+							}
+						};
+						end_time_error_signal.modify().clear();
+
+						let description_max_length = match description_max_length_from_input(&description_max_length_signal.get()) {
+							Ok(max_length) => max_length,
+							Err(error) => {
+								description_max_length_error_signal.set(error);
+								return;
+							}
+						};
+						description_max_length_error_signal.modify().clear();
+
+						let time_display = match time_display_from_input(&time_zone_signal.get()) {
+							Ok(time_display) => time_display,
+							Err(error) => {
+								time_zone_error_signal.set(error);
+								return;
+							}
+						};
+						time_zone_error_signal.modify().clear();
+
 						let editor_link_format = (*editor_link_format_signal.get()).clone();
 						let first_tab_name = (*first_tab_name_signal.get()).clone();
+						let require_tag_deletion_approval = *require_tag_deletion_approval_signal.get();
+						let archived = *archived_signal.get();
 
-						let updated_event = Event { id: event.id.clone(), name, start_time, editor_link_format, first_tab_name };
+						let updated_event = Event { id: event.id.clone(), name, start_time, end_time, editor_link_format, first_tab_name, description_max_length, require_tag_deletion_approval, time_display, archived };
 						let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(AdminEventUpdate::UpdateEvent(updated_event))));
 						let message_json = match serde_json::to_string(&message) {
 							Ok(msg) => msg,
@@ -198,6 +461,131 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 						});
 					};
 
+					let export_handler = {
+						let event = event.clone();
+						move |_web_event: WebEvent| {
+							let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(AdminEventUpdate::ExportTemplate(event.clone()))));
+							let message_json = match serde_json::to_string(&message) {
+								Ok(msg) => msg,
+								Err(error) => {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to serialize event template export message.", error));
+									return;
+								}
+							};
+							spawn_local_scoped(ctx, async move {
+								let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+								let mut ws = ws_context.lock().await;
+
+								if let Err(error) = ws.send(Message::Text(message_json)).await {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to send event template export message.", error));
+								}
+							});
+						}
+					};
+
+					let renumber_confirm_signal = create_signal(ctx, false);
+
+					let renumber_handler = move |_web_event: WebEvent| {
+						renumber_confirm_signal.set(true);
+					};
+
+					let renumber_confirm_handler = {
+						let event = event.clone();
+						move |_web_event: WebEvent| {
+							renumber_confirm_signal.set(false);
+							let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(AdminEventUpdate::RenumberEntries(event.clone()))));
+							let message_json = match serde_json::to_string(&message) {
+								Ok(msg) => msg,
+								Err(error) => {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to serialize entry renumber message.", error));
+									return;
+								}
+							};
+							spawn_local_scoped(ctx, async move {
+								let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+								let mut ws = ws_context.lock().await;
+
+								if let Err(error) = ws.send(Message::Text(message_json)).await {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to send entry renumber message.", error));
+								}
+							});
+						}
+					};
+
+					let renumber_cancel_handler = move |_web_event: WebEvent| {
+						renumber_confirm_signal.set(false);
+					};
+
+					let archive_confirm_signal = create_signal(ctx, false);
+
+					let archive_handler = move |_web_event: WebEvent| {
+						archive_confirm_signal.set(true);
+					};
+
+					let archive_confirm_handler = {
+						let event = event.clone();
+						move |_web_event: WebEvent| {
+							archive_confirm_signal.set(false);
+							let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventsUpdate(AdminEventUpdate::ArchiveAndPurge(event.clone()))));
+							let message_json = match serde_json::to_string(&message) {
+								Ok(msg) => msg,
+								Err(error) => {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to serialize event archive message.", error));
+									return;
+								}
+							};
+							spawn_local_scoped(ctx, async move {
+								let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+								let mut ws = ws_context.lock().await;
+
+								if let Err(error) = ws.send(Message::Text(message_json)).await {
+									let data: &DataSignals = use_context(ctx);
+									data.errors.modify().push(ErrorData::new_with_error("Failed to send event archive message.", error));
+								}
+							});
+						}
+					};
+
+					let archive_cancel_handler = move |_web_event: WebEvent| {
+						archive_confirm_signal.set(false);
+					};
+
+					let share_link_url = create_memo(ctx, {
+						let event_id = event.id.clone();
+						move || {
+							share_links_store.all_event_share_links
+								.get()
+								.iter()
+								.find(|share_link| share_link.event.id == event_id)
+								.map(|share_link| application_url(&format!("public/{}", share_link.token)).href())
+						}
+					});
+
+					let create_share_link_handler = {
+						let event = event.clone();
+						move |_web_event: WebEvent| {
+							let event = event.clone();
+							spawn_local_scoped(ctx, async move {
+								send_share_link_update(ctx, AdminEventShareLinkUpdate::CreateShareLink(event)).await;
+							});
+						}
+					};
+
+					let delete_share_link_handler = {
+						let event = event.clone();
+						move |_web_event: WebEvent| {
+							let event = event.clone();
+							spawn_local_scoped(ctx, async move {
+								send_share_link_update(ctx, AdminEventShareLinkUpdate::DeleteShareLink(event)).await;
+							});
+						}
+					};
+
 					view! {
 						ctx,
 						form(class="admin_manage_events_row", on:submit=submit_handler) {
@@ -207,14 +595,76 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 							div {
 								input(type="datetime-local", step=1, bind:value=time_signal, class=if time_error_signal.get().is_empty() { "" } else { "error" }, title=*time_error_signal.get())
 							}
+							div {
+								input(type="datetime-local", step=1, bind:value=end_time_signal, class=if end_time_error_signal.get().is_empty() { "" } else { "error" }, title=*end_time_error_signal.get())
+							}
 							div {
 								input(bind:value=editor_link_format_signal)
 							}
 							div {
 								input(bind:value=first_tab_name_signal)
 							}
+							div {
+								input(type="number", min=1, bind:value=description_max_length_signal, placeholder="No limit", class=if description_max_length_error_signal.get().is_empty() { "" } else { "error" }, title=*description_max_length_error_signal.get())
+							}
+							div {
+								input(type="checkbox", bind:checked=require_tag_deletion_approval_signal)
+							}
+							div {
+								input(bind:value=time_zone_signal, list="admin_manage_events_time_zones", placeholder="Time since start", class=if time_zone_error_signal.get().is_empty() { "" } else { "error" }, title=*time_zone_error_signal.get())
+							}
+							div {
+								input(type="checkbox", bind:checked=archived_signal)
+							}
 							div {
 								button(type="submit") { "Update" }
+								button(type="button", on:click=export_handler) { "Export Template" }
+								(if *renumber_confirm_signal.get() {
+									view! {
+										ctx,
+										div(class="admin_manage_events_renumber_confirm") {
+											"This will renumber the event's entries to match the log order. Entries with a video link will keep their numbers. Are you sure?"
+											button(type="button", on:click=renumber_confirm_handler.clone()) { "Yes, renumber them!" }
+											button(type="button", on:click=renumber_cancel_handler) { "No, keep the numbers!" }
+										}
+									}
+								} else {
+									view! {
+										ctx,
+										button(type="button", on:click=renumber_handler) { "Renumber Entries" }
+									}
+								})
+								(if *archive_confirm_signal.get() {
+									view! {
+										ctx,
+										div(class="admin_manage_events_archive_confirm") {
+											"This will archive the event to a file and delete it and all of its log entries. Are you sure?"
+											button(type="button", on:click=archive_confirm_handler.clone()) { "Yes, archive and delete it!" }
+											button(type="button", on:click=archive_cancel_handler) { "No, keep it!" }
+										}
+									}
+								} else {
+									view! {
+										ctx,
+										button(type="button", on:click=archive_handler) { "Archive & Purge" }
+									}
+								})
+								(if let Some(share_link_url) = (*share_link_url.get()).clone() {
+									view! {
+										ctx,
+										div(class="admin_manage_events_share_link") {
+											"Public view: "
+											a(href=share_link_url.clone(), target="_blank", rel="noopener") { (share_link_url) }
+											button(type="button", on:click=create_share_link_handler.clone(), title="Replaces the link so that the old one stops working") { "Replace Link" }
+											button(type="button", on:click=delete_share_link_handler.clone()) { "Disable Public View" }
+										}
+									}
+								} else {
+									view! {
+										ctx,
+										button(type="button", on:click=create_share_link_handler.clone(), title="Creates a link anyone can use to view the log without logging in") { "Enable Public View" }
+									}
+								})
 							}
 						}
 					}
@@ -230,17 +680,89 @@ async fn AdminManageEventsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 				div {
 					input(type="datetime-local", step=1, bind:value=new_event_time_signal, class=if new_event_time_error_signal.get().is_empty() { "" } else { "error" }, title=*new_event_time_error_signal.get())
 				}
+				div {
+					input(type="datetime-local", step=1, bind:value=new_event_end_time_signal, class=if new_event_end_time_error_signal.get().is_empty() { "" } else { "error" }, title=*new_event_end_time_error_signal.get())
+				}
 				div {
 					input(bind:value=new_event_editor_link_format_signal)
 				}
 				div {
 					input(bind:value=new_event_first_tab_name_signal)
 				}
+				div {
+					input(type="number", min=1, bind:value=new_event_description_max_length_signal, placeholder="No limit", class=if new_event_description_max_length_error_signal.get().is_empty() { "" } else { "error" }, title=*new_event_description_max_length_error_signal.get())
+				}
+				div {
+					input(type="checkbox", bind:checked=new_event_require_tag_deletion_approval_signal)
+				}
+				div {
+					input(bind:value=new_event_time_zone_signal, list="admin_manage_events_time_zones", placeholder="Time since start", class=if new_event_time_zone_error_signal.get().is_empty() { "" } else { "error" }, title=*new_event_time_zone_error_signal.get())
+				}
+				div { }
 				div {
 					button(type="submit") { "Add event" }
 				}
 			}
 		}
+		(if let Some((event, archive_file_name)) = (*events_store.archived_event.get()).clone() {
+			let archive_url = application_url(&format!("files/{}", archive_file_name)).href();
+			view! {
+				ctx,
+				div(id="admin_manage_events_archived") {
+					"The event " (event.name) " was archived to "
+					a(href=archive_url, target="_blank", rel="noopener") { (archive_file_name) }
+					" and deleted."
+				}
+			}
+		} else {
+			view! { ctx, }
+		})
+		(if let Some((event_name, template_json)) = (*exported_template.get()).clone() {
+			let copy_template_json = template_json.clone();
+			let copy_click_handler = move |_event: WebEvent| {
+				let clipboard = if let Some(window) = window() {
+					window.navigator().clipboard()
+				} else {
+					return;
+				};
+				// The JS Promise will handle itself, and we don't need to handle it here
+				let _ = clipboard.write_text(&copy_template_json);
+			};
+			view! {
+				ctx,
+				div(id="admin_manage_events_template_export") {
+					h2 { "Template for " (event_name) }
+					p { "Save this template to a JSON file to create new events with the same configuration." }
+					textarea(readonly=true) { (template_json) }
+					div {
+						button(type="button", on:click=copy_click_handler) { "Copy Template" }
+					}
+				}
+			}
+		} else {
+			view! { ctx, }
+		})
+		form(id="admin_manage_events_template_import", on:submit=import_submit_handler) {
+			h2 { "Create Event from Template" }
+			div {
+				label {
+					"Name: "
+					input(bind:value=import_name_signal, class=if import_name_error_signal.get().is_empty() { "" } else { "error" }, title=*import_name_error_signal.get())
+				}
+			}
+			div {
+				label {
+					"Start Time (UTC): "
+					input(type="datetime-local", step=1, bind:value=import_time_signal, class=if import_time_error_signal.get().is_empty() { "" } else { "error" }, title=*import_time_error_signal.get())
+				}
+			}
+			div {
+				textarea(bind:value=import_template_signal, placeholder="Template JSON", class=if import_template_error_signal.get().is_empty() { "" } else { "error" }, title=*import_template_error_signal.get())
+			}
+			div {
+				button(type="submit") { "Import Template" }
+			}
+		}
 	}
 }
 
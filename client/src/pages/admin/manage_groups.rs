@@ -7,13 +7,15 @@
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventsStore, AdminPermissionGroupsStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use stream_log_shared::messages::admin::{
-	AdminPermissionGroupUpdate, PermissionGroup, PermissionGroupEventAssociation,
+	standard_event_group_name, AdminPermissionGroupUpdate, PermissionGroup, PermissionGroupEventAssociation,
+	STANDARD_EVENT_GROUPS,
 };
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::permissions::PermissionLevel;
@@ -26,9 +28,35 @@ use sycamore::suspense::Suspense;
 use sycamore_router::navigate;
 use web_sys::Event as WebEvent;
 
+async fn send_permission_group_update(ctx: Scope<'_>, update: AdminPermissionGroupUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message =
+		FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminPermissionGroupsUpdate(update)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize permission group update message.",
+				error,
+			));
+			return;
+		}
+	};
+	if let Err(error) = ws.send(Message::Text(message_json)).await {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send permission group update message.",
+			error,
+		));
+	}
+}
+
 #[component]
 async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Permission Groups | Stream Log");
+	set_page_title("Manage Permission Groups");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -47,11 +75,15 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_permission_groups = create_memo(ctx, || (*data.all_permission_groups.get()).clone());
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
+	let groups_store: &AdminPermissionGroupsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminPermissionGroups));
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+
+	let all_permission_groups = create_memo(ctx, || (*groups_store.all_permission_groups.get()).clone());
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
 	let permission_group_events = create_memo(ctx, || {
 		let mut group_events: HashMap<String, HashMap<String, PermissionLevel>> = HashMap::new();
-		for group_event_association in data.permission_group_event_associations.get().iter() {
+		for group_event_association in groups_store.permission_group_event_associations.get().iter() {
 			let group_id = group_event_association.group.clone();
 			let event_id = group_event_association.event.clone();
 			group_events
@@ -63,7 +95,7 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	});
 
 	let event_names_index_signal = create_memo(ctx, || {
-		let event_names: HashMap<String, Event> = data
+		let event_names: HashMap<String, Event> = events_store
 			.all_events
 			.get()
 			.iter()
@@ -72,6 +104,16 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		event_names
 	});
 
+	let group_names_signal = create_memo(ctx, || {
+		let group_names: HashSet<String> = groups_store
+			.all_permission_groups
+			.get()
+			.iter()
+			.map(|group| group.name.clone())
+			.collect();
+		group_names
+	});
+
 	let new_group_name_signal = create_signal(ctx, String::new());
 	let new_group_error_signal = create_signal(ctx, String::new());
 	let new_group_submit_handler = move |event: WebEvent| {
@@ -119,8 +161,47 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		});
 	};
 
+	let standard_groups_event_signal = create_signal(ctx, String::new());
+	let standard_groups_error_signal = create_signal(ctx, String::new());
+	let standard_groups_submit_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let event_name = standard_groups_event_signal.get();
+		let Some(event) = event_names_index_signal.get().get(&*event_name).cloned() else {
+			standard_groups_error_signal.set(String::from("That's not the name of an event."));
+			return;
+		};
+		let group_names = group_names_signal.get();
+		let existing_group_name = STANDARD_EVENT_GROUPS
+			.iter()
+			.map(|(group_name, _)| standard_event_group_name(&event, group_name))
+			.find(|group_name| group_names.contains(group_name));
+		if let Some(group_name) = existing_group_name {
+			standard_groups_error_signal.set(format!("The group \"{}\" already exists.", group_name));
+			return;
+		}
+		standard_groups_error_signal.modify().clear();
+		standard_groups_event_signal.set(String::new());
+
+		spawn_local_scoped(ctx, async move {
+			send_permission_group_update(ctx, AdminPermissionGroupUpdate::CreateStandardEventGroups(event)).await;
+		});
+	};
+	let standard_groups_description = STANDARD_EVENT_GROUPS
+		.iter()
+		.map(|(group_name, permission)| format!("{} ({:?})", group_name, permission))
+		.collect::<Vec<String>>()
+		.join(", ");
+
 	view! {
 		ctx,
+		datalist(id="admin_manage_groups_events") {
+			Keyed(
+				iterable=all_events,
+				key=|event| event.id.clone(),
+				view=|ctx, event| view! { ctx, option(value=event.name) }
+			)
+		}
 		div(id="admin_manage_groups") {
 			h1 { "Permission Groups" }
 			Keyed(
@@ -133,6 +214,32 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 					});
 
 					let group_name_signal = create_signal(ctx, group.name.clone());
+					let duplicate_name_signal = create_signal(ctx, String::new());
+					let duplicate_error_signal = create_signal(ctx, String::new());
+
+					let duplicate_group_handler = {
+						let group = group.clone();
+						move |event: WebEvent| {
+							event.prevent_default();
+
+							let duplicate_name = (*duplicate_name_signal.get()).clone();
+							if duplicate_name.is_empty() {
+								duplicate_error_signal.set(String::from("Enter a name for the copy."));
+								return;
+							}
+							if group_names_signal.get().contains(&duplicate_name) {
+								duplicate_error_signal.set(format!("The group \"{}\" already exists.", duplicate_name));
+								return;
+							}
+							duplicate_error_signal.modify().clear();
+							duplicate_name_signal.set(String::new());
+
+							let update = AdminPermissionGroupUpdate::DuplicateGroup(group.clone(), duplicate_name);
+							spawn_local_scoped(ctx, async move {
+								send_permission_group_update(ctx, update).await;
+							});
+						}
+					};
 
 					let submit_group_name_handler = {
 						let group = group.clone();
@@ -170,10 +277,16 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 								input(bind:value=group_name_signal)
 								button(type="submit") { "Update Name" }
 							}
+							form(class="admin_manage_groups_duplicate", on:submit=duplicate_group_handler) {
+								input(bind:value=duplicate_name_signal, placeholder="Name for copy", class=if duplicate_error_signal.get().is_empty() { "" } else { "error" })
+								button(type="submit") { "Duplicate Group" }
+								span(class="input_error") { (duplicate_error_signal.get()) }
+							}
 						}
 						div(class="admin_manage_groups_events") {
 							div(class="admin_manage_groups_events_header") { "Event" }
 							div(class="admin_manage_groups_events_header") { "View" }
+							div(class="admin_manage_groups_events_header", title="Can see the video editor view without editing") { "Auditor" }
 							div(class="admin_manage_groups_events_header") { "Edit" }
 							div(class="admin_manage_groups_events_header") { "Supervisor" }
 							div(class="admin_manage_groups_events_header") { }
@@ -184,6 +297,7 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 								view=move |ctx, event| {
 									let group = group.clone();
 									let can_view_signal = create_signal(ctx, false);
+									let is_auditor_signal = create_signal(ctx, false);
 									let can_edit_signal = create_signal(ctx, false);
 									let is_supervisor_signal = create_signal(ctx, false);
 
@@ -192,13 +306,15 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 										move || {
 											let event_permissions_data = event_permissions.get();
 											let permission = event_permissions_data.get(&event_id);
-											let (can_view, can_edit, is_supervisor) = match permission {
-												Some(PermissionLevel::Supervisor) => (true, true, true),
-												Some(PermissionLevel::Edit) => (true, true, false),
-												Some(PermissionLevel::View) => (true, false, false),
-												None => (false, false, false)
+											let (can_view, is_auditor, can_edit, is_supervisor) = match permission {
+												Some(PermissionLevel::Supervisor) => (true, false, true, true),
+												Some(PermissionLevel::Edit) => (true, false, true, false),
+												Some(PermissionLevel::Auditor) => (true, true, false, false),
+												Some(PermissionLevel::View) => (true, false, false, false),
+												None => (false, false, false, false)
 											};
 											can_view_signal.set(can_view);
+											is_auditor_signal.set(is_auditor);
 											can_edit_signal.set(can_edit);
 											is_supervisor_signal.set(is_supervisor);
 										}
@@ -213,12 +329,20 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 									create_effect(ctx, || {
 										if *can_edit_signal.get() {
 											can_view_signal.set(true);
+											is_auditor_signal.set(false);
 										} else {
 											is_supervisor_signal.set(false);
 										}
 									});
+									create_effect(ctx, || {
+										if *is_auditor_signal.get() {
+											can_view_signal.set(true);
+											can_edit_signal.set(false);
+										}
+									});
 									create_effect(ctx, || {
 										if !*can_view_signal.get() {
+											is_auditor_signal.set(false);
 											can_edit_signal.set(false);
 											is_supervisor_signal.set(false);
 										}
@@ -233,6 +357,8 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 												AdminPermissionGroupUpdate::SetEventPermissionForGroup(PermissionGroupEventAssociation { group: group.id.clone(), event: event.id.clone(), permission: PermissionLevel::Supervisor })
 											} else if *can_edit_signal.get() {
 												AdminPermissionGroupUpdate::SetEventPermissionForGroup(PermissionGroupEventAssociation { group: group.id.clone(), event: event.id.clone(), permission: PermissionLevel::Edit })
+											} else if *is_auditor_signal.get() {
+												AdminPermissionGroupUpdate::SetEventPermissionForGroup(PermissionGroupEventAssociation { group: group.id.clone(), event: event.id.clone(), permission: PermissionLevel::Auditor })
 											} else if *can_view_signal.get() {
 												AdminPermissionGroupUpdate::SetEventPermissionForGroup(PermissionGroupEventAssociation { group: group.id.clone(), event: event.id.clone(), permission: PermissionLevel::View })
 											} else {
@@ -267,6 +393,9 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 											div(class="admin_manage_groups_events_view") {
 												input(type="checkbox", bind:checked=can_view_signal)
 											}
+											div(class="admin_manage_groups_events_auditor") {
+												input(type="checkbox", bind:checked=is_auditor_signal)
+											}
 											div(class="admin_manage_groups_events_edit") {
 												input(type="checkbox", bind:checked=can_edit_signal)
 											}
@@ -289,6 +418,16 @@ async fn AdminManageGroupsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 				button(type="submit") { "Add group" }
 				span(id="admin_manage_groups_new_group_error") { (new_group_error_signal.get()) }
 			}
+			form(id="admin_manage_groups_standard_groups", on:submit=standard_groups_submit_handler) {
+				input(
+					bind:value=standard_groups_event_signal,
+					list="admin_manage_groups_events",
+					placeholder="Event",
+					class=if standard_groups_error_signal.get().is_empty() { "" } else { "error" }
+				)
+				button(type="submit", title=standard_groups_description) { "Create Standard Groups for Event" }
+				span(id="admin_manage_groups_standard_groups_error") { (standard_groups_error_signal.get()) }
+			}
 		}
 	}
 }
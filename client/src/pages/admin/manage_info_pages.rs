@@ -7,6 +7,7 @@
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventsStore, AdminInfoPagesStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
@@ -14,6 +15,7 @@ use gloo_net::websocket::Message;
 use stream_log_shared::messages::admin::AdminInfoPageUpdate;
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::info_pages::InfoPage;
+use stream_log_shared::messages::permissions::PermissionLevel;
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
 use stream_log_shared::messages::user::SelfUserData;
 use stream_log_shared::messages::FromClientMessage;
@@ -23,6 +25,27 @@ use sycamore::suspense::Suspense;
 use sycamore_router::navigate;
 use web_sys::Event as WebEvent;
 
+/// Gets the value of the visibility selection for a page's minimum permission level
+fn visibility_option_value(minimum_permission: Option<PermissionLevel>) -> String {
+	let value = match minimum_permission {
+		None | Some(PermissionLevel::View) => "",
+		Some(PermissionLevel::Auditor) => "auditor",
+		Some(PermissionLevel::Edit) => "edit",
+		Some(PermissionLevel::Supervisor) => "supervisor",
+	};
+	String::from(value)
+}
+
+/// Gets the minimum permission level for a page from the value of the visibility selection
+fn minimum_permission_from_option_value(value: &str) -> Option<PermissionLevel> {
+	match value {
+		"auditor" => Some(PermissionLevel::Auditor),
+		"edit" => Some(PermissionLevel::Edit),
+		"supervisor" => Some(PermissionLevel::Supervisor),
+		_ => None,
+	}
+}
+
 #[derive(Clone)]
 enum SelectedInfoPage {
 	ExistingPage(InfoPage),
@@ -31,7 +54,7 @@ enum SelectedInfoPage {
 
 #[component]
 async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Info Pages | Stream Log");
+	set_page_title("Manage Info Pages");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -50,11 +73,14 @@ async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let info_pages_store: &AdminInfoPagesStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminInfoPages));
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
 	let selected_event: &Signal<Option<Event>> = create_signal(ctx, None);
 
 	let event_info_pages = create_memo(ctx, {
-		let all_info_pages = data.all_info_pages.clone();
+		let all_info_pages = info_pages_store.all_info_pages.clone();
 		move || {
 			let all_info_pages = all_info_pages.get();
 			let Some(selected_event) = (*selected_event.get()).clone() else {
@@ -94,6 +120,16 @@ async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		Some(SelectedInfoPage::NewPage) => String::new(),
 		None => String::new(),
 	});
+	let selected_page_visibility = create_memo(ctx, || match (*selected_page.get()).as_ref() {
+		Some(SelectedInfoPage::ExistingPage(page)) => event_info_pages
+			.get()
+			.iter()
+			.find(|p| p.id == page.id)
+			.map(|page| visibility_option_value(page.minimum_permission))
+			.unwrap_or_default(),
+		Some(SelectedInfoPage::NewPage) => String::new(),
+		None => String::new(),
+	});
 	let selected_page_id = create_memo(ctx, || match (*selected_page.get()).as_ref() {
 		Some(SelectedInfoPage::ExistingPage(page)) => page.id.clone(),
 		_ => String::new(),
@@ -123,6 +159,7 @@ async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			});
 
 			let contents_entry = create_signal(ctx, (*selected_page_contents.get()).clone());
+			let visibility_entry = create_signal(ctx, (*selected_page_visibility.get()).clone());
 
 			let preview = create_memo(ctx, || markdown::to_html(&contents_entry.get()));
 
@@ -141,8 +178,9 @@ async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 				}
 
 				let page_contents = (*contents_entry.get()).clone();
+				let minimum_permission = minimum_permission_from_option_value(&visibility_entry.get());
 
-				let updated_info_page = InfoPage { id: (*selected_page_id.get()).clone(), event: selected_event, title: page_title, contents: page_contents };
+				let updated_info_page = InfoPage { id: (*selected_page_id.get()).clone(), event: selected_event, title: page_title, contents: page_contents, minimum_permission };
 
 				spawn_local_scoped(ctx, async move {
 					let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
@@ -181,6 +219,15 @@ async fn AdminInfoPagesLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 						input(bind:value=title_entry, class=if title_error.get().is_empty() { "" } else { "error" })
 						span(class="input_error") { (title_error.get()) }
 					}
+					label {
+						"Visible to:"
+						select(bind:value=visibility_entry) {
+							option(value="") { "Everyone who can see the event" }
+							option(value="auditor") { "Auditors, editors, and supervisors" }
+							option(value="edit") { "Editors and supervisors" }
+							option(value="supervisor") { "Supervisors" }
+						}
+					}
 					textarea(bind:value=contents_entry)
 					h2 { "Preview" }
 					div(id="admin_info_pages_page_edit_preview", dangerously_set_inner_html=&preview.get())
@@ -8,6 +8,7 @@ use crate::entry_utils::{parse_time_field_value, ISO_DATETIME_FORMAT_STRING};
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventLogTabsStore, AdminEventsStore};
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
@@ -27,7 +28,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Tabs | Stream Log");
+	set_page_title("Manage Tabs");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -45,8 +46,10 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			.push(ErrorData::new_with_error("Failed to subscribe for admin tabs", error));
 	}
 
-	let all_events = create_memo(ctx, || (*data.all_events.get()).clone());
-	let all_tabs = create_memo(ctx, || (*data.all_event_log_tabs.get()).clone());
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let tabs_store: AdminEventLogTabsStore = data.stores.get(SubscriptionType::AdminEventLogTabs);
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
 
 	let selected_event: &Signal<Option<Event>> = create_signal(ctx, None);
 	let entered_event_name = create_signal(ctx, String::new());
@@ -85,17 +88,55 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		}
 	};
 
-	let current_event_tabs = create_memo(ctx, || {
-		let tabs = all_tabs.get();
-		let selected_event = selected_event.get();
+	view! {
+		ctx,
+		datalist(id="list_all_events") {
+			Keyed(
+				iterable=all_events,
+				key=|event| event.id.clone(),
+				view=|ctx, event| {
+					view! {
+						ctx,
+						option(value=&event.name)
+					}
+				}
+			)
+		}
+		form(id="admin_tabs_event_selection", on:submit=event_form_handler) {
+			input(list="list_all_events", bind:value=entered_event_name, title=entered_event_error.get(), class=if entered_event_error.get().is_empty() { "" } else { "error" })
+			button(type="submit") { "Load Event" }
+		}
+		(if let Some(event) = (*selected_event.get()).clone() {
+			view! { ctx, EventLogTabsEditor(event=event, tabs=tabs_store.clone()) }
+		} else {
+			view! { ctx, }
+		})
+	}
+}
 
-		match selected_event.as_ref() {
-			Some(event) => tabs
+#[derive(Prop)]
+pub struct EventLogTabsEditorProps {
+	event: Event,
+	/// The tabs data from the subscription used by the page
+	tabs: AdminEventLogTabsStore,
+}
+
+/// Lists the tabs for an event and allows adding, changing, and removing them
+#[component]
+pub fn EventLogTabsEditor<G: Html>(ctx: Scope<'_>, props: EventLogTabsEditorProps) -> View<G> {
+	let tabs_store: &AdminEventLogTabsStore = create_ref(ctx, props.tabs);
+
+	let current_event_tabs = create_memo(ctx, {
+		let event_id = props.event.id.clone();
+		move || {
+			let tabs: Vec<EventLogTab> = tabs_store
+				.all_event_log_tabs
+				.get()
 				.iter()
-				.filter(|(tab_event, _)| *tab_event == *event)
+				.filter(|(tab_event, _)| tab_event.id == event_id)
 				.map(|(_, tab)| tab.clone())
-				.collect(),
-			None => Vec::new(),
+				.collect();
+			tabs
 		}
 	});
 
@@ -103,17 +144,9 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let new_tab_time_entry = create_signal(ctx, String::new());
 	let new_tab_error = create_memo(ctx, || {
 		let new_tab_name = new_tab_name_entry.get();
-		let selected_event = selected_event.get();
-		let Some(selected_event) = selected_event.as_ref() else {
-			return String::new();
-		};
 		if new_tab_name.is_empty() {
 			String::new()
-		} else if all_tabs
-			.get()
-			.iter()
-			.any(|(event, tab)| event.id == selected_event.id && tab.name == *new_tab_name)
-		{
+		} else if current_event_tabs.get().iter().any(|tab| tab.name == *new_tab_name) {
 			String::from("Already the name of an event")
 		} else {
 			String::new()
@@ -123,11 +156,7 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	let new_tab_add_handler = move |event: WebEvent| {
 		event.prevent_default();
 
-		let selected_event = selected_event.get();
-		let Some(selected_event) = selected_event.as_ref() else {
-			return;
-		};
-		let selected_event = selected_event.clone();
+		let tab_event = props.event.clone();
 
 		let name = (*new_tab_name_entry.get()).clone();
 		let start_time = new_tab_time_entry.get();
@@ -148,10 +177,9 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 			let mut ws = ws_context.lock().await;
 
-			let message =
-				FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventLogTabsUpdate(
-					AdminEventLogTabsUpdate::AddTab(selected_event, new_tab),
-				)));
+			let message = FromClientMessage::SubscriptionMessage(Box::new(
+				SubscriptionTargetUpdate::AdminEventLogTabsUpdate(AdminEventLogTabsUpdate::AddTab(tab_event, new_tab)),
+			));
 			let message_json = match serde_json::to_string(&message) {
 				Ok(data) => data,
 				Err(error) => {
@@ -177,22 +205,6 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 
 	view! {
 		ctx,
-		datalist(id="list_all_events") {
-			Keyed(
-				iterable=all_events,
-				key=|event| event.id.clone(),
-				view=|ctx, event| {
-					view! {
-						ctx,
-						option(value=&event.name)
-					}
-				}
-			)
-		}
-		form(id="admin_tabs_event_selection", on:submit=event_form_handler) {
-			input(list="list_all_events", bind:value=entered_event_name, title=entered_event_error.get(), class=if entered_event_error.get().is_empty() { "" } else { "error" })
-			button(type="submit") { "Load Event" }
-		}
 		div(id="admin_tabs_list") {
 			Keyed(
 				iterable=current_event_tabs,
@@ -277,18 +289,11 @@ async fn AdminManageEventLogTabsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 				}
 			)
 		}
-		(if selected_event.get().is_some() {
-			view! {
-				ctx,
-				form(id="admin_tabs_add_tab", on:submit=new_tab_add_handler) {
-					input(placeholder="Tab name", bind:value=new_tab_name_entry, title=new_tab_error.get(), class=if new_tab_error.get().is_empty() { "" } else { "error" })
-					input(type="datetime-local", bind:value=new_tab_time_entry)
-					button(type="submit", disabled=!new_tab_error.get().is_empty()) { "Add Tab" }
-				}
-			}
-		} else {
-			view! { ctx, }
-		})
+		form(id="admin_tabs_add_tab", on:submit=new_tab_add_handler) {
+			input(placeholder="Tab name", bind:value=new_tab_name_entry, title=new_tab_error.get(), class=if new_tab_error.get().is_empty() { "" } else { "error" })
+			input(type="datetime-local", bind:value=new_tab_time_entry)
+			button(type="submit", disabled=!new_tab_error.get().is_empty()) { "Add Tab" }
+		}
 	}
 }
 
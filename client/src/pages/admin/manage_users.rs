@@ -9,12 +9,13 @@ use crate::components::color_input_with_contrast::ColorInputWithContrast;
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::AdminUsersStore;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
 use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
-use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::user::{SelfUserData, UpdateUser};
 use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
@@ -24,7 +25,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Manage Users | Stream Log");
+	set_page_title("Manage Users");
 
 	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 	let mut ws = ws_context.lock().await;
@@ -44,7 +45,9 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		));
 	}
 
-	let all_users = create_memo(ctx, || (*data.all_users.get()).clone());
+	let users_store: &AdminUsersStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminUsers));
+
+	let all_users = create_memo(ctx, || (*users_store.all_users.get()).clone());
 
 	view! {
 		ctx,
@@ -54,6 +57,8 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 				div { "Username" }
 				div { "Admin?" }
 				div { "Color" }
+				div { "Spell Check?" }
+				div(title="Deactivated users can't log in, but their past work is kept") { "Active?" }
 				div { }
 			}
 			Keyed(
@@ -65,6 +70,8 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 						let is_admin_signal = create_signal(ctx, user.is_admin);
 						let start_color = rgb_str_from_color(user.color);
 						let color_signal = create_signal(ctx, start_color);
+						let use_spell_check_signal = create_signal(ctx, user.use_spell_check);
+						let enabled_signal = create_signal(ctx, user.enabled);
 
 						let color_view_id = format!("admin_user_color_{}", user.id);
 
@@ -74,28 +81,49 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 								let Ok(new_color) = color_from_rgb_str(&color_signal.get()) else {
 									return;
 								};
-								let updated_user = SelfUserData {
-									id: user.id.clone(),
-									username: (*username_signal.get()).clone(),
-									color: new_color,
-									is_admin: *is_admin_signal.get(),
-									use_spell_check: user.use_spell_check
-								};
-
-								spawn_local_scoped(ctx, async move {
-									let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
-									let mut ws = ws_context.lock().await;
+								// Compare against the latest user data so we only send changes that haven't already been made
+								let user = users_store.all_users.get().iter().find(|current_user| current_user.id == user.id).cloned().unwrap_or_else(|| user.clone());
+								let mut messages: Vec<FromClientMessage> = Vec::new();
+								if *is_admin_signal.get() != user.is_admin || *enabled_signal.get() != user.enabled {
+									let updated_user = SelfUserData {
+										id: user.id.clone(),
+										username: user.username.clone(),
+										color: user.color,
+										is_admin: *is_admin_signal.get(),
+										use_spell_check: user.use_spell_check,
+										completed_guided_tour: user.completed_guided_tour,
+										enabled: *enabled_signal.get(),
+										avatar_updated_at: user.avatar_updated_at,
+										notification_preferences: user.notification_preferences.clone(),
+										time_display: user.time_display.clone()
+									};
+									messages.push(FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminUserUpdate(updated_user))));
+								}
+								if new_color != user.color || *use_spell_check_signal.get() != user.use_spell_check {
+									let profile_update = UpdateUser { color: new_color, use_spell_check: *use_spell_check_signal.get() };
+									messages.push(FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminUserProfileUpdate(user.clone(), profile_update))));
+								}
+								if messages.is_empty() {
+									return;
+								}
 
-									let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminUserUpdate(updated_user)));
-									let message_json = match serde_json::to_string(&message) {
-										Ok(msg) => msg,
+								let mut message_texts: Vec<Message> = Vec::with_capacity(messages.len());
+								for message in messages {
+									match serde_json::to_string(&message) {
+										Ok(msg) => message_texts.push(Message::Text(msg)),
 										Err(error) => {
 											let data: &DataSignals = use_context(ctx);
 											data.errors.modify().push(ErrorData::new_with_error("Failed to serialize user update message.", error));
 											return;
 										}
-									};
-									if let Err(error) = ws.send(Message::Text(message_json)).await {
+									}
+								}
+
+								spawn_local_scoped(ctx, async move {
+									let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+									let mut ws = ws_context.lock().await;
+
+									if let Err(error) = ws.send_multiple(message_texts).await {
 										let data: &DataSignals = use_context(ctx);
 										data.errors.modify().push(ErrorData::new_with_error("Failed to send user update message.", error));
 									}
@@ -103,6 +131,56 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 							}
 						};
 
+						let sign_out_handler = {
+							let user = user.clone();
+							move |_event: WebEvent| {
+								let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminUserSessionsRevoke(user.clone())));
+								let message_json = match serde_json::to_string(&message) {
+									Ok(msg) => msg,
+									Err(error) => {
+										let data: &DataSignals = use_context(ctx);
+										data.errors.modify().push(ErrorData::new_with_error("Failed to serialize user sign out message.", error));
+										return;
+									}
+								};
+
+								spawn_local_scoped(ctx, async move {
+									let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+									let mut ws = ws_context.lock().await;
+
+									if let Err(error) = ws.send(Message::Text(message_json)).await {
+										let data: &DataSignals = use_context(ctx);
+										data.errors.modify().push(ErrorData::new_with_error("Failed to send user sign out message.", error));
+									}
+								});
+							}
+						};
+
+						let view_as_handler = {
+							let user_id = user.id.clone();
+							move |_event: WebEvent| {
+								let message = FromClientMessage::StartImpersonation(user_id.clone());
+								let message_json = match serde_json::to_string(&message) {
+									Ok(msg) => msg,
+									Err(error) => {
+										let data: &DataSignals = use_context(ctx);
+										data.errors.modify().push(ErrorData::new_with_error("Failed to serialize view as user message.", error));
+										return;
+									}
+								};
+
+								spawn_local_scoped(ctx, async move {
+									let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+									let mut ws = ws_context.lock().await;
+
+									if let Err(error) = ws.send(Message::Text(message_json)).await {
+										let data: &DataSignals = use_context(ctx);
+										data.errors.modify().push(ErrorData::new_with_error("Failed to send view as user message.", error));
+									}
+								});
+							}
+						};
+
 						view! {
 							ctx,
 							form(class="admin_user_manage_row", on:submit=form_submit_handler) {
@@ -113,8 +191,16 @@ async fn AdminManageUsersLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
 								div(class="admin_user_color_selection") {
 									ColorInputWithContrast(color=color_signal, username=username_signal, view_id=&color_view_id)
 								}
+								div(class="admin_user_spell_check_toggle") {
+									input(type="checkbox", bind:checked=use_spell_check_signal)
+								}
+								div(class="admin_user_enabled_toggle") {
+									input(type="checkbox", bind:checked=enabled_signal)
+								}
 								div(class="admin_user_manage_submit") {
 									button { "Update" }
+									button(type="button", on:click=sign_out_handler) { "Sign Out Everywhere" }
+									button(type="button", title="See the site as this user does, without being able to change anything", on:click=view_as_handler) { "View As User" }
 								}
 							}
 						}
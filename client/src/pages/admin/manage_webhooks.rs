@@ -0,0 +1,266 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventWebhooksStore, AdminEventsStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::admin::{AdminEventWebhookUpdate, EventWebhook};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+use web_sys::Event as WebEvent;
+
+fn webhook_url_error(url: &str) -> String {
+	let url = url.trim();
+	if url.is_empty() {
+		String::from("URL cannot be empty.")
+	} else if !url.starts_with("http://") && !url.starts_with("https://") {
+		String::from("URL must start with http:// or https://")
+	} else {
+		String::new()
+	}
+}
+
+async fn send_webhook_update(ctx: Scope<'_>, update: AdminEventWebhookUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message =
+		FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::AdminEventWebhooksUpdate(update)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize webhook update message.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send webhook update message.",
+			error,
+		));
+	}
+}
+
+#[component]
+async fn AdminEventWebhooksLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Manage Webhooks");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let set_subscription_result = {
+		let subscriptions = vec![SubscriptionType::AdminEvents, SubscriptionType::AdminEventWebhooks];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = set_subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to subscribe to admin webhooks.",
+			error,
+		));
+	}
+
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let webhooks_store: &AdminEventWebhooksStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminEventWebhooks));
+
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
+	let selected_event: &Signal<Option<Event>> = create_signal(ctx, None);
+
+	let event_webhooks = create_memo(ctx, {
+		let all_webhooks = webhooks_store.all_event_webhooks.clone();
+		move || {
+			let all_webhooks = all_webhooks.get();
+			let Some(selected_event) = (*selected_event.get()).clone() else {
+				return Vec::new();
+			};
+			let event_webhooks: Vec<EventWebhook> = all_webhooks
+				.iter()
+				.filter(|webhook| webhook.event.id == selected_event.id)
+				.cloned()
+				.collect();
+			event_webhooks
+		}
+	});
+
+	view! {
+		ctx,
+		(if let Some(event) = selected_event.get().as_ref() {
+			let event_name = event.name.clone();
+			let go_back_handler = |_event: WebEvent| {
+				selected_event.set(None);
+			};
+
+			let new_url_entry = create_signal(ctx, String::new());
+			let new_url_error = create_memo(ctx, || {
+				let url = new_url_entry.get();
+				if url.is_empty() {
+					String::new()
+				} else {
+					webhook_url_error(&url)
+				}
+			});
+			let add_disabled = create_memo(ctx, || !webhook_url_error(&new_url_entry.get()).is_empty());
+
+			let add_webhook_handler = move |event: WebEvent| {
+				event.prevent_default();
+
+				let Some(selected_event) = (*selected_event.get()).clone() else {
+					return;
+				};
+				let url = new_url_entry.get().trim().to_string();
+				if !webhook_url_error(&url).is_empty() {
+					return;
+				}
+
+				let webhook = EventWebhook { id: String::new(), event: selected_event, url };
+				spawn_local_scoped(ctx, async move {
+					send_webhook_update(ctx, AdminEventWebhookUpdate::UpdateWebhook(webhook)).await;
+					new_url_entry.set(String::new());
+				});
+			};
+
+			view! {
+				ctx,
+				h1 {
+					"Webhooks for "
+					(event_name)
+				}
+				a(class="click", on:click=go_back_handler) {
+					"Back to event selection"
+				}
+				p {
+					"Whenever a log entry in this event is created, changed, or deleted, the entry is sent as JSON in a POST request to each of these URLs."
+				}
+				div(id="admin_event_webhooks_list") {
+					Keyed(
+						iterable=event_webhooks,
+						key=|webhook| (webhook.id.clone(), webhook.url.clone()),
+						view=move |ctx, webhook| {
+							let url_entry = create_signal(ctx, webhook.url.clone());
+							let url_error = create_memo(ctx, || webhook_url_error(&url_entry.get()));
+							let save_disabled = create_memo(ctx, {
+								let url = webhook.url.clone();
+								move || !url_error.get().is_empty() || url_entry.get().trim() == url
+							});
+
+							let save_handler = {
+								let webhook = webhook.clone();
+								move |event: WebEvent| {
+									event.prevent_default();
+									let url = url_entry.get().trim().to_string();
+									if !webhook_url_error(&url).is_empty() {
+										return;
+									}
+									let webhook = EventWebhook { url, ..webhook.clone() };
+									spawn_local_scoped(ctx, async move {
+										send_webhook_update(ctx, AdminEventWebhookUpdate::UpdateWebhook(webhook)).await;
+									});
+								}
+							};
+							let delete_handler = move |_event: WebEvent| {
+								let webhook = webhook.clone();
+								spawn_local_scoped(ctx, async move {
+									send_webhook_update(ctx, AdminEventWebhookUpdate::DeleteWebhook(webhook)).await;
+								});
+							};
+
+							view! {
+								ctx,
+								form(class="admin_event_webhooks_webhook", on:submit=save_handler) {
+									input(bind:value=url_entry, class=if url_error.get().is_empty() { "" } else { "error" })
+									button(type="submit", disabled=*save_disabled.get()) { "Save" }
+									button(type="button", on:click=delete_handler) { "Delete" }
+									span(class="input_error") { (url_error.get()) }
+								}
+							}
+						}
+					)
+					form(class="admin_event_webhooks_webhook", on:submit=add_webhook_handler) {
+						input(bind:value=new_url_entry, placeholder="https://", class=if new_url_error.get().is_empty() { "" } else { "error" })
+						button(type="submit", disabled=*add_disabled.get()) { "Add" }
+						span(class="input_error") { (new_url_error.get()) }
+					}
+				}
+			}
+		} else {
+			view! {
+				ctx,
+				div(id="admin_event_webhooks_event_selection") {
+					Keyed(
+						iterable=all_events,
+						key=|event| event.id.clone(),
+						view=move |ctx, event| {
+							let event_name = event.name.clone();
+							let edit_button_handler = move |_event: WebEvent| {
+								selected_event.set(Some(event.clone()));
+							};
+							view! {
+								ctx,
+								div(class="admin_event_webhooks_event_selection_event_name") {
+									(event_name)
+								}
+								div(class="admin_event_webhooks_event_selection_edit") {
+									button(on:click=edit_button_handler) {
+										"Edit Webhooks"
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn AdminEventWebhooksView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+	match user_signal.get().as_ref() {
+		Some(user) => {
+			if !user.is_admin {
+				spawn_local_scoped(ctx, async {
+					navigate("/");
+				});
+				return view! { ctx, };
+			}
+		}
+		None => {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading webhooks..." }) {
+			AdminEventWebhooksLoadedView
+		}
+	}
+}
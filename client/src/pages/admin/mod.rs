@@ -6,7 +6,12 @@
 
 pub mod assign_entry_types;
 pub mod assign_groups;
+pub mod audit_log;
+pub mod entry_type_matrix;
+pub mod jobs;
+pub mod manage_access_requests;
 pub mod manage_applications;
+pub mod manage_custom_fields;
 pub mod manage_editors;
 pub mod manage_entry_types;
 pub mod manage_events;
@@ -14,3 +19,5 @@ pub mod manage_groups;
 pub mod manage_info_pages;
 pub mod manage_tabs;
 pub mod manage_users;
+pub mod manage_webhooks;
+pub mod user_event_permissions;
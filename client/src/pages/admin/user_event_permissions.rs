@@ -0,0 +1,285 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::{AdminEventsStore, AdminUserEventPermissionsStore, AdminUsersStore};
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::admin::{AdminUserEventPermissionUpdate, UserEventPermission};
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycamore_router::navigate;
+use web_sys::Event as WebEvent;
+
+fn permission_level_name(level: PermissionLevel) -> &'static str {
+	match level {
+		PermissionLevel::View => "View",
+		PermissionLevel::Auditor => "Auditor",
+		PermissionLevel::Edit => "Edit",
+		PermissionLevel::Supervisor => "Supervisor",
+	}
+}
+
+async fn send_user_event_permission_update(ctx: Scope<'_>, update: AdminUserEventPermissionUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(
+		SubscriptionTargetUpdate::AdminUserEventPermissionsUpdate(update),
+	));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize user event permission update.",
+				error,
+			));
+			return;
+		}
+	};
+	if let Err(error) = ws.send(Message::Text(message_json)).await {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send user event permission update.",
+			error,
+		));
+	}
+}
+
+#[component]
+async fn AdminUserEventPermissionsLoadedView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	set_page_title("Direct Event Permissions");
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let add_subscriptions_result = {
+		let subscriptions = vec![
+			SubscriptionType::AdminUsers,
+			SubscriptionType::AdminEvents,
+			SubscriptionType::AdminUserEventPermissions,
+		];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = add_subscriptions_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send user event permission subscription message.",
+			error,
+		));
+	}
+
+	let users_store: &AdminUsersStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminUsers));
+	let events_store: &AdminEventsStore = create_ref(ctx, data.stores.get(SubscriptionType::AdminEvents));
+	let user_event_permissions_store: &AdminUserEventPermissionsStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::AdminUserEventPermissions));
+
+	let all_users = create_memo(ctx, || (*users_store.all_users.get()).clone());
+	let all_events = create_memo(ctx, || (*events_store.all_events.get()).clone());
+	let user_event_permissions = create_memo(ctx, || {
+		let mut user_event_permissions = (*user_event_permissions_store.user_event_permissions.get()).clone();
+		user_event_permissions.sort_by(|a, b| {
+			a.user
+				.username
+				.to_lowercase()
+				.cmp(&b.user.username.to_lowercase())
+				.then_with(|| a.event.name.cmp(&b.event.name))
+		});
+		user_event_permissions
+	});
+
+	let entered_username_signal = create_signal(ctx, String::new());
+	let entered_username_error_signal = create_signal(ctx, String::new());
+	let entered_event_name_signal = create_signal(ctx, String::new());
+	let entered_event_name_error_signal = create_signal(ctx, String::new());
+	let entered_level_signal = create_signal(ctx, String::from("supervisor"));
+
+	let grant_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let username = entered_username_signal.get();
+		let user = users_store
+			.all_users
+			.get()
+			.iter()
+			.find(|user| user.username == *username)
+			.cloned();
+		let Some(user) = user else {
+			entered_username_error_signal.set(String::from("That's not the name of a user."));
+			return;
+		};
+		entered_username_error_signal.set(String::new());
+
+		let event_name = entered_event_name_signal.get();
+		let event = events_store
+			.all_events
+			.get()
+			.iter()
+			.find(|event| event.name == *event_name)
+			.cloned();
+		let Some(event) = event else {
+			entered_event_name_error_signal.set(String::from("That's not the name of an event."));
+			return;
+		};
+		entered_event_name_error_signal.set(String::new());
+
+		let level = match entered_level_signal.get().as_str() {
+			"view" => PermissionLevel::View,
+			"auditor" => PermissionLevel::Auditor,
+			"edit" => PermissionLevel::Edit,
+			_ => PermissionLevel::Supervisor,
+		};
+
+		let permission = UserEventPermission {
+			user: user.into(),
+			event,
+			level,
+		};
+		spawn_local_scoped(ctx, async move {
+			send_user_event_permission_update(ctx, AdminUserEventPermissionUpdate::SetPermission(permission)).await;
+		});
+
+		entered_username_signal.set(String::new());
+		entered_event_name_signal.set(String::new());
+	};
+
+	view! {
+		ctx,
+		h1 { "Direct Event Permissions" }
+		p {
+			"Permissions granted here apply to a single user for a single event in addition to the permissions the user gets from their permission groups. Users have the highest permission level they get from either."
+		}
+		datalist(id="admin_user_event_permissions_users") {
+			Keyed(
+				iterable=all_users,
+				key=|user| user.id.clone(),
+				view=|ctx, user| view! { ctx, option(value=user.username) }
+			)
+		}
+		datalist(id="admin_user_event_permissions_events") {
+			Keyed(
+				iterable=all_events,
+				key=|event| event.id.clone(),
+				view=|ctx, event| view! { ctx, option(value=event.name) }
+			)
+		}
+		form(id="admin_user_event_permissions_grant", on:submit=grant_handler) {
+			div {
+				input(
+					list="admin_user_event_permissions_users",
+					placeholder="User",
+					bind:value=entered_username_signal,
+					class=if entered_username_error_signal.get().is_empty() { "" } else { "error" }
+				)
+				span(class="input_error") { (*entered_username_error_signal.get()) }
+			}
+			div {
+				input(
+					list="admin_user_event_permissions_events",
+					placeholder="Event",
+					bind:value=entered_event_name_signal,
+					class=if entered_event_name_error_signal.get().is_empty() { "" } else { "error" }
+				)
+				span(class="input_error") { (*entered_event_name_error_signal.get()) }
+			}
+			select(bind:value=entered_level_signal) {
+				option(value="supervisor") { "Supervisor" }
+				option(value="edit") { "Edit" }
+				option(value="auditor") { "Auditor" }
+				option(value="view") { "View" }
+			}
+			button(type="submit") { "Grant Permission" }
+		}
+		(if user_event_permissions.get().is_empty() {
+			view! {
+				ctx,
+				p { "No permissions have been granted directly to users." }
+			}
+		} else {
+			view! {
+				ctx,
+				table(id="admin_user_event_permissions") {
+					tr {
+						th { "User" }
+						th { "Event" }
+						th { "Permission" }
+						th {}
+					}
+					Keyed(
+						iterable=user_event_permissions,
+						key=|permission| (permission.user.id.clone(), permission.event.id.clone(), permission_level_name(permission.level)),
+						view=move |ctx, permission| {
+							let username_style = format!("color: {}", rgb_str_from_color(permission.user.color));
+							let level_name = permission_level_name(permission.level);
+							let remove_handler = {
+								let user = permission.user.clone();
+								let event = permission.event.clone();
+								move |_event: WebEvent| {
+									let update = AdminUserEventPermissionUpdate::RemovePermission(user.clone(), event.clone());
+									spawn_local_scoped(ctx, async move {
+										send_user_event_permission_update(ctx, update).await;
+									});
+								}
+							};
+
+							view! {
+								ctx,
+								tr {
+									td(style=username_style) { (permission.user.username) }
+									td { (permission.event.name) }
+									td { (level_name) }
+									td {
+										button(type="button", on:click=remove_handler) { "Remove" }
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn AdminUserEventPermissionsView<G: Html>(ctx: Scope<'_>) -> View<G> {
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+	match user.get().as_ref() {
+		Some(user) => {
+			if !user.is_admin {
+				spawn_local_scoped(ctx, async {
+					navigate("/");
+				});
+				return view! { ctx, };
+			}
+		}
+		None => {
+			spawn_local_scoped(ctx, async {
+				navigate("/");
+			});
+			return view! { ctx, };
+		}
+	}
+
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading permission data..." }) {
+			AdminUserEventPermissionsLoadedView
+		}
+	}
+}
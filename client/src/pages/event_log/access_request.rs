@@ -0,0 +1,90 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use web_sys::Event as WebEvent;
+
+#[derive(Prop)]
+pub struct EventAccessRequestProps {
+	event_id: String,
+}
+
+/// Shown in place of the event log when the user isn't allowed to access the event. Lets the user ask an administrator
+/// for access.
+#[component]
+pub fn EventAccessRequestView<G: Html>(ctx: Scope<'_>, props: EventAccessRequestProps) -> View<G> {
+	set_page_title("No Access");
+
+	let data: &DataSignals = use_context(ctx);
+	let event_id = create_ref(ctx, props.event_id);
+
+	let request_sent = create_memo(ctx, || data.requested_event_access.get().contains(event_id));
+	let request_pending = create_signal(ctx, false);
+
+	let request_handler = move |_event: WebEvent| {
+		if *request_pending.get() || *request_sent.get() {
+			return;
+		}
+
+		let message = FromClientMessage::RequestEventAccess(event_id.clone());
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize event access request.",
+					error,
+				));
+				return;
+			}
+		};
+
+		request_pending.set(true);
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			let send_result = ws.send(Message::Text(message_json)).await;
+			if let Err(error) = send_result {
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send event access request.", error));
+			}
+			request_pending.set(false);
+		});
+	};
+
+	view! {
+		ctx,
+		div(id="event_access_request") {
+			p {
+				"You don't have access to this event."
+			}
+			(if *request_sent.get() {
+				view! {
+					ctx,
+					p(class="event_access_request_sent") {
+						"Access requested. You'll be able to view the event once an administrator approves your request."
+					}
+				}
+			} else {
+				view! {
+					ctx,
+					button(type="button", disabled=*request_pending.get(), on:click=request_handler) {
+						"Request Access"
+					}
+				}
+			})
+		}
+	}
+}
@@ -0,0 +1,191 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::components::event_log_entry::utils::time_display_for_user;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::user::SelfUserData;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+
+#[derive(Prop)]
+pub struct EventLogDeadLinksProps {
+	id: String,
+}
+
+/// Gets the links on an entry that were found to be dead. Links that were flagged but have since been removed from
+/// the entry aren't included.
+fn current_dead_links(entry: &EventLogEntry) -> Vec<String> {
+	entry
+		.video_link
+		.iter()
+		.chain(entry.media_links.iter())
+		.filter(|link| entry.dead_links.contains(link))
+		.cloned()
+		.collect()
+}
+
+#[component]
+async fn EventLogDeadLinksLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogDeadLinksProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Dead Links] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Dead Links", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	create_effect(ctx, {
+		let event_signal = event_subscription_data.event.clone();
+		move || {
+			let event = event_signal.get();
+			let page_title = format!("{} - Dead Links", event.name);
+			set_page_title(&page_title);
+		}
+	});
+
+	let is_supervisor = create_memo(ctx, {
+		let permission_signal = event_subscription_data.permission.clone();
+		move || *permission_signal.get() == PermissionLevel::Supervisor
+	});
+
+	let entries_with_dead_links = create_memo(ctx, {
+		let entries_signal = event_subscription_data.event_log_entries.clone();
+		move || {
+			let entries: Vec<(EventLogEntry, Vec<String>)> = entries_signal
+				.get()
+				.iter()
+				.filter_map(|entry| {
+					let dead_links = current_dead_links(entry);
+					if dead_links.is_empty() {
+						None
+					} else {
+						Some((entry.clone(), dead_links))
+					}
+				})
+				.collect();
+			entries
+		}
+	});
+
+	let event = event_subscription_data.event.get();
+	let event_start = event.start_time;
+	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+	let time_display = create_ref(ctx, time_display_for_user(&user.get(), &event));
+
+	view! {
+		ctx,
+		(if !*is_supervisor.get() {
+			view! {
+				ctx,
+				p(id="event_log_dead_links_not_allowed") { "Only supervisors can view the dead link report." }
+			}
+		} else if entries_with_dead_links.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_dead_links_empty") { "No dead links have been found for this event." }
+			}
+		} else {
+			view! {
+				ctx,
+				table(id="event_log_dead_links") {
+					tr {
+						th { "Start" }
+						th { "Description" }
+						th { "Submitter/Winner" }
+						th { "Dead Links" }
+					}
+					Keyed(
+						iterable=entries_with_dead_links,
+						key=|(entry, dead_links)| (entry.id.clone(), dead_links.clone()),
+						view=move |ctx, (entry, dead_links)| {
+							let start_time = entry
+								.start_time
+								.map(|start_time| time_display.format_time(start_time, event_start))
+								.unwrap_or_default();
+							let dead_links_signal = create_signal(ctx, dead_links);
+
+							view! {
+								ctx,
+								tr {
+									td(class="event_log_dead_links_start_time") { (start_time) }
+									td(class="event_log_dead_links_description") { (entry.description) }
+									td(class="event_log_dead_links_submitter_winner") { (entry.submitter_or_winner) }
+									td(class="event_log_dead_links_links") {
+										Keyed(
+											iterable=dead_links_signal,
+											key=|link| link.clone(),
+											view=|ctx, link| {
+												let link_link = link.clone();
+												view! {
+													ctx,
+													a(href=link_link, target="_blank", rel="noopener") { (link) }
+												}
+											}
+										)
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn EventLogDeadLinksView<G: Html>(ctx: Scope<'_>, props: EventLogDeadLinksProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading dead link report..." }) {
+			EventLogDeadLinksLoadedView(id=props.id)
+		}
+	}
+}
@@ -0,0 +1,225 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::components::event_log_entry::utils::time_display_for_user;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::{EventLogEntry, VideoEditState};
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+
+#[derive(Prop)]
+pub struct EventLogEditorNotesProps {
+	id: String,
+}
+
+/// A set of entries with notes that share an editor and video edit state
+#[derive(Clone, Eq, PartialEq)]
+struct EditorNotesGroup {
+	editor: Option<PublicUserData>,
+	video_edit_state: VideoEditState,
+	entries: Vec<EventLogEntry>,
+}
+
+/// Groups the entries that have notes to the editor by editor and video edit state. The groups for the current user are
+/// listed first, followed by the groups for other editors in name order and then the entries with no editor.
+fn group_entries_with_notes(entries: &[EventLogEntry], current_user_id: Option<&str>) -> Vec<EditorNotesGroup> {
+	let mut groups: Vec<EditorNotesGroup> = Vec::new();
+	for entry in entries.iter().filter(|entry| !entry.notes.trim().is_empty()) {
+		let editor_id = entry.editor.as_ref().map(|editor| editor.id.as_str());
+		let existing_group = groups.iter_mut().find(|group| {
+			group.editor.as_ref().map(|editor| editor.id.as_str()) == editor_id
+				&& group.video_edit_state == entry.video_edit_state
+		});
+		match existing_group {
+			Some(group) => group.entries.push(entry.clone()),
+			None => groups.push(EditorNotesGroup {
+				editor: entry.editor.clone(),
+				video_edit_state: entry.video_edit_state,
+				entries: vec![entry.clone()],
+			}),
+		}
+	}
+
+	let video_edit_states = VideoEditState::all_states();
+	groups.sort_by(|a, b| {
+		let a_editor_order = editor_sort_key(a.editor.as_ref(), current_user_id);
+		let b_editor_order = editor_sort_key(b.editor.as_ref(), current_user_id);
+		let a_state_index = video_edit_states.iter().position(|state| *state == a.video_edit_state);
+		let b_state_index = video_edit_states.iter().position(|state| *state == b.video_edit_state);
+		a_editor_order
+			.cmp(&b_editor_order)
+			.then_with(|| a_state_index.cmp(&b_state_index))
+	});
+	groups
+}
+
+fn editor_sort_key(editor: Option<&PublicUserData>, current_user_id: Option<&str>) -> (u8, String) {
+	match editor {
+		Some(editor) if Some(editor.id.as_str()) == current_user_id => (0, String::new()),
+		Some(editor) => (1, editor.username.to_lowercase()),
+		None => (2, String::new()),
+	}
+}
+
+fn video_edit_state_name(state: VideoEditState) -> &'static str {
+	match state {
+		VideoEditState::NoVideo => "No Video",
+		VideoEditState::MarkedForEditing => "Marked",
+		VideoEditState::DoneEditing => "Done Editing",
+	}
+}
+
+#[component]
+async fn EventLogEditorNotesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEditorNotesProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Editor Notes] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Notes to Editor", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	create_effect(ctx, {
+		let event_signal = event_subscription_data.event.clone();
+		move || {
+			let event = event_signal.get();
+			let page_title = format!("{} - Notes to Editor", event.name);
+			set_page_title(&page_title);
+		}
+	});
+
+	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+	let notes_groups = create_memo(ctx, {
+		let entries_signal = event_subscription_data.event_log_entries.clone();
+		move || {
+			let user = user_signal.get();
+			let current_user_id = (*user).as_ref().map(|user| user.id.as_str());
+			group_entries_with_notes(&entries_signal.get(), current_user_id)
+		}
+	});
+
+	let event = event_subscription_data.event.get();
+	let event_start = event.start_time;
+	let time_display = create_ref(ctx, time_display_for_user(&user_signal.get(), &event));
+
+	view! {
+		ctx,
+		(if notes_groups.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_editor_notes_empty") { "No entries in this event have notes to the editor." }
+			}
+		} else {
+			view! {
+				ctx,
+				div(id="event_log_editor_notes") {
+					Indexed(
+						iterable=notes_groups,
+						view=move |ctx, group| {
+							let editor_name = group
+								.editor
+								.as_ref()
+								.map(|editor| editor.username.clone())
+								.unwrap_or_else(|| String::from("No editor"));
+							let heading = format!(
+								"{} — {} ({})",
+								editor_name,
+								video_edit_state_name(group.video_edit_state),
+								group.entries.len()
+							);
+							let entries_signal = create_signal(ctx, group.entries);
+
+							view! {
+								ctx,
+								h2(class="event_log_editor_notes_group_heading") { (heading) }
+								table(class="event_log_editor_notes_group") {
+									tr {
+										th { "Start" }
+										th { "Description" }
+										th { "Submitter/Winner" }
+										th { "Notes to Editor" }
+									}
+									Indexed(
+										iterable=entries_signal,
+										view=move |ctx, entry| {
+											let start_time = entry
+												.start_time
+												.map(|start_time| time_display.format_time(start_time, event_start))
+												.unwrap_or_default();
+
+											view! {
+												ctx,
+												tr {
+													td(class="event_log_editor_notes_start_time") { (start_time) }
+													td(class="event_log_editor_notes_description") { (entry.description) }
+													td(class="event_log_editor_notes_submitter_winner") { (entry.submitter_or_winner) }
+													td(class="event_log_editor_notes_notes") { (entry.notes) }
+												}
+											}
+										}
+									)
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn EventLogEditorNotesView<G: Html>(ctx: Scope<'_>, props: EventLogEditorNotesProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading notes to editor..." }) {
+			EventLogEditorNotesLoadedView(id=props.id)
+		}
+	}
+}
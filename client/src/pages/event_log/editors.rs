@@ -0,0 +1,123 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::pages::admin::manage_editors::EventEditorsEditor;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::AdminEventEditorsStore;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+
+#[derive(Prop)]
+pub struct EventLogEditorsProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogEditorsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEditorsProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Editors] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let event = (*event_subscription_data.event.get()).clone();
+	let page_title = format!("{} - Manage Editors", event.name);
+	set_page_title(&page_title);
+
+	if *event_subscription_data.permission.get() != PermissionLevel::Supervisor {
+		return view! {
+			ctx,
+			p(id="event_log_editors_not_allowed") { "Only supervisors can manage the editors for this event." }
+		};
+	}
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let subscription_result = {
+		let subscriptions = vec![
+			SubscriptionType::EventLogData(props.id.clone()),
+			SubscriptionType::AdminEventEditorsForEvent(props.id.clone()),
+		];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event editors subscription message.",
+			error,
+		));
+	}
+
+	let editors_store: &AdminEventEditorsStore = create_ref(
+		ctx,
+		data.stores
+			.get(SubscriptionType::AdminEventEditorsForEvent(props.id.clone())),
+	);
+	let candidate_users = create_memo(ctx, || (*editors_store.editor_candidates.get()).clone());
+
+	view! {
+		ctx,
+		h1 { "Manage Editors" }
+		p {
+			"Editors can be assigned to log entries to edit their videos. Users who can edit this event can be made editors."
+		}
+		EventEditorsEditor(event=event, users=candidate_users, editors=editors_store)
+	}
+}
+
+#[component]
+pub fn EventLogEditorsView<G: Html>(ctx: Scope<'_>, props: EventLogEditorsProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading editors..." }) {
+			EventLogEditorsLoadedView(id=props.id)
+		}
+	}
+}
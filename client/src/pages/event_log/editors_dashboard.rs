@@ -0,0 +1,190 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::EditorWorkloadStore;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use stream_log_shared::messages::editor_workload::EditorWorkload;
+use stream_log_shared::messages::event_log::{VideoEditState, VideoProcessingState};
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+
+fn video_edit_state_name(state: VideoEditState) -> &'static str {
+	match state {
+		VideoEditState::NoVideo => "No Video",
+		VideoEditState::MarkedForEditing => "Marked",
+		VideoEditState::DoneEditing => "Done Editing",
+	}
+}
+
+#[derive(Prop)]
+pub struct EventLogEditorsDashboardProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogEditorsDashboardLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEditorsDashboardProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_result = {
+		let subscriptions = vec![
+			SubscriptionType::EventLogData(props.id.clone()),
+			SubscriptionType::EditorWorkload(props.id.clone()),
+		];
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.set_subscriptions(subscriptions, &mut ws).await
+	};
+	if let Err(error) = subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send editor workload subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Editors Dashboard] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Editors Dashboard", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	let workload_store: &EditorWorkloadStore =
+		create_ref(ctx, data.stores.get(SubscriptionType::EditorWorkload(props.id.clone())));
+	let workloads = create_memo(ctx, || (*workload_store.workloads.get()).clone());
+	let all_workloads = create_memo(ctx, || (*workloads.get()).clone().unwrap_or_default());
+
+	let edit_state_count = VideoEditState::all_states().len();
+	let processing_state_count = VideoProcessingState::all_states().len();
+
+	view! {
+		ctx,
+		h1 { "Editors Dashboard" }
+		p {
+			"Entries assigned to each editor, counted by the state of their videos. Deleted entries aren't counted."
+		}
+		(if workloads.get().is_none() {
+			view! {
+				ctx,
+				p { "Loading editor workloads..." }
+			}
+		} else if all_workloads.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_editors_dashboard_empty") { "No entries are assigned to editors." }
+			}
+		} else {
+			let edit_state_headers: Vec<View<G>> = VideoEditState::all_states()
+				.into_iter()
+				.map(|state| view! { ctx, th { (video_edit_state_name(state)) } })
+				.collect();
+			let edit_state_headers = View::new_fragment(edit_state_headers);
+			let processing_state_headers: Vec<View<G>> = VideoProcessingState::all_states()
+				.into_iter()
+				.map(|state| view! { ctx, th { (state.to_string()) } })
+				.collect();
+			let processing_state_headers = View::new_fragment(processing_state_headers);
+			view! {
+				ctx,
+				table(id="event_log_editors_dashboard") {
+					thead {
+						tr {
+							th(rowspan=2) { "Editor" }
+							th(rowspan=2) { "Assigned" }
+							th(colspan=edit_state_count) { "Video Edit State" }
+							th(colspan=processing_state_count) { "Video Processing State" }
+						}
+						tr {
+							(edit_state_headers)
+							(processing_state_headers)
+						}
+					}
+					tbody {
+						Indexed(
+							iterable=all_workloads,
+							view=|ctx, workload| view! { ctx, EditorWorkloadRow(workload=workload) }
+						)
+					}
+				}
+			}
+		})
+	}
+}
+
+#[derive(Prop)]
+struct EditorWorkloadRowProps {
+	workload: EditorWorkload,
+}
+
+#[component]
+fn EditorWorkloadRow<G: Html>(ctx: Scope<'_>, props: EditorWorkloadRowProps) -> View<G> {
+	let workload = props.workload;
+	let edit_state_cells: Vec<View<G>> = VideoEditState::all_states()
+		.into_iter()
+		.map(|state| {
+			let count = workload.edit_state_count(state);
+			view! { ctx, td(class="event_log_editors_dashboard_count") { (count) } }
+		})
+		.collect();
+	let edit_state_cells = View::new_fragment(edit_state_cells);
+	let processing_state_cells: Vec<View<G>> = VideoProcessingState::all_states()
+		.into_iter()
+		.map(|state| {
+			let count = workload.processing_state_count(state);
+			view! { ctx, td(class="event_log_editors_dashboard_count") { (count) } }
+		})
+		.collect();
+	let processing_state_cells = View::new_fragment(processing_state_cells);
+	let editor_style = format!("color: {}", rgb_str_from_color(workload.editor.color));
+
+	view! {
+		ctx,
+		tr {
+			td(style=editor_style) { (workload.editor.username) }
+			td(class="event_log_editors_dashboard_count") { (workload.total_entries) }
+			(edit_state_cells)
+			(processing_state_cells)
+		}
+	}
+}
+
+#[component]
+pub fn EventLogEditorsDashboardView<G: Html>(ctx: Scope<'_>, props: EventLogEditorsDashboardProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading editors dashboard..." }) {
+			EventLogEditorsDashboardLoadedView(id=props.id)
+		}
+	}
+}
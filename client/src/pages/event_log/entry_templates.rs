@@ -0,0 +1,333 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::entry_templates::EntryTemplate;
+use stream_log_shared::messages::event_subscription::EventSubscriptionUpdate;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::tags::Tag;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use web_sys::Event as WebEvent;
+
+async fn send_template_update(ctx: Scope<'_>, event: Event, update: EventSubscriptionUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+		event,
+		Box::new(update),
+	)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize entry template update.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send entry template update.",
+			error,
+		));
+	}
+}
+
+#[derive(Prop)]
+pub struct EventLogEntryTemplatesProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogEntryTemplatesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEntryTemplatesProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Entry Templates] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Entry Templates", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	if *event_subscription_data.permission.get() != PermissionLevel::Supervisor {
+		return view! {
+			ctx,
+			p(id="event_log_entry_templates_not_allowed") { "Only supervisors can manage entry templates for this event." }
+		};
+	}
+
+	let event_signal = create_ref(ctx, event_subscription_data.event.clone());
+	let templates = create_memo(ctx, {
+		let templates_signal = event_subscription_data.entry_templates.clone();
+		move || (*templates_signal.get()).clone()
+	});
+	let entry_types = create_memo(ctx, {
+		let entry_types_signal = event_subscription_data.entry_types.clone();
+		move || {
+			let mut entry_types = (*entry_types_signal.get()).clone();
+			entry_types.sort_by_key(|entry_type| entry_type.name.to_lowercase());
+			entry_types
+		}
+	});
+	let event_tags = create_ref(ctx, event_subscription_data.tags.clone());
+
+	// The ID of the template being edited, which is empty when creating a new template
+	let editing_template_id = create_signal(ctx, String::new());
+	let entered_name = create_signal(ctx, String::new());
+	let entered_name_error = create_signal(ctx, String::new());
+	let entered_entry_type_id = create_signal(ctx, String::new());
+	let entered_description = create_signal(ctx, String::new());
+	let entered_tags = create_signal(ctx, String::new());
+	let entered_tags_error = create_signal(ctx, String::new());
+
+	let reset_form = move || {
+		editing_template_id.set(String::new());
+		entered_name.set(String::new());
+		entered_name_error.set(String::new());
+		entered_entry_type_id.set(String::new());
+		entered_description.set(String::new());
+		entered_tags.set(String::new());
+		entered_tags_error.set(String::new());
+	};
+
+	let save_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let name = entered_name.get().trim().to_string();
+		if name.is_empty() {
+			entered_name_error.set(String::from("Enter a name for the template."));
+			return;
+		}
+		entered_name_error.set(String::new());
+
+		let event_tags = event_tags.get();
+		let mut tags: Vec<Tag> = Vec::new();
+		for tag_name in entered_tags.get().split(',') {
+			let tag_name = tag_name.trim();
+			if tag_name.is_empty() {
+				continue;
+			}
+			let Some(tag) = event_tags.iter().find(|tag| tag.name == tag_name) else {
+				entered_tags_error.set(format!("The tag \"{}\" doesn't exist in this event.", tag_name));
+				return;
+			};
+			if !tags.iter().any(|entered_tag| entered_tag.id == tag.id) {
+				tags.push(tag.clone());
+			}
+		}
+		entered_tags_error.set(String::new());
+
+		let entry_type = Some((*entered_entry_type_id.get()).clone()).filter(|entry_type_id| !entry_type_id.is_empty());
+		let template = EntryTemplate {
+			id: (*editing_template_id.get()).clone(),
+			name,
+			entry_type,
+			description: entered_description.get().trim().to_string(),
+			tags,
+		};
+		let event = (*event_signal.get()).clone();
+		spawn_local_scoped(
+			ctx,
+			send_template_update(ctx, event, EventSubscriptionUpdate::UpdateEntryTemplate(template)),
+		);
+		reset_form();
+	};
+
+	let cancel_handler = move |_event: WebEvent| {
+		reset_form();
+	};
+
+	view! {
+		ctx,
+		h1 { "Entry Templates" }
+		p {
+			"Templates fill in the type, description, and tags of an entry that's logged often. They can be chosen from the entry form in the event log."
+		}
+		(if templates.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_entry_templates_empty") { "This event doesn't have any entry templates yet." }
+			}
+		} else {
+			view! {
+				ctx,
+				table(id="event_log_entry_templates") {
+					tr {
+						th { "Name" }
+						th { "Type" }
+						th { "Description" }
+						th { "Tags" }
+						th { }
+					}
+					Keyed(
+						iterable=templates,
+						key=|template| template.id.clone(),
+						view=move |ctx, template| {
+							let entry_type_name = template
+								.entry_type
+								.as_ref()
+								.and_then(|entry_type_id| {
+									entry_types
+										.get()
+										.iter()
+										.find(|entry_type| entry_type.id == *entry_type_id)
+										.map(|entry_type| entry_type.name.clone())
+								})
+								.unwrap_or_default();
+							let tag_names: Vec<&str> = template.tags.iter().map(|tag| tag.name.as_str()).collect();
+							let tag_names = tag_names.join(", ");
+
+							let edit_handler = {
+								let template = template.clone();
+								move |_event: WebEvent| {
+									let tag_names: Vec<&str> = template.tags.iter().map(|tag| tag.name.as_str()).collect();
+									editing_template_id.set(template.id.clone());
+									entered_name.set(template.name.clone());
+									entered_name_error.set(String::new());
+									entered_entry_type_id.set(template.entry_type.clone().unwrap_or_default());
+									entered_description.set(template.description.clone());
+									entered_tags.set(tag_names.join(", "));
+									entered_tags_error.set(String::new());
+								}
+							};
+							let delete_handler = {
+								let template = template.clone();
+								move |_event: WebEvent| {
+									if *editing_template_id.get() == template.id {
+										reset_form();
+									}
+									let event = (*event_signal.get()).clone();
+									let update = EventSubscriptionUpdate::DeleteEntryTemplate(template.clone());
+									spawn_local_scoped(ctx, send_template_update(ctx, event, update));
+								}
+							};
+
+							view! {
+								ctx,
+								tr {
+									td(class="event_log_entry_templates_name") { (template.name) }
+									td { (entry_type_name) }
+									td { (template.description) }
+									td { (tag_names) }
+									td {
+										button(type="button", on:click=edit_handler) { "Edit" }
+										button(type="button", on:click=delete_handler) { "Delete" }
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+		h2 {
+			(if editing_template_id.get().is_empty() { "New Template" } else { "Edit Template" })
+		}
+		form(id="event_log_entry_template_form", on:submit=save_handler) {
+			label(for="event_log_entry_template_name") { "Name" }
+			input(
+				id="event_log_entry_template_name",
+				bind:value=entered_name,
+				placeholder="Ad break",
+				class=if entered_name_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_name_error.get()).clone()
+			)
+			label(for="event_log_entry_template_type") { "Type" }
+			select(id="event_log_entry_template_type", bind:value=entered_entry_type_id) {
+				option(value="") { "No type" }
+				Keyed(
+					iterable=entry_types,
+					key=|entry_type| entry_type.id.clone(),
+					view=|ctx, entry_type| view! { ctx, option(value=entry_type.id) { (entry_type.name) } }
+				)
+			}
+			label(for="event_log_entry_template_description") { "Description" }
+			input(id="event_log_entry_template_description", bind:value=entered_description)
+			label(for="event_log_entry_template_tags") { "Tags" }
+			input(
+				id="event_log_entry_template_tags",
+				bind:value=entered_tags,
+				placeholder="Tags, separated by commas",
+				class=if entered_tags_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_tags_error.get()).clone()
+			)
+			div(id="event_log_entry_template_actions") {
+				button(type="submit") { "Save" }
+				(if editing_template_id.get().is_empty() {
+					view! { ctx, }
+				} else {
+					view! {
+						ctx,
+						button(type="button", on:click=cancel_handler) { "Cancel" }
+					}
+				})
+			}
+		}
+	}
+}
+
+#[component]
+pub fn EventLogEntryTemplatesView<G: Html>(ctx: Scope<'_>, props: EventLogEntryTemplatesProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading entry templates..." }) {
+			EventLogEntryTemplatesLoadedView(id=props.id)
+		}
+	}
+}
@@ -5,20 +5,35 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::color_utils::rgb_str_from_color;
+use crate::components::event_log_entry::utils::{entry_exceeds_expected_duration, format_duration};
 use crate::entry_type_colors::use_white_foreground;
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
 use crate::websocket::WebSocketSendStream;
 use crate::DataSignals;
+use chrono::Duration;
 use futures::future::poll_fn;
 use futures::lock::Mutex;
 use futures::task::{Context, Poll, Waker};
 use std::collections::HashMap;
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::event_log::EndTimeData;
 use stream_log_shared::messages::subscriptions::SubscriptionType;
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
 
+/// Summary of how long the entries of an entry type have run
+#[derive(Clone, Eq, Hash, PartialEq)]
+struct EntryTypeDurations {
+	/// Total duration of all of the type's entries that have both a start and end time
+	total: Duration,
+	/// Number of the type's entries that have both a start and end time
+	entry_count: i32,
+	/// Number of the type's entries that ran longer than the type's expected duration
+	over_expected_count: i32,
+}
+
 #[derive(Prop)]
 pub struct EventLogEntryTypesProps {
 	id: String,
@@ -64,30 +79,64 @@ async fn EventLogEntryTypesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEn
 	})
 	.await;
 
-	let page_title = format!(
-		"{} - Entry Types | Stream Log",
-		event_subscription_data.event.get().name
-	);
+	let page_title = format!("{} - Entry Types", event_subscription_data.event.get().name);
 	set_page_title(&page_title);
 
 	create_effect(ctx, {
 		let event_signal = event_subscription_data.event.clone();
 		move || {
 			let event = event_signal.get();
-			let page_title = format!("{} - Entry Types | Stream Log", event.name);
+			let page_title = format!("{} - Entry Types", event.name);
 			set_page_title(&page_title);
 		}
 	});
 
-	let event_entry_types = create_memo(ctx, move || (*event_subscription_data.entry_types.get()).clone());
+	// Each entry type is shown with how long its entries have actually run, so expectations can be compared with what
+	// happened when planning future schedules.
+	let event_entry_types = create_memo(ctx, move || {
+		let entry_types = event_subscription_data.entry_types.get();
+		let entries = event_subscription_data.event_log_entries.get();
+		let entry_type_durations: Vec<(EntryType, EntryTypeDurations)> = entry_types
+			.iter()
+			.map(|entry_type| {
+				let mut durations = EntryTypeDurations {
+					total: Duration::zero(),
+					entry_count: 0,
+					over_expected_count: 0,
+				};
+				for entry in entries
+					.iter()
+					.filter(|entry| entry.entry_type.as_ref() == Some(&entry_type.id))
+				{
+					let (Some(start_time), EndTimeData::Time(end_time)) = (entry.start_time, &entry.end_time) else {
+						continue;
+					};
+					durations.total = durations.total + (*end_time - start_time);
+					durations.entry_count += 1;
+					if entry_exceeds_expected_duration(entry, Some(entry_type)) {
+						durations.over_expected_count += 1;
+					}
+				}
+				(entry_type.clone(), durations)
+			})
+			.collect();
+		entry_type_durations
+	});
 
 	view! {
 		ctx,
 		table(id="event_log_entry_type_list") {
+			tr {
+				th { "Type" }
+				th { "Description" }
+				th { "Expected Duration" }
+				th { "Average Duration" }
+				th { "Longer Than Expected" }
+			}
 			Keyed(
 				iterable=event_entry_types,
-				key=|entry_type| entry_type.id.clone(),
-				view=|ctx, entry_type| {
+				key=|(entry_type, durations)| (entry_type.id.clone(), entry_type.expected_duration_minutes, durations.clone()),
+				view=|ctx, (entry_type, durations)| {
 					let entry_type_background = rgb_str_from_color(entry_type.color);
 					let entry_type_foreground = if use_white_foreground(&entry_type.color) {
 						"#ffffff"
@@ -96,6 +145,21 @@ async fn EventLogEntryTypesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEn
 					};
 					let entry_type_style = format!("background: {}; color: {}", entry_type_background, entry_type_foreground);
 
+					let expected_duration = entry_type
+						.expected_duration_minutes
+						.map(|minutes| format_duration(&Duration::minutes(minutes.into())))
+						.unwrap_or_default();
+					let average_duration = if durations.entry_count > 0 {
+						format_duration(&(durations.total / durations.entry_count))
+					} else {
+						String::new()
+					};
+					let over_expected = if entry_type.expected_duration_minutes.is_some() {
+						format!("{} of {}", durations.over_expected_count, durations.entry_count)
+					} else {
+						String::new()
+					};
+
 					view! {
 						ctx,
 						tr {
@@ -105,6 +169,15 @@ async fn EventLogEntryTypesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogEn
 							td(class="entry_type_list_description") {
 								(entry_type.description)
 							}
+							td(class="entry_type_list_expected_duration") {
+								(expected_duration)
+							}
+							td(class="entry_type_list_average_duration") {
+								(average_duration)
+							}
+							td(class="entry_type_list_over_expected") {
+								(over_expected)
+							}
 						}
 					}
 				}
@@ -0,0 +1,310 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::broadcasts::MAX_BROADCAST_LENGTH;
+use stream_log_shared::messages::event_subscription::EventSubscriptionUpdate;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::feedback::MAX_FEEDBACK_LENGTH;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use web_sys::Event as WebEvent;
+
+async fn send_feedback_update(ctx: Scope<'_>, event: Event, update: EventSubscriptionUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+		event,
+		Box::new(update),
+	)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize feedback message.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors
+			.modify()
+			.push(ErrorData::new_with_error("Failed to send feedback message.", error));
+	}
+}
+
+#[derive(Prop)]
+pub struct EventFeedbackFormProps {
+	event: RcSignal<Event>,
+}
+
+/// A small form for sending a short message to the event's supervisors. Feedback doesn't record who sent it.
+#[component]
+pub fn EventFeedbackForm<G: Html>(ctx: Scope<'_>, props: EventFeedbackFormProps) -> View<G> {
+	let entered_message = create_signal(ctx, String::new());
+	let entered_message_error = create_signal(ctx, String::new());
+	let sent_message_visible = create_signal(ctx, false);
+
+	let submit_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let message = entered_message.get().trim().to_string();
+		if message.is_empty() {
+			entered_message_error.set(String::from("Enter a message to send."));
+			return;
+		}
+		if message.chars().count() > MAX_FEEDBACK_LENGTH {
+			entered_message_error.set(format!(
+				"Feedback can't be longer than {} characters.",
+				MAX_FEEDBACK_LENGTH
+			));
+			return;
+		}
+		entered_message_error.set(String::new());
+		entered_message.set(String::new());
+		sent_message_visible.set(true);
+
+		let event = (*props.event.get()).clone();
+		spawn_local_scoped(
+			ctx,
+			send_feedback_update(ctx, event, EventSubscriptionUpdate::SubmitFeedback(message)),
+		);
+	};
+
+	let input_handler = |_event: WebEvent| {
+		sent_message_visible.set(false);
+	};
+
+	view! {
+		ctx,
+		form(id="event_log_feedback_form", on:submit=submit_handler) {
+			input(
+				bind:value=entered_message,
+				placeholder="Report an issue to supervisors",
+				maxlength=MAX_FEEDBACK_LENGTH,
+				class=if entered_message_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_message_error.get()).clone(),
+				on:input=input_handler
+			)
+			button(type="submit", title="Send anonymous feedback to the event's supervisors") { "Send Feedback" }
+			(if *sent_message_visible.get() {
+				view! {
+					ctx,
+					span(id="event_log_feedback_sent") { "Sent!" }
+				}
+			} else {
+				view! { ctx, }
+			})
+		}
+	}
+}
+
+#[derive(Prop)]
+struct EventBroadcastFormProps {
+	event: RcSignal<Event>,
+}
+
+/// Lets supervisors send a message that's shown prominently to everyone currently viewing the event
+#[component]
+fn EventBroadcastForm<G: Html>(ctx: Scope<'_>, props: EventBroadcastFormProps) -> View<G> {
+	let entered_message = create_signal(ctx, String::new());
+	let entered_message_error = create_signal(ctx, String::new());
+
+	let submit_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let message = entered_message.get().trim().to_string();
+		if message.is_empty() {
+			entered_message_error.set(String::from("Enter a message to broadcast."));
+			return;
+		}
+		if message.chars().count() > MAX_BROADCAST_LENGTH {
+			entered_message_error.set(format!(
+				"Broadcasts can't be longer than {} characters.",
+				MAX_BROADCAST_LENGTH
+			));
+			return;
+		}
+		entered_message_error.set(String::new());
+		entered_message.set(String::new());
+
+		let event = (*props.event.get()).clone();
+		spawn_local_scoped(
+			ctx,
+			send_feedback_update(ctx, event, EventSubscriptionUpdate::SendBroadcast(message)),
+		);
+	};
+
+	view! {
+		ctx,
+		h2 { "Broadcast" }
+		p {
+			"Send a message to everyone currently viewing the event. It's shown on their screen until they dismiss it."
+		}
+		form(id="event_log_broadcast_form", on:submit=submit_handler) {
+			input(
+				bind:value=entered_message,
+				placeholder="Switch to backup stream timestamps now",
+				maxlength=MAX_BROADCAST_LENGTH,
+				class=if entered_message_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_message_error.get()).clone()
+			)
+			button(type="submit") { "Send Broadcast" }
+		}
+	}
+}
+
+#[derive(Prop)]
+pub struct EventLogFeedbackProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogFeedbackLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogFeedbackProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Feedback] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Feedback", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	if *event_subscription_data.permission.get() != PermissionLevel::Supervisor {
+		return view! {
+			ctx,
+			p(id="event_log_feedback_not_allowed") { "Only supervisors can see feedback for this event." }
+		};
+	}
+
+	// Newest feedback is shown first
+	let feedback = create_memo(ctx, {
+		let feedback_signal = event_subscription_data.feedback.clone();
+		move || {
+			let mut feedback = (*feedback_signal.get()).clone();
+			feedback.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+			feedback
+		}
+	});
+	let event_signal = event_subscription_data.event.clone();
+	let broadcast_event_signal = event_subscription_data.event.clone();
+
+	view! {
+		ctx,
+		h1 { "Feedback" }
+		p {
+			"Editors can send feedback about the event from the event log. Feedback doesn't show who sent it."
+		}
+		(if feedback.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_feedback_empty") { "There's no feedback to review." }
+			}
+		} else {
+			let event_signal = event_signal.clone();
+			view! {
+				ctx,
+				table(id="event_log_feedback") {
+					tr {
+						th { "Sent At" }
+						th { "Feedback" }
+						th { }
+					}
+					Keyed(
+						iterable=feedback,
+						key=|feedback| feedback.id.clone(),
+						view=move |ctx, feedback| {
+							let submitted_at = format!("{}", feedback.submitted_at.format("%Y-%m-%d %H:%M:%S UTC"));
+							let dismiss_handler = {
+								let event_signal = event_signal.clone();
+								let feedback_id = feedback.id.clone();
+								move |_event: WebEvent| {
+									let event = (*event_signal.get()).clone();
+									let update = EventSubscriptionUpdate::DismissFeedback(feedback_id.clone());
+									spawn_local_scoped(ctx, send_feedback_update(ctx, event, update));
+								}
+							};
+
+							view! {
+								ctx,
+								tr {
+									td(class="event_log_feedback_time") { (submitted_at) }
+									td(class="event_log_feedback_message") { (feedback.message) }
+									td {
+										button(type="button", on:click=dismiss_handler) { "Dismiss" }
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+		EventBroadcastForm(event=broadcast_event_signal)
+	}
+}
+
+#[component]
+pub fn EventLogFeedbackView<G: Html>(ctx: Scope<'_>, props: EventLogFeedbackProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading feedback..." }) {
+			EventLogFeedbackLoadedView(id=props.id)
+		}
+	}
+}
@@ -0,0 +1,167 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use stream_log_shared::messages::view_preferences::{
+	EventLogFilters, FilterPreset, FilterPresetUpdate, MAX_FILTER_PRESET_NAME_LENGTH,
+};
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Event as WebEvent, HtmlSelectElement};
+
+#[derive(Prop)]
+pub struct FilterPresetsProps<'a> {
+	/// The filters currently applied to the log
+	current_filters: &'a ReadSignal<EventLogFilters>,
+	/// Set to the filters of a preset when the user picks one
+	selected_filters: &'a Signal<Option<EventLogFilters>>,
+}
+
+/// Lets the user switch the event log between their saved filter presets and save the current filters as a preset
+#[component]
+pub fn FilterPresetsView<'a, G: Html>(ctx: Scope<'a>, props: FilterPresetsProps<'a>) -> View<G> {
+	let data: &DataSignals = use_context(ctx);
+
+	let presets = create_memo(ctx, {
+		let filter_presets = data.filter_presets.clone();
+		move || (*filter_presets.get()).clone()
+	});
+	let active_preset_id = create_memo(ctx, move || {
+		let current_filters = props.current_filters.get();
+		presets
+			.get()
+			.iter()
+			.find(|preset| preset.filters == *current_filters)
+			.map(|preset| preset.id.clone())
+			.unwrap_or_default()
+	});
+
+	let entered_name = create_signal(ctx, String::new());
+	let entered_name_error = create_signal(ctx, String::new());
+
+	let send_update = move |update: FilterPresetUpdate| {
+		let message = FromClientMessage::UpdateFilterPreset(update);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize filter preset update.",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send filter preset update.", error));
+			}
+		});
+	};
+
+	let preset_change_handler = move |event: WebEvent| {
+		let Some(target) = event.target() else {
+			return;
+		};
+		let select_element: HtmlSelectElement = target.unchecked_into();
+		let preset_id = select_element.value();
+		let selected_preset = presets.get().iter().find(|preset| preset.id == preset_id).cloned();
+		if let Some(preset) = selected_preset {
+			entered_name.set(preset.name);
+			props.selected_filters.set(Some(preset.filters));
+		}
+	};
+
+	let save_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let name = entered_name.get().trim().to_string();
+		if name.is_empty() {
+			entered_name_error.set(String::from("Enter a name for the preset."));
+			return;
+		}
+		if name.len() > MAX_FILTER_PRESET_NAME_LENGTH {
+			entered_name_error.set(format!(
+				"Preset names can't be longer than {} characters.",
+				MAX_FILTER_PRESET_NAME_LENGTH
+			));
+			return;
+		}
+		entered_name_error.set(String::new());
+
+		// Saving with the name of an existing preset replaces that preset
+		let id = presets
+			.get()
+			.iter()
+			.find(|preset| preset.name == name)
+			.map(|preset| preset.id.clone())
+			.unwrap_or_default();
+		let preset = FilterPreset {
+			id,
+			name,
+			filters: (*props.current_filters.get()).clone(),
+		};
+		send_update(FilterPresetUpdate::Save(preset));
+	};
+
+	let delete_handler = move |_event: WebEvent| {
+		let preset_id = (*active_preset_id.get()).clone();
+		if !preset_id.is_empty() {
+			send_update(FilterPresetUpdate::Delete(preset_id));
+		}
+	};
+
+	view! {
+		ctx,
+		form(id="event_log_filter_presets", on:submit=save_handler) {
+			select(on:change=preset_change_handler, title="Switch to a saved set of filters") {
+				option(value="", selected=active_preset_id.get().is_empty()) { "No preset" }
+				Keyed(
+					iterable=presets,
+					key=|preset| preset.id.clone(),
+					view=move |ctx, preset| {
+						let is_active = create_memo(ctx, {
+							let preset_id = preset.id.clone();
+							move || *active_preset_id.get() == preset_id
+						});
+						view! {
+							ctx,
+							option(value=preset.id, selected=*is_active.get()) { (preset.name) }
+						}
+					}
+				)
+			}
+			input(
+				bind:value=entered_name,
+				placeholder="Preset name",
+				class=if entered_name_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_name_error.get()).clone()
+			)
+			button(type="submit", title="Save the current filters as a preset") { "Save Filters" }
+			(if active_preset_id.get().is_empty() {
+				view! { ctx, }
+			} else {
+				view! {
+					ctx,
+					button(type="button", on:click=delete_handler) { "Delete Preset" }
+				}
+			})
+		}
+	}
+}
@@ -0,0 +1,263 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Event as WebEvent, FileReader, HtmlInputElement};
+
+#[derive(Prop)]
+pub struct EventLogImportProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogImportLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogImportProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Import] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Import Entries", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	if *event_subscription_data.permission.get() != PermissionLevel::Supervisor {
+		return view! {
+			ctx,
+			p(id="event_log_import_not_allowed") { "Only supervisors can import entries into this event." }
+		};
+	}
+
+	let event_id = create_ref(ctx, props.id);
+	data.event_log_import_result.set(None);
+
+	let file_field_ref = create_node_ref(ctx);
+	let file_error = create_signal(ctx, String::new());
+	let importing = create_signal(ctx, false);
+	// Filled in by the file reader once it has read the chosen file
+	let file_contents: RcSignal<Option<String>> = create_rc_signal(None);
+
+	create_effect(ctx, {
+		let file_contents = file_contents.clone();
+		move || {
+			let Some(contents) = (*file_contents.get()).clone() else {
+				return;
+			};
+			spawn_local_scoped(ctx, async move {
+				let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+				let mut ws = ws_context.lock().await;
+
+				let message = FromClientMessage::ImportEventLog(event_id.clone(), contents);
+				let message_json = match serde_json::to_string(&message) {
+					Ok(msg) => msg,
+					Err(error) => {
+						data.errors.modify().push(ErrorData::new_with_error(
+							"Failed to serialize event log import.",
+							error,
+						));
+						importing.set(false);
+						return;
+					}
+				};
+				if let Err(error) = ws.send(Message::Text(message_json)).await {
+					data.errors
+						.modify()
+						.push(ErrorData::new_with_error("Failed to send event log import.", error));
+					importing.set(false);
+				}
+			});
+		}
+	});
+
+	let import_result = create_memo(ctx, move || {
+		let import_result = data.event_log_import_result.get();
+		(*import_result)
+			.clone()
+			.filter(|import_result| import_result.event_id == *event_id)
+	});
+	create_effect(ctx, move || {
+		if import_result.get().is_some() {
+			importing.set(false);
+		}
+	});
+	// If the server rejects the import outright (for example, if the event was archived), it reports an error instead
+	// of sending a result.
+	create_effect(ctx, move || {
+		data.errors.track();
+		importing.set(false);
+	});
+	let row_errors = create_memo(ctx, move || {
+		(*import_result.get())
+			.as_ref()
+			.map(|import_result| import_result.row_errors.clone())
+			.unwrap_or_default()
+	});
+
+	let import_handler = move |event: WebEvent| {
+		event.prevent_default();
+		if *importing.get() {
+			return;
+		}
+
+		let file_field_node: DomNode = file_field_ref.get();
+		let file_field: HtmlInputElement = file_field_node.unchecked_into();
+		let Some(file) = file_field.files().and_then(|files| files.get(0)) else {
+			file_error.set(String::from("Choose a file to import."));
+			return;
+		};
+		file_error.set(String::new());
+
+		let reader = match FileReader::new() {
+			Ok(reader) => reader,
+			Err(_) => {
+				data.errors
+					.modify()
+					.push(ErrorData::new("Failed to set up reading the import file."));
+				return;
+			}
+		};
+		let load_listener = {
+			let reader = reader.clone();
+			let file_contents = file_contents.clone();
+			Closure::once_into_js(move || {
+				let contents = reader.result().ok().and_then(|result| result.as_string());
+				file_contents.set(contents);
+			})
+		};
+		reader.set_onload(Some(load_listener.unchecked_ref()));
+		if reader.read_as_text(&file).is_err() {
+			data.errors
+				.modify()
+				.push(ErrorData::new("Failed to read the import file."));
+			return;
+		}
+
+		data.event_log_import_result.set(None);
+		importing.set(true);
+	};
+
+	view! {
+		ctx,
+		h1 { "Import Entries" }
+		p {
+			"Adds entries to the log from a CSV file with the same columns as the log export. The file needs a \"Start\" column (a time relative to the start of the event, like 1:23) or a \"Start Time\" column (a full timestamp). These columns are also read if present: End, End Time, Type, Description, Submitter/Winner, Media Links, Tags, Notes, Poster Moment, Missing Giveaway Information, Number, and Parent Number. Other columns are ignored."
+		}
+		p {
+			"Types and tags are matched by name and must already be set up for the event. Child entries must come after their parent, which is matched using the Number column. If any row has a problem, nothing is imported."
+		}
+		form(id="event_log_import_form", on:submit=import_handler) {
+			input(
+				ref=file_field_ref,
+				id="event_log_import_file",
+				type="file",
+				accept=".csv,text/csv",
+				class=if file_error.get().is_empty() { "" } else { "error" },
+				title=(*file_error.get()).clone()
+			)
+			button(type="submit", disabled=*importing.get()) { "Import" }
+		}
+		(if *importing.get() {
+			view! {
+				ctx,
+				p(id="event_log_import_progress") { "Importing..." }
+			}
+		} else {
+			match (*import_result.get()).as_ref() {
+				Some(import_result) if import_result.row_errors.is_empty() => {
+					let message = if import_result.imported_count == 1 {
+						String::from("Imported 1 entry.")
+					} else {
+						format!("Imported {} entries.", import_result.imported_count)
+					};
+					view! {
+						ctx,
+						p(id="event_log_import_success") { (message) }
+					}
+				}
+				Some(_) => view! {
+					ctx,
+					p(id="event_log_import_failure") { "Nothing was imported because of these problems with the file:" }
+					table(id="event_log_import_errors") {
+						tr {
+							th { "Row" }
+							th { "Problem" }
+						}
+						Indexed(
+							iterable=row_errors,
+							view=|ctx, row_error| view! {
+								ctx,
+								tr {
+									td(class="event_log_import_error_row") { (row_error.row) }
+									td { (row_error.error) }
+								}
+							}
+						)
+					}
+				},
+				None => view! { ctx, }
+			}
+		})
+	}
+}
+
+#[component]
+pub fn EventLogImportView<G: Html>(ctx: Scope<'_>, props: EventLogImportProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading event data..." }) {
+			EventLogImportLoadedView(id=props.id)
+		}
+	}
+}
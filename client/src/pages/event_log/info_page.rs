@@ -89,16 +89,26 @@ async fn EventLogInfoPageLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogInfo
 		let event_signal = event_subscription_data.event.clone();
 		move || {
 			let event = event_signal.get();
-			let page_title = format!("{} - {} | Stream Log", page_title.get(), event.name);
+			let page_title = format!("{} - {}", page_title.get(), event.name);
 			set_page_title(&page_title);
 		}
 	});
 
 	view! {
 		ctx,
-		h1 { (page_title.get()) }
-		// The markdown parser escapes HTML.
-		div(dangerously_set_inner_html=&page_contents.get())
+		(if info_page.get().is_some() {
+			view! {
+				ctx,
+				h1 { (page_title.get()) }
+				// The markdown parser escapes HTML.
+				div(dangerously_set_inner_html=&page_contents.get())
+			}
+		} else {
+			view! {
+				ctx,
+				p(id="event_log_info_page_unavailable") { "This page doesn't exist, or you don't have access to it." }
+			}
+		})
 	}
 }
 
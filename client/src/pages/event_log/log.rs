@@ -4,12 +4,30 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::color_utils::rgb_str_from_color;
 use crate::components::event_log_entry::edit::EventLogEntryEdit;
+use crate::components::event_log_entry::edit_panes::EditPanes;
 use crate::components::event_log_entry::entry::EventLogEntry as EventLogEntryView;
 use crate::components::event_log_entry::typing::EventLogEntryTyping;
+use crate::components::event_log_entry::utils::entry_matches_filters;
 use crate::components::event_log_entry::UserTypingData;
-use crate::page_utils::set_page_title;
+use crate::components::guided_tour::GuidedTour;
+use crate::components::user_avatar::UserAvatar;
+use crate::config::client_config;
+use crate::event_cache::{cache_event, load_cached_event};
+use crate::page_utils::{application_url, set_page_title};
+use crate::pages::event_log::access_request::EventAccessRequestView;
+use crate::pages::event_log::feedback::EventFeedbackForm;
+use crate::pages::event_log::filter_presets::FilterPresetsView;
+use crate::pages::event_log::tab_export::{download_file, entries_csv, entries_printable_html, export_file_name};
+use crate::pages::event_log::view_state::EventLogViewState;
+use crate::pending_edits::{
+	add_pending_edit, entry_part_name, pending_edit_count, pending_edits, remove_applied_pending_edits,
+	remove_pending_edit, PendingEntryEdit,
+};
+use crate::subscriptions::connection::ConnectionState;
 use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::event::EventSubscriptionSignals;
 use crate::subscriptions::manager::SubscriptionManager;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
@@ -18,34 +36,80 @@ use futures::future::poll_fn;
 use futures::lock::Mutex;
 use futures::task::{Context, Poll, Waker};
 use gloo_net::websocket::Message;
-use std::collections::HashMap;
-use stream_log_shared::messages::event_log::{EventLogEntry, EventLogTab, VideoEditState, VideoProcessingState};
+use gloo_timers::callback::Interval;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::event_log::{
+	EndTimeData, EventLogEntry, EventLogTab, VideoEditState, VideoProcessingState,
+};
+use stream_log_shared::messages::event_subscription::{
+	entry_parts_match, EventSubscriptionUpdate, ModifiedEventLogEntryParts,
+};
 use stream_log_shared::messages::permissions::PermissionLevel;
-use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::FromClientMessage;
-use sycamore::futures::spawn_local_scoped;
+use stream_log_shared::messages::view_preferences::EventLogFilters;
+use stream_log_shared::messages::{FromClientMessage, RequestFailure};
+use sycamore::futures::{spawn_local, spawn_local_scoped};
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
 use sycamore_router::navigate;
-use web_sys::{window, Event as WebEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
-
-fn add_entries_for_parent(
-	entries_by_parent: &HashMap<String, Vec<EventLogEntry>>,
-	entry_numbers: &mut HashMap<String, usize>,
-	current_number: &mut usize,
-	parent: &str,
-) {
-	let Some(entries) = entries_by_parent.get(parent) else {
-		return;
-	};
-	for entry in entries.iter() {
-		*current_number += 1;
-		entry_numbers.insert(entry.id.clone(), *current_number);
-		add_entries_for_parent(entries_by_parent, entry_numbers, current_number, &entry.id);
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+	window, Element, Event as WebEvent, HtmlElement, MouseEvent, ScrollIntoViewOptions, ScrollLogicalPosition,
+};
+
+/// Tabs with at least this many top-level entries only render the entries near the visible part of the log. Smaller
+/// tabs render every entry.
+const VIRTUALIZED_ENTRY_THRESHOLD: usize = 150;
+/// The height, in pixels, assumed for each entry row until rendered rows are measured
+const DEFAULT_ROW_HEIGHT_PX: f64 = 28.0;
+/// How many entries are rendered past each edge of the visible part of the log, so they're ready before they're
+/// scrolled into view
+const RENDERED_ENTRY_MARGIN: usize = 20;
+
+/// How often locks on the entries being edited are renewed, in milliseconds. This is well under the time the server
+/// keeps a lock without it being renewed, so locks don't lapse while entries are still open.
+const ENTRY_LOCK_RENEW_INTERVAL_MS: u32 = 100_000;
+
+/// How long to wait between sending stored entry changes, in milliseconds. This keeps a large backlog of changes under
+/// the server's message rate limit.
+const PENDING_EDIT_REPLAY_INTERVAL_MS: u32 = 200;
+
+/// Counts of the entries in a tab of the event log
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+struct TabEntryCounts {
+	entries: usize,
+	/// The number of entries for which the end time has been filled in
+	completed_entries: usize,
+}
+
+impl TabEntryCounts {
+	fn completion_percent(&self) -> usize {
+		if self.entries == 0 {
+			100
+		} else {
+			self.completed_entries * 100 / self.entries
+		}
+	}
+
+	fn is_complete(&self) -> bool {
+		self.completed_entries == self.entries
 	}
 }
 
+/// Gets the ID of the top-level entry that the entry with the given ID is under. For top-level entries, this is the
+/// entry's own ID.
+fn top_level_entry_id(entries: &[EventLogEntry], entry_id: &str) -> Option<String> {
+	let mut entry = entries.iter().find(|entry| entry.id == entry_id)?;
+	while let Some(parent_id) = entry.parent.as_ref() {
+		entry = entries.iter().find(|parent_entry| parent_entry.id == *parent_id)?;
+	}
+	Some(entry.id.clone())
+}
+
 #[derive(Prop)]
 pub struct EventLogProps {
 	id: String,
@@ -60,6 +124,7 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 	log::debug!("Got websocket to load event {}", props.id);
 
 	let data: &DataSignals = use_context(ctx);
+	data.denied_events.modify().remove(&props.id);
 
 	let add_subscription_data = {
 		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
@@ -76,13 +141,39 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 	}
 	log::debug!("Added subscription data for event {}", props.id);
 
+	let user_id = {
+		let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+		let user = user_signal.get_untracked();
+		(*user).as_ref().map(|user| user.id.clone())
+	};
+
+	// If we have the event's data from the last time it was opened, we show that while the server sends the current
+	// data, which replaces it once it arrives.
+	if let Some(user_id) = user_id.as_ref() {
+		if !data.events.get_untracked().contains_key(&props.id) {
+			if let Some(cached_data) = load_cached_event(user_id, &props.id).await {
+				// The server's data may have arrived while the cache was being read, in which case it's already current
+				let event_loaded = data.events.get_untracked().contains_key(&props.id);
+				if !event_loaded && !data.denied_events.get_untracked().contains(&props.id) {
+					log::debug!("Showing cached data for event {}", props.id);
+					data.events
+						.modify()
+						.insert(props.id.clone(), EventSubscriptionSignals::new(cached_data));
+				}
+			}
+		}
+	}
+
 	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
 		log::debug!(
 			"Checking whether event {} is present yet in the subscription manager",
 			props.id
 		);
+		if data.denied_events.get().contains(&props.id) {
+			return Poll::Ready(None);
+		}
 		match data.events.get().get(&props.id) {
-			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			Some(event_subscription_data) => Poll::Ready(Some(event_subscription_data.clone())),
 			None => {
 				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
 				event_wakers
@@ -95,12 +186,16 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 		}
 	})
 	.await;
+	let Some(event_subscription_data) = event_subscription_data else {
+		drop(ws);
+		return view! { ctx, EventAccessRequestView(event_id=props.id) };
+	};
 
 	create_effect(ctx, {
 		let event_signal = event_subscription_data.event.clone();
 		move || {
 			let event = event_signal.get();
-			let page_title = format!("{} - Log | Stream Log", event.name);
+			let page_title = format!("{} - Log", event.name);
 			set_page_title(&page_title);
 		}
 	});
@@ -120,20 +215,18 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 		}
 	});
 
-	let entry_numbers_signal = create_memo(ctx, || {
-		let entries_by_parent = entries_by_parent_signal.get();
-		let mut current_number: usize = 0;
-		let mut entry_numbers: HashMap<String, usize> = HashMap::new();
-
-		add_entries_for_parent(&entries_by_parent, &mut entry_numbers, &mut current_number, "");
-
-		entry_numbers
-	});
-
 	let read_event_tabs_signal = create_memo(ctx, {
 		let event_log_tabs = event_subscription_data.event_log_tabs.clone();
 		move || (*event_log_tabs.get()).clone()
 	});
+	let read_entry_templates_signal = create_memo(ctx, {
+		let entry_templates = event_subscription_data.entry_templates.clone();
+		move || (*entry_templates.get()).clone()
+	});
+	let read_custom_fields_signal = create_memo(ctx, {
+		let custom_fields = event_subscription_data.custom_fields.clone();
+		move || (*custom_fields.get()).clone()
+	});
 
 	let event_signal = event_subscription_data.event.clone();
 	let permission_signal = event_subscription_data.permission.clone();
@@ -183,31 +276,64 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 			let editors = available_editors.get();
 
 			match (*user).as_ref() {
-				Some(user) => {
-					*permission == PermissionLevel::Supervisor || editors.iter().any(|editor| editor.id == user.id)
-				}
-				None => *permission == PermissionLevel::Supervisor,
+				Some(user) => permission.can_see_editor_view() || editors.iter().any(|editor| editor.id == user.id),
+				None => permission.can_see_editor_view(),
 			}
 		}
 	});
 
-	let editing_log_entry: &Signal<Option<EventLogEntry>> = create_signal(ctx, None);
+	let edit_panes = create_ref(ctx, EditPanes::new());
+	let quick_edit = create_signal(ctx, false);
+	let dragged_entry: &Signal<Option<EventLogEntry>> = create_signal(ctx, None);
 
 	let video_processing_state_filters = event_subscription_data.video_processing_state_filters.clone();
 	let video_edit_state_filters = event_subscription_data.video_edit_state_filters.clone();
 
-	let current_time = Utc::now();
-	let mut current_tab: Option<&EventLogTab> = None;
+	// A view state in the URL (from a link or from going back) takes precedence over how the log was last viewed
+	let url_view_state = EventLogViewState::from_current_url();
+	if let Some(view_state) = url_view_state.as_ref() {
+		let filters = &view_state.filters;
+		video_edit_state_filters.set(filters.video_edit_states.clone());
+		video_processing_state_filters.set(filters.video_processing_states.clone());
+		event_subscription_data.created_by_me_filter.set(filters.created_by_me);
+		event_subscription_data
+			.assigned_to_me_filter
+			.set(filters.assigned_to_me);
+		event_subscription_data
+			.over_expected_duration_filter
+			.set(filters.over_expected_duration);
+		event_subscription_data
+			.missing_giveaway_information_filter
+			.set(filters.missing_giveaway_information);
+	}
+
 	let event_log_tabs = event_subscription_data.event_log_tabs.get();
-	for next_tab in event_log_tabs.iter() {
-		if next_tab.start_time <= current_time {
-			current_tab = Some(next_tab);
+	let url_tab = url_view_state.as_ref().and_then(|view_state| {
+		if view_state.tab_id.is_empty() {
+			Some(None)
 		} else {
-			break;
+			event_log_tabs
+				.iter()
+				.find(|tab| tab.id == view_state.tab_id)
+				.map(|tab| Some(tab.clone()))
 		}
-	}
-	let current_tab = current_tab.cloned();
-	let selected_tab = create_signal(ctx, current_tab);
+	});
+	let initial_tab = match url_tab {
+		Some(tab) => tab,
+		None => {
+			let current_time = Utc::now();
+			let mut current_tab: Option<&EventLogTab> = None;
+			for next_tab in event_log_tabs.iter() {
+				if next_tab.start_time <= current_time {
+					current_tab = Some(next_tab);
+				} else {
+					break;
+				}
+			}
+			current_tab.cloned()
+		}
+	};
+	let selected_tab = create_signal(ctx, initial_tab);
 
 	let log_entries_by_tab = create_memo(ctx, {
 		let event_log_tabs = event_subscription_data.event_log_tabs.clone();
@@ -285,6 +411,216 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 		top_level_entries
 	});
 
+	// Rendering thousands of rows makes every update to the log slow, so only the entries near the visible part of
+	// large tabs are rendered. The space of the others is filled in based on the average height of the rendered
+	// rows. Top-level entries are shown along with all of their children, so the space is based on how many rows each
+	// entry takes up.
+	let event_log_ref = create_node_ref(ctx);
+	let top_spacer_ref = create_node_ref(ctx);
+	let bottom_spacer_ref = create_node_ref(ctx);
+	let log_scroll_top = create_signal(ctx, 0.0);
+	let log_viewport_height = create_signal(
+		ctx,
+		window()
+			.and_then(|window| window.inner_height().ok())
+			.and_then(|height| height.as_f64())
+			.unwrap_or(1000.0),
+	);
+	let row_height = create_signal(ctx, DEFAULT_ROW_HEIGHT_PX);
+	// The number of rows before each top-level entry in the tab, followed by the total number of rows
+	let active_entry_row_offsets = create_memo(ctx, move || {
+		let selected_tab = selected_tab.get();
+		let tab_id = (*selected_tab).as_ref().map(|tab| tab.id.as_str()).unwrap_or("");
+		let entries_by_tab = log_entries_by_tab.get();
+		let mut row_offsets: Vec<usize> = Vec::new();
+		let mut row_count = 0;
+		for entry in entries_by_tab.get(tab_id).into_iter().flatten() {
+			if entry.parent.is_none() {
+				row_offsets.push(row_count);
+			}
+			row_count += 1;
+		}
+		row_offsets.push(row_count);
+		row_offsets
+	});
+	let rows_before_entry = create_ref(ctx, move |entry_index: usize| -> usize {
+		let row_offsets = active_entry_row_offsets.get_untracked();
+		row_offsets
+			.get(entry_index)
+			.or(row_offsets.last())
+			.copied()
+			.unwrap_or(0)
+	});
+	// Printing shows the whole log, so every entry is rendered while the page is being printed
+	let printing: RcSignal<bool> = create_rc_signal(false);
+	let rendered_entry_range = create_memo(ctx, {
+		let printing = printing.clone();
+		move || {
+			let entry_count = active_log_entries.get().len();
+			if entry_count < VIRTUALIZED_ENTRY_THRESHOLD || *printing.get() {
+				return (0, entry_count);
+			}
+			let row_offsets = active_entry_row_offsets.get();
+			let row_height = *row_height.get();
+			let scroll_top = *log_scroll_top.get();
+			let first_visible_row = (scroll_top / row_height) as usize;
+			let last_visible_row = ((scroll_top + *log_viewport_height.get()) / row_height).ceil() as usize;
+			let first_visible_entry = row_offsets
+				.partition_point(|offset| *offset <= first_visible_row)
+				.saturating_sub(1);
+			let visible_entries_end = row_offsets.partition_point(|offset| *offset < last_visible_row);
+			let start = first_visible_entry
+				.saturating_sub(RENDERED_ENTRY_MARGIN)
+				.min(entry_count);
+			let end = (visible_entries_end + RENDERED_ENTRY_MARGIN).min(entry_count);
+			(start, end)
+		}
+	});
+	let rendered_log_entries = create_memo(ctx, || {
+		let (start, end) = *rendered_entry_range.get();
+		active_log_entries.get()[start..end].to_vec()
+	});
+	let top_spacer_style = create_memo(ctx, || {
+		let (start, _) = *rendered_entry_range.get();
+		active_entry_row_offsets.track();
+		format!("height: {}px", rows_before_entry(start) as f64 * *row_height.get())
+	});
+	let bottom_spacer_style = create_memo(ctx, || {
+		let (_, end) = *rendered_entry_range.get();
+		let row_offsets = active_entry_row_offsets.get();
+		let total_row_count = row_offsets.last().copied().unwrap_or(0);
+		let unrendered_row_count = total_row_count - rows_before_entry(end);
+		format!("height: {}px", unrendered_row_count as f64 * *row_height.get())
+	});
+	let update_log_scroll = move || {
+		let Some(event_log) = event_log_ref.try_get::<DomNode>() else {
+			return;
+		};
+		let event_log: Element = event_log.unchecked_into();
+		log_viewport_height.set(event_log.client_height() as f64);
+
+		// The rendered rows are measured before the scroll position is updated, since that can change which entries are
+		// rendered
+		let (start, end) = *rendered_entry_range.get_untracked();
+		let rendered_row_count = rows_before_entry(end).saturating_sub(rows_before_entry(start));
+		if let (true, Some(top_spacer), Some(bottom_spacer)) = (
+			rendered_row_count > 0,
+			top_spacer_ref.try_get::<DomNode>(),
+			bottom_spacer_ref.try_get::<DomNode>(),
+		) {
+			let top_spacer: HtmlElement = top_spacer.unchecked_into();
+			let bottom_spacer: HtmlElement = bottom_spacer.unchecked_into();
+			let rendered_height =
+				(bottom_spacer.offset_top() - (top_spacer.offset_top() + top_spacer.offset_height())) as f64;
+			let measured_row_height = rendered_height / rendered_row_count as f64;
+			// Small differences are ignored so that the spacers don't keep changing size while scrolling
+			if measured_row_height > 0.0 && (measured_row_height - *row_height.get_untracked()).abs() >= 1.0 {
+				row_height.set(measured_row_height);
+			}
+		}
+
+		log_scroll_top.set(event_log.scroll_top() as f64);
+	};
+	let log_scroll_handler = move |_event: WebEvent| update_log_scroll();
+
+	if let Some(browser_window) = window() {
+		let before_print_listener = Closure::<dyn Fn(WebEvent)>::new({
+			let printing = printing.clone();
+			move |_event: WebEvent| printing.set(true)
+		});
+		let after_print_listener = Closure::<dyn Fn(WebEvent)>::new(move |_event: WebEvent| printing.set(false));
+		let _ = browser_window
+			.add_event_listener_with_callback("beforeprint", before_print_listener.as_ref().unchecked_ref());
+		let _ = browser_window
+			.add_event_listener_with_callback("afterprint", after_print_listener.as_ref().unchecked_ref());
+		on_cleanup(ctx, move || {
+			if let Some(window) = window() {
+				let _ = window
+					.remove_event_listener_with_callback("beforeprint", before_print_listener.as_ref().unchecked_ref());
+				let _ = window
+					.remove_event_listener_with_callback("afterprint", after_print_listener.as_ref().unchecked_ref());
+			}
+		});
+	}
+
+	// Each tab keeps its own scroll position, so switching back to a tab shows the same entries as before
+	let tab_scroll_positions: &Signal<HashMap<String, f64>> = create_signal(ctx, HashMap::new());
+	let shown_tab_id = create_signal(ctx, String::new());
+	create_effect(ctx, move || {
+		let selected_tab = selected_tab.get();
+		let tab_id = (*selected_tab).as_ref().map(|tab| tab.id.clone()).unwrap_or_default();
+		let previous_tab_id = (*shown_tab_id.get_untracked()).clone();
+		if tab_id == previous_tab_id {
+			return;
+		}
+		tab_scroll_positions
+			.modify()
+			.insert(previous_tab_id, *log_scroll_top.get_untracked());
+		let scroll_top = tab_scroll_positions
+			.get_untracked()
+			.get(&tab_id)
+			.copied()
+			.unwrap_or(0.0);
+		shown_tab_id.set(tab_id);
+
+		// The new tab's entries are rendered after this runs, so the position is restored once they're there
+		spawn_local_scoped(ctx, async move {
+			let Some(event_log) = event_log_ref.try_get::<DomNode>() else {
+				return;
+			};
+			let event_log: Element = event_log.unchecked_into();
+			event_log.set_scroll_top(scroll_top as i32);
+			update_log_scroll();
+		});
+	});
+
+	// Events with many entries only have their most recent tabs loaded at first, so the entries of other tabs are
+	// requested when those tabs are viewed
+	let selected_tab_loading = create_memo(ctx, {
+		let unloaded_tab_ids = event_subscription_data.unloaded_tab_ids.clone();
+		move || {
+			let selected_tab = selected_tab.get();
+			let tab_id = (*selected_tab).as_ref().map(|tab| tab.id.as_str()).unwrap_or("");
+			unloaded_tab_ids.get().contains(tab_id)
+		}
+	});
+	create_effect(ctx, {
+		let unloaded_tab_ids = event_subscription_data.unloaded_tab_ids.clone();
+		let requested_tab_ids = event_subscription_data.requested_tab_ids.clone();
+		let event_id = props.id.clone();
+		move || {
+			let selected_tab = selected_tab.get();
+			let tab_id = (*selected_tab).as_ref().map(|tab| tab.id.clone()).unwrap_or_default();
+			if !unloaded_tab_ids.get().contains(&tab_id) || requested_tab_ids.get_untracked().contains(&tab_id) {
+				return;
+			}
+			requested_tab_ids.modify().insert(tab_id.clone());
+
+			let message = FromClientMessage::RequestTabEntries(event_id.clone(), tab_id);
+			spawn_local_scoped(ctx, async move {
+				let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+				let mut ws = ws_context.lock().await;
+				let data: &DataSignals = use_context(ctx);
+
+				let message_json = match serde_json::to_string(&message) {
+					Ok(msg) => msg,
+					Err(error) => {
+						data.errors.modify().push(ErrorData::new_with_error(
+							"Failed to serialize tab entries request.",
+							error,
+						));
+						return;
+					}
+				};
+				if let Err(error) = ws.send(Message::Text(message_json)).await {
+					data.errors
+						.modify()
+						.push(ErrorData::new_with_error("Failed to send tab entries request.", error));
+				}
+			});
+		}
+	});
+
 	let tabs_by_entry_id = create_memo(ctx, move || {
 		let entries_by_tab = log_entries_by_tab.get();
 		let mut tabs_by_entry_id: HashMap<String, String> = HashMap::new();
@@ -296,87 +632,176 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 		tabs_by_entry_id
 	});
 
-	let can_edit = create_memo(ctx, move || permission_signal.get().can_edit());
+	let tab_entry_counts = create_memo(ctx, move || {
+		let entries_by_tab = log_entries_by_tab.get();
+		let mut counts_by_tab: HashMap<String, TabEntryCounts> = HashMap::new();
+		for (tab_id, entries) in entries_by_tab.iter() {
+			let completed_entries = entries
+				.iter()
+				.filter(|entry| entry.end_time != EndTimeData::NotEntered)
+				.count();
+			let counts = TabEntryCounts {
+				entries: entries.len(),
+				completed_entries,
+			};
+			counts_by_tab.insert(tab_id.clone(), counts);
+		}
+		counts_by_tab
+	});
 
-	log::debug!("Set up loaded data signals for event {}", props.id);
+	// Archived events can still be viewed, but the server rejects any changes to them
+	let can_edit = create_memo(ctx, || {
+		read_permission_signal.get().can_edit() && !read_event_signal.get().archived
+	});
 
-	let editing_entry_parent: &Signal<Option<EventLogEntry>> = create_signal(ctx, None);
-	create_effect(ctx, || {
-		let editing_entry = editing_log_entry.get();
-		let parent_entry = editing_entry_parent.get();
-		if let Some(edit_entry) = editing_entry.as_ref() {
-			if let Some(parent) = parent_entry.as_ref() {
-				if edit_entry.id == parent.id {
-					editing_entry_parent.set(None);
-				}
-			}
-		}
+	let viewers = create_memo(ctx, {
+		let viewers_signal = event_subscription_data.viewers.clone();
+		move || (*viewers_signal.get()).clone()
 	});
 
+	log::debug!("Set up loaded data signals for event {}", props.id);
+
 	create_effect(ctx, {
 		let log_entries = log_entries.clone();
 		move || {
 			let log_entries = log_entries.get();
 			let new_log_entries = new_log_entries.get();
-			let editing_entry = editing_log_entry.get_untracked();
-			let editing_entry_id = (*editing_entry).as_ref().map(|entry| entry.id.clone());
-			if let Some(id) = editing_entry_id {
-				if !log_entries.iter().any(|entry| entry.id == id)
-					&& !new_log_entries.iter().any(|entry| entry.id == id)
-				{
-					editing_log_entry.set(None);
-				}
-			}
+			let entry_ids: Vec<&str> = log_entries
+				.iter()
+				.chain(new_log_entries.iter())
+				.map(|entry| entry.id.as_str())
+				.collect();
+			edit_panes.close_missing(&entry_ids);
 		}
 	});
 
-	let editing_typing_data = create_memo(ctx, {
-		let typing_events = event_subscription_data.typing_events.clone();
+	let edit_pane_entry_ids = create_memo(ctx, || {
+		let entry_ids: Vec<String> = edit_panes
+			.panes
+			.get()
+			.iter()
+			.map(|pane| pane.entry_id.clone())
+			.collect();
+		entry_ids
+	});
+	let edit_pane_typing_events = event_subscription_data.typing_events.clone();
+
+	// Existing entries open for editing are locked so other users can see who's editing them. The locks are released
+	// when the entries are closed, and the server releases them if we disconnect.
+	let send_entry_lock_updates = move |updates: Vec<EventSubscriptionUpdate>| {
+		if updates.is_empty() {
+			return;
+		}
+		let event = (*read_event_signal.get_untracked()).clone();
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+			// Locks are all dropped while disconnected, and the periodic renewal locks the entries again once we're back
+			if !ws.is_connected() {
+				return;
+			}
+
+			for update in updates {
+				let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+					event.clone(),
+					Box::new(update),
+				)));
+				let message_json = match serde_json::to_string(&message) {
+					Ok(msg) => msg,
+					Err(error) => {
+						let data: &DataSignals = use_context(ctx);
+						data.errors.modify().push(ErrorData::new_with_error(
+							"Failed to serialize entry editing status.",
+							error,
+						));
+						continue;
+					}
+				};
+				if let Err(error) = ws.send(Message::Text(message_json)).await {
+					let data: &DataSignals = use_context(ctx);
+					data.errors
+						.modify()
+						.push(ErrorData::new_with_error("Failed to send entry editing status.", error));
+				}
+			}
+		});
+	};
+	let locked_entry_ids: &Signal<HashSet<String>> = create_signal(ctx, HashSet::new());
+	create_effect(ctx, {
+		let log_entries = log_entries.clone();
 		move || {
-			let mut typing_data: HashMap<String, UserTypingData> = HashMap::new();
-			let editing_entry = editing_log_entry.get();
-			let editing_entry_id = (*editing_entry)
-				.as_ref()
-				.map(|entry| entry.id.clone())
-				.unwrap_or_default();
-			for typing_event in typing_events
+			let log_entries = log_entries.get();
+			let open_entry_ids: HashSet<String> = edit_panes
+				.panes
 				.get()
 				.iter()
-				.filter(|typing_event| typing_event.event_log_entry.id == editing_entry_id)
-			{
-				let (_, user_typing_data) = typing_data
-					.entry(typing_event.user.id.clone())
-					.or_insert((typing_event.user.clone(), HashMap::new()));
-				user_typing_data.insert(typing_event.target_field, typing_event.data.clone());
+				.filter(|pane| log_entries.iter().any(|entry| entry.id == pane.entry_id))
+				.map(|pane| pane.entry_id.clone())
+				.collect();
+			let previous_entry_ids = locked_entry_ids.get_untracked();
+			if *previous_entry_ids == open_entry_ids {
+				return;
 			}
-			typing_data
+
+			let mut updates: Vec<EventSubscriptionUpdate> = open_entry_ids
+				.difference(&previous_entry_ids)
+				.map(|entry_id| EventSubscriptionUpdate::BeginEditingEntry(entry_id.clone()))
+				.collect();
+			updates.extend(
+				previous_entry_ids
+					.difference(&open_entry_ids)
+					.map(|entry_id| EventSubscriptionUpdate::EndEditingEntry(entry_id.clone())),
+			);
+			locked_entry_ids.set(open_entry_ids);
+			send_entry_lock_updates(updates);
 		}
 	});
+	let lock_renew_tick = create_rc_signal(());
+	let lock_renew_interval = Interval::new(ENTRY_LOCK_RENEW_INTERVAL_MS, {
+		let lock_renew_tick = lock_renew_tick.clone();
+		move || lock_renew_tick.set(())
+	});
+	on_cleanup(ctx, move || drop(lock_renew_interval));
+	create_effect(ctx, move || {
+		lock_renew_tick.track();
+		let updates: Vec<EventSubscriptionUpdate> = locked_entry_ids
+			.get_untracked()
+			.iter()
+			.map(|entry_id| EventSubscriptionUpdate::BeginEditingEntry(entry_id.clone()))
+			.collect();
+		send_entry_lock_updates(updates);
+	});
 
 	let all_video_processing_states = VideoProcessingState::all_states();
-	let all_video_processing_state_filters: Vec<(String, &Signal<bool>)> = all_video_processing_states
-		.into_iter()
-		.map(|processing_state| {
-			let state_name = format!("{}", processing_state);
-			let active_signal = create_signal(ctx, video_processing_state_filters.get().contains(&processing_state));
+	let video_processing_state_filter_signals: Vec<(VideoProcessingState, String, &Signal<bool>)> =
+		all_video_processing_states
+			.into_iter()
+			.map(|processing_state| {
+				let state_name = format!("{}", processing_state);
+				let active_signal =
+					create_signal(ctx, video_processing_state_filters.get().contains(&processing_state));
 
-			create_effect(ctx, {
-				let video_processing_state_filters = video_processing_state_filters.clone();
-				move || {
-					if *active_signal.get() {
-						video_processing_state_filters.modify().insert(processing_state);
-					} else {
-						video_processing_state_filters.modify().remove(&processing_state);
+				create_effect(ctx, {
+					let video_processing_state_filters = video_processing_state_filters.clone();
+					move || {
+						if *active_signal.get() {
+							video_processing_state_filters.modify().insert(processing_state);
+						} else {
+							video_processing_state_filters.modify().remove(&processing_state);
+						}
 					}
-				}
-			});
+				});
 
-			(state_name, active_signal)
-		})
+				(processing_state, state_name, active_signal)
+			})
+			.collect();
+	let all_video_processing_state_filters: Vec<(String, &Signal<bool>)> = video_processing_state_filter_signals
+		.iter()
+		.map(|(_, state_name, active_signal)| (state_name.clone(), *active_signal))
 		.collect();
 	let all_video_processing_state_filters = create_signal(ctx, all_video_processing_state_filters);
 
-	let all_video_edit_state_filters: Vec<(&str, &Signal<bool>)> = VideoEditState::all_states()
+	let video_edit_state_filter_signals: Vec<(VideoEditState, &str, &Signal<bool>)> = VideoEditState::all_states()
 		.into_iter()
 		.map(|edit_state| {
 			let state_name = match edit_state {
@@ -397,15 +822,144 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 				}
 			});
 
-			(state_name, active_signal)
+			(edit_state, state_name, active_signal)
 		})
 		.collect();
+	let all_video_edit_state_filters: Vec<(&str, &Signal<bool>)> = video_edit_state_filter_signals
+		.iter()
+		.map(|(_, state_name, active_signal)| (*state_name, *active_signal))
+		.collect();
 	let all_video_edit_state_filters = create_signal(ctx, all_video_edit_state_filters);
 
+	let created_by_me_filter = create_signal(ctx, *event_subscription_data.created_by_me_filter.get());
+	create_effect(ctx, {
+		let created_by_me_filter_data = event_subscription_data.created_by_me_filter.clone();
+		move || created_by_me_filter_data.set(*created_by_me_filter.get())
+	});
+
+	let over_expected_duration_filter =
+		create_signal(ctx, *event_subscription_data.over_expected_duration_filter.get());
+	create_effect(ctx, {
+		let over_expected_duration_filter_data = event_subscription_data.over_expected_duration_filter.clone();
+		move || over_expected_duration_filter_data.set(*over_expected_duration_filter.get())
+	});
+
+	let assigned_to_me_filter = create_signal(ctx, *event_subscription_data.assigned_to_me_filter.get());
+	create_effect(ctx, {
+		let assigned_to_me_filter_data = event_subscription_data.assigned_to_me_filter.clone();
+		move || assigned_to_me_filter_data.set(*assigned_to_me_filter.get())
+	});
+
+	let missing_giveaway_information_filter =
+		create_signal(ctx, *event_subscription_data.missing_giveaway_information_filter.get());
+	create_effect(ctx, {
+		let missing_giveaway_information_filter_data =
+			event_subscription_data.missing_giveaway_information_filter.clone();
+		move || missing_giveaway_information_filter_data.set(*missing_giveaway_information_filter.get())
+	});
+
+	// The selected tab and filters are kept in the URL. Each change gets a browser history entry so that the back button
+	// can undo it.
+	let current_view_state = create_memo(ctx, {
+		let video_edit_state_filters = video_edit_state_filters.clone();
+		let video_processing_state_filters = video_processing_state_filters.clone();
+		move || EventLogViewState {
+			tab_id: (*selected_tab.get())
+				.as_ref()
+				.map(|tab| tab.id.clone())
+				.unwrap_or_default(),
+			filters: EventLogFilters {
+				video_edit_states: (*video_edit_state_filters.get()).clone(),
+				video_processing_states: (*video_processing_state_filters.get()).clone(),
+				created_by_me: *created_by_me_filter.get(),
+				assigned_to_me: *assigned_to_me_filter.get(),
+				over_expected_duration: *over_expected_duration_filter.get(),
+				missing_giveaway_information: *missing_giveaway_information_filter.get(),
+			},
+		}
+	});
+	let current_filters = create_memo(ctx, move || current_view_state.get().filters.clone());
+	let applying_url_view_state = create_ref(ctx, Cell::new(false));
+	let view_state_saved = create_ref(ctx, Cell::new(false));
+	create_effect(ctx, move || {
+		let view_state = current_view_state.get();
+		if applying_url_view_state.get() {
+			return;
+		}
+		// The view the page opens with replaces the current history entry, so going back from it leaves the log
+		view_state.save_to_url(view_state_saved.get());
+		view_state_saved.set(true);
+	});
+
+	let url_view_state_change: RcSignal<Option<EventLogViewState>> = create_rc_signal(None);
+	let video_edit_state_filter_signals = create_ref(ctx, video_edit_state_filter_signals);
+	let video_processing_state_filter_signals = create_ref(ctx, video_processing_state_filter_signals);
+	let apply_filters = create_ref(ctx, move |filters: &EventLogFilters| {
+		for (edit_state, _, filter_active) in video_edit_state_filter_signals.iter() {
+			filter_active.set(filters.video_edit_states.contains(edit_state));
+		}
+		for (processing_state, _, filter_active) in video_processing_state_filter_signals.iter() {
+			filter_active.set(filters.video_processing_states.contains(processing_state));
+		}
+		created_by_me_filter.set(filters.created_by_me);
+		assigned_to_me_filter.set(filters.assigned_to_me);
+		over_expected_duration_filter.set(filters.over_expected_duration);
+		missing_giveaway_information_filter.set(filters.missing_giveaway_information);
+	});
+	create_effect(ctx, {
+		let url_view_state_change = url_view_state_change.clone();
+		let event_log_tabs = event_subscription_data.event_log_tabs.clone();
+		move || {
+			let Some(view_state) = (*url_view_state_change.get()).clone() else {
+				return;
+			};
+			applying_url_view_state.set(true);
+			let tab = event_log_tabs
+				.get_untracked()
+				.iter()
+				.find(|tab| tab.id == view_state.tab_id)
+				.cloned();
+			selected_tab.set(tab);
+			apply_filters(&view_state.filters);
+			applying_url_view_state.set(false);
+		}
+	});
+
+	// Switching to a filter preset changes the view like any other filter change, so it's added to the browser history
+	let selected_preset_filters: &Signal<Option<EventLogFilters>> = create_signal(ctx, None);
+	create_effect(ctx, move || {
+		if let Some(filters) = (*selected_preset_filters.get()).as_ref() {
+			apply_filters(filters);
+		}
+	});
+
+	if let Some(browser_window) = window() {
+		let page_path = browser_window.location().pathname().unwrap_or_default();
+		let popstate_listener = Closure::<dyn Fn(WebEvent)>::new(move |_event: WebEvent| {
+			// The router handles going back to a different page
+			let current_path = window().and_then(|window| window.location().pathname().ok());
+			if current_path.as_deref() != Some(page_path.as_str()) {
+				return;
+			}
+			url_view_state_change.set(Some(EventLogViewState::from_current_url().unwrap_or_default()));
+		});
+		let add_listener_result =
+			browser_window.add_event_listener_with_callback("popstate", popstate_listener.as_ref().unchecked_ref());
+		if add_listener_result.is_ok() {
+			on_cleanup(ctx, move || {
+				if let Some(window) = window() {
+					let _ = window
+						.remove_event_listener_with_callback("popstate", popstate_listener.as_ref().unchecked_ref());
+				}
+			});
+		}
+	}
+
 	let jump_highlight_row_id = create_signal(ctx, String::new());
 	let jump_id_entry = create_signal(ctx, String::new());
 	let jump_handler = {
 		let event_log_tabs = event_subscription_data.event_log_tabs.clone();
+		let log_entries = log_entries.clone();
 		move |event: WebEvent| {
 			event.prevent_default();
 
@@ -421,23 +975,64 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 			} else if let Some(tab) = event_log_tabs.get().iter().find(|tab| tab.id == *tab_id) {
 				selected_tab.set(Some(tab.clone()));
 			}
-			let jump_to_id = format!("event_log_entry_{}", jump_id);
-			let Some(window) = window() else {
-				return;
-			};
-			let Some(document) = window.document() else {
-				return;
-			};
-			let Some(row_top_element) = document.get_element_by_id(&jump_to_id) else {
-				return;
-			};
-			let scroll_into_view_options = ScrollIntoViewOptions::new();
-			scroll_into_view_options.set_block(ScrollLogicalPosition::Center);
-			row_top_element.scroll_into_view_with_scroll_into_view_options(&scroll_into_view_options);
-			jump_highlight_row_id.set(jump_id);
+
+			// This runs after switching tabs restores the tab's scroll position, so it doesn't undo the jump
+			let log_entries = log_entries.clone();
+			spawn_local_scoped(ctx, async move {
+				// The entry may be too far from the visible part of the log to be rendered, so we scroll near it first
+				let top_level_entry_id = top_level_entry_id(&log_entries.get_untracked(), &jump_id);
+				let entry_index = active_log_entries
+					.get_untracked()
+					.iter()
+					.position(|entry| Some(&entry.id) == top_level_entry_id.as_ref());
+				if let Some(entry_index) = entry_index {
+					let (start, end) = *rendered_entry_range.get_untracked();
+					if entry_index < start || entry_index >= end {
+						if let Some(event_log) = event_log_ref.try_get::<DomNode>() {
+							let event_log: Element = event_log.unchecked_into();
+							let scroll_top = rows_before_entry(entry_index) as f64 * *row_height.get_untracked();
+							event_log.set_scroll_top(scroll_top as i32);
+							log_scroll_top.set(scroll_top);
+						}
+					}
+				}
+
+				let jump_to_id = format!("event_log_entry_{}", jump_id);
+				let Some(window) = window() else {
+					return;
+				};
+				let Some(document) = window.document() else {
+					return;
+				};
+				let Some(row_top_element) = document.get_element_by_id(&jump_to_id) else {
+					return;
+				};
+				let scroll_into_view_options = ScrollIntoViewOptions::new();
+				scroll_into_view_options.set_block(ScrollLogicalPosition::Center);
+				row_top_element.scroll_into_view_with_scroll_into_view_options(&scroll_into_view_options);
+				jump_highlight_row_id.set(jump_id);
+			});
 		}
 	};
 
+	// Entry changes that can't be sent are stored so they can be sent once the connection is back and the event's data
+	// has been reloaded, even if the page is closed in the meantime.
+	let unsent_edit_count = create_signal(ctx, pending_edit_count(&props.id));
+	let store_unsent_edit = create_ref(ctx, {
+		let log_entries = log_entries.clone();
+		let event_id = props.id.clone();
+		move |message: &FromClientMessage| -> bool {
+			let log_entries = log_entries.get_untracked();
+			let Some(mut edit) = PendingEntryEdit::from_message(message) else {
+				return false;
+			};
+			edit.original_entry = log_entries.iter().find(|entry| entry.id == edit.entry.id).cloned();
+			add_pending_edit(edit);
+			unsent_edit_count.set(pending_edit_count(&event_id));
+			true
+		}
+	});
+
 	let save_message_queue: &Signal<Vec<FromClientMessage>> = create_signal(ctx, Vec::new());
 	create_effect(ctx, move || {
 		save_message_queue.track();
@@ -449,6 +1044,10 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 			let mut ws = ws_context.lock().await;
 
 			for message in messages {
+				if !ws.is_connected() && store_unsent_edit(&message) {
+					continue;
+				}
+
 				let message_json = match serde_json::to_string(&message) {
 					Ok(msg) => msg,
 					Err(error) => {
@@ -463,6 +1062,9 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 
 				let send_result = ws.send(Message::Text(message_json)).await;
 				if let Err(error) = send_result {
+					if store_unsent_edit(&message) {
+						continue;
+					}
 					let data: &DataSignals = use_context(ctx);
 					data.errors.modify().push(ErrorData::new_with_error(
 						"Failed to send event log entry update.",
@@ -473,36 +1075,463 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 		});
 	});
 
+	// Stored changes stay stored until the server has applied them, which we see once the entries it sends us have them
+	create_effect(ctx, {
+		let log_entries = log_entries.clone();
+		let event_id = props.id.clone();
+		move || {
+			let log_entries = log_entries.get();
+			if *unsent_edit_count.get_untracked() == 0 {
+				return;
+			}
+			if remove_applied_pending_edits(&event_id, &log_entries) {
+				unsent_edit_count.set(pending_edit_count(&event_id));
+			}
+		}
+	});
+
+	// Once we're connected and have the current entries, stored changes are sent unless someone else changed the same
+	// parts of the entry in the meantime. For those, the user decides which changes to keep. Changes are sent one at a
+	// time so a long time offline doesn't run into the server's rate limit.
+	let edit_conflicts: &Signal<Vec<(PendingEntryEdit, Vec<ModifiedEventLogEntryParts>)>> =
+		create_signal(ctx, Vec::new());
+	let replaying_edits = create_ref(ctx, Cell::new(false));
+	// The stored change most recently sent, until the next one is sent. The server reports failures in the order it
+	// receives changes, so a failure reported in that time is for this change.
+	let replayed_edit: &RefCell<Option<PendingEntryEdit>> = create_ref(ctx, RefCell::new(None));
+	create_effect(ctx, {
+		let loaded_at = event_subscription_data.loaded_at.clone();
+		let log_entries = log_entries.clone();
+		let event_id = props.id.clone();
+		move || {
+			let data: &DataSignals = use_context(ctx);
+			if !matches!(*data.connection_state.get(), ConnectionState::Connected) {
+				return;
+			}
+			let Some(loaded_at) = *loaded_at.get() else {
+				return;
+			};
+			if replaying_edits.get() {
+				return;
+			}
+
+			let edits = pending_edits(&event_id, loaded_at);
+			if edits.is_empty() {
+				return;
+			}
+
+			let log_entries = log_entries.get_untracked();
+			let mut replay_edits: Vec<PendingEntryEdit> = Vec::new();
+			let mut conflicts: Vec<(PendingEntryEdit, Vec<ModifiedEventLogEntryParts>)> = Vec::new();
+			for edit in edits {
+				let conflicting_parts = match log_entries.iter().find(|entry| entry.id == edit.entry.id) {
+					Some(current_entry) => edit.conflicting_parts(current_entry),
+					None => {
+						// The entry is gone, so the server can't apply the change and we'd never see it applied. We
+						// still send it so the server tells the user why it failed.
+						remove_pending_edit(&edit);
+						Vec::new()
+					}
+				};
+				if conflicting_parts.is_empty() {
+					replay_edits.push(edit);
+				} else {
+					remove_pending_edit(&edit);
+					conflicts.push((edit, conflicting_parts));
+				}
+			}
+			unsent_edit_count.set(pending_edit_count(&event_id));
+			if !conflicts.is_empty() {
+				edit_conflicts.modify().extend(conflicts);
+			}
+			if replay_edits.is_empty() {
+				return;
+			}
+
+			replaying_edits.set(true);
+			spawn_local_scoped(ctx, async move {
+				for edit in replay_edits {
+					if !matches!(*data.connection_state.get_untracked(), ConnectionState::Connected) {
+						break;
+					}
+					let message_json = match serde_json::to_string(&edit.message()) {
+						Ok(msg) => msg,
+						Err(error) => {
+							data.errors.modify().push(ErrorData::new_with_error(
+								"Failed to serialize event log entry update.",
+								error,
+							));
+							continue;
+						}
+					};
+
+					*replayed_edit.borrow_mut() = Some(edit.clone());
+					let send_result = {
+						let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+						let mut ws = ws_context.lock().await;
+						ws.send(Message::Text(message_json)).await
+					};
+					// Anything not sent is still stored and is sent once we've reconnected
+					if send_result.is_err() {
+						break;
+					}
+					TimeoutFuture::new(PENDING_EDIT_REPLAY_INTERVAL_MS).await;
+				}
+				*replayed_edit.borrow_mut() = None;
+				replaying_edits.set(false);
+			});
+		}
+	});
+	// A stored change the server rejected would be rejected again, so it's removed rather than sent every time the log
+	// loads. Changes that failed for reasons that may be temporary stay stored to be sent again later.
+	create_effect(ctx, {
+		let event_id = props.id.clone();
+		move || {
+			let data: &DataSignals = use_context(ctx);
+			let failure = data.request_failure.get();
+			let Some(failure) = failure.as_ref() else {
+				return;
+			};
+			let Some(edit) = replayed_edit.borrow_mut().take() else {
+				return;
+			};
+			if matches!(failure, RequestFailure::Temporary | RequestFailure::RateLimited) {
+				return;
+			}
+			remove_pending_edit(&edit);
+			unsent_edit_count.set(pending_edit_count(&event_id));
+		}
+	});
+	// Changes the server didn't save because they conflicted with someone else's are resolved the same way
+	create_effect(ctx, {
+		let entry_update_conflicts = event_subscription_data.entry_update_conflicts.clone();
+		move || {
+			if entry_update_conflicts.get().is_empty() {
+				return;
+			}
+			let conflicts = std::mem::take(&mut *entry_update_conflicts.modify());
+			let event = (*read_event_signal.get_untracked()).clone();
+			// A stored change the server reported a conflict for is resolved here, so it isn't sent again
+			for stored_edit in pending_edits(&event.id, Utc::now()) {
+				let is_conflicting_edit = conflicts.iter().any(|conflict| {
+					stored_edit.entry.id == conflict.entry.id
+						&& conflict
+							.conflicting_parts
+							.iter()
+							.all(|part| entry_parts_match(*part, &stored_edit.entry, &conflict.entry))
+				});
+				if is_conflicting_edit {
+					remove_pending_edit(&stored_edit);
+				}
+			}
+			unsent_edit_count.set(pending_edit_count(&event.id));
+			let conflicts = conflicts.into_iter().map(|conflict| {
+				let edit = PendingEntryEdit {
+					event: event.clone(),
+					original_entry: Some(conflict.base_entry),
+					entry: conflict.entry,
+					modified_parts: conflict.conflicting_parts.clone(),
+					queued_at: Utc::now(),
+				};
+				(edit, conflict.conflicting_parts)
+			});
+			edit_conflicts.modify().extend(conflicts);
+		}
+	});
+	let edit_conflict_list = create_memo(ctx, || (*edit_conflicts.get()).clone());
+	let conflict_log_entries = log_entries.clone();
+	let resolve_edit_conflict = move |edit: &PendingEntryEdit| {
+		edit_conflicts.modify().retain(|(conflict_edit, _)| {
+			conflict_edit.entry.id != edit.entry.id || conflict_edit.queued_at != edit.queued_at
+		});
+	};
+
 	let visible_event_signal = event_signal.clone();
 	let typing_event = event_signal.clone();
 	let typing_event_log = log_entries.clone();
+	let feedback_event = event_signal.clone();
 
 	let first_tab_click_handler = |_event: WebEvent| {
 		selected_tab.set(None);
 	};
 
+	let tab_export_menu: &Signal<Option<TabExportMenu>> = create_signal(ctx, None);
+	let tab_context_menu_handler = move |tab: Option<EventLogTab>| {
+		move |event: WebEvent| {
+			event.prevent_default();
+			let Some(mouse_event) = event.dyn_ref::<MouseEvent>() else {
+				return;
+			};
+			tab_export_menu.set(Some(TabExportMenu {
+				tab: tab.clone(),
+				x: mouse_event.client_x(),
+				y: mouse_event.client_y(),
+			}));
+		}
+	};
+	let tab_export_menu_close_handler = |_event: WebEvent| {
+		tab_export_menu.set(None);
+	};
+
+	// Exports include only the entries that pass the current filters, just like what's shown in the log
+	let filtered_tab_entries = create_ref(ctx, {
+		let event_subscription_data = event_subscription_data.clone();
+		move |tab: &Option<EventLogTab>| -> Vec<EventLogEntry> {
+			let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+			let user = user.get();
+			let filters = event_subscription_data.filters();
+			let entry_types = read_entry_types_signal.get();
+			let tab_id = tab.as_ref().map(|tab| tab.id.as_str()).unwrap_or("");
+			log_entries_by_tab
+				.get()
+				.get(tab_id)
+				.map(|entries| {
+					entries
+						.iter()
+						.filter(|entry| entry_matches_filters(entry, &filters, &user, &entry_types))
+						.cloned()
+						.collect()
+				})
+				.unwrap_or_default()
+		}
+	});
+	let tab_export_file_name = move |tab: &Option<EventLogTab>, extension: &str| -> String {
+		let tab_name = match tab {
+			Some(tab) => tab.name.clone(),
+			None => (*first_tab_name_signal.get()).clone(),
+		};
+		export_file_name(&format!("{} - {}", read_event_signal.get().name, tab_name), extension)
+	};
+	let push_download_error = move |error: JsValue| {
+		log::error!("Failed to download tab export: {:?}", error);
+		let data: &DataSignals = use_context(ctx);
+		data.errors
+			.modify()
+			.push(ErrorData::new("Failed to download the tab export."));
+	};
+	let export_tab_csv = move |tab: Option<EventLogTab>| {
+		tab_export_menu.set(None);
+		let entries = filtered_tab_entries(&tab);
+		let csv = entries_csv(
+			read_event_signal.get().start_time,
+			&entries,
+			&read_entry_types_signal.get(),
+		);
+		if let Err(error) = download_file(&tab_export_file_name(&tab, "csv"), "text/csv", &csv) {
+			push_download_error(error);
+		}
+	};
+	let export_tab_html = move |tab: Option<EventLogTab>| {
+		tab_export_menu.set(None);
+		let entries = filtered_tab_entries(&tab);
+		let event = read_event_signal.get();
+		let tab_name = match tab.as_ref() {
+			Some(tab) => tab.name.clone(),
+			None => (*first_tab_name_signal.get()).clone(),
+		};
+		let html = entries_printable_html(
+			&event.name,
+			&tab_name,
+			event.start_time,
+			&entries,
+			&read_entry_types_signal.get(),
+		);
+		if let Err(error) = download_file(&tab_export_file_name(&tab, "html"), "text/html", &html) {
+			push_download_error(error);
+		}
+	};
+	let print_tab = move |tab: Option<EventLogTab>| {
+		tab_export_menu.set(None);
+		selected_tab.set(tab);
+		if let Some(window) = window() {
+			if let Err(error) = window.print() {
+				log::error!("Failed to print tab: {:?}", error);
+			}
+		}
+	};
+
+	// The event's data is cached once it's loaded and again when leaving the page, so that it can be shown right away
+	// the next time the event is opened
+	create_effect(ctx, {
+		let event_subscription_data = event_subscription_data.clone();
+		let user_id = user_id.clone();
+		move || {
+			if event_subscription_data.loaded_at.get().is_none() {
+				return;
+			}
+			let Some(user_id) = user_id.clone() else {
+				return;
+			};
+			let event_subscription_data = event_subscription_data.clone();
+			spawn_local_scoped(ctx, async move {
+				cache_event(&user_id, &event_subscription_data).await;
+			});
+		}
+	});
+	on_cleanup(ctx, {
+		let event_subscription_data = event_subscription_data.clone();
+		move || {
+			if event_subscription_data.loaded_at.get_untracked().is_none() {
+				return;
+			}
+			if let Some(user_id) = user_id {
+				spawn_local(async move {
+					cache_event(&user_id, &event_subscription_data).await;
+				});
+			}
+		}
+	});
+
 	log::debug!("Created signals and handlers for event {}", props.id);
 
 	let new_entries_event_subscription_data = event_subscription_data.clone();
 
-	view! {
+	let export_url = |format: &str| {
+		let url = application_url(&format!(
+			"{}/event/{}/log/export",
+			client_config().api_base_path,
+			props.id
+		));
+		url.set_search(&format!("format={}", format));
+		url.href()
+	};
+	let export_csv_url = export_url("csv");
+	let export_tsv_url = export_url("tsv");
+
+	let log_view = view! {
 		ctx,
 		div(id="event_log_layout") {
+			(if *unsent_edit_count.get() > 0 {
+				view! {
+					ctx,
+					div(id="event_log_unsent_edits") {
+						(unsent_edit_count.get())
+						(if *unsent_edit_count.get() == 1 { " change hasn't" } else { " changes haven't" })
+						" been sent yet. They'll be sent once the connection to the server is restored."
+					}
+				}
+			} else {
+				view! { ctx, }
+			})
+			div(id="event_log_edit_conflicts") {
+				Keyed(
+					iterable=edit_conflict_list,
+					key=|(edit, _)| (edit.entry.id.clone(), edit.queued_at),
+					view=move |ctx, (edit, conflicting_parts)| {
+						let entry_name = match (edit.entry.entry_number, edit.entry.description.is_empty()) {
+							(Some(number), true) => format!("#{}", number),
+							(Some(number), false) => format!("#{} ({})", number, edit.entry.description),
+							(None, _) => format!("\"{}\"", edit.entry.description),
+						};
+						let part_names: Vec<&str> = conflicting_parts.iter().map(|part| entry_part_name(*part)).collect();
+						let part_names = part_names.join(", ");
+
+						let keep_mine_handler = {
+							let edit = edit.clone();
+							let log_entries = conflict_log_entries.clone();
+							move |_event: WebEvent| {
+								// The user chose their changes over the current ones, so the changes are now based on the
+								// current version of the entry
+								let mut resolved_edit = edit.clone();
+								resolved_edit.original_entry = log_entries
+									.get_untracked()
+									.iter()
+									.find(|entry| entry.id == edit.entry.id)
+									.cloned();
+								save_message_queue.modify().push(resolved_edit.message());
+								resolve_edit_conflict(&edit);
+							}
+						};
+						let keep_theirs_handler = move |_event: WebEvent| {
+							resolve_edit_conflict(&edit);
+						};
+
+						view! {
+							ctx,
+							div(class="event_log_edit_conflict") {
+								"Your changes weren't saved because someone else also changed the "
+								(part_names)
+								" of entry "
+								(entry_name)
+								"."
+								button(type="button", on:click=keep_mine_handler) { "Use my changes" }
+								button(type="button", on:click=keep_theirs_handler) { "Discard my changes" }
+							}
+						}
+					}
+				)
+			}
 			div(id="event_log_header") {
 				h1(id="event_log_title") { (visible_event_signal.get().name) }
+				(if read_event_signal.get().archived {
+					view! {
+						ctx,
+						div(id="event_log_archived_notice") { "This event is archived, so its log can't be changed." }
+					}
+				} else {
+					view! { ctx, }
+				})
+				div(id="event_log_viewers", title="Users viewing this event") {
+					Keyed(
+						iterable=viewers,
+						key=|viewer| viewer.clone(),
+						view=|ctx, viewer| {
+							let viewer_style = format!("border-color: {}", rgb_str_from_color(viewer.color));
+							let username = viewer.username.clone();
+							view! {
+								ctx,
+								span(class="event_log_viewer", style=viewer_style) {
+									UserAvatar(user=viewer)
+									(username)
+								}
+							}
+						}
+					)
+				}
 				div(id="event_log_view_search") {
 					form(id="event_log_jump", on:submit=jump_handler) {
 						input(type="text", bind:value=jump_id_entry, placeholder="ID")
 						button(type="submit") { "Jump" }
 					}
 				}
+				FilterPresetsView(current_filters=current_filters, selected_filters=selected_preset_filters)
+				div(id="event_log_export") {
+					"Export: "
+					a(href=export_csv_url, download="") { "CSV" }
+					" "
+					a(href=export_tsv_url, download="") { "TSV" }
+				}
+				(if *can_edit.get() {
+					view! {
+						ctx,
+						div(id="event_log_quick_edit", title="Double-click an end time, description, or video edit state to edit it in place") {
+							label {
+								input(type="checkbox", bind:checked=quick_edit)
+								"Quick edit"
+							}
+						}
+					}
+				} else {
+					view! { ctx, }
+				})
+				(if *can_edit.get() {
+					let feedback_event = feedback_event.clone();
+					view! {
+						ctx,
+						EventFeedbackForm(event=feedback_event)
+					}
+				} else {
+					view! { ctx, }
+				})
 			}
 			div(id="event_log_tabs") {
 				div(
 					class=if selected_tab.get().is_none() { "event_log_tab_active click" } else { "click" },
-					on:click=first_tab_click_handler
+					on:click=first_tab_click_handler,
+					on:contextmenu=tab_context_menu_handler(None)
 				) {
 					(first_tab_name_signal.get())
+					TabEntryCountsView(tab_id=String::new(), counts=tab_entry_counts)
 				}
 				Keyed(
 					iterable=read_event_tabs_signal,
@@ -525,22 +1554,73 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 								} else {
 									"click"
 								}
-							}, on:click=tab_click_handler) {
+							}, on:click=tab_click_handler, on:contextmenu=tab_context_menu_handler(Some(tab.clone()))) {
 								(tab.name)
+								TabEntryCountsView(tab_id=tab.id.clone(), counts=tab_entry_counts)
 							}
 						}
 					}
 				)
 			}
-			div(id="event_log") {
+			(if let Some(menu) = (*tab_export_menu.get()).clone() {
+				let menu_position = format!("left: {}px; top: {}px;", menu.x, menu.y);
+				let csv_tab = menu.tab.clone();
+				let html_tab = menu.tab.clone();
+				let print_tab_value = menu.tab;
+				view! {
+					ctx,
+					ul(id="event_log_tab_menu", style=menu_position, on:mouseleave=tab_export_menu_close_handler) {
+						li(class="click", on:click=move |_event: WebEvent| export_tab_csv(csv_tab.clone())) { "Export CSV" }
+						li(class="click", on:click=move |_event: WebEvent| export_tab_html(html_tab.clone())) { "Export Printable Page" }
+						li(class="click", on:click=move |_event: WebEvent| print_tab(print_tab_value.clone())) { "Print Tab" }
+					}
+				}
+			} else {
+				view! { ctx, }
+			})
+			(if *selected_tab_loading.get() {
+				view! {
+					ctx,
+					p(id="event_log_tab_loading") { "Loading the entries for this tab..." }
+				}
+			} else {
+				view! { ctx, }
+			})
+			div(id="event_log", ref=event_log_ref, on:scroll=log_scroll_handler) {
 				div(id="event_log_data", class=if *use_editor_view.get() { "event_log_data_editor" } else { "" }) {
 					div(class="event_log_header") { }
 					div(class="event_log_header") { }
 					div(class="event_log_header") { "Start" }
-					div(class="event_log_header") { "End" }
+					div(class="event_log_header") {
+						"End"
+						div(class="event_log_column_filter_menu") {
+							img(src="images/filter.png", alt="Filter entry durations", class="event_log_header_filter_icon")
+							ul(class="event_log_column_filter_dropdown") {
+								li {
+									label {
+										input(type="checkbox", bind:checked=over_expected_duration_filter)
+										span { "Longer than expected" }
+									}
+								}
+							}
+						}
+					}
 					div(class="event_log_header") { "Type" }
 					div(class="event_log_header") { "Description" }
-					div(class="event_log_header") { "Submitter/Winner" }
+					div(class="event_log_header") {
+						"Submitter/Winner"
+						div(class="event_log_column_filter_menu") {
+							img(src="images/filter.png", alt="Filter giveaway information", class="event_log_header_filter_icon")
+							ul(class="event_log_column_filter_dropdown") {
+								li {
+									label {
+										input(type="checkbox", bind:checked=missing_giveaway_information_filter)
+										span { "Missing giveaway information" }
+									}
+								}
+							}
+						}
+					}
 					div(class="event_log_header") { "Media link" }
 					div(class="event_log_header") { "Tags" }
 					div(class="event_log_header") { "Poster?" }
@@ -578,7 +1658,34 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 					(if *use_editor_view.get() {
 						view! {
 							ctx,
-							div(class="event_log_header") { "Editor" }
+							div(class="event_log_header") {
+								"Editor"
+								div(class="event_log_column_filter_menu") {
+									img(src="images/filter.png", alt="Filter entry editors", class="event_log_header_filter_icon")
+									ul(class="event_log_column_filter_dropdown") {
+										li {
+											label {
+												input(type="checkbox", bind:checked=assigned_to_me_filter)
+												span { "Assigned to me" }
+											}
+										}
+									}
+								}
+							}
+							div(class="event_log_header") {
+								"Created By"
+								div(class="event_log_column_filter_menu") {
+									img(src="images/filter.png", alt="Filter entry creators", class="event_log_header_filter_icon")
+									ul(class="event_log_column_filter_dropdown") {
+										li {
+											label {
+												input(type="checkbox", bind:checked=created_by_me_filter)
+												span { "Created by me" }
+											}
+										}
+									}
+								}
+							}
 						}
 					} else {
 						view! { ctx, }
@@ -615,8 +1722,9 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 					} else {
 						view! { ctx, }
 					})
+					div(ref=top_spacer_ref, class="event_log_spacer", style=top_spacer_style.get())
 					Keyed(
-						iterable=active_log_entries,
+						iterable=rendered_log_entries,
 						key=|entry| entry.id.clone(),
 						view={
 							let event_subscription_data = event_subscription_data.clone();
@@ -630,18 +1738,20 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 										jump_highlight_row_id=jump_highlight_row_id,
 										event_subscription_data=event_subscription_data,
 										can_edit=can_edit,
-										editing_log_entry=editing_log_entry,
+										edit_panes=edit_panes,
 										read_entry_types_signal=read_entry_types_signal,
-										editing_entry_parent=editing_entry_parent,
 										entries_by_parent=entries_by_parent_signal,
 										child_depth=0,
-										entry_numbers=entry_numbers_signal,
-										use_editor_view=use_editor_view
+										use_editor_view=use_editor_view,
+										quick_edit=quick_edit,
+										save_message_queue=save_message_queue,
+										dragged_entry=dragged_entry
 									)
 								}
 							}
 						}
 					)
+					div(ref=bottom_spacer_ref, class="event_log_spacer", style=bottom_spacer_style.get())
 					Keyed(
 						iterable=read_new_log_entries,
 						key=|entry| entry.id.clone(),
@@ -657,13 +1767,14 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 										jump_highlight_row_id=jump_highlight_row_id,
 										event_subscription_data=event_subscription_data,
 										can_edit=can_edit,
-										editing_log_entry=editing_log_entry,
+										edit_panes=edit_panes,
 										read_entry_types_signal=read_entry_types_signal,
-										editing_entry_parent=editing_entry_parent,
 										entries_by_parent=entries_by_parent_signal,
 										child_depth=0,
-										entry_numbers=entry_numbers_signal,
-										use_editor_view=use_editor_view
+										use_editor_view=use_editor_view,
+										quick_edit=quick_edit,
+										save_message_queue=save_message_queue,
+										dragged_entry=dragged_entry
 									)
 								}
 							}
@@ -677,92 +1788,129 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 				view! {
 					ctx,
 					div(id="event_log_new_entry") {
-						({
-							if editing_typing_data.get().is_empty() {
-								view! { ctx, }
-							} else {
-								let typing_event = typing_event.clone();
-								let typing_event_log = typing_event_log.clone();
-								view! {
-									ctx,
-									div(id="event_log_new_entry_typing", class=if *use_editor_view.get() { "event_log_new_entry_typing_editor" } else { "" }) {
-										div(class="event_log_header") {}
-										div(class="event_log_header") {}
-										div(class="event_log_header") { "Start" }
-										div(class="event_log_header") { "End" }
-										div(class="event_log_header") { "Type" }
-										div(class="event_log_header") { "Description" }
-										div(class="event_log_header") { "Submitter/Winner" }
-										div(class="event_log_header") { "Media link" }
-										div(class="event_log_header") {}
-										div(class="event_log_header") {}
-										div(class="event_log_header") {}
-										(if *use_editor_view.get() {
-											view! {
-												ctx,
-												div(class="event_log_header") {}
-											}
-										} else {
-											view! { ctx, }
-										})
-										div(class="event_log_header") {}
-										(if *use_editor_view.get() {
-											view! {
-												ctx,
-												div(class="event_log_header") {}
+						div(id="event_log_edit_panes") {
+							Keyed(
+								iterable=edit_pane_entry_ids,
+								key=|entry_id| entry_id.clone(),
+								view={
+									let typing_event = typing_event.clone();
+									let typing_event_log = typing_event_log.clone();
+									let typing_events = edit_pane_typing_events.clone();
+									move |ctx, entry_id| {
+										let Some(pane) = edit_panes.pane(&entry_id) else {
+											return view! { ctx, };
+										};
+										let typing_data = create_memo(ctx, {
+											let typing_events = typing_events.clone();
+											move || {
+												let mut typing_data: HashMap<String, UserTypingData> = HashMap::new();
+												for typing_event in typing_events
+													.get()
+													.iter()
+													.filter(|typing_event| typing_event.event_log_entry.id == entry_id)
+												{
+													let (_, user_typing_data) = typing_data
+														.entry(typing_event.user.id.clone())
+														.or_insert((typing_event.user.clone(), HashMap::new()));
+													user_typing_data.insert(typing_event.target_field, typing_event.data.clone());
+												}
+												typing_data
 											}
-										} else {
-											view! { ctx, }
-										})
-										div(class="event_log_header") { "Notes to editor" }
-										(if *use_editor_view.get() {
-											view! {
-												ctx,
-												div(class="event_log_header") {}
-												div(class="event_log_header") {}
+										});
+										let typing_event = typing_event.clone();
+										let typing_event_log = typing_event_log.clone();
+
+										view! {
+											ctx,
+											div(class="event_log_entry_edit_pane") {
+												({
+													if typing_data.get().is_empty() {
+														view! { ctx, }
+													} else {
+														let typing_event = typing_event.clone();
+														let typing_event_log = typing_event_log.clone();
+														view! {
+															ctx,
+															div(class=if *use_editor_view.get() { "event_log_new_entry_typing event_log_new_entry_typing_editor" } else { "event_log_new_entry_typing" }) {
+																div(class="event_log_header") {}
+																div(class="event_log_header") {}
+																div(class="event_log_header") { "Start" }
+																div(class="event_log_header") { "End" }
+																div(class="event_log_header") { "Type" }
+																div(class="event_log_header") { "Description" }
+																div(class="event_log_header") { "Submitter/Winner" }
+																div(class="event_log_header") { "Media link" }
+																div(class="event_log_header") {}
+																div(class="event_log_header") {}
+																div(class="event_log_header") {}
+																(if *use_editor_view.get() {
+																	view! {
+																		ctx,
+																		div(class="event_log_header") {}
+																	}
+																} else {
+																	view! { ctx, }
+																})
+																div(class="event_log_header") {}
+																(if *use_editor_view.get() {
+																	view! {
+																		ctx,
+																		div(class="event_log_header") {}
+																		div(class="event_log_header") {}
+																	}
+																} else {
+																	view! { ctx, }
+																})
+																div(class="event_log_header") { "Notes to editor" }
+																(if *use_editor_view.get() {
+																	view! {
+																		ctx,
+																		div(class="event_log_header") {}
+																		div(class="event_log_header") {}
+																	}
+																} else {
+																	view! { ctx, }
+																})
+																EventLogEntryTyping(
+																	event=typing_event,
+																	event_entry_types=read_entry_types_signal,
+																	event_log=typing_event_log,
+																	typing_data=typing_data,
+																	use_editor_view=use_editor_view
+																)
+															}
+														}
+													}
+												})
+												EventLogEntryEdit(
+													event=read_event_signal,
+													permission_level=read_permission_signal,
+													event_entry_types=read_entry_types_signal,
+													event_tags=read_tags_signal,
+													event_editors=read_available_editors,
+													event_log_tabs=read_event_tabs_signal,
+													entry_templates=read_entry_templates_signal,
+													custom_fields=read_custom_fields_signal,
+													current_tab=selected_tab,
+													event_log_entries=read_log_entries,
+													new_event_log_entries=read_new_log_entries,
+													pane=pane,
+													edit_panes=edit_panes,
+													save_message_queue=save_message_queue
+												)
 											}
-										} else {
-											view! { ctx, }
-										})
-										EventLogEntryTyping(
-											event=typing_event,
-											event_entry_types=read_entry_types_signal,
-											event_log=typing_event_log,
-											typing_data=editing_typing_data,
-											use_editor_view=use_editor_view
-										)
+										}
 									}
 								}
-							}
-						})
-						({
-							if editing_log_entry.get().is_some() {
-								view! {
-									ctx,
-									EventLogEntryEdit(
-										event=read_event_signal,
-										permission_level=read_permission_signal,
-										event_entry_types=read_entry_types_signal,
-										event_tags=read_tags_signal,
-										event_editors=read_available_editors,
-										event_log_tabs=read_event_tabs_signal,
-										current_tab=selected_tab,
-										event_log_entries=read_log_entries,
-										editing_log_entry=editing_log_entry,
-										edit_parent_log_entry=editing_entry_parent,
-										save_message_queue=save_message_queue
-									)
-								}
-							} else {
-								view! { ctx, }
-							}
-						})
+							)
+						}
 					}
 				}
 			} else {
 				view! { ctx, }
 			})
 		}
+		GuidedTour
 		datalist(id="event_entry_types") {
 			Keyed(
 				iterable=read_entry_types_signal,
@@ -802,6 +1950,57 @@ async fn EventLogLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogProps) -> Vi
 				}
 			)
 		}
+	};
+
+	// When we showed cached data for an event the user can no longer access, we find out only after the page is shown
+	let event_id = props.id.clone();
+	let access_denied = create_memo(ctx, {
+		let event_id = event_id.clone();
+		move || data.denied_events.get().contains(&event_id)
+	});
+	view! {
+		ctx,
+		(if *access_denied.get() {
+			let event_id = event_id.clone();
+			view! { ctx, EventAccessRequestView(event_id=event_id) }
+		} else {
+			log_view.clone()
+		})
+	}
+}
+
+/// Where the tab export menu was opened and for which tab
+#[derive(Clone)]
+struct TabExportMenu {
+	/// The tab for which the menu was opened. None for the first tab.
+	tab: Option<EventLogTab>,
+	x: i32,
+	y: i32,
+}
+
+#[derive(Prop)]
+struct TabEntryCountsProps<'a> {
+	tab_id: String,
+	counts: &'a ReadSignal<HashMap<String, TabEntryCounts>>,
+}
+
+#[component]
+fn TabEntryCountsView<'a, G: Html>(ctx: Scope<'a>, props: TabEntryCountsProps<'a>) -> View<G> {
+	let tab_counts = create_memo(ctx, move || {
+		props.counts.get().get(&props.tab_id).copied().unwrap_or_default()
+	});
+
+	view! {
+		ctx,
+		span(class="event_log_tab_entry_count", title="Entries in this tab") {
+			(tab_counts.get().entries)
+		}
+		span(
+			class=if tab_counts.get().is_complete() { "event_log_tab_completion event_log_tab_complete" } else { "event_log_tab_completion" },
+			title="Entries in this tab with end times entered"
+		) {
+			(format!("{}%", tab_counts.get().completion_percent()))
+		}
 	}
 }
 
@@ -4,7 +4,22 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub mod access_request;
+pub mod dead_links;
+pub mod editor_notes;
+pub mod editors;
+pub mod editors_dashboard;
+pub mod entry_templates;
 pub mod entry_types;
+pub mod feedback;
+pub mod filter_presets;
+pub mod import;
 pub mod info_page;
 pub mod log;
+pub mod recurring_entries;
+pub mod stats;
+pub mod tab_export;
+pub mod tabs;
 pub mod tags;
+pub mod time_shift;
+pub mod view_state;
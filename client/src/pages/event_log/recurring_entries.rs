@@ -0,0 +1,382 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::components::event_log_entry::utils::{format_duration, get_duration_from_formatted};
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::event_subscription::EventSubscriptionUpdate;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::recurring_entries::RecurringEntry;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use web_sys::Event as WebEvent;
+
+async fn send_recurring_entry_update(ctx: Scope<'_>, event: Event, update: EventSubscriptionUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+		event,
+		Box::new(update),
+	)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize recurring entry update.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send recurring entry update.",
+			error,
+		));
+	}
+}
+
+#[derive(Prop)]
+pub struct EventLogRecurringEntriesProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogRecurringEntriesLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogRecurringEntriesProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Recurring Entries] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Recurring Entries", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	if *event_subscription_data.permission.get() != PermissionLevel::Supervisor {
+		return view! {
+			ctx,
+			p(id="event_log_recurring_entries_not_allowed") { "Only supervisors can manage recurring entries for this event." }
+		};
+	}
+
+	let event_signal = create_ref(ctx, event_subscription_data.event.clone());
+	let recurring_entries = create_memo(ctx, {
+		let recurring_entries_signal = event_subscription_data.recurring_entries.clone();
+		move || (*recurring_entries_signal.get()).clone()
+	});
+	let entry_types = create_memo(ctx, {
+		let entry_types_signal = event_subscription_data.entry_types.clone();
+		move || {
+			let mut entry_types = (*entry_types_signal.get()).clone();
+			entry_types.sort_by_key(|entry_type| entry_type.name.to_lowercase());
+			entry_types
+		}
+	});
+
+	// The ID of the recurring entry being edited, which is empty when creating a new recurring entry
+	let editing_recurring_entry_id = create_signal(ctx, String::new());
+	let entered_entry_type_id = create_signal(ctx, String::new());
+	let entered_description = create_signal(ctx, String::new());
+	let entered_description_error = create_signal(ctx, String::new());
+	let entered_interval = create_signal(ctx, String::new());
+	let entered_interval_error = create_signal(ctx, String::new());
+	let entered_first_time = create_signal(ctx, String::new());
+	let entered_first_time_error = create_signal(ctx, String::new());
+	let entered_last_time = create_signal(ctx, String::new());
+	let entered_last_time_error = create_signal(ctx, String::new());
+
+	let reset_form = move || {
+		editing_recurring_entry_id.set(String::new());
+		entered_entry_type_id.set(String::new());
+		entered_description.set(String::new());
+		entered_description_error.set(String::new());
+		entered_interval.set(String::new());
+		entered_interval_error.set(String::new());
+		entered_first_time.set(String::new());
+		entered_first_time_error.set(String::new());
+		entered_last_time.set(String::new());
+		entered_last_time_error.set(String::new());
+	};
+
+	let save_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let description = entered_description.get().trim().to_string();
+		if description.is_empty() {
+			entered_description_error.set(String::from("Enter a description for the created entries."));
+			return;
+		}
+		entered_description_error.set(String::new());
+
+		let interval_minutes = match entered_interval.get().trim().parse::<i32>() {
+			Ok(interval) if interval > 0 => interval,
+			_ => {
+				entered_interval_error.set(String::from("The interval must be a whole number of minutes."));
+				return;
+			}
+		};
+		entered_interval_error.set(String::new());
+
+		let event_start = event_signal.get().start_time;
+		let first_time = match get_duration_from_formatted(&entered_first_time.get()) {
+			Ok(duration) => event_start + duration,
+			Err(error) => {
+				entered_first_time_error.set(error);
+				return;
+			}
+		};
+		entered_first_time_error.set(String::new());
+
+		let last_time = match get_duration_from_formatted(&entered_last_time.get()) {
+			Ok(duration) => event_start + duration,
+			Err(error) => {
+				entered_last_time_error.set(error);
+				return;
+			}
+		};
+		if last_time < first_time {
+			entered_last_time_error.set(String::from("The last time can't be before the first time."));
+			return;
+		}
+		entered_last_time_error.set(String::new());
+
+		let entry_type = Some((*entered_entry_type_id.get()).clone()).filter(|entry_type_id| !entry_type_id.is_empty());
+		let recurring_entry = RecurringEntry {
+			id: (*editing_recurring_entry_id.get()).clone(),
+			entry_type,
+			description,
+			interval_minutes,
+			first_time,
+			last_time,
+		};
+		let event = (*event_signal.get()).clone();
+		spawn_local_scoped(
+			ctx,
+			send_recurring_entry_update(
+				ctx,
+				event,
+				EventSubscriptionUpdate::UpdateRecurringEntry(recurring_entry),
+			),
+		);
+		reset_form();
+	};
+
+	let cancel_handler = move |_event: WebEvent| {
+		reset_form();
+	};
+
+	view! {
+		ctx,
+		h1 { "Recurring Entries" }
+		p {
+			"Recurring entries are added to the log automatically at a regular interval between their first and last times. Times are relative to the start of the event."
+		}
+		(if recurring_entries.get().is_empty() {
+			view! {
+				ctx,
+				p(id="event_log_recurring_entries_empty") { "This event doesn't have any recurring entries yet." }
+			}
+		} else {
+			view! {
+				ctx,
+				table(id="event_log_recurring_entries") {
+					tr {
+						th { "Description" }
+						th { "Type" }
+						th { "Interval" }
+						th { "First" }
+						th { "Last" }
+						th { }
+					}
+					Keyed(
+						iterable=recurring_entries,
+						key=|recurring_entry| recurring_entry.id.clone(),
+						view=move |ctx, recurring_entry| {
+							let entry_type_name = recurring_entry
+								.entry_type
+								.as_ref()
+								.and_then(|entry_type_id| {
+									entry_types
+										.get()
+										.iter()
+										.find(|entry_type| entry_type.id == *entry_type_id)
+										.map(|entry_type| entry_type.name.clone())
+								})
+								.unwrap_or_default();
+							let interval = format!("{} min", recurring_entry.interval_minutes);
+							let event_start = event_signal.get().start_time;
+							let first_time = format_duration(&(recurring_entry.first_time - event_start));
+							let last_time = format_duration(&(recurring_entry.last_time - event_start));
+
+							let edit_handler = {
+								let recurring_entry = recurring_entry.clone();
+								let first_time = first_time.clone();
+								let last_time = last_time.clone();
+								move |_event: WebEvent| {
+									editing_recurring_entry_id.set(recurring_entry.id.clone());
+									entered_entry_type_id.set(recurring_entry.entry_type.clone().unwrap_or_default());
+									entered_description.set(recurring_entry.description.clone());
+									entered_description_error.set(String::new());
+									entered_interval.set(recurring_entry.interval_minutes.to_string());
+									entered_interval_error.set(String::new());
+									entered_first_time.set(first_time.clone());
+									entered_first_time_error.set(String::new());
+									entered_last_time.set(last_time.clone());
+									entered_last_time_error.set(String::new());
+								}
+							};
+							let delete_handler = {
+								let recurring_entry = recurring_entry.clone();
+								move |_event: WebEvent| {
+									if *editing_recurring_entry_id.get() == recurring_entry.id {
+										reset_form();
+									}
+									let event = (*event_signal.get()).clone();
+									let update = EventSubscriptionUpdate::DeleteRecurringEntry(recurring_entry.clone());
+									spawn_local_scoped(ctx, send_recurring_entry_update(ctx, event, update));
+								}
+							};
+
+							view! {
+								ctx,
+								tr {
+									td(class="event_log_recurring_entries_description") { (recurring_entry.description) }
+									td { (entry_type_name) }
+									td { (interval) }
+									td { (first_time) }
+									td { (last_time) }
+									td {
+										button(type="button", on:click=edit_handler) { "Edit" }
+										button(type="button", on:click=delete_handler) { "Delete" }
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+		h2 {
+			(if editing_recurring_entry_id.get().is_empty() { "New Recurring Entry" } else { "Edit Recurring Entry" })
+		}
+		form(id="event_log_recurring_entry_form", on:submit=save_handler) {
+			label(for="event_log_recurring_entry_description") { "Description" }
+			input(
+				id="event_log_recurring_entry_description",
+				bind:value=entered_description,
+				placeholder="Sponsor read",
+				class=if entered_description_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_description_error.get()).clone()
+			)
+			label(for="event_log_recurring_entry_type") { "Type" }
+			select(id="event_log_recurring_entry_type", bind:value=entered_entry_type_id) {
+				option(value="") { "No type" }
+				Keyed(
+					iterable=entry_types,
+					key=|entry_type| entry_type.id.clone(),
+					view=|ctx, entry_type| view! { ctx, option(value=entry_type.id) { (entry_type.name) } }
+				)
+			}
+			label(for="event_log_recurring_entry_interval") { "Interval (minutes)" }
+			input(
+				id="event_log_recurring_entry_interval",
+				type="number",
+				min=1,
+				step=1,
+				bind:value=entered_interval,
+				class=if entered_interval_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_interval_error.get()).clone()
+			)
+			label(for="event_log_recurring_entry_first_time") { "First" }
+			input(
+				id="event_log_recurring_entry_first_time",
+				bind:value=entered_first_time,
+				placeholder="0:00:00",
+				class=if entered_first_time_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_first_time_error.get()).clone()
+			)
+			label(for="event_log_recurring_entry_last_time") { "Last" }
+			input(
+				id="event_log_recurring_entry_last_time",
+				bind:value=entered_last_time,
+				placeholder="24:00:00",
+				class=if entered_last_time_error.get().is_empty() { "" } else { "error" },
+				title=(*entered_last_time_error.get()).clone()
+			)
+			div(id="event_log_recurring_entry_actions") {
+				button(type="submit") { "Save" }
+				(if editing_recurring_entry_id.get().is_empty() {
+					view! { ctx, }
+				} else {
+					view! {
+						ctx,
+						button(type="button", on:click=cancel_handler) { "Cancel" }
+					}
+				})
+			}
+		}
+	}
+}
+
+#[component]
+pub fn EventLogRecurringEntriesView<G: Html>(ctx: Scope<'_>, props: EventLogRecurringEntriesProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading recurring entries..." }) {
+			EventLogRecurringEntriesLoadedView(id=props.id)
+		}
+	}
+}
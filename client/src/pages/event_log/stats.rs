@@ -0,0 +1,423 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::color_utils::rgb_str_from_color;
+use crate::config::client_config;
+use crate::page_utils::{application_url, set_page_title};
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use chrono::{Duration, Local, NaiveDateTime, Timelike};
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::{BTreeMap, HashMap};
+use stream_log_shared::messages::event_log::{VideoEditState, VideoProcessingState};
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+
+/// A single bar in one of the statistics charts
+#[derive(Clone, PartialEq)]
+struct StatsBar {
+	label: String,
+	count: usize,
+	color: Option<String>,
+}
+
+#[derive(Prop)]
+pub struct EventLogStatsProps {
+	id: String,
+}
+
+#[component]
+async fn EventLogStatsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogStatsProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_result = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_result {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Stats] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Statistics", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	// The statistics cover the whole event, so we need the entries of every tab, including those the log page hasn't
+	// loaded yet
+	create_effect(ctx, {
+		let unloaded_tab_ids = event_subscription_data.unloaded_tab_ids.clone();
+		let requested_tab_ids = event_subscription_data.requested_tab_ids.clone();
+		let event_id = props.id.clone();
+		move || {
+			let tab_ids: Vec<String> = {
+				let requested_tab_ids = requested_tab_ids.get_untracked();
+				unloaded_tab_ids
+					.get()
+					.iter()
+					.filter(|tab_id| !requested_tab_ids.contains(*tab_id))
+					.cloned()
+					.collect()
+			};
+			if tab_ids.is_empty() {
+				return;
+			}
+			requested_tab_ids.modify().extend(tab_ids.iter().cloned());
+
+			let messages: Vec<FromClientMessage> = tab_ids
+				.into_iter()
+				.map(|tab_id| FromClientMessage::RequestTabEntries(event_id.clone(), tab_id))
+				.collect();
+			spawn_local_scoped(ctx, async move {
+				let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+				let mut ws = ws_context.lock().await;
+				let data: &DataSignals = use_context(ctx);
+
+				for message in messages {
+					let message_json = match serde_json::to_string(&message) {
+						Ok(msg) => msg,
+						Err(error) => {
+							data.errors.modify().push(ErrorData::new_with_error(
+								"Failed to serialize tab entries request.",
+								error,
+							));
+							return;
+						}
+					};
+					if let Err(error) = ws.send(Message::Text(message_json)).await {
+						data.errors
+							.modify()
+							.push(ErrorData::new_with_error("Failed to send tab entries request.", error));
+						return;
+					}
+				}
+			});
+		}
+	});
+
+	let entries_loading = create_memo(ctx, {
+		let pending_entry_count = event_subscription_data.pending_entry_count.clone();
+		let unloaded_tab_ids = event_subscription_data.unloaded_tab_ids.clone();
+		move || *pending_entry_count.get() > 0 || !unloaded_tab_ids.get().is_empty()
+	});
+
+	let log_entries = create_memo(ctx, {
+		let event_log_entries = event_subscription_data.event_log_entries.clone();
+		move || (*event_log_entries.get()).clone()
+	});
+	let entry_count = create_memo(ctx, || log_entries.get().len());
+
+	let entries_per_hour = create_memo(ctx, || {
+		let mut counts: BTreeMap<NaiveDateTime, usize> = BTreeMap::new();
+		for entry in log_entries.get().iter() {
+			let Some(start_time) = entry.start_time else {
+				continue;
+			};
+			let local_start_time = start_time.with_timezone(&Local).naive_local();
+			let Some(hour) = local_start_time
+				.with_minute(0)
+				.and_then(|time| time.with_second(0))
+				.and_then(|time| time.with_nanosecond(0))
+			else {
+				continue;
+			};
+			*counts.entry(hour).or_default() += 1;
+		}
+
+		// Hours without any entries are included so that quiet periods stand out
+		let mut bars = Vec::new();
+		if let (Some(first_hour), Some(last_hour)) = (counts.keys().next(), counts.keys().next_back()) {
+			let mut hour = *first_hour;
+			while hour <= *last_hour {
+				bars.push(StatsBar {
+					label: hour.format("%Y-%m-%d %H:00").to_string(),
+					count: counts.get(&hour).copied().unwrap_or(0),
+					color: None,
+				});
+				hour += Duration::hours(1);
+			}
+		}
+		bars
+	});
+	let entries_without_start_time = create_memo(ctx, || {
+		log_entries
+			.get()
+			.iter()
+			.filter(|entry| entry.start_time.is_none())
+			.count()
+	});
+
+	let entries_per_type = create_memo(ctx, {
+		let entry_types = event_subscription_data.entry_types.clone();
+		move || {
+			let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+			for entry in log_entries.get().iter() {
+				*counts.entry(entry.entry_type.clone()).or_default() += 1;
+			}
+
+			let entry_types = entry_types.get();
+			let mut bars: Vec<StatsBar> = counts
+				.into_iter()
+				.map(|(entry_type_id, count)| {
+					let entry_type = entry_type_id
+						.as_ref()
+						.and_then(|id| entry_types.iter().find(|entry_type| entry_type.id == *id));
+					match entry_type {
+						Some(entry_type) => StatsBar {
+							label: entry_type.name.clone(),
+							count,
+							color: Some(rgb_str_from_color(entry_type.color)),
+						},
+						None => StatsBar {
+							label: String::from("(No type)"),
+							count,
+							color: None,
+						},
+					}
+				})
+				.collect();
+			bars.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+			bars
+		}
+	});
+
+	let tag_frequency = create_memo(ctx, || {
+		let mut counts: HashMap<String, usize> = HashMap::new();
+		for entry in log_entries.get().iter() {
+			for tag in entry.tags.iter() {
+				*counts.entry(tag.name.clone()).or_default() += 1;
+			}
+		}
+		let mut bars: Vec<StatsBar> = counts
+			.into_iter()
+			.map(|(label, count)| StatsBar {
+				label,
+				count,
+				color: None,
+			})
+			.collect();
+		bars.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+		bars
+	});
+
+	let video_entry_count = create_memo(ctx, || {
+		log_entries
+			.get()
+			.iter()
+			.filter(|entry| entry.video_edit_state != VideoEditState::NoVideo)
+			.count()
+	});
+	let done_editing_count = create_memo(ctx, || {
+		log_entries
+			.get()
+			.iter()
+			.filter(|entry| entry.video_edit_state == VideoEditState::DoneEditing)
+			.count()
+	});
+	let done_processing_count = create_memo(ctx, || {
+		log_entries
+			.get()
+			.iter()
+			.filter(|entry| {
+				entry.video_edit_state != VideoEditState::NoVideo
+					&& entry.video_processing_state == VideoProcessingState::Done
+			})
+			.count()
+	});
+	let percent_of_videos = move |count: usize| {
+		let total = *video_entry_count.get();
+		if total == 0 {
+			0.0
+		} else {
+			count as f64 * 100.0 / total as f64
+		}
+	};
+	let done_editing_text = create_memo(ctx, move || {
+		let count = *done_editing_count.get();
+		format!(
+			"{} of {} ({:.1}%)",
+			count,
+			video_entry_count.get(),
+			percent_of_videos(count)
+		)
+	});
+	let done_processing_text = create_memo(ctx, move || {
+		let count = *done_processing_count.get();
+		format!(
+			"{} of {} ({:.1}%)",
+			count,
+			video_entry_count.get(),
+			percent_of_videos(count)
+		)
+	});
+
+	let wrap_report_url = |format: &str| {
+		let url = application_url(&format!(
+			"{}/event/{}/wrap_report",
+			client_config().api_base_path,
+			props.id
+		));
+		url.set_search(&format!("format={}", format));
+		url.href()
+	};
+	let wrap_report_markdown_url = wrap_report_url("markdown");
+	let wrap_report_html_url = wrap_report_url("html");
+
+	view! {
+		ctx,
+		h1 { "Statistics" }
+		div(id="event_log_stats_wrap_report") {
+			"Wrap report: "
+			a(href=wrap_report_markdown_url, download="") { "Markdown" }
+			" "
+			a(href=wrap_report_html_url, download="") { "HTML" }
+		}
+		(if *entries_loading.get() {
+			view! {
+				ctx,
+				p(id="event_log_stats_loading") { "Some entries are still loading, so these numbers may be incomplete." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		p { "Entries: " (entry_count.get()) }
+		h2 { "Video Completion" }
+		table(id="event_log_stats_video_completion") {
+			tr {
+				th { "Entries with videos" }
+				td { (video_entry_count.get()) }
+			}
+			tr {
+				th { "Done editing" }
+				td { (done_editing_text.get()) }
+			}
+			tr {
+				th { "Done processing" }
+				td { (done_processing_text.get()) }
+			}
+		}
+		h2 { "Entries per Hour" }
+		(if *entries_without_start_time.get() > 0 {
+			view! {
+				ctx,
+				p { (entries_without_start_time.get()) " entries don't have a start time and aren't counted here." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		StatsBarChart(bars=entries_per_hour)
+		h2 { "Entries per Entry Type" }
+		StatsBarChart(bars=entries_per_type)
+		h2 { "Tag Frequency" }
+		StatsBarChart(bars=tag_frequency)
+	}
+}
+
+#[derive(Prop)]
+struct StatsBarChartProps<'a> {
+	bars: &'a ReadSignal<Vec<StatsBar>>,
+}
+
+#[component]
+fn StatsBarChart<'a, G: Html>(ctx: Scope<'a>, props: StatsBarChartProps<'a>) -> View<G> {
+	// Bars are sized relative to the largest one
+	let sized_bars = create_memo(ctx, || {
+		let bars = props.bars.get();
+		let max_count = bars.iter().map(|bar| bar.count).max().unwrap_or(0);
+		let sized_bars: Vec<(StatsBar, f64)> = bars
+			.iter()
+			.map(|bar| {
+				let width = if max_count == 0 {
+					0.0
+				} else {
+					bar.count as f64 * 100.0 / max_count as f64
+				};
+				(bar.clone(), width)
+			})
+			.collect();
+		sized_bars
+	});
+
+	view! {
+		ctx,
+		(if props.bars.get().is_empty() {
+			view! {
+				ctx,
+				p(class="event_log_stats_empty") { "There's nothing to show yet." }
+			}
+		} else {
+			view! {
+				ctx,
+				table(class="event_log_stats_chart") {
+					Indexed(
+						iterable=sized_bars,
+						view=|ctx, (bar, width)| {
+							let bar_style = match bar.color.as_ref() {
+								Some(color) => format!("width: {}%; background: {}", width, color),
+								None => format!("width: {}%", width),
+							};
+							view! {
+								ctx,
+								tr {
+									td(class="event_log_stats_chart_label") { (bar.label) }
+									td(class="event_log_stats_chart_count") { (bar.count) }
+									td(class="event_log_stats_chart_bar_cell") {
+										div(class="event_log_stats_chart_bar", style=bar_style)
+									}
+								}
+							}
+						}
+					)
+				}
+			}
+		})
+	}
+}
+
+#[component]
+pub fn EventLogStatsView<G: Html>(ctx: Scope<'_>, props: EventLogStatsProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading statistics..." }) {
+			EventLogStatsLoadedView(id=props.id)
+		}
+	}
+}
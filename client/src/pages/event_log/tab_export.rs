@@ -0,0 +1,217 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::components::event_log_entry::utils::format_duration;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry, VideoEditState};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, HtmlElement};
+
+const EXPORT_HEADERS: [&str; 12] = [
+	"Number",
+	"Parent",
+	"Start",
+	"End",
+	"Type",
+	"Description",
+	"Submitter/Winner",
+	"Media Links",
+	"Tags",
+	"Poster Moment",
+	"Video Edit State",
+	"Notes",
+];
+
+/// Index of the description column in [EXPORT_HEADERS]
+const DESCRIPTION_COLUMN: usize = 5;
+
+/// An entry prepared for export
+struct ExportRow {
+	/// How many levels of parents the entry has in the export
+	child_depth: usize,
+	/// Values for each column in [EXPORT_HEADERS]
+	fields: Vec<String>,
+}
+
+/// Converts the provided entries to rows for export. Entries should be provided in the order they appear in the log.
+fn export_rows(event_start: DateTime<Utc>, entries: &[EventLogEntry], entry_types: &[EntryType]) -> Vec<ExportRow> {
+	let entry_types_by_id: HashMap<&str, &EntryType> = entry_types
+		.iter()
+		.map(|entry_type| (entry_type.id.as_str(), entry_type))
+		.collect();
+	let entries_by_id: HashMap<&str, &EventLogEntry> = entries.iter().map(|entry| (entry.id.as_str(), entry)).collect();
+
+	entries
+		.iter()
+		.map(|entry| {
+			let mut child_depth = 0;
+			let mut parent_id = entry.parent.as_deref();
+			while let Some(parent) = parent_id.and_then(|id| entries_by_id.get(id)) {
+				child_depth += 1;
+				parent_id = parent.parent.as_deref();
+			}
+
+			let entry_number = entry.entry_number.map(|num| num.to_string()).unwrap_or_default();
+			let parent_number = entry
+				.parent
+				.as_deref()
+				.and_then(|parent| entries_by_id.get(parent))
+				.and_then(|parent| parent.entry_number)
+				.map(|num| num.to_string())
+				.unwrap_or_default();
+			let start_time = entry
+				.start_time
+				.map(|start_time| format_duration(&(start_time - event_start)))
+				.unwrap_or_default();
+			let end_time = match entry.end_time {
+				EndTimeData::Time(end_time) => format_duration(&(end_time - event_start)),
+				EndTimeData::NotEntered => String::new(),
+				EndTimeData::NoTime => String::from("—"),
+			};
+			let entry_type = entry
+				.entry_type
+				.as_deref()
+				.and_then(|entry_type| entry_types_by_id.get(entry_type))
+				.map(|entry_type| entry_type.name.clone())
+				.unwrap_or_default();
+			let tags: Vec<&str> = entry.tags.iter().map(|tag| tag.name.as_str()).collect();
+			let poster_moment = if entry.poster_moment { "Yes" } else { "" };
+			let video_edit_state = match entry.video_edit_state {
+				VideoEditState::NoVideo => "",
+				VideoEditState::MarkedForEditing => "Marked",
+				VideoEditState::DoneEditing => "Done Editing",
+			};
+
+			let fields = vec![
+				entry_number,
+				parent_number,
+				start_time,
+				end_time,
+				entry_type,
+				entry.description.clone(),
+				entry.submitter_or_winner.clone(),
+				entry.media_links.join("\n"),
+				tags.join(", "),
+				poster_moment.to_string(),
+				video_edit_state.to_string(),
+				entry.notes.clone(),
+			];
+			ExportRow { child_depth, fields }
+		})
+		.collect()
+}
+
+/// Generates a CSV file containing the provided entries
+pub fn entries_csv(event_start: DateTime<Utc>, entries: &[EventLogEntry], entry_types: &[EntryType]) -> String {
+	let mut csv = String::new();
+	let headers: Vec<String> = EXPORT_HEADERS.iter().map(|header| csv_field(header)).collect();
+	csv.push_str(&headers.join(","));
+	csv.push_str("\r\n");
+	for row in export_rows(event_start, entries, entry_types) {
+		let fields: Vec<String> = row.fields.iter().map(|field| csv_field(field)).collect();
+		csv.push_str(&fields.join(","));
+		csv.push_str("\r\n");
+	}
+	csv
+}
+
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+/// Generates a standalone HTML document containing the provided entries, formatted for printing
+pub fn entries_printable_html(
+	event_name: &str,
+	tab_name: &str,
+	event_start: DateTime<Utc>,
+	entries: &[EventLogEntry],
+	entry_types: &[EntryType],
+) -> String {
+	let title = html_escape(&format!("{} - {}", event_name, tab_name));
+	let mut html = format!(
+		"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n<table>\n<thead>\n<tr>",
+		title, PRINTABLE_HTML_STYLE, title
+	);
+	for header in EXPORT_HEADERS {
+		html.push_str(&format!("<th>{}</th>", html_escape(header)));
+	}
+	html.push_str("</tr>\n</thead>\n<tbody>\n");
+	for row in export_rows(event_start, entries, entry_types) {
+		html.push_str("<tr>");
+		for (index, field) in row.fields.iter().enumerate() {
+			let mut value = html_escape(field).replace('\n', "<br>");
+			if index == DESCRIPTION_COLUMN && row.child_depth > 0 {
+				value = format!("{}{}", "↳ ".repeat(row.child_depth), value);
+			}
+			html.push_str(&format!("<td>{}</td>", value));
+		}
+		html.push_str("</tr>\n");
+	}
+	html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+	html
+}
+
+const PRINTABLE_HTML_STYLE: &str = "body { font-family: sans-serif; font-size: 10pt; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #000; padding: 2px 4px; text-align: left; vertical-align: top; }
+thead { display: table-header-group; }
+tr { page-break-inside: avoid; }";
+
+fn html_escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// Has the browser download a file with the provided contents
+pub fn download_file(file_name: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+	let document = window()
+		.and_then(|window| window.document())
+		.ok_or_else(|| JsValue::from_str("The page document isn't available"))?;
+	let body = document
+		.body()
+		.ok_or_else(|| JsValue::from_str("The page body isn't available"))?;
+
+	let mut data_url = format!("data:{};charset=utf-8,", mime_type);
+	for byte in contents.bytes() {
+		if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+			data_url.push(byte as char);
+		} else {
+			data_url.push_str(&format!("%{:02X}", byte));
+		}
+	}
+
+	let link: HtmlElement = document.create_element("a")?.unchecked_into();
+	link.set_attribute("href", &data_url)?;
+	link.set_attribute("download", file_name)?;
+	body.append_child(&link)?;
+	link.click();
+	body.remove_child(&link)?;
+	Ok(())
+}
+
+/// Makes a file name from the provided text by removing characters that aren't allowed in file names on some systems
+pub fn export_file_name(name: &str, extension: &str) -> String {
+	let name: String = name
+		.chars()
+		.map(|c| {
+			if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect();
+	format!("{}.{}", name.trim(), extension)
+}
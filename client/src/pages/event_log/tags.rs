@@ -7,13 +7,16 @@
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::manager::SubscriptionManager;
+use crate::subscriptions::stores::AdminEventsStore;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
+use chrono::{NaiveDate, Utc};
 use futures::future::poll_fn;
 use futures::lock::Mutex;
 use futures::task::{Context, Poll, Waker};
 use gloo_net::websocket::Message;
 use std::collections::HashMap;
+use stream_log_shared::messages::event_log::VideoEditState;
 use stream_log_shared::messages::event_subscription::EventSubscriptionUpdate;
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::permissions::PermissionLevel;
@@ -24,13 +27,71 @@ use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore::suspense::Suspense;
-use web_sys::Event as WebEvent;
+use wasm_bindgen::JsCast;
+use web_sys::{Event as WebEvent, HtmlSelectElement};
+
+const TAG_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a date entered into a tag date field, where an empty field means the tag isn't limited in that direction
+fn tag_date_from_input(input: &str) -> Result<Option<NaiveDate>, String> {
+	if input.is_empty() {
+		return Ok(None);
+	}
+	NaiveDate::parse_from_str(input, TAG_DATE_FORMAT)
+		.map(Some)
+		.map_err(|error| format!("Invalid date: {}", error))
+}
+
+/// Describes the days on which a tag is offered for new entries
+fn describe_tag_valid_dates(tag: &Tag) -> String {
+	match (tag.valid_from, tag.valid_to) {
+		(Some(valid_from), Some(valid_to)) => format!(
+			"{} to {}",
+			valid_from.format(TAG_DATE_FORMAT),
+			valid_to.format(TAG_DATE_FORMAT)
+		),
+		(Some(valid_from), None) => format!("From {}", valid_from.format(TAG_DATE_FORMAT)),
+		(None, Some(valid_to)) => format!("Until {}", valid_to.format(TAG_DATE_FORMAT)),
+		(None, None) => String::from("Any day"),
+	}
+}
 
 #[derive(Prop)]
 pub struct EventLogTagsProps {
 	id: String,
 }
 
+/// Sends an approval or rejection for a pending tag action
+async fn send_pending_tag_action_update(ctx: Scope<'_>, event: Event, update: EventSubscriptionUpdate) {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+
+	let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+		event,
+		Box::new(update),
+	)));
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors.modify().push(ErrorData::new_with_error(
+				"Failed to serialize tag change approval message.",
+				error,
+			));
+			return;
+		}
+	};
+
+	let send_result = ws.send(Message::Text(message_json)).await;
+	if let Err(error) = send_result {
+		let data: &DataSignals = use_context(ctx);
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Failed to send tag change approval message.",
+			error,
+		));
+	}
+}
+
 #[component]
 async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProps) -> View<G> {
 	let user: &Signal<Option<SelfUserData>> = use_context(ctx);
@@ -84,16 +145,37 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 	let event_signal = event_subscription_data.event.clone();
 	let permission_signal = event_subscription_data.permission.clone();
 	let tags_signal = event_subscription_data.tags.clone();
+	let video_edit_state_rules_signal = event_subscription_data.video_edit_state_rules.clone();
+	let pending_tag_actions_signal = event_subscription_data.pending_tag_actions.clone();
 
-	let read_events_signal = create_memo(ctx, || (*data.all_events.get()).clone());
+	// Only administrators subscribe to the list of all events
+	let events_store: &Option<AdminEventsStore> = create_ref(
+		ctx,
+		if *user_is_admin_signal.get() {
+			Some(data.stores.get(SubscriptionType::AdminEvents))
+		} else {
+			None
+		},
+	);
+	let read_events_signal = create_memo(ctx, || {
+		events_store
+			.as_ref()
+			.map(|events_store| (*events_store.all_events.get()).clone())
+			.unwrap_or_default()
+	});
 	let read_tags_signal = create_memo(ctx, {
 		let tags_signal = tags_signal.clone();
 		move || (*tags_signal.get()).clone()
 	});
 
+	let read_pending_tag_actions_signal = create_memo(ctx, move || (*pending_tag_actions_signal.get()).clone());
+	let requires_approval_signal = create_memo(ctx, {
+		let event_signal = event_signal.clone();
+		move || event_signal.get().require_tag_deletion_approval
+	});
+
 	let event_names_index = create_memo(ctx, || {
-		let event_names: HashMap<String, Event> = data
-			.all_events
+		let event_names: HashMap<String, Event> = read_events_signal
 			.get()
 			.iter()
 			.map(|event| (event.name.clone(), event.clone()))
@@ -112,23 +194,36 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 		}
 	});
 
+	let video_edit_state_rules_by_tag = create_memo(ctx, move || {
+		let rules: HashMap<String, VideoEditState> = video_edit_state_rules_signal
+			.get()
+			.iter()
+			.map(|rule| (rule.tag.id.clone(), rule.video_edit_state))
+			.collect();
+		rules
+	});
+
 	let can_edit_signal = create_memo(ctx, {
 		let permission_signal = permission_signal.clone();
-		move || permission_signal.get().can_edit()
+		let event_signal = event_signal.clone();
+		move || permission_signal.get().can_edit() && !event_signal.get().archived
 	});
 	let is_supervisor_signal = create_memo(ctx, {
 		let permission_signal = permission_signal.clone();
 		move || *permission_signal.get() == PermissionLevel::Supervisor
 	});
 
+	let today = Utc::now().date_naive();
+
 	let new_event_signal = event_signal.clone();
+	let pending_event_signal = event_signal.clone();
 	let copy_event_signal = event_signal.clone();
 
 	create_effect(ctx, {
 		let event_signal = event_signal.clone();
 		move || {
 			let event = event_signal.get();
-			let page_title = format!("{} - Tags | Stream Log", event.name);
+			let page_title = format!("{} - Tags", event.name);
 			set_page_title(&page_title);
 		}
 	});
@@ -163,6 +258,12 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 			tr {
 				th { "Name" }
 				th { "Description" }
+				th(title="Tags are only suggested for new entries on these days (UTC), but stay on entries that already have them") { "Valid Dates" }
+				(if *user_is_admin_signal.get() {
+					view! { ctx, th { "Video Edit State Rule" } }
+				} else {
+					view! { ctx, }
+				})
 				th { "Playlist" }
 			}
 			Keyed(
@@ -172,6 +273,10 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 					let entered_description = create_signal(ctx, tag.description.clone());
 					let entered_description_error = create_signal(ctx, String::new());
 
+					let entered_valid_from = create_signal(ctx, tag.valid_from.map(|date| date.format(TAG_DATE_FORMAT).to_string()).unwrap_or_default());
+					let entered_valid_to = create_signal(ctx, tag.valid_to.map(|date| date.format(TAG_DATE_FORMAT).to_string()).unwrap_or_default());
+					let entered_valid_dates_error = create_signal(ctx, String::new());
+
 					let entered_playlist_id = create_signal(ctx, tag.playlist.as_ref().map(|playlist| playlist.id.clone()).unwrap_or_default());
 					let entered_playlist_title = create_signal(ctx, tag.playlist.as_ref().map(|playlist| playlist.title.clone()).unwrap_or_default());
 					let entered_playlist_shows_in_video_descriptions = create_signal(ctx, tag.playlist.as_ref().map(|playlist| playlist.shows_in_video_descriptions).unwrap_or_default());
@@ -181,6 +286,20 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 					let entered_replacement_tag = create_signal(ctx, String::new());
 					let entered_replacement_tag_error = create_signal(ctx, String::new());
 
+					let entered_video_edit_state_rule = create_signal(ctx, String::new());
+					create_effect(ctx, {
+						let tag_id = tag.id.clone();
+						move || {
+							let rule_value = match video_edit_state_rules_by_tag.get().get(&tag_id) {
+								Some(VideoEditState::NoVideo) => "NoVideo",
+								Some(VideoEditState::MarkedForEditing) => "MarkedForEditing",
+								Some(VideoEditState::DoneEditing) => "DoneEditing",
+								None => ""
+							};
+							entered_video_edit_state_rule.set(String::from(rule_value));
+						}
+					});
+
 					let start_delete_handler = |_event: WebEvent| {
 						confirming_delete.set(true);
 					};
@@ -192,14 +311,22 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 					let description_event_signal = event_signal.clone();
 					let description_tag = tag.clone();
 
+					let valid_dates_event_signal = event_signal.clone();
+					let valid_dates_tag = tag.clone();
+					let valid_dates_description = describe_tag_valid_dates(&tag);
+					let row_class = if tag.is_expired_on(today) { "manage_tags_expired" } else { "" };
+
 					let handler_event_signal = event_signal.clone();
 					let handler_tag = tag.clone();
 
+					let rule_event_signal = event_signal.clone();
+					let rule_tag = tag.clone();
+
 					let tag_playlist = tag.playlist.clone();
 
 					view! {
 						ctx,
-						tr {
+						tr(class=row_class) {
 							td { (tag.name) }
 							td {
 								(if *can_edit_signal.get() {
@@ -280,6 +407,176 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 									}
 								})
 							}
+							td {
+								(if *can_edit_signal.get() {
+									let submit_valid_dates_handler = {
+										let event_signal = valid_dates_event_signal.clone();
+										let tag = valid_dates_tag.clone();
+
+										move |event: WebEvent| {
+											event.prevent_default();
+
+											let event_signal = event_signal.clone();
+
+											let valid_from = match tag_date_from_input(&entered_valid_from.get()) {
+												Ok(date) => date,
+												Err(error) => {
+													entered_valid_dates_error.set(error);
+													return;
+												}
+											};
+											let valid_to = match tag_date_from_input(&entered_valid_to.get()) {
+												Ok(date) => date,
+												Err(error) => {
+													entered_valid_dates_error.set(error);
+													return;
+												}
+											};
+											if let (Some(valid_from), Some(valid_to)) = (valid_from, valid_to) {
+												if valid_to < valid_from {
+													entered_valid_dates_error.set(String::from("The last valid day can't be before the first valid day."));
+													return;
+												}
+											}
+											entered_valid_dates_error.modify().clear();
+
+											let mut tag = tag.clone();
+											tag.valid_from = valid_from;
+											tag.valid_to = valid_to;
+
+											spawn_local_scoped(ctx, async move {
+												let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+												let mut ws = ws_context.lock().await;
+
+												let message = FromClientMessage::SubscriptionMessage(
+													Box::new(
+														SubscriptionTargetUpdate::EventUpdate(
+															(*event_signal.get()).clone(),
+															Box::new(
+																EventSubscriptionUpdate::UpdateTag(tag)
+															)
+														)
+													)
+												);
+												let message_json = match serde_json::to_string(&message) {
+													Ok(msg) => msg,
+													Err(error) => {
+														let data: &DataSignals = use_context(ctx);
+														data.errors.modify().push(ErrorData::new_with_error("Failed to serialize tag valid dates update.", error));
+														return;
+													}
+												};
+
+												let send_result = ws.send(Message::Text(message_json)).await;
+												if let Err(error) = send_result {
+													let data: &DataSignals = use_context(ctx);
+													data.errors.modify().push(ErrorData::new_with_error("Failed to send tag valid dates update.", error));
+												}
+											});
+										}
+									};
+
+									view! {
+										ctx,
+										form(on:submit=submit_valid_dates_handler, class="manage_tags_valid_dates") {
+											input(
+												type="date",
+												bind:value=entered_valid_from,
+												class=if entered_valid_dates_error.get().is_empty() { "" } else { "error" },
+												title=entered_valid_dates_error.get()
+											)
+											" to "
+											input(
+												type="date",
+												bind:value=entered_valid_to,
+												class=if entered_valid_dates_error.get().is_empty() { "" } else { "error" },
+												title=entered_valid_dates_error.get()
+											)
+											button(type="submit") { "Set Dates" }
+										}
+									}
+								} else {
+									let valid_dates_description = valid_dates_description.clone();
+									view! {
+										ctx,
+										(valid_dates_description)
+									}
+								})
+							}
+							(if *user_is_admin_signal.get() {
+								let rule_change_handler = move |event: WebEvent| {
+									let Some(target) = event.target() else {
+										return;
+									};
+									let select_element: HtmlSelectElement = target.unchecked_into();
+									entered_video_edit_state_rule.set(select_element.value());
+								};
+
+								let set_rule_handler = {
+									let event_signal = rule_event_signal.clone();
+									let tag = rule_tag.clone();
+
+									move |event: WebEvent| {
+										event.prevent_default();
+
+										let event_signal = event_signal.clone();
+										let tag = tag.clone();
+
+										let video_edit_state = match entered_video_edit_state_rule.get().as_str() {
+											"NoVideo" => Some(VideoEditState::NoVideo),
+											"MarkedForEditing" => Some(VideoEditState::MarkedForEditing),
+											"DoneEditing" => Some(VideoEditState::DoneEditing),
+											_ => None
+										};
+
+										spawn_local_scoped(ctx, async move {
+											let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+											let mut ws = ws_context.lock().await;
+
+											let message = FromClientMessage::SubscriptionMessage(
+												Box::new(
+													SubscriptionTargetUpdate::EventUpdate(
+														(*event_signal.get()).clone(),
+														Box::new(
+															EventSubscriptionUpdate::SetVideoEditStateRule(tag, video_edit_state)
+														)
+													)
+												)
+											);
+											let message_json = match serde_json::to_string(&message) {
+												Ok(msg) => msg,
+												Err(error) => {
+													let data: &DataSignals = use_context(ctx);
+													data.errors.modify().push(ErrorData::new_with_error("Failed to serialize video edit state rule update.", error));
+													return;
+												}
+											};
+
+											let send_result = ws.send(Message::Text(message_json)).await;
+											if let Err(error) = send_result {
+												let data: &DataSignals = use_context(ctx);
+												data.errors.modify().push(ErrorData::new_with_error("Failed to send video edit state rule update.", error));
+											}
+										});
+									}
+								};
+
+								view! {
+									ctx,
+									td {
+										form(on:submit=set_rule_handler, title="Entries given this tag while they have no video get this video edit state") {
+											select(on:change=rule_change_handler) {
+												option(value="", selected=entered_video_edit_state_rule.get().is_empty()) { "No rule" }
+												option(value="MarkedForEditing", selected=*entered_video_edit_state_rule.get() == "MarkedForEditing") { "Marked" }
+												option(value="DoneEditing", selected=*entered_video_edit_state_rule.get() == "DoneEditing") { "Done Editing" }
+											}
+											button(type="submit") { "Set Rule" }
+										}
+									}
+								}
+							} else {
+								view! { ctx, }
+							})
 							(if *is_supervisor_signal.get() {
 								let handler_event_signal = handler_event_signal.clone();
 								let handler_tag = handler_tag.clone();
@@ -388,6 +685,8 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 												let data: &DataSignals = use_context(ctx);
 												data.errors.modify().push(ErrorData::new_with_error("Failed to send tag replacement message.", error));
 											}
+
+											entered_replacement_tag.set(String::new());
 										});
 									}
 								};
@@ -426,6 +725,7 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 												move |_event: WebEvent| {
 													let event_signal = event_signal.clone();
 													let tag = tag.clone();
+													confirming_delete.set(false);
 
 													spawn_local_scoped(ctx, async move {
 														let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
@@ -462,6 +762,11 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 											view! {
 												ctx,
 												"Removing this tag will remove all uses of it from the event log."
+												(if *requires_approval_signal.get() {
+													" Another supervisor must approve the removal before it happens."
+												} else {
+													""
+												})
 												button(type="button", on:click=confirm_delete_handler) { "Yes, delete it!" }
 												button(type="button", on:click=cancel_delete_handler) { "No, keep it!" }
 											}
@@ -504,6 +809,81 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 				}
 			)
 		}
+		(if *is_supervisor_signal.get() && !read_pending_tag_actions_signal.get().is_empty() {
+			let event_signal = pending_event_signal.clone();
+			view! {
+				ctx,
+				div(id="manage_tags_pending_actions") {
+					h1 { "Tag Changes Awaiting Approval" }
+					table {
+						tr {
+							th { "Change" }
+							th { "Requested By" }
+							th { "Requested At" }
+							th { }
+						}
+						Keyed(
+							iterable=read_pending_tag_actions_signal,
+							key=|action| action.id.clone(),
+							view=move |ctx, action| {
+								let change_description = match action.replacement_tag.as_ref() {
+									Some(replacement_tag) => format!("Replace {} with {}", action.tag.name, replacement_tag.name),
+									None => format!("Remove {}", action.tag.name)
+								};
+								let requested_at = format!("{}", action.requested_at.format("%Y-%m-%d %H:%M:%S UTC"));
+								let requested_by_me = match user.get().as_ref() {
+									Some(user) => user.id == action.requested_by.id,
+									None => false
+								};
+
+								let approve_handler = {
+									let event_signal = event_signal.clone();
+									let action_id = action.id.clone();
+									move |_event: WebEvent| {
+										let event = (*event_signal.get()).clone();
+										let update = EventSubscriptionUpdate::ApprovePendingTagAction(action_id.clone());
+										spawn_local_scoped(ctx, send_pending_tag_action_update(ctx, event, update));
+									}
+								};
+								let reject_handler = {
+									let event_signal = event_signal.clone();
+									let action_id = action.id.clone();
+									move |_event: WebEvent| {
+										let event = (*event_signal.get()).clone();
+										let update = EventSubscriptionUpdate::RejectPendingTagAction(action_id.clone());
+										spawn_local_scoped(ctx, send_pending_tag_action_update(ctx, event, update));
+									}
+								};
+
+								view! {
+									ctx,
+									tr {
+										td { (change_description) }
+										td { (action.requested_by.username) }
+										td { (requested_at) }
+										td {
+											button(
+												type="button",
+												on:click=approve_handler,
+												disabled=requested_by_me,
+												title=if requested_by_me { "A different supervisor must approve your change" } else { "" }
+											) {
+												"Approve"
+											}
+											button(type="button", on:click=reject_handler) {
+												(if requested_by_me { "Cancel" } else { "Reject" })
+											}
+										}
+									}
+								}
+							}
+						)
+					}
+				}
+			}
+		} else {
+			view! { ctx, }
+		})
 		(if *can_edit_signal.get() {
 			let entered_tag = create_signal(ctx, String::new());
 			let entered_tag_error = create_signal(ctx, String::new());
@@ -542,7 +922,9 @@ async fn EventLogTagsLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTagsProp
 						id: String::new(),
 						name,
 						description,
-						playlist: None
+						playlist: None,
+						valid_from: None,
+						valid_to: None
 					};
 
 					spawn_local_scoped(ctx, async move {
@@ -0,0 +1,357 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::components::event_log_entry::utils::{format_duration, get_duration_from_formatted};
+use crate::page_utils::set_page_title;
+use crate::subscriptions::errors::ErrorData;
+use crate::subscriptions::manager::SubscriptionManager;
+use crate::websocket::WebSocketSendStream;
+use crate::DataSignals;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::poll_fn;
+use futures::lock::Mutex;
+use futures::task::{Context, Poll, Waker};
+use gloo_net::websocket::Message;
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry};
+use stream_log_shared::messages::event_subscription::{EntryTimeShift, EventSubscriptionUpdate};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::subscriptions::{SubscriptionTargetUpdate, SubscriptionType};
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use web_sys::Event as WebEvent;
+
+#[derive(Prop)]
+pub struct EventLogTimeShiftProps {
+	id: String,
+}
+
+/// The time shift described by the values the user entered
+#[derive(Clone)]
+struct EnteredTimeShift {
+	range_start: DateTime<Utc>,
+	range_end: Option<DateTime<Utc>>,
+	shift: Duration,
+}
+
+impl EnteredTimeShift {
+	fn applies_to(&self, entry: &EventLogEntry) -> bool {
+		let Some(start_time) = entry.start_time else {
+			return false;
+		};
+		start_time >= self.range_start && self.range_end.map(|end| start_time <= end).unwrap_or(true)
+	}
+}
+
+/// Parses the entered time shift values, returning an error message for the user if they aren't valid
+fn parse_time_shift(
+	event_start: DateTime<Utc>,
+	range_start: &str,
+	range_end: &str,
+	shift: &str,
+) -> Result<EnteredTimeShift, String> {
+	let range_start = match get_duration_from_formatted(range_start) {
+		Ok(duration) => event_start + duration,
+		Err(error) => return Err(format!("From: {}", error)),
+	};
+	let range_end = if range_end.is_empty() {
+		None
+	} else {
+		match get_duration_from_formatted(range_end) {
+			Ok(duration) => Some(event_start + duration),
+			Err(error) => return Err(format!("Until: {}", error)),
+		}
+	};
+	if let Some(range_end) = range_end {
+		if range_end < range_start {
+			return Err(String::from("The end of the range is before the start."));
+		}
+	}
+	let shift = match get_duration_from_formatted(shift) {
+		Ok(duration) => duration,
+		Err(error) => return Err(format!("Shift by: {}", error)),
+	};
+	if shift.is_zero() {
+		return Err(String::from("Enter a time to shift the entries by."));
+	}
+
+	Ok(EnteredTimeShift {
+		range_start,
+		range_end,
+		shift,
+	})
+}
+
+#[component]
+async fn EventLogTimeShiftLoadedView<G: Html>(ctx: Scope<'_>, props: EventLogTimeShiftProps) -> View<G> {
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let data: &DataSignals = use_context(ctx);
+
+	let subscription_data = {
+		let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager
+			.set_subscription(SubscriptionType::EventLogData(props.id.clone()), &mut ws)
+			.await
+	};
+	if let Err(error) = subscription_data {
+		data.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send event subscription message.",
+			error,
+		));
+	}
+	drop(ws);
+
+	let event_subscription_data = poll_fn(|poll_context: &mut Context<'_>| {
+		log::debug!(
+			"[Time Shift] Checking whether event {} is present yet in the subscription manager",
+			props.id
+		);
+		match data.events.get().get(&props.id) {
+			Some(event_subscription_data) => Poll::Ready(event_subscription_data.clone()),
+			None => {
+				let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+				event_wakers
+					.modify()
+					.entry(props.id.clone())
+					.or_default()
+					.push(poll_context.waker().clone());
+				Poll::Pending
+			}
+		}
+	})
+	.await;
+
+	let page_title = format!("{} - Shift Entry Times", event_subscription_data.event.get().name);
+	set_page_title(&page_title);
+
+	create_effect(ctx, {
+		let event_signal = event_subscription_data.event.clone();
+		move || {
+			let event = event_signal.get();
+			let page_title = format!("{} - Shift Entry Times", event.name);
+			set_page_title(&page_title);
+		}
+	});
+
+	let is_supervisor = create_memo(ctx, {
+		let permission_signal = event_subscription_data.permission.clone();
+		move || *permission_signal.get() == PermissionLevel::Supervisor
+	});
+
+	let event_signal: &RcSignal<Event> = create_ref(ctx, event_subscription_data.event.clone());
+	let entered_range_start = create_signal(ctx, String::from("0:00"));
+	let entered_range_end = create_signal(ctx, String::new());
+	let entered_shift = create_signal(ctx, String::new());
+	let confirming = create_signal(ctx, false);
+
+	let parsed_time_shift = create_memo(ctx, || {
+		parse_time_shift(
+			event_signal.get().start_time,
+			&entered_range_start.get(),
+			&entered_range_end.get(),
+			&entered_shift.get(),
+		)
+	});
+	let time_shift_error = create_memo(ctx, || match &*parsed_time_shift.get() {
+		Ok(_) => String::new(),
+		Err(error) => error.clone(),
+	});
+
+	// The preview of the shift lists each entry that would be moved, along with its current and new times
+	let preview_entries = create_memo(ctx, {
+		let entries_signal = event_subscription_data.event_log_entries.clone();
+		move || {
+			let Ok(time_shift) = (*parsed_time_shift.get()).clone() else {
+				return Vec::new();
+			};
+			let entries: Vec<EventLogEntry> = entries_signal
+				.get()
+				.iter()
+				.filter(|entry| time_shift.applies_to(entry))
+				.cloned()
+				.collect();
+			entries
+		}
+	});
+
+	create_effect(ctx, || {
+		parsed_time_shift.track();
+		confirming.set(false);
+	});
+
+	let shift_handler = move |event: WebEvent| {
+		event.prevent_default();
+		if parsed_time_shift.get().is_err() || preview_entries.get().is_empty() {
+			return;
+		}
+		confirming.set(true);
+	};
+
+	let confirm_handler = move |_event: WebEvent| {
+		let Ok(time_shift) = (*parsed_time_shift.get()).clone() else {
+			return;
+		};
+		confirming.set(false);
+		let event = (*event_signal.get()).clone();
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			let time_shift = EntryTimeShift {
+				range_start: time_shift.range_start,
+				range_end: time_shift.range_end,
+				shift_seconds: time_shift.shift.num_seconds(),
+			};
+			let message = FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+				event,
+				Box::new(EventSubscriptionUpdate::ShiftEntryTimes(time_shift)),
+			)));
+			let message_json = match serde_json::to_string(&message) {
+				Ok(msg) => msg,
+				Err(error) => {
+					let data: &DataSignals = use_context(ctx);
+					data.errors.modify().push(ErrorData::new_with_error(
+						"Failed to serialize entry time shift.",
+						error,
+					));
+					return;
+				}
+			};
+			let send_result = ws.send(Message::Text(message_json)).await;
+			if let Err(error) = send_result {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send entry time shift.", error));
+				return;
+			}
+
+			entered_shift.set(String::new());
+		});
+	};
+
+	let cancel_handler = move |_event: WebEvent| {
+		confirming.set(false);
+	};
+
+	view! {
+		ctx,
+		(if *is_supervisor.get() {
+			view! {
+				ctx,
+				h1 { "Shift Entry Times" }
+				p {
+					"Moves the start and end times of all entries starting in a range. Times are relative to the start of the event. Use a negative time (like -0:12) to move entries earlier."
+				}
+				form(id="event_log_time_shift", on:submit=shift_handler) {
+					label {
+						"From: "
+						input(bind:value=entered_range_start, placeholder="0:00")
+					}
+					label {
+						"Until: "
+						input(bind:value=entered_range_end, placeholder="End of event")
+					}
+					label {
+						"Shift by: "
+						input(bind:value=entered_shift, placeholder="0:12")
+					}
+					button(type="submit", disabled=parsed_time_shift.get().is_err() || preview_entries.get().is_empty()) { "Shift" }
+					span(class="input_error") { (time_shift_error.get()) }
+				}
+				(if *confirming.get() {
+					let confirm_message = format!("This will move {} entries. Are you sure?", preview_entries.get().len());
+					view! {
+						ctx,
+						div(id="event_log_time_shift_confirm") {
+							(confirm_message)
+							button(type="button", on:click=confirm_handler) { "Yes, shift them!" }
+							button(type="button", on:click=cancel_handler) { "No, leave them!" }
+						}
+					}
+				} else {
+					view! { ctx, }
+				})
+				(if preview_entries.get().is_empty() {
+					view! {
+						ctx,
+						p(id="event_log_time_shift_preview_empty") { "No entries are in the selected range." }
+					}
+				} else {
+					view! {
+						ctx,
+						table(id="event_log_time_shift_preview") {
+							tr {
+								th { "Start" }
+								th { "New Start" }
+								th { "End" }
+								th { "New End" }
+								th { "Description" }
+							}
+							Keyed(
+								iterable=preview_entries,
+								key=|entry| entry.id.clone(),
+								view=move |ctx, entry| {
+									let event_start = event_signal.get().start_time;
+									let shift = match &*parsed_time_shift.get() {
+										Ok(time_shift) => time_shift.shift,
+										Err(_) => Duration::zero(),
+									};
+									let (start, new_start) = match entry.start_time {
+										Some(start_time) => (
+											format_duration(&(start_time - event_start)),
+											format_duration(&(start_time + shift - event_start)),
+										),
+										None => (String::new(), String::new()),
+									};
+									let (end, new_end) = match entry.end_time {
+										EndTimeData::Time(end_time) => (
+											format_duration(&(end_time - event_start)),
+											format_duration(&(end_time + shift - event_start)),
+										),
+										EndTimeData::NotEntered | EndTimeData::NoTime => (String::new(), String::new()),
+									};
+
+									view! {
+										ctx,
+										tr {
+											td { (start) }
+											td(class="event_log_time_shift_new_time") { (new_start) }
+											td { (end) }
+											td(class="event_log_time_shift_new_time") { (new_end) }
+											td(class="event_log_time_shift_description") { (entry.description) }
+										}
+									}
+								}
+							)
+						}
+					}
+				})
+			}
+		} else {
+			view! {
+				ctx,
+				p(id="event_log_time_shift_not_allowed") { "Only supervisors can shift entry times." }
+			}
+		})
+	}
+}
+
+#[component]
+pub fn EventLogTimeShiftView<G: Html>(ctx: Scope<'_>, props: EventLogTimeShiftProps) -> View<G> {
+	view! {
+		ctx,
+		Suspense(fallback=view! { ctx, "Loading entries..." }) {
+			EventLogTimeShiftLoadedView(id=props.id)
+		}
+	}
+}
@@ -0,0 +1,148 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use stream_log_shared::messages::event_log::{VideoEditState, VideoProcessingState};
+use stream_log_shared::messages::view_preferences::EventLogFilters;
+use wasm_bindgen::JsValue;
+use web_sys::{window, UrlSearchParams};
+
+const TAB_PARAM: &str = "tab";
+const VIDEO_EDIT_STATE_PARAM: &str = "edit_state";
+const VIDEO_PROCESSING_STATE_PARAM: &str = "processing_state";
+const CREATED_BY_ME_PARAM: &str = "mine";
+const ASSIGNED_TO_ME_PARAM: &str = "assigned";
+const OVER_EXPECTED_DURATION_PARAM: &str = "over_duration";
+const MISSING_GIVEAWAY_INFORMATION_PARAM: &str = "giveaway_missing";
+
+/// The parts of the event log view kept in the URL query string. Keeping them in the URL lets the browser's back button
+/// undo changes to them and lets links to the log open the same view.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct EventLogViewState {
+	/// ID of the selected tab, or an empty string for the first tab
+	pub tab_id: String,
+	pub filters: EventLogFilters,
+}
+
+impl EventLogViewState {
+	/// Reads the view state from the URL of the current page. Returns None if the URL doesn't have one.
+	pub fn from_current_url() -> Option<Self> {
+		let query = window()?.location().search().ok()?;
+		let params = UrlSearchParams::new_with_str(&query).ok()?;
+		let tab_id = params.get(TAB_PARAM)?;
+
+		let video_edit_states = list_param(&params, VIDEO_EDIT_STATE_PARAM)
+			.into_iter()
+			.filter_map(|name| {
+				VideoEditState::all_states()
+					.into_iter()
+					.find(|state| video_edit_state_url_name(*state) == name)
+			})
+			.collect();
+		let video_processing_states = list_param(&params, VIDEO_PROCESSING_STATE_PARAM)
+			.into_iter()
+			.filter_map(|name| {
+				VideoProcessingState::all_states()
+					.into_iter()
+					.find(|state| state.to_string() == name)
+			})
+			.collect();
+
+		let filters = EventLogFilters {
+			video_edit_states,
+			video_processing_states,
+			created_by_me: params.has(CREATED_BY_ME_PARAM),
+			assigned_to_me: params.has(ASSIGNED_TO_ME_PARAM),
+			over_expected_duration: params.has(OVER_EXPECTED_DURATION_PARAM),
+			missing_giveaway_information: params.has(MISSING_GIVEAWAY_INFORMATION_PARAM),
+		};
+
+		Some(Self { tab_id, filters })
+	}
+
+	fn to_query_string(&self) -> Option<String> {
+		let params = UrlSearchParams::new().ok()?;
+		params.set(TAB_PARAM, &self.tab_id);
+
+		let filters = &self.filters;
+		if !filters.video_edit_states.is_empty() {
+			let mut names: Vec<&str> = filters
+				.video_edit_states
+				.iter()
+				.map(|state| video_edit_state_url_name(*state))
+				.collect();
+			names.sort_unstable();
+			params.set(VIDEO_EDIT_STATE_PARAM, &names.join(","));
+		}
+		if !filters.video_processing_states.is_empty() {
+			let mut names: Vec<String> = filters
+				.video_processing_states
+				.iter()
+				.map(|state| state.to_string())
+				.collect();
+			names.sort_unstable();
+			params.set(VIDEO_PROCESSING_STATE_PARAM, &names.join(","));
+		}
+		if filters.created_by_me {
+			params.set(CREATED_BY_ME_PARAM, "1");
+		}
+		if filters.assigned_to_me {
+			params.set(ASSIGNED_TO_ME_PARAM, "1");
+		}
+		if filters.over_expected_duration {
+			params.set(OVER_EXPECTED_DURATION_PARAM, "1");
+		}
+		if filters.missing_giveaway_information {
+			params.set(MISSING_GIVEAWAY_INFORMATION_PARAM, "1");
+		}
+
+		Some(format!("?{}", String::from(params.to_string())))
+	}
+
+	/// Stores the view state in the URL of the current page. If `add_history_entry` is set, the state gets a new entry in
+	/// the browser history so that going back returns to the previous state; otherwise, the current entry is updated.
+	pub fn save_to_url(&self, add_history_entry: bool) {
+		let Some(window) = window() else {
+			return;
+		};
+		let Some(query) = self.to_query_string() else {
+			return;
+		};
+		if window.location().search().ok().as_deref() == Some(query.as_str()) {
+			return;
+		}
+		let Ok(history) = window.history() else {
+			return;
+		};
+
+		let save_result = if add_history_entry {
+			history.push_state_with_url(&JsValue::NULL, "", Some(&query))
+		} else {
+			history.replace_state_with_url(&JsValue::NULL, "", Some(&query))
+		};
+		if let Err(error) = save_result {
+			log::warn!("Failed to store the event log view in the URL: {:?}", error);
+		}
+	}
+}
+
+fn list_param(params: &UrlSearchParams, name: &str) -> Vec<String> {
+	match params.get(name) {
+		Some(value) => value
+			.split(',')
+			.filter(|item| !item.is_empty())
+			.map(String::from)
+			.collect(),
+		None => Vec::new(),
+	}
+}
+
+fn video_edit_state_url_name(state: VideoEditState) -> &'static str {
+	match state {
+		VideoEditState::NoVideo => "none",
+		VideoEditState::MarkedForEditing => "marked",
+		VideoEditState::DoneEditing => "done",
+	}
+}
@@ -6,14 +6,32 @@
 
 use crate::page_utils::set_page_title;
 use crate::subscriptions::DataSignals;
+use chrono::prelude::*;
+use chrono::TimeDelta;
+use gloo_timers::callback::Interval;
+use stream_log_shared::messages::events::{Event, EventStatus};
 use stream_log_shared::messages::user::SelfUserData;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore_router::navigate;
 
+const COUNTDOWN_UPDATE_INTERVAL_MS: u32 = 1000;
+
+fn format_countdown(time_until_start: TimeDelta) -> String {
+	let days = time_until_start.num_days();
+	let hours = time_until_start.num_hours() % 24;
+	let minutes = time_until_start.num_minutes() % 60;
+	let seconds = time_until_start.num_seconds() % 60;
+	if days > 0 {
+		format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+	} else {
+		format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+	}
+}
+
 #[component]
 pub fn EventSelectionView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Events | Stream Log");
+	set_page_title("Events");
 
 	{
 		let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
@@ -26,27 +44,87 @@ pub fn EventSelectionView<G: Html>(ctx: Scope<'_>) -> View<G> {
 	}
 
 	let data: &DataSignals = use_context(ctx);
-	let available_events = create_memo(ctx, || (*data.available_events.get()).clone());
 
-	view! {
+	let current_time = create_rc_signal(Utc::now());
+	let _current_time_interval = create_ref(
 		ctx,
-		h1 { "Select an event" }
-		ul {
-			Keyed(
-				iterable=available_events,
-				key=|event| event.id.clone(),
-				view=|ctx, event| {
-					let event_url = format!("/log/{}", event.id);
+		Interval::new(COUNTDOWN_UPDATE_INTERVAL_MS, {
+			let current_time = current_time.clone();
+			move || current_time.set(Utc::now())
+		}),
+	);
+	let current_time = create_ref(ctx, current_time);
+
+	let status_sections: Vec<View<G>> = EventStatus::all()
+		.into_iter()
+		.map(|status| {
+			// A selector is used so the lists are only rebuilt when an event moves between statuses, not on every tick
+			let events = create_selector(ctx, move || {
+				let now = *current_time.get();
+				let mut events: Vec<Event> = data
+					.available_events
+					.get()
+					.iter()
+					.filter(|event| event.status(now) == status)
+					.cloned()
+					.collect();
+				match status {
+					EventStatus::Upcoming => events.sort_by_key(|event| event.start_time),
+					EventStatus::Live | EventStatus::Archived => events.sort_by(|a, b| b.start_time.cmp(&a.start_time)),
+				}
+				events
+			});
+
+			view! {
+				ctx,
+				(if events.get().is_empty() {
+					view! { ctx, }
+				} else {
 					view! {
 						ctx,
-						li {
-							a(href=event_url) {
-								(event.name)
-							}
+						h2 { (status.name()) }
+						ul(class="event_selection_list") {
+							Keyed(
+								iterable=events,
+								key=|event| event.id.clone(),
+								view=move |ctx, event| {
+									let event_url = format!("/log/{}", event.id);
+									let countdown = if status == EventStatus::Upcoming {
+										let start_time = event.start_time;
+										let countdown_text = create_memo(ctx, move || {
+											format!("Starts in {}", format_countdown(start_time - *current_time.get()))
+										});
+										view! {
+											ctx,
+											span(class="event_selection_countdown") { (countdown_text.get()) }
+										}
+									} else {
+										view! { ctx, }
+									};
+									view! {
+										ctx,
+										li {
+											a(href=event_url) {
+												(event.name)
+											}
+											(countdown)
+										}
+									}
+								}
+							)
 						}
 					}
-				}
-			)
+				})
+			}
+		})
+		.collect();
+	let status_sections = View::new_fragment(status_sections);
+
+	view! {
+		ctx,
+		h1 { "Select an event" }
+		div(id="event_selection") {
+			(status_sections)
 		}
 	}
 }
@@ -24,7 +24,7 @@ use web_sys::Event as WebEvent;
 
 #[component]
 pub fn RegistrationView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Register Account | Stream Log");
+	set_page_title("Register Account");
 
 	{
 		let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
@@ -12,7 +12,7 @@ use sycamore_router::navigate;
 
 #[component]
 pub fn RegistrationCompleteView<G: Html>(ctx: Scope) -> View<G> {
-	set_page_title("Registration Complete | Stream Log");
+	set_page_title("Registration Complete");
 
 	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
 	if user_signal.get().is_none() {
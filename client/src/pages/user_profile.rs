@@ -6,22 +6,119 @@
 
 use crate::color_utils::{color_from_rgb_str, rgb_str_from_color};
 use crate::components::color_input_with_contrast::ColorInputWithContrast;
+use crate::components::guided_tour::send_guided_tour_completed;
+use crate::components::user_avatar::UserAvatar;
+use crate::notifications::{desktop_notifications_denied, request_desktop_notification_permission};
 use crate::page_utils::set_page_title;
 use crate::subscriptions::errors::ErrorData;
 use crate::subscriptions::DataSignals;
 use crate::websocket::WebSocketSendStream;
 use futures::lock::Mutex;
 use gloo_net::websocket::Message;
-use stream_log_shared::messages::user::{SelfUserData, UpdateUser};
+use std::collections::HashMap;
+use stream_log_shared::messages::hotkeys::{effective_hotkeys, hotkey_conflicts, Hotkey, HotkeyAction, HotkeyBinding};
+use stream_log_shared::messages::notifications::NotificationPreferences;
+use stream_log_shared::messages::time_display::{time_zone_names, TimeDisplay};
+use stream_log_shared::messages::user::{
+	PublicUserData, SelfUserData, UpdateUser, UserSession, AVATAR_SIZE_LIMIT, USERNAME_CHANGE_COOLDOWN_DAYS,
+};
+use stream_log_shared::messages::user_register::USERNAME_LENGTH_LIMIT;
 use stream_log_shared::messages::FromClientMessage;
 use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
 use sycamore_router::navigate;
-use web_sys::Event as WebEvent;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Event as WebEvent, FileReader, HtmlInputElement, KeyboardEvent};
+
+/// Gets a short description of the browser and operating system that sent a User-Agent header
+fn describe_user_agent(user_agent: &str) -> String {
+	// Browsers also claim to be the browsers they're based on, so the more specific names are checked first
+	let browser = [
+		("Edg/", "Edge"),
+		("OPR/", "Opera"),
+		("Firefox/", "Firefox"),
+		("Chrome/", "Chrome"),
+		("Safari/", "Safari"),
+	]
+	.iter()
+	.find(|(token, _)| user_agent.contains(token))
+	.map(|(_, name)| *name);
+	let system = [
+		("Windows", "Windows"),
+		("Android", "Android"),
+		("iPhone", "iOS"),
+		("iPad", "iOS"),
+		("Mac OS X", "macOS"),
+		("CrOS", "ChromeOS"),
+		("Linux", "Linux"),
+	]
+	.iter()
+	.find(|(token, _)| user_agent.contains(token))
+	.map(|(_, name)| *name);
+
+	match (browser, system) {
+		(Some(browser), Some(system)) => format!("{} on {}", browser, system),
+		(Some(browser), None) => browser.to_string(),
+		(None, Some(system)) => format!("Unknown browser on {}", system),
+		(None, None) => String::from("Unknown browser"),
+	}
+}
+
+fn send_session_message(ctx: Scope<'_>, message: FromClientMessage) {
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to serialize session request", error));
+			return;
+		}
+	};
+
+	spawn_local_scoped(ctx, async move {
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+
+		if let Err(error) = ws.send(Message::Text(message_json)).await {
+			let data: &DataSignals = use_context(ctx);
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to send session request", error));
+		}
+	});
+}
+
+fn send_avatar_update(ctx: Scope<'_>, image: Option<String>) {
+	let message = FromClientMessage::UpdateAvatar(image);
+	let message_json = match serde_json::to_string(&message) {
+		Ok(msg) => msg,
+		Err(error) => {
+			let data: &DataSignals = use_context(ctx);
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to serialize avatar update", error));
+			return;
+		}
+	};
+
+	spawn_local_scoped(ctx, async move {
+		let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+		let mut ws = ws_context.lock().await;
+
+		if let Err(error) = ws.send(Message::Text(message_json)).await {
+			let data: &DataSignals = use_context(ctx);
+			data.errors
+				.modify()
+				.push(ErrorData::new_with_error("Failed to send avatar update", error));
+		}
+	});
+}
 
 #[component]
 pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
-	set_page_title("Profile | Stream Log");
+	set_page_title("Profile");
 
 	let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
 	let user_data = match (*user_signal.get()).clone() {
@@ -48,9 +145,8 @@ pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			let new_color = match color_from_rgb_str(color_signal.get().as_str()) {
 				Ok(color) => color,
 				Err(error) => {
-					let data: &RcSignal<DataSignals> = use_context(ctx);
-					data.get()
-						.errors
+					let data: &DataSignals = use_context(ctx);
+					data.errors
 						.modify()
 						.push(ErrorData::new_with_error("Failed to handle new color", error));
 					return;
@@ -66,8 +162,8 @@ pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			let message_json = match serde_json::to_string(&message) {
 				Ok(msg) => msg,
 				Err(error) => {
-					let data: &RcSignal<DataSignals> = use_context(ctx);
-					data.get().errors.modify().push(ErrorData::new_with_error(
+					let data: &DataSignals = use_context(ctx);
+					data.errors.modify().push(ErrorData::new_with_error(
 						"Failed to serialize user color update request",
 						error,
 					));
@@ -82,8 +178,8 @@ pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
 					let mut ws = ws_context.lock().await;
 
 					if let Err(error) = ws.send(Message::Text(message_json)).await {
-						let data: &RcSignal<DataSignals> = use_context(ctx);
-						data.get().errors.modify().push(ErrorData::new_with_error(
+						let data: &DataSignals = use_context(ctx);
+						data.errors.modify().push(ErrorData::new_with_error(
 							"Failed to send user color update request",
 							error,
 						));
@@ -99,9 +195,351 @@ pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
 		}
 	};
 
+	let current_username = create_memo(ctx, || {
+		user_signal
+			.get()
+			.as_ref()
+			.as_ref()
+			.map(|user| user.username.clone())
+			.unwrap_or_default()
+	});
+	let new_username_signal = create_signal(ctx, user_data.username.clone());
+	let new_username_error_signal = create_signal(ctx, String::new());
+	let submit_username_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let new_username = new_username_signal.get().trim().to_string();
+		if new_username.is_empty() {
+			new_username_error_signal.set(String::from("Enter a username."));
+			return;
+		}
+		if new_username.len() > USERNAME_LENGTH_LIMIT {
+			new_username_error_signal.set(format!(
+				"Usernames can't be longer than {} characters.",
+				USERNAME_LENGTH_LIMIT
+			));
+			return;
+		}
+		if new_username == *current_username.get() {
+			new_username_error_signal.set(String::from("That's already your username."));
+			return;
+		}
+		new_username_error_signal.set(String::new());
+
+		let message = FromClientMessage::UpdateUsername(new_username);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize username change request",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to send username change request",
+					error,
+				));
+			}
+		});
+	};
+
+	let current_public_user = create_memo(ctx, || {
+		(*user_signal.get())
+			.clone()
+			.map(|user| -> PublicUserData { user.into() })
+	});
+	let has_uploaded_avatar = create_memo(ctx, || {
+		user_signal
+			.get()
+			.as_ref()
+			.as_ref()
+			.is_some_and(|user| user.avatar_updated_at.is_some())
+	});
+	let avatar_file_ref = create_node_ref(ctx);
+	let avatar_error_signal = create_signal(ctx, String::new());
+	// Filled in by the file reader once it has read the chosen file
+	let avatar_upload_data: RcSignal<Option<String>> = create_rc_signal(None);
+	create_effect(ctx, {
+		let avatar_upload_data = avatar_upload_data.clone();
+		move || {
+			if let Some(image) = (*avatar_upload_data.get()).clone() {
+				send_avatar_update(ctx, Some(image));
+			}
+		}
+	});
+	let submit_avatar_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let avatar_file_node: DomNode = avatar_file_ref.get();
+		let avatar_file_field: HtmlInputElement = avatar_file_node.unchecked_into();
+		let Some(file) = avatar_file_field.files().and_then(|files| files.get(0)) else {
+			avatar_error_signal.set(String::from("Choose an image to upload."));
+			return;
+		};
+		if file.size() > AVATAR_SIZE_LIMIT as f64 {
+			avatar_error_signal.set(format!(
+				"Avatar images can't be larger than {} KiB.",
+				AVATAR_SIZE_LIMIT / 1024
+			));
+			return;
+		}
+		avatar_error_signal.set(String::new());
+
+		let reader = match FileReader::new() {
+			Ok(reader) => reader,
+			Err(_) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new("Failed to set up reading the avatar image."));
+				return;
+			}
+		};
+		let load_listener = {
+			let reader = reader.clone();
+			let avatar_upload_data = avatar_upload_data.clone();
+			Closure::once_into_js(move || {
+				// The file is read as a data URL, which has the base64-encoded file contents after the comma
+				let image = reader
+					.result()
+					.ok()
+					.and_then(|result| result.as_string())
+					.and_then(|data_url| data_url.split_once(',').map(|(_, image)| image.to_string()));
+				avatar_upload_data.set(image);
+			})
+		};
+		reader.set_onload(Some(load_listener.unchecked_ref()));
+		if reader.read_as_data_url(&file).is_err() {
+			let data: &DataSignals = use_context(ctx);
+			data.errors
+				.modify()
+				.push(ErrorData::new("Failed to read the avatar image."));
+			return;
+		}
+		avatar_file_field.set_value("");
+	};
+	let remove_avatar_handler = move |_event: WebEvent| {
+		avatar_error_signal.set(String::new());
+		send_avatar_update(ctx, None);
+	};
+
+	let notification_preferences = user_data.notification_preferences.clone();
+	let editor_assigned_sound_signal = create_signal(ctx, notification_preferences.editor_assigned_sound);
+	let editor_assigned_desktop_signal = create_signal(ctx, notification_preferences.editor_assigned_desktop);
+	let typing_in_edited_entry_sound_signal = create_signal(ctx, notification_preferences.typing_in_edited_entry_sound);
+	let typing_in_edited_entry_desktop_signal =
+		create_signal(ctx, notification_preferences.typing_in_edited_entry_desktop);
+	let other_notifications_sound_signal = create_signal(ctx, notification_preferences.other_notifications_sound);
+	let other_notifications_desktop_signal = create_signal(ctx, notification_preferences.other_notifications_desktop);
+	let notification_preferences_saved = create_signal(ctx, false);
+	let desktop_notifications_blocked = create_signal(ctx, false);
+	let submit_notification_preferences_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let preferences = NotificationPreferences {
+			editor_assigned_sound: *editor_assigned_sound_signal.get(),
+			editor_assigned_desktop: *editor_assigned_desktop_signal.get(),
+			typing_in_edited_entry_sound: *typing_in_edited_entry_sound_signal.get(),
+			typing_in_edited_entry_desktop: *typing_in_edited_entry_desktop_signal.get(),
+			other_notifications_sound: *other_notifications_sound_signal.get(),
+			other_notifications_desktop: *other_notifications_desktop_signal.get(),
+		};
+		if preferences.uses_desktop_notifications() {
+			request_desktop_notification_permission();
+		}
+		desktop_notifications_blocked.set(preferences.uses_desktop_notifications() && desktop_notifications_denied());
+
+		let message = FromClientMessage::UpdateNotificationPreferences(preferences);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize notification preferences update",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to send notification preferences update",
+					error,
+				));
+				return;
+			}
+			notification_preferences_saved.set(true);
+		});
+	};
+
+	let time_display_mode_signal = create_signal(
+		ctx,
+		String::from(match user_data.time_display.as_ref() {
+			None => "event",
+			Some(TimeDisplay::EventRelative) => "relative",
+			Some(TimeDisplay::WallClock(_)) => "wall_clock",
+		}),
+	);
+	let time_display_zone_signal = create_signal(
+		ctx,
+		user_data
+			.time_display
+			.as_ref()
+			.and_then(|time_display| time_display.time_zone_name())
+			.unwrap_or_default()
+			.to_string(),
+	);
+	let time_display_error_signal = create_signal(ctx, String::new());
+	let time_display_saved = create_signal(ctx, false);
+	let time_zone_options = View::new_fragment(
+		time_zone_names()
+			.map(|time_zone| view! { ctx, option(value=time_zone) })
+			.collect(),
+	);
+	let submit_time_display_handler = move |event: WebEvent| {
+		event.prevent_default();
+
+		let time_display = match time_display_mode_signal.get().as_str() {
+			"relative" => Some(TimeDisplay::EventRelative),
+			"wall_clock" => Some(TimeDisplay::WallClock(
+				time_display_zone_signal.get().trim().to_string(),
+			)),
+			_ => None,
+		};
+		if let Some(Err(error)) = time_display.as_ref().map(|time_display| time_display.validate()) {
+			time_display_error_signal.set(error);
+			return;
+		}
+		time_display_error_signal.modify().clear();
+
+		let message = FromClientMessage::UpdateTimeDisplay(time_display);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize time display update",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send time display update", error));
+				return;
+			}
+			time_display_saved.set(true);
+		});
+	};
+
+	let data: &DataSignals = use_context(ctx);
+	let hotkeys_signal: &Signal<HashMap<HotkeyAction, Hotkey>> =
+		create_signal(ctx, effective_hotkeys(&data.hotkeys.get()));
+	let recording_hotkey_action: &Signal<Option<HotkeyAction>> = create_signal(ctx, None);
+	let hotkey_conflict_descriptions = create_memo(ctx, || {
+		let conflicts: Vec<String> = hotkey_conflicts(&hotkeys_signal.get())
+			.iter()
+			.map(|actions| {
+				let descriptions: Vec<&str> = actions.iter().map(|action| action.description()).collect();
+				format!("These actions use the same hotkey: {}", descriptions.join(", "))
+			})
+			.collect();
+		conflicts
+	});
+	let has_unusable_hotkey = create_memo(ctx, || hotkeys_signal.get().values().any(|hotkey| !hotkey.is_usable()));
+	let disable_hotkeys_save = create_memo(ctx, || {
+		!hotkey_conflict_descriptions.get().is_empty() || *has_unusable_hotkey.get()
+	});
+
+	let submit_hotkeys_handler = move |event: WebEvent| {
+		event.prevent_default();
+		recording_hotkey_action.set(None);
+
+		// Only hotkeys that differ from the defaults are saved so that users get any future changes to the defaults
+		let mut bindings: Vec<HotkeyBinding> = hotkeys_signal
+			.get()
+			.iter()
+			.filter(|(action, hotkey)| action.default_hotkey() != **hotkey)
+			.map(|(action, hotkey)| HotkeyBinding {
+				action: *action,
+				hotkey: hotkey.clone(),
+			})
+			.collect();
+		bindings.sort_by_key(|binding| binding.action);
+
+		let message = FromClientMessage::UpdateHotkeys(bindings);
+		let message_json = match serde_json::to_string(&message) {
+			Ok(msg) => msg,
+			Err(error) => {
+				let data: &DataSignals = use_context(ctx);
+				data.errors.modify().push(ErrorData::new_with_error(
+					"Failed to serialize hotkey update request",
+					error,
+				));
+				return;
+			}
+		};
+
+		spawn_local_scoped(ctx, async move {
+			let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+			let mut ws = ws_context.lock().await;
+
+			if let Err(error) = ws.send(Message::Text(message_json)).await {
+				let data: &DataSignals = use_context(ctx);
+				data.errors
+					.modify()
+					.push(ErrorData::new_with_error("Failed to send hotkey update request", error));
+			}
+		});
+	};
+
+	let reset_all_hotkeys_handler = |_event: WebEvent| {
+		recording_hotkey_action.set(None);
+		hotkeys_signal.set(effective_hotkeys(&[]));
+	};
+
+	let hotkey_actions = create_signal(ctx, HotkeyAction::all().to_vec());
+
+	data.user_sessions.set(None);
+	send_session_message(ctx, FromClientMessage::RequestSessions);
+	let sessions = create_memo(ctx, || (*data.user_sessions.get()).clone().unwrap_or_default());
+	let has_other_sessions = create_memo(ctx, || sessions.get().iter().any(|session| !session.is_current));
+	let revoke_other_sessions_handler =
+		move |_event: WebEvent| send_session_message(ctx, FromClientMessage::RevokeOtherSessions);
+
+	let tour_restart_requested = create_signal(ctx, false);
+	let restart_tour_handler = move |_event: WebEvent| {
+		send_guided_tour_completed(ctx, false);
+		tour_restart_requested.set(true);
+	};
+
 	view! {
 		ctx,
-		h1 { (user_data.username) }
+		h1 { (current_username.get()) }
 		form(id="user_profile_edit", on:submit=submit_profile_handler) {
 			ColorInputWithContrast(color=color_signal, username=username_signal, view_id="user_profile")
 			div {
@@ -112,5 +550,324 @@ pub fn UserProfileView<G: Html>(ctx: Scope<'_>) -> View<G> {
 			}
 			button(type="submit") { "Update" }
 		}
+		h2 { "Username" }
+		p {
+			"Your username is shown with everything you do in event logs. You can change it once every "
+			(USERNAME_CHANGE_COOLDOWN_DAYS)
+			" days."
+		}
+		form(id="user_profile_username", on:submit=submit_username_handler) {
+			input(
+				bind:value=new_username_signal,
+				maxlength=USERNAME_LENGTH_LIMIT,
+				class=if new_username_error_signal.get().is_empty() { "" } else { "error" }
+			)
+			button(type="submit") { "Change Username" }
+			span(class="input_error") { (new_username_error_signal.get()) }
+		}
+		h2 { "Avatar" }
+		p {
+			"Your avatar is shown next to your name in event logs. If you don't upload an image, a pattern generated for your account is used."
+		}
+		div(id="user_profile_avatar") {
+			(match (*current_public_user.get()).clone() {
+				Some(user) => view! { ctx, UserAvatar(user=user) },
+				None => view! { ctx, }
+			})
+		}
+		form(id="user_profile_avatar_upload", on:submit=submit_avatar_handler) {
+			input(
+				ref=avatar_file_ref,
+				type="file",
+				accept="image/png,image/jpeg,image/gif,image/webp",
+				class=if avatar_error_signal.get().is_empty() { "" } else { "error" }
+			)
+			button(type="submit") { "Upload Avatar" }
+			(if *has_uploaded_avatar.get() {
+				view! {
+					ctx,
+					button(type="button", on:click=remove_avatar_handler) { "Remove Avatar" }
+				}
+			} else {
+				view! { ctx, }
+			})
+			span(class="input_error") { (avatar_error_signal.get()) }
+		}
+		h2 { "Notifications" }
+		p {
+			"Sounds and desktop notifications are only given while you have Stream Log open."
+		}
+		form(id="user_profile_notifications", on:submit=submit_notification_preferences_handler) {
+			table {
+				tr {
+					th {}
+					th { "Play a sound" }
+					th { "Desktop notification" }
+				}
+				tr {
+					td { "When I'm made the editor of an entry" }
+					td { input(type="checkbox", bind:checked=editor_assigned_sound_signal) }
+					td { input(type="checkbox", bind:checked=editor_assigned_desktop_signal) }
+				}
+				tr {
+					td { "When someone else types in an entry I'm the editor of" }
+					td { input(type="checkbox", bind:checked=typing_in_edited_entry_sound_signal) }
+					td { input(type="checkbox", bind:checked=typing_in_edited_entry_desktop_signal) }
+				}
+				tr {
+					td { "For my other notifications" }
+					td { input(type="checkbox", bind:checked=other_notifications_sound_signal) }
+					td { input(type="checkbox", bind:checked=other_notifications_desktop_signal) }
+				}
+			}
+			button(type="submit") { "Save Notification Settings" }
+			(if *notification_preferences_saved.get() {
+				view! { ctx, span(id="user_profile_notifications_saved") { "Saved" } }
+			} else {
+				view! { ctx, }
+			})
+		}
+		(if *desktop_notifications_blocked.get() {
+			view! {
+				ctx,
+				p(id="user_profile_notifications_blocked") {
+					"Desktop notifications won't be shown until you allow them for this site in your browser."
+				}
+			}
+		} else {
+			view! { ctx, }
+		})
+		h2 { "Log Times" }
+		form(id="user_profile_time_display", on:submit=submit_time_display_handler) {
+			div {
+				label {
+					"Show times in event logs as: "
+					select(bind:value=time_display_mode_signal) {
+						option(value="event") { "The event's default" }
+						option(value="relative") { "Time since the event started" }
+						option(value="wall_clock") { "Time of day in a time zone" }
+					}
+				}
+			}
+			(if *time_display_mode_signal.get() == "wall_clock" {
+				let time_zone_options = time_zone_options.clone();
+				view! {
+					ctx,
+					div {
+						label {
+							"Time zone: "
+							input(
+								bind:value=time_display_zone_signal,
+								list="user_profile_time_zones",
+								placeholder="America/New_York",
+								class=if time_display_error_signal.get().is_empty() { "" } else { "error" }
+							)
+						}
+						datalist(id="user_profile_time_zones") {
+							(time_zone_options)
+						}
+						span(class="input_error") { (time_display_error_signal.get()) }
+					}
+				}
+			} else {
+				view! { ctx, }
+			})
+			button(type="submit") { "Save Time Display" }
+			(if *time_display_saved.get() {
+				view! { ctx, span(id="user_profile_time_display_saved") { "Saved" } }
+			} else {
+				view! { ctx, }
+			})
+		}
+		h2 { "Hotkeys" }
+		p {
+			"Hotkeys are matched by the position of the key on the keyboard, so they work the same way with any keyboard layout. Each hotkey must use Alt or Ctrl."
+		}
+		form(id="user_profile_hotkeys", on:submit=submit_hotkeys_handler) {
+			table {
+				Indexed(
+					iterable=hotkey_actions,
+					view=move |ctx, action| {
+						let is_recording = create_memo(ctx, move || *recording_hotkey_action.get() == Some(action));
+						let hotkey_text = create_memo(ctx, move || {
+							if *is_recording.get() {
+								String::from("Press a key combination...")
+							} else {
+								hotkeys_signal
+									.get()
+									.get(&action)
+									.map(|hotkey| hotkey.to_string())
+									.unwrap_or_default()
+							}
+						});
+						let is_unusable = create_memo(ctx, move || {
+							hotkeys_signal.get().get(&action).map(|hotkey| !hotkey.is_usable()).unwrap_or(false)
+						});
+
+						let record_handler = move |_event: WebEvent| {
+							if *is_recording.get() {
+								recording_hotkey_action.set(None);
+							} else {
+								recording_hotkey_action.set(Some(action));
+							}
+						};
+						let record_key_handler = move |event: WebEvent| {
+							if !*is_recording.get() {
+								return;
+							}
+							let key_event: KeyboardEvent = event.unchecked_into();
+							let code = key_event.code();
+
+							// Wait for a key to go with any modifier keys being held
+							if ["Alt", "Control", "Shift", "Meta", "OS"].iter().any(|modifier| code.starts_with(modifier)) {
+								return;
+							}
+							key_event.prevent_default();
+							if code == "Escape" {
+								recording_hotkey_action.set(None);
+								return;
+							}
+							if code == "Tab" {
+								return;
+							}
+
+							let hotkey = Hotkey {
+								code,
+								alt: key_event.alt_key(),
+								ctrl: key_event.ctrl_key(),
+								shift: key_event.shift_key(),
+							};
+							hotkeys_signal.modify().insert(action, hotkey);
+							recording_hotkey_action.set(None);
+						};
+						let reset_handler = move |_event: WebEvent| {
+							recording_hotkey_action.set(None);
+							hotkeys_signal.modify().insert(action, action.default_hotkey());
+						};
+
+						view! {
+							ctx,
+							tr {
+								td { (action.description()) }
+								td {
+									button(
+										type="button",
+										class=if *is_unusable.get() { "user_profile_hotkey user_profile_hotkey_unusable" } else { "user_profile_hotkey" },
+										on:click=record_handler,
+										on:keydown=record_key_handler
+									) {
+										(hotkey_text.get())
+									}
+								}
+								td {
+									button(type="button", on:click=reset_handler) { "Reset" }
+								}
+							}
+						}
+					}
+				)
+			}
+			ul(class="user_profile_hotkey_errors") {
+				Indexed(
+					iterable=hotkey_conflict_descriptions,
+					view=|ctx, conflict| {
+						view! {
+							ctx,
+							li { (conflict) }
+						}
+					}
+				)
+				(if *has_unusable_hotkey.get() {
+					view! {
+						ctx,
+						li { "Hotkeys must use Alt or Ctrl so that they don't get in the way of typing." }
+					}
+				} else {
+					view! { ctx, }
+				})
+			}
+			div {
+				button(type="submit", disabled=*disable_hotkeys_save.get()) { "Save Hotkeys" }
+				button(type="button", on:click=reset_all_hotkeys_handler) { "Reset All to Defaults" }
+			}
+		}
+		h2 { "Active Sessions" }
+		p {
+			"The browsers you're logged in with. Signing out of a session logs that browser out right away."
+		}
+		(if data.user_sessions.get().is_none() {
+			view! {
+				ctx,
+				p { "Loading sessions..." }
+			}
+		} else {
+			view! { ctx, }
+		})
+		table(id="user_profile_sessions") {
+			tr {
+				th { "Browser" }
+				th { "Signed In" }
+				th { "Last Active" }
+				th { }
+			}
+			Keyed(
+				iterable=sessions,
+				key=|session| session.id.clone(),
+				view=move |ctx, session: UserSession| {
+					let description = describe_user_agent(&session.user_agent);
+					let user_agent = session.user_agent.clone();
+					let is_current = session.is_current;
+					let created_at = format!("{}", session.created_at.format("%Y-%m-%d %H:%M UTC"));
+					let last_active = if session.is_connected {
+						String::from("Now")
+					} else {
+						format!("{}", session.last_active_at.format("%Y-%m-%d %H:%M UTC"))
+					};
+					let revoke_handler = {
+						let session_id = session.id.clone();
+						move |_event: WebEvent| {
+							send_session_message(ctx, FromClientMessage::RevokeSession(session_id.clone()))
+						}
+					};
+
+					view! {
+						ctx,
+						tr {
+							td(title=user_agent) {
+								(description)
+								(if is_current {
+									view! {
+										ctx,
+										span(class="user_profile_session_current") { " (this browser)" }
+									}
+								} else {
+									view! { ctx, }
+								})
+							}
+							td { (created_at) }
+							td { (last_active) }
+							td {
+								button(type="button", on:click=revoke_handler) { "Sign Out" }
+							}
+						}
+					}
+				}
+			)
+		}
+		button(type="button", disabled=!*has_other_sessions.get(), on:click=revoke_other_sessions_handler) {
+			"Sign Out All Other Sessions"
+		}
+		h2 { "Guided Tour" }
+		div(id="user_profile_guided_tour") {
+			button(type="button", disabled=*tour_restart_requested.get(), on:click=restart_tour_handler) { "Show the Guided Tour Again" }
+			(if *tour_restart_requested.get() {
+				view! {
+					ctx,
+					p { "The tour will start the next time you open an event log." }
+				}
+			} else {
+				view! { ctx, }
+			})
+		}
 	}
 }
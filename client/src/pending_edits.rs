@@ -0,0 +1,228 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::{
+	conflicting_entry_parts, entry_parts_match, EventSubscriptionUpdate, ModifiedEventLogEntryParts,
+};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionTargetUpdate;
+use stream_log_shared::messages::FromClientMessage;
+use web_sys::{window, Storage};
+
+/// Key under which entry changes that couldn't be sent are kept in the browser's local storage
+const STORAGE_KEY: &str = "stream_log_pending_entry_edits";
+
+/// A change to a log entry that couldn't be sent to the server. These are kept in local storage so that they survive
+/// both losing the connection and closing the page, and they're sent once the event's data has been loaded again.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct PendingEntryEdit {
+	pub event: Event,
+	/// The entry as it was shown to the user when they made the change, or None if it wasn't in the log yet
+	pub original_entry: Option<EventLogEntry>,
+	/// The entry with the user's change applied
+	pub entry: EventLogEntry,
+	pub modified_parts: Vec<ModifiedEventLogEntryParts>,
+	pub queued_at: DateTime<Utc>,
+}
+
+impl PendingEntryEdit {
+	/// Creates a pending edit from an entry update message. Returns None if the message isn't an entry update. The
+	/// message only identifies the version of the entry it's based on, so the original entry is left for the caller to
+	/// fill in.
+	pub fn from_message(message: &FromClientMessage) -> Option<Self> {
+		let FromClientMessage::SubscriptionMessage(subscription_update) = message else {
+			return None;
+		};
+		let SubscriptionTargetUpdate::EventUpdate(event, event_update) = subscription_update.as_ref() else {
+			return None;
+		};
+		let EventSubscriptionUpdate::UpdateLogEntry(entry, modified_parts, _) = event_update.as_ref() else {
+			return None;
+		};
+		Some(Self {
+			event: event.clone(),
+			original_entry: None,
+			entry: entry.clone(),
+			modified_parts: modified_parts.clone(),
+			queued_at: Utc::now(),
+		})
+	}
+
+	/// Gets the message to send to the server for this edit
+	pub fn message(&self) -> FromClientMessage {
+		FromClientMessage::SubscriptionMessage(Box::new(SubscriptionTargetUpdate::EventUpdate(
+			self.event.clone(),
+			Box::new(EventSubscriptionUpdate::UpdateLogEntry(
+				self.entry.clone(),
+				self.modified_parts.clone(),
+				self.original_entry.as_ref().and_then(|entry| entry.history_id.clone()),
+			)),
+		)))
+	}
+
+	/// Gets the parts of the entry this edit changes that someone else also changed to something different since the
+	/// edit was made
+	pub fn conflicting_parts(&self, current_entry: &EventLogEntry) -> Vec<ModifiedEventLogEntryParts> {
+		let Some(original_entry) = self.original_entry.as_ref() else {
+			return Vec::new();
+		};
+		conflicting_entry_parts(&self.modified_parts, original_entry, &self.entry, current_entry)
+	}
+
+	/// Checks whether the current entry already has every part this edit changes, meaning the server has applied it
+	pub fn is_applied_to(&self, current_entry: &EventLogEntry) -> bool {
+		self.modified_parts
+			.iter()
+			.all(|part| entry_parts_match(*part, &self.entry, current_entry))
+	}
+
+	fn is_same_edit(&self, other: &PendingEntryEdit) -> bool {
+		self.entry.id == other.entry.id && self.queued_at == other.queued_at
+	}
+}
+
+/// Copies the given part of one entry into another
+pub fn copy_entry_part(part: ModifiedEventLogEntryParts, from: &EventLogEntry, to: &mut EventLogEntry) {
+	match part {
+		ModifiedEventLogEntryParts::StartTime => to.start_time = from.start_time,
+		ModifiedEventLogEntryParts::EndTime => to.end_time = from.end_time,
+		ModifiedEventLogEntryParts::EntryType => to.entry_type.clone_from(&from.entry_type),
+		ModifiedEventLogEntryParts::Description => to.description.clone_from(&from.description),
+		ModifiedEventLogEntryParts::MediaLinks => to.media_links.clone_from(&from.media_links),
+		ModifiedEventLogEntryParts::SubmitterOrWinner => to.submitter_or_winner.clone_from(&from.submitter_or_winner),
+		ModifiedEventLogEntryParts::Tags => to.tags.clone_from(&from.tags),
+		ModifiedEventLogEntryParts::VideoEditState => to.video_edit_state = from.video_edit_state,
+		ModifiedEventLogEntryParts::PosterMoment => to.poster_moment = from.poster_moment,
+		ModifiedEventLogEntryParts::Notes => to.notes.clone_from(&from.notes),
+		ModifiedEventLogEntryParts::Editor => to.editor.clone_from(&from.editor),
+		ModifiedEventLogEntryParts::MissingGiveawayInfo => {
+			to.missing_giveaway_information = from.missing_giveaway_information
+		}
+		ModifiedEventLogEntryParts::SortKey => to.manual_sort_key = from.manual_sort_key,
+		ModifiedEventLogEntryParts::Parent => to.parent.clone_from(&from.parent),
+		ModifiedEventLogEntryParts::CustomFields => to.custom_fields.clone_from(&from.custom_fields),
+	}
+}
+
+/// Gets a name for an entry part suitable for showing to the user
+pub fn entry_part_name(part: ModifiedEventLogEntryParts) -> &'static str {
+	match part {
+		ModifiedEventLogEntryParts::StartTime => "start time",
+		ModifiedEventLogEntryParts::EndTime => "end time",
+		ModifiedEventLogEntryParts::EntryType => "type",
+		ModifiedEventLogEntryParts::Description => "description",
+		ModifiedEventLogEntryParts::MediaLinks => "media links",
+		ModifiedEventLogEntryParts::SubmitterOrWinner => "submitter/winner",
+		ModifiedEventLogEntryParts::Tags => "tags",
+		ModifiedEventLogEntryParts::VideoEditState => "video edit state",
+		ModifiedEventLogEntryParts::PosterMoment => "poster moment",
+		ModifiedEventLogEntryParts::Notes => "notes",
+		ModifiedEventLogEntryParts::Editor => "editor",
+		ModifiedEventLogEntryParts::MissingGiveawayInfo => "missing giveaway information",
+		ModifiedEventLogEntryParts::SortKey => "sort key",
+		ModifiedEventLogEntryParts::Parent => "parent",
+		ModifiedEventLogEntryParts::CustomFields => "custom fields",
+	}
+}
+
+fn local_storage() -> Option<Storage> {
+	window()?.local_storage().ok().flatten()
+}
+
+fn load_pending_edits() -> Vec<PendingEntryEdit> {
+	let Some(storage) = local_storage() else {
+		return Vec::new();
+	};
+	let Ok(Some(stored_edits)) = storage.get_item(STORAGE_KEY) else {
+		return Vec::new();
+	};
+	match serde_json::from_str(&stored_edits) {
+		Ok(edits) => edits,
+		Err(error) => {
+			log::warn!("Discarding unreadable stored entry changes: {}", error);
+			Vec::new()
+		}
+	}
+}
+
+fn store_pending_edits(edits: &[PendingEntryEdit]) {
+	let Some(storage) = local_storage() else {
+		log::warn!("Local storage is unavailable; changes made while offline will be lost if the page is closed");
+		return;
+	};
+	let store_result = if edits.is_empty() {
+		storage.remove_item(STORAGE_KEY)
+	} else {
+		match serde_json::to_string(edits) {
+			Ok(edits_json) => storage.set_item(STORAGE_KEY, &edits_json),
+			Err(error) => {
+				log::error!("Failed to serialize entry changes for storage: {}", error);
+				return;
+			}
+		}
+	};
+	if let Err(error) = store_result {
+		log::error!("Failed to store entry changes: {:?}", error);
+	}
+}
+
+/// Stores an entry change to be sent later
+pub fn add_pending_edit(edit: PendingEntryEdit) {
+	let mut edits = load_pending_edits();
+	edits.push(edit);
+	store_pending_edits(&edits);
+}
+
+/// Gets the number of stored entry changes for an event
+pub fn pending_edit_count(event_id: &str) -> usize {
+	load_pending_edits()
+		.iter()
+		.filter(|edit| edit.event.id == event_id)
+		.count()
+}
+
+/// Gets the stored entry changes for an event that were made before the given time. They stay stored until they're
+/// removed, so a change that's sent but never reaches the server is sent again later.
+pub fn pending_edits(event_id: &str, made_before: DateTime<Utc>) -> Vec<PendingEntryEdit> {
+	load_pending_edits()
+		.into_iter()
+		.filter(|edit| edit.event.id == event_id && edit.queued_at < made_before)
+		.collect()
+}
+
+/// Removes a stored entry change
+pub fn remove_pending_edit(edit: &PendingEntryEdit) {
+	let mut edits = load_pending_edits();
+	let stored_edit_count = edits.len();
+	edits.retain(|stored_edit| !stored_edit.is_same_edit(edit));
+	if edits.len() != stored_edit_count {
+		store_pending_edits(&edits);
+	}
+}
+
+/// Removes the stored entry changes for an event that the server has applied, based on the event's current entries.
+/// Returns whether any changes were removed.
+pub fn remove_applied_pending_edits(event_id: &str, entries: &[EventLogEntry]) -> bool {
+	let mut edits = load_pending_edits();
+	let stored_edit_count = edits.len();
+	edits.retain(|edit| {
+		if edit.event.id != event_id {
+			return true;
+		}
+		match entries.iter().find(|entry| entry.id == edit.entry.id) {
+			Some(current_entry) => !edit.is_applied_to(current_entry),
+			None => true,
+		}
+	});
+	if edits.len() == stored_edit_count {
+		return false;
+	}
+	store_pending_edits(&edits);
+	true
+}
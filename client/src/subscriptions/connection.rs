@@ -4,9 +4,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use gloo_timers::future::TimeoutFuture;
+
 #[derive(Clone, Copy, Debug)]
 pub enum ConnectionState {
 	Connected,
+	/// The connection is still open, but nothing has been received from the server for a while
+	Stale,
 	Reconnecting,
 	Lost,
 }
@@ -16,3 +20,35 @@ impl Default for ConnectionState {
 		Self::Connected
 	}
 }
+
+/// How long to wait before the first attempt to reconnect after the connection is lost
+const INITIAL_RECONNECT_DELAY_MS: u32 = 1000;
+
+/// The longest we'll wait between reconnection attempts
+const MAX_RECONNECT_DELAY_MS: u32 = 60_000;
+
+/// Tracks the delay between attempts to reconnect, doubling it after each failed attempt
+pub struct ReconnectBackoff {
+	delay_ms: u32,
+}
+
+impl Default for ReconnectBackoff {
+	fn default() -> Self {
+		Self {
+			delay_ms: INITIAL_RECONNECT_DELAY_MS,
+		}
+	}
+}
+
+impl ReconnectBackoff {
+	/// Waits before the next reconnection attempt
+	pub async fn wait(&mut self) {
+		TimeoutFuture::new(self.delay_ms).await;
+		self.delay_ms = (self.delay_ms * 2).min(MAX_RECONNECT_DELAY_MS);
+	}
+
+	/// Resets the delay after a successful reconnection
+	pub fn reset(&mut self) {
+		self.delay_ms = INITIAL_RECONNECT_DELAY_MS;
+	}
+}
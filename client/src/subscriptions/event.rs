@@ -6,26 +6,52 @@
 
 use chrono::{DateTime, Duration, Utc};
 use gloo_timers::callback::Interval;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use stream_log_shared::messages::custom_fields::CustomField;
+use stream_log_shared::messages::entry_templates::EntryTemplate;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::{EventLogEntry, EventLogTab, VideoEditState, VideoProcessingState};
+use stream_log_shared::messages::event_subscription::{EntryEditLock, EntryUpdateConflict};
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::feedback::EventFeedback;
 use stream_log_shared::messages::info_pages::InfoPage;
 use stream_log_shared::messages::permissions::PermissionLevel;
-use stream_log_shared::messages::tags::Tag;
+use stream_log_shared::messages::recurring_entries::RecurringEntry;
+use stream_log_shared::messages::tags::{PendingTagAction, Tag, VideoEditStateTagRule};
 use stream_log_shared::messages::user::PublicUserData;
+use stream_log_shared::messages::view_preferences::EventLogFilters;
 use sycamore::prelude::*;
 
+/// How long a typing notification is shown after it's received if it isn't updated or cleared. Notifications from users
+/// who disconnect without clearing them are cleared by the server, but this keeps any that are missed from sticking
+/// around.
+const TYPING_EXPIRE_SECONDS: i64 = 30;
+/// How often expired typing notifications are removed, in milliseconds
+const TYPING_EXPIRE_CHECK_INTERVAL_MS: u32 = 5000;
+/// How often expired entry edit locks are removed, in milliseconds. Locks are normally renewed or released long before
+/// they expire, so this only cleans up after clients that stopped responding.
+const ENTRY_LOCK_EXPIRE_CHECK_INTERVAL_MS: u32 = 10000;
+
 pub struct EventSubscriptionSignalsInitData {
 	pub event: Event,
 	pub permission: PermissionLevel,
 	pub entry_types: Vec<EntryType>,
 	pub tags: Vec<Tag>,
+	pub video_edit_state_rules: Vec<VideoEditStateTagRule>,
+	pub pending_tag_actions: Vec<PendingTagAction>,
+	pub feedback: Vec<EventFeedback>,
 	pub editors: Vec<PublicUserData>,
+	pub viewers: Vec<PublicUserData>,
+	pub entry_locks: Vec<EntryEditLock>,
 	pub info_pages: Vec<InfoPage>,
 	pub event_log_tabs: Vec<EventLogTab>,
+	pub entry_templates: Vec<EntryTemplate>,
+	pub recurring_entries: Vec<RecurringEntry>,
+	pub custom_fields: Vec<CustomField>,
 	pub event_log_entries: Vec<EventLogEntry>,
+	pub pending_entry_count: usize,
+	pub unloaded_tab_ids: Vec<String>,
 	pub new_event_log_entries: Vec<EventLogEntry>,
 }
 
@@ -35,57 +61,220 @@ pub struct EventSubscriptionSignals {
 	pub permission: RcSignal<PermissionLevel>,
 	pub entry_types: RcSignal<Vec<EntryType>>,
 	pub tags: RcSignal<Vec<Tag>>,
+	pub video_edit_state_rules: RcSignal<Vec<VideoEditStateTagRule>>,
+	pub pending_tag_actions: RcSignal<Vec<PendingTagAction>>,
+	/// Feedback sent for the event. This is only sent to supervisors.
+	pub feedback: RcSignal<Vec<EventFeedback>>,
 	pub editors: RcSignal<Vec<PublicUserData>>,
+	/// The users currently viewing the event
+	pub viewers: RcSignal<Vec<PublicUserData>>,
+	/// The entries currently being edited, by entry ID
+	pub entry_locks: RcSignal<HashMap<String, EntryEditLock>>,
+	_entry_lock_expire_interval: Rc<Interval>,
 	pub info_pages: RcSignal<Vec<InfoPage>>,
 	pub event_log_tabs: RcSignal<Vec<EventLogTab>>,
+	pub entry_templates: RcSignal<Vec<EntryTemplate>>,
+	pub recurring_entries: RcSignal<Vec<RecurringEntry>>,
+	/// The extra fields the event's entries have
+	pub custom_fields: RcSignal<Vec<CustomField>>,
 	pub event_log_entries: RcSignal<Vec<EventLogEntry>>,
+	/// The number of existing entries the server has yet to send us as part of the initial subscription load
+	pub pending_entry_count: RcSignal<usize>,
+	/// The IDs of tabs whose entries haven't been loaded from the server. The first tab has an empty ID.
+	pub unloaded_tab_ids: RcSignal<HashSet<String>>,
+	/// The IDs of unloaded tabs whose entries we've asked the server for
+	pub requested_tab_ids: RcSignal<HashSet<String>>,
 	pub new_event_log_entries: RcSignal<Vec<EventLogEntry>>,
+	/// When all of the event's entries were last loaded from the server, or None while they're being loaded
+	pub loaded_at: RcSignal<Option<DateTime<Utc>>>,
 	pub typing_events: RcSignal<Vec<TypingEvent>>,
+	/// Changes we made that the server didn't save because someone else changed the same parts of the entry first.
+	/// These are taken by the event log page to show to the user.
+	pub entry_update_conflicts: RcSignal<Vec<EntryUpdateConflict>>,
 	_typing_expire_interval: Rc<Interval>,
 	pub video_edit_state_filters: RcSignal<HashSet<VideoEditState>>,
 	pub video_processing_state_filters: RcSignal<HashSet<VideoProcessingState>>,
+	pub created_by_me_filter: RcSignal<bool>,
+	pub assigned_to_me_filter: RcSignal<bool>,
+	pub over_expected_duration_filter: RcSignal<bool>,
+	pub missing_giveaway_information_filter: RcSignal<bool>,
 }
 
 impl EventSubscriptionSignals {
 	pub fn new(init_data: EventSubscriptionSignalsInitData) -> Self {
 		let typing_events: RcSignal<Vec<TypingEvent>> = create_rc_signal(Vec::new());
-		let typing_expire_interval = Interval::new(10_000, {
+		let typing_expire_interval = Interval::new(TYPING_EXPIRE_CHECK_INTERVAL_MS, {
 			let typing_events = typing_events.clone();
 			move || {
-				let mut typing_events = typing_events.modify();
-				let expire_time = Utc::now() - Duration::seconds(30);
-				typing_events.retain(|event| event.time_received > expire_time);
+				let expire_time = Utc::now() - Duration::seconds(TYPING_EXPIRE_SECONDS);
+				// Only modifying the signal when something expires avoids updating everything that shows typing data
+				// every time this runs
+				let has_expired_events = typing_events
+					.get_untracked()
+					.iter()
+					.any(|event| event.time_received <= expire_time);
+				if has_expired_events {
+					typing_events.modify().retain(|event| event.time_received > expire_time);
+				}
 			}
 		});
 		let _typing_expire_interval = Rc::new(typing_expire_interval);
 
+		let entry_locks: RcSignal<HashMap<String, EntryEditLock>> = create_rc_signal(
+			init_data
+				.entry_locks
+				.into_iter()
+				.map(|lock| (lock.entry_id.clone(), lock))
+				.collect(),
+		);
+		let entry_lock_expire_interval = Interval::new(ENTRY_LOCK_EXPIRE_CHECK_INTERVAL_MS, {
+			let entry_locks = entry_locks.clone();
+			move || {
+				let now = Utc::now();
+				let has_expired_locks = entry_locks.get_untracked().values().any(|lock| lock.expires_at <= now);
+				if has_expired_locks {
+					entry_locks.modify().retain(|_, lock| lock.expires_at > now);
+				}
+			}
+		});
+		let _entry_lock_expire_interval = Rc::new(entry_lock_expire_interval);
+
 		let event = create_rc_signal(init_data.event);
 		let permission = create_rc_signal(init_data.permission);
 		let entry_types = create_rc_signal(init_data.entry_types);
 		let tags = create_rc_signal(init_data.tags);
+		let video_edit_state_rules = create_rc_signal(init_data.video_edit_state_rules);
+		let pending_tag_actions = create_rc_signal(init_data.pending_tag_actions);
+		let feedback = create_rc_signal(init_data.feedback);
+		let viewers = create_rc_signal(init_data.viewers);
 		let editors = create_rc_signal(init_data.editors);
 		let info_pages = create_rc_signal(init_data.info_pages);
 		let event_log_tabs = create_rc_signal(init_data.event_log_tabs);
+		let entry_templates = create_rc_signal(init_data.entry_templates);
+		let recurring_entries = create_rc_signal(init_data.recurring_entries);
+		let custom_fields = create_rc_signal(init_data.custom_fields);
 		let event_log_entries = create_rc_signal(init_data.event_log_entries);
+		let pending_entry_count = create_rc_signal(init_data.pending_entry_count);
+		let unloaded_tab_ids = create_rc_signal(init_data.unloaded_tab_ids.into_iter().collect());
+		let requested_tab_ids = create_rc_signal(HashSet::new());
 		let new_event_log_entries = create_rc_signal(init_data.new_event_log_entries);
+		let loaded_at = create_rc_signal(None);
+		let entry_update_conflicts = create_rc_signal(Vec::new());
 
 		let video_edit_state_filters = create_rc_signal(HashSet::new());
 		let video_processing_state_filters = create_rc_signal(HashSet::new());
+		let created_by_me_filter = create_rc_signal(false);
+		let assigned_to_me_filter = create_rc_signal(false);
+		let over_expected_duration_filter = create_rc_signal(false);
+		let missing_giveaway_information_filter = create_rc_signal(false);
 
 		Self {
 			event,
 			permission,
 			entry_types,
 			tags,
+			video_edit_state_rules,
+			pending_tag_actions,
+			feedback,
+			viewers,
+			entry_locks,
+			_entry_lock_expire_interval,
 			editors,
 			info_pages,
 			event_log_tabs,
+			entry_templates,
+			recurring_entries,
+			custom_fields,
 			event_log_entries,
+			pending_entry_count,
+			unloaded_tab_ids,
+			requested_tab_ids,
 			new_event_log_entries,
+			loaded_at,
 			typing_events,
+			entry_update_conflicts,
 			_typing_expire_interval,
 			video_edit_state_filters,
 			video_processing_state_filters,
+			created_by_me_filter,
+			assigned_to_me_filter,
+			over_expected_duration_filter,
+			missing_giveaway_information_filter,
+		}
+	}
+
+	/// Gets the filters currently applied to the event log. When used in a reactive scope, the scope tracks all of the
+	/// filters.
+	pub fn filters(&self) -> EventLogFilters {
+		EventLogFilters {
+			video_edit_states: (*self.video_edit_state_filters.get()).clone(),
+			video_processing_states: (*self.video_processing_state_filters.get()).clone(),
+			created_by_me: *self.created_by_me_filter.get(),
+			assigned_to_me: *self.assigned_to_me_filter.get(),
+			over_expected_duration: *self.over_expected_duration_filter.get(),
+			missing_giveaway_information: *self.missing_giveaway_information_filter.get(),
+		}
+	}
+
+	/// Replaces the copies of a user's public data in the event's data with the given data. Signals are only modified
+	/// if they have a copy to replace.
+	pub fn update_public_user(&self, user: &PublicUserData) {
+		let replace_user = |existing_user: &mut PublicUserData| {
+			if existing_user.id == user.id {
+				*existing_user = user.clone();
+			}
+		};
+
+		for users in [&self.editors, &self.viewers] {
+			if users
+				.get_untracked()
+				.iter()
+				.any(|existing_user| existing_user.id == user.id)
+			{
+				users.modify().iter_mut().for_each(replace_user);
+			}
+		}
+
+		let entry_has_user = |entry: &EventLogEntry| {
+			entry.editor.as_ref().is_some_and(|editor| editor.id == user.id)
+				|| entry
+					.created_by
+					.as_ref()
+					.is_some_and(|created_by| created_by.id == user.id)
+		};
+		for entries in [&self.event_log_entries, &self.new_event_log_entries] {
+			if entries.get_untracked().iter().any(entry_has_user) {
+				for entry in entries.modify().iter_mut() {
+					if let Some(editor) = entry.editor.as_mut() {
+						replace_user(editor);
+					}
+					if let Some(created_by) = entry.created_by.as_mut() {
+						replace_user(created_by);
+					}
+				}
+			}
+		}
+
+		if self
+			.typing_events
+			.get_untracked()
+			.iter()
+			.any(|typing_event| typing_event.user.id == user.id)
+		{
+			for typing_event in self.typing_events.modify().iter_mut() {
+				replace_user(&mut typing_event.user);
+			}
+		}
+
+		if self
+			.entry_locks
+			.get_untracked()
+			.values()
+			.any(|lock| lock.user.id == user.id)
+		{
+			for lock in self.entry_locks.modify().values_mut() {
+				replace_user(&mut lock.user);
+			}
 		}
 	}
 }
@@ -100,6 +289,8 @@ pub enum TypingTarget {
 	MediaLink,
 	SubmitterWinner,
 	Notes,
+	Tags,
+	SortKey,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,59 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionState;
+use super::DataSignals;
+use crate::websocket::WebSocketSendStream;
+use chrono::{Duration, Utc};
+use futures::lock::Mutex;
+use gloo_net::websocket::Message;
+use gloo_timers::future::TimeoutFuture;
+use stream_log_shared::messages::FromClientMessage;
+use sycamore::prelude::*;
+
+/// How often a keepalive message is sent to the server
+const KEEPALIVE_INTERVAL_MS: u32 = 20_000;
+
+/// How long we can go without receiving anything from the server before the connection is considered stale. This is
+/// long enough for a couple of keepalive messages to be missed.
+const STALE_CONNECTION_SECONDS: i64 = 60;
+
+/// Periodically sends keepalive messages to the server and marks the connection as stale when the server hasn't sent
+/// anything (including responses to those messages) for too long. Runs until the connection is lost.
+pub async fn keep_connection_alive(ctx: Scope<'_>) {
+	let data_signals: &DataSignals = use_context(ctx);
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+
+	loop {
+		TimeoutFuture::new(KEEPALIVE_INTERVAL_MS).await;
+
+		match *data_signals.connection_state.get_untracked() {
+			ConnectionState::Connected | ConnectionState::Stale => (),
+			ConnectionState::Reconnecting => continue,
+			ConnectionState::Lost => break,
+		}
+
+		let time_since_last_message = Utc::now() - *data_signals.last_message_time.get_untracked();
+		if time_since_last_message > Duration::seconds(STALE_CONNECTION_SECONDS) {
+			if let ConnectionState::Connected = *data_signals.connection_state.get_untracked() {
+				log::info!("Haven't heard from the server in a while; marking connection as stale");
+				data_signals.connection_state.set(ConnectionState::Stale);
+			}
+		}
+
+		let message = match serde_json::to_string(&FromClientMessage::KeepAlive) {
+			Ok(msg) => msg,
+			Err(error) => {
+				log::error!("Failed to serialize keepalive message: {}", error);
+				continue;
+			}
+		};
+		let mut ws = ws_context.lock().await;
+		if let Err(error) = ws.send(Message::Text(message)).await {
+			log::warn!("Failed to send keepalive message: {}", error);
+		}
+	}
+}
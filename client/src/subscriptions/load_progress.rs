@@ -0,0 +1,15 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+/// Progress of processing a large amount of data from the server
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoadProgress {
+	/// A large message was received and is being read. Contains the size of the message in bytes.
+	ReadingMessage(usize),
+	/// The log entries for an event are being added to the page. Contains the name of the event, the number of entries
+	/// loaded so far, and the total number of entries.
+	EventEntries(String, usize, usize),
+}
@@ -4,15 +4,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::stores::SubscriptionStores;
 use crate::websocket::WebSocketSendStream;
 use futures::stream::SplitSink;
 use futures::SinkExt;
 use gloo_net::websocket::futures::WebSocket;
 use gloo_net::websocket::{Message, WebSocketError};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::subscriptions::{SubscriptionSequence, SubscriptionType};
 use stream_log_shared::messages::FromClientMessage;
 
 pub enum SubscriptionError {
@@ -41,18 +42,52 @@ impl fmt::Display for SubscriptionError {
 	}
 }
 
-#[derive(Debug, Default)]
 pub struct SubscriptionManager {
 	active_subscriptions: HashMap<SubscriptionType, u32>,
 	requested_subscriptions: HashMap<SubscriptionType, u32>,
+	/// Subscriptions kept regardless of which page is shown. These aren't ended when the page changes the set of
+	/// subscriptions or when subscriptions are released.
+	persistent_subscriptions: HashSet<SubscriptionType>,
+	/// The sequence number of the last message received for each active subscription
+	sequence_numbers: HashMap<SubscriptionType, u64>,
+	/// Identifies the most recently scheduled release of all subscriptions, if that release is still pending. Any
+	/// change to the set of subscriptions cancels a pending release.
+	pending_release: Option<u32>,
+	/// Used to generate identifiers for scheduled subscription releases
+	release_counter: u32,
+	/// The data for each subscription, which is dropped when the subscription ends
+	stores: SubscriptionStores,
 }
 
 impl SubscriptionManager {
+	pub fn new(stores: SubscriptionStores) -> Self {
+		Self {
+			active_subscriptions: HashMap::new(),
+			requested_subscriptions: HashMap::new(),
+			persistent_subscriptions: HashSet::new(),
+			sequence_numbers: HashMap::new(),
+			pending_release: None,
+			release_counter: 0,
+			stores,
+		}
+	}
+
+	/// Gets the message that starts a subscription. For synced lists we already have a copy of, this asks the server
+	/// for only what changed since our copy.
+	fn start_subscription_message(&self, subscription_type: &SubscriptionType) -> FromClientMessage {
+		match self.stores.list_version(subscription_type) {
+			Some(list_version) => FromClientMessage::ResumeSubscription(subscription_type.clone(), list_version),
+			None => FromClientMessage::StartSubscription(subscription_type.clone()),
+		}
+	}
+
 	/// Removes a subscription for data.
 	pub fn remove_subscription(&mut self, subscription_type: SubscriptionType) {
 		if let Entry::Occupied(mut active_entry) = self.active_subscriptions.entry(subscription_type.clone()) {
 			let current_count = *active_entry.get() - 1;
 			if current_count == 0 {
+				self.sequence_numbers.remove(active_entry.key());
+				self.stores.release(active_entry.key());
 				active_entry.remove();
 				true
 			} else {
@@ -62,6 +97,7 @@ impl SubscriptionManager {
 		} else if let Entry::Occupied(mut requested_entry) = self.requested_subscriptions.entry(subscription_type) {
 			let current_count = *requested_entry.get() - 1;
 			if current_count == 0 {
+				self.stores.release(requested_entry.key());
 				requested_entry.remove();
 				true
 			} else {
@@ -73,31 +109,65 @@ impl SubscriptionManager {
 		};
 	}
 
-	/// Changes the current set of subscriptions so that it contains only the one specified subscription type.
+	/// Adds a subscription that's kept when pages change their subscriptions. Does nothing if the subscription is
+	/// already persistent.
+	pub async fn add_persistent_subscription(
+		&mut self,
+		subscription_type: SubscriptionType,
+		stream: &mut WebSocketSendStream,
+	) -> Result<(), SubscriptionError> {
+		if !self.persistent_subscriptions.insert(subscription_type.clone()) {
+			return Ok(());
+		}
+		if self.active_subscriptions.contains_key(&subscription_type)
+			|| self.requested_subscriptions.contains_key(&subscription_type)
+		{
+			return Ok(());
+		}
+
+		let subscription_message = self.start_subscription_message(&subscription_type);
+		let subscription_message_json = serde_json::to_string(&subscription_message)?;
+		stream.send(Message::Text(subscription_message_json)).await?;
+		self.requested_subscriptions.insert(subscription_type, 1);
+
+		Ok(())
+	}
+
+	/// Changes the current set of subscriptions so that it contains only the one specified subscription type (along
+	/// with any persistent subscriptions).
 	pub async fn set_subscription(
 		&mut self,
 		subscription_type: SubscriptionType,
 		stream: &mut WebSocketSendStream,
 	) -> Result<(), SubscriptionError> {
+		self.pending_release = None;
+
 		let mut new_active_subscriptions: HashMap<SubscriptionType, u32> = HashMap::new();
 		let mut new_requested_subscriptions: HashMap<SubscriptionType, u32> = HashMap::new();
 		let mut unsubscription_messages: Vec<Message> = Vec::new();
-		for (current_subscription, _) in self.active_subscriptions.iter() {
+		let mut ended_subscriptions: Vec<SubscriptionType> = Vec::new();
+		for (current_subscription, count) in self.active_subscriptions.iter() {
 			if *current_subscription == subscription_type {
 				new_active_subscriptions.insert(current_subscription.clone(), 1);
+			} else if self.persistent_subscriptions.contains(current_subscription) {
+				new_active_subscriptions.insert(current_subscription.clone(), *count);
 			} else {
 				let unsubscription_message = FromClientMessage::EndSubscription(current_subscription.clone());
 				let unsubscription_message_json = serde_json::to_string(&unsubscription_message)?;
 				unsubscription_messages.push(Message::Text(unsubscription_message_json));
+				ended_subscriptions.push(current_subscription.clone());
 			}
 		}
-		for (current_subscription, _) in self.requested_subscriptions.iter() {
+		for (current_subscription, count) in self.requested_subscriptions.iter() {
 			if *current_subscription == subscription_type {
 				new_requested_subscriptions.insert(current_subscription.clone(), 1);
+			} else if self.persistent_subscriptions.contains(current_subscription) {
+				new_requested_subscriptions.insert(current_subscription.clone(), *count);
 			} else {
 				let unsubscription_message = FromClientMessage::EndSubscription(current_subscription.clone());
 				let unsubscription_message_json = serde_json::to_string(&unsubscription_message)?;
 				unsubscription_messages.push(Message::Text(unsubscription_message_json));
+				ended_subscriptions.push(current_subscription.clone());
 			}
 		}
 
@@ -105,9 +175,16 @@ impl SubscriptionManager {
 
 		self.active_subscriptions = new_active_subscriptions;
 		self.requested_subscriptions = new_requested_subscriptions;
+		self.sequence_numbers
+			.retain(|subscription, _| self.active_subscriptions.contains_key(subscription));
+		for subscription in ended_subscriptions.iter() {
+			self.stores.release(subscription);
+		}
 
-		if self.active_subscriptions.is_empty() && self.requested_subscriptions.is_empty() {
-			let subscription_message = FromClientMessage::StartSubscription(subscription_type.clone());
+		if !self.active_subscriptions.contains_key(&subscription_type)
+			&& !self.requested_subscriptions.contains_key(&subscription_type)
+		{
+			let subscription_message = self.start_subscription_message(&subscription_type);
 			let subscription_message_json = serde_json::to_string(&subscription_message)?;
 			stream.send(Message::Text(subscription_message_json)).await?;
 			self.requested_subscriptions.insert(subscription_type, 1);
@@ -122,36 +199,45 @@ impl SubscriptionManager {
 		subscription_types: Vec<SubscriptionType>,
 		stream: &mut WebSocketSendStream,
 	) -> Result<(), SubscriptionError> {
+		self.pending_release = None;
+
 		let mut subscription_update_messages: Vec<Message> = Vec::new();
+		let mut ended_subscriptions: Vec<SubscriptionType> = Vec::new();
 		let mut new_subscriptions: HashMap<SubscriptionType, u32> = HashMap::new();
 		for subscription in subscription_types {
 			*new_subscriptions.entry(subscription).or_default() += 1;
 		}
 
 		let mut new_active_subscriptions = HashMap::new();
-		for current_subscription in self.active_subscriptions.keys() {
+		for (current_subscription, count) in self.active_subscriptions.iter() {
 			if let Some(new_count) = new_subscriptions.remove(current_subscription) {
 				new_active_subscriptions.insert(current_subscription.clone(), new_count);
+			} else if self.persistent_subscriptions.contains(current_subscription) {
+				new_active_subscriptions.insert(current_subscription.clone(), *count);
 			} else {
 				let unsubscription_message = FromClientMessage::EndSubscription(current_subscription.clone());
 				let unsubscription_message_json = serde_json::to_string(&unsubscription_message)?;
 				subscription_update_messages.push(Message::Text(unsubscription_message_json));
+				ended_subscriptions.push(current_subscription.clone());
 			}
 		}
 
 		let mut new_requested_subscriptions = HashMap::new();
-		for current_subscription in self.requested_subscriptions.keys() {
+		for (current_subscription, count) in self.requested_subscriptions.iter() {
 			if let Some(new_count) = new_subscriptions.remove(current_subscription) {
 				new_requested_subscriptions.insert(current_subscription.clone(), new_count);
+			} else if self.persistent_subscriptions.contains(current_subscription) {
+				new_requested_subscriptions.insert(current_subscription.clone(), *count);
 			} else {
 				let unsubscription_message = FromClientMessage::EndSubscription(current_subscription.clone());
 				let unsubscription_message_json = serde_json::to_string(&unsubscription_message)?;
 				subscription_update_messages.push(Message::Text(unsubscription_message_json));
+				ended_subscriptions.push(current_subscription.clone());
 			}
 		}
 
 		for (new_subscription, new_count) in new_subscriptions.into_iter() {
-			let subscription_message = FromClientMessage::StartSubscription(new_subscription.clone());
+			let subscription_message = self.start_subscription_message(&new_subscription);
 			let subscription_message_json = serde_json::to_string(&subscription_message)?;
 			subscription_update_messages.push(Message::Text(subscription_message_json));
 			new_requested_subscriptions.insert(new_subscription, new_count);
@@ -161,24 +247,83 @@ impl SubscriptionManager {
 
 		self.active_subscriptions = new_active_subscriptions;
 		self.requested_subscriptions = new_requested_subscriptions;
+		self.sequence_numbers
+			.retain(|subscription, _| self.active_subscriptions.contains_key(subscription));
+		for subscription in ended_subscriptions.iter() {
+			self.stores.release(subscription);
+		}
+
+		Ok(())
+	}
+
+	/// Schedules all current subscriptions to be released. Returns an identifier for the scheduled release to be passed
+	/// to [Self::release_scheduled] when it's time to release them.
+	pub fn schedule_release(&mut self) -> u32 {
+		self.release_counter = self.release_counter.wrapping_add(1);
+		self.pending_release = Some(self.release_counter);
+		self.release_counter
+	}
+
+	/// Ends all current subscriptions if the identified scheduled release wasn't cancelled or replaced by a later
+	/// one.
+	pub async fn release_scheduled(
+		&mut self,
+		release_id: u32,
+		stream: &mut WebSocketSendStream,
+	) -> Result<(), SubscriptionError> {
+		if self.pending_release != Some(release_id) {
+			return Ok(());
+		}
+		self.pending_release = None;
+
+		let released_subscriptions: Vec<SubscriptionType> = self
+			.active_subscriptions
+			.keys()
+			.chain(self.requested_subscriptions.keys())
+			.filter(|subscription| !self.persistent_subscriptions.contains(*subscription))
+			.cloned()
+			.collect();
+		let mut unsubscription_messages: Vec<Message> = Vec::with_capacity(released_subscriptions.len());
+		for subscription in released_subscriptions.iter() {
+			let unsubscription_message = FromClientMessage::EndSubscription(subscription.clone());
+			let unsubscription_message_json = serde_json::to_string(&unsubscription_message)?;
+			unsubscription_messages.push(Message::Text(unsubscription_message_json));
+		}
+
+		stream.send_multiple(unsubscription_messages).await?;
+
+		for subscription in released_subscriptions.iter() {
+			self.active_subscriptions.remove(subscription);
+			self.requested_subscriptions.remove(subscription);
+			self.sequence_numbers.remove(subscription);
+			self.stores.release(subscription);
+		}
 
 		Ok(())
 	}
 
 	/// To be called when a subscription confirmation is received from the server. Updates tracking from requested subscription to active.
-	pub fn subscription_confirmation_received(&mut self, subscription_type: SubscriptionType) {
+	/// Returns whether we still want the subscription, and so whether its data should be kept.
+	pub fn subscription_confirmation_received(&mut self, subscription_type: SubscriptionType) -> bool {
 		let subscription_count = self.requested_subscriptions.remove(&subscription_type);
 
 		// If we don't have a subscription count, we already got an unsubscription request and sent the unsubscribe.
 		// In this case, we don't do anything here.
-		if let Some(count) = subscription_count {
-			self.active_subscriptions.insert(subscription_type, count);
+		match subscription_count {
+			Some(count) => {
+				self.sequence_numbers.insert(subscription_type.clone(), 0);
+				self.active_subscriptions.insert(subscription_type, count);
+				true
+			}
+			None => false,
 		}
 	}
 
 	/// To be called when a subscription failure message is received from the server. Removes requested subscription.
 	pub fn subscription_failure_received(&mut self, subscription_type: SubscriptionType) {
+		self.persistent_subscriptions.remove(&subscription_type);
 		self.requested_subscriptions.remove(&subscription_type);
+		self.stores.release(&subscription_type);
 	}
 
 	pub async fn resend_subscriptions(
@@ -186,16 +331,74 @@ impl SubscriptionManager {
 		stream: &mut SplitSink<WebSocket, Message>,
 	) -> Result<(), SubscriptionError> {
 		let active_subscriptions = std::mem::take(&mut self.active_subscriptions);
+		self.sequence_numbers.clear();
 		for (subscription, count) in active_subscriptions {
 			*self.requested_subscriptions.entry(subscription).or_default() += count;
 		}
 
 		for new_subscription in self.requested_subscriptions.keys() {
-			let subscription_message = FromClientMessage::StartSubscription(new_subscription.clone());
+			let subscription_message = self.start_subscription_message(new_subscription);
 			let subscription_message_json = serde_json::to_string(&subscription_message)?;
 			stream.feed(Message::Text(subscription_message_json)).await?;
 		}
 		stream.flush().await?;
 		Ok(())
 	}
+
+	/// Records the sequence number of a received subscription message. Returns whether the message directly follows the
+	/// last message received for the subscription. Messages for subscriptions that haven't been confirmed yet are
+	/// always considered to be in order, as the initial subscription load will replace any data they contain.
+	pub fn record_message_sequence(&mut self, sequence: &SubscriptionSequence) -> bool {
+		let Some(last_number) = self.sequence_numbers.get_mut(&sequence.subscription_type) else {
+			return true;
+		};
+		if sequence.number == *last_number + 1 {
+			*last_number = sequence.number;
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Requests a fresh copy of the data for an active subscription, such as after messages for it were missed.
+	pub async fn resync_subscription(
+		&mut self,
+		subscription_type: SubscriptionType,
+		stream: &mut WebSocketSendStream,
+	) -> Result<(), SubscriptionError> {
+		let Some(count) = self.active_subscriptions.remove(&subscription_type) else {
+			return Ok(());
+		};
+		self.sequence_numbers.remove(&subscription_type);
+
+		let subscription_message = self.start_subscription_message(&subscription_type);
+		let subscription_message_json = serde_json::to_string(&subscription_message)?;
+		stream.send(Message::Text(subscription_message_json)).await?;
+		*self.requested_subscriptions.entry(subscription_type).or_default() += count;
+
+		Ok(())
+	}
+
+	/// Requests a fresh copy of the data for all active subscriptions. Used when the user wants to make sure they have
+	/// the latest data without reloading the page.
+	pub async fn resync_all_subscriptions(
+		&mut self,
+		stream: &mut WebSocketSendStream,
+	) -> Result<(), SubscriptionError> {
+		let mut subscription_messages: Vec<Message> = Vec::with_capacity(self.active_subscriptions.len());
+		for subscription_type in self.active_subscriptions.keys() {
+			let subscription_message = FromClientMessage::StartSubscription(subscription_type.clone());
+			let subscription_message_json = serde_json::to_string(&subscription_message)?;
+			subscription_messages.push(Message::Text(subscription_message_json));
+		}
+		stream.send_multiple(subscription_messages).await?;
+
+		let active_subscriptions = std::mem::take(&mut self.active_subscriptions);
+		self.sequence_numbers.clear();
+		for (subscription_type, count) in active_subscriptions {
+			*self.requested_subscriptions.entry(subscription_type).or_default() += count;
+		}
+
+		Ok(())
+	}
 }
@@ -4,40 +4,51 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::websocket::{read_websocket, websocket_endpoint, WebSocketSendStream};
-use chrono::Utc;
+use crate::event_cache::remove_cached_event;
+use crate::notifications::{alert_for_notification, alert_for_typing};
+use crate::websocket::{
+	parse_websocket_message, read_websocket, read_websocket_text, websocket_endpoint, WebSocketReadError,
+	WebSocketSendStream, LARGE_MESSAGE_SIZE,
+};
+use chrono::{DateTime, Utc};
 use futures::lock::Mutex;
 use futures::stream::SplitStream;
 use futures::task::Waker;
 use futures::StreamExt;
 use gloo_net::websocket::futures::WebSocket;
+use gloo_timers::callback::Timeout;
 use gloo_timers::future::TimeoutFuture;
 use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use stream_log_shared::messages::admin::{
-	AdminApplicationData, AdminEntryTypeData, AdminEntryTypeEventData, AdminEventData, AdminEventEditorData,
-	AdminEventLogTabsData, AdminInfoPageData, AdminPermissionGroupData, AdminUserPermissionGroupData, Application,
-	EditorEventAssociation, EntryTypeEventAssociation, PermissionGroup, PermissionGroupEventAssociation,
-	UserPermissionGroupAssociation,
+	AdminApplicationData, AdminAuditLogData, AdminEntryTypeData, AdminEntryTypeEventData, AdminEventAccessRequestData,
+	AdminEventCustomFieldData, AdminEventData, AdminEventEditorData, AdminEventLogTabsData, AdminEventShareLinkData,
+	AdminEventWebhookData, AdminInfoPageData, AdminJobData, AdminPermissionGroupData, AdminRejectedUpdateData,
+	AdminUserEventPermissionData, AdminUserPermissionGroupData,
 };
-use stream_log_shared::messages::entry_types::EntryType;
-use stream_log_shared::messages::event_log::{EventLogEntry, EventLogTab};
+use stream_log_shared::messages::broadcasts::EventBroadcast;
+use stream_log_shared::messages::event_log::{EventLogEntry, EventLogEntryRevision};
+use stream_log_shared::messages::event_log_import::EventLogImportResult;
 use stream_log_shared::messages::event_subscription::{EventSubscriptionData, TypingData};
 use stream_log_shared::messages::events::Event;
-use stream_log_shared::messages::info_pages::InfoPage;
-use stream_log_shared::messages::initial::{InitialMessage, UserDataLoad};
+use stream_log_shared::messages::hotkeys::HotkeyBinding;
+use stream_log_shared::messages::initial::{InitialMessage, SyncCompatibility, UserDataLoad};
+use stream_log_shared::messages::list_sync::{ListSyncLoad, ListVersion};
+use stream_log_shared::messages::notifications::{UserNotification, UserNotificationData};
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
-use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData, UserSession};
 use stream_log_shared::messages::user_register::RegistrationResponse;
-use stream_log_shared::messages::{DataError, FromServerMessage};
-use stream_log_shared::SYNC_VERSION;
+use stream_log_shared::messages::view_preferences::FilterPreset;
+use stream_log_shared::messages::{DataError, FromServerMessage, RequestFailure};
+use sycamore::futures::spawn_local_scoped;
 use sycamore::prelude::*;
+use sycamore_router::navigate;
 
 pub mod connection;
-use connection::ConnectionState;
+use connection::{ConnectionState, ReconnectBackoff};
 
 pub mod errors;
 use errors::ErrorData;
@@ -45,102 +56,172 @@ use errors::ErrorData;
 pub mod event;
 use event::{EventSubscriptionSignals, EventSubscriptionSignalsInitData, TypingEvent, TypingTarget};
 
+pub mod keepalive;
+
+pub mod load_progress;
+use load_progress::LoadProgress;
+
 pub mod manager;
 use manager::SubscriptionManager;
 
 pub mod registration;
 use registration::RegistrationData;
 
+pub mod stores;
+use stores::{
+	AdminApplicationsStore, AdminAuditLogStore, AdminEntryTypesEventsStore, AdminEntryTypesStore,
+	AdminEventAccessRequestsStore, AdminEventCustomFieldsStore, AdminEventEditorsStore, AdminEventLogTabsStore,
+	AdminEventShareLinksStore, AdminEventWebhooksStore, AdminEventsStore, AdminInfoPagesStore, AdminJobsStore,
+	AdminPermissionGroupUsersStore, AdminPermissionGroupsStore, AdminRejectedUpdatesStore,
+	AdminUserEventPermissionsStore, AdminUsersStore, EditorWorkloadStore, SubscriptionStores,
+};
+
 /// A struct containing all of the signals that can be updated by server messages.
 #[derive(Clone)]
 pub struct DataSignals {
 	/// Connection state. Used to determine whether we're currently connected or not.
 	pub connection_state: RcSignal<ConnectionState>,
 
+	/// Whether the server told us it's shutting down (usually to restart). This stays set until we've reconnected.
+	pub server_restarting: RcSignal<bool>,
+
+	/// Whether the server is newer than this client and, if it is, whether it still supports this client
+	pub sync_compatibility: RcSignal<SyncCompatibility>,
+
+	/// Whether an administrator switched the connection to see the site as another user. Reconnecting returns to the
+	/// administrator's own account.
+	pub impersonating: RcSignal<bool>,
+
+	/// When we last received a message from the server. Used to tell whether the connection has gone stale.
+	pub last_message_time: RcSignal<DateTime<Utc>>,
+
+	/// Progress of processing large data from the server, if any is being processed
+	pub load_progress: RcSignal<Option<LoadProgress>>,
+
 	/// List of errors. These are displayed to the user.
 	pub errors: RcSignal<Vec<ErrorData>>,
 
+	/// The most recent failure the server reported for an update we sent. The failure is also added to the errors.
+	pub request_failure: RcSignal<Option<RequestFailure>>,
+
 	/// Subscription data for each event for which we have a subscription.
 	pub events: RcSignal<HashMap<String, EventSubscriptionSignals>>,
 
+	/// Data for each of the other subscriptions we have
+	pub stores: SubscriptionStores,
+
 	/// When we're going through a registration workflow, contains all the data relevant for registering a new account.
 	pub registration: RegistrationData,
 
 	/// List of events available to the currently logged-in user.
 	pub available_events: RcSignal<Vec<Event>>,
 
-	/// List of all users registered.
-	pub all_users: RcSignal<Vec<SelfUserData>>,
+	/// Hotkeys the currently logged-in user has chosen. Actions not in the list use their default hotkeys.
+	pub hotkeys: RcSignal<Vec<HotkeyBinding>>,
 
-	/// List of all events that exist.
-	pub all_events: RcSignal<Vec<Event>>,
+	/// Event log filter presets the currently logged-in user has saved, sorted by name.
+	pub filter_presets: RcSignal<Vec<FilterPreset>>,
 
-	/// List of all entry types that have been created.
-	pub all_entry_types: RcSignal<Vec<EntryType>>,
+	/// The user's most recent notifications, most recent first.
+	pub notifications: RcSignal<Vec<UserNotification>>,
 
-	/// List of all permission groups that have been set up.
-	pub all_permission_groups: RcSignal<Vec<PermissionGroup>>,
+	/// Notifications currently shown as pop-ups. Each is removed after a short time or when dismissed.
+	pub notification_toasts: RcSignal<Vec<UserNotification>>,
 
-	/// List of associations between permission groups and events
-	pub permission_group_event_associations: RcSignal<Vec<PermissionGroupEventAssociation>>,
+	/// Broadcasts received from event supervisors. These stay on screen until dismissed.
+	pub broadcast_toasts: RcSignal<Vec<EventBroadcast>>,
 
-	/// List of all editor user/event pairings
-	pub event_editors: RcSignal<Vec<EditorEventAssociation>>,
+	/// IDs of events to which the user was denied access when trying to subscribe
+	pub denied_events: RcSignal<HashSet<String>>,
 
-	/// List of all user/permission group pairings
-	pub user_permission_groups: RcSignal<Vec<UserPermissionGroupAssociation>>,
+	/// IDs of events for which the server has recorded the user's access request
+	pub requested_event_access: RcSignal<HashSet<String>>,
 
-	/// List of all pairings of entry types and events
-	pub entry_type_event_associations: RcSignal<Vec<EntryTypeEventAssociation>>,
+	/// The most recently received entry edit history, along with the ID of the entry it's for
+	pub entry_history: RcSignal<Option<(String, Vec<EventLogEntryRevision>)>>,
 
-	/// List of all event log tabs with their associated events
-	pub all_event_log_tabs: RcSignal<Vec<(Event, EventLogTab)>>,
+	/// The outcome of the user's most recent event log import
+	pub event_log_import_result: RcSignal<Option<EventLogImportResult>>,
 
-	/// List of all applications
-	pub all_applications: RcSignal<Vec<Application>>,
-
-	/// List of all info pages
-	pub all_info_pages: RcSignal<Vec<InfoPage>>,
-
-	/// List of application auth keys to show
-	pub show_application_auth_keys: RcSignal<Vec<(Application, String)>>,
+	/// The user's sessions, as most recently sent by the server
+	pub user_sessions: RcSignal<Option<Vec<UserSession>>>,
 }
 
 impl DataSignals {
 	pub fn new() -> Self {
+		let events = create_rc_signal(HashMap::new());
+		let stores = SubscriptionStores::new(events.clone());
 		Self {
 			connection_state: create_rc_signal(ConnectionState::default()),
+			server_restarting: create_rc_signal(false),
+			sync_compatibility: create_rc_signal(SyncCompatibility::Current),
+			impersonating: create_rc_signal(false),
+			last_message_time: create_rc_signal(Utc::now()),
+			load_progress: create_rc_signal(None),
 			errors: create_rc_signal(Vec::new()),
-			events: create_rc_signal(HashMap::new()),
+			request_failure: create_rc_signal(None),
+			events,
+			stores,
 			registration: RegistrationData::new(),
 			available_events: create_rc_signal(Vec::new()),
-			all_users: create_rc_signal(Vec::new()),
-			all_events: create_rc_signal(Vec::new()),
-			all_entry_types: create_rc_signal(Vec::new()),
-			all_permission_groups: create_rc_signal(Vec::new()),
-			permission_group_event_associations: create_rc_signal(Vec::new()),
-			event_editors: create_rc_signal(Vec::new()),
-			user_permission_groups: create_rc_signal(Vec::new()),
-			entry_type_event_associations: create_rc_signal(Vec::new()),
-			all_event_log_tabs: create_rc_signal(Vec::new()),
-			all_applications: create_rc_signal(Vec::new()),
-			all_info_pages: create_rc_signal(Vec::new()),
-			show_application_auth_keys: create_rc_signal(Vec::new()),
+			hotkeys: create_rc_signal(Vec::new()),
+			filter_presets: create_rc_signal(Vec::new()),
+			notifications: create_rc_signal(Vec::new()),
+			notification_toasts: create_rc_signal(Vec::new()),
+			broadcast_toasts: create_rc_signal(Vec::new()),
+			denied_events: create_rc_signal(HashSet::new()),
+			requested_event_access: create_rc_signal(HashSet::new()),
+			entry_history: create_rc_signal(None),
+			event_log_import_result: create_rc_signal(None),
+			user_sessions: create_rc_signal(None),
 		}
 	}
 }
 
+/// How long subscriptions are kept after navigating to a page that doesn't use them
+const SUBSCRIPTION_RELEASE_GRACE_PERIOD_MS: u32 = 15_000;
+
+/// How long a notification is shown as a pop-up before it's only available in the notification list
+const NOTIFICATION_TOAST_DURATION_MS: u32 = 10_000;
+
+/// The number of log entries added to an event's data at once when loading an event with many entries. Between each
+/// batch, the browser gets a chance to update the page.
+const EVENT_ENTRY_LOAD_CHUNK_SIZE: usize = 500;
+
 /// The message update loop
 pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket>) {
 	let data_signals: &DataSignals = use_context(ctx);
 	let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
-	let mut reconnect_interval: u32 = 1;
+	let mut reconnect_backoff = ReconnectBackoff::default();
 
 	loop {
 		match &*data_signals.connection_state.get() {
-			ConnectionState::Connected => {
-				let message: FromServerMessage = match read_websocket(&mut ws_read).await {
-					Ok(msg) => msg,
+			ConnectionState::Connected | ConnectionState::Stale => {
+				let message: Result<FromServerMessage, WebSocketReadError> =
+					match read_websocket_text(&mut ws_read).await {
+						Ok(message_text) => {
+							let is_large_message = message_text.len() >= LARGE_MESSAGE_SIZE;
+							if is_large_message {
+								data_signals
+									.load_progress
+									.set(Some(LoadProgress::ReadingMessage(message_text.len())));
+							}
+							let message = parse_websocket_message(message_text).await;
+							if is_large_message {
+								data_signals.load_progress.set(None);
+							}
+							message
+						}
+						Err(error) => Err(error),
+					};
+				let message = match message {
+					Ok(msg) => {
+						data_signals.last_message_time.set(Utc::now());
+						if let ConnectionState::Stale = *data_signals.connection_state.get_untracked() {
+							data_signals.connection_state.set(ConnectionState::Connected);
+						}
+						msg
+					}
 					Err(_) => {
 						{
 							let ws_write_context: &Mutex<WebSocketSendStream> = use_context(ctx);
@@ -151,7 +232,7 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 							log::info!("Connection lost; attempting to reconnect");
 						}
 
-						TimeoutFuture::new(reconnect_interval * 1000).await;
+						reconnect_backoff.wait().await;
 						continue;
 					}
 				};
@@ -163,6 +244,19 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 							InitialSubscriptionLoadData::Event(event_load_data) => {
 								let mut event_signals = data_signals.events.modify();
 								let event_id = event_load_data.event.id.clone();
+								let event_name = event_load_data.event.name.clone();
+
+								// Rendering a very large number of entries at once can freeze the page, so we start with
+								// the first batch of entries and add the rest in batches once the page is shown.
+								let mut event_entries = event_load_data.entries;
+								let pending_entry_count = event_load_data.pending_entry_count;
+								let total_entry_count = event_entries.len() + pending_entry_count;
+								let remaining_entries = if event_entries.len() > EVENT_ENTRY_LOAD_CHUNK_SIZE {
+									event_entries.split_off(EVENT_ENTRY_LOAD_CHUNK_SIZE)
+								} else {
+									Vec::new()
+								};
+								let shown_entry_count = event_entries.len();
 								match event_signals.entry(event_id.clone()) {
 									Entry::Occupied(mut event_entry) => {
 										let event_data = event_entry.get_mut();
@@ -170,10 +264,36 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 										event_data.permission.set(event_load_data.permission);
 										event_data.entry_types.set(event_load_data.entry_types);
 										event_data.tags.set(event_load_data.tags);
+										event_data
+											.video_edit_state_rules
+											.set(event_load_data.video_edit_state_rules);
+										event_data.pending_tag_actions.set(event_load_data.pending_tag_actions);
+										event_data.feedback.set(event_load_data.feedback);
 										event_data.editors.set(event_load_data.editors);
+										event_data.viewers.set(event_load_data.viewers);
+										event_data.entry_locks.set(
+											event_load_data
+												.entry_locks
+												.into_iter()
+												.map(|lock| (lock.entry_id.clone(), lock))
+												.collect(),
+										);
 										event_data.info_pages.set(event_load_data.info_pages);
 										event_data.event_log_tabs.set(event_load_data.tabs);
-										event_data.event_log_entries.set(event_load_data.entries);
+										event_data.entry_templates.set(event_load_data.entry_templates);
+										event_data.recurring_entries.set(event_load_data.recurring_entries);
+										event_data.custom_fields.set(event_load_data.custom_fields);
+										event_data.event_log_entries.set(event_entries);
+										event_data.pending_entry_count.set(pending_entry_count);
+										event_data
+											.unloaded_tab_ids
+											.set(event_load_data.unloaded_tab_ids.into_iter().collect());
+										event_data.requested_tab_ids.modify().clear();
+										// Typing notifications from before a reconnection may never be cleared, so we
+										// drop them. New entries being written are kept as they are locally, as any
+										// changes made while disconnected haven't reached the server yet.
+										event_data.typing_events.modify().clear();
+										event_data.loaded_at.set(None);
 									}
 									Entry::Vacant(event_entry) => {
 										let signal_data = EventSubscriptionSignalsInitData {
@@ -181,10 +301,20 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 											permission: event_load_data.permission,
 											entry_types: event_load_data.entry_types,
 											tags: event_load_data.tags,
+											video_edit_state_rules: event_load_data.video_edit_state_rules,
+											pending_tag_actions: event_load_data.pending_tag_actions,
+											feedback: event_load_data.feedback,
 											editors: event_load_data.editors,
+											viewers: event_load_data.viewers,
+											entry_locks: event_load_data.entry_locks,
 											info_pages: event_load_data.info_pages,
 											event_log_tabs: event_load_data.tabs,
-											event_log_entries: event_load_data.entries,
+											entry_templates: event_load_data.entry_templates,
+											recurring_entries: event_load_data.recurring_entries,
+											custom_fields: event_load_data.custom_fields,
+											event_log_entries: event_entries,
+											pending_entry_count,
+											unloaded_tab_ids: event_load_data.unloaded_tab_ids,
 											new_event_log_entries: event_load_data.new_entries,
 										};
 										event_entry.insert(EventSubscriptionSignals::new(signal_data));
@@ -193,6 +323,7 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 								subscription_manager.subscription_confirmation_received(
 									SubscriptionType::EventLogData(event_id.clone()),
 								);
+								data_signals.denied_events.modify().remove(&event_id);
 
 								log::debug!("Running subscription wakers for event {}", event_id);
 
@@ -203,587 +334,1233 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 										waker.wake_by_ref();
 									}
 								}
+
+								if !remaining_entries.is_empty() {
+									drop(event_signals);
+									drop(subscription_manager);
+									load_remaining_event_entries(
+										data_signals,
+										&event_id,
+										&event_name,
+										remaining_entries,
+										shown_entry_count,
+										total_entry_count,
+									)
+									.await;
+									if pending_entry_count == 0 {
+										if let Some(event_data) = data_signals.events.get().get(&event_id) {
+											event_data.loaded_at.set(Some(Utc::now()));
+										}
+									}
+								} else if pending_entry_count > 0 {
+									data_signals.load_progress.set(Some(LoadProgress::EventEntries(
+										event_name,
+										shown_entry_count,
+										total_entry_count,
+									)));
+								} else if let Some(event_data) = event_signals.get(&event_id) {
+									event_data.loaded_at.set(Some(Utc::now()));
+								}
 							}
-							InitialSubscriptionLoadData::AdminUsers(users) => {
-								data_signals.all_users.set(users);
-								subscription_manager.subscription_confirmation_received(SubscriptionType::AdminUsers);
+							InitialSubscriptionLoadData::AdminUsers(users_load) => {
+								let subscription_type = SubscriptionType::AdminUsers;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminUsersStore = data_signals.stores.get(subscription_type);
+									match users_load {
+										ListSyncLoad::Snapshot(version, users) => {
+											store.all_users.set(users);
+											store.list_version.set(Some(version));
+										}
+										ListSyncLoad::Patches(patches) => {
+											for patch in patches {
+												if accept_list_patch(&store.list_version, patch.version) {
+													apply_admin_user_change(&store, patch.change);
+												}
+											}
+										}
+									}
+								}
 							}
-							InitialSubscriptionLoadData::AdminEvents(events) => {
-								data_signals.all_events.set(events);
-								subscription_manager.subscription_confirmation_received(SubscriptionType::AdminEvents);
+							InitialSubscriptionLoadData::AdminEvents(events_load) => {
+								let subscription_type = SubscriptionType::AdminEvents;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventsStore = data_signals.stores.get(subscription_type);
+									match events_load {
+										ListSyncLoad::Snapshot(version, events) => {
+											store.all_events.set(events);
+											store.list_version.set(Some(version));
+										}
+										ListSyncLoad::Patches(patches) => {
+											for patch in patches {
+												if accept_list_patch(&store.list_version, patch.version) {
+													apply_admin_event_change(data_signals, &store, patch.change);
+												}
+											}
+										}
+									}
+								}
 							}
 							InitialSubscriptionLoadData::AdminPermissionGroups(
 								permission_groups,
 								permission_group_events,
 							) => {
-								data_signals.all_permission_groups.set(permission_groups);
-								data_signals
-									.permission_group_event_associations
-									.set(permission_group_events);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminPermissionGroups);
+								let subscription_type = SubscriptionType::AdminPermissionGroups;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminPermissionGroupsStore = data_signals.stores.get(subscription_type);
+									store.all_permission_groups.set(permission_groups);
+									store.permission_group_event_associations.set(permission_group_events);
+								}
 							}
 							InitialSubscriptionLoadData::AdminPermissionGroupUsers(user_permission_groups) => {
-								data_signals.user_permission_groups.set(user_permission_groups);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminPermissionGroupUsers);
+								let subscription_type = SubscriptionType::AdminPermissionGroupUsers;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminPermissionGroupUsersStore =
+										data_signals.stores.get(subscription_type);
+									store.user_permission_groups.set(user_permission_groups);
+								}
+							}
+							InitialSubscriptionLoadData::AdminUserEventPermissions(user_event_permissions) => {
+								let subscription_type = SubscriptionType::AdminUserEventPermissions;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminUserEventPermissionsStore =
+										data_signals.stores.get(subscription_type);
+									store.user_event_permissions.set(user_event_permissions);
+								}
 							}
 							InitialSubscriptionLoadData::AdminEntryTypes(entry_types) => {
-								data_signals.all_entry_types.set(entry_types);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminEntryTypes);
+								let subscription_type = SubscriptionType::AdminEntryTypes;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEntryTypesStore = data_signals.stores.get(subscription_type);
+									store.all_entry_types.set(entry_types);
+								}
 							}
 							InitialSubscriptionLoadData::AdminEntryTypesEvents(entry_types_events) => {
-								data_signals.entry_type_event_associations.set(entry_types_events);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminEntryTypesEvents);
+								let subscription_type = SubscriptionType::AdminEntryTypesEvents;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEntryTypesEventsStore = data_signals.stores.get(subscription_type);
+									store.entry_type_event_associations.set(entry_types_events);
+								}
 							}
 							InitialSubscriptionLoadData::AdminEventEditors(event_editors) => {
-								data_signals.event_editors.set(event_editors);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminEventEditors);
+								let subscription_type = SubscriptionType::AdminEventEditors;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventEditorsStore = data_signals.stores.get(subscription_type);
+									store.event_editors.set(event_editors);
+								}
 							}
-							InitialSubscriptionLoadData::AdminEventLogTabs(tabs) => {
-								data_signals.all_event_log_tabs.set(tabs);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminEventLogTabs);
+							InitialSubscriptionLoadData::AdminEventEditorsForEvent(
+								event,
+								event_editors,
+								candidate_users,
+							) => {
+								let subscription_type = SubscriptionType::AdminEventEditorsForEvent(event.id);
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventEditorsStore = data_signals.stores.get(subscription_type);
+									store.event_editors.set(event_editors);
+									store.editor_candidates.set(candidate_users);
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventLogTabs(tabs_load) => {
+								let subscription_type = SubscriptionType::AdminEventLogTabs;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventLogTabsStore = data_signals.stores.get(subscription_type);
+									match tabs_load {
+										ListSyncLoad::Snapshot(version, tabs) => {
+											store.all_event_log_tabs.set(tabs);
+											store.list_version.set(Some(version));
+										}
+										ListSyncLoad::Patches(patches) => {
+											for patch in patches {
+												if accept_list_patch(&store.list_version, patch.version) {
+													apply_admin_event_log_tabs_change(&store, &patch.change);
+												}
+											}
+										}
+									}
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventLogTabsForEvent(event, tabs) => {
+								let subscription_type = SubscriptionType::AdminEventLogTabsForEvent(event.id.clone());
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventLogTabsStore = data_signals.stores.get(subscription_type);
+									store
+										.all_event_log_tabs
+										.set(tabs.into_iter().map(|tab| (event.clone(), tab)).collect());
+								}
 							}
 							InitialSubscriptionLoadData::AdminApplications(applications) => {
-								data_signals.all_applications.set(applications);
-								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminApplications);
+								let subscription_type = SubscriptionType::AdminApplications;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminApplicationsStore = data_signals.stores.get(subscription_type);
+									store.all_applications.set(applications);
+								}
 							}
 							InitialSubscriptionLoadData::AdminInfoPages(info_pages) => {
-								data_signals.all_info_pages.set(info_pages);
+								let subscription_type = SubscriptionType::AdminInfoPages;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminInfoPagesStore = data_signals.stores.get(subscription_type);
+									store.all_info_pages.set(info_pages);
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventAccessRequests(requests) => {
+								let subscription_type = SubscriptionType::AdminEventAccessRequests;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventAccessRequestsStore =
+										data_signals.stores.get(subscription_type);
+									store.event_access_requests.set(requests);
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventWebhooks(webhooks) => {
+								let subscription_type = SubscriptionType::AdminEventWebhooks;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventWebhooksStore = data_signals.stores.get(subscription_type);
+									store.all_event_webhooks.set(webhooks);
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventCustomFields(custom_fields) => {
+								let subscription_type = SubscriptionType::AdminEventCustomFields;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventCustomFieldsStore = data_signals.stores.get(subscription_type);
+									store.all_custom_fields.set(custom_fields);
+								}
+							}
+							InitialSubscriptionLoadData::AdminEventShareLinks(share_links) => {
+								let subscription_type = SubscriptionType::AdminEventShareLinks;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminEventShareLinksStore = data_signals.stores.get(subscription_type);
+									store.all_event_share_links.set(share_links);
+								}
+							}
+							InitialSubscriptionLoadData::AdminRejectedUpdates(rejected_updates) => {
+								let subscription_type = SubscriptionType::AdminRejectedUpdates;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminRejectedUpdatesStore = data_signals.stores.get(subscription_type);
+									store.rejected_event_updates.set(rejected_updates);
+								}
+							}
+							InitialSubscriptionLoadData::AdminAuditLog(audit_log_entries) => {
+								let subscription_type = SubscriptionType::AdminAuditLog;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminAuditLogStore = data_signals.stores.get(subscription_type);
+									store.admin_audit_log.set(audit_log_entries);
+								}
+							}
+							InitialSubscriptionLoadData::AdminJobs(jobs) => {
+								let subscription_type = SubscriptionType::AdminJobs;
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: AdminJobsStore = data_signals.stores.get(subscription_type);
+									store.jobs.set(jobs);
+								}
+							}
+							InitialSubscriptionLoadData::EditorWorkload(event, workloads) => {
+								let subscription_type = SubscriptionType::EditorWorkload(event.id);
+								if subscription_manager.subscription_confirmation_received(subscription_type.clone()) {
+									let store: EditorWorkloadStore = data_signals.stores.get(subscription_type);
+									store.workloads.set(Some(workloads));
+								}
+							}
+							InitialSubscriptionLoadData::UserNotifications(notifications) => {
+								data_signals.notifications.set(notifications);
 								subscription_manager
-									.subscription_confirmation_received(SubscriptionType::AdminInfoPages);
+									.subscription_confirmation_received(SubscriptionType::UserNotifications);
 							}
 						}
 					}
-					FromServerMessage::SubscriptionMessage(subscription_data) => match *subscription_data {
-						SubscriptionData::EventUpdate(event, update_data) => {
-							let mut events_data = data_signals.events.modify();
-							let Some(event_data) = events_data.get_mut(&event.id) else {
+					FromServerMessage::SubscriptionMessage(subscription_data, sequence) => {
+						if let Some(sequence) = sequence {
+							let in_sequence = subscription_manager.lock().await.record_message_sequence(&sequence);
+							if !in_sequence {
+								log::warn!(
+									"Missed messages for subscription {:?}; resynchronizing",
+									sequence.subscription_type
+								);
+								let ws_write_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+								let mut ws_write = ws_write_context.lock().await;
+								let mut subscription_manager = subscription_manager.lock().await;
+								let resync_result = subscription_manager
+									.resync_subscription(sequence.subscription_type, &mut ws_write)
+									.await;
+								if let Err(error) = resync_result {
+									data_signals.errors.modify().push(ErrorData::new_with_error(
+										"Failed to resynchronize data after missed messages.",
+										error,
+									));
+								}
 								continue;
-							};
-							match *update_data {
-								EventSubscriptionData::UpdateEvent => event_data.event.set(event),
-								EventSubscriptionData::UpdateLogEntry(log_entry, update_user) => {
-									if let Some(update_user) = update_user {
-										let mut typing_events = event_data.typing_events.modify();
-										typing_events.retain(|typing_event| {
-											typing_event.user.id != update_user.id
-												|| typing_event.event_log_entry.id != log_entry.id
-										});
-									}
+							}
+						}
 
-									let mut log_entries = event_data.event_log_entries.modify();
-									let mut new_log_entries = event_data.new_event_log_entries.modify();
-									let existing_entry_index = log_entries
-										.iter_mut()
-										.enumerate()
-										.find(|(_, entry)| entry.id == log_entry.id)
-										.map(|(index, _)| index);
-									if let Some(index) = existing_entry_index {
-										if log_entries[index].start_time != log_entry.start_time
-											|| log_entries[index].manual_sort_key != log_entry.manual_sort_key
-											|| log_entries[index].created_at != log_entry.created_at
+						match *subscription_data {
+							SubscriptionData::EventUpdate(event, update_data) => {
+								let mut events_data = data_signals.events.modify();
+								let Some(event_data) = events_data.get_mut(&event.id) else {
+									continue;
+								};
+								match *update_data {
+									EventSubscriptionData::UpdateEvent => event_data.event.set(event),
+									EventSubscriptionData::UpdateLogEntry(log_entry, update_user) => {
+										if let Some(update_user) = update_user {
+											let mut typing_events = event_data.typing_events.modify();
+											typing_events.retain(|typing_event| {
+												typing_event.user.id != update_user.id
+													|| typing_event.event_log_entry.id != log_entry.id
+											});
+										}
+
+										let mut log_entries = event_data.event_log_entries.modify();
+										let mut new_log_entries = event_data.new_event_log_entries.modify();
+										let existing_entry_index = log_entries
+											.iter_mut()
+											.enumerate()
+											.find(|(_, entry)| entry.id == log_entry.id)
+											.map(|(index, _)| index);
+										if let Some(index) = existing_entry_index {
+											if log_entries[index].start_time != log_entry.start_time
+												|| log_entries[index].manual_sort_key != log_entry.manual_sort_key
+												|| log_entries[index].created_at != log_entry.created_at
+											{
+												log_entries.remove(index);
+												let new_index = entry_insertion_index(&log_entries, &log_entry);
+												log_entries.insert(new_index, log_entry);
+											} else {
+												log_entries[index] = log_entry;
+											}
+										} else if log_entry.start_time.is_some() {
+											let log_entry_id = log_entry.id.clone();
+											match log_entries.last() {
+												Some(last_entry) => {
+													if log_entry.start_time >= last_entry.start_time {
+														log_entries.push(log_entry);
+													} else {
+														let insert_index =
+															entry_insertion_index(&log_entries, &log_entry);
+														log_entries.insert(insert_index, log_entry);
+													}
+												}
+												None => log_entries.push(log_entry),
+											}
+											// Force flushing modifications to log_entries before updating new_log_entries;
+											// otherwise the new entry won't exist briefly while it's transferred
+											drop(log_entries);
+											new_log_entries.retain(|entry| entry.id != log_entry_id);
+										} else if let Some(entry) =
+											new_log_entries.iter_mut().find(|entry| entry.id == log_entry.id)
 										{
-											log_entries.remove(index);
-											let new_index = entry_insertion_index(&log_entries, &log_entry);
-											log_entries.insert(new_index, log_entry);
+											*entry = log_entry;
 										} else {
-											log_entries[index] = log_entry;
-										}
-									} else if log_entry.start_time.is_some() {
-										let log_entry_id = log_entry.id.clone();
-										match log_entries.last() {
-											Some(last_entry) => {
-												if log_entry.start_time >= last_entry.start_time {
-													log_entries.push(log_entry);
-												} else {
-													let insert_index = entry_insertion_index(&log_entries, &log_entry);
-													log_entries.insert(insert_index, log_entry);
+											new_log_entries.push(log_entry);
+										}
+									}
+									EventSubscriptionData::DeleteLogEntry(log_entry) => {
+										let mut log_entries = event_data.event_log_entries.modify();
+										let log_index = log_entries
+											.iter()
+											.enumerate()
+											.find(|(_, entry)| log_entry.id == entry.id)
+											.map(|(index, _)| index);
+										if let Some(log_index) = log_index {
+											log_entries.remove(log_index);
+										}
+									}
+									EventSubscriptionData::Typing(typing_data) => {
+										let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+										// If we're not logged in, we shouldn't be receiving typing data.
+										let user = user.get();
+										let Some(user) = user.as_ref() else {
+											continue;
+										};
+										// If we're not subscribed to the event in question, we don't need to track this data.
+										let data_events = data_signals.events.get();
+										let Some(event_data) = data_events.get(&event.id) else {
+											continue;
+										};
+										match typing_data {
+											TypingData::Parent(event_log_entry, parent_entry_id, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														parent_entry_id,
+														typing_user,
+														TypingTarget::Parent,
+													);
 												}
 											}
-											None => log_entries.push(log_entry),
-										}
-										// Force flushing modifications to log_entries before updating new_log_entries;
-										// otherwise the new entry won't exist briefly while it's transferred
-										drop(log_entries);
-										new_log_entries.retain(|entry| entry.id != log_entry_id);
-									} else if let Some(entry) =
-										new_log_entries.iter_mut().find(|entry| entry.id == log_entry.id)
-									{
-										*entry = log_entry;
-									} else {
-										new_log_entries.push(log_entry);
+											TypingData::StartTime(event_log_entry, typed_time, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_time,
+														typing_user,
+														TypingTarget::StartTime,
+													);
+												}
+											}
+											TypingData::EndTime(event_log_entry, typed_time, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_time,
+														typing_user,
+														TypingTarget::EndTime,
+													);
+												}
+											}
+											TypingData::EntryType(event_log_entry, typed_type, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_type,
+														typing_user,
+														TypingTarget::EntryType,
+													);
+												}
+											}
+											TypingData::Description(
+												event_log_entry,
+												typed_description,
+												typing_user,
+											) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_description,
+														typing_user,
+														TypingTarget::Description,
+													);
+												}
+											}
+											TypingData::MediaLinks(event_log_entry, typed_link, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_link,
+														typing_user,
+														TypingTarget::MediaLink,
+													);
+												}
+											}
+											TypingData::SubmitterWinner(event_log_entry, typed_name, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_name,
+														typing_user,
+														TypingTarget::SubmitterWinner,
+													);
+												}
+											}
+											TypingData::Notes(event_log_entry, typed_notes, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_notes,
+														typing_user,
+														TypingTarget::Notes,
+													);
+												}
+											}
+											TypingData::Tags(event_log_entry, typed_tags, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_tags,
+														typing_user,
+														TypingTarget::Tags,
+													);
+												}
+											}
+											TypingData::SortKey(event_log_entry, typed_sort_key, typing_user) => {
+												if user.id != typing_user.id {
+													handle_typing_data(
+														event_data,
+														user,
+														event_log_entry,
+														typed_sort_key,
+														typing_user,
+														TypingTarget::SortKey,
+													);
+												}
+											}
+											TypingData::Clear(event_log_entry, typing_user) => {
+												event_data.typing_events.modify().retain(|typing_event| {
+													typing_event.user != typing_user
+														|| typing_event.event_log_entry.id != event_log_entry.id
+												})
+											}
+										}
 									}
-								}
-								EventSubscriptionData::DeleteLogEntry(log_entry) => {
-									let mut log_entries = event_data.event_log_entries.modify();
-									let log_index = log_entries
-										.iter()
-										.enumerate()
-										.find(|(_, entry)| log_entry.id == entry.id)
-										.map(|(index, _)| index);
-									if let Some(log_index) = log_index {
-										log_entries.remove(log_index);
+									EventSubscriptionData::AddEntryType(new_entry_type) => {
+										event_data.entry_types.modify().push(new_entry_type)
 									}
-								}
-								EventSubscriptionData::Typing(typing_data) => {
-									let user: &Signal<Option<SelfUserData>> = use_context(ctx);
-									// If we're not logged in, we shouldn't be receiving typing data.
-									let user = user.get();
-									let Some(user) = user.as_ref() else {
-										continue;
-									};
-									// If we're not subscribed to the event in question, we don't need to track this data.
-									let data_events = data_signals.events.get();
-									let Some(event_data) = data_events.get(&event.id) else {
-										continue;
-									};
-									match typing_data {
-										TypingData::Parent(event_log_entry, parent_entry_id, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													parent_entry_id,
-													typing_user,
-													TypingTarget::Parent,
-												);
-											}
+									EventSubscriptionData::UpdateEntryType(updated_entry_type) => {
+										let mut entry_types = event_data.entry_types.modify();
+										let entry_type = entry_types
+											.iter_mut()
+											.find(|entry_type| entry_type.id == updated_entry_type.id);
+										if let Some(entry_type) = entry_type {
+											*entry_type = updated_entry_type;
 										}
-										TypingData::StartTime(event_log_entry, typed_time, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_time,
-													typing_user,
-													TypingTarget::StartTime,
-												);
-											}
+									}
+									EventSubscriptionData::DeleteEntryType(deleted_entry_type) => {
+										let mut entry_types = event_data.entry_types.modify();
+										let entry_type_index = entry_types
+											.iter()
+											.enumerate()
+											.find(|(_, entry_type)| entry_type.id == deleted_entry_type.id)
+											.map(|(index, _)| index);
+										if let Some(index) = entry_type_index {
+											entry_types.remove(index);
 										}
-										TypingData::EndTime(event_log_entry, typed_time, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_time,
-													typing_user,
-													TypingTarget::EndTime,
-												);
-											}
+									}
+									EventSubscriptionData::AddEditor(new_editor) => {
+										event_data.editors.modify().push(new_editor)
+									}
+									EventSubscriptionData::RemoveEditor(removed_editor) => {
+										let mut editors = event_data.editors.modify();
+										let editor_index = editors
+											.iter()
+											.enumerate()
+											.find(|(_, editor)| editor.id == removed_editor.id)
+											.map(|(index, _)| index);
+										if let Some(index) = editor_index {
+											editors.remove(index);
 										}
-										TypingData::EntryType(event_log_entry, typed_type, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_type,
-													typing_user,
-													TypingTarget::EntryType,
-												);
-											}
+									}
+									EventSubscriptionData::UpdateInfoPage(info_page) => {
+										let mut info_pages = event_data.info_pages.modify();
+										let info_page_entry =
+											info_pages.iter_mut().find(|page| page.id == info_page.id);
+										match info_page_entry {
+											Some(entry) => *entry = info_page,
+											None => info_pages.push(info_page),
 										}
-										TypingData::Description(event_log_entry, typed_description, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_description,
-													typing_user,
-													TypingTarget::Description,
-												);
-											}
+									}
+									EventSubscriptionData::DeleteInfoPage(info_page_id) => {
+										let mut info_pages = event_data.info_pages.modify();
+										let info_page_index = info_pages
+											.iter()
+											.enumerate()
+											.find(|(_, page)| page.id == info_page_id)
+											.map(|(index, _)| index);
+										if let Some(index) = info_page_index {
+											info_pages.remove(index);
 										}
-										TypingData::MediaLinks(event_log_entry, typed_link, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_link,
-													typing_user,
-													TypingTarget::MediaLink,
-												);
+									}
+									EventSubscriptionData::UpdateTab(tab) => {
+										let mut tabs = event_data.event_log_tabs.modify();
+										let tab_entry = tabs.iter_mut().find(|t| tab.id == t.id);
+										match tab_entry {
+											Some(entry) => *entry = tab,
+											None => {
+												match tabs
+													.binary_search_by_key(&tab.start_time, |section| section.start_time)
+												{
+													Ok(index) => tabs.insert(index, tab),
+													Err(index) => tabs.insert(index, tab),
+												}
 											}
 										}
-										TypingData::SubmitterWinner(event_log_entry, typed_name, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_name,
-													typing_user,
-													TypingTarget::SubmitterWinner,
-												);
-											}
+									}
+									EventSubscriptionData::DeleteTab(tab) => event_data
+										.event_log_tabs
+										.modify()
+										.retain(|tab_entry| tab_entry.id != tab.id),
+									EventSubscriptionData::UpdateTag(tag) => {
+										let mut tags = event_data.tags.modify();
+										let tag_entry = tags.iter_mut().find(|t| t.id == tag.id);
+										match tag_entry {
+											Some(entry) => *entry = tag,
+											None => tags.push(tag),
 										}
-										TypingData::Notes(event_log_entry, typed_notes, typing_user) => {
-											if user.id != typing_user.id {
-												handle_typing_data(
-													event_data,
-													event_log_entry,
-													typed_notes,
-													typing_user,
-													TypingTarget::Notes,
-												);
-											}
+									}
+									EventSubscriptionData::RemoveTag(tag) => {
+										let mut tags = event_data.tags.modify();
+										let tag_index = tags
+											.iter()
+											.enumerate()
+											.find(|(_, t)| t.id == tag.id)
+											.map(|(index, _)| index);
+										if let Some(index) = tag_index {
+											tags.remove(index);
 										}
-										TypingData::Clear(event_log_entry, typing_user) => {
-											event_data.typing_events.modify().retain(|typing_event| {
-												typing_event.user != typing_user
-													|| typing_event.event_log_entry != event_log_entry
-											})
+										event_data
+											.video_edit_state_rules
+											.modify()
+											.retain(|rule| rule.tag.id != tag.id);
+										for template in event_data.entry_templates.modify().iter_mut() {
+											template.tags.retain(|template_tag| template_tag.id != tag.id);
 										}
 									}
-								}
-								EventSubscriptionData::AddEntryType(new_entry_type) => {
-									event_data.entry_types.modify().push(new_entry_type)
-								}
-								EventSubscriptionData::UpdateEntryType(updated_entry_type) => {
-									let mut entry_types = event_data.entry_types.modify();
-									let entry_type = entry_types
-										.iter_mut()
-										.find(|entry_type| entry_type.id == updated_entry_type.id);
-									if let Some(entry_type) = entry_type {
-										*entry_type = updated_entry_type;
-									}
-								}
-								EventSubscriptionData::DeleteEntryType(deleted_entry_type) => {
-									let mut entry_types = event_data.entry_types.modify();
-									let entry_type_index = entry_types
-										.iter()
-										.enumerate()
-										.find(|(_, entry_type)| entry_type.id == deleted_entry_type.id)
-										.map(|(index, _)| index);
-									if let Some(index) = entry_type_index {
-										entry_types.remove(index);
-									}
-								}
-								EventSubscriptionData::AddEditor(new_editor) => {
-									event_data.editors.modify().push(new_editor)
-								}
-								EventSubscriptionData::RemoveEditor(removed_editor) => {
-									let mut editors = event_data.editors.modify();
-									let editor_index = editors
-										.iter()
-										.enumerate()
-										.find(|(_, editor)| editor.id == removed_editor.id)
-										.map(|(index, _)| index);
-									if let Some(index) = editor_index {
-										editors.remove(index);
-									}
-								}
-								EventSubscriptionData::UpdateInfoPage(info_page) => {
-									let mut info_pages = event_data.info_pages.modify();
-									let info_page_entry = info_pages.iter_mut().find(|page| page.id == info_page.id);
-									match info_page_entry {
-										Some(entry) => *entry = info_page,
-										None => info_pages.push(info_page),
-									}
-								}
-								EventSubscriptionData::DeleteInfoPage(info_page) => {
-									let mut info_pages = event_data.info_pages.modify();
-									let info_page_index = info_pages
-										.iter()
-										.enumerate()
-										.find(|(_, page)| page.id == info_page.id)
-										.map(|(index, _)| index);
-									if let Some(index) = info_page_index {
-										info_pages.remove(index);
-									}
-								}
-								EventSubscriptionData::UpdateTab(tab) => {
-									let mut tabs = event_data.event_log_tabs.modify();
-									let tab_entry = tabs.iter_mut().find(|t| tab.id == t.id);
-									match tab_entry {
-										Some(entry) => *entry = tab,
-										None => {
-											match tabs
-												.binary_search_by_key(&tab.start_time, |section| section.start_time)
-											{
-												Ok(index) => tabs.insert(index, tab),
-												Err(index) => tabs.insert(index, tab),
+									EventSubscriptionData::UpdateVideoEditStateRule(rule) => {
+										let mut rules = event_data.video_edit_state_rules.modify();
+										let rule_entry = rules.iter_mut().find(|r| r.tag.id == rule.tag.id);
+										match rule_entry {
+											Some(entry) => *entry = rule,
+											None => rules.push(rule),
+										}
+									}
+									EventSubscriptionData::DeleteVideoEditStateRule(tag) => event_data
+										.video_edit_state_rules
+										.modify()
+										.retain(|rule| rule.tag.id != tag.id),
+									EventSubscriptionData::UpdateEntryNumbers(entry_numbers) => {
+										let mut log_entries = event_data.event_log_entries.modify();
+										for entry in log_entries.iter_mut() {
+											if let Some(entry_number) = entry_numbers.get(&entry.id) {
+												entry.entry_number = Some(*entry_number);
 											}
 										}
 									}
+									EventSubscriptionData::InitialLogEntryChunk(entries_chunk) => {
+										let chunk_entry_count = entries_chunk.len();
+										merge_entry_chunk(&mut event_data.event_log_entries.modify(), entries_chunk);
+
+										let pending_entry_count =
+											event_data.pending_entry_count.get().saturating_sub(chunk_entry_count);
+										event_data.pending_entry_count.set(pending_entry_count);
+										if pending_entry_count == 0 {
+											data_signals.load_progress.set(None);
+											event_data.loaded_at.set(Some(Utc::now()));
+										} else {
+											let loaded_entry_count = event_data.event_log_entries.get().len();
+											data_signals.load_progress.set(Some(LoadProgress::EventEntries(
+												event.name.clone(),
+												loaded_entry_count,
+												loaded_entry_count + pending_entry_count,
+											)));
+										}
+									}
+									EventSubscriptionData::TabEntries(tab_id, tab_entries) => {
+										merge_entry_chunk(&mut event_data.event_log_entries.modify(), tab_entries);
+										event_data.unloaded_tab_ids.modify().remove(&tab_id);
+										event_data.requested_tab_ids.modify().remove(&tab_id);
+									}
+									EventSubscriptionData::AddPendingTagAction(action) => {
+										event_data.pending_tag_actions.modify().push(action)
+									}
+									EventSubscriptionData::RemovePendingTagAction(action_id) => event_data
+										.pending_tag_actions
+										.modify()
+										.retain(|action| action.id != action_id),
+									EventSubscriptionData::Broadcast(broadcast) => {
+										data_signals.broadcast_toasts.modify().push(broadcast)
+									}
+									EventSubscriptionData::AddFeedback(feedback) => {
+										event_data.feedback.modify().push(feedback)
+									}
+									EventSubscriptionData::RemoveFeedback(feedback_id) => event_data
+										.feedback
+										.modify()
+										.retain(|feedback| feedback.id != feedback_id),
+									EventSubscriptionData::AddViewer(viewer) => {
+										let mut viewers = event_data.viewers.modify();
+										if !viewers.iter().any(|user| user.id == viewer.id) {
+											viewers.push(viewer);
+											viewers.sort_by_key(|user| user.username.to_lowercase());
+										}
+									}
+									EventSubscriptionData::RemoveViewer(user_id) => {
+										event_data.viewers.modify().retain(|user| user.id != user_id)
+									}
+									EventSubscriptionData::EntryLocked(lock) => {
+										event_data.entry_locks.modify().insert(lock.entry_id.clone(), lock);
+									}
+									EventSubscriptionData::EntryUnlocked(entry_id) => {
+										event_data.entry_locks.modify().remove(&entry_id);
+									}
+									EventSubscriptionData::LogEntryUpdateConflict(conflict) => {
+										event_data.entry_update_conflicts.modify().push(*conflict)
+									}
+									EventSubscriptionData::UpdateEntryTemplate(template) => {
+										let mut templates = event_data.entry_templates.modify();
+										match templates.iter_mut().find(|t| t.id == template.id) {
+											Some(entry) => *entry = template,
+											None => templates.push(template),
+										}
+										templates.sort_by_key(|template| template.name.to_lowercase());
+									}
+									EventSubscriptionData::DeleteEntryTemplate(template) => {
+										event_data.entry_templates.modify().retain(|t| t.id != template.id)
+									}
+									EventSubscriptionData::UpdateRecurringEntry(recurring_entry) => {
+										let mut recurring_entries = event_data.recurring_entries.modify();
+										match recurring_entries.iter_mut().find(|r| r.id == recurring_entry.id) {
+											Some(entry) => *entry = recurring_entry,
+											None => recurring_entries.push(recurring_entry),
+										}
+										recurring_entries.sort_by_key(|recurring_entry| recurring_entry.first_time);
+									}
+									EventSubscriptionData::DeleteRecurringEntry(recurring_entry) => event_data
+										.recurring_entries
+										.modify()
+										.retain(|r| r.id != recurring_entry.id),
+									EventSubscriptionData::UpdateCustomField(field) => {
+										let mut fields = event_data.custom_fields.modify();
+										match fields.iter_mut().find(|f| f.id == field.id) {
+											Some(entry) => *entry = field,
+											None => fields.push(field),
+										}
+										fields.sort_by_key(|field| field.name.to_lowercase());
+									}
+									EventSubscriptionData::DeleteCustomField(field) => {
+										event_data.custom_fields.modify().retain(|f| f.id != field.id);
+										// The server removes the field's values along with the field, so we do the
+										// same to keep them from being sent back with later edits.
+										for entry in event_data.event_log_entries.modify().iter_mut() {
+											entry.custom_fields.remove(&field.id);
+										}
+										for entry in event_data.new_event_log_entries.modify().iter_mut() {
+											entry.custom_fields.remove(&field.id);
+										}
+									}
 								}
-								EventSubscriptionData::DeleteTab(tab) => event_data
-									.event_log_tabs
-									.modify()
-									.retain(|tab_entry| tab_entry.id != tab.id),
-								EventSubscriptionData::UpdateTag(tag) => {
-									let mut tags = event_data.tags.modify();
-									let tag_entry = tags.iter_mut().find(|t| t.id == tag.id);
-									match tag_entry {
-										Some(entry) => *entry = tag,
-										None => tags.push(tag),
+							}
+							SubscriptionData::UserUpdate(user_update) => {
+								let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+								user_signal.set(Some(user_update.user));
+								let mut available_events = user_update.available_events;
+								available_events.sort_unstable_by(|a, b| a.start_time.cmp(&b.start_time).reverse());
+								data_signals.available_events.set(available_events);
+							}
+							SubscriptionData::UserHotkeysUpdate(hotkeys) => data_signals.hotkeys.set(hotkeys),
+							SubscriptionData::UserFilterPresetsUpdate(presets) => {
+								data_signals.filter_presets.set(presets)
+							}
+							SubscriptionData::UserNotificationsUpdate(notification_data) => match notification_data {
+								UserNotificationData::NewNotification(notification) => {
+									let notification = *notification;
+									data_signals.notifications.modify().insert(0, notification.clone());
+									let user: &Signal<Option<SelfUserData>> = use_context(ctx);
+									if let Some(user) = user.get().as_ref() {
+										alert_for_notification(&user.notification_preferences, &notification);
 									}
+
+									let notification_id = notification.id.clone();
+									data_signals.notification_toasts.modify().push(notification);
+									let notification_toasts = data_signals.notification_toasts.clone();
+									Timeout::new(NOTIFICATION_TOAST_DURATION_MS, move || {
+										notification_toasts.modify().retain(|toast| toast.id != notification_id);
+									})
+									.forget();
 								}
-								EventSubscriptionData::RemoveTag(tag) => {
-									let mut tags = event_data.tags.modify();
-									let tag_index = tags
-										.iter()
-										.enumerate()
-										.find(|(_, t)| t.id == tag.id)
-										.map(|(index, _)| index);
-									if let Some(index) = tag_index {
-										tags.remove(index);
+								UserNotificationData::MarkedRead(notification_ids) => {
+									let mut notifications = data_signals.notifications.modify();
+									for notification in notifications.iter_mut() {
+										if notification_ids.contains(&notification.id) {
+											notification.read = true;
+										}
 									}
 								}
+							},
+							SubscriptionData::AdminEventsUpdate(patch) => {
+								let Some(events_store) = data_signals
+									.stores
+									.existing::<AdminEventsStore>(&SubscriptionType::AdminEvents)
+								else {
+									continue;
+								};
+								if accept_list_patch(&events_store.list_version, patch.version) {
+									apply_admin_event_change(data_signals, &events_store, patch.change);
+								}
 							}
-						}
-						SubscriptionData::UserUpdate(user_update) => {
-							let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
-							user_signal.set(Some(user_update.user));
-							let mut available_events = user_update.available_events;
-							available_events.sort_unstable_by(|a, b| a.start_time.cmp(&b.start_time).reverse());
-							data_signals.available_events.set(available_events);
-						}
-						SubscriptionData::AdminEventsUpdate(event_data) => match event_data {
-							AdminEventData::UpdateEvent(event) => {
-								let mut all_events = data_signals.all_events.modify();
-								let event_data = all_events.iter_mut().find(|an_event| an_event.id == event.id);
-								match event_data {
-									Some(event_data) => *event_data = event,
-									None => all_events.push(event),
-								}
-							}
-						},
-						SubscriptionData::AdminEntryTypesUpdate(entry_type_data) => match entry_type_data {
-							AdminEntryTypeData::UpdateEntryType(entry_type) => {
-								let mut all_entry_types = data_signals.all_entry_types.modify();
-								let entry_type_data = all_entry_types.iter_mut().find(|et| et.id == entry_type.id);
-								match entry_type_data {
-									Some(entry_type_data) => *entry_type_data = entry_type,
-									None => all_entry_types.push(entry_type),
-								}
-							}
-						},
-						SubscriptionData::AdminEntryTypesEventsUpdate(entry_type_event_data) => {
-							match entry_type_event_data {
-								AdminEntryTypeEventData::AddTypeToEvent(entry_type_event_association) => {
-									let mut entry_type_event_associations =
-										data_signals.entry_type_event_associations.modify();
-									let exists = entry_type_event_associations.iter().any(|association| {
-										association.entry_type.id == entry_type_event_association.entry_type.id
-											&& association.event.id == entry_type_event_association.event.id
-									});
-									if !exists {
-										entry_type_event_associations.push(entry_type_event_association);
-									}
-								}
-								AdminEntryTypeEventData::RemoveTypeFromEvent(entry_type_event_association) => {
-									let mut entry_type_event_associations =
-										data_signals.entry_type_event_associations.modify();
-									let association_index = entry_type_event_associations
-										.iter()
-										.enumerate()
-										.find(|(_, association)| {
+							SubscriptionData::AdminEventTemplate(event, template) => {
+								if let Some(events_store) = data_signals
+									.stores
+									.existing::<AdminEventsStore>(&SubscriptionType::AdminEvents)
+								{
+									events_store.exported_event_template.set(Some((event, template)));
+								}
+							}
+							SubscriptionData::AdminEventArchived(event, archive_file_name) => {
+								if let Some(events_store) = data_signals
+									.stores
+									.existing::<AdminEventsStore>(&SubscriptionType::AdminEvents)
+								{
+									events_store.archived_event.set(Some((event, archive_file_name)));
+								}
+							}
+							SubscriptionData::AdminEntryTypesUpdate(entry_type_data) => match entry_type_data {
+								AdminEntryTypeData::UpdateEntryType(entry_type) => {
+									let Some(entry_types_store) = data_signals
+										.stores
+										.existing::<AdminEntryTypesStore>(&SubscriptionType::AdminEntryTypes)
+									else {
+										continue;
+									};
+									let mut all_entry_types = entry_types_store.all_entry_types.modify();
+									let entry_type_data = all_entry_types.iter_mut().find(|et| et.id == entry_type.id);
+									match entry_type_data {
+										Some(entry_type_data) => *entry_type_data = entry_type,
+										None => all_entry_types.push(entry_type),
+									}
+								}
+							},
+							SubscriptionData::AdminEntryTypesEventsUpdate(entry_type_event_data) => {
+								let Some(entry_types_events_store) = data_signals
+									.stores
+									.existing::<AdminEntryTypesEventsStore>(&SubscriptionType::AdminEntryTypesEvents)
+								else {
+									continue;
+								};
+								match entry_type_event_data {
+									AdminEntryTypeEventData::AddTypeToEvent(entry_type_event_association) => {
+										let mut entry_type_event_associations =
+											entry_types_events_store.entry_type_event_associations.modify();
+										let exists = entry_type_event_associations.iter().any(|association| {
 											association.entry_type.id == entry_type_event_association.entry_type.id
 												&& association.event.id == entry_type_event_association.event.id
-										})
-										.map(|(index, _)| index);
-									if let Some(index) = association_index {
-										entry_type_event_associations.remove(index);
+										});
+										if !exists {
+											entry_type_event_associations.push(entry_type_event_association);
+										}
+									}
+									AdminEntryTypeEventData::RemoveTypeFromEvent(entry_type_event_association) => {
+										let mut entry_type_event_associations =
+											entry_types_events_store.entry_type_event_associations.modify();
+										let association_index = entry_type_event_associations
+											.iter()
+											.enumerate()
+											.find(|(_, association)| {
+												association.entry_type.id == entry_type_event_association.entry_type.id
+													&& association.event.id == entry_type_event_association.event.id
+											})
+											.map(|(index, _)| index);
+										if let Some(index) = association_index {
+											entry_type_event_associations.remove(index);
+										}
 									}
 								}
 							}
-						}
-						SubscriptionData::AdminPermissionGroupsUpdate(permission_group_update) => {
-							match permission_group_update {
-								AdminPermissionGroupData::UpdateGroup(permission_group) => {
-									let mut permission_groups = data_signals.all_permission_groups.modify();
-									let existing_group = permission_groups
-										.iter_mut()
-										.find(|group| group.id == permission_group.id);
-									match existing_group {
-										Some(group) => *group = permission_group,
-										None => permission_groups.push(permission_group),
-									}
-								}
-								AdminPermissionGroupData::SetEventPermissionForGroup(
-									permission_group_event_association,
-								) => {
-									let mut permission_group_event_associations =
-										data_signals.permission_group_event_associations.modify();
-									let association =
-										permission_group_event_associations.iter_mut().find(|association| {
-											association.group == permission_group_event_association.group
-												&& association.event == permission_group_event_association.event
-										});
-									match association {
-										Some(association) => *association = permission_group_event_association,
-										None => {
-											permission_group_event_associations.push(permission_group_event_association)
+							SubscriptionData::AdminPermissionGroupsUpdate(permission_group_update) => {
+								let Some(groups_store) = data_signals
+									.stores
+									.existing::<AdminPermissionGroupsStore>(&SubscriptionType::AdminPermissionGroups)
+								else {
+									continue;
+								};
+								match permission_group_update {
+									AdminPermissionGroupData::UpdateGroup(permission_group) => {
+										let mut permission_groups = groups_store.all_permission_groups.modify();
+										let existing_group = permission_groups
+											.iter_mut()
+											.find(|group| group.id == permission_group.id);
+										match existing_group {
+											Some(group) => *group = permission_group,
+											None => permission_groups.push(permission_group),
 										}
 									}
-								}
-								AdminPermissionGroupData::RemoveEventFromGroup(group, event) => {
-									let mut permission_group_event_associations =
-										data_signals.permission_group_event_associations.modify();
-									let association_index = permission_group_event_associations
-										.iter()
-										.enumerate()
-										.find(|(_, association)| {
-											association.group == group.id && association.event == event.id
-										})
-										.map(|(index, _)| index);
-									if let Some(index) = association_index {
-										permission_group_event_associations.remove(index);
+									AdminPermissionGroupData::SetEventPermissionForGroup(
+										permission_group_event_association,
+									) => {
+										let mut permission_group_event_associations =
+											groups_store.permission_group_event_associations.modify();
+										let association =
+											permission_group_event_associations.iter_mut().find(|association| {
+												association.group == permission_group_event_association.group
+													&& association.event == permission_group_event_association.event
+											});
+										match association {
+											Some(association) => *association = permission_group_event_association,
+											None => permission_group_event_associations
+												.push(permission_group_event_association),
+										}
+									}
+									AdminPermissionGroupData::RemoveEventFromGroup(group, event) => {
+										let mut permission_group_event_associations =
+											groups_store.permission_group_event_associations.modify();
+										let association_index = permission_group_event_associations
+											.iter()
+											.enumerate()
+											.find(|(_, association)| {
+												association.group == group.id && association.event == event.id
+											})
+											.map(|(index, _)| index);
+										if let Some(index) = association_index {
+											permission_group_event_associations.remove(index);
+										}
 									}
 								}
 							}
-						}
-						SubscriptionData::AdminUsersUpdate(user_data) => {
-							let mut all_users = data_signals.all_users.modify();
-							let existing_user = all_users.iter_mut().find(|user| user.id == user_data.id);
-							match existing_user {
-								Some(user) => *user = user_data,
-								None => all_users.push(user_data),
+							SubscriptionData::AdminUsersUpdate(patch) => {
+								let Some(users_store) = data_signals
+									.stores
+									.existing::<AdminUsersStore>(&SubscriptionType::AdminUsers)
+								else {
+									continue;
+								};
+								if accept_list_patch(&users_store.list_version, patch.version) {
+									apply_admin_user_change(&users_store, patch.change);
+								}
 							}
-						}
-						SubscriptionData::AdminEventEditorsUpdate(event_editor_data) => match event_editor_data {
-							AdminEventEditorData::AddEditor(editor_event_association) => {
-								let mut event_editors = data_signals.event_editors.modify();
-								if !event_editors.iter().any(|association| {
-									association.editor.id == editor_event_association.editor.id
-										&& association.event.id == editor_event_association.event.id
-								}) {
-									event_editors.push(editor_event_association);
-								}
-							}
-							AdminEventEditorData::RemoveEditor(editor_event_association) => {
-								let mut event_editors = data_signals.event_editors.modify();
-								let association_index = event_editors
-									.iter()
-									.enumerate()
-									.find(|(_, association)| {
-										association.editor.id == editor_event_association.editor.id
-											&& association.event.id == editor_event_association.event.id
-									})
-									.map(|(index, _)| index);
-								if let Some(index) = association_index {
-									event_editors.remove(index);
-								}
-							}
-						},
-						SubscriptionData::AdminUserPermissionGroupsUpdate(user_permission_group_update) => {
-							match user_permission_group_update {
-								AdminUserPermissionGroupData::AddUserToGroup(user_group_association) => {
-									let mut user_group_associations = data_signals.user_permission_groups.modify();
-									if !user_group_associations.iter().any(|association| {
-										association.user.id == user_group_association.user.id
-											&& association.permission_group.id
-												== user_group_association.permission_group.id
-									}) {
-										user_group_associations.push(user_group_association);
-									}
-								}
-								AdminUserPermissionGroupData::RemoveUserFromGroup(user_group_association) => {
-									let mut user_group_associations = data_signals.user_permission_groups.modify();
-									let association_index = user_group_associations
-										.iter()
-										.enumerate()
-										.find(|(_, association)| {
+							SubscriptionData::AdminEventEditorsUpdate(event_editor_data) => match event_editor_data {
+								AdminEventEditorData::AddEditor(editor_event_association) => {
+									let event_id = editor_event_association.event.id.clone();
+									for editors_store in event_editors_stores(data_signals, event_id) {
+										let mut event_editors = editors_store.event_editors.modify();
+										if !event_editors.iter().any(|association| {
+											association.editor.id == editor_event_association.editor.id
+												&& association.event.id == editor_event_association.event.id
+										}) {
+											event_editors.push(editor_event_association.clone());
+										}
+									}
+								}
+								AdminEventEditorData::RemoveEditor(editor_event_association) => {
+									let event_id = editor_event_association.event.id.clone();
+									for editors_store in event_editors_stores(data_signals, event_id) {
+										editors_store.event_editors.modify().retain(|association| {
+											association.editor.id != editor_event_association.editor.id
+												|| association.event.id != editor_event_association.event.id
+										});
+									}
+								}
+							},
+							SubscriptionData::AdminUserPermissionGroupsUpdate(user_permission_group_update) => {
+								let Some(group_users_store) =
+									data_signals.stores.existing::<AdminPermissionGroupUsersStore>(
+										&SubscriptionType::AdminPermissionGroupUsers,
+									)
+								else {
+									continue;
+								};
+								match user_permission_group_update {
+									AdminUserPermissionGroupData::AddUserToGroup(user_group_association) => {
+										let mut user_group_associations =
+											group_users_store.user_permission_groups.modify();
+										if !user_group_associations.iter().any(|association| {
 											association.user.id == user_group_association.user.id
 												&& association.permission_group.id
 													== user_group_association.permission_group.id
-										})
-										.map(|(index, _)| index);
-									if let Some(index) = association_index {
-										user_group_associations.remove(index);
+										}) {
+											user_group_associations.push(user_group_association);
+										}
+									}
+									AdminUserPermissionGroupData::RemoveUserFromGroup(user_group_association) => {
+										let mut user_group_associations =
+											group_users_store.user_permission_groups.modify();
+										let association_index = user_group_associations
+											.iter()
+											.enumerate()
+											.find(|(_, association)| {
+												association.user.id == user_group_association.user.id
+													&& association.permission_group.id
+														== user_group_association.permission_group.id
+											})
+											.map(|(index, _)| index);
+										if let Some(index) = association_index {
+											user_group_associations.remove(index);
+										}
 									}
 								}
 							}
-						}
-						SubscriptionData::AdminEventLogTabsUpdate(event_log_tabs_update) => match event_log_tabs_update
-						{
-							AdminEventLogTabsData::AddTab(event, new_tab) => {
-								data_signals.all_event_log_tabs.modify().push((event, new_tab))
-							}
-							AdminEventLogTabsData::UpdateTab(new_tab_data) => {
-								let mut event_log_tabs = data_signals.all_event_log_tabs.modify();
-								let tab_entry = event_log_tabs.iter_mut().find(|entry| entry.1.id == new_tab_data.id);
-								if let Some(entry) = tab_entry {
-									entry.1 = new_tab_data;
-								}
-							}
-							AdminEventLogTabsData::DeleteTab(tab) => data_signals
-								.all_event_log_tabs
-								.modify()
-								.retain(|entry| entry.1.id != tab.id),
-						},
-						SubscriptionData::AdminApplicationsUpdate(application_update) => match application_update {
-							AdminApplicationData::UpdateApplication(application) => {
-								let mut all_applications = data_signals.all_applications.modify();
-								let application_entry =
-									all_applications.iter_mut().find(|app| app.id == application.id);
-								match application_entry {
-									Some(app) => *app = application,
-									None => all_applications.push(application),
-								}
-							}
-							AdminApplicationData::ShowApplicationAuthKey(application, auth_key) => {
-								{
-									let mut application_auth_keys = data_signals.show_application_auth_keys.modify();
-									let auth_key_entry = application_auth_keys
-										.iter_mut()
-										.find(|(app, _)| app.id == application.id);
-									match auth_key_entry {
-										Some(entry) => *entry = (application, auth_key),
-										None => application_auth_keys.push((application, auth_key)),
+							SubscriptionData::AdminUserEventPermissionsUpdate(user_event_permission_update) => {
+								let Some(user_event_permissions_store) =
+									data_signals.stores.existing::<AdminUserEventPermissionsStore>(
+										&SubscriptionType::AdminUserEventPermissions,
+									)
+								else {
+									continue;
+								};
+								let mut user_event_permissions =
+									user_event_permissions_store.user_event_permissions.modify();
+								match user_event_permission_update {
+									AdminUserEventPermissionData::SetPermission(new_permission) => {
+										match user_event_permissions.iter_mut().find(|permission| {
+											permission.user.id == new_permission.user.id
+												&& permission.event.id == new_permission.event.id
+										}) {
+											Some(permission) => *permission = new_permission,
+											None => user_event_permissions.push(new_permission),
+										}
+									}
+									AdminUserEventPermissionData::RemovePermission(user, event) => {
+										user_event_permissions.retain(|permission| {
+											permission.user.id != user.id || permission.event.id != event.id
+										});
 									}
 								}
-								data_signals.show_application_auth_keys.trigger_subscribers();
 							}
-							AdminApplicationData::RevokeApplication(application) => {
-								let mut all_applications = data_signals.all_applications.modify();
-								let application_index = all_applications
+							SubscriptionData::EditorWorkloadUpdate(event, workloads) => {
+								let subscription_type = SubscriptionType::EditorWorkload(event.id);
+								if let Some(workload_store) =
+									data_signals.stores.existing::<EditorWorkloadStore>(&subscription_type)
+								{
+									workload_store.workloads.set(Some(workloads));
+								}
+							}
+							SubscriptionData::AdminEventLogTabsUpdate(patch) => {
+								let tabs_stores: Vec<AdminEventLogTabsStore> = match &patch.change {
+									AdminEventLogTabsData::AddTab(event, _) => [
+										SubscriptionType::AdminEventLogTabs,
+										SubscriptionType::AdminEventLogTabsForEvent(event.id.clone()),
+									]
 									.iter()
-									.enumerate()
-									.find(|(_, app)| app.id == application.id)
-									.map(|(index, _)| index);
-								if let Some(index) = application_index {
-									all_applications.remove(index);
+									.filter_map(|subscription_type| {
+										data_signals
+											.stores
+											.existing::<AdminEventLogTabsStore>(subscription_type)
+									})
+									.collect(),
+									_ => data_signals.stores.all_of_kind::<AdminEventLogTabsStore>(),
+								};
+								for tabs_store in tabs_stores {
+									if accept_list_patch(&tabs_store.list_version, patch.version) {
+										apply_admin_event_log_tabs_change(&tabs_store, &patch.change);
+									}
 								}
+							}
+							SubscriptionData::AdminApplicationsUpdate(application_update) => {
+								let Some(applications_store) = data_signals
+									.stores
+									.existing::<AdminApplicationsStore>(&SubscriptionType::AdminApplications)
+								else {
+									continue;
+								};
+								match application_update {
+									AdminApplicationData::UpdateApplication(application) => {
+										let mut all_applications = applications_store.all_applications.modify();
+										let application_entry =
+											all_applications.iter_mut().find(|app| app.id == application.id);
+										match application_entry {
+											Some(app) => *app = application,
+											None => all_applications.push(application),
+										}
+									}
+									AdminApplicationData::ShowApplicationAuthKey(application, auth_key) => {
+										{
+											let mut application_auth_keys =
+												applications_store.show_application_auth_keys.modify();
+											let auth_key_entry = application_auth_keys
+												.iter_mut()
+												.find(|(app, _)| app.id == application.id);
+											match auth_key_entry {
+												Some(entry) => *entry = (application, auth_key),
+												None => application_auth_keys.push((application, auth_key)),
+											}
+										}
+										applications_store.show_application_auth_keys.trigger_subscribers();
+									}
+									AdminApplicationData::RevokeApplication(application) => {
+										let mut all_applications = applications_store.all_applications.modify();
+										let application_index = all_applications
+											.iter()
+											.enumerate()
+											.find(|(_, app)| app.id == application.id)
+											.map(|(index, _)| index);
+										if let Some(index) = application_index {
+											all_applications.remove(index);
+										}
 
-								let mut application_auth_keys = data_signals.show_application_auth_keys.modify();
-								let auth_key_index = application_auth_keys
-									.iter()
-									.enumerate()
-									.find(|(_, (app, _))| app.id == application.id)
-									.map(|(index, _)| index);
-								if let Some(index) = auth_key_index {
-									application_auth_keys.remove(index);
-								}
-							}
-						},
-						SubscriptionData::AdminInfoPagesUpdate(info_pages_update) => match info_pages_update {
-							AdminInfoPageData::UpdateInfoPage(info_page) => {
-								let mut all_info_pages = data_signals.all_info_pages.modify();
-								let info_page_entry = all_info_pages.iter_mut().find(|page| page.id == info_page.id);
-								match info_page_entry {
-									Some(entry) => *entry = info_page,
-									None => all_info_pages.push(info_page),
-								}
-							}
-							AdminInfoPageData::DeleteInfoPage(info_page) => {
-								let mut all_info_pages = data_signals.all_info_pages.modify();
-								let info_page_index = all_info_pages
-									.iter()
-									.enumerate()
-									.find(|(_, page)| page.id == info_page.id)
-									.map(|(index, _)| index);
-								if let Some(index) = info_page_index {
-									all_info_pages.remove(index);
+										let mut application_auth_keys =
+											applications_store.show_application_auth_keys.modify();
+										let auth_key_index = application_auth_keys
+											.iter()
+											.enumerate()
+											.find(|(_, (app, _))| app.id == application.id)
+											.map(|(index, _)| index);
+										if let Some(index) = auth_key_index {
+											application_auth_keys.remove(index);
+										}
+									}
 								}
 							}
-						},
-					},
+							SubscriptionData::AdminInfoPagesUpdate(info_pages_update) => {
+								let Some(info_pages_store) = data_signals
+									.stores
+									.existing::<AdminInfoPagesStore>(&SubscriptionType::AdminInfoPages)
+								else {
+									continue;
+								};
+								match info_pages_update {
+									AdminInfoPageData::UpdateInfoPage(info_page) => {
+										let mut all_info_pages = info_pages_store.all_info_pages.modify();
+										let info_page_entry =
+											all_info_pages.iter_mut().find(|page| page.id == info_page.id);
+										match info_page_entry {
+											Some(entry) => *entry = info_page,
+											None => all_info_pages.push(info_page),
+										}
+									}
+									AdminInfoPageData::DeleteInfoPage(info_page) => {
+										let mut all_info_pages = info_pages_store.all_info_pages.modify();
+										let info_page_index = all_info_pages
+											.iter()
+											.enumerate()
+											.find(|(_, page)| page.id == info_page.id)
+											.map(|(index, _)| index);
+										if let Some(index) = info_page_index {
+											all_info_pages.remove(index);
+										}
+									}
+								}
+							}
+							SubscriptionData::AdminEventAccessRequestsUpdate(request_update) => {
+								let Some(access_requests_store) =
+									data_signals.stores.existing::<AdminEventAccessRequestsStore>(
+										&SubscriptionType::AdminEventAccessRequests,
+									)
+								else {
+									continue;
+								};
+								match request_update {
+									AdminEventAccessRequestData::NewRequest(request) => {
+										let mut requests = access_requests_store.event_access_requests.modify();
+										if !requests.contains(&request) {
+											requests.push(request);
+										}
+									}
+									AdminEventAccessRequestData::RemoveRequest(request) => {
+										access_requests_store.event_access_requests.modify().retain(
+											|pending_request| {
+												pending_request.user.id != request.user.id
+													|| pending_request.event.id != request.event.id
+											},
+										);
+									}
+								}
+							}
+							SubscriptionData::AdminEventWebhooksUpdate(webhook_update) => {
+								let Some(webhooks_store) = data_signals
+									.stores
+									.existing::<AdminEventWebhooksStore>(&SubscriptionType::AdminEventWebhooks)
+								else {
+									continue;
+								};
+								match webhook_update {
+									AdminEventWebhookData::UpdateWebhook(webhook) => {
+										let mut all_webhooks = webhooks_store.all_event_webhooks.modify();
+										let webhook_entry =
+											all_webhooks.iter_mut().find(|entry| entry.id == webhook.id);
+										match webhook_entry {
+											Some(entry) => *entry = webhook,
+											None => all_webhooks.push(webhook),
+										}
+									}
+									AdminEventWebhookData::DeleteWebhook(webhook) => {
+										webhooks_store
+											.all_event_webhooks
+											.modify()
+											.retain(|entry| entry.id != webhook.id);
+									}
+								}
+							}
+							SubscriptionData::AdminEventCustomFieldsUpdate(custom_field_update) => {
+								let Some(custom_fields_store) = data_signals
+									.stores
+									.existing::<AdminEventCustomFieldsStore>(&SubscriptionType::AdminEventCustomFields)
+								else {
+									continue;
+								};
+								match custom_field_update {
+									AdminEventCustomFieldData::UpdateField(field) => {
+										let mut all_fields = custom_fields_store.all_custom_fields.modify();
+										let field_entry =
+											all_fields.iter_mut().find(|entry| entry.field.id == field.field.id);
+										match field_entry {
+											Some(entry) => *entry = field,
+											None => all_fields.push(field),
+										}
+									}
+									AdminEventCustomFieldData::DeleteField(field) => {
+										custom_fields_store
+											.all_custom_fields
+											.modify()
+											.retain(|entry| entry.field.id != field.field.id);
+									}
+								}
+							}
+							SubscriptionData::AdminEventShareLinksUpdate(share_link_update) => {
+								let Some(share_links_store) = data_signals
+									.stores
+									.existing::<AdminEventShareLinksStore>(&SubscriptionType::AdminEventShareLinks)
+								else {
+									continue;
+								};
+								match share_link_update {
+									AdminEventShareLinkData::UpdateShareLink(share_link) => {
+										let mut all_share_links = share_links_store.all_event_share_links.modify();
+										let share_link_entry = all_share_links
+											.iter_mut()
+											.find(|entry| entry.event.id == share_link.event.id);
+										match share_link_entry {
+											Some(entry) => *entry = share_link,
+											None => all_share_links.push(share_link),
+										}
+									}
+									AdminEventShareLinkData::DeleteShareLink(event) => {
+										share_links_store
+											.all_event_share_links
+											.modify()
+											.retain(|entry| entry.event.id != event.id);
+									}
+								}
+							}
+							SubscriptionData::AdminRejectedUpdatesUpdate(rejected_update_data) => {
+								match rejected_update_data {
+									AdminRejectedUpdateData::NewRejectedUpdate(rejected_update) => {
+										let subscription_type = SubscriptionType::AdminRejectedUpdates;
+										if let Some(rejected_updates_store) =
+											data_signals
+												.stores
+												.existing::<AdminRejectedUpdatesStore>(&subscription_type)
+										{
+											rejected_updates_store
+												.rejected_event_updates
+												.modify()
+												.insert(0, rejected_update);
+										}
+									}
+								}
+							}
+							SubscriptionData::AdminAuditLogUpdate(audit_log_data) => match audit_log_data {
+								AdminAuditLogData::NewEntry(audit_log_entry) => {
+									let subscription_type = SubscriptionType::AdminAuditLog;
+									if let Some(audit_log_store) =
+										data_signals.stores.existing::<AdminAuditLogStore>(&subscription_type)
+									{
+										audit_log_store.admin_audit_log.modify().insert(0, audit_log_entry);
+									}
+								}
+							},
+							SubscriptionData::AdminJobsUpdate(job_data) => {
+								let subscription_type = SubscriptionType::AdminJobs;
+								if let Some(jobs_store) =
+									data_signals.stores.existing::<AdminJobsStore>(&subscription_type)
+								{
+									match job_data {
+										AdminJobData::UpdateJob(job) => {
+											let mut jobs = jobs_store.jobs.modify();
+											match jobs.iter_mut().find(|existing_job| existing_job.id == job.id) {
+												Some(existing_job) => *existing_job = job,
+												None => jobs.insert(0, job),
+											}
+										}
+										AdminJobData::RemoveJob(job_id) => {
+											jobs_store.jobs.modify().retain(|job| job.id != job_id);
+										}
+									}
+								}
+							}
+						}
+					}
 					FromServerMessage::Unsubscribed(subscription_type) => {
 						let mut subscription_manager = subscription_manager.lock().await;
 						subscription_manager.remove_subscription(subscription_type);
@@ -791,6 +1568,33 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 					FromServerMessage::SubscriptionFailure(subscription_type, failure_info) => {
 						let mut subscription_manager = subscription_manager.lock().await;
 
+						if let (SubscriptionType::EventLogData(event_id), SubscriptionFailureInfo::NotAllowed) =
+							(&subscription_type, &failure_info)
+						{
+							// The event log page offers to request access, so we let it handle this instead of
+							// showing an error.
+							data_signals.denied_events.modify().insert(event_id.clone());
+							subscription_manager.subscription_failure_received(subscription_type.clone());
+
+							// Data for the event may have been shown from the cache, which the user shouldn't keep
+							if data_signals.events.get_untracked().contains_key(event_id) {
+								data_signals.events.modify().remove(event_id);
+							}
+							spawn_local_scoped(ctx, {
+								let event_id = event_id.clone();
+								async move { remove_cached_event(&event_id).await }
+							});
+
+							let event_wakers: &Signal<HashMap<String, Vec<Waker>>> = use_context(ctx);
+							let event_wakers = event_wakers.modify().remove(event_id);
+							if let Some(wakers) = event_wakers {
+								for waker in wakers.iter() {
+									waker.wake_by_ref();
+								}
+							}
+							continue;
+						}
+
 						let error_message = match failure_info {
 							SubscriptionFailureInfo::Error(DataError::DatabaseError) => {
 								ErrorData::new_from_string(format!(
@@ -816,6 +1620,72 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 						data_signals.errors.modify().push(error_message);
 						subscription_manager.subscription_failure_received(subscription_type);
 					}
+					FromServerMessage::EventAccessRequested(event_id) => {
+						data_signals.requested_event_access.modify().insert(event_id);
+					}
+					FromServerMessage::EventLogEntryHistory(entry_id, revisions) => {
+						data_signals.entry_history.set(Some((entry_id, revisions)));
+					}
+					FromServerMessage::EventLogImportResult(result) => {
+						data_signals.event_log_import_result.set(Some(result));
+					}
+					FromServerMessage::UserSessions(sessions) => data_signals.user_sessions.set(Some(sessions)),
+					FromServerMessage::SessionRevoked => {
+						// The session no longer exists on the server, so the user needs to log in again
+						if let Some(window) = web_sys::window() {
+							let _ = window.location().set_href("/logout");
+						}
+					}
+					FromServerMessage::ImpersonationStarted(user_update) => {
+						let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
+						user_signal.set(Some(user_update.user));
+						let mut available_events = user_update.available_events;
+						initial_events_sort(&mut available_events);
+						data_signals.available_events.set(available_events);
+						data_signals.impersonating.set(true);
+						navigate("/");
+
+						// The server ended all of the connection's subscriptions, so the ones still in use after leaving
+						// the page are requested again for the impersonated user
+						spawn_local_scoped(ctx, async move {
+							let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+							let mut ws = ws_context.lock().await;
+							let mut subscription_manager = subscription_manager.lock().await;
+							if let Err(error) = subscription_manager.resync_all_subscriptions(&mut ws).await {
+								data_signals.errors.modify().push(ErrorData::new_with_error(
+									"Failed to request data for the impersonated user.",
+									error,
+								));
+							}
+						});
+					}
+					FromServerMessage::RequestFailure(failure) => {
+						let error_message = ErrorData::new_from_string(failure.to_string());
+						data_signals.errors.modify().push(error_message);
+						data_signals.request_failure.set(Some(failure));
+					}
+					FromServerMessage::KeepAlive => (),
+					FromServerMessage::PublicUserUpdate(user) => {
+						for event_signals in data_signals.events.get_untracked().values() {
+							event_signals.update_public_user(&user);
+						}
+					}
+					FromServerMessage::ServerShuttingDown => data_signals.server_restarting.set(true),
+					FromServerMessage::EventPermissionChanged(event) => {
+						let ws_write_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+						let mut ws_write = ws_write_context.lock().await;
+						let resync_result = subscription_manager
+							.lock()
+							.await
+							.resync_subscription(SubscriptionType::EventLogData(event.id), &mut ws_write)
+							.await;
+						if let Err(error) = resync_result {
+							data_signals.errors.modify().push(ErrorData::new_with_error(
+								"Failed to reload the event after your permissions changed.",
+								error,
+							));
+						}
+					}
 					FromServerMessage::RegistrationResponse(response) => match response {
 						RegistrationResponse::UsernameCheck(check_data) => {
 							data_signals.registration.username_check.set(Some(check_data))
@@ -836,31 +1706,26 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 						let initial_message: InitialMessage = match read_websocket(&mut ws_read).await {
 							Ok(msg) => msg,
 							Err(_) => {
-								TimeoutFuture::new(reconnect_interval * 1000).await;
-								reconnect_interval *= 2;
-								if reconnect_interval > 128 {
-									log::info!("Reconnection failed too many times");
-									data_signals.connection_state.set(ConnectionState::Lost);
-									break;
-								}
+								reconnect_backoff.wait().await;
 								continue;
 							}
 						};
 
 						log::info!("Reconnection successful; reinitializing data...");
-						reconnect_interval = 1;
 
-						if initial_message.sync_version != SYNC_VERSION {
+						let sync_compatibility = initial_message.client_compatibility();
+						data_signals.sync_compatibility.set(sync_compatibility);
+						if sync_compatibility == SyncCompatibility::UpdateRequired {
 							data_signals.connection_state.set(ConnectionState::Lost);
-							log::error!("Server sync version changed.");
+							log::error!("Server sync version changed to one that no longer supports this client.");
 							break;
 						}
-						let (user_data, available_events) = match initial_message.user_data {
-							UserDataLoad::User(user, mut available_events) => {
+						let (user_data, available_events, hotkeys, filter_presets) = match initial_message.user_data {
+							UserDataLoad::User(user, mut available_events, hotkeys, filter_presets) => {
 								initial_events_sort(&mut available_events);
-								(Some(user), Some(available_events))
+								(Some(user), Some(available_events), hotkeys, filter_presets)
 							}
-							UserDataLoad::NewUser => (None, None),
+							UserDataLoad::NewUser => (None, None, Vec::new(), Vec::new()),
 							_ => {
 								data_signals.connection_state.set(ConnectionState::Lost);
 								log::error!("Connection user state has been lost.");
@@ -870,6 +1735,8 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 						let user_signal: &Signal<Option<SelfUserData>> = use_context(ctx);
 						user_signal.set(user_data);
 						data_signals.available_events.set(available_events.unwrap_or_default());
+						data_signals.hotkeys.set(hotkeys);
+						data_signals.filter_presets.set(filter_presets);
 
 						let mut subscription_manager = subscription_manager.lock().await;
 						let resend_subscriptions_result =
@@ -880,23 +1747,27 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 							break;
 						}
 
+						// Changes made while disconnected are sent after resubscribing so that the server handles them
+						// with the subscriptions in place.
 						let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
 						let mut ws = ws_context.lock().await;
-						ws.set_new_connection(ws_write);
+						if ws.set_new_connection(ws_write).await.is_err() {
+							log::info!("Connection lost while sending queued messages");
+							drop(ws);
+							drop(subscription_manager);
+							reconnect_backoff.wait().await;
+							continue;
+						}
 
+						reconnect_backoff.reset();
+						data_signals.last_message_time.set(Utc::now());
 						data_signals.connection_state.set(ConnectionState::Connected);
+						data_signals.server_restarting.set(false);
+						data_signals.impersonating.set(false);
 
 						log::info!("Reinitialization complete.");
 					}
-					Err(_) => {
-						TimeoutFuture::new(reconnect_interval * 1000).await;
-						reconnect_interval *= 2;
-						if reconnect_interval > 128 {
-							log::info!("Reconnection failed too many times");
-							data_signals.connection_state.set(ConnectionState::Lost);
-							break;
-						}
-					}
+					Err(_) => reconnect_backoff.wait().await,
 				}
 			}
 			ConnectionState::Lost => break,
@@ -904,8 +1775,137 @@ pub async fn process_messages(ctx: Scope<'_>, mut ws_read: SplitStream<WebSocket
 	}
 }
 
+/// Gets the stores for the editor subscriptions that include the given event
+/// Checks whether a change to a synced list should be applied to our copy of the list, and updates the version of
+/// our copy if so. Changes we already have (such as those broadcast while the subscription was starting) are skipped.
+/// Lists we don't track the version of, like the tabs of a single event, take every change.
+fn accept_list_patch(list_version: &RcSignal<Option<ListVersion>>, patch_version: u64) -> bool {
+	let mut list_version = list_version.modify();
+	match list_version.as_mut() {
+		Some(version) if patch_version <= version.number => false,
+		Some(version) => {
+			version.number = patch_version;
+			true
+		}
+		None => true,
+	}
+}
+
+fn apply_admin_user_change(users_store: &AdminUsersStore, user_data: SelfUserData) {
+	let mut all_users = users_store.all_users.modify();
+	let existing_user = all_users.iter_mut().find(|user| user.id == user_data.id);
+	match existing_user {
+		Some(user) => *user = user_data,
+		None => all_users.push(user_data),
+	}
+}
+
+/// Applies a change to the admin event list, along with the changes to other admin data that it implies
+fn apply_admin_event_change(data_signals: &DataSignals, events_store: &AdminEventsStore, event_data: AdminEventData) {
+	match event_data {
+		AdminEventData::UpdateEvent(event) => {
+			let mut all_events = events_store.all_events.modify();
+			let event_data = all_events.iter_mut().find(|an_event| an_event.id == event.id);
+			match event_data {
+				Some(event_data) => *event_data = event,
+				None => all_events.push(event),
+			}
+		}
+		AdminEventData::RemoveEvent(event) => {
+			events_store
+				.all_events
+				.modify()
+				.retain(|an_event| an_event.id != event.id);
+			let stores = &data_signals.stores;
+			for tabs_store in stores.all_of_kind::<AdminEventLogTabsStore>() {
+				tabs_store
+					.all_event_log_tabs
+					.modify()
+					.retain(|(tab_event, _)| tab_event.id != event.id);
+			}
+			if let Some(info_pages_store) = stores.existing::<AdminInfoPagesStore>(&SubscriptionType::AdminInfoPages) {
+				info_pages_store
+					.all_info_pages
+					.modify()
+					.retain(|page| page.event.id != event.id);
+			}
+			if let Some(groups_store) =
+				stores.existing::<AdminPermissionGroupsStore>(&SubscriptionType::AdminPermissionGroups)
+			{
+				groups_store
+					.permission_group_event_associations
+					.modify()
+					.retain(|association| association.event != event.id);
+			}
+			for editors_store in stores.all_of_kind::<AdminEventEditorsStore>() {
+				editors_store
+					.event_editors
+					.modify()
+					.retain(|association| association.event.id != event.id);
+			}
+			if let Some(entry_types_events_store) =
+				stores.existing::<AdminEntryTypesEventsStore>(&SubscriptionType::AdminEntryTypesEvents)
+			{
+				entry_types_events_store
+					.entry_type_event_associations
+					.modify()
+					.retain(|association| association.event.id != event.id);
+			}
+			if let Some(access_requests_store) =
+				stores.existing::<AdminEventAccessRequestsStore>(&SubscriptionType::AdminEventAccessRequests)
+			{
+				access_requests_store
+					.event_access_requests
+					.modify()
+					.retain(|request| request.event.id != event.id);
+			}
+		}
+	}
+}
+
+/// Applies a change to a list of tabs. Changes may be applied more than once (for example, when they arrive both as a
+/// broadcast and in the changes sent when resuming a subscription), so applying one again does nothing.
+fn apply_admin_event_log_tabs_change(tabs_store: &AdminEventLogTabsStore, tabs_data: &AdminEventLogTabsData) {
+	match tabs_data {
+		AdminEventLogTabsData::AddTab(event, new_tab) => {
+			let mut event_log_tabs = tabs_store.all_event_log_tabs.modify();
+			if !event_log_tabs.iter().any(|(_, tab)| tab.id == new_tab.id) {
+				event_log_tabs.push((event.clone(), new_tab.clone()));
+			}
+		}
+		AdminEventLogTabsData::UpdateTab(new_tab_data) => {
+			let mut event_log_tabs = tabs_store.all_event_log_tabs.modify();
+			let tab_entry = event_log_tabs.iter_mut().find(|entry| entry.1.id == new_tab_data.id);
+			if let Some(entry) = tab_entry {
+				entry.1 = new_tab_data.clone();
+			}
+		}
+		AdminEventLogTabsData::DeleteTab(tab) => {
+			tabs_store
+				.all_event_log_tabs
+				.modify()
+				.retain(|entry| entry.1.id != tab.id);
+		}
+	}
+}
+
+fn event_editors_stores(data_signals: &DataSignals, event_id: String) -> Vec<AdminEventEditorsStore> {
+	[
+		SubscriptionType::AdminEventEditors,
+		SubscriptionType::AdminEventEditorsForEvent(event_id),
+	]
+	.iter()
+	.filter_map(|subscription_type| {
+		data_signals
+			.stores
+			.existing::<AdminEventEditorsStore>(subscription_type)
+	})
+	.collect()
+}
+
 fn handle_typing_data(
 	event_data: &EventSubscriptionSignals,
+	user: &SelfUserData,
 	event_log_entry: EventLogEntry,
 	typed_data: String,
 	typing_user: PublicUserData,
@@ -926,9 +1926,11 @@ fn handle_typing_data(
 			typing_events.remove(index);
 		}
 	} else {
+		let mut found_user_event = false;
 		let mut found_exact_event = false;
 		for typing_event in typing_events.iter_mut() {
 			if typing_event.event_log_entry.id == event_log_entry.id && typing_event.user.id == typing_user.id {
+				found_user_event = true;
 				typing_event.time_received = Utc::now();
 
 				if typing_event.target_field == target_field {
@@ -937,6 +1939,9 @@ fn handle_typing_data(
 				}
 			}
 		}
+		if !found_user_event {
+			alert_for_typing(user, &event_data.event.get().name, &event_log_entry, &typing_user);
+		}
 		if !found_exact_event {
 			typing_events.push(TypingEvent {
 				event_log_entry,
@@ -949,21 +1954,21 @@ fn handle_typing_data(
 	}
 }
 
+/// Compares entries by the order in which they're shown in the event log
+fn entry_order(a: &EventLogEntry, b: &EventLogEntry) -> Ordering {
+	a.start_time
+		.cmp(&b.start_time)
+		.then_with(|| match (a.manual_sort_key, b.manual_sort_key) {
+			(Some(a_sort_key), Some(b_sort_key)) => a_sort_key.cmp(&b_sort_key),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => Ordering::Equal,
+		})
+		.then_with(|| a.created_at.cmp(&b.created_at))
+}
+
 fn entry_insertion_index(entries: &[EventLogEntry], log_entry_to_insert: &EventLogEntry) -> usize {
-	match entries.binary_search_by(|check_entry| {
-		check_entry
-			.start_time
-			.cmp(&log_entry_to_insert.start_time)
-			.then_with(
-				|| match (check_entry.manual_sort_key, log_entry_to_insert.manual_sort_key) {
-					(Some(check_sort_key), Some(log_entry_sort_key)) => check_sort_key.cmp(&log_entry_sort_key),
-					(Some(_), None) => Ordering::Less,
-					(None, Some(_)) => Ordering::Greater,
-					(None, None) => Ordering::Equal,
-				},
-			)
-			.then_with(|| check_entry.created_at.cmp(&log_entry_to_insert.created_at))
-	}) {
+	match entries.binary_search_by(|check_entry| entry_order(check_entry, log_entry_to_insert)) {
 		Ok(mut found_entry_index) => {
 			while found_entry_index < entries.len()
 				&& entries[found_entry_index].start_time == log_entry_to_insert.start_time
@@ -978,6 +1983,93 @@ fn entry_insertion_index(entries: &[EventLogEntry], log_entry_to_insert: &EventL
 	}
 }
 
+/// Adds a chunk of entries from the initial load of an event to the event's entries. Entries we already have were
+/// updated after the chunk was loaded by the server, so the chunk's copies of those entries are discarded.
+fn merge_entry_chunk(entries: &mut Vec<EventLogEntry>, entries_chunk: Vec<EventLogEntry>) {
+	let existing_entry_ids: HashSet<String> = entries.iter().map(|entry| entry.id.clone()).collect();
+	let new_entries: Vec<EventLogEntry> = entries_chunk
+		.into_iter()
+		.filter(|entry| !existing_entry_ids.contains(&entry.id))
+		.collect();
+	let Some(first_new_entry) = new_entries.first() else {
+		return;
+	};
+
+	// Chunks arrive in order, so the new entries usually all go at the end
+	let needs_sort = entries
+		.last()
+		.map(|last_entry| entry_order(last_entry, first_new_entry) == Ordering::Greater)
+		.unwrap_or(false);
+	entries.extend(new_entries);
+	if needs_sort {
+		entries.sort_by(entry_order);
+	}
+}
+
+/// Adds the remaining entries of a large event load to the event's data in batches, giving the browser a chance to
+/// update the page (including the load progress display) between batches
+async fn load_remaining_event_entries(
+	data_signals: &DataSignals,
+	event_id: &str,
+	event_name: &str,
+	remaining_entries: Vec<EventLogEntry>,
+	mut loaded_entry_count: usize,
+	total_entry_count: usize,
+) {
+	let mut remaining_entries = remaining_entries.into_iter();
+	loop {
+		data_signals.load_progress.set(Some(LoadProgress::EventEntries(
+			event_name.to_string(),
+			loaded_entry_count,
+			total_entry_count,
+		)));
+		TimeoutFuture::new(0).await;
+
+		let entries_chunk: Vec<EventLogEntry> = remaining_entries.by_ref().take(EVENT_ENTRY_LOAD_CHUNK_SIZE).collect();
+		if entries_chunk.is_empty() {
+			break;
+		}
+		loaded_entry_count += entries_chunk.len();
+
+		let events = data_signals.events.get();
+		let Some(event_data) = events.get(event_id) else {
+			// The event's data was released while we were loading it
+			break;
+		};
+		event_data.event_log_entries.modify().extend(entries_chunk);
+	}
+	// If the server is still sending entries, the progress display is updated as they arrive.
+	if loaded_entry_count >= total_entry_count {
+		data_signals.load_progress.set(None);
+	}
+}
+
+/// Releases all current subscriptions once the grace period has passed, unless the set of subscriptions changes before
+/// then. This is used when navigating to pages that don't use any subscriptions so that we don't keep receiving updates
+/// we don't use, while quickly navigating back doesn't need to load everything again.
+pub async fn release_subscriptions_after_grace_period(ctx: Scope<'_>) {
+	let subscription_manager: &Mutex<SubscriptionManager> = use_context(ctx);
+	let release_id = subscription_manager.lock().await.schedule_release();
+
+	TimeoutFuture::new(SUBSCRIPTION_RELEASE_GRACE_PERIOD_MS).await;
+
+	let ws_context: &Mutex<WebSocketSendStream> = use_context(ctx);
+	let mut ws = ws_context.lock().await;
+	let release_result = {
+		let mut subscription_manager = subscription_manager.lock().await;
+		subscription_manager.release_scheduled(release_id, &mut ws).await
+	};
+
+	// The subscription manager drops the data for the released subscriptions
+	if let Err(error) = release_result {
+		let data_signals: &DataSignals = use_context(ctx);
+		data_signals.errors.modify().push(ErrorData::new_with_error(
+			"Couldn't send unsubscription messages.",
+			error,
+		));
+	}
+}
+
 pub fn initial_events_sort(events: &mut [Event]) {
 	events.sort_unstable_by(|a, b| a.start_time.cmp(&b.start_time).reverse());
 }
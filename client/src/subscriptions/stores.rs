@@ -0,0 +1,408 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event::EventSubscriptionSignals;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use stream_log_shared::messages::admin::{
+	AdminAuditLogEntry, Application, EditorEventAssociation, EntryTypeEventAssociation, EventAccessRequest,
+	EventCustomField, EventShareLink, EventTemplate, EventWebhook, Job, PermissionGroup,
+	PermissionGroupEventAssociation, RejectedEventUpdate, UserEventPermission, UserPermissionGroupAssociation,
+};
+use stream_log_shared::messages::editor_workload::EditorWorkload;
+use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::event_log::EventLogTab;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::info_pages::InfoPage;
+use stream_log_shared::messages::list_sync::ListVersion;
+use stream_log_shared::messages::subscriptions::SubscriptionType;
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use sycamore::prelude::*;
+
+/// The signals holding the data for one kind of subscription
+pub trait SubscriptionStore: Clone + 'static {
+	/// Creates an empty store to be filled in once the subscription's data is received
+	fn new() -> Self;
+}
+
+/// Holds the data for each active subscription. The data for a subscription is created when a page or the initial
+/// subscription load first needs it, and it's dropped when the subscription ends so that data for pages we've left
+/// doesn't stay around.
+#[derive(Clone)]
+pub struct SubscriptionStores {
+	stores: Rc<RefCell<HashMap<SubscriptionType, Box<dyn Any>>>>,
+	/// The data for each event log subscription. This is kept in [DataSignals](super::DataSignals) so that pages can
+	/// wait for it to arrive; the registry only removes it when the subscription ends.
+	events: RcSignal<HashMap<String, EventSubscriptionSignals>>,
+}
+
+impl SubscriptionStores {
+	pub fn new(events: RcSignal<HashMap<String, EventSubscriptionSignals>>) -> Self {
+		Self {
+			stores: Rc::new(RefCell::new(HashMap::new())),
+			events,
+		}
+	}
+
+	/// Gets the data for a subscription, creating an empty store for it if we don't have one
+	pub fn get<T: SubscriptionStore>(&self, subscription_type: SubscriptionType) -> T {
+		let mut stores = self.stores.borrow_mut();
+		let store = stores.entry(subscription_type).or_insert_with(|| Box::new(T::new()));
+		if let Some(store) = store.downcast_ref::<T>() {
+			return store.clone();
+		}
+		let new_store = T::new();
+		*store = Box::new(new_store.clone());
+		new_store
+	}
+
+	/// Gets the data for a subscription if we have any
+	pub fn existing<T: SubscriptionStore>(&self, subscription_type: &SubscriptionType) -> Option<T> {
+		self.stores
+			.borrow()
+			.get(subscription_type)
+			.and_then(|store| store.downcast_ref::<T>())
+			.cloned()
+	}
+
+	/// Gets all of the stores of one kind. Used for updates that affect the data of several subscriptions, such as the
+	/// per-event and all-event versions of the same data.
+	pub fn all_of_kind<T: SubscriptionStore>(&self) -> Vec<T> {
+		self.stores
+			.borrow()
+			.values()
+			.filter_map(|store| store.downcast_ref::<T>())
+			.cloned()
+			.collect()
+	}
+
+	/// Gets the version of the synced list we have for a subscription, if it's a synced list and we have a copy of it
+	pub fn list_version(&self, subscription_type: &SubscriptionType) -> Option<ListVersion> {
+		let list_version = match subscription_type {
+			SubscriptionType::AdminUsers => self.existing::<AdminUsersStore>(subscription_type)?.list_version,
+			SubscriptionType::AdminEvents => self.existing::<AdminEventsStore>(subscription_type)?.list_version,
+			SubscriptionType::AdminEventLogTabs => {
+				self.existing::<AdminEventLogTabsStore>(subscription_type)?.list_version
+			}
+			_ => return None,
+		};
+		let list_version = list_version.get_untracked();
+		(*list_version).clone()
+	}
+
+	/// Drops the data for a subscription that has ended
+	pub fn release(&self, subscription_type: &SubscriptionType) {
+		if let SubscriptionType::EventLogData(event_id) = subscription_type {
+			if self.events.get_untracked().contains_key(event_id) {
+				self.events.modify().remove(event_id);
+			}
+			return;
+		}
+		self.stores.borrow_mut().remove(subscription_type);
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminUsersStore {
+	/// List of all users registered.
+	pub all_users: RcSignal<Vec<SelfUserData>>,
+
+	/// The version of the user list we have
+	pub list_version: RcSignal<Option<ListVersion>>,
+}
+
+impl SubscriptionStore for AdminUsersStore {
+	fn new() -> Self {
+		Self {
+			all_users: create_rc_signal(Vec::new()),
+			list_version: create_rc_signal(None),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEventsStore {
+	/// List of all events that exist.
+	pub all_events: RcSignal<Vec<Event>>,
+
+	/// The version of the event list we have
+	pub list_version: RcSignal<Option<ListVersion>>,
+
+	/// Event configuration template most recently exported by the user
+	pub exported_event_template: RcSignal<Option<(Event, EventTemplate)>>,
+
+	/// Event most recently archived by the user, along with the name of the archive file
+	pub archived_event: RcSignal<Option<(Event, String)>>,
+}
+
+impl SubscriptionStore for AdminEventsStore {
+	fn new() -> Self {
+		Self {
+			all_events: create_rc_signal(Vec::new()),
+			list_version: create_rc_signal(None),
+			exported_event_template: create_rc_signal(None),
+			archived_event: create_rc_signal(None),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEntryTypesStore {
+	/// List of all entry types that have been created.
+	pub all_entry_types: RcSignal<Vec<EntryType>>,
+}
+
+impl SubscriptionStore for AdminEntryTypesStore {
+	fn new() -> Self {
+		Self {
+			all_entry_types: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEntryTypesEventsStore {
+	/// List of all pairings of entry types and events
+	pub entry_type_event_associations: RcSignal<Vec<EntryTypeEventAssociation>>,
+}
+
+impl SubscriptionStore for AdminEntryTypesEventsStore {
+	fn new() -> Self {
+		Self {
+			entry_type_event_associations: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminPermissionGroupsStore {
+	/// List of all permission groups that have been set up.
+	pub all_permission_groups: RcSignal<Vec<PermissionGroup>>,
+
+	/// List of associations between permission groups and events
+	pub permission_group_event_associations: RcSignal<Vec<PermissionGroupEventAssociation>>,
+}
+
+impl SubscriptionStore for AdminPermissionGroupsStore {
+	fn new() -> Self {
+		Self {
+			all_permission_groups: create_rc_signal(Vec::new()),
+			permission_group_event_associations: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminPermissionGroupUsersStore {
+	/// List of all user/permission group pairings
+	pub user_permission_groups: RcSignal<Vec<UserPermissionGroupAssociation>>,
+}
+
+impl SubscriptionStore for AdminPermissionGroupUsersStore {
+	fn new() -> Self {
+		Self {
+			user_permission_groups: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminUserEventPermissionsStore {
+	/// List of all permissions granted to users for events directly
+	pub user_event_permissions: RcSignal<Vec<UserEventPermission>>,
+}
+
+impl SubscriptionStore for AdminUserEventPermissionsStore {
+	fn new() -> Self {
+		Self {
+			user_event_permissions: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+/// Editor data for either all events or a single event
+#[derive(Clone)]
+pub struct AdminEventEditorsStore {
+	/// List of editor user/event pairings
+	pub event_editors: RcSignal<Vec<EditorEventAssociation>>,
+
+	/// For a single event, the users who have access to edit the event and so can be made editors
+	pub editor_candidates: RcSignal<Vec<PublicUserData>>,
+}
+
+impl SubscriptionStore for AdminEventEditorsStore {
+	fn new() -> Self {
+		Self {
+			event_editors: create_rc_signal(Vec::new()),
+			editor_candidates: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+/// Event log tabs for either all events or a single event
+#[derive(Clone)]
+pub struct AdminEventLogTabsStore {
+	/// List of event log tabs with their associated events
+	pub all_event_log_tabs: RcSignal<Vec<(Event, EventLogTab)>>,
+
+	/// The version of the tab list we have. This is only tracked for the subscription to all events' tabs.
+	pub list_version: RcSignal<Option<ListVersion>>,
+}
+
+impl SubscriptionStore for AdminEventLogTabsStore {
+	fn new() -> Self {
+		Self {
+			all_event_log_tabs: create_rc_signal(Vec::new()),
+			list_version: create_rc_signal(None),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminApplicationsStore {
+	/// List of all applications
+	pub all_applications: RcSignal<Vec<Application>>,
+
+	/// List of application auth keys to show
+	pub show_application_auth_keys: RcSignal<Vec<(Application, String)>>,
+}
+
+impl SubscriptionStore for AdminApplicationsStore {
+	fn new() -> Self {
+		Self {
+			all_applications: create_rc_signal(Vec::new()),
+			show_application_auth_keys: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminInfoPagesStore {
+	/// List of all info pages
+	pub all_info_pages: RcSignal<Vec<InfoPage>>,
+}
+
+impl SubscriptionStore for AdminInfoPagesStore {
+	fn new() -> Self {
+		Self {
+			all_info_pages: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEventAccessRequestsStore {
+	/// List of pending event access requests
+	pub event_access_requests: RcSignal<Vec<EventAccessRequest>>,
+}
+
+impl SubscriptionStore for AdminEventAccessRequestsStore {
+	fn new() -> Self {
+		Self {
+			event_access_requests: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEventWebhooksStore {
+	/// List of all event webhooks
+	pub all_event_webhooks: RcSignal<Vec<EventWebhook>>,
+}
+
+impl SubscriptionStore for AdminEventWebhooksStore {
+	fn new() -> Self {
+		Self {
+			all_event_webhooks: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEventCustomFieldsStore {
+	/// List of all custom entry fields for all events
+	pub all_custom_fields: RcSignal<Vec<EventCustomField>>,
+}
+
+impl SubscriptionStore for AdminEventCustomFieldsStore {
+	fn new() -> Self {
+		Self {
+			all_custom_fields: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminEventShareLinksStore {
+	/// List of all public share links for events
+	pub all_event_share_links: RcSignal<Vec<EventShareLink>>,
+}
+
+impl SubscriptionStore for AdminEventShareLinksStore {
+	fn new() -> Self {
+		Self {
+			all_event_share_links: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminRejectedUpdatesStore {
+	/// List of event changes the server refused to make, most recent first
+	pub rejected_event_updates: RcSignal<Vec<RejectedEventUpdate>>,
+}
+
+impl SubscriptionStore for AdminRejectedUpdatesStore {
+	fn new() -> Self {
+		Self {
+			rejected_event_updates: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminAuditLogStore {
+	/// List of changes administrators have made, most recent first
+	pub admin_audit_log: RcSignal<Vec<AdminAuditLogEntry>>,
+}
+
+impl SubscriptionStore for AdminAuditLogStore {
+	fn new() -> Self {
+		Self {
+			admin_audit_log: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct AdminJobsStore {
+	/// List of the server's background jobs, most recently queued first
+	pub jobs: RcSignal<Vec<Job>>,
+}
+
+impl SubscriptionStore for AdminJobsStore {
+	fn new() -> Self {
+		Self {
+			jobs: create_rc_signal(Vec::new()),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct EditorWorkloadStore {
+	/// The entries assigned to each editor, or None until the subscription's data is loaded
+	pub workloads: RcSignal<Option<Vec<EditorWorkload>>>,
+}
+
+impl SubscriptionStore for EditorWorkloadStore {
+	fn new() -> Self {
+		Self {
+			workloads: create_rc_signal(None),
+		}
+	}
+}
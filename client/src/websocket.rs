@@ -4,20 +4,26 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::client_config;
+use crate::page_utils::application_url;
+use flate2::read::DeflateDecoder;
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use gloo_net::websocket::futures::WebSocket;
 use gloo_net::websocket::{Message, WebSocketError};
+use gloo_timers::future::TimeoutFuture;
 use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
 use std::fmt::Display;
-use wasm_bindgen::JsCast;
-use web_sys::Url;
+use std::io::Read;
+use stream_log_shared::messages::initial::CLIENT_SYNC_VERSION_PARAMETER;
+use stream_log_shared::messages::FromClientMessage;
+use stream_log_shared::SYNC_VERSION;
 
 /// Errors that can occur when reading data from a WebSocket connection
 pub enum WebSocketReadError {
 	ConnectionClosed,
-	BinaryMessage,
+	DecompressionError(std::io::Error),
 	WebSocketError(WebSocketError),
 	JsonError(serde_json::Error),
 }
@@ -28,7 +34,7 @@ impl Display for WebSocketReadError {
 			Self::JsonError(err) => write!(f, "{}", err),
 			Self::WebSocketError(err) => write!(f, "{}", err),
 			Self::ConnectionClosed => write!(f, "WebSocket connection closed"),
-			Self::BinaryMessage => write!(f, "An unexpected binary message was received from the WebSocket"),
+			Self::DecompressionError(err) => write!(f, "Failed to decompress a message: {}", err),
 		}
 	}
 }
@@ -52,49 +58,74 @@ impl From<WebSocketError> for WebSocketReadError {
 ///
 /// This function panics when the browser context (window, location, URL, etc.) is inaccessible.
 pub fn websocket_endpoint() -> String {
-	let doc = web_sys::window()
-		.expect("Failed to get browser window context")
-		.document()
-		.expect("Failed to get webpage document root");
-	let doc_node: web_sys::Node = doc.unchecked_into();
-	let web_endpoint = doc_node
-		.base_uri()
-		.expect("Failed to get base address")
-		.expect("Failed to get base address");
-	let url = Url::new(&web_endpoint).expect("Failed to generate URL instance");
-	url.set_search(""); // Query string is unnecessary and should be cleared
+	let url = application_url(&client_config().websocket_path);
 	if url.protocol() == "http:" {
 		url.set_protocol("ws:");
 	} else {
 		url.set_protocol("wss:");
 	}
-	let url_path = url.pathname();
-	let ws_path = if let Some(path) = url_path.strip_suffix('/') {
-		format!("{}/ws", path)
-	} else {
-		format!("{}/ws", url_path)
-	};
-	url.set_pathname(&ws_path);
+	// The server uses the client's version to send messages in a form the client can read
+	url.search_params()
+		.set(CLIENT_SYNC_VERSION_PARAMETER, &SYNC_VERSION.to_string());
 	url.to_string().into()
 }
 
+/// Messages at least this many bytes long are treated as large. Large messages can take long enough to process that
+/// the browser would otherwise appear frozen, so we give the browser a chance to update the page before processing them.
+pub const LARGE_MESSAGE_SIZE: usize = 1024 * 1024;
+
 /// Reads a single unit of data from a WebSocket connection.
 ///
 /// # Errors
 ///
 /// Errors occur in a variety of situations: when the connection unexpectedly closes,
-/// when we unexpectedly get binary data, when there's an error reading from the connection,
+/// when binary data can't be decompressed, when there's an error reading from the connection,
 /// and when the text can't be deserialized appropriately as JSON.
 pub async fn read_websocket<T: DeserializeOwned>(
 	read_stream: &mut SplitStream<WebSocket>,
 ) -> Result<T, WebSocketReadError> {
+	let msg = read_websocket_text(read_stream).await?;
+	parse_websocket_message(msg).await
+}
+
+/// Reads the text of a single message from a WebSocket connection without deserializing it. This allows callers to
+/// check the size of a message (see [LARGE_MESSAGE_SIZE]) before processing it with [parse_websocket_message].
+///
+/// The server sends large messages as binary messages containing DEFLATE-compressed JSON (see
+/// [stream_log_shared::messages::initial::MessageCompression]); these are decompressed here.
+///
+/// # Errors
+///
+/// Errors occur when the connection unexpectedly closes, when binary data can't be decompressed, and when there's an
+/// error reading from the connection.
+pub async fn read_websocket_text(read_stream: &mut SplitStream<WebSocket>) -> Result<String, WebSocketReadError> {
 	let msg = match read_stream.next().await {
 		Some(data) => data?,
 		None => return Err(WebSocketReadError::ConnectionClosed),
 	};
-	let Message::Text(msg) = msg else {
-		return Err(WebSocketReadError::BinaryMessage);
-	};
+	match msg {
+		Message::Text(msg) => Ok(msg),
+		Message::Bytes(data) => {
+			let mut msg = String::new();
+			DeflateDecoder::new(data.as_slice())
+				.read_to_string(&mut msg)
+				.map_err(WebSocketReadError::DecompressionError)?;
+			Ok(msg)
+		}
+	}
+}
+
+/// Deserializes the text of a message read from a WebSocket connection. Large messages are deserialized only after
+/// yielding to the browser.
+///
+/// # Errors
+///
+/// An error occurs when the text can't be deserialized appropriately as JSON.
+pub async fn parse_websocket_message<T: DeserializeOwned>(msg: String) -> Result<T, WebSocketReadError> {
+	if msg.len() >= LARGE_MESSAGE_SIZE {
+		log::debug!("Processing a large message ({} bytes)", msg.len());
+		TimeoutFuture::new(0).await;
+	}
 	Ok(serde_json::from_str(&msg)?)
 }
 
@@ -141,11 +172,60 @@ impl WebSocketSendStream {
 		}
 	}
 
+	pub fn is_connected(&self) -> bool {
+		self.write_stream.is_some()
+	}
+
 	pub fn mark_disconnected(&mut self) {
 		self.write_stream = None;
 	}
 
-	pub fn set_new_connection(&mut self, write_stream: SplitSink<WebSocket, Message>) {
-		self.write_stream = Some(write_stream);
+	/// Switches to sending on a new connection, then sends the messages queued while disconnected. Queued subscription
+	/// changes are skipped, as the subscription manager resends the current set of subscriptions when reconnecting.
+	///
+	/// # Errors
+	///
+	/// An error occurs when the queued messages can't be sent. The messages stay queued, and the stream is marked as
+	/// disconnected again.
+	pub async fn set_new_connection(
+		&mut self,
+		write_stream: SplitSink<WebSocket, Message>,
+	) -> Result<(), WebSocketError> {
+		self.disconnected_message_queue
+			.retain(|message| !is_subscription_change_message(message));
+		let stream = self.write_stream.insert(write_stream);
+		if self.disconnected_message_queue.is_empty() {
+			return Ok(());
+		}
+
+		log::debug!(
+			"Sending {} messages queued while disconnected",
+			self.disconnected_message_queue.len()
+		);
+		for message in self.disconnected_message_queue.iter() {
+			if let Err(error) = stream.feed(message.clone()).await {
+				self.write_stream = None;
+				return Err(error);
+			}
+		}
+		if let Err(error) = stream.flush().await {
+			self.write_stream = None;
+			return Err(error);
+		}
+		self.disconnected_message_queue.clear();
+		Ok(())
 	}
 }
+
+/// Checks whether a message sent by the client starts or ends a subscription
+fn is_subscription_change_message(message: &Message) -> bool {
+	let Message::Text(message_text) = message else {
+		return false;
+	};
+	matches!(
+		serde_json::from_str(message_text),
+		Ok(FromClientMessage::StartSubscription(_)
+			| FromClientMessage::ResumeSubscription(_, _)
+			| FromClientMessage::EndSubscription(_))
+	)
+}
@@ -0,0 +1,13 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE video_edit_state_tag_rules (
+	tag TEXT PRIMARY KEY REFERENCES tags,
+	event TEXT NOT NULL REFERENCES events,
+	video_edit_state video_edit_state NOT NULL
+);
+
+ALTER TABLE event_log_history ADD COLUMN video_edit_state_rule_tag TEXT REFERENCES tags;
\ No newline at end of file
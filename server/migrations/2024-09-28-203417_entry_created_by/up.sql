@@ -0,0 +1,13 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE event_log ADD COLUMN created_by TEXT REFERENCES users;
+
+-- Entries created before this column existed get their creator from the first history entry, which is recorded when
+-- the entry is created.
+UPDATE event_log SET created_by = first_edit.edit_user FROM (
+	SELECT DISTINCT ON (log_entry) log_entry, edit_user FROM event_log_history ORDER BY log_entry, edit_time
+) AS first_edit WHERE event_log.id = first_edit.log_entry;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE event_access_requests (
+	user_id TEXT NOT NULL REFERENCES users,
+	event TEXT NOT NULL REFERENCES events,
+	requested_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	PRIMARY KEY (user_id, event)
+);
\ No newline at end of file
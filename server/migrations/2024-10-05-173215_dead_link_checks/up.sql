@@ -0,0 +1,7 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE event_log ADD COLUMN dead_links TEXT[] NOT NULL DEFAULT '{}', ADD COLUMN links_checked_at TIMESTAMP WITH TIME ZONE;
\ No newline at end of file
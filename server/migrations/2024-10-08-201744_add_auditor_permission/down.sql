@@ -0,0 +1,9 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+-- Type values can't be removed, so the auditor value stays in the type. Auditors are downgraded to viewers so that
+-- older versions only see permission levels they know about.
+UPDATE permission_events SET level = 'view' WHERE level = 'auditor';
\ No newline at end of file
@@ -0,0 +1,25 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE event_log ADD COLUMN entry_number INTEGER;
+
+-- Number existing entries the same way the log numbered them: in log order, with each entry's children following it.
+-- Deleted entries are numbered after everything else so that the numbers of entries people can see don't change.
+WITH RECURSIVE sibling_order AS (
+	SELECT id, event, parent, deleted_by, ROW_NUMBER() OVER (
+		PARTITION BY event, parent ORDER BY start_time, manual_sort_key NULLS LAST, created_at
+	) AS sibling_number FROM event_log
+), entry_paths AS (
+	SELECT id, event, deleted_by IS NOT NULL AS deleted, ARRAY[sibling_number] AS path FROM sibling_order WHERE parent IS NULL
+	UNION ALL
+	SELECT child.id, child.event, entry_paths.deleted OR child.deleted_by IS NOT NULL, entry_paths.path || child.sibling_number
+	FROM sibling_order AS child JOIN entry_paths ON child.parent = entry_paths.id
+), numbered_entries AS (
+	SELECT id, ROW_NUMBER() OVER (PARTITION BY event ORDER BY deleted, path) AS entry_number FROM entry_paths
+)
+UPDATE event_log SET entry_number = numbered_entries.entry_number FROM numbered_entries WHERE event_log.id = numbered_entries.id;
+
+ALTER TABLE event_log ALTER COLUMN entry_number SET NOT NULL, ADD CONSTRAINT event_log_entry_number_unique UNIQUE (event, entry_number) DEFERRABLE INITIALLY DEFERRED;
\ No newline at end of file
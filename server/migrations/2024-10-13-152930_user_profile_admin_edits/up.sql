@@ -0,0 +1,20 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE user_profile_admin_edits (
+	id TEXT PRIMARY KEY,
+	edited_user TEXT NOT NULL REFERENCES users,
+	edit_user TEXT NOT NULL REFERENCES users,
+	edit_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	old_color_red INTEGER NOT NULL,
+	old_color_green INTEGER NOT NULL,
+	old_color_blue INTEGER NOT NULL,
+	old_use_spell_check BOOLEAN NOT NULL,
+	new_color_red INTEGER NOT NULL,
+	new_color_green INTEGER NOT NULL,
+	new_color_blue INTEGER NOT NULL,
+	new_use_spell_check BOOLEAN NOT NULL
+);
\ No newline at end of file
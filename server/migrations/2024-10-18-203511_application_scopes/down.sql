@@ -0,0 +1,9 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP TABLE application_events;
+ALTER TABLE applications DROP COLUMN restrict_events;
+ALTER TABLE applications DROP COLUMN write_processing_state;
\ No newline at end of file
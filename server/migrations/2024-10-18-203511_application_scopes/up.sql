@@ -0,0 +1,16 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE applications ADD COLUMN write_processing_state BOOLEAN NOT NULL DEFAULT false;
+UPDATE applications SET write_processing_state = write_links;
+ALTER TABLE applications ALTER COLUMN write_processing_state DROP DEFAULT;
+ALTER TABLE applications ADD COLUMN restrict_events BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE application_events (
+	application TEXT NOT NULL REFERENCES applications,
+	event TEXT NOT NULL REFERENCES events,
+	PRIMARY KEY (application, event)
+);
\ No newline at end of file
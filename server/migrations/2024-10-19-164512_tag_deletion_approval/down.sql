@@ -0,0 +1,8 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP TABLE pending_tag_actions;
+ALTER TABLE events DROP COLUMN require_tag_deletion_approval;
\ No newline at end of file
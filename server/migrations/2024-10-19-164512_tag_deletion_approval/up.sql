@@ -0,0 +1,17 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE events ADD COLUMN require_tag_deletion_approval BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE events ALTER COLUMN require_tag_deletion_approval DROP DEFAULT;
+
+CREATE TABLE pending_tag_actions (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	tag TEXT NOT NULL REFERENCES tags,
+	replacement_tag TEXT REFERENCES tags,
+	requested_by TEXT NOT NULL REFERENCES users,
+	requested_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE event_webhooks (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	url TEXT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE event_share_links (
+	event TEXT PRIMARY KEY REFERENCES events,
+	token TEXT NOT NULL UNIQUE
+);
\ No newline at end of file
@@ -0,0 +1,18 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+-- Entries that haven't been given a start time yet aren't stored in the database, so log_entry isn't a reference.
+CREATE TABLE rejected_event_updates (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	user_id TEXT NOT NULL REFERENCES users,
+	log_entry TEXT,
+	update_kind TEXT NOT NULL,
+	reason TEXT NOT NULL,
+	rejected_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX rejected_event_updates_rejected_at ON rejected_event_updates (rejected_at);
\ No newline at end of file
@@ -0,0 +1,18 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE hotkey_action AS ENUM ('start_time_now', 'end_time_now', 'toggle_missing_giveaway_info');
+
+-- Only hotkeys users have chosen are stored; actions without a row use their default hotkey.
+CREATE TABLE user_hotkeys (
+	user_id TEXT NOT NULL REFERENCES users,
+	action hotkey_action NOT NULL,
+	key_code TEXT NOT NULL,
+	alt BOOLEAN NOT NULL,
+	ctrl BOOLEAN NOT NULL,
+	shift BOOLEAN NOT NULL,
+	PRIMARY KEY (user_id, action)
+);
\ No newline at end of file
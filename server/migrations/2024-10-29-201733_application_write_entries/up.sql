@@ -0,0 +1,8 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE applications ADD COLUMN write_entries BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE applications ALTER COLUMN write_entries DROP DEFAULT;
\ No newline at end of file
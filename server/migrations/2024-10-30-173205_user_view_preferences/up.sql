@@ -0,0 +1,18 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE user_view_preferences (
+	id TEXT PRIMARY KEY,
+	user_id TEXT NOT NULL REFERENCES users,
+	name TEXT NOT NULL,
+	video_edit_states video_edit_state[] NOT NULL,
+	video_processing_states video_processing_state[] NOT NULL,
+	created_by_me BOOLEAN NOT NULL,
+	assigned_to_me BOOLEAN NOT NULL,
+	over_expected_duration BOOLEAN NOT NULL,
+	missing_giveaway_information BOOLEAN NOT NULL,
+	UNIQUE (user_id, name)
+);
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE event_feedback (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	message TEXT NOT NULL,
+	submitted_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX event_feedback_event ON event_feedback (event);
\ No newline at end of file
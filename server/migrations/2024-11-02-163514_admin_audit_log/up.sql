@@ -0,0 +1,17 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE admin_action_type AS ENUM ('event', 'entry_type', 'permission_group', 'user_permission_group', 'user', 'editor', 'event_log_tab', 'application', 'info_page', 'access_request', 'webhook', 'share_link');
+
+CREATE TABLE admin_audit_log (
+	id TEXT PRIMARY KEY,
+	actor TEXT NOT NULL REFERENCES users,
+	action_type admin_action_type NOT NULL,
+	description TEXT NOT NULL,
+	performed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX admin_audit_log_performed_at ON admin_audit_log (performed_at);
\ No newline at end of file
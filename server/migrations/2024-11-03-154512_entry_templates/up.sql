@@ -0,0 +1,21 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE entry_templates (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	name TEXT NOT NULL,
+	entry_type TEXT REFERENCES entry_types,
+	description TEXT NOT NULL
+);
+
+CREATE INDEX entry_templates_event ON entry_templates (event);
+
+CREATE TABLE entry_template_tags (
+	template TEXT NOT NULL REFERENCES entry_templates ON DELETE CASCADE,
+	tag TEXT NOT NULL REFERENCES tags,
+	PRIMARY KEY (template, tag)
+);
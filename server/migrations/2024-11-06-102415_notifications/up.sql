@@ -0,0 +1,21 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE notification_type AS ENUM ('video_processing_state_changed', 'editor_assigned', 'added_to_event', 'missing_giveaway_information');
+
+CREATE TABLE notifications (
+	id TEXT PRIMARY KEY,
+	user_id TEXT NOT NULL REFERENCES users,
+	event TEXT NOT NULL REFERENCES events,
+	notification_type notification_type NOT NULL,
+	log_entry TEXT REFERENCES event_log,
+	entry_description TEXT NOT NULL DEFAULT '',
+	video_processing_state video_processing_state,
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	is_read BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE INDEX notifications_user_created_at ON notifications (user_id, created_at);
\ No newline at end of file
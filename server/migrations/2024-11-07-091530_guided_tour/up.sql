@@ -0,0 +1,9 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+-- Users who already existed have been using the log, so only new users are shown the tour automatically
+ALTER TABLE users ADD COLUMN completed_guided_tour BOOLEAN NOT NULL DEFAULT false;
+UPDATE users SET completed_guided_tour = true;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+DROP TABLE event_log_custom_field_values;
+DROP TABLE event_custom_fields;
+DROP TYPE custom_field_type;
\ No newline at end of file
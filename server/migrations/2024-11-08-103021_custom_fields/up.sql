@@ -0,0 +1,25 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+CREATE TYPE custom_field_type AS ENUM ('text', 'number', 'checkbox', 'select');
+
+CREATE TABLE event_custom_fields (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	name TEXT NOT NULL,
+	field_type custom_field_type NOT NULL,
+	select_options TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX event_custom_fields_event ON event_custom_fields (event);
+
+CREATE TABLE event_log_custom_field_values (
+	log_entry TEXT NOT NULL REFERENCES event_log,
+	field TEXT NOT NULL REFERENCES event_custom_fields ON DELETE CASCADE,
+	value TEXT NOT NULL,
+	PRIMARY KEY (log_entry, field)
+);
\ No newline at end of file
@@ -0,0 +1,21 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+CREATE TABLE recurring_entries (
+	id TEXT PRIMARY KEY,
+	event TEXT NOT NULL REFERENCES events,
+	entry_type TEXT REFERENCES entry_types,
+	description TEXT NOT NULL,
+	interval_minutes INTEGER NOT NULL CHECK (interval_minutes > 0),
+	first_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	last_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	next_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	created_by TEXT NOT NULL REFERENCES users
+);
+
+CREATE INDEX recurring_entries_event ON recurring_entries (event);
+CREATE INDEX recurring_entries_next_time ON recurring_entries (next_time);
\ No newline at end of file
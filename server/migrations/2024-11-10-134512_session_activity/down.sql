@@ -0,0 +1,13 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP INDEX sessions_openid_user_id;
+
+ALTER TABLE sessions
+	DROP COLUMN openid_user_id,
+	DROP COLUMN user_agent,
+	DROP COLUMN created_at,
+	DROP COLUMN last_active_at;
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE sessions
+	ADD COLUMN openid_user_id TEXT,
+	ADD COLUMN user_agent TEXT NOT NULL DEFAULT '',
+	ADD COLUMN created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+	ADD COLUMN last_active_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now();
+
+CREATE INDEX sessions_openid_user_id ON sessions (openid_user_id);
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+CREATE TABLE user_event_permissions (
+	user_id TEXT NOT NULL REFERENCES users,
+	event TEXT NOT NULL REFERENCES events,
+	level permission NOT NULL,
+	PRIMARY KEY (user_id, event)
+);
+
+CREATE INDEX user_event_permissions_event ON user_event_permissions (event);
\ No newline at end of file
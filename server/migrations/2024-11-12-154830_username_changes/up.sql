@@ -0,0 +1,17 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+CREATE TABLE username_changes (
+	id TEXT PRIMARY KEY,
+	user_id TEXT NOT NULL REFERENCES users,
+	changed_by TEXT NOT NULL REFERENCES users,
+	change_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	old_name TEXT NOT NULL,
+	new_name TEXT NOT NULL
+);
+
+CREATE INDEX username_changes_user ON username_changes (user_id, change_time);
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE users ADD avatar_updated_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE user_avatars (
+	user_id TEXT PRIMARY KEY REFERENCES users,
+	content_type TEXT NOT NULL,
+	image BYTEA NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE users
+	DROP COLUMN editor_assigned_sound,
+	DROP COLUMN editor_assigned_desktop,
+	DROP COLUMN typing_in_edited_entry_sound,
+	DROP COLUMN typing_in_edited_entry_desktop,
+	DROP COLUMN other_notifications_sound,
+	DROP COLUMN other_notifications_desktop;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE users
+	ADD editor_assigned_sound BOOLEAN NOT NULL DEFAULT false,
+	ADD editor_assigned_desktop BOOLEAN NOT NULL DEFAULT false,
+	ADD typing_in_edited_entry_sound BOOLEAN NOT NULL DEFAULT false,
+	ADD typing_in_edited_entry_desktop BOOLEAN NOT NULL DEFAULT false,
+	ADD other_notifications_sound BOOLEAN NOT NULL DEFAULT false,
+	ADD other_notifications_desktop BOOLEAN NOT NULL DEFAULT false;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE events DROP COLUMN time_display_zone;
+
+ALTER TABLE users
+	DROP COLUMN use_event_time_display,
+	DROP COLUMN time_display_zone;
\ No newline at end of file
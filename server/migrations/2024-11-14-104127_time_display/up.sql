@@ -0,0 +1,12 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE events ADD COLUMN time_display_zone TEXT;
+
+ALTER TABLE users
+	ADD use_event_time_display BOOLEAN NOT NULL DEFAULT true,
+	ADD time_display_zone TEXT;
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+CREATE TABLE queued_jobs (
+	id TEXT PRIMARY KEY,
+	webhook_url TEXT,
+	webhook_body BYTEA,
+	recurring_entry TEXT REFERENCES recurring_entries ON DELETE CASCADE,
+	occurrence_time TIMESTAMP WITH TIME ZONE,
+	attempts INTEGER NOT NULL DEFAULT 0,
+	queued_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	CHECK (
+		(webhook_url IS NOT NULL AND webhook_body IS NOT NULL AND recurring_entry IS NULL AND occurrence_time IS NULL)
+		OR (webhook_url IS NULL AND webhook_body IS NULL AND recurring_entry IS NOT NULL AND occurrence_time IS NOT NULL)
+	)
+);
+
+CREATE TABLE recurring_entry_occurrences (
+	recurring_entry TEXT NOT NULL REFERENCES recurring_entries ON DELETE CASCADE,
+	occurrence_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	PRIMARY KEY (recurring_entry, occurrence_time)
+);
\ No newline at end of file
@@ -0,0 +1,17 @@
+-- © 2022-2024 Jacob Riddle (ElementalAlchemist)
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+
+ALTER TABLE event_log ADD COLUMN latest_history_id TEXT;
+
+UPDATE event_log
+SET latest_history_id = latest_history.id
+FROM (
+	SELECT DISTINCT ON (log_entry) log_entry, id
+	FROM event_log_history
+	ORDER BY log_entry, edit_time DESC
+) AS latest_history
+WHERE event_log.id = latest_history.log_entry;
\ No newline at end of file
@@ -5,6 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::data_sync::SubscriptionManager;
+use crate::metrics::Metrics;
 use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
@@ -17,6 +18,7 @@ pub fn add_routes(
 	app: &mut Server<()>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	metrics: Arc<Metrics>,
 ) -> miette::Result<()> {
-	add_v1_routes(app, db_connection_pool, subscription_manager)
+	add_v1_routes(app, db_connection_pool, subscription_manager, metrics)
 }
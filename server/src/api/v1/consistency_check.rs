@@ -0,0 +1,170 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log_list::load_event_log_entries;
+use super::structures::consistency_report::{ConsistencyReport, EntryMismatch};
+use crate::data_sync::event_log_entries_from_db;
+use crate::database::handle_lost_db_connection;
+use crate::models::{Event as EventDb, EventLogEntry as EventLogEntryDb};
+use crate::schema::{event_log, events, users};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use http_types::mime;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::EventLogEntrySummary;
+use tide::{Request, Response, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+/// GET /api/v1/event/:id/consistency
+///
+/// Compares the event log for the specified event as served through the API with the event log as sent to clients
+/// over the websocket, and reports any entries whose data doesn't match. Responds with a [ConsistencyReport] object.
+///
+/// This is an internal check rather than part of the API for applications, so it requires a logged-in administrator
+/// instead of an application token.
+pub async fn consistency_check(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let Some(openid_user_id) = request.user_id() else {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not logged in."),
+		));
+	};
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+
+	let is_admin: QueryResult<Option<bool>> = users::table
+		.filter(users::openid_user_id.eq(&openid_user_id))
+		.select(users::is_admin)
+		.first(&mut *db_connection)
+		.optional();
+	match is_admin {
+		Ok(Some(true)) => (),
+		Ok(_) => {
+			return Err(tide::Error::new(
+				StatusCode::Forbidden,
+				anyhow::Error::msg("Not authorized to access this resource."),
+			))
+		}
+		Err(error) => {
+			tide::log::error!("API error checking consistency check access: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	}
+
+	let event_id = request.param("id")?;
+	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
+	let event: EventDb = match event {
+		Ok(event) => event,
+		Err(error) => {
+			if let diesel::result::Error::NotFound = error {
+				return Err(tide::Error::new(
+					StatusCode::NotFound,
+					anyhow::Error::msg("No such event"),
+				));
+			}
+			tide::log::error!("API error loading event: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let rest_entries = load_event_log_entries(&mut db_connection, &event, None)?;
+	let rest_summaries: Vec<EventLogEntrySummary> = rest_entries.iter().map(|entry| entry.into()).collect();
+
+	let websocket_entries = db_connection.transaction(|db_connection| {
+		let log_entries: Vec<EventLogEntryDb> = event_log::table
+			.filter(event_log::event.eq(&event.id).and(event_log::deleted_by.is_null()))
+			.load(db_connection)?;
+		event_log_entries_from_db(db_connection, &event.id, log_entries)
+	});
+	let websocket_entries = match websocket_entries {
+		Ok(entries) => entries,
+		Err(error) => {
+			tide::log::error!("API error loading event log for consistency check: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+	let websocket_summaries: Vec<EventLogEntrySummary> = websocket_entries.iter().map(|entry| entry.into()).collect();
+
+	let rest_fingerprint = summaries_fingerprint(&rest_summaries);
+	let websocket_fingerprint = summaries_fingerprint(&websocket_summaries);
+
+	let mut websocket_summaries_by_id: HashMap<&str, &EventLogEntrySummary> = websocket_summaries
+		.iter()
+		.map(|summary| (summary.id.as_str(), summary))
+		.collect();
+	let mut missing_from_websocket: Vec<String> = Vec::new();
+	let mut mismatches: Vec<EntryMismatch> = Vec::new();
+	for rest_summary in rest_summaries.iter() {
+		match websocket_summaries_by_id.remove(rest_summary.id.as_str()) {
+			Some(websocket_summary) => {
+				let fields = rest_summary.differing_fields(websocket_summary);
+				if !fields.is_empty() {
+					mismatches.push(EntryMismatch {
+						entry_id: rest_summary.id.clone(),
+						fields,
+					});
+				}
+			}
+			None => missing_from_websocket.push(rest_summary.id.clone()),
+		}
+	}
+	let mut missing_from_rest: Vec<String> = websocket_summaries_by_id.into_keys().map(String::from).collect();
+	missing_from_rest.sort();
+
+	let report = ConsistencyReport {
+		event_id: event.id,
+		rest_entry_count: rest_summaries.len(),
+		websocket_entry_count: websocket_summaries.len(),
+		rest_fingerprint,
+		websocket_fingerprint,
+		missing_from_rest,
+		missing_from_websocket,
+		mismatches,
+	};
+	let report_json = match serde_json::to_string(&report) {
+		Ok(data) => data,
+		Err(error) => {
+			tide::log::error!("API error serializing consistency report: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Failed to generate the response."),
+			));
+		}
+	};
+	Ok(Response::builder(StatusCode::Ok)
+		.body(report_json)
+		.content_type(mime::JSON)
+		.build())
+}
+
+/// Hashes a set of entry summaries in a way that doesn't depend on the order in which they were loaded
+fn summaries_fingerprint(summaries: &[EventLogEntrySummary]) -> String {
+	let mut summaries: Vec<&EventLogEntrySummary> = summaries.iter().collect();
+	summaries.sort_by(|a, b| a.id.cmp(&b.id));
+	let mut hasher = Sha256::new();
+	for summary in summaries {
+		// Summaries contain only plain data, so serializing them can't fail
+		let serialized = serde_json::to_vec(summary).expect("entry summaries can be serialized");
+		hasher.update(&serialized);
+	}
+	format!("{:x}", hasher.finalize())
+}
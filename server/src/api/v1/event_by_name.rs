@@ -5,7 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::structures::event::Event as EventApi;
-use super::utils::check_application;
+use super::utils::{check_application, check_application_event_access};
 use crate::database::handle_lost_db_connection;
 use crate::models::Event as EventDb;
 use crate::schema::events;
@@ -40,6 +40,7 @@ pub async fn event_by_name(
 		.first(&mut *db_connection);
 	match event {
 		Ok(event) => {
+			check_application_event_access(&mut db_connection, &application, &event.id)?;
 			let event = EventApi {
 				id: event.id,
 				name: event.name,
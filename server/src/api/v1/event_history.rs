@@ -0,0 +1,284 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::structures::event_log_entry::EndTimeData;
+use super::structures::event_log_history_entry::EventLogHistoryEntry as EventLogHistoryEntryApi;
+use super::structures::tag::Tag as TagApi;
+use super::structures::user::User as UserApi;
+use super::utils::{check_application, check_application_event_access};
+use crate::database::handle_lost_db_connection;
+use crate::models::{EventLogHistoryEntry as EventLogHistoryEntryDb, Tag as TagDb, User as UserDb};
+use crate::schema::{event_log, event_log_history, event_log_history_tags, events, tags, users};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use tide::{Request, Response, StatusCode};
+
+/// The number of history records sent in a page if the request doesn't specify a limit
+const DEFAULT_PAGE_SIZE: i64 = 1000;
+/// The largest number of history records that can be requested in one page
+const MAX_PAGE_SIZE: i64 = 10000;
+
+#[derive(Deserialize)]
+struct QueryParams {
+	since: Option<DateTime<Utc>>,
+	until: Option<DateTime<Utc>>,
+	after: Option<String>,
+	limit: Option<i64>,
+}
+
+/// GET /api/v1/event/:id/history
+///
+/// Gets the recorded revisions of the entries in the event log for the specified event, including deleted entries.
+/// The response is newline-delimited JSON, with one [EventLogHistoryEntryApi] object per line, in the order the
+/// revisions were made.
+///
+/// The `since` and `until` query arguments take ISO 8601 timestamps and limit the response to revisions made at or
+/// after and before those times respectively. At most `limit` revisions are sent (1000 by default, and never more than
+/// 10000). If there are more revisions to send, the `X-Next-After` response header is set; pass its value as the
+/// `after` query argument (with the same `since` and `until` arguments) to get the next page.
+pub async fn event_history(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let query_params: QueryParams = request.query()?;
+	let limit = query_params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+	if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+		return Err(tide::Error::new(
+			StatusCode::BadRequest,
+			anyhow::Error::msg(format!("The limit must be between 1 and {}.", MAX_PAGE_SIZE)),
+		));
+	}
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+	let application = check_application(&request, &mut db_connection).await?;
+	if !application.read_log {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		));
+	}
+
+	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
+	let event_count: QueryResult<i64> = events::table
+		.filter(events::id.eq(event_id))
+		.count()
+		.get_result(&mut *db_connection);
+	match event_count {
+		Ok(0) => {
+			return Err(tide::Error::new(
+				StatusCode::NotFound,
+				anyhow::Error::msg("No such event"),
+			))
+		}
+		Ok(_) => (),
+		Err(error) => {
+			tide::log::error!("API error loading event for history: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	}
+
+	// Pages are ordered by edit time and then by ID, so a page continues from the edit time of the last record sent
+	let after: Option<(DateTime<Utc>, String)> = match query_params.after {
+		Some(after_id) => {
+			let after_time: QueryResult<Option<DateTime<Utc>>> = event_log_history::table
+				.filter(
+					event_log_history::id.eq(&after_id).and(
+						event_log_history::log_entry.eq_any(
+							event_log::table
+								.filter(event_log::event.eq(event_id))
+								.select(event_log::id),
+						),
+					),
+				)
+				.select(event_log_history::edit_time)
+				.first(&mut *db_connection)
+				.optional();
+			match after_time {
+				Ok(Some(after_time)) => Some((after_time, after_id)),
+				Ok(None) => {
+					return Err(tide::Error::new(
+						StatusCode::BadRequest,
+						anyhow::Error::msg("The history record to continue after doesn't exist in this event."),
+					))
+				}
+				Err(error) => {
+					tide::log::error!("API error loading history page position: {}", error);
+					return Err(tide::Error::new(
+						StatusCode::InternalServerError,
+						anyhow::Error::msg("Database error"),
+					));
+				}
+			}
+		}
+		None => None,
+	};
+
+	let mut history_query = event_log_history::table
+		.filter(
+			event_log_history::log_entry.eq_any(
+				event_log::table
+					.filter(event_log::event.eq(event_id))
+					.select(event_log::id),
+			),
+		)
+		.into_boxed();
+	if let Some(since) = query_params.since {
+		history_query = history_query.filter(event_log_history::edit_time.ge(since));
+	}
+	if let Some(until) = query_params.until {
+		history_query = history_query.filter(event_log_history::edit_time.lt(until));
+	}
+	if let Some((after_time, after_id)) = after.as_ref() {
+		history_query = history_query.filter(
+			event_log_history::edit_time
+				.gt(after_time)
+				.or(event_log_history::edit_time
+					.eq(after_time)
+					.and(event_log_history::id.gt(after_id))),
+		);
+	}
+	let history: QueryResult<Vec<EventLogHistoryEntryDb>> = history_query
+		.order((event_log_history::edit_time.asc(), event_log_history::id.asc()))
+		.limit(limit + 1)
+		.load(&mut *db_connection);
+	let mut history = match history {
+		Ok(history) => history,
+		Err(error) => {
+			tide::log::error!("API error loading event log history: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+	let next_after = if history.len() as i64 > limit {
+		history.pop();
+		history.last().map(|history_entry| history_entry.id.clone())
+	} else {
+		None
+	};
+
+	let history_ids: Vec<String> = history.iter().map(|history_entry| history_entry.id.clone()).collect();
+	let history_tags: QueryResult<Vec<(String, TagDb)>> = event_log_history_tags::table
+		.inner_join(tags::table)
+		.filter(event_log_history_tags::history_log_entry.eq_any(&history_ids))
+		.select((event_log_history_tags::history_log_entry, tags::all_columns))
+		.load(&mut *db_connection);
+	let history_tags = match history_tags {
+		Ok(history_tags) => history_tags,
+		Err(error) => {
+			tide::log::error!("API error loading event log history tags: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+	let mut tags_by_history_id: HashMap<String, Vec<TagApi>> = HashMap::new();
+	for (history_id, tag) in history_tags {
+		tags_by_history_id.entry(history_id).or_default().push(tag.into());
+	}
+
+	let user_ids: HashSet<String> = history
+		.iter()
+		.flat_map(|history_entry| {
+			history_entry
+				.edit_user
+				.iter()
+				.chain(history_entry.editor.iter())
+				.chain(history_entry.deleted_by.iter())
+				.cloned()
+		})
+		.collect();
+	let user_ids: Vec<String> = user_ids.into_iter().collect();
+	let users: QueryResult<Vec<UserDb>> = users::table
+		.filter(users::id.eq_any(&user_ids))
+		.load(&mut *db_connection);
+	let users: HashMap<String, UserDb> = match users {
+		Ok(users) => users.into_iter().map(|user| (user.id.clone(), user)).collect(),
+		Err(error) => {
+			tide::log::error!("API error loading event log history users: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	drop(db_connection);
+
+	let get_user = |user_id: Option<String>| -> Option<UserApi> {
+		user_id.and_then(|user_id| users.get(&user_id).cloned().map(|user| user.into()))
+	};
+
+	let mut body = String::new();
+	for history_entry in history {
+		let end_time = match (history_entry.end_time, history_entry.end_time_incomplete) {
+			(Some(time), _) => EndTimeData::Time(time),
+			(None, true) => EndTimeData::NotEntered,
+			(None, false) => EndTimeData::NoTime,
+		};
+		let tags = tags_by_history_id.remove(&history_entry.id).unwrap_or_default();
+		let history_entry = EventLogHistoryEntryApi {
+			edit_user: get_user(history_entry.edit_user),
+			editor: get_user(history_entry.editor),
+			deleted_by: get_user(history_entry.deleted_by),
+			id: history_entry.id,
+			entry_id: history_entry.log_entry,
+			edit_time: history_entry.edit_time,
+			edit_application: history_entry.edit_application,
+			start_time: history_entry.start_time,
+			end_time,
+			entry_type: history_entry.entry_type,
+			description: history_entry.description,
+			media_links: history_entry.media_links.into_iter().flatten().collect(),
+			submitter_or_winner: history_entry.submitter_or_winner,
+			tags,
+			notes: history_entry.notes,
+			video_link: history_entry.video_link,
+			parent: history_entry.parent,
+			created_at: history_entry.created_at,
+			manual_sort_key: history_entry.manual_sort_key,
+			video_edit_state: history_entry.video_edit_state.into(),
+			video_edit_state_rule_tag: history_entry.video_edit_state_rule_tag,
+			video_processing_state: history_entry.video_processing_state.into(),
+			video_errors: history_entry.video_errors,
+			poster_moment: history_entry.poster_moment,
+			missing_giveaway_information: history_entry.missing_giveaway_information,
+		};
+		match serde_json::to_string(&history_entry) {
+			Ok(json) => {
+				body.push_str(&json);
+				body.push('\n');
+			}
+			Err(error) => {
+				tide::log::error!("API error occurred serializing event log history: {}", error);
+				return Err(tide::Error::new(
+					StatusCode::InternalServerError,
+					anyhow::Error::msg("Failed to generate the response"),
+				));
+			}
+		}
+	}
+
+	let mut response = Response::builder(StatusCode::Ok)
+		.body(body)
+		.content_type("application/x-ndjson");
+	if let Some(next_after) = next_after {
+		response = response.header("X-Next-After", next_after);
+	}
+	Ok(response.build())
+}
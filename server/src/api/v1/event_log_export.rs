@@ -0,0 +1,277 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log_list::load_event_log_entries;
+use super::structures::event_log_entry::{EndTimeData, EventLogEntry as EventLogEntryApi};
+use super::structures::user::User as UserApi;
+use super::structures::video_edit_state::VideoEditState;
+use super::structures::video_processing_state::VideoProcessingState;
+use super::utils::check_event_log_read_access;
+use crate::database::handle_lost_db_connection;
+use crate::models::Event as EventDb;
+use crate::schema::events;
+use chrono::TimeDelta;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tide::{Request, Response, StatusCode};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExportFormat {
+	#[default]
+	Csv,
+	Tsv,
+}
+
+impl ExportFormat {
+	fn extension(&self) -> &'static str {
+		match self {
+			Self::Csv => "csv",
+			Self::Tsv => "tsv",
+		}
+	}
+
+	fn content_type(&self) -> &'static str {
+		match self {
+			Self::Csv => "text/csv; charset=utf-8",
+			Self::Tsv => "text/tab-separated-values; charset=utf-8",
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+	#[serde(default)]
+	format: ExportFormat,
+}
+
+const EXPORT_HEADERS: [&str; 25] = [
+	"Number",
+	"Parent Number",
+	"Depth",
+	"Tab",
+	"Start",
+	"End",
+	"Start Time",
+	"End Time",
+	"Type",
+	"Description",
+	"Submitter/Winner",
+	"Media Links",
+	"Tags",
+	"Notes",
+	"Poster Moment",
+	"Missing Giveaway Information",
+	"Editor",
+	"Editor Link",
+	"Created By",
+	"Video Link",
+	"Video Edit State",
+	"Video Processing State",
+	"Video Errors",
+	"ID",
+	"Parent ID",
+];
+
+/// GET /api/v1/event/:id/log/export
+///
+/// Downloads the full event log for the specified event as a spreadsheet file. The `format` query argument may be
+/// `csv` (the default) or `tsv`. Each entry is one row, and child entries come directly after their parents. The
+/// parent/child structure is flattened into the "Parent Number" and "Depth" columns. The "Start" and "End" columns
+/// are relative to the event start, as shown in the log; the "Start Time" and "End Time" columns are full ISO 8601
+/// timestamps.
+///
+/// Unlike most API endpoints, this can also be used by logged-in users with access to the event (without an
+/// application token) so that the log can be downloaded from the web client.
+pub async fn event_log_export(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let query_params: QueryParams = request.query()?;
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+
+	let event_id = request.param("id")?;
+	check_event_log_read_access(&request, &mut db_connection, event_id).await?;
+
+	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
+	let event: EventDb = match event {
+		Ok(event) => event,
+		Err(error) => {
+			if let diesel::result::Error::NotFound = error {
+				return Err(tide::Error::new(
+					StatusCode::NotFound,
+					anyhow::Error::msg("No such event"),
+				));
+			}
+			tide::log::error!("API error loading event: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let event_log = load_event_log_entries(&mut db_connection, &event, None)?;
+
+	let mut export = String::new();
+	write_row(&mut export, query_params.format, EXPORT_HEADERS.iter().copied());
+
+	let mut entry_numbers: HashMap<&str, i32> = HashMap::new();
+	let mut entry_depths: HashMap<&str, u32> = HashMap::new();
+	for entry in event_log.iter() {
+		entry_numbers.insert(&entry.id, entry.entry_number);
+		// Parents always come before their children, so the parent's depth is already known.
+		let depth = entry
+			.parent
+			.as_ref()
+			.and_then(|parent| entry_depths.get(parent.as_str()))
+			.map(|parent_depth| parent_depth + 1)
+			.unwrap_or(0);
+		entry_depths.insert(&entry.id, depth);
+
+		let parent_number = entry
+			.parent
+			.as_ref()
+			.and_then(|parent| entry_numbers.get(parent.as_str()))
+			.map(|number| number.to_string())
+			.unwrap_or_default();
+		let fields = export_fields(entry, &event, parent_number, depth);
+		write_row(
+			&mut export,
+			query_params.format,
+			fields.iter().map(|field| field.as_str()),
+		);
+	}
+
+	let file_name = format!("{}.{}", export_file_name(&event.name), query_params.format.extension());
+	Ok(Response::builder(StatusCode::Ok)
+		.body(export)
+		.content_type(query_params.format.content_type())
+		.header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+		.build())
+}
+
+/// Gets the values of each column for an entry's row
+fn export_fields(entry: &EventLogEntryApi, event: &EventDb, parent_number: String, depth: u32) -> Vec<String> {
+	let (end, end_time) = match &entry.end_time {
+		EndTimeData::Time(time) => (format_duration(*time - event.start_time), time.to_rfc3339()),
+		EndTimeData::NotEntered => (String::new(), String::new()),
+		EndTimeData::NoTime => (String::from("—"), String::new()),
+	};
+	let user_name = |user: &Option<UserApi>| user.as_ref().map(|user| user.username.clone()).unwrap_or_default();
+
+	vec![
+		entry.entry_number.to_string(),
+		parent_number,
+		depth.to_string(),
+		entry.tab.name.clone(),
+		format_duration(entry.start_time - event.start_time),
+		end,
+		entry.start_time.to_rfc3339(),
+		end_time,
+		entry
+			.entry_type
+			.as_ref()
+			.map(|entry_type| entry_type.name.clone())
+			.unwrap_or_default(),
+		entry.description.clone(),
+		entry.submitter_or_winner.clone(),
+		entry.media_links.join(" "),
+		entry
+			.tags
+			.iter()
+			.map(|tag| tag.tag.as_str())
+			.collect::<Vec<_>>()
+			.join(", "),
+		entry.notes.clone(),
+		yes_no(entry.poster_moment),
+		yes_no(entry.missing_giveaway_information),
+		user_name(&entry.editor),
+		entry.editor_link.clone().unwrap_or_default(),
+		user_name(&entry.created_by),
+		entry.video_link.clone().unwrap_or_default(),
+		video_edit_state_name(&entry.video_edit_state).to_string(),
+		video_processing_state_name(&entry.video_processing_state).to_string(),
+		entry.video_errors.clone(),
+		entry.id.clone(),
+		entry.parent.clone().unwrap_or_default(),
+	]
+}
+
+/// Appends a row to the export in the given format
+fn write_row<'a>(export: &mut String, format: ExportFormat, fields: impl Iterator<Item = &'a str>) {
+	let fields: Vec<String> = match format {
+		ExportFormat::Csv => fields
+			.map(|field| {
+				if field.contains([',', '"', '\r', '\n']) {
+					format!("\"{}\"", field.replace('"', "\"\""))
+				} else {
+					field.to_string()
+				}
+			})
+			.collect(),
+		// TSV has no way to escape separators, so they're replaced with spaces
+		ExportFormat::Tsv => fields.map(|field| field.replace(['\t', '\r', '\n'], " ")).collect(),
+	};
+	let separator = match format {
+		ExportFormat::Csv => ",",
+		ExportFormat::Tsv => "\t",
+	};
+	export.push_str(&fields.join(separator));
+	export.push_str("\r\n");
+}
+
+/// Formats a time relative to the event start the same way the event log does
+pub fn format_duration(duration: TimeDelta) -> String {
+	let sign = if duration < TimeDelta::zero() { "-" } else { "" };
+	let duration = duration.abs();
+	format!("{}{}:{:02}", sign, duration.num_hours(), duration.num_minutes() % 60)
+}
+
+fn yes_no(value: bool) -> String {
+	String::from(if value { "Yes" } else { "No" })
+}
+
+fn video_edit_state_name(state: &VideoEditState) -> &'static str {
+	match state {
+		VideoEditState::NoVideo => "No Video",
+		VideoEditState::MarkedForEditing => "Marked for Editing",
+		VideoEditState::DoneEditing => "Done Editing",
+	}
+}
+
+fn video_processing_state_name(state: &VideoProcessingState) -> &'static str {
+	match state {
+		VideoProcessingState::Unedited => "Unedited",
+		VideoProcessingState::Edited => "Edited",
+		VideoProcessingState::Claimed => "Claimed",
+		VideoProcessingState::Finalizing => "Finalizing",
+		VideoProcessingState::Transcoding => "Transcoding",
+		VideoProcessingState::Done => "Done",
+		VideoProcessingState::Modified => "Modified",
+		VideoProcessingState::Unlisted => "Unlisted",
+	}
+}
+
+/// Makes a file name from the event name by replacing characters that aren't allowed in file names on common systems
+pub fn export_file_name(event_name: &str) -> String {
+	event_name
+		.chars()
+		.map(|c| {
+			if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+				'_'
+			} else {
+				c
+			}
+		})
+		.collect()
+}
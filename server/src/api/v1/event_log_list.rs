@@ -10,7 +10,7 @@ use super::structures::event_log_response::EventLogResponse;
 use super::structures::event_log_tab::EventLogTab;
 use super::structures::tag::{Tag as TagApi, TagPlaylist};
 use super::structures::user::User as UserApi;
-use super::utils::check_application;
+use super::utils::{check_application, check_application_event_access};
 use crate::database::handle_lost_db_connection;
 use crate::models::{
 	EntryType as EntryTypeDb, Event as EventDb, EventLogEntry as EventLogEntryDb, EventLogTab as EventLogTabDb,
@@ -56,6 +56,7 @@ pub async fn event_log_list(
 	}
 
 	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
 	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
 	let event: EventDb = match event {
 		Ok(event) => event,
@@ -74,16 +75,45 @@ pub async fn event_log_list(
 		}
 	};
 
+	let retrieved_time = Utc::now();
+	let event_log = load_event_log_entries(&mut db_connection, &event, query_params.since)?;
+
+	let event_log_response = EventLogResponse {
+		event_log,
+		retrieved_time,
+	};
+	let event_log_json = match serde_json::to_string(&event_log_response) {
+		Ok(json) => json,
+		Err(error) => {
+			tide::log::error!("API error occurred serializing the event log: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Failed to generate the response"),
+			));
+		}
+	};
+	Ok(Response::builder(StatusCode::Ok)
+		.body(event_log_json)
+		.content_type(mime::JSON)
+		.build())
+}
+
+/// Loads the event log for an event in the form provided by the API. Child entries are placed directly after their
+/// parents. If `edited_since` is provided, only entries last edited at or after that time are included.
+pub fn load_event_log_entries(
+	db_connection: &mut PgConnection,
+	event: &EventDb,
+	edited_since: Option<DateTime<Utc>>,
+) -> Result<Vec<EventLogEntryApi>, tide::Error> {
 	let default_event_tab = EventLogTab {
 		id: String::new(),
-		name: event.first_tab_name,
+		name: event.first_tab_name.clone(),
 	};
 
-	let retrieved_time = Utc::now();
-	let event_log: QueryResult<Vec<EventLogEntryDb>> = if let Some(edited_since) = query_params.since {
+	let event_log: QueryResult<Vec<EventLogEntryDb>> = if let Some(edited_since) = edited_since {
 		event_log::table
 			.filter(
-				event_log::event.eq(event_id).and(event_log::deleted_by.is_null()).and(
+				event_log::event.eq(&event.id).and(event_log::deleted_by.is_null()).and(
 					event_log_history::table
 						.filter(event_log_history::log_entry.eq(event_log::id))
 						.select(max(event_log_history::edit_time))
@@ -96,16 +126,16 @@ pub async fn event_log_list(
 				event_log::manual_sort_key.asc().nulls_last(),
 				event_log::created_at.asc(),
 			))
-			.load(&mut *db_connection)
+			.load(db_connection)
 	} else {
 		event_log::table
-			.filter(event_log::event.eq(event_id).and(event_log::deleted_by.is_null()))
+			.filter(event_log::event.eq(&event.id).and(event_log::deleted_by.is_null()))
 			.order_by((
 				event_log::start_time.asc(),
 				event_log::manual_sort_key.asc().nulls_last(),
 				event_log::created_at.asc(),
 			))
-			.load(&mut *db_connection)
+			.load(db_connection)
 	};
 
 	let event_log: Vec<EventLogEntryDb> = match event_log {
@@ -147,8 +177,8 @@ pub async fn event_log_list(
 	let event_log = event_log;
 
 	let event_log_tabs: QueryResult<Vec<EventLogTabDb>> = event_log_tabs::table
-		.filter(event_log_tabs::event.eq(&event_id))
-		.load(&mut *db_connection);
+		.filter(event_log_tabs::event.eq(&event.id))
+		.load(db_connection);
 	let event_log_tabs = match event_log_tabs {
 		Ok(tabs) => tabs,
 		Err(error) => {
@@ -172,7 +202,7 @@ pub async fn event_log_list(
 	} else {
 		let entry_types: QueryResult<Vec<EntryTypeDb>> = entry_types::table
 			.filter(entry_types::id.eq_any(&entry_type_ids))
-			.load(&mut *db_connection);
+			.load(db_connection);
 		match entry_types {
 			Ok(entry_types) => entry_types
 				.into_iter()
@@ -199,19 +229,17 @@ pub async fn event_log_list(
 		}
 	};
 
-	let editor_ids: HashSet<String> = event_log
+	let user_ids: HashSet<String> = event_log
 		.iter()
-		.filter_map(|log_entry| log_entry.editor.clone())
+		.flat_map(|log_entry| log_entry.editor.iter().chain(log_entry.created_by.iter()).cloned())
 		.collect();
-	let editor_ids: Vec<String> = editor_ids.into_iter().collect();
-	let editors: HashMap<String, UserApi> = if editor_ids.is_empty() {
+	let user_ids: Vec<String> = user_ids.into_iter().collect();
+	let users: HashMap<String, UserApi> = if user_ids.is_empty() {
 		HashMap::new()
 	} else {
-		let editors: QueryResult<Vec<UserDb>> = users::table
-			.filter(users::id.eq_any(&editor_ids))
-			.load(&mut *db_connection);
-		match editors {
-			Ok(editors) => editors
+		let users: QueryResult<Vec<UserDb>> = users::table.filter(users::id.eq_any(&user_ids)).load(db_connection);
+		match users {
+			Ok(users) => users
 				.into_iter()
 				.map(|user| {
 					(
@@ -227,7 +255,7 @@ pub async fn event_log_list(
 				})
 				.collect(),
 			Err(error) => {
-				tide::log::error!("API error loading event log editors: {}", error);
+				tide::log::error!("API error loading event log users: {}", error);
 				return Err(tide::Error::new(
 					StatusCode::InternalServerError,
 					anyhow::Error::msg("Database error"),
@@ -239,7 +267,7 @@ pub async fn event_log_list(
 	let event_log_ids: Vec<String> = event_log.iter().map(|entry| entry.id.clone()).collect();
 	let entry_tags: QueryResult<Vec<EventLogTag>> = event_log_tags::table
 		.filter(event_log_tags::log_entry.eq_any(event_log_ids))
-		.load(&mut *db_connection);
+		.load(db_connection);
 	let entry_tags = match entry_tags {
 		Ok(entry_tags) => entry_tags,
 		Err(error) => {
@@ -251,7 +279,7 @@ pub async fn event_log_list(
 		}
 	};
 	let tag_ids: Vec<String> = entry_tags.iter().map(|entry_tag| entry_tag.tag.clone()).collect();
-	let tags: QueryResult<Vec<TagDb>> = tags::table.filter(tags::id.eq_any(tag_ids)).load(&mut *db_connection);
+	let tags: QueryResult<Vec<TagDb>> = tags::table.filter(tags::id.eq_any(tag_ids)).load(db_connection);
 	let tags = match tags {
 		Ok(tags) => tags,
 		Err(error) => {
@@ -346,7 +374,7 @@ pub async fn event_log_list(
 						tab_start_time_entry = parent_entry.clone();
 					} else {
 						let parent_entry: QueryResult<EventLogEntryDb> =
-							event_log::table.find(parent_id).first(&mut *db_connection);
+							event_log::table.find(parent_id).first(db_connection);
 						let parent_entry = match parent_entry {
 							Ok(entry) => entry,
 							Err(_) => break, // We can't go any further or emit an error from here, so we'll just do our best
@@ -370,6 +398,7 @@ pub async fn event_log_list(
 
 			EventLogEntryApi {
 				id: entry.id.clone(),
+				entry_number: entry.entry_number,
 				start_time: entry.start_time,
 				end_time,
 				entry_type,
@@ -382,7 +411,11 @@ pub async fn event_log_list(
 				editor: entry
 					.editor
 					.as_ref()
-					.map(|editor_id| editors.get(editor_id).unwrap().clone()),
+					.map(|editor_id| users.get(editor_id).unwrap().clone()),
+				created_by: entry
+					.created_by
+					.as_ref()
+					.map(|creator_id| users.get(creator_id).unwrap().clone()),
 				video_link: entry.video_link.clone(),
 				parent: entry.parent.clone(),
 				manual_sort_key: entry.manual_sort_key,
@@ -400,22 +433,5 @@ pub async fn event_log_list(
 		})
 		.collect();
 
-	let event_log_response = EventLogResponse {
-		event_log,
-		retrieved_time,
-	};
-	let event_log_json = match serde_json::to_string(&event_log_response) {
-		Ok(json) => json,
-		Err(error) => {
-			tide::log::error!("API error occurred serializing the event log: {}", error);
-			return Err(tide::Error::new(
-				StatusCode::InternalServerError,
-				anyhow::Error::msg("Failed to generate the response"),
-			));
-		}
-	};
-	Ok(Response::builder(StatusCode::Ok)
-		.body(event_log_json)
-		.content_type(mime::JSON)
-		.build())
+	Ok(event_log)
 }
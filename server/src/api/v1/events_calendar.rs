@@ -0,0 +1,188 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::utils::{check_application, check_application_auth_key};
+use crate::data_sync::load_event_permissions;
+use crate::database::handle_lost_db_connection;
+use crate::models::Event as EventDb;
+use crate::schema::{application_events, events, users};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde::Deserialize;
+use tide::{Request, Response, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+#[derive(Deserialize)]
+struct QueryParams {
+	token: Option<String>,
+}
+
+/// The longest a content line is allowed to be, in octets, before it must be folded (RFC 5545 section 3.1)
+const MAX_LINE_LENGTH: usize = 75;
+
+/// GET /api/v1/events.ics
+///
+/// Gets an iCalendar feed of the events the requester can access. Each event has its start time, its end time (if one
+/// is set), and a link to its log.
+///
+/// Requests can come from an application allowed to read event logs or from a logged-in user. Since calendar
+/// applications generally can't set headers on feed requests, an application's token may be passed as the `token`
+/// query argument instead of in the `Authorization` header.
+pub async fn events_calendar(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let query_params: QueryParams = request.query()?;
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+
+	let application = if request.header("Authorization").is_some() {
+		Some(check_application(&request, &mut db_connection).await?)
+	} else if let Some(token) = query_params.token.as_ref() {
+		Some(check_application_auth_key(token, &mut db_connection)?)
+	} else {
+		None
+	};
+
+	let events: QueryResult<Vec<EventDb>> = match application {
+		Some(application) => {
+			if !application.read_log {
+				return Err(tide::Error::new(
+					StatusCode::Unauthorized,
+					anyhow::Error::msg("Not authorized to access this resource"),
+				));
+			}
+			if application.restrict_events {
+				events::table
+					.filter(
+						events::id.eq_any(
+							application_events::table
+								.filter(application_events::application.eq(&application.id))
+								.select(application_events::event),
+						),
+					)
+					.order(events::start_time)
+					.load(&mut *db_connection)
+			} else {
+				events::table.order(events::start_time).load(&mut *db_connection)
+			}
+		}
+		None => {
+			let Some(openid_user_id) = request.user_id() else {
+				return Err(tide::Error::new(
+					StatusCode::Unauthorized,
+					anyhow::Error::msg("Not authorized"),
+				));
+			};
+			let user_id: QueryResult<Option<String>> = users::table
+				.filter(users::openid_user_id.eq(&openid_user_id))
+				.select(users::id)
+				.first(&mut *db_connection)
+				.optional();
+			user_id.and_then(|user_id| {
+				let Some(user_id) = user_id else {
+					return Ok(Vec::new());
+				};
+				let event_ids: Vec<String> = load_event_permissions(&mut *db_connection, &user_id)?
+					.into_keys()
+					.map(|event| event.id)
+					.collect();
+				events::table
+					.filter(events::id.eq_any(&event_ids))
+					.order(events::start_time)
+					.load(&mut *db_connection)
+			})
+		}
+	};
+	let events = match events {
+		Ok(events) => events,
+		Err(error) => {
+			tide::log::error!("API error listing events for calendar: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let request_url = request.url();
+	let host = request_url.host_str().unwrap_or("stream-log");
+	let now = Utc::now();
+
+	let mut calendar = String::new();
+	write_line(&mut calendar, "BEGIN:VCALENDAR");
+	write_line(&mut calendar, "VERSION:2.0");
+	write_line(&mut calendar, "PRODID:-//ElementalAlchemist//Stream Log//EN");
+	write_line(&mut calendar, "CALSCALE:GREGORIAN");
+	write_line(&mut calendar, "X-WR-CALNAME:Stream Log Events");
+	for event in events.iter() {
+		let mut log_url = request_url.clone();
+		log_url.set_path(&format!("/log/{}", event.id));
+		log_url.set_query(None);
+		log_url.set_fragment(None);
+
+		write_line(&mut calendar, "BEGIN:VEVENT");
+		write_line(&mut calendar, &format!("UID:{}@{}", event.id, host));
+		write_line(&mut calendar, &format!("DTSTAMP:{}", format_time(&now)));
+		write_line(&mut calendar, &format!("DTSTART:{}", format_time(&event.start_time)));
+		if let Some(end_time) = event.end_time.as_ref() {
+			write_line(&mut calendar, &format!("DTEND:{}", format_time(end_time)));
+		}
+		write_line(&mut calendar, &format!("SUMMARY:{}", escape_text(&event.name)));
+		write_line(&mut calendar, &format!("URL:{}", log_url));
+		write_line(
+			&mut calendar,
+			&format!("DESCRIPTION:{}", escape_text(&format!("Event log: {}", log_url))),
+		);
+		write_line(&mut calendar, "END:VEVENT");
+	}
+	write_line(&mut calendar, "END:VCALENDAR");
+
+	Ok(Response::builder(StatusCode::Ok)
+		.body(calendar)
+		.content_type("text/calendar; charset=utf-8")
+		.build())
+}
+
+/// Formats a time as an iCalendar UTC date-time value
+fn format_time(time: &DateTime<Utc>) -> String {
+	time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a value for use in an iCalendar text property
+fn escape_text(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'\\' => escaped.push_str("\\\\"),
+			';' => escaped.push_str("\\;"),
+			',' => escaped.push_str("\\,"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => (),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Appends a content line to the calendar, folding it onto continuation lines if it's too long
+fn write_line(calendar: &mut String, line: &str) {
+	let mut line_length = 0;
+	for c in line.chars() {
+		// Continuation lines start with a space, which counts toward the line length
+		if line_length + c.len_utf8() > MAX_LINE_LENGTH {
+			calendar.push_str("\r\n ");
+			line_length = 1;
+		}
+		calendar.push(c);
+		line_length += c.len_utf8();
+	}
+	calendar.push_str("\r\n");
+}
@@ -0,0 +1,101 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::structures::user::User as UserApi;
+use super::utils::{check_application, check_application_event_access};
+use crate::database::handle_lost_db_connection;
+use crate::models::{Event as EventDb, User};
+use crate::schema::{event_editors, events, users};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use http_types::mime;
+use tide::{Request, Response, StatusCode};
+
+/// GET /api/v1/event/:id/editors
+///
+/// Gets the list of users who are editors for an event. Responds with the list of [User](UserApi) objects as an array,
+/// sorted by username.
+pub async fn list_editors(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+	let application = check_application(&request, &mut db_connection).await?;
+	if !application.read_log {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		));
+	}
+
+	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
+	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
+	let event: EventDb = match event {
+		Ok(event) => event,
+		Err(diesel::result::Error::NotFound) => {
+			return Err(tide::Error::new(
+				StatusCode::NotFound,
+				anyhow::Error::msg("No such event"),
+			))
+		}
+		Err(error) => {
+			tide::log::error!("API error loading event: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let editors: QueryResult<Vec<User>> = users::table
+		.filter(
+			users::id.eq_any(
+				event_editors::table
+					.filter(event_editors::event.eq(&event.id))
+					.select(event_editors::editor),
+			),
+		)
+		.order(users::name.asc())
+		.load(&mut *db_connection);
+	let editors: Vec<UserApi> = match editors {
+		Ok(editors) => editors
+			.into_iter()
+			.map(|user| UserApi {
+				id: user.id,
+				username: user.name,
+				color_red: user.color_red.try_into().unwrap(),
+				color_green: user.color_green.try_into().unwrap(),
+				color_blue: user.color_blue.try_into().unwrap(),
+			})
+			.collect(),
+		Err(error) => {
+			tide::log::error!("API error loading event editors: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let editors_json = match serde_json::to_string(&editors) {
+		Ok(json) => json,
+		Err(error) => {
+			tide::log::error!("API error occurred serializing event editors: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Failed to generate the response"),
+			));
+		}
+	};
+	Ok(Response::builder(StatusCode::Ok)
+		.body(editors_json)
+		.content_type(mime::JSON)
+		.build())
+}
@@ -8,7 +8,7 @@ use super::structures::event::Event as EventApi;
 use super::utils::check_application;
 use crate::database::handle_lost_db_connection;
 use crate::models::Event as EventDb;
-use crate::schema::events;
+use crate::schema::{application_events, events};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use http_types::mime;
@@ -33,7 +33,19 @@ pub async fn list_events(
 		));
 	}
 
-	let events: QueryResult<Vec<EventDb>> = events::table.load(&mut *db_connection);
+	let events: QueryResult<Vec<EventDb>> = if application.restrict_events {
+		events::table
+			.filter(
+				events::id.eq_any(
+					application_events::table
+						.filter(application_events::application.eq(&application.id))
+						.select(application_events::event),
+				),
+			)
+			.load(&mut *db_connection)
+	} else {
+		events::table.load(&mut *db_connection)
+	};
 	let events: Vec<EventApi> = match events {
 		Ok(events) => events
 			.iter()
@@ -0,0 +1,168 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::structures::info_page::InfoPage as InfoPageApi;
+use super::utils::{check_application, check_application_event_access};
+use crate::database::handle_lost_db_connection;
+use crate::models::{Event as EventDb, InfoPage as InfoPageDb};
+use crate::schema::{events, info_pages};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use http_types::mime;
+use tide::{Request, Response, StatusCode};
+
+/// GET /api/v1/event/:id/info_pages
+///
+/// Gets the list of info pages for an event. Responds with the list of [InfoPage](InfoPageApi) objects as an array.
+/// Pages restricted to users with a minimum permission level aren't included.
+pub async fn list_info_pages(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+	let application = check_application(&request, &mut db_connection).await?;
+	if !application.read_log {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		));
+	}
+
+	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
+	let event = load_event(&mut db_connection, event_id)?;
+
+	let pages: QueryResult<Vec<InfoPageDb>> = info_pages::table
+		.filter(
+			info_pages::event
+				.eq(&event.id)
+				.and(info_pages::minimum_permission.is_null()),
+		)
+		.order(info_pages::title.asc())
+		.load(&mut *db_connection);
+	let pages: Vec<InfoPageApi> = match pages {
+		Ok(pages) => pages.into_iter().map(api_info_page).collect(),
+		Err(error) => {
+			tide::log::error!("API error loading event info pages: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let pages_json = match serde_json::to_string(&pages) {
+		Ok(json) => json,
+		Err(error) => {
+			tide::log::error!("API error occurred serializing event info pages: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Failed to generate the response"),
+			));
+		}
+	};
+	Ok(Response::builder(StatusCode::Ok)
+		.body(pages_json)
+		.content_type(mime::JSON)
+		.build())
+}
+
+/// GET /api/v1/event/:id/info_pages/:page_id
+///
+/// Gets a single info page for an event. Responds with the [InfoPage](InfoPageApi) object. Pages restricted to users
+/// with a minimum permission level aren't available.
+pub async fn get_info_page(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+	let application = check_application(&request, &mut db_connection).await?;
+	if !application.read_log {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		));
+	}
+
+	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
+	let event = load_event(&mut db_connection, event_id)?;
+
+	let page_id = request.param("page_id")?;
+	let page: QueryResult<InfoPageDb> = info_pages::table
+		.filter(
+			info_pages::id
+				.eq(page_id)
+				.and(info_pages::event.eq(&event.id))
+				.and(info_pages::minimum_permission.is_null()),
+		)
+		.first(&mut *db_connection);
+	let page = match page {
+		Ok(page) => api_info_page(page),
+		Err(diesel::result::Error::NotFound) => {
+			return Err(tide::Error::new(
+				StatusCode::NotFound,
+				anyhow::Error::msg("No such info page"),
+			))
+		}
+		Err(error) => {
+			tide::log::error!("API error loading event info page: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let page_json = match serde_json::to_string(&page) {
+		Ok(json) => json,
+		Err(error) => {
+			tide::log::error!("API error occurred serializing event info page: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Failed to generate the response"),
+			));
+		}
+	};
+	Ok(Response::builder(StatusCode::Ok)
+		.body(page_json)
+		.content_type(mime::JSON)
+		.build())
+}
+
+fn load_event(db_connection: &mut PgConnection, event_id: &str) -> Result<EventDb, tide::Error> {
+	let event: QueryResult<EventDb> = events::table.find(event_id).first(db_connection);
+	match event {
+		Ok(event) => Ok(event),
+		Err(diesel::result::Error::NotFound) => Err(tide::Error::new(
+			StatusCode::NotFound,
+			anyhow::Error::msg("No such event"),
+		)),
+		Err(error) => {
+			tide::log::error!("API error loading event: {}", error);
+			Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			))
+		}
+	}
+}
+
+fn api_info_page(page: InfoPageDb) -> InfoPageApi {
+	let rendered_contents = markdown::to_html(&page.contents);
+	InfoPageApi {
+		id: page.id,
+		title: page.title,
+		contents: page.contents,
+		rendered_contents,
+	}
+}
@@ -5,7 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::structures::tag::{Tag as TagApi, TagPlaylist};
-use super::utils::check_application;
+use super::utils::{check_application, check_application_event_access};
 use crate::database::handle_lost_db_connection;
 use crate::models::{Event as EventDb, Tag as TagDb};
 use crate::schema::{events, tags};
@@ -34,6 +34,7 @@ pub async fn list_tags(
 	}
 
 	let event_id = request.param("id")?;
+	check_application_event_access(&mut db_connection, &application, event_id)?;
 	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
 	let event: EventDb = match event {
 		Ok(event) => event,
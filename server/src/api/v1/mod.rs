@@ -5,26 +5,48 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::data_sync::SubscriptionManager;
+use crate::metrics::{Metrics, RequestMetricsMiddleware};
 use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
-use tide::Server;
+use tide::{Route, Server};
 
 mod structures;
 mod utils;
 
+mod consistency_check;
+use consistency_check::consistency_check;
+
 mod event_by_name;
 use event_by_name::event_by_name;
 
+mod event_history;
+use event_history::event_history;
+
+mod event_log_export;
+use event_log_export::event_log_export;
+
 mod event_log_list;
 use event_log_list::event_log_list;
 
+mod events_calendar;
+use events_calendar::events_calendar;
+
+mod list_editors;
+use list_editors::list_editors;
+
 mod list_events;
 use list_events::list_events;
 
+mod list_info_pages;
+use list_info_pages::{get_info_page, list_info_pages};
+
 mod list_tags;
 use list_tags::list_tags;
 
+mod public_event_log;
+use public_event_log::public_event_log;
+
 mod set_video_errors;
 use set_video_errors::set_video_errors;
 
@@ -34,28 +56,84 @@ use set_video_link::{delete_video_link, set_video_link};
 mod set_video_processing_state;
 use set_video_processing_state::set_video_processing_state;
 
+mod update_entry;
+use update_entry::update_entry;
+
+mod wrap_report;
+use wrap_report::wrap_report;
+
+/// Adds a route whose requests are counted in the server's metrics
+fn metered_route<'a>(app: &'a mut Server<()>, path: &'static str, metrics: &Arc<Metrics>) -> Route<'a, ()> {
+	let mut route = app.at(path);
+	route.with(RequestMetricsMiddleware::new(Arc::clone(metrics), path));
+	route
+}
+
 pub fn add_routes(
 	app: &mut Server<()>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	metrics: Arc<Metrics>,
 ) -> miette::Result<()> {
-	app.at("/api/v1/events").get({
+	metered_route(app, "/api/v1/events", &metrics).get({
 		let db_connection_pool = db_connection_pool.clone();
 		move |request| list_events(request, db_connection_pool.clone())
 	});
-	app.at("/api/v1/event_by_name/:name").get({
+	metered_route(app, "/api/v1/events.ics", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| events_calendar(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event_by_name/:name", &metrics).get({
 		let db_connection_pool = db_connection_pool.clone();
 		move |request| event_by_name(request, db_connection_pool.clone())
 	});
-	app.at("/api/v1/event/:id/log").get({
+	metered_route(app, "/api/v1/event/:id/log", &metrics).get({
 		let db_connection_pool = db_connection_pool.clone();
 		move |request| event_log_list(request, db_connection_pool.clone())
 	});
-	app.at("/api/v1/event/:id/tags").get({
+	metered_route(app, "/api/v1/event/:id/log/export", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| event_log_export(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/wrap_report", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| wrap_report(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/history", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| event_history(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/tags", &metrics).get({
 		let db_connection_pool = db_connection_pool.clone();
 		move |request| list_tags(request, db_connection_pool.clone())
 	});
-	app.at("/api/v1/entry/:id/video")
+	metered_route(app, "/api/v1/event/:id/consistency", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| consistency_check(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/editors", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| list_editors(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/info_pages", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| list_info_pages(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/event/:id/info_pages/:page_id", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| get_info_page(request, db_connection_pool.clone())
+	});
+	// This is a page for people rather than an API endpoint, but it's built from the same data as the API's event log.
+	metered_route(app, "/public/:token", &metrics).get({
+		let db_connection_pool = db_connection_pool.clone();
+		move |request| public_event_log(request, db_connection_pool.clone())
+	});
+	metered_route(app, "/api/v1/entry/:id", &metrics).patch({
+		let db_connection_pool = db_connection_pool.clone();
+		let subscription_manager = Arc::clone(&subscription_manager);
+		move |request| update_entry(request, db_connection_pool.clone(), Arc::clone(&subscription_manager))
+	});
+	metered_route(app, "/api/v1/entry/:id/video", &metrics)
 		.post({
 			let db_connection_pool = db_connection_pool.clone();
 			let subscription_manager = Arc::clone(&subscription_manager);
@@ -66,14 +144,14 @@ pub fn add_routes(
 			let subscription_manager = Arc::clone(&subscription_manager);
 			move |request| delete_video_link(request, db_connection_pool.clone(), Arc::clone(&subscription_manager))
 		});
-	app.at("/api/v1/entry/:id/video_processing_state").post({
+	metered_route(app, "/api/v1/entry/:id/video_processing_state", &metrics).post({
 		let db_connection_pool = db_connection_pool.clone();
 		let subscription_manager = Arc::clone(&subscription_manager);
 		move |request| {
 			set_video_processing_state(request, db_connection_pool.clone(), Arc::clone(&subscription_manager))
 		}
 	});
-	app.at("/api/v1/entry/:id/video_errors").post({
+	metered_route(app, "/api/v1/entry/:id/video_errors", &metrics).post({
 		let subscription_manager = Arc::clone(&subscription_manager);
 		move |request| set_video_errors(request, db_connection_pool.clone(), Arc::clone(&subscription_manager))
 	});
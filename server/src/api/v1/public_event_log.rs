@@ -0,0 +1,182 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log_export::format_duration;
+use super::event_log_list::load_event_log_entries;
+use super::structures::event_log_entry::{EndTimeData, EventLogEntry as EventLogEntryApi};
+use super::utils::{escape_html, is_web_link};
+use crate::database::handle_lost_db_connection;
+use crate::models::Event as EventDb;
+use crate::schema::{event_share_links, events};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use http_types::mime;
+use std::collections::HashMap;
+use tide::{Request, Response, StatusCode};
+
+const PAGE_STYLE: &str = "body { font-family: sans-serif; margin: 1em; } \
+	table { border-collapse: collapse; } \
+	th, td { border: 1px solid #888; padding: 3px 6px; text-align: left; vertical-align: top; } \
+	.tab_name { background: #ddd; font-weight: bold; } \
+	.entry_type { border-left: 1em solid transparent; padding-left: 4px; } \
+	@media (prefers-color-scheme: dark) { \
+		body { background: #111; color: #eee; } \
+		a { color: #8bf; } \
+		.tab_name { background: #333; } \
+	}";
+
+const PUBLIC_LOG_HEADERS: [&str; 7] = [
+	"Start",
+	"End",
+	"Type",
+	"Description",
+	"Submitter/Winner",
+	"Media",
+	"Tags",
+];
+
+/// GET /public/:token
+///
+/// Shows a read-only copy of an event log as a plain HTML page to anyone with the event's share link. No login is
+/// required, so only the parts of the log meant for viewers are included; editor assignments, notes, and video
+/// information are left out.
+pub async fn public_event_log(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+
+	let token = request.param("token")?;
+	let event: QueryResult<Option<EventDb>> = events::table
+		.filter(
+			events::id.eq_any(
+				event_share_links::table
+					.filter(event_share_links::token.eq(token))
+					.select(event_share_links::event),
+			),
+		)
+		.first(&mut *db_connection)
+		.optional();
+	let event = match event {
+		Ok(Some(event)) => event,
+		Ok(None) => {
+			return Err(tide::Error::new(
+				StatusCode::NotFound,
+				anyhow::Error::msg("No such event"),
+			))
+		}
+		Err(error) => {
+			tide::log::error!("Error loading event for public view: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let event_log = load_event_log_entries(&mut db_connection, &event, None)?;
+
+	let mut page = String::new();
+	page.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+	page.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">");
+	page.push_str("<meta name=\"robots\" content=\"noindex\">");
+	page.push_str(&format!("<title>{}</title>", escape_html(&event.name)));
+	page.push_str(&format!("<style>{}</style>", PAGE_STYLE));
+	page.push_str("</head><body>");
+	page.push_str(&format!("<h1>{}</h1>", escape_html(&event.name)));
+	page.push_str("<table><thead><tr>");
+	for header in PUBLIC_LOG_HEADERS {
+		page.push_str(&format!("<th>{}</th>", header));
+	}
+	page.push_str("</tr></thead><tbody>");
+
+	let mut current_tab_id: Option<&str> = None;
+	let mut entry_depths: HashMap<&str, u32> = HashMap::new();
+	for entry in event_log.iter() {
+		if current_tab_id != Some(entry.tab.id.as_str()) {
+			current_tab_id = Some(entry.tab.id.as_str());
+			page.push_str(&format!(
+				"<tr><td class=\"tab_name\" colspan=\"{}\">{}</td></tr>",
+				PUBLIC_LOG_HEADERS.len(),
+				escape_html(&entry.tab.name)
+			));
+		}
+
+		// Parents always come before their children, so the parent's depth is already known.
+		let depth = entry
+			.parent
+			.as_ref()
+			.and_then(|parent| entry_depths.get(parent.as_str()))
+			.map(|parent_depth| parent_depth + 1)
+			.unwrap_or(0);
+		entry_depths.insert(&entry.id, depth);
+
+		write_entry_row(&mut page, entry, &event, depth);
+	}
+
+	page.push_str("</tbody></table></body></html>");
+
+	Ok(Response::builder(StatusCode::Ok)
+		.body(page)
+		.content_type(mime::HTML)
+		.header("Referrer-Policy", "no-referrer")
+		.build())
+}
+
+/// Appends the table row for an entry to the page
+fn write_entry_row(page: &mut String, entry: &EventLogEntryApi, event: &EventDb, depth: u32) {
+	let end = match &entry.end_time {
+		EndTimeData::Time(time) => format_duration(*time - event.start_time),
+		EndTimeData::NotEntered => String::new(),
+		EndTimeData::NoTime => String::from("—"),
+	};
+	let entry_type = match entry.entry_type.as_ref() {
+		Some(entry_type) => format!(
+			"<span class=\"entry_type\" style=\"border-left-color: rgb({}, {}, {});\">{}</span>",
+			entry_type.color_red,
+			entry_type.color_green,
+			entry_type.color_blue,
+			escape_html(&entry_type.name)
+		),
+		None => String::new(),
+	};
+	let media_links: Vec<String> = entry
+		.media_links
+		.iter()
+		.map(|link| {
+			if is_web_link(link) {
+				format!(
+					"<a href=\"{}\" rel=\"noopener noreferrer\">{}</a>",
+					escape_html(link),
+					escape_html(link)
+				)
+			} else {
+				escape_html(link)
+			}
+		})
+		.collect();
+	let tags: Vec<String> = entry.tags.iter().map(|tag| escape_html(&tag.tag)).collect();
+
+	page.push_str("<tr>");
+	page.push_str(&format!(
+		"<td>{}</td>",
+		format_duration(entry.start_time - event.start_time)
+	));
+	page.push_str(&format!("<td>{}</td>", end));
+	page.push_str(&format!("<td>{}</td>", entry_type));
+	page.push_str(&format!(
+		"<td style=\"padding-left: {}em;\">{}</td>",
+		depth as f32 * 1.5 + 0.4,
+		escape_html(&entry.description)
+	));
+	page.push_str(&format!("<td>{}</td>", escape_html(&entry.submitter_or_winner)));
+	page.push_str(&format!("<td>{}</td>", media_links.join("<br>")));
+	page.push_str(&format!("<td>{}</td>", tags.join(", ")));
+	page.push_str("</tr>");
+}
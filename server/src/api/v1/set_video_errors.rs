@@ -4,7 +4,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::utils::{check_application, update_history};
+use super::utils::{check_application, check_application_entry_access, update_history};
+use crate::data_sync::custom_fields::entry_custom_field_values;
 use crate::data_sync::SubscriptionManager;
 use crate::database::handle_lost_db_connection;
 use crate::models::{Event as EventDb, EventLogEntry as EventLogEntryDb, Tag as TagDb, User};
@@ -32,23 +33,22 @@ pub async fn set_video_errors(
 		Err(error) => return handle_lost_db_connection(error),
 	};
 	let application = check_application(&request, &mut db_connection).await?;
-	if !application.write_links {
+	if !application.write_processing_state {
 		return Err(tide::Error::new(
 			StatusCode::Unauthorized,
 			anyhow::Error::msg("Not authorized to access this resource."),
 		));
 	}
+	check_application_entry_access(&mut db_connection, &application, request.param("id")?)?;
 
 	let video_errors = request.body_string().await?;
 	let entry_id = request.param("id")?;
 	let update_result: QueryResult<(Event, EventLogEntry)> = db_connection.transaction(|db_connection| {
-		let entry: EventLogEntryDb = diesel::update(event_log::table)
+		let mut entry: EventLogEntryDb = diesel::update(event_log::table)
 			.filter(event_log::id.eq(entry_id).and(event_log::deleted_by.is_null()))
 			.set(event_log::video_errors.eq(video_errors))
 			.get_result(db_connection)?;
-		update_history(db_connection, entry.clone(), &application.id)?;
-
-		let end_time = entry.end_time_data();
+		entry.latest_history_id = Some(update_history(db_connection, entry.clone(), &application.id, None)?);
 
 		let entry_tags: Vec<TagDb> = tags::table
 			.filter(
@@ -64,31 +64,20 @@ pub async fn set_video_errors(
 		} else {
 			None
 		};
+		let created_by: Option<User> = if let Some(creator) = entry.created_by.as_ref() {
+			Some(users::table.find(creator).first(db_connection)?)
+		} else {
+			None
+		};
 
 		let event: EventDb = events::table.find(&entry.event).first(db_connection)?;
 		let event: Event = event.into();
 
-		let entry = EventLogEntry {
-			id: entry.id,
-			start_time: Some(entry.start_time),
-			end_time,
-			entry_type: entry.entry_type,
-			description: entry.description,
-			media_links: entry.media_links.into_iter().flatten().collect(),
-			submitter_or_winner: entry.submitter_or_winner,
-			tags: entry_tags.into_iter().map(|tag| tag.into()).collect(),
-			notes: entry.notes,
-			editor: editor.map(|editor| editor.into()),
-			video_link: entry.video_link,
-			parent: entry.parent,
-			created_at: entry.created_at,
-			manual_sort_key: entry.manual_sort_key,
-			video_processing_state: entry.video_processing_state.into(),
-			video_errors: entry.video_errors,
-			poster_moment: entry.poster_moment,
-			video_edit_state: entry.video_edit_state.into(),
-			missing_giveaway_information: entry.missing_giveaway_information,
-		};
+		let tags = entry_tags.into_iter().map(|tag| tag.into()).collect();
+		let editor = editor.map(|editor| editor.into());
+		let created_by = created_by.map(|creator| creator.into());
+		let custom_fields = entry_custom_field_values(db_connection, &entry.id)?;
+		let entry = entry.into_sync_entry(tags, editor, created_by, custom_fields);
 		Ok((event, entry))
 	});
 
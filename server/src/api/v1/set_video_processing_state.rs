@@ -5,8 +5,9 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::structures::video_processing_state::VideoProcessingState as VideoProcessingStateApi;
-use super::utils::{check_application, update_history};
-use crate::data_sync::SubscriptionManager;
+use super::utils::{check_application, check_application_entry_access, update_history};
+use crate::data_sync::custom_fields::entry_custom_field_values;
+use crate::data_sync::{send_user_notification, SubscriptionManager};
 use crate::database::handle_lost_db_connection;
 use crate::models::{
 	Event as EventDb, EventLogEntry as EventLogEntryDb, Tag as TagDb, User,
@@ -19,6 +20,7 @@ use diesel::r2d2::{ConnectionManager, Pool};
 use stream_log_shared::messages::event_log::EventLogEntry;
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::notifications::UserNotificationDetails;
 use stream_log_shared::messages::subscriptions::SubscriptionData;
 use tide::{Request, Response, StatusCode};
 
@@ -35,12 +37,13 @@ pub async fn set_video_processing_state(
 		Err(error) => return handle_lost_db_connection(error),
 	};
 	let application = check_application(&request, &mut db_connection).await?;
-	if !application.write_links {
+	if !application.write_processing_state {
 		return Err(tide::Error::new(
 			StatusCode::Unauthorized,
 			anyhow::Error::msg("Not authorized to access this resource."),
 		));
 	}
+	check_application_entry_access(&mut db_connection, &application, request.param("id")?)?;
 
 	let video_processing_state = request.body_string().await?;
 	let video_processing_state: VideoProcessingStateApi = match video_processing_state.parse() {
@@ -54,15 +57,17 @@ pub async fn set_video_processing_state(
 	};
 
 	let event_id = request.param("id")?;
-	let update_result: QueryResult<(Event, EventLogEntry)> = db_connection.transaction(|db_connection| {
+	let update_result: QueryResult<(Event, EventLogEntry, bool)> = db_connection.transaction(|db_connection| {
 		let video_processing_state: VideoProcessingStateDb = video_processing_state.into();
-		let entry: EventLogEntryDb = diesel::update(event_log::table)
+		let previous_state: VideoProcessingStateDb = event_log::table
+			.find(event_id)
+			.select(event_log::video_processing_state)
+			.first(db_connection)?;
+		let mut entry: EventLogEntryDb = diesel::update(event_log::table)
 			.filter(event_log::id.eq(event_id).and(event_log::deleted_by.is_null()))
 			.set(event_log::video_processing_state.eq(video_processing_state))
 			.get_result(db_connection)?;
-		update_history(db_connection, entry.clone(), &application.id)?;
-
-		let end_time = entry.end_time_data();
+		entry.latest_history_id = Some(update_history(db_connection, entry.clone(), &application.id, None)?);
 
 		let tags: Vec<TagDb> = tags::table
 			.filter(
@@ -78,39 +83,41 @@ pub async fn set_video_processing_state(
 		} else {
 			None
 		};
+		let created_by: Option<User> = if let Some(creator) = entry.created_by.as_ref() {
+			Some(users::table.find(creator).first(db_connection)?)
+		} else {
+			None
+		};
 
 		let event: EventDb = events::table.find(&entry.event).first(db_connection)?;
 		let event: Event = event.into();
 
-		let entry = EventLogEntry {
-			id: entry.id,
-			start_time: Some(entry.start_time),
-			end_time,
-			entry_type: entry.entry_type,
-			description: entry.description,
-			media_links: entry.media_links.into_iter().flatten().collect(),
-			submitter_or_winner: entry.submitter_or_winner,
-			tags: tags.into_iter().map(|tag| tag.into()).collect(),
-			notes: entry.notes,
-			editor: editor.map(|editor| editor.into()),
-			video_link: entry.video_link,
-			parent: entry.parent,
-			created_at: entry.created_at,
-			manual_sort_key: entry.manual_sort_key,
-			video_processing_state: entry.video_processing_state.into(),
-			video_errors: entry.video_errors,
-			poster_moment: entry.poster_moment,
-			video_edit_state: entry.video_edit_state.into(),
-			missing_giveaway_information: entry.missing_giveaway_information,
-		};
+		let tags = tags.into_iter().map(|tag| tag.into()).collect();
+		let editor = editor.map(|editor| editor.into());
+		let created_by = created_by.map(|creator| creator.into());
+		let custom_fields = entry_custom_field_values(db_connection, &entry.id)?;
+		let entry = entry.into_sync_entry(tags, editor, created_by, custom_fields);
 
-		Ok((event, entry))
+		Ok((event, entry, previous_state != video_processing_state))
 	});
 
 	drop(db_connection);
 
 	match update_result {
-		Ok((event, entry)) => {
+		Ok((event, entry, state_changed)) => {
+			// Editors otherwise have to watch the log to find out when their videos are done processing
+			if state_changed {
+				if let Some(editor) = entry.editor.as_ref() {
+					let details = UserNotificationDetails::VideoProcessingStateChanged {
+						entry_id: entry.id.clone(),
+						entry_description: entry.description.clone(),
+						state: entry.video_processing_state,
+					};
+					send_user_notification(&db_connection_pool, &subscription_manager, &editor.id, &event, details)
+						.await;
+				}
+			}
+
 			let subscription_manager = subscription_manager.lock().await;
 			let event_id = event.id.clone();
 			let message =
@@ -0,0 +1,36 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::Serialize;
+
+/// The result of comparing the event log as served by the API with the event log as sent to clients
+#[derive(Serialize)]
+pub struct ConsistencyReport {
+	/// The ID of the checked event
+	pub event_id: String,
+	/// The number of entries loaded through the API
+	pub rest_entry_count: usize,
+	/// The number of entries loaded the way they're sent to clients
+	pub websocket_entry_count: usize,
+	/// A hash of the API entries' data. If this matches the websocket fingerprint, both views agree completely.
+	pub rest_fingerprint: String,
+	/// A hash of the websocket entries' data
+	pub websocket_fingerprint: String,
+	/// IDs of entries that are sent to clients but not included in the API
+	pub missing_from_rest: Vec<String>,
+	/// IDs of entries that are included in the API but not sent to clients
+	pub missing_from_websocket: Vec<String>,
+	/// Entries that are present in both but have data that doesn't match
+	pub mismatches: Vec<EntryMismatch>,
+}
+
+#[derive(Serialize)]
+pub struct EntryMismatch {
+	/// The ID of the entry
+	pub entry_id: String,
+	/// The names of the fields that don't match
+	pub fields: Vec<&'static str>,
+}
@@ -10,10 +10,12 @@ use super::tag::Tag;
 use super::user::User;
 use super::video_edit_state::VideoEditState;
 use super::video_processing_state::VideoProcessingState;
+use crate::models::{VideoEditState as VideoEditStateDb, VideoProcessingState as VideoProcessingStateDb};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use stream_log_shared::messages::event_log::{EndTimeData as EndTimeDataWs, EventLogEntrySummary};
 
-#[derive(Serialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(tag = "type", content = "time")]
 pub enum EndTimeData {
 	Time(DateTime<Utc>),
@@ -26,6 +28,10 @@ pub enum EndTimeData {
 pub struct EventLogEntry {
 	/// The ID of the entry
 	pub id: String,
+	/// The number of the entry in its event. Entry numbers are assigned when the entry is created and are unique within
+	/// the event. They don't change unless the event's entries are renumbered, and entries with a video link keep their
+	/// numbers when that happens.
+	pub entry_number: i32,
 	/// The start time of the entry
 	pub start_time: DateTime<Utc>,
 	/// The end time of the entry, if entered
@@ -46,6 +52,8 @@ pub struct EventLogEntry {
 	pub editor_link: Option<String>,
 	/// The editor assigned to this entry
 	pub editor: Option<User>,
+	/// The user who created this entry. This may be missing for entries created before creators were recorded.
+	pub created_by: Option<User>,
 	/// The link to the uploaded video for this entry
 	pub video_link: Option<String>,
 	/// The ID of the parent entry, if this entry is a child
@@ -66,3 +74,37 @@ pub struct EventLogEntry {
 	/// changes to tab data do not count as changes to the individual affected entries.
 	pub tab: EventLogTab,
 }
+
+impl From<&EventLogEntry> for EventLogEntrySummary {
+	fn from(entry: &EventLogEntry) -> Self {
+		let end_time = match entry.end_time {
+			EndTimeData::Time(time) => EndTimeDataWs::Time(time),
+			EndTimeData::NotEntered => EndTimeDataWs::NotEntered,
+			EndTimeData::NoTime => EndTimeDataWs::NoTime,
+		};
+		let mut tags: Vec<String> = entry.tags.iter().map(|tag| tag.id.clone()).collect();
+		tags.sort();
+		Self {
+			id: entry.id.clone(),
+			entry_number: Some(entry.entry_number),
+			start_time: Some(entry.start_time),
+			end_time,
+			entry_type: entry.entry_type.as_ref().map(|entry_type| entry_type.id.clone()),
+			description: entry.description.clone(),
+			media_links: entry.media_links.clone(),
+			submitter_or_winner: entry.submitter_or_winner.clone(),
+			tags,
+			notes: entry.notes.clone(),
+			editor: entry.editor.as_ref().map(|editor| editor.id.clone()),
+			created_by: entry.created_by.as_ref().map(|creator| creator.id.clone()),
+			video_link: entry.video_link.clone(),
+			parent: entry.parent.clone(),
+			manual_sort_key: entry.manual_sort_key,
+			video_edit_state: VideoEditStateDb::from(entry.video_edit_state).into(),
+			video_processing_state: VideoProcessingStateDb::from(entry.video_processing_state).into(),
+			video_errors: entry.video_errors.clone(),
+			poster_moment: entry.poster_moment,
+			missing_giveaway_information: entry.missing_giveaway_information,
+		}
+	}
+}
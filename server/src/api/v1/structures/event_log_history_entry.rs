@@ -0,0 +1,69 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log_entry::EndTimeData;
+use super::tag::Tag;
+use super::user::User;
+use super::video_edit_state::VideoEditState;
+use super::video_processing_state::VideoProcessingState;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A revision of an event log entry, as recorded in the entry's history. A revision is recorded each time an entry is
+/// created, changed, or deleted, and contains the whole entry as it was after that change.
+#[derive(Serialize)]
+pub struct EventLogHistoryEntry {
+	/// The ID of the history record
+	pub id: String,
+	/// The ID of the entry of which this is a revision
+	pub entry_id: String,
+	/// When the entry was changed to this revision
+	pub edit_time: DateTime<Utc>,
+	/// The user who made the change, if it was made by a user
+	pub edit_user: Option<User>,
+	/// The ID of the application that made the change, if it was made by an application
+	pub edit_application: Option<String>,
+	/// The start time of the entry
+	pub start_time: DateTime<Utc>,
+	/// The end time of the entry, if entered
+	pub end_time: EndTimeData,
+	/// The ID of the entry type the entry had
+	pub entry_type: Option<String>,
+	/// The entry description
+	pub description: String,
+	/// The media links associated with the entry
+	pub media_links: Vec<String>,
+	/// The submitter or winner related to the entry
+	pub submitter_or_winner: String,
+	/// The tags associated with the entry
+	pub tags: Vec<Tag>,
+	/// The notes about this entry to the editor
+	pub notes: String,
+	/// The editor assigned to the entry
+	pub editor: Option<User>,
+	/// The link to the uploaded video for the entry
+	pub video_link: Option<String>,
+	/// The ID of the parent entry, if the entry was a child
+	pub parent: Option<String>,
+	/// The user who deleted the entry, if this revision is the entry's deletion
+	pub deleted_by: Option<User>,
+	/// When the entry was created
+	pub created_at: DateTime<Utc>,
+	/// The entered manual sort key for the entry
+	pub manual_sort_key: Option<i32>,
+	/// The selected edit state for the video
+	pub video_edit_state: VideoEditState,
+	/// The ID of the tag whose video edit state rule set the video edit state in this revision, if any
+	pub video_edit_state_rule_tag: Option<String>,
+	/// The state of the video processing for the entry
+	pub video_processing_state: VideoProcessingState,
+	/// Video errors for the entry; if empty, no video errors were set
+	pub video_errors: String,
+	/// Whether the entry was marked as a poster moment
+	pub poster_moment: bool,
+	/// Whether the entry was marked as needing giveaway information to be entered
+	pub missing_giveaway_information: bool,
+}
@@ -0,0 +1,20 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::Serialize;
+
+/// Info page object representing an event info page
+#[derive(Serialize)]
+pub struct InfoPage {
+	/// The info page's ID
+	pub id: String,
+	/// The title of the page
+	pub title: String,
+	/// The page contents in Markdown format, as entered by the user
+	pub contents: String,
+	/// The page contents rendered as HTML
+	pub rendered_contents: String,
+}
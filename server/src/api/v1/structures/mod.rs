@@ -4,11 +4,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub mod consistency_report;
 pub mod entry_type;
 pub mod event;
 pub mod event_log_entry;
+pub mod event_log_history_entry;
 pub mod event_log_response;
 pub mod event_log_tab;
+pub mod info_page;
 pub mod tag;
 pub mod user;
 pub mod video_edit_state;
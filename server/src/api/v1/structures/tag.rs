@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::models::Tag as TagDb;
 use serde::Serialize;
 
 /// Tag object representing the tag
@@ -28,3 +29,27 @@ pub struct TagPlaylist {
 	/// Whether this playlist should be shown in video descriptions
 	pub shows_in_video_descriptions: bool,
 }
+
+impl From<TagDb> for Tag {
+	fn from(tag: TagDb) -> Self {
+		let playlist = if let (Some(id), Some(title), Some(shows_in_video_descriptions)) = (
+			tag.playlist,
+			tag.playlist_title,
+			tag.playlist_shows_in_video_descriptions,
+		) {
+			Some(TagPlaylist {
+				id,
+				title,
+				shows_in_video_descriptions,
+			})
+		} else {
+			None
+		};
+		Self {
+			id: tag.id,
+			tag: tag.tag,
+			description: tag.description,
+			playlist,
+		}
+	}
+}
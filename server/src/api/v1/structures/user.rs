@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::models::User as UserDb;
 use serde::Serialize;
 
 /// User object representing a user.
@@ -20,3 +21,15 @@ pub struct User {
 	/// The blue component of the user's color
 	pub color_blue: u8,
 }
+
+impl From<UserDb> for User {
+	fn from(user: UserDb) -> Self {
+		Self {
+			id: user.id,
+			username: user.name,
+			color_red: user.color_red.try_into().unwrap(),
+			color_green: user.color_green.try_into().unwrap(),
+			color_blue: user.color_blue.try_into().unwrap(),
+		}
+	}
+}
@@ -5,9 +5,9 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::models::VideoEditState as VideoEditStateDb;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Deserialize, Serialize)]
 pub enum VideoEditState {
 	NoVideo,
 	MarkedForEditing,
@@ -23,3 +23,13 @@ impl From<VideoEditStateDb> for VideoEditState {
 		}
 	}
 }
+
+impl From<VideoEditState> for VideoEditStateDb {
+	fn from(state: VideoEditState) -> Self {
+		match state {
+			VideoEditState::NoVideo => Self::NoVideo,
+			VideoEditState::MarkedForEditing => Self::MarkedForEditing,
+			VideoEditState::DoneEditing => Self::DoneEditing,
+		}
+	}
+}
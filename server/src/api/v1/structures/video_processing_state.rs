@@ -8,7 +8,7 @@ use crate::models::VideoProcessingState as VideoProcessingStateDb;
 use serde::Serialize;
 use std::str::FromStr;
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Serialize)]
 pub enum VideoProcessingState {
 	Unedited,
 	Edited,
@@ -0,0 +1,291 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::structures::event_log_entry::EndTimeData as EndTimeDataApi;
+use super::structures::video_edit_state::VideoEditState as VideoEditStateApi;
+use super::utils::{check_application, check_application_entry_access, update_history};
+use crate::data_sync::custom_fields::entry_custom_field_values;
+use crate::data_sync::SubscriptionManager;
+use crate::database::handle_lost_db_connection;
+use crate::models::{
+	Event as EventDb, EventLogEntry as EventLogEntryDb, EventLogEntryChanges, EventLogTag, Tag as TagDb, User,
+	VideoEditState, VideoEditStateTagRule,
+};
+use crate::schema::{event_log, event_log_tags, events, tags, users, video_edit_state_tag_rules};
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use http_types::mime;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+use tide::{Request, Response, StatusCode};
+
+/// The fields of an entry that can be changed through the API. Fields that aren't provided aren't changed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntryUpdate {
+	description: Option<String>,
+	notes: Option<String>,
+	end_time: Option<EndTimeDataApi>,
+	/// IDs of all the tags the entry should have
+	tags: Option<Vec<String>>,
+	video_edit_state: Option<VideoEditStateApi>,
+}
+
+/// PATCH /api/v1/entry/:id
+///
+/// Updates some of the fields of an event log entry. The body of the request is a JSON object with any of the fields
+/// `description`, `notes`, `end_time`, `tags`, and `video_edit_state`, given in the same form as the event log
+/// provides them except that `tags` is a list of tag IDs. Fields that aren't included are left as they are. If any of
+/// the given fields are invalid, nothing is changed, and the response is a JSON object mapping each invalid field to
+/// a description of the problem.
+pub async fn update_entry(
+	mut request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> tide::Result {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+	let application = check_application(&request, &mut db_connection).await?;
+	if !application.write_entries {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		));
+	}
+	let entry_id = request.param("id")?.to_string();
+	check_application_entry_access(&mut db_connection, &application, &entry_id)?;
+
+	let update: EntryUpdate = match request.body_json().await {
+		Ok(update) => update,
+		Err(error) => {
+			return Err(tide::Error::new(
+				StatusCode::BadRequest,
+				anyhow::Error::msg(format!("Invalid entry update: {}", error)),
+			))
+		}
+	};
+
+	let current_data: QueryResult<(EventLogEntryDb, EventDb)> = event_log::table
+		.inner_join(events::table)
+		.filter(event_log::id.eq(&entry_id).and(event_log::deleted_by.is_null()))
+		.first(&mut *db_connection);
+	let (current_entry, event) = match current_data {
+		Ok(data) => data,
+		Err(diesel::result::Error::NotFound) => return Ok(Response::builder(StatusCode::NotFound).build()),
+		Err(error) => {
+			tide::log::error!("API error loading entry for update: {}", error);
+			return Ok(Response::builder(StatusCode::InternalServerError)
+				.body("Database error")
+				.build());
+		}
+	};
+	let event: Event = event.into();
+
+	let mut field_errors: BTreeMap<&str, String> = BTreeMap::new();
+
+	let description = update.description.map(|description| description.trim().to_string());
+	if let Some(description) = description.as_ref() {
+		if let Err(error) = event.check_description_length(description) {
+			field_errors.insert("description", error);
+		}
+	}
+
+	if let Some(EndTimeDataApi::Time(end_time)) = update.end_time.as_ref() {
+		if *end_time < current_entry.start_time {
+			field_errors.insert("end_time", String::from("The end time can't be before the start time."));
+		}
+	}
+
+	let tag_ids: Option<Vec<String>> = update.tags.map(|tag_ids| {
+		let mut seen_tag_ids: HashSet<String> = HashSet::new();
+		tag_ids
+			.into_iter()
+			.filter(|tag_id| seen_tag_ids.insert(tag_id.clone()))
+			.collect()
+	});
+	if let Some(tag_ids) = tag_ids.as_ref() {
+		let available_tag_count: QueryResult<i64> = tags::table
+			.filter(
+				tags::id
+					.eq_any(tag_ids)
+					.and(tags::for_event.eq(&event.id))
+					.and(tags::deleted.eq(false)),
+			)
+			.count()
+			.get_result(&mut *db_connection);
+		match available_tag_count {
+			Ok(count) => {
+				if count as usize != tag_ids.len() {
+					field_errors.insert(
+						"tags",
+						String::from("All tags must be the IDs of tags available in the entry's event."),
+					);
+				}
+			}
+			Err(error) => {
+				tide::log::error!("API error checking tags for entry update: {}", error);
+				return Ok(Response::builder(StatusCode::InternalServerError)
+					.body("Database error")
+					.build());
+			}
+		}
+	}
+
+	if !field_errors.is_empty() {
+		let errors_json = serde_json::to_string(&field_errors)?;
+		return Ok(Response::builder(StatusCode::BadRequest)
+			.body(errors_json)
+			.content_type(mime::JSON)
+			.build());
+	}
+
+	let mut changes = EventLogEntryChanges {
+		description,
+		notes: update.notes,
+		video_edit_state: update.video_edit_state.map(|state| state.into()),
+		..Default::default()
+	};
+	match update.end_time {
+		Some(EndTimeDataApi::Time(time)) => {
+			changes.end_time = Some(Some(time));
+			changes.end_time_incomplete = Some(false);
+		}
+		Some(EndTimeDataApi::NotEntered) => {
+			changes.end_time = Some(None);
+			changes.end_time_incomplete = Some(true);
+		}
+		Some(EndTimeDataApi::NoTime) => {
+			changes.end_time = Some(None);
+			changes.end_time_incomplete = Some(false);
+		}
+		None => (),
+	}
+
+	let update_result: QueryResult<(Event, EventLogEntry)> = db_connection.transaction(|db_connection| {
+		let mut added_tag_ids: Vec<String> = Vec::new();
+		if let Some(tag_ids) = tag_ids {
+			let previous_tag_ids: HashSet<String> = event_log_tags::table
+				.filter(event_log_tags::log_entry.eq(&entry_id))
+				.select(event_log_tags::tag)
+				.load::<String>(db_connection)?
+				.into_iter()
+				.collect();
+			added_tag_ids = tag_ids
+				.iter()
+				.filter(|tag_id| !previous_tag_ids.contains(*tag_id))
+				.cloned()
+				.collect();
+			let entry_tags: Vec<EventLogTag> = tag_ids
+				.into_iter()
+				.map(|tag| EventLogTag {
+					tag,
+					log_entry: entry_id.clone(),
+				})
+				.collect();
+			diesel::delete(event_log_tags::table)
+				.filter(event_log_tags::log_entry.eq(&entry_id))
+				.execute(db_connection)?;
+			diesel::insert_into(event_log_tags::table)
+				.values(entry_tags)
+				.execute(db_connection)?;
+		}
+
+		// Video edit state rules apply to added tags the same way they do for changes made by users
+		let mut applied_rule_tag: Option<String> = None;
+		if changes.video_edit_state.is_none()
+			&& !added_tag_ids.is_empty()
+			&& current_entry.video_edit_state == VideoEditState::NoVideo
+		{
+			let rule: Option<VideoEditStateTagRule> = video_edit_state_tag_rules::table
+				.filter(
+					video_edit_state_tag_rules::event
+						.eq(&event.id)
+						.and(video_edit_state_tag_rules::tag.eq_any(&added_tag_ids)),
+				)
+				.order(video_edit_state_tag_rules::tag.asc())
+				.first(db_connection)
+				.optional()?;
+			if let Some(rule) = rule {
+				changes.video_edit_state = Some(rule.video_edit_state);
+				applied_rule_tag = Some(rule.tag);
+			}
+		}
+
+		let mut entry: EventLogEntryDb = if changes.has_changes() {
+			diesel::update(event_log::table)
+				.filter(event_log::id.eq(&entry_id).and(event_log::deleted_by.is_null()))
+				.set(changes)
+				.get_result(db_connection)?
+		} else {
+			event_log::table.find(&entry_id).first(db_connection)?
+		};
+		entry.latest_history_id = Some(update_history(
+			db_connection,
+			entry.clone(),
+			&application.id,
+			applied_rule_tag,
+		)?);
+
+		let tags: Vec<TagDb> = tags::table
+			.filter(
+				tags::id.eq_any(
+					event_log_tags::table
+						.filter(event_log_tags::log_entry.eq(&entry.id))
+						.select(event_log_tags::tag),
+				),
+			)
+			.load(db_connection)?;
+		let editor: Option<User> = if let Some(editor) = entry.editor.as_ref() {
+			Some(users::table.find(editor).first(db_connection)?)
+		} else {
+			None
+		};
+		let created_by: Option<User> = if let Some(creator) = entry.created_by.as_ref() {
+			Some(users::table.find(creator).first(db_connection)?)
+		} else {
+			None
+		};
+
+		let tags = tags.into_iter().map(|tag| tag.into()).collect();
+		let editor = editor.map(|editor| editor.into());
+		let created_by = created_by.map(|creator| creator.into());
+		let custom_fields = entry_custom_field_values(db_connection, &entry.id)?;
+		let entry = entry.into_sync_entry(tags, editor, created_by, custom_fields);
+
+		Ok((event, entry))
+	});
+
+	drop(db_connection);
+
+	let response = match update_result {
+		Ok((event, entry)) => {
+			let subscription_manager = subscription_manager.lock().await;
+			let event_id = event.id.clone();
+			let message =
+				SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::UpdateLogEntry(entry, None)));
+			if let Err(error) = subscription_manager.broadcast_event_message(&event_id, message).await {
+				tide::log::error!("Failed to broadcast entry update for API entry update: {}", error);
+			}
+
+			Response::builder(StatusCode::Ok).build()
+		}
+		Err(diesel::result::Error::NotFound) => Response::builder(StatusCode::NotFound).build(),
+		Err(error) => {
+			tide::log::error!("Database error updating entry from API: {}", error);
+			Response::builder(StatusCode::InternalServerError)
+				.body("Database error")
+				.build()
+		}
+	};
+	Ok(response)
+}
@@ -4,11 +4,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::data_sync::load_event_permission;
 use crate::models::{Application, EventLogEntry, EventLogHistoryEntry, EventLogHistoryTag, EventLogTag};
-use crate::schema::{applications, event_log_history, event_log_history_tags, event_log_tags};
+use crate::schema::{
+	application_events, applications, event_log, event_log_history, event_log_history_tags, event_log_tags, users,
+};
 use chrono::Utc;
 use diesel::prelude::*;
 use tide::{Request, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
 
 #[derive(Debug)]
 enum RequestApplicationError {
@@ -23,22 +27,41 @@ async fn get_requesting_application(
 	let auth_token_header = request.header("Authorization");
 
 	match auth_token_header {
-		Some(token_header) => {
-			let token_header_value = token_header.last();
-			applications::table
-				.filter(applications::auth_key.eq(token_header_value.as_str()))
-				.first(db_connection)
-				.map_err(|_| RequestApplicationError::InvalidToken)
-		}
+		Some(token_header) => get_application_by_auth_key(token_header.last().as_str(), db_connection),
 		None => Err(RequestApplicationError::NoToken),
 	}
 }
 
+fn get_application_by_auth_key(
+	auth_key: &str,
+	db_connection: &mut PgConnection,
+) -> Result<Application, RequestApplicationError> {
+	applications::table
+		.filter(applications::auth_key.eq(auth_key))
+		.first(db_connection)
+		.map_err(|_| RequestApplicationError::InvalidToken)
+}
+
 pub async fn check_application(
 	request: &Request<()>,
 	db_connection: &mut PgConnection,
 ) -> Result<Application, tide::Error> {
 	let application_result = get_requesting_application(request, db_connection).await;
+	application_result_to_response(application_result)
+}
+
+/// Checks an application auth key passed some way other than the `Authorization` header. This is for endpoints meant
+/// to be used by clients that can't set headers, like calendar applications.
+pub fn check_application_auth_key(
+	auth_key: &str,
+	db_connection: &mut PgConnection,
+) -> Result<Application, tide::Error> {
+	application_result_to_response(get_application_by_auth_key(auth_key, db_connection))
+}
+
+fn application_result_to_response(
+	application_result: Result<Application, RequestApplicationError>,
+) -> Result<Application, tide::Error> {
 	match application_result {
 		Ok(application) => Ok(application),
 		Err(RequestApplicationError::InvalidToken) => Err(tide::Error::new(
@@ -52,7 +75,151 @@ pub async fn check_application(
 	}
 }
 
-pub fn update_history(db_connection: &mut PgConnection, entry: EventLogEntry, application_id: &str) -> QueryResult<()> {
+/// Checks whether the application's event scope includes the given event
+pub fn check_application_event_access(
+	db_connection: &mut PgConnection,
+	application: &Application,
+	event_id: &str,
+) -> Result<(), tide::Error> {
+	if !application.restrict_events {
+		return Ok(());
+	}
+
+	let has_access: QueryResult<bool> = diesel::select(diesel::dsl::exists(
+		application_events::table.filter(
+			application_events::application
+				.eq(&application.id)
+				.and(application_events::event.eq(event_id)),
+		),
+	))
+	.get_result(db_connection);
+	match has_access {
+		Ok(true) => Ok(()),
+		Ok(false) => Err(tide::Error::new(
+			StatusCode::Forbidden,
+			anyhow::Error::msg("Not authorized to access this event."),
+		)),
+		Err(error) => {
+			tide::log::error!("API error checking application event access: {}", error);
+			Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			))
+		}
+	}
+}
+
+/// Checks whether the application's event scope includes the event containing the given log entry
+pub fn check_application_entry_access(
+	db_connection: &mut PgConnection,
+	application: &Application,
+	entry_id: &str,
+) -> Result<(), tide::Error> {
+	if !application.restrict_events {
+		return Ok(());
+	}
+
+	let event_id: QueryResult<String> = event_log::table
+		.find(entry_id)
+		.select(event_log::event)
+		.first(db_connection);
+	match event_id {
+		Ok(event_id) => check_application_event_access(db_connection, application, &event_id),
+		Err(diesel::result::Error::NotFound) => Err(tide::Error::new(
+			StatusCode::NotFound,
+			anyhow::Error::msg("Entry not found"),
+		)),
+		Err(error) => {
+			tide::log::error!("API error loading entry event for application access check: {}", error);
+			Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			))
+		}
+	}
+}
+
+/// Checks whether the request may read the given event's log. Requests can come either from an application allowed to
+/// read event logs (identified by its token) or from a logged-in user with access to the event, so that the web client
+/// can link directly to read-only endpoints.
+pub async fn check_event_log_read_access(
+	request: &Request<()>,
+	db_connection: &mut PgConnection,
+	event_id: &str,
+) -> Result<(), tide::Error> {
+	if request.header("Authorization").is_some() {
+		let application = check_application(request, db_connection).await?;
+		if !application.read_log {
+			return Err(tide::Error::new(
+				StatusCode::Unauthorized,
+				anyhow::Error::msg("Not authorized to access this resource."),
+			));
+		}
+		return check_application_event_access(db_connection, &application, event_id);
+	}
+
+	let Some(openid_user_id) = request.user_id() else {
+		return Err(tide::Error::new(
+			StatusCode::Unauthorized,
+			anyhow::Error::msg("Not authorized"),
+		));
+	};
+	let user_id: QueryResult<Option<String>> = users::table
+		.filter(users::openid_user_id.eq(&openid_user_id))
+		.select(users::id)
+		.first(db_connection)
+		.optional();
+	let permission = user_id.and_then(|user_id| match user_id {
+		Some(user_id) => load_event_permission(db_connection, &user_id, event_id),
+		None => Ok(None),
+	});
+	match permission {
+		Ok(Some(_)) => Ok(()),
+		Ok(None) => Err(tide::Error::new(
+			StatusCode::Forbidden,
+			anyhow::Error::msg("Not authorized to access this resource."),
+		)),
+		Err(error) => {
+			tide::log::error!("API error checking event access: {}", error);
+			Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			))
+		}
+	}
+}
+
+/// Escapes text for use in HTML, including in quoted attribute values
+pub fn escape_html(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Checks whether a user-entered link is a web link. Only these are safe to make into clickable links, as other schemes
+/// (like `javascript:`) can run code when clicked.
+pub fn is_web_link(link: &str) -> bool {
+	link.starts_with("http://") || link.starts_with("https://")
+}
+
+/// Records the current state of an entry changed by an application in the entry's history. If the change applied a
+/// video edit state rule, the ID of the rule's tag should be given. Returns the ID of the new history entry, which is also
+/// stored as the entry's latest history ID.
+pub fn update_history(
+	db_connection: &mut PgConnection,
+	entry: EventLogEntry,
+	application_id: &str,
+	applied_rule_tag: Option<String>,
+) -> QueryResult<String> {
 	let tags: Vec<EventLogTag> = event_log_tags::table
 		.filter(event_log_tags::log_entry.eq(&entry.id))
 		.load(db_connection)?;
@@ -82,6 +249,7 @@ pub fn update_history(db_connection: &mut PgConnection, entry: EventLogEntry, ap
 		video_edit_state: entry.video_edit_state,
 		missing_giveaway_information: entry.missing_giveaway_information,
 		end_time_incomplete: entry.end_time_incomplete,
+		video_edit_state_rule_tag: applied_rule_tag,
 	};
 	let history_tags: Vec<EventLogHistoryTag> = tags
 		.iter()
@@ -90,12 +258,17 @@ pub fn update_history(db_connection: &mut PgConnection, entry: EventLogEntry, ap
 			tag: entry_tag.tag.clone(),
 		})
 		.collect();
+	let history_id = history_entry.id.clone();
+	let entry_id = history_entry.log_entry.clone();
 	diesel::insert_into(event_log_history::table)
 		.values(history_entry)
 		.execute(db_connection)?;
 	diesel::insert_into(event_log_history_tags::table)
 		.values(history_tags)
 		.execute(db_connection)?;
+	diesel::update(event_log::table.find(&entry_id))
+		.set(event_log::latest_history_id.eq(&history_id))
+		.execute(db_connection)?;
 
-	Ok(())
+	Ok(history_id)
 }
@@ -0,0 +1,305 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log_export::{export_file_name, format_duration};
+use super::event_log_list::load_event_log_entries;
+use super::structures::event_log_entry::{EndTimeData, EventLogEntry as EventLogEntryApi};
+use super::structures::video_edit_state::VideoEditState;
+use super::utils::{check_event_log_read_access, escape_html, is_web_link};
+use crate::database::handle_lost_db_connection;
+use crate::models::Event as EventDb;
+use crate::schema::events;
+use chrono::TimeDelta;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use tide::{Request, Response, StatusCode};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ReportFormat {
+	#[default]
+	Markdown,
+	Html,
+}
+
+impl ReportFormat {
+	fn extension(&self) -> &'static str {
+		match self {
+			Self::Markdown => "md",
+			Self::Html => "html",
+		}
+	}
+
+	fn content_type(&self) -> &'static str {
+		match self {
+			Self::Markdown => "text/markdown; charset=utf-8",
+			Self::Html => "text/html; charset=utf-8",
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+	#[serde(default)]
+	format: ReportFormat,
+}
+
+/// The number of tags to list in the report's top tags section
+const TOP_TAG_COUNT: usize = 10;
+
+/// The number of entries to list in the report's longest segments section
+const LONGEST_SEGMENT_COUNT: usize = 10;
+
+/// Per-editor totals for the report's editor section
+#[derive(Default)]
+struct EditorTotals {
+	assigned: usize,
+	done_editing: usize,
+}
+
+/// GET /api/v1/event/:id/wrap_report
+///
+/// Generates an end-of-event summary of the event log, suitable for newsletters and similar. The report has totals by
+/// entry type, the most used tags, poster moments, editor statistics, and the longest segments. The `format` query
+/// argument may be `markdown` (the default) or `html`.
+///
+/// Like the log export, this can also be used by logged-in users with access to the event (without an application
+/// token) so that the report can be downloaded from the web client.
+pub async fn wrap_report(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let query_params: QueryParams = request.query()?;
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return handle_lost_db_connection(error),
+	};
+
+	let event_id = request.param("id")?;
+	check_event_log_read_access(&request, &mut db_connection, event_id).await?;
+
+	let event: QueryResult<EventDb> = events::table.find(event_id).first(&mut *db_connection);
+	let event: EventDb = match event {
+		Ok(event) => event,
+		Err(diesel::result::Error::NotFound) => {
+			return Err(tide::Error::new(
+				StatusCode::NotFound,
+				anyhow::Error::msg("No such event"),
+			));
+		}
+		Err(error) => {
+			tide::log::error!("API error loading event: {}", error);
+			return Err(tide::Error::new(
+				StatusCode::InternalServerError,
+				anyhow::Error::msg("Database error"),
+			));
+		}
+	};
+
+	let event_log = load_event_log_entries(&mut db_connection, &event, None)?;
+	let report = report_markdown(&event, &event_log);
+	let report = match query_params.format {
+		ReportFormat::Markdown => report,
+		ReportFormat::Html => format!(
+			"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{} Wrap Report</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+			escape_html(&event.name),
+			markdown::to_html(&report)
+		),
+	};
+
+	let file_name = format!(
+		"{} Wrap Report.{}",
+		export_file_name(&event.name),
+		query_params.format.extension()
+	);
+	Ok(Response::builder(StatusCode::Ok)
+		.body(report)
+		.content_type(query_params.format.content_type())
+		.header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+		.build())
+}
+
+/// Builds the report as Markdown. The HTML report is rendered from this.
+fn report_markdown(event: &EventDb, event_log: &[EventLogEntryApi]) -> String {
+	let mut report = String::new();
+	// Writing to a String can't fail, so the results of the writes below are ignored.
+	let _ = writeln!(report, "# {} Wrap Report", escape_markdown(&event.name));
+	let _ = writeln!(report);
+	let _ = write!(report, "Started {}", event.start_time.format("%Y-%m-%d %H:%M UTC"));
+	if let Some(end_time) = event.end_time.as_ref() {
+		let _ = write!(report, " and ended {}", end_time.format("%Y-%m-%d %H:%M UTC"));
+	}
+	let _ = writeln!(report, ".");
+	let _ = writeln!(report);
+
+	let video_count = event_log.iter().filter(|entry| entry.video_link.is_some()).count();
+	let _ = writeln!(report, "## Totals");
+	let _ = writeln!(report);
+	let _ = writeln!(report, "- Entries: {}", event_log.len());
+	let _ = writeln!(report, "- Videos: {}", video_count);
+	let mut type_counts: HashMap<&str, usize> = HashMap::new();
+	for entry in event_log.iter() {
+		let type_name = entry
+			.entry_type
+			.as_ref()
+			.map(|entry_type| entry_type.name.as_str())
+			.unwrap_or("No type");
+		*type_counts.entry(type_name).or_default() += 1;
+	}
+	for (type_name, count) in sorted_counts(type_counts) {
+		let _ = writeln!(report, "- {}: {}", escape_markdown(type_name), count);
+	}
+	let _ = writeln!(report);
+
+	let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+	for entry in event_log.iter() {
+		for tag in entry.tags.iter() {
+			*tag_counts.entry(&tag.tag).or_default() += 1;
+		}
+	}
+	let _ = writeln!(report, "## Top Tags");
+	let _ = writeln!(report);
+	if tag_counts.is_empty() {
+		let _ = writeln!(report, "No entries were tagged.");
+	} else {
+		for (tag, count) in sorted_counts(tag_counts).into_iter().take(TOP_TAG_COUNT) {
+			let _ = writeln!(report, "1. {}: {}", escape_markdown(tag), count);
+		}
+	}
+	let _ = writeln!(report);
+
+	let _ = writeln!(report, "## Poster Moments");
+	let _ = writeln!(report);
+	let poster_moments: Vec<&EventLogEntryApi> = event_log.iter().filter(|entry| entry.poster_moment).collect();
+	if poster_moments.is_empty() {
+		let _ = writeln!(report, "No entries were marked as poster moments.");
+	} else {
+		for entry in poster_moments {
+			let _ = writeln!(report, "- {}", entry_summary(event, entry));
+		}
+	}
+	let _ = writeln!(report);
+
+	let mut editor_totals: HashMap<&str, EditorTotals> = HashMap::new();
+	for entry in event_log.iter() {
+		let Some(editor) = entry.editor.as_ref() else {
+			continue;
+		};
+		let totals = editor_totals.entry(&editor.username).or_default();
+		totals.assigned += 1;
+		if let VideoEditState::DoneEditing = entry.video_edit_state {
+			totals.done_editing += 1;
+		}
+	}
+	let _ = writeln!(report, "## Editors");
+	let _ = writeln!(report);
+	if editor_totals.is_empty() {
+		let _ = writeln!(report, "No entries were assigned to editors.");
+	} else {
+		let mut editor_totals: Vec<(&str, EditorTotals)> = editor_totals.into_iter().collect();
+		editor_totals.sort_by(|(name_a, totals_a), (name_b, totals_b)| {
+			totals_b
+				.assigned
+				.cmp(&totals_a.assigned)
+				.then_with(|| name_a.cmp(name_b))
+		});
+		for (editor, totals) in editor_totals {
+			let _ = writeln!(
+				report,
+				"- {}: {} assigned, {} done editing",
+				escape_markdown(editor),
+				totals.assigned,
+				totals.done_editing
+			);
+		}
+	}
+	let _ = writeln!(report);
+
+	let mut segments: Vec<(&EventLogEntryApi, TimeDelta)> = event_log
+		.iter()
+		.filter_map(|entry| match &entry.end_time {
+			EndTimeData::Time(end_time) => Some((entry, *end_time - entry.start_time)),
+			_ => None,
+		})
+		.collect();
+	segments.sort_by(|(_, duration_a), (_, duration_b)| duration_b.cmp(duration_a));
+	let _ = writeln!(report, "## Longest Segments");
+	let _ = writeln!(report);
+	if segments.is_empty() {
+		let _ = writeln!(report, "No entries have end times.");
+	} else {
+		for (entry, duration) in segments.into_iter().take(LONGEST_SEGMENT_COUNT) {
+			let _ = writeln!(
+				report,
+				"1. {} ({})",
+				entry_summary(event, entry),
+				format_duration(duration)
+			);
+		}
+	}
+
+	report
+}
+
+/// Sorts counts with the highest first, breaking ties by name
+fn sorted_counts(counts: HashMap<&str, usize>) -> Vec<(&str, usize)> {
+	let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+	counts.sort_by(|(name_a, count_a), (name_b, count_b)| count_b.cmp(count_a).then_with(|| name_a.cmp(name_b)));
+	counts
+}
+
+/// Describes an entry for lists in the report
+fn entry_summary(event: &EventDb, entry: &EventLogEntryApi) -> String {
+	let description = if entry.description.is_empty() {
+		String::from("(no description)")
+	} else {
+		escape_markdown(&entry.description)
+	};
+	let mut summary = format!(
+		"#{} at {}: {}",
+		entry.entry_number,
+		format_duration(entry.start_time - event.start_time),
+		description
+	);
+	if let Some(video_link) = entry.video_link.as_ref().filter(|link| is_web_link(link)) {
+		let _ = write!(summary, " ([video]({}))", escape_markdown_link(video_link));
+	}
+	summary
+}
+
+/// Escapes characters in user-entered text that Markdown would otherwise treat as formatting
+fn escape_markdown(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'\\' | '`' | '*' | '_' | '[' | ']' | '#' | '<' | '>' => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+			'\r' | '\n' => escaped.push(' '),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Percent-encodes the characters in a link that would end a Markdown link destination early
+fn escape_markdown_link(link: &str) -> String {
+	let mut escaped = String::with_capacity(link.len());
+	for c in link.chars() {
+		match c {
+			' ' | '"' | '\'' | '(' | ')' | '<' | '>' | '\\' | '`' | '\r' | '\n' => {
+				let _ = write!(escaped, "%{:02X}", c as u32);
+			}
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
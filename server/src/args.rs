@@ -17,4 +17,19 @@ pub struct CliArgs {
 		help = "Only run database migrations to update the schema (don't start the web server)"
 	)]
 	pub migrations_only: bool,
+	#[arg(
+		long,
+		help = "Check stored event log data for problems at startup and log any that are found"
+	)]
+	pub check_integrity: bool,
+	#[arg(
+		long,
+		help = "Check stored event log data for problems at startup and fix any that are found (implies --check-integrity)"
+	)]
+	pub fix_integrity: bool,
+	#[arg(
+		long,
+		help = "Serve the app and read-only API requests, but reject all changes (for use with a read replica of the database)"
+	)]
+	pub read_only: bool,
 }
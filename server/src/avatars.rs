@@ -0,0 +1,82 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::database::log_lost_db_connection;
+use crate::models::UserAvatar;
+use crate::schema::{user_avatars, users};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use tide::{Request, Response, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+/// Determines the type of an uploaded avatar image from its contents. Only raster image formats browsers can display
+/// are accepted; anything else (including SVG, which can contain scripts) returns None.
+pub fn avatar_content_type(image: &[u8]) -> Option<&'static str> {
+	if image.starts_with(b"\x89PNG\r\n\x1a\n") {
+		Some("image/png")
+	} else if image.starts_with(b"\xff\xd8\xff") {
+		Some("image/jpeg")
+	} else if image.starts_with(b"GIF87a") || image.starts_with(b"GIF89a") {
+		Some("image/gif")
+	} else if image.len() >= 12 && image.starts_with(b"RIFF") && &image[8..12] == b"WEBP" {
+		Some("image/webp")
+	} else {
+		None
+	}
+}
+
+/// Serves a user's uploaded avatar to any registered user. The client adds the time the avatar was updated to the URL,
+/// so responses can be cached for a long time.
+pub async fn serve_avatar(
+	request: Request<()>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let Some(openid_user_id) = request.user_id() else {
+		return Ok(Response::new(StatusCode::Unauthorized));
+	};
+	let user_id = request.param("user_id")?;
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => {
+			log_lost_db_connection(error);
+			return Ok(Response::new(StatusCode::InternalServerError));
+		}
+	};
+
+	let requester_enabled: QueryResult<Option<bool>> = users::table
+		.filter(users::openid_user_id.eq(&openid_user_id))
+		.select(users::enabled)
+		.first(&mut *db_connection)
+		.optional();
+	match requester_enabled {
+		Ok(Some(true)) => (),
+		Ok(_) => return Ok(Response::new(StatusCode::Forbidden)),
+		Err(error) => {
+			tide::log::error!("Database error checking access to an avatar: {}", error);
+			return Ok(Response::new(StatusCode::InternalServerError));
+		}
+	}
+
+	let avatar: QueryResult<Option<UserAvatar>> =
+		user_avatars::table.find(user_id).first(&mut *db_connection).optional();
+	match avatar {
+		Ok(Some(avatar)) => {
+			let response = Response::builder(StatusCode::Ok)
+				.body(avatar.image)
+				.content_type(avatar.content_type.as_str())
+				.header("Cache-Control", "private, max-age=31536000, immutable")
+				.header("X-Content-Type-Options", "nosniff")
+				.build();
+			Ok(response)
+		}
+		Ok(None) => Ok(Response::new(StatusCode::NotFound)),
+		Err(error) => {
+			tide::log::error!("Database error loading an avatar: {}", error);
+			Ok(Response::new(StatusCode::InternalServerError))
+		}
+	}
+}
@@ -5,7 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use async_std::fs;
-use knuffel::Decode;
+use knuffel::{Decode, DecodeScalar};
 use miette::{IntoDiagnostic, Result};
 
 pub async fn parse_config(config_path: &str) -> Result<ConfigDocument> {
@@ -26,6 +26,18 @@ pub struct ConfigDocument {
 	pub database: DatabaseArgs,
 	#[knuffel(child, unwrap(argument))]
 	pub favicon_file: Option<String>,
+	#[knuffel(child, unwrap(argument))]
+	pub client_config_file: Option<String>,
+	#[knuffel(child, unwrap(argument))]
+	pub archive_directory: Option<String>,
+	#[knuffel(child)]
+	pub link_checker: Option<LinkCheckerConfig>,
+	#[knuffel(child)]
+	pub object_storage: Option<ObjectStorageConfig>,
+	#[knuffel(child)]
+	pub metrics: Option<MetricsConfig>,
+	#[knuffel(child)]
+	pub logging: Option<LoggingConfig>,
 }
 
 #[derive(Debug, Decode)]
@@ -48,6 +60,61 @@ pub struct ListenAddr {
 	pub addr: String,
 }
 
+#[derive(Debug, Decode)]
+pub struct LinkCheckerConfig {
+	#[knuffel(child, unwrap(argument))]
+	pub interval_minutes: u64,
+	#[knuffel(child, unwrap(argument))]
+	pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Decode)]
+pub struct ObjectStorageConfig {
+	#[knuffel(child, unwrap(argument))]
+	pub endpoint: String,
+	#[knuffel(child, unwrap(argument))]
+	pub region: String,
+	#[knuffel(child, unwrap(argument))]
+	pub bucket: String,
+	#[knuffel(child, unwrap(argument))]
+	pub access_key_id: String,
+	#[knuffel(child, unwrap(argument))]
+	pub secret_access_key: String,
+	#[knuffel(child, unwrap(argument))]
+	pub presigned_url_expiry_minutes: Option<u64>,
+}
+
+#[derive(Debug, Decode)]
+pub struct MetricsConfig {
+	#[knuffel(child, unwrap(argument))]
+	pub auth_token: Option<String>,
+}
+
+#[derive(Debug, Decode)]
+pub struct LoggingConfig {
+	#[knuffel(child, unwrap(argument))]
+	pub format: Option<LogFormat>,
+	#[knuffel(child, unwrap(argument))]
+	pub level: Option<LogLevel>,
+}
+
+#[derive(Clone, Copy, Debug, Default, DecodeScalar)]
+pub enum LogFormat {
+	#[default]
+	Text,
+	Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, DecodeScalar)]
+pub enum LogLevel {
+	Error,
+	Warn,
+	#[default]
+	Info,
+	Debug,
+	Trace,
+}
+
 #[derive(Debug, Decode)]
 pub struct DatabaseArgs {
 	#[knuffel(child, unwrap(argument))]
@@ -4,30 +4,74 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::entry_history::handle_entry_history_request;
+use super::event_access::handle_event_access_request;
+use super::event_log_import::{handle_event_log_import_request, EventLogImportArgs};
+use super::feedback_rate_limiter::FeedbackRateLimiter;
+use super::impersonation::{handle_impersonation_request, ImpersonationArgs};
+use super::message_rate_limiter::{MessageRateLimiter, RateLimitResult};
 use super::new_event_entries::NewEventEntries;
 use super::register::{check_username, register_user};
+use super::subscriptions::admin_access_requests::{
+	handle_admin_event_access_requests_message, subscribe_to_admin_event_access_requests,
+};
 use super::subscriptions::admin_applications::{handle_admin_applications_message, subscribe_to_admin_applications};
-use super::subscriptions::admin_editors::{handle_admin_editors_message, subscribe_to_admin_editors};
+use super::subscriptions::admin_audit_log::subscribe_to_admin_audit_log;
+use super::subscriptions::admin_custom_fields::{
+	handle_admin_event_custom_fields_message, subscribe_to_admin_event_custom_fields,
+};
+use super::subscriptions::admin_editors::{
+	handle_admin_editors_message, subscribe_to_admin_editors, subscribe_to_admin_editors_for_event,
+};
 use super::subscriptions::admin_entry_types::{
 	handle_admin_entry_type_event_message, handle_admin_entry_type_message, subscribe_to_admin_entry_types,
 	subscribe_to_admin_entry_types_events,
 };
 use super::subscriptions::admin_events::{handle_admin_event_message, subscribe_to_admin_events};
+use super::subscriptions::admin_jobs::subscribe_to_admin_jobs;
 use super::subscriptions::admin_pages::{handle_admin_info_pages_message, subscribe_to_admin_info_pages};
 use super::subscriptions::admin_permission_groups::{
 	handle_admin_permission_group_users_message, handle_admin_permission_groups_message,
 	subscribe_to_admin_permission_groups, subscribe_to_admin_permission_groups_users,
 };
-use super::subscriptions::admin_tabs::{handle_admin_event_log_tabs_message, subscribe_to_admin_event_log_tabs};
-use super::subscriptions::admin_users::{handle_admin_users_message, subscribe_to_admin_users};
+use super::subscriptions::admin_rejected_updates::subscribe_to_admin_rejected_updates;
+use super::subscriptions::admin_share_links::{
+	handle_admin_event_share_links_message, subscribe_to_admin_event_share_links,
+};
+use super::subscriptions::admin_tabs::{
+	handle_admin_event_log_tabs_message, subscribe_to_admin_event_log_tabs, subscribe_to_admin_event_log_tabs_for_event,
+};
+use super::subscriptions::admin_user_event_permissions::{
+	handle_admin_user_event_permissions_message, subscribe_to_admin_user_event_permissions,
+};
+use super::subscriptions::admin_users::{
+	handle_admin_user_profile_message, handle_admin_user_sessions_revoke_message, handle_admin_users_message,
+	subscribe_to_admin_users,
+};
+use super::subscriptions::admin_webhooks::{handle_admin_event_webhooks_message, subscribe_to_admin_event_webhooks};
+use super::subscriptions::editor_workload::subscribe_to_editor_workload;
 use super::subscriptions::events::{handle_event_update, subscribe_to_event, SubscribeToEventArgs};
-use super::user_profile::handle_profile_update;
-use super::HandleConnectionError;
+use super::subscriptions::user_notifications::{handle_user_notifications_message, subscribe_to_user_notifications};
+use super::tab_entries::handle_tab_entries_request;
+use super::user::load_event_permissions;
+use super::user_profile::{
+	handle_avatar_update, handle_filter_preset_update, handle_guided_tour_update, handle_hotkeys_update,
+	handle_notification_preferences_update, handle_profile_update, handle_time_display_update, handle_username_update,
+};
+use super::user_sessions::{handle_session_revoke, handle_sessions_request};
+use super::{report_handler_result, run_db_operation, HandleConnectionError, HandlerError};
+use crate::config::ConfigDocument;
 use crate::data_sync::{SubscriptionManager, UserDataUpdate};
 use crate::database::handle_lost_db_connection;
-use crate::models::{Event as EventDb, Permission, PermissionEvent, User};
-use crate::schema::{events, permission_events, user_permissions, users};
-use crate::websocket_msg::{recv_msg, WebSocketRecvError};
+use crate::jobs::JobQueue;
+use crate::logging::{set_log_connection, set_log_user, start_log_message};
+use crate::metrics::Metrics;
+use crate::models::{Permission, User, UserHotkey, UserViewPreference};
+use crate::plugins::PluginRegistry;
+use crate::schema::{user_hotkeys, user_view_preferences, users};
+use crate::session::{record_session_activity, SessionSelection};
+use crate::storage::FileStorage;
+use crate::websocket_msg::{recv_msg, send_msg, WebSocketRecvError};
 use async_std::channel::{unbounded, Receiver, RecvError, Sender};
 use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
@@ -36,11 +80,19 @@ use erased_serde::Serialize;
 use futures::{select, FutureExt};
 use rgb::RGB8;
 use std::collections::HashMap;
+use std::time::Instant;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::{
+	EventSubscriptionData, EventSubscriptionUpdate, NewTypingData, TypingData,
+};
 use stream_log_shared::messages::events::Event;
-use stream_log_shared::messages::initial::{InitialMessage, UserDataLoad};
+use stream_log_shared::messages::hotkeys::HotkeyBinding;
+use stream_log_shared::messages::initial::{InitialMessage, UserDataLoad, CLIENT_SYNC_VERSION_PARAMETER};
+use stream_log_shared::messages::permissions::PermissionLevel;
 use stream_log_shared::messages::subscriptions::{SubscriptionData, SubscriptionTargetUpdate, SubscriptionType};
-use stream_log_shared::messages::user::{SelfUserData, UserSubscriptionUpdate};
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData, UserSubscriptionUpdate};
 use stream_log_shared::messages::user_register::UserRegistration;
+use stream_log_shared::messages::view_preferences::FilterPreset;
 use stream_log_shared::messages::{FromClientMessage, FromServerMessage};
 use tide::Request;
 use tide_openidconnect::OpenIdConnectRequestExt;
@@ -48,9 +100,19 @@ use tide_websockets::WebSocketConnection;
 
 pub enum ConnectionUpdate {
 	SendData(Box<dyn Serialize + Send + Sync>),
+	SendDataByEventPermission(Box<PermissionRestrictedData>),
 	UserUpdate(UserDataUpdate),
 }
 
+/// Data that should only be sent to users with at least some permission level for an event
+pub struct PermissionRestrictedData {
+	pub event_id: String,
+	pub minimum_permission: Permission,
+	pub data: Box<dyn Serialize + Send + Sync>,
+	/// What to send instead to users without the required permission level
+	pub alternate_data: Box<dyn Serialize + Send + Sync>,
+}
+
 /// Runs the WebSocket connection with the user
 pub async fn handle_connection(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -58,17 +120,44 @@ pub async fn handle_connection(
 	mut stream: WebSocketConnection,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	new_entries: Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: Arc<PluginRegistry>,
+	config: Arc<ConfigDocument>,
+	file_storage: Option<Arc<FileStorage>>,
+	metrics: Arc<Metrics>,
+	job_queue: Arc<JobQueue>,
+	read_only: bool,
 ) -> tide::Result<()> {
 	let Some(openid_user_id) = request.user_id() else {
 		let message = InitialMessage::new(UserDataLoad::MissingId);
 		stream.send_json(&message).await?;
 		return Ok(());
 	};
+	let connection_id = cuid2::create_id();
+	set_log_connection(&connection_id, &openid_user_id);
+	let session_id = request.session().id().to_string();
+	let user_agent = request
+		.header("User-Agent")
+		.map(|header| header.last().as_str().to_string())
+		.unwrap_or_default();
+	// Clients from before they sent their version are older than any version the server supports
+	let client_sync_version: u32 = request
+		.url()
+		.query_pairs()
+		.find(|(name, _)| name == CLIENT_SYNC_VERSION_PARAMETER)
+		.and_then(|(_, value)| value.parse().ok())
+		.unwrap_or(0);
 
 	let mut db_connection = match db_connection_pool.get() {
 		Ok(connection) => connection,
 		Err(error) => return handle_lost_db_connection(error).map(|_| ()),
 	};
+	// Sessions are kept in memory in read-only mode, so there's no stored session to update
+	if !read_only {
+		if let Err(error) = record_session_activity(&mut db_connection, &session_id, &openid_user_id, &user_agent) {
+			tide::log::error!("Failed to record session activity: {}", error);
+		}
+	}
 	let results: QueryResult<Vec<User>> = users::table
 		.filter(users::openid_user_id.eq(&openid_user_id))
 		.load(&mut *db_connection);
@@ -91,6 +180,12 @@ pub async fn handle_connection(
 			return Ok(());
 		}
 	};
+	if user.as_ref().is_some_and(|user| !user.enabled) {
+		tide::log::info!("A deactivated user attempted to connect");
+		let message = InitialMessage::new(UserDataLoad::Deactivated);
+		stream.send_json(&message).await?;
+		return Ok(());
+	}
 	let user_data = user.map(|user| {
 		let color = RGB8::new(
 			user.color_red.try_into().unwrap(),
@@ -103,57 +198,62 @@ pub async fn handle_connection(
 			is_admin: user.is_admin,
 			color,
 			use_spell_check: user.use_spell_check,
+			completed_guided_tour: user.completed_guided_tour,
+			enabled: user.enabled,
+			avatar_updated_at: user.avatar_updated_at,
+			notification_preferences: user.notification_preferences(),
+			time_display: user.time_display(),
 		}
 	});
+	set_log_user(user_data.as_ref().map(|user| user.id.as_str()));
 
 	let event_permission_cache: HashMap<Event, Option<Permission>> = if let Some(user) = user_data.as_ref() {
-		let permission_events: QueryResult<Vec<PermissionEvent>> = permission_events::table
-			.filter(
-				permission_events::permission_group.eq_any(
-					user_permissions::table
-						.filter(user_permissions::user_id.eq(&user.id))
-						.select(user_permissions::permission_group),
-				),
-			)
-			.load(&mut *db_connection);
-		let permission_events = match permission_events {
-			Ok(permission_events) => permission_events,
+		match load_event_permissions(&mut db_connection, &user.id) {
+			Ok(available_events) => available_events,
 			Err(error) => {
 				tide::log::error!("Failed to retrieve available events from database: {}", error);
 				let message = InitialMessage::new(UserDataLoad::Error);
 				stream.send_json(&message).await?;
 				return Ok(());
 			}
-		};
-		let event_ids: Vec<String> = permission_events
-			.iter()
-			.map(|permission_event| permission_event.event.clone())
-			.collect();
-		let events: Vec<EventDb> = match events::table
-			.filter(events::id.eq_any(&event_ids))
-			.load(&mut *db_connection)
-		{
-			Ok(events) => events,
+		}
+	} else {
+		HashMap::new()
+	};
+
+	let hotkeys: Vec<HotkeyBinding> = if let Some(user) = user_data.as_ref() {
+		let hotkeys: QueryResult<Vec<UserHotkey>> = user_hotkeys::table
+			.filter(user_hotkeys::user_id.eq(&user.id))
+			.load(&mut *db_connection);
+		match hotkeys {
+			Ok(hotkeys) => hotkeys.into_iter().map(|hotkey| hotkey.into()).collect(),
 			Err(error) => {
-				tide::log::error!("Failed to retrieve events from database: {}", error);
+				tide::log::error!("Failed to retrieve user hotkeys from database: {}", error);
 				let message = InitialMessage::new(UserDataLoad::Error);
 				stream.send_json(&message).await?;
 				return Ok(());
 			}
-		};
-		let events: HashMap<String, Event> = events
-			.into_iter()
-			.map(|event| (event.id.clone(), event.into()))
-			.collect();
-		let mut available_events: HashMap<Event, Option<Permission>> = HashMap::new();
-		for permission_event in permission_events {
-			// We can expect the events we found to remain in the database, as nothing should remove them.
-			let event = events.get(&permission_event.event).unwrap().clone();
-			available_events.insert(event, Some(permission_event.level));
-		}
-		available_events
+		}
 	} else {
-		HashMap::new()
+		Vec::new()
+	};
+
+	let filter_presets: Vec<FilterPreset> = if let Some(user) = user_data.as_ref() {
+		let presets: QueryResult<Vec<UserViewPreference>> = user_view_preferences::table
+			.filter(user_view_preferences::user_id.eq(&user.id))
+			.order(user_view_preferences::name.asc())
+			.load(&mut *db_connection);
+		match presets {
+			Ok(presets) => presets.into_iter().map(|preset| preset.into()).collect(),
+			Err(error) => {
+				tide::log::error!("Failed to retrieve user filter presets from database: {}", error);
+				let message = InitialMessage::new(UserDataLoad::Error);
+				stream.send_json(&message).await?;
+				return Ok(());
+			}
+		}
+	} else {
+		Vec::new()
 	};
 
 	drop(db_connection);
@@ -165,7 +265,12 @@ pub async fn handle_connection(
 				.filter(|(_, permission)| permission.is_some())
 				.map(|(event, _)| event.clone())
 				.collect();
-			InitialMessage::new(UserDataLoad::User(user.clone(), available_events))
+			InitialMessage::new(UserDataLoad::User(
+				user.clone(),
+				available_events,
+				hotkeys,
+				filter_presets,
+			))
 		}
 		None => InitialMessage::new(UserDataLoad::NewUser),
 	};
@@ -177,11 +282,31 @@ pub async fn handle_connection(
 		user_data,
 		Arc::clone(&subscription_manager),
 		Arc::clone(&new_entries),
+		Arc::clone(&feedback_rate_limiter),
+		&plugins,
 		&openid_user_id,
+		&connection_id,
+		&session_id,
+		client_sync_version,
 		event_permission_cache,
+		&config,
+		file_storage.as_deref(),
+		&metrics,
+		&job_queue,
+		read_only,
 	)
 	.await;
 
+	if !read_only {
+		let record_result = run_db_operation(&db_connection_pool, "recording session activity", |db_connection| {
+			record_session_activity(db_connection, &session_id, &openid_user_id, &user_agent)
+		})
+		.await;
+		if let Err(error) = record_result {
+			tide::log::error!("{}", error);
+		}
+	}
+
 	match process_messages_result {
 		Err(HandleConnectionError::SendError(error)) => Err(error),
 		_ => Ok(()),
@@ -195,17 +320,33 @@ async fn process_messages(
 	mut user: Option<SelfUserData>,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	new_entries: Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: &PluginRegistry,
 	openid_user_id: &str,
+	connection_id: &str,
+	session_id: &str,
+	client_sync_version: u32,
 	mut event_permission_cache: HashMap<Event, Option<Permission>>,
+	config: &ConfigDocument,
+	file_storage: Option<&FileStorage>,
+	metrics: &Metrics,
+	job_queue: &JobQueue,
+	read_only: bool,
 ) -> Result<(), HandleConnectionError> {
 	let (conn_update_tx, conn_update_rx) = unbounded::<ConnectionUpdate>();
-	let connection_id = cuid2::create_id();
+	let mut typing_entries: HashMap<String, (Event, EventLogEntry)> = HashMap::new();
+	let mut message_rate_limiter = MessageRateLimiter::default();
+	let mut impersonating = false;
+	metrics.connection_opened();
 
-	if let Some(user) = user.as_ref() {
+	{
 		let mut subscription_manager = subscription_manager.lock().await;
-		subscription_manager
-			.subscribe_to_self_user(&connection_id, user, conn_update_tx.clone())
-			.await;
+		subscription_manager.add_connection(connection_id, session_id, client_sync_version, conn_update_tx.clone());
+		if let Some(user) = user.as_ref() {
+			subscription_manager
+				.subscribe_to_self_user(connection_id, user, conn_update_tx.clone())
+				.await;
+		}
 	}
 
 	let result = loop {
@@ -213,23 +354,55 @@ async fn process_messages(
 			db_connection_pool: db_connection_pool.clone(),
 			stream,
 			user: &mut user,
-			connection_id: &connection_id,
+			connection_id,
+			session_id,
 			subscription_manager: &subscription_manager,
 			new_entries: &new_entries,
+			feedback_rate_limiter: &feedback_rate_limiter,
+			plugins,
 			openid_user_id,
 			event_permission_cache: &mut event_permission_cache,
+			typing_entries: &mut typing_entries,
+			message_rate_limiter: &mut message_rate_limiter,
+			impersonating: &mut impersonating,
 			conn_update_tx: conn_update_tx.clone(),
 			conn_update_rx: &conn_update_rx,
+			config,
+			file_storage,
+			metrics,
+			job_queue,
+			read_only,
 		};
 		if let Err(error) = process_message(args).await {
 			break Err(error);
 		}
 	};
+	metrics.connection_closed();
+
+	// If the connection closed while the user was typing, their typing data would otherwise be shown to everyone else
+	// until it expires
+	if let Some(user) = user.as_ref() {
+		let subscription_manager = subscription_manager.lock().await;
+		let user_data: PublicUserData = user.clone().into();
+		for (event, log_entry) in typing_entries.into_values() {
+			let event_id = event.id.clone();
+			let message = SubscriptionData::EventUpdate(
+				event,
+				Box::new(EventSubscriptionData::Typing(TypingData::Clear(
+					log_entry,
+					user_data.clone(),
+				))),
+			);
+			if let Err(error) = subscription_manager.broadcast_event_message(&event_id, message).await {
+				tide::log::error!("Failed to clear typing data for a closed connection: {}", error);
+			}
+		}
+	}
 
 	subscription_manager
 		.lock()
 		.await
-		.unsubscribe_from_all(&connection_id)
+		.unsubscribe_from_all(connection_id)
 		.await?;
 
 	result
@@ -240,12 +413,25 @@ struct ProcessMessageParams<'a> {
 	stream: &'a mut WebSocketConnection,
 	user: &'a mut Option<SelfUserData>,
 	connection_id: &'a str,
+	session_id: &'a str,
 	subscription_manager: &'a Arc<Mutex<SubscriptionManager>>,
 	new_entries: &'a Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: &'a Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: &'a PluginRegistry,
 	openid_user_id: &'a str,
 	event_permission_cache: &'a mut HashMap<Event, Option<Permission>>,
+	/// The entries the user is typing in, along with the events they're in, by entry ID
+	typing_entries: &'a mut HashMap<String, (Event, EventLogEntry)>,
+	message_rate_limiter: &'a mut MessageRateLimiter,
+	/// Whether an administrator has switched the connection to another user. Such connections can't make changes.
+	impersonating: &'a mut bool,
 	conn_update_tx: Sender<ConnectionUpdate>,
 	conn_update_rx: &'a Receiver<ConnectionUpdate>,
+	config: &'a ConfigDocument,
+	file_storage: Option<&'a FileStorage>,
+	metrics: &'a Metrics,
+	job_queue: &'a JobQueue,
+	read_only: bool,
 }
 
 async fn process_message(args: ProcessMessageParams<'_>) -> Result<(), HandleConnectionError> {
@@ -253,7 +439,7 @@ async fn process_message(args: ProcessMessageParams<'_>) -> Result<(), HandleCon
 		let mut conn_update_future = args.conn_update_rx.recv().fuse();
 		let mut recv_msg_future = Box::pin(recv_msg(args.stream).fuse());
 		select! {
-			conn_update_result = conn_update_future => process_connection_update(conn_update_result, args.user, args.event_permission_cache),
+			conn_update_result = conn_update_future => process_connection_update(conn_update_result, args.user, args.event_permission_cache, &args.conn_update_tx),
 			recv_msg_result = recv_msg_future => {
 				let incoming_msg_params = ProcessIncomingMessageParams {
 					recv_msg_result,
@@ -261,10 +447,21 @@ async fn process_message(args: ProcessMessageParams<'_>) -> Result<(), HandleCon
 					conn_update_tx: args.conn_update_tx,
 					user: args.user,
 					connection_id: args.connection_id,
+					session_id: args.session_id,
 					subscription_manager: args.subscription_manager,
 					new_entries: args.new_entries,
+					feedback_rate_limiter: args.feedback_rate_limiter,
+					plugins: args.plugins,
 					openid_user_id: args.openid_user_id,
-					event_permission_cache: args.event_permission_cache
+					event_permission_cache: args.event_permission_cache,
+					typing_entries: args.typing_entries,
+					message_rate_limiter: args.message_rate_limiter,
+					impersonating: args.impersonating,
+					config: args.config,
+					file_storage: args.file_storage,
+					metrics: args.metrics,
+					job_queue: args.job_queue,
+					read_only: args.read_only
 				};
 				match process_incoming_message(incoming_msg_params).await {
 					Ok(_) => Ok(None),
@@ -277,7 +474,7 @@ async fn process_message(args: ProcessMessageParams<'_>) -> Result<(), HandleCon
 	match process_result {
 		Ok(message) => {
 			if let Some(message_to_send) = message {
-				args.stream.send_json(&message_to_send).await?;
+				send_msg(args.stream, &message_to_send).await?;
 			}
 			Ok(())
 		}
@@ -289,15 +486,50 @@ fn process_connection_update(
 	conn_update_result: Result<ConnectionUpdate, RecvError>,
 	user: &mut Option<SelfUserData>,
 	event_permission_cache: &mut HashMap<Event, Option<Permission>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
 ) -> Result<Option<Box<dyn Serialize + Send + Sync>>, HandleConnectionError> {
 	match conn_update_result {
 		Ok(conn_update) => match conn_update {
 			ConnectionUpdate::SendData(send_message) => Ok(Some(send_message)),
+			ConnectionUpdate::SendDataByEventPermission(restricted_data) => {
+				let restricted_data = *restricted_data;
+				let permission = event_permission_cache
+					.iter()
+					.find(|(event, _)| event.id == restricted_data.event_id)
+					.and_then(|(_, permission)| *permission);
+				let minimum_permission = PermissionLevel::from(restricted_data.minimum_permission);
+				match permission {
+					Some(permission) if PermissionLevel::from(permission).is_at_least(minimum_permission) => {
+						Ok(Some(restricted_data.data))
+					}
+					_ => Ok(Some(restricted_data.alternate_data)),
+				}
+			}
 			ConnectionUpdate::UserUpdate(user_data_update) => {
 				match user_data_update {
 					UserDataUpdate::User(new_user_data) => *user = Some(new_user_data),
 					UserDataUpdate::EventPermissions(event, new_permission) => {
-						event_permission_cache.insert(event, new_permission);
+						let previous_permission =
+							event_permission_cache.insert(event.clone(), new_permission).flatten();
+						if previous_permission != new_permission {
+							// The update channel is unbounded, so this only fails if the connection is closing
+							let message = FromServerMessage::EventPermissionChanged(event);
+							let _ = conn_update_tx.try_send(ConnectionUpdate::SendData(Box::new(message)));
+						}
+					}
+					UserDataUpdate::Hotkeys(hotkeys) => {
+						let message = FromServerMessage::SubscriptionMessage(
+							Box::new(SubscriptionData::UserHotkeysUpdate(hotkeys)),
+							None,
+						);
+						return Ok(Some(Box::new(message)));
+					}
+					UserDataUpdate::FilterPresets(presets) => {
+						let message = FromServerMessage::SubscriptionMessage(
+							Box::new(SubscriptionData::UserFilterPresetsUpdate(presets)),
+							None,
+						);
+						return Ok(Some(Box::new(message)));
 					}
 				}
 				if let Some(user) = user.clone() {
@@ -307,9 +539,10 @@ fn process_connection_update(
 						.map(|(event, _)| event.clone())
 						.collect();
 					let user_subscription_data = UserSubscriptionUpdate { user, available_events };
-					let message = FromServerMessage::SubscriptionMessage(Box::new(SubscriptionData::UserUpdate(
-						user_subscription_data,
-					)));
+					let message = FromServerMessage::SubscriptionMessage(
+						Box::new(SubscriptionData::UserUpdate(user_subscription_data)),
+						None,
+					);
 					Ok(Some(Box::new(message)))
 				} else {
 					Ok(None)
@@ -326,10 +559,21 @@ struct ProcessIncomingMessageParams<'a> {
 	conn_update_tx: Sender<ConnectionUpdate>,
 	user: &'a mut Option<SelfUserData>,
 	connection_id: &'a str,
+	session_id: &'a str,
 	subscription_manager: &'a Arc<Mutex<SubscriptionManager>>,
 	new_entries: &'a Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: &'a Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: &'a PluginRegistry,
 	openid_user_id: &'a str,
 	event_permission_cache: &'a mut HashMap<Event, Option<Permission>>,
+	typing_entries: &'a mut HashMap<String, (Event, EventLogEntry)>,
+	message_rate_limiter: &'a mut MessageRateLimiter,
+	impersonating: &'a mut bool,
+	config: &'a ConfigDocument,
+	file_storage: Option<&'a FileStorage>,
+	metrics: &'a Metrics,
+	job_queue: &'a JobQueue,
+	read_only: bool,
 }
 
 async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Result<(), HandleConnectionError> {
@@ -349,7 +593,44 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 			return Err(HandleConnectionError::ConnectionClosed);
 		}
 	};
+	let message_type = message_type_name(&incoming_msg);
+	set_log_user(args.user.as_ref().map(|user| user.id.as_str()));
+	let _log_message_scope = start_log_message(message_type);
+
+	if let RateLimitResult::Throttled { notify } = args.message_rate_limiter.check(&incoming_msg) {
+		if notify {
+			report_handler_result(Err(HandlerError::RateLimited), &args.conn_update_tx).await?;
+		}
+		return Ok(());
+	}
+
+	if args.read_only && incoming_msg.makes_changes() {
+		report_handler_result(Err(HandlerError::ReadOnly), &args.conn_update_tx).await?;
+		return Ok(());
+	}
+	// Impersonation only shows what the user would see, so nothing is done in an event as them, including typing
+	// notifications that are otherwise allowed without making changes
+	let is_event_update = matches!(
+		&incoming_msg,
+		FromClientMessage::SubscriptionMessage(update)
+			if matches!(update.as_ref(), SubscriptionTargetUpdate::EventUpdate(_, _))
+	);
+	if *args.impersonating && (incoming_msg.makes_changes() || is_event_update) {
+		report_handler_result(Err(HandlerError::Impersonating), &args.conn_update_tx).await?;
+		return Ok(());
+	}
+
+	// Resuming a subscription is handled like starting one, except that synced lists can send only what changed since
+	// the version the client has
+	let (incoming_msg, known_list_version) = match incoming_msg {
+		FromClientMessage::ResumeSubscription(subscription_type, known_version) => (
+			FromClientMessage::StartSubscription(subscription_type),
+			Some(known_version),
+		),
+		message => (message, None),
+	};
 
+	let handling_start_time = Instant::now();
 	match incoming_msg {
 		FromClientMessage::StartSubscription(subscription_type) => {
 			let Some(user) = args.user.as_ref() else {
@@ -366,6 +647,7 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						new_entries: Arc::clone(args.new_entries),
 						event_id: &event_id,
 						event_permission_cache: args.event_permission_cache,
+						impersonating: *args.impersonating,
 					};
 					subscribe_to_event(subscribe_args).await?
 				}
@@ -376,6 +658,7 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						args.connection_id,
 						user,
 						Arc::clone(args.subscription_manager),
+						known_list_version,
 					)
 					.await?
 				}
@@ -386,6 +669,7 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						args.connection_id,
 						user,
 						Arc::clone(args.subscription_manager),
+						known_list_version,
 					)
 					.await?
 				}
@@ -409,6 +693,16 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await?
 				}
+				SubscriptionType::AdminUserEventPermissions => {
+					subscribe_to_admin_user_event_permissions(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
 				SubscriptionType::AdminEntryTypes => {
 					subscribe_to_admin_entry_types(
 						args.db_connection_pool.clone(),
@@ -439,6 +733,17 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await?
 				}
+				SubscriptionType::AdminEventEditorsForEvent(event_id) => {
+					subscribe_to_admin_editors_for_event(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						&event_id,
+					)
+					.await?
+				}
 				SubscriptionType::AdminEventLogTabs => {
 					subscribe_to_admin_event_log_tabs(
 						args.db_connection_pool.clone(),
@@ -446,6 +751,18 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						args.connection_id,
 						user,
 						Arc::clone(args.subscription_manager),
+						known_list_version,
+					)
+					.await?
+				}
+				SubscriptionType::AdminEventLogTabsForEvent(event_id) => {
+					subscribe_to_admin_event_log_tabs_for_event(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						&event_id,
 					)
 					.await?
 				}
@@ -469,10 +786,103 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await?
 				}
+				SubscriptionType::AdminEventAccessRequests => {
+					subscribe_to_admin_event_access_requests(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminEventWebhooks => {
+					subscribe_to_admin_event_webhooks(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminEventCustomFields => {
+					subscribe_to_admin_event_custom_fields(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminEventShareLinks => {
+					subscribe_to_admin_event_share_links(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminRejectedUpdates => {
+					subscribe_to_admin_rejected_updates(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminAuditLog => {
+					subscribe_to_admin_audit_log(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::AdminJobs => {
+					subscribe_to_admin_jobs(
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						args.job_queue,
+					)
+					.await?
+				}
+				SubscriptionType::UserNotifications => {
+					subscribe_to_user_notifications(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+					)
+					.await?
+				}
+				SubscriptionType::EditorWorkload(event_id) => {
+					subscribe_to_editor_workload(
+						args.db_connection_pool.clone(),
+						args.conn_update_tx,
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						&event_id,
+					)
+					.await?
+				}
 			}
 		}
+		// These were handled as StartSubscription messages above
+		FromClientMessage::ResumeSubscription(_, _) => (),
 		FromClientMessage::EndSubscription(subscription_type) => {
-			let subscription_manager = args.subscription_manager.lock().await;
+			let mut subscription_manager = args.subscription_manager.lock().await;
 			match subscription_type {
 				SubscriptionType::EventLogData(event_id) => {
 					subscription_manager
@@ -499,6 +909,11 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						.remove_admin_permission_group_users_subscription(args.connection_id)
 						.await?
 				}
+				SubscriptionType::AdminUserEventPermissions => {
+					subscription_manager
+						.remove_admin_user_event_permissions_subscription(args.connection_id)
+						.await?
+				}
 				SubscriptionType::AdminEntryTypes => {
 					subscription_manager
 						.remove_admin_entry_types_subscription(args.connection_id)
@@ -514,11 +929,21 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						.remove_admin_editors_subscription(args.connection_id)
 						.await?
 				}
+				SubscriptionType::AdminEventEditorsForEvent(event_id) => {
+					subscription_manager
+						.remove_admin_editors_for_event_subscription(&event_id, args.connection_id)
+						.await?
+				}
 				SubscriptionType::AdminEventLogTabs => {
 					subscription_manager
 						.remove_admin_event_log_tabs_subscription(args.connection_id)
 						.await?
 				}
+				SubscriptionType::AdminEventLogTabsForEvent(event_id) => {
+					subscription_manager
+						.remove_admin_event_log_tabs_for_event_subscription(&event_id, args.connection_id)
+						.await?
+				}
 				SubscriptionType::AdminApplications => {
 					subscription_manager
 						.remove_admin_applications_subscription(args.connection_id)
@@ -529,24 +954,90 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						.remove_admin_info_pages_subscription(args.connection_id)
 						.await?
 				}
+				SubscriptionType::AdminEventAccessRequests => {
+					subscription_manager
+						.remove_admin_event_access_requests_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminEventWebhooks => {
+					subscription_manager
+						.remove_admin_event_webhooks_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminEventCustomFields => {
+					subscription_manager
+						.remove_admin_event_custom_fields_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminEventShareLinks => {
+					subscription_manager
+						.remove_admin_event_share_links_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminRejectedUpdates => {
+					subscription_manager
+						.remove_admin_rejected_updates_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminAuditLog => {
+					subscription_manager
+						.remove_admin_audit_log_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::AdminJobs => {
+					subscription_manager
+						.remove_admin_jobs_subscription(args.connection_id)
+						.await?
+				}
+				SubscriptionType::UserNotifications => {
+					if let Some(user) = args.user.as_ref() {
+						subscription_manager
+							.remove_user_notifications_subscription(&user.id, args.connection_id)
+							.await?
+					}
+				}
+				SubscriptionType::EditorWorkload(event_id) => {
+					subscription_manager
+						.remove_editor_workload_subscription(&event_id, args.connection_id)
+						.await?
+				}
 			}
 		}
 		FromClientMessage::SubscriptionMessage(subscription_update) => {
 			let Some(user) = args.user.as_ref() else {
 				return Ok(());
 			}; // One must be subscribed (and therefore logged in) to send a subscription update message
-			match *subscription_update {
+			let result = match *subscription_update {
 				SubscriptionTargetUpdate::EventUpdate(event, update_data) => {
-					handle_event_update(
+					let typing_update = match update_data.as_ref() {
+						EventSubscriptionUpdate::Typing(typing_data) => Some((
+							typing_data.log_entry().clone(),
+							matches!(typing_data, NewTypingData::Clear(_)),
+						)),
+						_ => None,
+					};
+					let result = handle_event_update(
 						args.db_connection_pool.clone(),
 						Arc::clone(args.subscription_manager),
 						Arc::clone(args.new_entries),
+						Arc::clone(args.feedback_rate_limiter),
+						args.plugins,
 						&event,
 						user,
+						args.connection_id,
+						&args.conn_update_tx,
 						args.event_permission_cache,
 						update_data,
 					)
-					.await?
+					.await;
+					if let (Ok(()), Some((log_entry, is_clear))) = (&result, typing_update) {
+						if is_clear {
+							args.typing_entries.remove(&log_entry.id);
+						} else {
+							args.typing_entries.insert(log_entry.id.clone(), (event, log_entry));
+						}
+					}
+					result
 				}
 				SubscriptionTargetUpdate::AdminEventsUpdate(update_data) => {
 					handle_admin_event_message(
@@ -555,6 +1046,8 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						user,
 						Arc::clone(args.subscription_manager),
 						update_data,
+						args.conn_update_tx.clone(),
+						args.file_storage,
 					)
 					.await
 				}
@@ -598,6 +1091,27 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await
 				}
+				SubscriptionTargetUpdate::AdminUserProfileUpdate(modified_user, update_data) => {
+					handle_admin_user_profile_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						&modified_user,
+						update_data,
+					)
+					.await
+				}
+				SubscriptionTargetUpdate::AdminUserSessionsRevoke(modified_user) => {
+					handle_admin_user_sessions_revoke_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						&modified_user,
+					)
+					.await
+				}
 				SubscriptionTargetUpdate::AdminEventEditorsUpdate(update_data) => {
 					handle_admin_editors_message(
 						args.db_connection_pool.clone(),
@@ -618,6 +1132,16 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await
 				}
+				SubscriptionTargetUpdate::AdminUserEventPermissionsUpdate(update_data) => {
+					handle_admin_user_event_permissions_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
 				SubscriptionTargetUpdate::AdminEventLogTabsUpdate(update_data) => {
 					handle_admin_event_log_tabs_message(
 						args.db_connection_pool.clone(),
@@ -635,7 +1159,7 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 						user,
 						Arc::clone(args.subscription_manager),
 						update_data,
-						args.conn_update_tx,
+						args.conn_update_tx.clone(),
 					)
 					.await
 				}
@@ -649,7 +1173,57 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 					)
 					.await
 				}
-			}
+				SubscriptionTargetUpdate::AdminEventAccessRequestsUpdate(update_data) => {
+					handle_admin_event_access_requests_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
+				SubscriptionTargetUpdate::AdminEventWebhooksUpdate(update_data) => {
+					handle_admin_event_webhooks_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
+				SubscriptionTargetUpdate::AdminEventCustomFieldsUpdate(update_data) => {
+					handle_admin_event_custom_fields_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
+				SubscriptionTargetUpdate::AdminEventShareLinksUpdate(update_data) => {
+					handle_admin_event_share_links_message(
+						args.db_connection_pool.clone(),
+						args.connection_id,
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
+				SubscriptionTargetUpdate::UserNotificationsUpdate(update_data) => {
+					handle_user_notifications_message(
+						args.db_connection_pool.clone(),
+						user,
+						Arc::clone(args.subscription_manager),
+						update_data,
+					)
+					.await
+				}
+			};
+			report_handler_result(result, &args.conn_update_tx).await?;
 		}
 		FromClientMessage::RegistrationRequest(registration_data) => {
 			if args.user.is_none() {
@@ -674,16 +1248,271 @@ async fn process_incoming_message(args: ProcessIncomingMessageParams<'_>) -> Res
 		}
 		FromClientMessage::UpdateProfile(profile_data) => {
 			if let Some(user) = args.user.as_ref() {
-				handle_profile_update(
+				let result = handle_profile_update(
 					args.db_connection_pool.clone(),
 					user,
 					Arc::clone(args.subscription_manager),
 					profile_data,
 				)
-				.await?;
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::UpdateUsername(new_username) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_username_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					new_username,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::UpdateAvatar(image) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_avatar_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					image,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::UpdateNotificationPreferences(preferences) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_notification_preferences_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					preferences,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::UpdateTimeDisplay(time_display) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_time_display_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					time_display,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::SetGuidedTourCompleted(completed) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_guided_tour_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					completed,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::UpdateHotkeys(hotkeys) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_hotkeys_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					hotkeys,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
 			}
 		}
+		FromClientMessage::UpdateFilterPreset(update) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_filter_preset_update(
+					args.db_connection_pool.clone(),
+					user,
+					Arc::clone(args.subscription_manager),
+					update,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::RequestEventAccess(event_id) => {
+			if let Some(user) = args.user.as_ref() {
+				let result = handle_event_access_request(
+					args.db_connection_pool.clone(),
+					&args.conn_update_tx,
+					user,
+					Arc::clone(args.subscription_manager),
+					event_id,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::EventLogEntryHistory(entry_id) => {
+			if args.user.is_some() {
+				let result = handle_entry_history_request(
+					args.db_connection_pool.clone(),
+					&args.conn_update_tx,
+					args.event_permission_cache,
+					entry_id,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::RequestTabEntries(event_id, tab_id) => {
+			if args.user.is_some() {
+				let result = handle_tab_entries_request(
+					args.db_connection_pool.clone(),
+					&args.conn_update_tx,
+					args.event_permission_cache,
+					event_id,
+					tab_id,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::ImportEventLog(event_id, contents) => {
+			if let Some(user) = args.user.as_ref() {
+				let import_args = EventLogImportArgs {
+					db_connection_pool: args.db_connection_pool.clone(),
+					conn_update_tx: &args.conn_update_tx,
+					subscription_manager: Arc::clone(args.subscription_manager),
+					plugins: args.plugins,
+					event_permission_cache: args.event_permission_cache,
+					user,
+					event_id,
+					contents,
+				};
+				let result = handle_event_log_import_request(import_args).await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::RequestSessions => {
+			// The sessions belong to the administrator, not the impersonated user
+			if args.user.is_some() && !*args.impersonating {
+				let result = handle_sessions_request(
+					&args.db_connection_pool,
+					&args.conn_update_tx,
+					args.subscription_manager,
+					args.openid_user_id,
+					args.session_id,
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::RevokeSession(session_id) => {
+			if args.user.is_some() {
+				let result = handle_session_revoke(
+					&args.db_connection_pool,
+					&args.conn_update_tx,
+					args.subscription_manager,
+					args.openid_user_id,
+					args.session_id,
+					SessionSelection::Only(&session_id),
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::RevokeOtherSessions => {
+			if args.user.is_some() {
+				let result = handle_session_revoke(
+					&args.db_connection_pool,
+					&args.conn_update_tx,
+					args.subscription_manager,
+					args.openid_user_id,
+					args.session_id,
+					SessionSelection::AllExcept(args.session_id),
+				)
+				.await;
+				report_handler_result(result, &args.conn_update_tx).await?;
+			}
+		}
+		FromClientMessage::StartImpersonation(user_id) => {
+			let impersonation_args = ImpersonationArgs {
+				db_connection_pool: &args.db_connection_pool,
+				conn_update_tx: args.conn_update_tx.clone(),
+				connection_id: args.connection_id,
+				session_id: args.session_id,
+				subscription_manager: args.subscription_manager,
+				user: args.user,
+				event_permission_cache: args.event_permission_cache,
+				impersonating: args.impersonating,
+			};
+			let result = handle_impersonation_request(impersonation_args, &user_id).await;
+			report_handler_result(result, &args.conn_update_tx).await?;
+		}
+		FromClientMessage::KeepAlive => {
+			args.conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(FromServerMessage::KeepAlive)))
+				.await?;
+		}
 	};
+	args.metrics
+		.record_message_handling(message_type, handling_start_time.elapsed());
 
 	Ok(())
 }
+
+/// Gets the name under which handling times for a message are recorded. Subscription messages are named by the kind
+/// of update they contain, and typing updates are named separately from other event updates since they're much more
+/// frequent and much cheaper to handle.
+fn message_type_name(message: &FromClientMessage) -> &'static str {
+	match message {
+		FromClientMessage::StartSubscription(_) => "StartSubscription",
+		FromClientMessage::ResumeSubscription(_, _) => "ResumeSubscription",
+		FromClientMessage::EndSubscription(_) => "EndSubscription",
+		FromClientMessage::SubscriptionMessage(update) => match update.as_ref() {
+			SubscriptionTargetUpdate::EventUpdate(_, event_update) => match event_update.as_ref() {
+				EventSubscriptionUpdate::Typing(_) => "EventTyping",
+				_ => "EventUpdate",
+			},
+			SubscriptionTargetUpdate::AdminEventsUpdate(_) => "AdminEventsUpdate",
+			SubscriptionTargetUpdate::AdminEntryTypesUpdate(_) => "AdminEntryTypesUpdate",
+			SubscriptionTargetUpdate::AdminEntryTypesEventsUpdate(_) => "AdminEntryTypesEventsUpdate",
+			SubscriptionTargetUpdate::AdminPermissionGroupsUpdate(_) => "AdminPermissionGroupsUpdate",
+			SubscriptionTargetUpdate::AdminUserUpdate(_) => "AdminUserUpdate",
+			SubscriptionTargetUpdate::AdminUserProfileUpdate(_, _) => "AdminUserProfileUpdate",
+			SubscriptionTargetUpdate::AdminUserSessionsRevoke(_) => "AdminUserSessionsRevoke",
+			SubscriptionTargetUpdate::AdminEventEditorsUpdate(_) => "AdminEventEditorsUpdate",
+			SubscriptionTargetUpdate::AdminUserPermissionGroupsUpdate(_) => "AdminUserPermissionGroupsUpdate",
+			SubscriptionTargetUpdate::AdminUserEventPermissionsUpdate(_) => "AdminUserEventPermissionsUpdate",
+			SubscriptionTargetUpdate::AdminEventLogTabsUpdate(_) => "AdminEventLogTabsUpdate",
+			SubscriptionTargetUpdate::AdminApplicationsUpdate(_) => "AdminApplicationsUpdate",
+			SubscriptionTargetUpdate::AdminInfoPagesUpdate(_) => "AdminInfoPagesUpdate",
+			SubscriptionTargetUpdate::AdminEventAccessRequestsUpdate(_) => "AdminEventAccessRequestsUpdate",
+			SubscriptionTargetUpdate::AdminEventWebhooksUpdate(_) => "AdminEventWebhooksUpdate",
+			SubscriptionTargetUpdate::AdminEventShareLinksUpdate(_) => "AdminEventShareLinksUpdate",
+			SubscriptionTargetUpdate::AdminEventCustomFieldsUpdate(_) => "AdminEventCustomFieldsUpdate",
+			SubscriptionTargetUpdate::UserNotificationsUpdate(_) => "UserNotificationsUpdate",
+		},
+		FromClientMessage::RegistrationRequest(_) => "RegistrationRequest",
+		FromClientMessage::UpdateProfile(_) => "UpdateProfile",
+		FromClientMessage::UpdateUsername(_) => "UpdateUsername",
+		FromClientMessage::UpdateAvatar(_) => "UpdateAvatar",
+		FromClientMessage::UpdateNotificationPreferences(_) => "UpdateNotificationPreferences",
+		FromClientMessage::UpdateTimeDisplay(_) => "UpdateTimeDisplay",
+		FromClientMessage::UpdateHotkeys(_) => "UpdateHotkeys",
+		FromClientMessage::UpdateFilterPreset(_) => "UpdateFilterPreset",
+		FromClientMessage::RequestEventAccess(_) => "RequestEventAccess",
+		FromClientMessage::KeepAlive => "KeepAlive",
+		FromClientMessage::EventLogEntryHistory(_) => "EventLogEntryHistory",
+		FromClientMessage::RequestTabEntries(_, _) => "RequestTabEntries",
+		FromClientMessage::SetGuidedTourCompleted(_) => "SetGuidedTourCompleted",
+		FromClientMessage::ImportEventLog(_, _) => "ImportEventLog",
+		FromClientMessage::RequestSessions => "RequestSessions",
+		FromClientMessage::RevokeSession(_) => "RevokeSession",
+		FromClientMessage::RevokeOtherSessions => "RevokeOtherSessions",
+		FromClientMessage::StartImpersonation(_) => "StartImpersonation",
+	}
+}
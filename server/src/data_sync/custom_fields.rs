@@ -0,0 +1,86 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::models::{EventCustomField, EventLogCustomFieldValue};
+use crate::schema::{event_custom_fields, event_log_custom_field_values};
+use diesel::prelude::*;
+use std::collections::HashMap;
+use stream_log_shared::messages::custom_fields::CustomField;
+
+/// Loads an event's custom fields, sorted by name
+pub fn event_custom_fields(db_connection: &mut PgConnection, event_id: &str) -> QueryResult<Vec<CustomField>> {
+	let fields: Vec<EventCustomField> = event_custom_fields::table
+		.filter(event_custom_fields::event.eq(event_id))
+		.order(event_custom_fields::name.asc())
+		.load(db_connection)?;
+	Ok(fields.into_iter().map(|field| field.into()).collect())
+}
+
+/// Loads the custom field values of a log entry, keyed by field ID
+pub fn entry_custom_field_values(
+	db_connection: &mut PgConnection,
+	log_entry_id: &str,
+) -> QueryResult<HashMap<String, String>> {
+	let values: Vec<EventLogCustomFieldValue> = event_log_custom_field_values::table
+		.filter(event_log_custom_field_values::log_entry.eq(log_entry_id))
+		.load(db_connection)?;
+	Ok(values.into_iter().map(|value| (value.field, value.value)).collect())
+}
+
+/// Loads the custom field values of many log entries at once. Maps entry IDs to each entry's values, keyed by field
+/// ID. Entries with no values are left out.
+pub fn custom_field_values_by_entry(
+	db_connection: &mut PgConnection,
+	log_entry_ids: &[String],
+) -> QueryResult<HashMap<String, HashMap<String, String>>> {
+	let values: Vec<EventLogCustomFieldValue> = event_log_custom_field_values::table
+		.filter(event_log_custom_field_values::log_entry.eq_any(log_entry_ids))
+		.load(db_connection)?;
+	let mut values_by_entry: HashMap<String, HashMap<String, String>> = HashMap::new();
+	for value in values {
+		values_by_entry
+			.entry(value.log_entry)
+			.or_default()
+			.insert(value.field, value.value);
+	}
+	Ok(values_by_entry)
+}
+
+/// Replaces all of a log entry's custom field values. Empty values are removed rather than stored.
+pub fn set_entry_custom_field_values(
+	db_connection: &mut PgConnection,
+	log_entry_id: &str,
+	values: &HashMap<String, String>,
+) -> QueryResult<()> {
+	let new_values: Vec<EventLogCustomFieldValue> = values
+		.iter()
+		.filter(|(_, value)| !value.is_empty())
+		.map(|(field, value)| EventLogCustomFieldValue {
+			log_entry: log_entry_id.to_string(),
+			field: field.clone(),
+			value: value.clone(),
+		})
+		.collect();
+	diesel::delete(event_log_custom_field_values::table)
+		.filter(event_log_custom_field_values::log_entry.eq(log_entry_id))
+		.execute(db_connection)?;
+	diesel::insert_into(event_log_custom_field_values::table)
+		.values(new_values)
+		.execute(db_connection)?;
+	Ok(())
+}
+
+/// Checks custom field values sent by a client against the event's fields. Returns a description of the problem if
+/// any value is for a field the event doesn't have or isn't valid for its field.
+pub fn check_custom_field_values(fields: &[CustomField], values: &HashMap<String, String>) -> Result<(), String> {
+	for (field_id, value) in values.iter() {
+		let Some(field) = fields.iter().find(|field| field.id == *field_id) else {
+			return Err(String::from("A value was entered for a field the event doesn't have"));
+		};
+		field.check_value(value)?;
+	}
+	Ok(())
+}
@@ -0,0 +1,170 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::{run_db_operation, HandlerError};
+use crate::models::{
+	Application, EventLogEntry as EventLogEntryDb, EventLogHistoryEntry, Permission, Tag as TagDb, User,
+};
+use crate::schema::{applications, event_log, event_log_history, event_log_history_tags, tags, users};
+use async_std::channel::Sender;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::{EndTimeData, EntryChangeSource, EventLogEntry, EventLogEntryRevision};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::user::PublicUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn handle_entry_history_request(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	event_permission_cache: &HashMap<Event, Option<Permission>>,
+	entry_id: String,
+) -> Result<(), HandlerError> {
+	let entry: Option<EventLogEntryDb> = run_db_operation(
+		&db_connection_pool,
+		"loading an entry for its history",
+		|db_connection| event_log::table.find(&entry_id).first(db_connection).optional(),
+	)
+	.await?;
+	let Some(entry) = entry else {
+		return Err(HandlerError::InvalidData(String::from("The entry doesn't exist.")));
+	};
+
+	// Users can see the history for entries in any event they can see
+	let can_view_event = event_permission_cache
+		.iter()
+		.any(|(event, permission)| event.id == entry.event && permission.is_some());
+	if !can_view_event {
+		return Err(HandlerError::NotAllowed);
+	}
+
+	let revisions: Vec<EventLogEntryRevision> =
+		run_db_operation(&db_connection_pool, "loading entry history", |db_connection| {
+			db_connection.transaction(|db_connection| {
+				let history: Vec<EventLogHistoryEntry> = event_log_history::table
+					.filter(event_log_history::log_entry.eq(&entry_id))
+					.order(event_log_history::edit_time.asc())
+					.load(db_connection)?;
+				let history_ids: Vec<String> = history.iter().map(|revision| revision.id.clone()).collect();
+				let history_tags: Vec<(String, TagDb)> = event_log_history_tags::table
+					.inner_join(tags::table)
+					.filter(event_log_history_tags::history_log_entry.eq_any(&history_ids))
+					.select((event_log_history_tags::history_log_entry, tags::all_columns))
+					.load(db_connection)?;
+
+				let user_ids: Vec<String> = history
+					.iter()
+					.flat_map(|revision| [revision.edit_user.clone(), revision.editor.clone()])
+					.flatten()
+					.collect();
+				let users: Vec<User> = users::table.filter(users::id.eq_any(&user_ids)).load(db_connection)?;
+				let application_ids: Vec<String> = history
+					.iter()
+					.filter_map(|revision| revision.edit_application.clone())
+					.collect();
+				let applications: Vec<Application> = applications::table
+					.filter(applications::id.eq_any(&application_ids))
+					.load(db_connection)?;
+
+				let mut tags_by_revision: HashMap<String, Vec<TagDb>> = HashMap::new();
+				for (revision_id, tag) in history_tags {
+					tags_by_revision.entry(revision_id).or_default().push(tag);
+				}
+				let users_by_id: HashMap<String, PublicUserData> =
+					users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
+				let application_names_by_id: HashMap<String, String> = applications
+					.into_iter()
+					.map(|application| (application.id, application.name))
+					.collect();
+
+				let revisions = history
+					.into_iter()
+					.map(|revision| {
+						let changed_by = match (&revision.edit_user, &revision.edit_application) {
+							(Some(user_id), _) => users_by_id
+								.get(user_id)
+								.cloned()
+								.map(EntryChangeSource::User)
+								.unwrap_or(EntryChangeSource::Unknown),
+							(None, Some(application_id)) => application_names_by_id
+								.get(application_id)
+								.cloned()
+								.map(EntryChangeSource::Application)
+								.unwrap_or(EntryChangeSource::Unknown),
+							(None, None) => EntryChangeSource::Unknown,
+						};
+						let tags = tags_by_revision.remove(&revision.id).unwrap_or_default();
+						let editor = revision
+							.editor
+							.as_ref()
+							.and_then(|editor_id| users_by_id.get(editor_id))
+							.cloned();
+						let edit_time = revision.edit_time;
+						let deleted = revision.deleted_by.is_some();
+						let entry = revision_entry(revision, tags, editor);
+
+						EventLogEntryRevision {
+							edit_time,
+							changed_by,
+							entry,
+							deleted,
+						}
+					})
+					.collect();
+				Ok(revisions)
+			})
+		})
+		.await?;
+
+	let message = FromServerMessage::EventLogEntryHistory(entry_id, revisions);
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+/// Builds the entry as it was at a revision in its history. History doesn't keep custom field values, so the entry has
+/// none.
+pub fn revision_entry(
+	revision: EventLogHistoryEntry,
+	tags: Vec<TagDb>,
+	editor: Option<PublicUserData>,
+) -> EventLogEntry {
+	let end_time = match (revision.end_time, revision.end_time_incomplete) {
+		(Some(time), _) => EndTimeData::Time(time),
+		(None, true) => EndTimeData::NotEntered,
+		(None, false) => EndTimeData::NoTime,
+	};
+	EventLogEntry {
+		id: revision.log_entry,
+		start_time: Some(revision.start_time),
+		end_time,
+		entry_type: revision.entry_type,
+		description: revision.description,
+		media_links: revision.media_links.into_iter().flatten().collect(),
+		submitter_or_winner: revision.submitter_or_winner,
+		tags: tags.into_iter().map(|tag| tag.into()).collect(),
+		notes: revision.notes,
+		editor,
+		video_link: revision.video_link,
+		parent: revision.parent,
+		created_at: revision.created_at,
+		created_by: None,
+		manual_sort_key: revision.manual_sort_key,
+		video_processing_state: revision.video_processing_state.into(),
+		video_errors: revision.video_errors,
+		poster_moment: revision.poster_moment,
+		video_edit_state: revision.video_edit_state.into(),
+		missing_giveaway_information: revision.missing_giveaway_information,
+		dead_links: Vec::new(),
+		entry_number: None,
+		custom_fields: HashMap::new(),
+		history_id: Some(revision.id),
+	}
+}
@@ -0,0 +1,102 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::user::load_event_permission;
+use super::{run_db_operation, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, EventAccessRequest as EventAccessRequestDb};
+use crate::schema::{event_access_requests, events};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use chrono::prelude::*;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::{AdminEventAccessRequestData, EventAccessRequest};
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// The outcome of recording an access request in the database
+enum RecordedRequest {
+	NoEvent,
+	AlreadyAllowed,
+	/// The request was recorded. Contains the event and whether the request is new (as opposed to the user having
+	/// already requested access).
+	Recorded(EventDb, bool),
+}
+
+pub async fn handle_event_access_request(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	event_id: String,
+) -> Result<(), HandlerError> {
+	let requested_at = Utc::now();
+	let recorded_request = run_db_operation(
+		&db_connection_pool,
+		"recording an event access request",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let event: Option<EventDb> = events::table.find(&event_id).first(db_connection).optional()?;
+				let Some(event) = event else {
+					return Ok(RecordedRequest::NoEvent);
+				};
+
+				if load_event_permission(db_connection, &user.id, &event_id)?.is_some() {
+					return Ok(RecordedRequest::AlreadyAllowed);
+				}
+
+				let request = EventAccessRequestDb {
+					user_id: user.id.clone(),
+					event: event_id.clone(),
+					requested_at,
+				};
+				let added_requests = diesel::insert_into(event_access_requests::table)
+					.values(&request)
+					.on_conflict_do_nothing()
+					.execute(db_connection)?;
+				Ok(RecordedRequest::Recorded(event, added_requests > 0))
+			})
+		},
+	)
+	.await?;
+
+	let (event, new_request) = match recorded_request {
+		RecordedRequest::NoEvent => return Err(HandlerError::InvalidData(String::from("The event doesn't exist."))),
+		RecordedRequest::AlreadyAllowed => {
+			return Err(HandlerError::InvalidData(String::from(
+				"You already have access to the event.",
+			)))
+		}
+		RecordedRequest::Recorded(event, new_request) => (event, new_request),
+	};
+
+	if new_request {
+		let request = EventAccessRequest {
+			user: user.clone().into(),
+			event: event.into(),
+			requested_at,
+		};
+		let admin_message =
+			SubscriptionData::AdminEventAccessRequestsUpdate(AdminEventAccessRequestData::NewRequest(request));
+		let send_result = subscription_manager
+			.lock()
+			.await
+			.broadcast_admin_event_access_requests_message(admin_message)
+			.await;
+		if let Err(error) = send_result {
+			tide::log::error!("Failed to broadcast new event access request: {}", error);
+		}
+	}
+
+	let message = FromServerMessage::EventAccessRequested(event_id);
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,616 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::subscriptions::events::video_edit_state_rule_for_tags;
+use super::{run_db_operation, HandlerError, SubscriptionManager};
+use crate::models::{
+	EditSource, EntryType as EntryTypeDb, Event as EventDb, EventLogEntry as EventLogEntryDb, EventLogHistoryEntry,
+	EventLogHistoryTag, EventLogTag, Permission, Tag as TagDb, VideoEditState, VideoProcessingState,
+};
+use crate::plugins::PluginRegistry;
+use crate::recurring_entries::truncate_to_minute;
+use crate::schema::{
+	available_entry_types_for_event, entry_types, event_log, event_log_history, event_log_history_tags, event_log_tags,
+	events, tags,
+};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_log_import::{EventLogImportResult, EventLogImportRowError};
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// The most entries that can be imported from one file. All of the entries are created in one transaction, so this
+/// keeps a single import from holding the event's log for too long.
+const MAX_IMPORT_ROWS: usize = 5000;
+
+/// An entry read from a row of the imported file
+struct ImportedEntry {
+	/// The row's value in the "Number" column, which other rows can refer to as their parent
+	number: Option<String>,
+	/// The index in the list of imported entries of this entry's parent
+	parent_index: Option<usize>,
+	start_time: DateTime<Utc>,
+	end_time: Option<DateTime<Utc>>,
+	end_time_incomplete: bool,
+	entry_type: Option<String>,
+	description: String,
+	submitter_or_winner: String,
+	media_links: Vec<String>,
+	tags: Vec<String>,
+	notes: String,
+	poster_moment: bool,
+	missing_giveaway_information: bool,
+}
+
+/// The columns of the imported file that are used for the import. Other columns, like the ones for video data, are
+/// ignored so that a file exported from another event can be imported as-is.
+struct ImportColumns {
+	number: Option<usize>,
+	parent_number: Option<usize>,
+	start: Option<usize>,
+	start_time: Option<usize>,
+	end: Option<usize>,
+	end_time: Option<usize>,
+	entry_type: Option<usize>,
+	description: Option<usize>,
+	submitter_or_winner: Option<usize>,
+	media_links: Option<usize>,
+	tags: Option<usize>,
+	notes: Option<usize>,
+	poster_moment: Option<usize>,
+	missing_giveaway_information: Option<usize>,
+}
+
+impl ImportColumns {
+	fn from_headers(headers: &[String]) -> Self {
+		let column = |name: &str| {
+			headers
+				.iter()
+				.position(|header| header.trim().eq_ignore_ascii_case(name))
+		};
+		Self {
+			number: column("Number"),
+			parent_number: column("Parent Number"),
+			start: column("Start"),
+			start_time: column("Start Time"),
+			end: column("End"),
+			end_time: column("End Time"),
+			entry_type: column("Type"),
+			description: column("Description"),
+			submitter_or_winner: column("Submitter/Winner"),
+			media_links: column("Media Links"),
+			tags: column("Tags"),
+			notes: column("Notes"),
+			poster_moment: column("Poster Moment"),
+			missing_giveaway_information: column("Missing Giveaway Information"),
+		}
+	}
+}
+
+pub struct EventLogImportArgs<'a> {
+	pub db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	pub conn_update_tx: &'a Sender<ConnectionUpdate>,
+	pub subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	pub plugins: &'a PluginRegistry,
+	pub event_permission_cache: &'a HashMap<Event, Option<Permission>>,
+	pub user: &'a SelfUserData,
+	pub event_id: String,
+	/// The contents of the imported CSV file
+	pub contents: String,
+}
+
+pub async fn handle_event_log_import_request(args: EventLogImportArgs<'_>) -> Result<(), HandlerError> {
+	let EventLogImportArgs {
+		db_connection_pool,
+		conn_update_tx,
+		subscription_manager,
+		plugins,
+		event_permission_cache,
+		user,
+		event_id,
+		contents,
+	} = args;
+
+	// Subscribing to the event adds it to the permission cache, so this also checks that the user loaded the event
+	let Some((event, permission)) = event_permission_cache.iter().find(|(event, _)| event.id == event_id) else {
+		return Err(HandlerError::NotAllowed);
+	};
+	if *permission != Some(Permission::Supervisor) && !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+
+	let event_db: EventDb = run_db_operation(&db_connection_pool, "loading an event for an import", |db_connection| {
+		events::table.find(&event.id).first(db_connection)
+	})
+	.await?;
+	if event_db.archived {
+		return Err(HandlerError::InvalidData(String::from(
+			"The event is archived, so its log can't be changed.",
+		)));
+	}
+	let current_event: Event = event_db.into();
+
+	let (event_entry_types, event_tags): (Vec<EntryTypeDb>, Vec<TagDb>) = run_db_operation(
+		&db_connection_pool,
+		"loading entry types and tags for an import",
+		|db_connection| {
+			let entry_types: Vec<EntryTypeDb> = entry_types::table
+				.filter(
+					entry_types::id.eq_any(
+						available_entry_types_for_event::table
+							.filter(available_entry_types_for_event::event_id.eq(&event.id))
+							.select(available_entry_types_for_event::entry_type),
+					),
+				)
+				.load(db_connection)?;
+			let tags: Vec<TagDb> = tags::table
+				.filter(tags::for_event.eq(&event.id).and(tags::deleted.eq(false)))
+				.load(db_connection)?;
+			Ok((entry_types, tags))
+		},
+	)
+	.await?;
+
+	let (imported_entries, row_errors) = read_entries(&contents, &current_event, &event_entry_types, &event_tags);
+	if !row_errors.is_empty() || imported_entries.is_empty() {
+		let result = EventLogImportResult {
+			event_id,
+			imported_count: 0,
+			row_errors,
+		};
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(
+				FromServerMessage::EventLogImportResult(result),
+			)))
+			.await?;
+		return Ok(());
+	}
+
+	let created_entries: Vec<EventLogEntry> =
+		run_db_operation(&db_connection_pool, "importing event log entries", |db_connection| {
+			db_connection.transaction(|db_connection| create_entries(db_connection, &event.id, user, &imported_entries))
+		})
+		.await?;
+
+	let result = EventLogImportResult {
+		event_id,
+		imported_count: created_entries.len(),
+		row_errors: Vec::new(),
+	};
+
+	{
+		let subscription_manager = subscription_manager.lock().await;
+		for entry in created_entries {
+			plugins.entry_created(&current_event, &entry, user);
+			let message = SubscriptionData::EventUpdate(
+				current_event.clone(),
+				Box::new(EventSubscriptionData::UpdateLogEntry(entry, Some(user.clone().into()))),
+			);
+			let broadcast_result = subscription_manager
+				.broadcast_event_message(&current_event.id, message)
+				.await;
+			if let Err(error) = broadcast_result {
+				tide::log::error!("Error occurred broadcasting an imported entry: {}", error);
+			}
+		}
+	}
+
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(
+			FromServerMessage::EventLogImportResult(result),
+		)))
+		.await?;
+
+	Ok(())
+}
+
+/// Reads the entries from the imported file, along with any problems with its rows
+fn read_entries(
+	contents: &str,
+	event: &Event,
+	event_entry_types: &[EntryTypeDb],
+	event_tags: &[TagDb],
+) -> (Vec<ImportedEntry>, Vec<EventLogImportRowError>) {
+	let mut rows = parse_csv(contents).into_iter();
+	let mut row_errors: Vec<EventLogImportRowError> = Vec::new();
+
+	let Some(headers) = rows.next() else {
+		row_errors.push(EventLogImportRowError {
+			row: 1,
+			error: String::from("The file is empty."),
+		});
+		return (Vec::new(), row_errors);
+	};
+	let columns = ImportColumns::from_headers(&headers);
+	if columns.start.is_none() && columns.start_time.is_none() {
+		row_errors.push(EventLogImportRowError {
+			row: 1,
+			error: String::from("The file needs a \"Start\" or \"Start Time\" column."),
+		});
+		return (Vec::new(), row_errors);
+	}
+
+	let mut entries: Vec<ImportedEntry> = Vec::new();
+	for (row_index, row) in rows.enumerate() {
+		// Rows are counted from 1, and the header is the first row
+		let row_number = row_index + 2;
+		if row.iter().all(|field| field.trim().is_empty()) {
+			continue;
+		}
+		if entries.len() >= MAX_IMPORT_ROWS {
+			row_errors.push(EventLogImportRowError {
+				row: row_number,
+				error: format!("No more than {} entries can be imported at once.", MAX_IMPORT_ROWS),
+			});
+			break;
+		}
+
+		let mut add_error = |error: String| row_errors.push(EventLogImportRowError { row: row_number, error });
+		let field = |column: Option<usize>| {
+			column
+				.and_then(|column| row.get(column))
+				.map(|value| value.trim())
+				.unwrap_or_default()
+		};
+
+		let start_time = match read_time(field(columns.start), field(columns.start_time), event.start_time) {
+			Ok(Some(time)) => time,
+			Ok(None) => {
+				add_error(String::from("The entry has no start time."));
+				continue;
+			}
+			Err(error) => {
+				add_error(format!("The start time is invalid: {}", error));
+				continue;
+			}
+		};
+
+		let end = field(columns.end);
+		let (end_time, end_time_incomplete) = if end == "—" || end == "-" {
+			(None, false)
+		} else {
+			match read_time(end, field(columns.end_time), event.start_time) {
+				Ok(Some(time)) if time < start_time => {
+					add_error(String::from("The end time is before the start time."));
+					continue;
+				}
+				Ok(Some(time)) => (Some(time), false),
+				Ok(None) => (None, true),
+				Err(error) => {
+					add_error(format!("The end time is invalid: {}", error));
+					continue;
+				}
+			}
+		};
+
+		let entry_type_name = field(columns.entry_type);
+		let entry_type = if entry_type_name.is_empty() {
+			None
+		} else {
+			match event_entry_types
+				.iter()
+				.find(|entry_type| entry_type.name.eq_ignore_ascii_case(entry_type_name))
+			{
+				Some(entry_type) => Some(entry_type.id.clone()),
+				None => {
+					add_error(format!(
+						"The entry type \"{}\" isn't available for this event.",
+						entry_type_name
+					));
+					continue;
+				}
+			}
+		};
+
+		let description = field(columns.description).to_string();
+		if let Err(error) = event.check_description_length(&description) {
+			add_error(error);
+			continue;
+		}
+
+		let mut entry_tags: Vec<String> = Vec::new();
+		let mut missing_tag = None;
+		for tag_name in field(columns.tags).split(',') {
+			let tag_name = tag_name.trim();
+			if tag_name.is_empty() {
+				continue;
+			}
+			match event_tags.iter().find(|tag| tag.tag.eq_ignore_ascii_case(tag_name)) {
+				Some(tag) => {
+					if !entry_tags.contains(&tag.id) {
+						entry_tags.push(tag.id.clone());
+					}
+				}
+				None => {
+					missing_tag = Some(tag_name);
+					break;
+				}
+			}
+		}
+		if let Some(tag_name) = missing_tag {
+			add_error(format!("The tag \"{}\" doesn't exist in this event.", tag_name));
+			continue;
+		}
+
+		let Some(poster_moment) = read_yes_no(field(columns.poster_moment)) else {
+			add_error(String::from("The poster moment column must be \"Yes\" or \"No\"."));
+			continue;
+		};
+		let Some(missing_giveaway_information) = read_yes_no(field(columns.missing_giveaway_information)) else {
+			add_error(String::from(
+				"The missing giveaway information column must be \"Yes\" or \"No\".",
+			));
+			continue;
+		};
+
+		let parent_number = field(columns.parent_number);
+		let parent_index = if parent_number.is_empty() {
+			None
+		} else {
+			// Parents must come before their children, which is how the export writes them
+			match entries
+				.iter()
+				.position(|entry| entry.number.as_deref() == Some(parent_number))
+			{
+				Some(index) => Some(index),
+				None => {
+					add_error(format!(
+						"The parent entry number {} doesn't match an earlier row.",
+						parent_number
+					));
+					continue;
+				}
+			}
+		};
+
+		let number = field(columns.number);
+		let number = if number.is_empty() {
+			None
+		} else {
+			Some(number.to_string())
+		};
+
+		entries.push(ImportedEntry {
+			number,
+			parent_index,
+			start_time,
+			end_time,
+			end_time_incomplete,
+			entry_type,
+			description,
+			submitter_or_winner: field(columns.submitter_or_winner).to_string(),
+			media_links: field(columns.media_links)
+				.split_whitespace()
+				.map(String::from)
+				.collect(),
+			tags: entry_tags,
+			notes: field(columns.notes).to_string(),
+			poster_moment,
+			missing_giveaway_information,
+		});
+	}
+
+	(entries, row_errors)
+}
+
+/// Adds the imported entries to the event log, recording each in the entry history
+fn create_entries(
+	db_connection: &mut PgConnection,
+	event_id: &str,
+	user: &SelfUserData,
+	imported_entries: &[ImportedEntry],
+) -> QueryResult<Vec<EventLogEntry>> {
+	// Lock the event while we pick the new entries' numbers so that entries created at the same time can't end up
+	// with the same number.
+	events::table
+		.find(event_id)
+		.for_update()
+		.select(events::id)
+		.first::<String>(db_connection)?;
+	let highest_entry_number: Option<i32> = event_log::table
+		.filter(event_log::event.eq(event_id))
+		.select(max(event_log::entry_number))
+		.first(db_connection)?;
+	let mut entry_number = highest_entry_number.unwrap_or(0);
+
+	let create_time = Utc::now();
+	let mut entry_ids: Vec<String> = Vec::with_capacity(imported_entries.len());
+	let mut created_entries: Vec<EventLogEntry> = Vec::with_capacity(imported_entries.len());
+	for imported_entry in imported_entries.iter() {
+		entry_number += 1;
+		let mut db_entry = EventLogEntryDb {
+			id: cuid2::create_id(),
+			event: event_id.to_string(),
+			start_time: imported_entry.start_time,
+			end_time: imported_entry.end_time,
+			entry_type: imported_entry.entry_type.clone(),
+			description: imported_entry.description.clone(),
+			media_links: imported_entry
+				.media_links
+				.iter()
+				.map(|link| Some(link.clone()))
+				.collect(),
+			submitter_or_winner: imported_entry.submitter_or_winner.clone(),
+			notes: imported_entry.notes.clone(),
+			editor: None,
+			video_link: None,
+			parent: imported_entry
+				.parent_index
+				.and_then(|index| entry_ids.get(index))
+				.cloned(),
+			deleted_by: None,
+			created_at: create_time,
+			manual_sort_key: None,
+			video_processing_state: VideoProcessingState::default(),
+			video_errors: String::new(),
+			poster_moment: imported_entry.poster_moment,
+			video_edit_state: VideoEditState::NoVideo,
+			missing_giveaway_information: imported_entry.missing_giveaway_information,
+			end_time_incomplete: imported_entry.end_time_incomplete,
+			created_by: Some(user.id.clone()),
+			dead_links: Vec::new(),
+			links_checked_at: None,
+			entry_number,
+			latest_history_id: None,
+		};
+		let mut history_entry =
+			EventLogHistoryEntry::new_from_event_log_entry(&db_entry, create_time, EditSource::User(user.id.clone()));
+		db_entry.latest_history_id = Some(history_entry.id.clone());
+
+		let rule = video_edit_state_rule_for_tags(db_connection, event_id, &imported_entry.tags)?;
+		if let Some(rule) = rule {
+			db_entry.video_edit_state = rule.video_edit_state;
+			history_entry.video_edit_state = rule.video_edit_state;
+			history_entry.video_edit_state_rule_tag = Some(rule.tag);
+		}
+
+		let db_tags: Vec<EventLogTag> = imported_entry
+			.tags
+			.iter()
+			.map(|tag| EventLogTag {
+				tag: tag.clone(),
+				log_entry: db_entry.id.clone(),
+			})
+			.collect();
+		let history_tags: Vec<EventLogHistoryTag> = imported_entry
+			.tags
+			.iter()
+			.map(|tag| EventLogHistoryTag {
+				tag: tag.clone(),
+				history_log_entry: history_entry.id.clone(),
+			})
+			.collect();
+
+		let new_row: EventLogEntryDb = diesel::insert_into(event_log::table)
+			.values(&db_entry)
+			.get_result(db_connection)?;
+		diesel::insert_into(event_log_tags::table)
+			.values(&db_tags)
+			.execute(db_connection)?;
+		diesel::insert_into(event_log_history::table)
+			.values(&history_entry)
+			.execute(db_connection)?;
+		diesel::insert_into(event_log_history_tags::table)
+			.values(&history_tags)
+			.execute(db_connection)?;
+		let entry_tags: Vec<TagDb> = tags::table
+			.filter(tags::id.eq_any(&imported_entry.tags))
+			.load(db_connection)?;
+
+		entry_ids.push(new_row.id.clone());
+		created_entries.push(new_row.into_sync_entry(
+			entry_tags.into_iter().map(|tag| tag.into()).collect(),
+			None,
+			Some(user.clone().into()),
+			HashMap::new(),
+		));
+	}
+
+	Ok(created_entries)
+}
+
+/// Reads a time from the relative time column (as shown in the log) or, if that's empty, the full timestamp column.
+/// Returns no time if both are empty.
+fn read_time(relative: &str, timestamp: &str, event_start: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+	if !relative.is_empty() {
+		let duration = parse_relative_time(relative)
+			.ok_or_else(|| format!("\"{}\" isn't a time like 1:23 or 1:23:45.", relative))?;
+		return Ok(Some(truncate_to_minute(event_start + duration)));
+	}
+	if !timestamp.is_empty() {
+		let time = DateTime::parse_from_rfc3339(timestamp)
+			.map_err(|_| format!("\"{}\" isn't an ISO 8601 timestamp.", timestamp))?;
+		return Ok(Some(truncate_to_minute(time.with_timezone(&Utc))));
+	}
+	Ok(None)
+}
+
+/// Parses a time relative to the event start in the format used by the log, which is hours and minutes and optionally
+/// seconds separated by colons
+fn parse_relative_time(value: &str) -> Option<TimeDelta> {
+	let (negative, value) = match value.strip_prefix('-') {
+		Some(value) => (true, value),
+		None => (false, value),
+	};
+	let parts: Vec<&str> = value.split(':').collect();
+	if parts.len() < 2 || parts.len() > 3 {
+		return None;
+	}
+	let hours: i64 = parts[0].parse().ok()?;
+	let minutes: i64 = parts[1].parse().ok()?;
+	let seconds: i64 = match parts.get(2) {
+		Some(seconds) => seconds.parse().ok()?,
+		None => 0,
+	};
+	if hours < 0 || !(0..60).contains(&minutes) || !(0..60).contains(&seconds) {
+		return None;
+	}
+	let duration = TimeDelta::try_hours(hours)? + TimeDelta::minutes(minutes) + TimeDelta::seconds(seconds);
+	Some(if negative { -duration } else { duration })
+}
+
+/// Reads a yes/no column value. Empty values are treated as no.
+fn read_yes_no(value: &str) -> Option<bool> {
+	match value.to_lowercase().as_str() {
+		"" | "no" | "n" | "false" => Some(false),
+		"yes" | "y" | "true" => Some(true),
+		_ => None,
+	}
+}
+
+/// Splits CSV file contents into rows of fields. Quoted fields may contain commas, line breaks, and doubled quotes.
+fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+	let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+	let mut rows: Vec<Vec<String>> = Vec::new();
+	let mut row: Vec<String> = Vec::new();
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut chars = contents.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		if in_quotes {
+			if c == '"' {
+				if chars.peek() == Some(&'"') {
+					chars.next();
+					field.push('"');
+				} else {
+					in_quotes = false;
+				}
+			} else {
+				field.push(c);
+			}
+			continue;
+		}
+		match c {
+			'"' => in_quotes = true,
+			',' => row.push(std::mem::take(&mut field)),
+			'\r' | '\n' => {
+				if c == '\r' && chars.peek() == Some(&'\n') {
+					chars.next();
+				}
+				row.push(std::mem::take(&mut field));
+				rows.push(std::mem::take(&mut row));
+			}
+			_ => field.push(c),
+		}
+	}
+	if !field.is_empty() || !row.is_empty() {
+		row.push(field);
+		rows.push(row);
+	}
+
+	rows
+}
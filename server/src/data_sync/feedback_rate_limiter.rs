@@ -0,0 +1,35 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+use stream_log_shared::messages::feedback::FEEDBACK_INTERVAL_SECONDS;
+
+/// Tracks when users last sent feedback for each event. Since feedback is anonymous, who sent it isn't stored with
+/// the feedback, so this is kept in memory only.
+#[derive(Default)]
+pub struct FeedbackRateLimiter {
+	/// Maps (user ID, event ID) pairs to when that user last sent feedback for that event
+	last_feedback_times: HashMap<(String, String), DateTime<Utc>>,
+}
+
+impl FeedbackRateLimiter {
+	/// Records that the user is sending feedback for the event if they're allowed to send feedback now. If they've
+	/// sent feedback too recently, returns how long they still need to wait instead.
+	pub fn try_record_feedback(&mut self, user_id: &str, event_id: &str) -> Result<(), TimeDelta> {
+		let now = Utc::now();
+		let interval = TimeDelta::seconds(FEEDBACK_INTERVAL_SECONDS);
+		self.last_feedback_times
+			.retain(|_, last_feedback_time| now - *last_feedback_time < interval);
+
+		let key = (user_id.to_string(), event_id.to_string());
+		if let Some(last_feedback_time) = self.last_feedback_times.get(&key) {
+			return Err(interval - (now - *last_feedback_time));
+		}
+		self.last_feedback_times.insert(key, now);
+		Ok(())
+	}
+}
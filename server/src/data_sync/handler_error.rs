@@ -0,0 +1,207 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::HandleConnectionError;
+use async_std::channel::{SendError, Sender};
+use async_std::task;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use r2d2::Error as R2D2Error;
+use std::fmt;
+use std::io;
+use std::time::Duration;
+use stream_log_shared::messages::{FromServerMessage, RequestFailure};
+
+/// Errors that can occur while handling an update message from a client
+pub enum HandlerError {
+	/// A database connection couldn't be retrieved from the pool. Contains a description of what was being done when
+	/// the error occurred.
+	LostDbConnection(&'static str, R2D2Error),
+	/// A database operation failed. Contains a description of what was being done when the error occurred.
+	Database(&'static str, DieselError),
+	/// Reading or writing a file failed. Contains a description of what was being done when the error occurred.
+	Storage(&'static str, io::Error),
+	/// The user isn't allowed to perform the requested update
+	NotAllowed,
+	/// The update contained data that can't be accepted; contains a description of the problem for the user
+	InvalidData(String),
+	/// Communicating with the client connection failed
+	Connection(HandleConnectionError),
+	/// The server is running in read-only mode and can't accept the update
+	ReadOnly,
+	/// The client sent messages faster than its connection is allowed to, so the update was dropped
+	RateLimited,
+	/// The connection is impersonating another user and can't make changes
+	Impersonating,
+}
+
+impl HandlerError {
+	/// Whether the error might be temporary, such that the same operation might succeed if tried again
+	pub fn is_retryable(&self) -> bool {
+		match self {
+			Self::LostDbConnection(_, _) => true,
+			Self::Database(_, DieselError::DatabaseError(kind, _)) => matches!(
+				kind,
+				DatabaseErrorKind::SerializationFailure
+					| DatabaseErrorKind::ClosedConnection
+					| DatabaseErrorKind::UnableToSendCommand
+			),
+			_ => false,
+		}
+	}
+
+	/// If the error means the server refused the requested change (rather than failing to make it), gets the reason
+	/// the change was refused
+	pub fn rejection_reason(&self) -> Option<String> {
+		match self {
+			Self::NotAllowed => Some(String::from("The user doesn't have permission to make this change.")),
+			Self::InvalidData(description) => Some(description.clone()),
+			Self::ReadOnly => Some(String::from("The server is read-only.")),
+			Self::Impersonating => Some(String::from("The update was sent while impersonating another user.")),
+			Self::Database(_, DieselError::RollbackTransaction) => {
+				Some(String::from("The requested change isn't valid."))
+			}
+			_ => None,
+		}
+	}
+
+	/// Gets the failure information to send to the client that made the request
+	pub fn client_failure(&self) -> RequestFailure {
+		if self.is_retryable() {
+			return RequestFailure::Temporary;
+		}
+		match self {
+			Self::NotAllowed => RequestFailure::NotAllowed,
+			Self::InvalidData(description) => RequestFailure::InvalidData(description.clone()),
+			Self::ReadOnly => RequestFailure::ReadOnly,
+			Self::RateLimited => RequestFailure::RateLimited,
+			Self::Impersonating => RequestFailure::Impersonating,
+			Self::Database(_, DieselError::RollbackTransaction) => {
+				RequestFailure::InvalidData(String::from("The requested change isn't valid."))
+			}
+			Self::Database(_, DieselError::NotFound) => {
+				RequestFailure::InvalidData(String::from("Some of the data being changed no longer exists."))
+			}
+			Self::Database(_, DieselError::DatabaseError(kind, _)) => match kind {
+				DatabaseErrorKind::UniqueViolation => {
+					RequestFailure::InvalidData(String::from("It conflicts with existing data."))
+				}
+				DatabaseErrorKind::ForeignKeyViolation => {
+					RequestFailure::InvalidData(String::from("It refers to data that doesn't exist."))
+				}
+				DatabaseErrorKind::NotNullViolation | DatabaseErrorKind::CheckViolation => {
+					RequestFailure::InvalidData(String::from("Some required data is missing or invalid."))
+				}
+				_ => RequestFailure::ServerError,
+			},
+			_ => RequestFailure::ServerError,
+		}
+	}
+}
+
+impl fmt::Display for HandlerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::LostDbConnection(action, error) => {
+				write!(f, "A database connection error occurred {}: {}", action, error)
+			}
+			Self::Database(action, error) => write!(f, "A database error occurred {}: {}", action, error),
+			Self::Storage(action, error) => write!(f, "A storage error occurred {}: {}", action, error),
+			Self::NotAllowed => write!(f, "A user attempted an update they aren't allowed to make"),
+			Self::InvalidData(description) => write!(f, "A user sent an invalid update: {}", description),
+			Self::Connection(_) => write!(f, "The client connection failed"),
+			Self::ReadOnly => write!(f, "A user attempted an update while the server is read-only"),
+			Self::Impersonating => write!(f, "An administrator attempted an update while impersonating a user"),
+			Self::RateLimited => write!(
+				f,
+				"A user's connection is sending messages too quickly and is being throttled"
+			),
+		}
+	}
+}
+
+impl From<HandleConnectionError> for HandlerError {
+	fn from(error: HandleConnectionError) -> Self {
+		Self::Connection(error)
+	}
+}
+
+impl From<SendError<ConnectionUpdate>> for HandlerError {
+	fn from(error: SendError<ConnectionUpdate>) -> Self {
+		Self::Connection(error.into())
+	}
+}
+
+/// The number of times a database operation is attempted before a temporary failure is reported
+const DB_OPERATION_ATTEMPTS: u32 = 3;
+/// How long to wait before the first retry of a database operation. The wait doubles for each retry after that.
+const DB_OPERATION_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Runs a database operation using a connection from the pool. Each attempt runs in a transaction, so a failed attempt
+/// leaves no partial changes behind. If the operation fails in a way that might be temporary, it's tried again with a
+/// new connection after a short wait, up to [DB_OPERATION_ATTEMPTS] times in total.
+pub async fn run_db_operation<T>(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	action: &'static str,
+	mut operation: impl FnMut(&mut PgConnection) -> QueryResult<T>,
+) -> Result<T, HandlerError> {
+	let mut attempt = 1;
+	let mut retry_delay = DB_OPERATION_RETRY_DELAY;
+	loop {
+		match try_db_operation(db_connection_pool, action, &mut operation) {
+			Err(error) if error.is_retryable() && attempt < DB_OPERATION_ATTEMPTS => {
+				tide::log::warn!("Retrying after a temporary failure: {}", error);
+				task::sleep(retry_delay).await;
+				retry_delay *= 2;
+				attempt += 1;
+			}
+			result => return result,
+		}
+	}
+}
+
+fn try_db_operation<T>(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	action: &'static str,
+	operation: &mut impl FnMut(&mut PgConnection) -> QueryResult<T>,
+) -> Result<T, HandlerError> {
+	let mut db_connection = db_connection_pool
+		.get()
+		.map_err(|error| HandlerError::LostDbConnection(action, error))?;
+	db_connection
+		.transaction(|db_connection| operation(db_connection))
+		.map_err(|error| HandlerError::Database(action, error))
+}
+
+/// Handles the result of processing an update message from a client. Errors are logged, and the client is informed
+/// of the failure. Errors with the client connection itself are passed back to the caller.
+pub async fn report_handler_result(
+	result: Result<(), HandlerError>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+) -> Result<(), HandleConnectionError> {
+	let error = match result {
+		Ok(()) => return Ok(()),
+		Err(HandlerError::Connection(error)) => return Err(error),
+		Err(error) => error,
+	};
+	match &error {
+		HandlerError::NotAllowed
+		| HandlerError::InvalidData(_)
+		| HandlerError::ReadOnly
+		| HandlerError::Impersonating => {
+			tide::log::info!("{}", error)
+		}
+		HandlerError::RateLimited => tide::log::warn!("{}", error),
+		_ => tide::log::error!("{}", error),
+	}
+	let message = FromServerMessage::RequestFailure(error.client_failure());
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+	Ok(())
+}
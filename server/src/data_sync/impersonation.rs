@@ -0,0 +1,106 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::subscriptions::admin_audit_log::record_admin_action;
+use super::user::load_event_permissions;
+use super::{run_db_operation, HandlerError, SubscriptionManager};
+use crate::models::{Permission, User};
+use crate::schema::users;
+use async_std::channel::Sender;
+use async_std::sync::Mutex;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use stream_log_shared::messages::admin::AdminActionType;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::user::{SelfUserData, UserSubscriptionUpdate};
+use stream_log_shared::messages::FromServerMessage;
+
+pub struct ImpersonationArgs<'a> {
+	pub db_connection_pool: &'a Pool<ConnectionManager<PgConnection>>,
+	pub conn_update_tx: Sender<ConnectionUpdate>,
+	pub connection_id: &'a str,
+	pub session_id: &'a str,
+	pub subscription_manager: &'a Mutex<SubscriptionManager>,
+	pub user: &'a mut Option<SelfUserData>,
+	pub event_permission_cache: &'a mut HashMap<Event, Option<Permission>>,
+	/// Set once the connection is impersonating another user
+	pub impersonating: &'a mut bool,
+}
+
+/// Switches the connection to the user with the given ID, so that an administrator can see exactly which events and
+/// permissions the user has. The connection's subscriptions are all ended, as they were made with the administrator's
+/// access.
+pub async fn handle_impersonation_request(
+	args: ImpersonationArgs<'_>,
+	impersonated_user_id: &str,
+) -> Result<(), HandlerError> {
+	let Some(admin) = args.user.as_ref().filter(|user| user.is_admin).cloned() else {
+		return Err(HandlerError::NotAllowed);
+	};
+
+	let (impersonated_user, event_permissions) = run_db_operation(
+		args.db_connection_pool,
+		"loading a user to impersonate",
+		|db_connection| {
+			let user: Option<User> = users::table
+				.find(impersonated_user_id)
+				.first(db_connection)
+				.optional()?;
+			let Some(user) = user else {
+				return Ok(None);
+			};
+			let event_permissions = load_event_permissions(db_connection, &user.id)?;
+			Ok(Some((user, event_permissions)))
+		},
+	)
+	.await?
+	.ok_or_else(|| HandlerError::InvalidData(String::from("The user to view as doesn't exist.")))?;
+	let impersonated_user: SelfUserData = impersonated_user.into();
+
+	record_admin_action(
+		args.db_connection_pool,
+		args.subscription_manager,
+		&admin,
+		AdminActionType::User,
+		format!("Viewed the site as user \"{}\"", impersonated_user.username),
+	)
+	.await;
+
+	{
+		let mut subscription_manager = args.subscription_manager.lock().await;
+		let client_sync_version = subscription_manager.client_sync_version(args.connection_id);
+		subscription_manager.unsubscribe_from_all(args.connection_id).await?;
+		subscription_manager.add_connection(
+			args.connection_id,
+			args.session_id,
+			client_sync_version,
+			args.conn_update_tx.clone(),
+		);
+		subscription_manager
+			.subscribe_to_self_user(args.connection_id, &impersonated_user, args.conn_update_tx.clone())
+			.await;
+	}
+
+	let available_events: Vec<Event> = event_permissions
+		.iter()
+		.filter(|(_, permission)| permission.is_some())
+		.map(|(event, _)| event.clone())
+		.collect();
+	*args.user = Some(impersonated_user.clone());
+	*args.event_permission_cache = event_permissions;
+	*args.impersonating = true;
+
+	let message = FromServerMessage::ImpersonationStarted(UserSubscriptionUpdate {
+		user: impersonated_user,
+		available_events,
+	});
+	args.conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+	Ok(())
+}
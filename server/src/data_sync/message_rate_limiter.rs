@@ -0,0 +1,110 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Instant;
+use stream_log_shared::messages::event_subscription::EventSubscriptionUpdate;
+use stream_log_shared::messages::subscriptions::SubscriptionTargetUpdate;
+use stream_log_shared::messages::FromClientMessage;
+
+/// The number of typing notifications a connection can send at once before they start being dropped
+const TYPING_BURST: f64 = 40.0;
+/// The sustained rate, in messages per second, at which a connection can send typing notifications
+const TYPING_PER_SECOND: f64 = 10.0;
+/// The number of other messages a connection can send at once before they start being dropped. This is large enough
+/// for the bursts of entry updates the client sends when, for example, reordering entries.
+const MESSAGE_BURST: f64 = 100.0;
+/// The sustained rate, in messages per second, at which a connection can send other messages
+const MESSAGE_PER_SECOND: f64 = 10.0;
+
+/// The outcome of checking an incoming message against the rate limits
+pub enum RateLimitResult {
+	/// The message should be handled
+	Allowed,
+	/// The message should be dropped. The user should be told that they're being throttled only for the first dropped
+	/// message, so that the notices don't add to the flood.
+	Throttled { notify: bool },
+}
+
+struct TokenBucket {
+	tokens: f64,
+	capacity: f64,
+	refill_per_second: f64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(capacity: f64, refill_per_second: f64) -> Self {
+		Self {
+			tokens: capacity,
+			capacity,
+			refill_per_second,
+			last_refill: Instant::now(),
+		}
+	}
+
+	fn try_take(&mut self) -> bool {
+		let now = Instant::now();
+		let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+		self.last_refill = now;
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// Limits how quickly a single connection can send messages, so that a misbehaving client or runaway automation using
+/// a user's session can't flood the server. Typing notifications are limited separately from other messages, since
+/// they're sent much more often during normal use.
+pub struct MessageRateLimiter {
+	typing: TokenBucket,
+	messages: TokenBucket,
+	/// Whether a message has been dropped since the connection was last allowed to send one
+	throttled: bool,
+}
+
+impl Default for MessageRateLimiter {
+	fn default() -> Self {
+		Self {
+			typing: TokenBucket::new(TYPING_BURST, TYPING_PER_SECOND),
+			messages: TokenBucket::new(MESSAGE_BURST, MESSAGE_PER_SECOND),
+			throttled: false,
+		}
+	}
+}
+
+impl MessageRateLimiter {
+	/// Records an incoming message and checks whether it's within the connection's rate limits
+	pub fn check(&mut self, message: &FromClientMessage) -> RateLimitResult {
+		let allowed = match message {
+			// Keepalive messages are how the client knows the connection still works, so they're never dropped
+			FromClientMessage::KeepAlive => true,
+			FromClientMessage::SubscriptionMessage(update)
+				if matches!(
+					update.as_ref(),
+					SubscriptionTargetUpdate::EventUpdate(_, event_update)
+						if matches!(event_update.as_ref(), EventSubscriptionUpdate::Typing(_))
+				) =>
+			{
+				self.typing.try_take()
+			}
+			_ => self.messages.try_take(),
+		};
+
+		if allowed {
+			self.throttled = false;
+			RateLimitResult::Allowed
+		} else {
+			let notify = !self.throttled;
+			self.throttled = true;
+			RateLimitResult::Throttled { notify }
+		}
+	}
+}
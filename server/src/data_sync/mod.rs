@@ -5,14 +5,29 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 pub mod connection;
+pub mod custom_fields;
+mod entry_history;
+mod event_access;
+mod event_log_import;
+pub mod feedback_rate_limiter;
+mod handler_error;
+mod impersonation;
+mod message_rate_limiter;
 pub mod new_event_entries;
 mod register;
 mod subscription_manager;
 mod subscriptions;
+mod tab_entries;
 mod user;
 mod user_profile;
+mod user_sessions;
 
+pub use handler_error::{report_handler_result, run_db_operation, HandlerError};
 pub use subscription_manager::SubscriptionManager;
+pub use subscriptions::editor_workload::run_editor_workload_updater;
+pub use subscriptions::user_notifications::send_user_notification;
+pub use tab_entries::event_log_entries_from_db;
+pub use user::{load_event_permission, load_event_permissions};
 
 use async_std::channel::SendError;
 use connection::ConnectionUpdate;
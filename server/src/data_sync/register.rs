@@ -14,7 +14,6 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::result::DatabaseErrorKind;
 use rgb::RGB8;
-use stream_log_shared::messages::subscriptions::SubscriptionData;
 use stream_log_shared::messages::user::SelfUserData;
 use stream_log_shared::messages::user_register::{
 	RegistrationFinalizeResponse, RegistrationResponse, UserRegistrationFinalize, UsernameCheckResponse,
@@ -115,6 +114,17 @@ pub async fn register_user(
 					color_green,
 					color_blue,
 					use_spell_check: registration_data.use_spell_check,
+					completed_guided_tour: false,
+					enabled: true,
+					avatar_updated_at: None,
+					editor_assigned_sound: false,
+					editor_assigned_desktop: false,
+					typing_in_edited_entry_sound: false,
+					typing_in_edited_entry_desktop: false,
+					other_notifications_sound: false,
+					other_notifications_desktop: false,
+					use_event_time_display: true,
+					time_display_zone: None,
 				};
 
 				let user_record: User = diesel::insert_into(users::table)
@@ -137,6 +147,11 @@ pub async fn register_user(
 					is_admin: new_user.is_admin,
 					color,
 					use_spell_check: new_user.use_spell_check,
+					completed_guided_tour: new_user.completed_guided_tour,
+					enabled: new_user.enabled,
+					avatar_updated_at: new_user.avatar_updated_at,
+					notification_preferences: new_user.notification_preferences(),
+					time_display: new_user.time_display(),
 				};
 				*user = Some(user_data.clone());
 
@@ -145,8 +160,9 @@ pub async fn register_user(
 					.subscribe_to_self_user(connection_id, &user_data, conn_update_tx.clone())
 					.await;
 
-				let admin_message = SubscriptionData::AdminUsersUpdate(user_data.clone());
-				let send_result = subscription_manager.broadcast_admin_user_message(admin_message).await;
+				let send_result = subscription_manager
+					.broadcast_admin_user_message(user_data.clone())
+					.await;
 				if let Err(error) = send_result {
 					tide::log::error!(
 						"Failed to send user registration to the admin users subscription: {}",
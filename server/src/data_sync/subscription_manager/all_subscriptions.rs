@@ -4,134 +4,639 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::event_scoped_subscriptions::EventScopedSubscriptionManager;
+use super::list_history::ListHistory;
 use super::one_subscription::SingleSubscriptionManager;
 use crate::data_sync::connection::ConnectionUpdate;
 use crate::data_sync::UserDataUpdate;
+use crate::models::Permission;
+use crate::webhooks::WebhookPayload;
 use async_std::channel::{SendError, Sender};
+use async_std::future::timeout;
+use async_std::task;
+use chrono::{TimeDelta, Utc};
 use futures::future::join_all;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex as SyncMutex;
+use std::time::Duration;
+use stream_log_shared::messages::admin::{AdminEventData, AdminEventLogTabsData};
+use stream_log_shared::messages::event_subscription::{
+	EntryEditLock, EventSubscriptionData, ENTRY_EDIT_LOCK_DURATION_SECONDS,
+};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::list_sync::{ListPatch, ListVersion};
 use stream_log_shared::messages::subscriptions::{SubscriptionData, SubscriptionType};
-use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use stream_log_shared::messages::FromServerMessage;
+use stream_log_shared::SYNC_VERSION;
+
+/// How long to wait during shutdown for connections to send what's left in their queues and close
+const CONNECTION_CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often to check whether connections have closed during shutdown
+const CONNECTION_CLOSE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 /// A manager for all the subscriptions we need to track
 pub struct SubscriptionManager {
 	event_subscriptions: HashMap<String, SingleSubscriptionManager>,
+	/// The users viewing each event by event ID
+	event_viewers: HashMap<String, EventViewers>,
 	user_subscriptions: HashMap<String, HashMap<String, Sender<ConnectionUpdate>>>,
+	/// Every open connection by connection ID, including those without a registered user
+	connections: HashMap<String, Sender<ConnectionUpdate>>,
+	/// The ID of the login session each open connection was opened with by connection ID
+	connection_sessions: HashMap<String, String>,
+	/// The sync version of the client on each open connection by connection ID
+	connection_sync_versions: HashMap<String, u32>,
 	admin_user_subscriptions: SingleSubscriptionManager,
 	admin_event_subscriptions: SingleSubscriptionManager,
+	/// The changes made to the lists of users, events, and tabs sent to admin subscriptions, so that clients that
+	/// resubscribe can be sent only what changed
+	admin_user_history: SyncMutex<ListHistory<SelfUserData>>,
+	admin_event_history: SyncMutex<ListHistory<AdminEventData>>,
+	admin_event_log_tabs_history: SyncMutex<ListHistory<AdminEventLogTabsData>>,
 	admin_permission_group_subscriptions: SingleSubscriptionManager,
 	admin_permission_group_user_subscriptions: SingleSubscriptionManager,
+	admin_user_event_permission_subscriptions: SingleSubscriptionManager,
 	admin_entry_type_subscriptions: SingleSubscriptionManager,
 	admin_entry_type_event_subscriptions: SingleSubscriptionManager,
 	admin_event_editor_subscriptions: SingleSubscriptionManager,
+	admin_event_editor_for_event_subscriptions: EventScopedSubscriptionManager,
 	admin_event_log_tabs_subscriptions: SingleSubscriptionManager,
+	admin_event_log_tabs_for_event_subscriptions: EventScopedSubscriptionManager,
 	admin_applications_subscriptions: SingleSubscriptionManager,
 	admin_info_pages_subscriptions: SingleSubscriptionManager,
+	admin_event_access_requests_subscriptions: SingleSubscriptionManager,
+	admin_event_webhooks_subscriptions: SingleSubscriptionManager,
+	admin_event_custom_fields_subscriptions: SingleSubscriptionManager,
+	admin_event_share_links_subscriptions: SingleSubscriptionManager,
+	admin_rejected_updates_subscriptions: SingleSubscriptionManager,
+	admin_audit_log_subscriptions: SingleSubscriptionManager,
+	admin_jobs_subscriptions: SingleSubscriptionManager,
+	/// Notification subscriptions, scoped by user ID rather than event ID
+	user_notification_subscriptions: EventScopedSubscriptionManager,
+	editor_workload_subscriptions: EventScopedSubscriptionManager,
+	webhook_tx: Option<Sender<WebhookPayload>>,
+	/// Receives the IDs of events with workload subscriptions whose entries changed, so their workloads can be
+	/// recalculated
+	editor_workload_tx: Option<Sender<String>>,
 }
 
 impl SubscriptionManager {
 	pub fn new() -> Self {
 		Self {
 			event_subscriptions: HashMap::new(),
+			event_viewers: HashMap::new(),
 			user_subscriptions: HashMap::new(),
+			connections: HashMap::new(),
+			connection_sessions: HashMap::new(),
+			connection_sync_versions: HashMap::new(),
 			admin_user_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminUsers),
 			admin_event_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminEvents),
+			admin_user_history: SyncMutex::new(ListHistory::new()),
+			admin_event_history: SyncMutex::new(ListHistory::new()),
+			admin_event_log_tabs_history: SyncMutex::new(ListHistory::new()),
 			admin_permission_group_subscriptions: SingleSubscriptionManager::new(
 				SubscriptionType::AdminPermissionGroups,
 			),
 			admin_permission_group_user_subscriptions: SingleSubscriptionManager::new(
 				SubscriptionType::AdminPermissionGroupUsers,
 			),
+			admin_user_event_permission_subscriptions: SingleSubscriptionManager::new(
+				SubscriptionType::AdminUserEventPermissions,
+			),
 			admin_entry_type_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminEntryTypes),
 			admin_entry_type_event_subscriptions: SingleSubscriptionManager::new(
 				SubscriptionType::AdminEntryTypesEvents,
 			),
 			admin_event_editor_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminEventEditors),
+			admin_event_editor_for_event_subscriptions: EventScopedSubscriptionManager::new(
+				SubscriptionType::AdminEventEditorsForEvent,
+			),
 			admin_event_log_tabs_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminEventLogTabs),
+			admin_event_log_tabs_for_event_subscriptions: EventScopedSubscriptionManager::new(
+				SubscriptionType::AdminEventLogTabsForEvent,
+			),
 			admin_applications_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminApplications),
 			admin_info_pages_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminInfoPages),
+			admin_event_access_requests_subscriptions: SingleSubscriptionManager::new(
+				SubscriptionType::AdminEventAccessRequests,
+			),
+			admin_event_webhooks_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminEventWebhooks),
+			admin_event_custom_fields_subscriptions: SingleSubscriptionManager::new(
+				SubscriptionType::AdminEventCustomFields,
+			),
+			admin_event_share_links_subscriptions: SingleSubscriptionManager::new(
+				SubscriptionType::AdminEventShareLinks,
+			),
+			admin_rejected_updates_subscriptions: SingleSubscriptionManager::new(
+				SubscriptionType::AdminRejectedUpdates,
+			),
+			admin_audit_log_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminAuditLog),
+			admin_jobs_subscriptions: SingleSubscriptionManager::new(SubscriptionType::AdminJobs),
+			user_notification_subscriptions: EventScopedSubscriptionManager::new(|_| {
+				SubscriptionType::UserNotifications
+			}),
+			editor_workload_subscriptions: EventScopedSubscriptionManager::new(SubscriptionType::EditorWorkload),
+			webhook_tx: None,
+			editor_workload_tx: None,
 		}
 	}
 
-	/// Shuts down the subscription manager and all subscription tasks.
+	/// Sets the channel to which log entry changes are sent for delivery to event webhooks
+	pub fn set_webhook_sender(&mut self, webhook_tx: Sender<WebhookPayload>) {
+		self.webhook_tx = Some(webhook_tx);
+	}
+
+	/// Sets the channel to which the IDs of events are sent when their editor workloads need to be recalculated
+	pub fn set_editor_workload_sender(&mut self, editor_workload_tx: Sender<String>) {
+		self.editor_workload_tx = Some(editor_workload_tx);
+	}
+
+	/// Shuts down the subscription manager and all subscription tasks. Every connection is told that the server is
+	/// shutting down, and broadcasts that are still queued are sent before the connections are closed.
 	pub async fn shutdown(mut self) {
-		let mut handles = Vec::new();
-		for (_, subscription_manager) in self.event_subscriptions.drain() {
-			handles.push(subscription_manager.thread_handle);
-		}
-
-		let subscription_shutdown_handles = vec![
-			self.admin_user_subscriptions.shutdown(),
-			self.admin_event_subscriptions.shutdown(),
-			self.admin_permission_group_subscriptions.shutdown(),
-			self.admin_permission_group_user_subscriptions.shutdown(),
-			self.admin_entry_type_subscriptions.shutdown(),
-			self.admin_entry_type_event_subscriptions.shutdown(),
-			self.admin_event_editor_subscriptions.shutdown(),
-			self.admin_applications_subscriptions.shutdown(),
-			self.admin_info_pages_subscriptions.shutdown(),
-		];
-		for handle in join_all(subscription_shutdown_handles).await {
-			handles.push(handle);
+		for connection in self.connections.values() {
+			let message = ConnectionUpdate::SendData(Box::new(FromServerMessage::ServerShuttingDown));
+			let _ = connection.send(message).await;
 		}
 
-		for (_, user_connection) in self.user_subscriptions.drain() {
-			for (_, connection) in user_connection.iter() {
-				connection.close();
+		let mut subscriptions: Vec<SingleSubscriptionManager> = self
+			.event_subscriptions
+			.drain()
+			.map(|(_, event_subscription)| event_subscription)
+			.collect();
+		subscriptions.extend([
+			self.admin_user_subscriptions,
+			self.admin_event_subscriptions,
+			self.admin_permission_group_subscriptions,
+			self.admin_permission_group_user_subscriptions,
+			self.admin_user_event_permission_subscriptions,
+			self.admin_entry_type_subscriptions,
+			self.admin_entry_type_event_subscriptions,
+			self.admin_event_editor_subscriptions,
+			self.admin_event_log_tabs_subscriptions,
+			self.admin_applications_subscriptions,
+			self.admin_info_pages_subscriptions,
+			self.admin_event_access_requests_subscriptions,
+			self.admin_event_webhooks_subscriptions,
+			self.admin_event_custom_fields_subscriptions,
+			self.admin_event_share_links_subscriptions,
+			self.admin_rejected_updates_subscriptions,
+			self.admin_audit_log_subscriptions,
+			self.admin_jobs_subscriptions,
+		]);
+		join_all(subscriptions.into_iter().map(|subscription| subscription.shutdown())).await;
+		join_all([
+			self.admin_event_editor_for_event_subscriptions.shutdown(),
+			self.admin_event_log_tabs_for_event_subscriptions.shutdown(),
+			self.user_notification_subscriptions.shutdown(),
+			self.editor_workload_subscriptions.shutdown(),
+		])
+		.await;
+
+		// Closing a connection's channel lets it finish sending what's already queued before it closes. Once it's
+		// closed, the connection drops its receiver.
+		for connection in self.connections.values() {
+			connection.close();
+		}
+		let connections_closed = async {
+			while self
+				.connections
+				.values()
+				.any(|connection| connection.receiver_count() > 0)
+			{
+				task::sleep(CONNECTION_CLOSE_CHECK_INTERVAL).await;
 			}
+		};
+		if timeout(CONNECTION_CLOSE_TIMEOUT, connections_closed).await.is_err() {
+			tide::log::warn!("Some connections didn't close before shutdown");
 		}
+	}
 
-		join_all(handles).await;
+	/// Tracks a newly opened connection, so it can be notified when the server shuts down or its session is revoked
+	pub fn add_connection(
+		&mut self,
+		connection_id: &str,
+		session_id: &str,
+		client_sync_version: u32,
+		conn_update_tx: Sender<ConnectionUpdate>,
+	) {
+		self.connections.insert(connection_id.to_owned(), conn_update_tx);
+		self.connection_sessions
+			.insert(connection_id.to_owned(), session_id.to_owned());
+		self.connection_sync_versions
+			.insert(connection_id.to_owned(), client_sync_version);
+	}
+
+	/// Gets the sync version of the client on a connection. Connections that aren't tracked are assumed to be current.
+	pub fn client_sync_version(&self, connection_id: &str) -> u32 {
+		self.connection_sync_versions
+			.get(connection_id)
+			.copied()
+			.unwrap_or(SYNC_VERSION)
 	}
 
-	/// Subscribes the provided connection to the provided event
+	/// Gets the IDs of the login sessions that have connections open
+	pub fn connected_session_ids(&self) -> HashSet<String> {
+		self.connection_sessions.values().cloned().collect()
+	}
+
+	/// Tells the connections opened with any of the given login sessions that their session was revoked and closes
+	/// them
+	pub async fn close_session_connections(&self, session_ids: &[String]) {
+		for (connection_id, session_id) in self.connection_sessions.iter() {
+			if !session_ids.contains(session_id) {
+				continue;
+			}
+			let Some(connection) = self.connections.get(connection_id) else {
+				continue;
+			};
+			let message = ConnectionUpdate::SendData(Box::new(FromServerMessage::SessionRevoked));
+			let _ = connection.send(message).await;
+			connection.close();
+		}
+	}
+
+	/// Subscribes the provided connection to the provided event. If a viewer is provided, everyone viewing the event is
+	/// told that the user is viewing it if they weren't already viewing it from another connection.
 	pub async fn subscribe_to_event(
 		&mut self,
-		event_id: &str,
+		event: &Event,
+		viewer: Option<&PublicUserData>,
 		connection_id: &str,
 		conn_update_tx: Sender<ConnectionUpdate>,
 	) {
+		let event_id = event.id.as_str();
+		let client_sync_version = self.client_sync_version(connection_id);
 		match self.event_subscriptions.entry(event_id.to_string()) {
 			Entry::Occupied(mut event_subscription) => {
 				event_subscription
 					.get_mut()
-					.subscribe(connection_id, conn_update_tx)
+					.subscribe(connection_id, client_sync_version, conn_update_tx)
 					.await
 			}
 			Entry::Vacant(event_entry) => {
 				let event_subscription =
 					SingleSubscriptionManager::new(SubscriptionType::EventLogData(event_id.to_string()));
-				event_subscription.subscribe(connection_id, conn_update_tx).await;
+				event_subscription
+					.subscribe(connection_id, client_sync_version, conn_update_tx)
+					.await;
 				event_entry.insert(event_subscription);
 			}
 		}
+
+		let Some(user) = viewer else {
+			return;
+		};
+		let event_viewers = self
+			.event_viewers
+			.entry(event_id.to_string())
+			.or_insert_with(|| EventViewers {
+				event: event.clone(),
+				viewers_by_connection: HashMap::new(),
+				entry_locks: HashMap::new(),
+			});
+		let already_viewing = event_viewers.is_viewing(&user.id);
+		event_viewers
+			.viewers_by_connection
+			.insert(connection_id.to_string(), user.clone());
+		if !already_viewing {
+			let message = SubscriptionData::EventUpdate(
+				event_viewers.event.clone(),
+				Box::new(EventSubscriptionData::AddViewer(user.clone())),
+			);
+			if let Err(error) = self.broadcast_event_message(event_id, message).await {
+				tide::log::error!("Failed to broadcast event viewer addition: {}", error);
+			}
+		}
+	}
+
+	/// Replaces the copies of a user's public data the subscription manager has and sends the new data to every
+	/// connection, so that clients can update the copies they have
+	pub async fn update_public_user(&mut self, user: &PublicUserData) {
+		for event_viewers in self.event_viewers.values_mut() {
+			for viewer in event_viewers.viewers_by_connection.values_mut() {
+				if viewer.id == user.id {
+					*viewer = user.clone();
+				}
+			}
+			for held_lock in event_viewers.entry_locks.values_mut() {
+				if held_lock.lock.user.id == user.id {
+					held_lock.lock.user = user.clone();
+				}
+			}
+		}
+
+		for connection in self.connections.values() {
+			let message = ConnectionUpdate::SendData(Box::new(FromServerMessage::PublicUserUpdate(user.clone())));
+			let _ = connection.send(message).await;
+		}
+	}
+
+	/// Gets the users viewing the provided event, sorted by username
+	pub fn event_viewers(&self, event_id: &str) -> Vec<PublicUserData> {
+		let Some(event_viewers) = self.event_viewers.get(event_id) else {
+			return Vec::new();
+		};
+		let mut viewers: Vec<PublicUserData> = Vec::new();
+		for user in event_viewers.viewers_by_connection.values() {
+			if !viewers.iter().any(|viewer| viewer.id == user.id) {
+				viewers.push(user.clone());
+			}
+		}
+		viewers.sort_by_key(|viewer| viewer.username.to_lowercase());
+		viewers
+	}
+
+	/// Locks an entry for editing by the user of the provided connection, or renews the lock if the user already holds
+	/// it. Gets the new lock, or None if the connection isn't viewing the event or another user holds an unexpired lock
+	/// on the entry.
+	pub fn lock_entry(
+		&mut self,
+		event_id: &str,
+		entry_id: &str,
+		user: &PublicUserData,
+		connection_id: &str,
+	) -> Option<EntryEditLock> {
+		let event_viewers = self.event_viewers.get_mut(event_id)?;
+		if !event_viewers.viewers_by_connection.contains_key(connection_id) {
+			return None;
+		}
+
+		let now = Utc::now();
+		if let Some(held_lock) = event_viewers.entry_locks.get(entry_id) {
+			if held_lock.lock.user.id != user.id && held_lock.lock.expires_at > now {
+				return None;
+			}
+		}
+
+		let lock = EntryEditLock {
+			entry_id: entry_id.to_string(),
+			user: user.clone(),
+			expires_at: now + TimeDelta::seconds(ENTRY_EDIT_LOCK_DURATION_SECONDS),
+		};
+		event_viewers.entry_locks.insert(
+			entry_id.to_string(),
+			HeldEntryLock {
+				connection_id: connection_id.to_string(),
+				lock: lock.clone(),
+			},
+		);
+		Some(lock)
+	}
+
+	/// Releases the lock on an entry if it's held by the provided connection. Returns whether a lock was released.
+	pub fn unlock_entry(&mut self, event_id: &str, entry_id: &str, connection_id: &str) -> bool {
+		let Some(event_viewers) = self.event_viewers.get_mut(event_id) else {
+			return false;
+		};
+		match event_viewers.entry_locks.entry(entry_id.to_string()) {
+			Entry::Occupied(held_lock) if held_lock.get().connection_id == connection_id => {
+				held_lock.remove();
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/// Gets the unexpired entry locks for the provided event
+	pub fn entry_locks(&self, event_id: &str) -> Vec<EntryEditLock> {
+		let Some(event_viewers) = self.event_viewers.get(event_id) else {
+			return Vec::new();
+		};
+		let now = Utc::now();
+		event_viewers
+			.entry_locks
+			.values()
+			.filter(|held_lock| held_lock.lock.expires_at > now)
+			.map(|held_lock| held_lock.lock.clone())
+			.collect()
+	}
+
+	/// Gets the number of subscriptions of each type and the number of broadcast messages waiting to be sent to them
+	pub async fn subscription_stats(&self) -> Vec<SubscriptionStats> {
+		let mut event_log_subscriptions = 0;
+		let mut event_log_queued_broadcasts = 0;
+		for event_subscription in self.event_subscriptions.values() {
+			event_log_subscriptions += event_subscription.subscription_count().await;
+			event_log_queued_broadcasts += event_subscription.queued_broadcast_count();
+		}
+		let mut stats = vec![
+			SubscriptionStats {
+				subscription_type: "event_log",
+				subscriptions: event_log_subscriptions,
+				queued_broadcasts: event_log_queued_broadcasts,
+			},
+			// User updates are sent directly to each of the user's connections rather than broadcast
+			SubscriptionStats {
+				subscription_type: "user",
+				subscriptions: self
+					.user_subscriptions
+					.values()
+					.map(|connections| connections.len())
+					.sum(),
+				queued_broadcasts: 0,
+			},
+		];
+
+		let single_subscriptions = [
+			("admin_users", &self.admin_user_subscriptions),
+			("admin_events", &self.admin_event_subscriptions),
+			("admin_permission_groups", &self.admin_permission_group_subscriptions),
+			(
+				"admin_permission_group_users",
+				&self.admin_permission_group_user_subscriptions,
+			),
+			(
+				"admin_user_event_permissions",
+				&self.admin_user_event_permission_subscriptions,
+			),
+			("admin_entry_types", &self.admin_entry_type_subscriptions),
+			("admin_entry_types_events", &self.admin_entry_type_event_subscriptions),
+			("admin_event_editors", &self.admin_event_editor_subscriptions),
+			("admin_event_log_tabs", &self.admin_event_log_tabs_subscriptions),
+			("admin_applications", &self.admin_applications_subscriptions),
+			("admin_info_pages", &self.admin_info_pages_subscriptions),
+			(
+				"admin_event_access_requests",
+				&self.admin_event_access_requests_subscriptions,
+			),
+			("admin_event_webhooks", &self.admin_event_webhooks_subscriptions),
+			(
+				"admin_event_custom_fields",
+				&self.admin_event_custom_fields_subscriptions,
+			),
+			("admin_event_share_links", &self.admin_event_share_links_subscriptions),
+			("admin_rejected_updates", &self.admin_rejected_updates_subscriptions),
+			("admin_audit_log", &self.admin_audit_log_subscriptions),
+			("admin_jobs", &self.admin_jobs_subscriptions),
+		];
+		for (subscription_type, subscriptions) in single_subscriptions {
+			stats.push(SubscriptionStats {
+				subscription_type,
+				subscriptions: subscriptions.subscription_count().await,
+				queued_broadcasts: subscriptions.queued_broadcast_count(),
+			});
+		}
+
+		let event_scoped_subscriptions = [
+			(
+				"admin_event_editors_for_event",
+				&self.admin_event_editor_for_event_subscriptions,
+			),
+			(
+				"admin_event_log_tabs_for_event",
+				&self.admin_event_log_tabs_for_event_subscriptions,
+			),
+			("user_notifications", &self.user_notification_subscriptions),
+			("editor_workload", &self.editor_workload_subscriptions),
+		];
+		for (subscription_type, subscriptions) in event_scoped_subscriptions {
+			stats.push(SubscriptionStats {
+				subscription_type,
+				subscriptions: subscriptions.subscription_count().await,
+				queued_broadcasts: subscriptions.queued_broadcast_count(),
+			});
+		}
+
+		stats
+	}
+
+	/// Gets the number of items waiting in the queues of the background tasks the subscription manager sends work to
+	pub fn background_queue_stats(&self) -> Vec<(&'static str, usize)> {
+		let mut stats = Vec::new();
+		if let Some(webhook_tx) = self.webhook_tx.as_ref() {
+			stats.push(("webhooks", webhook_tx.len()));
+		}
+		if let Some(editor_workload_tx) = self.editor_workload_tx.as_ref() {
+			stats.push(("editor_workload", editor_workload_tx.len()));
+		}
+		stats
+	}
+
+	/// Stops tracking the provided connection as viewing the provided event and releases the entry locks it held. If
+	/// the connection's user isn't viewing the event from any other connection, everyone else viewing the event is told
+	/// that the user left.
+	async fn remove_event_viewer(&mut self, event_id: &str, connection_id: &str) {
+		let Some(event_viewers) = self.event_viewers.get_mut(event_id) else {
+			return;
+		};
+		let Some(user) = event_viewers.viewers_by_connection.remove(connection_id) else {
+			return;
+		};
+		let still_viewing = event_viewers.is_viewing(&user.id);
+		let event = event_viewers.event.clone();
+		let released_entry_ids: Vec<String> = event_viewers
+			.entry_locks
+			.iter()
+			.filter(|(_, held_lock)| held_lock.connection_id == connection_id)
+			.map(|(entry_id, _)| entry_id.clone())
+			.collect();
+		for entry_id in released_entry_ids.iter() {
+			event_viewers.entry_locks.remove(entry_id);
+		}
+		if event_viewers.viewers_by_connection.is_empty() {
+			self.event_viewers.remove(event_id);
+		}
+		for entry_id in released_entry_ids {
+			let message =
+				SubscriptionData::EventUpdate(event.clone(), Box::new(EventSubscriptionData::EntryUnlocked(entry_id)));
+			if let Err(error) = self.broadcast_event_message(event_id, message).await {
+				tide::log::error!("Failed to broadcast entry lock release: {}", error);
+			}
+		}
+		if !still_viewing {
+			let message = SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::RemoveViewer(user.id)));
+			if let Err(error) = self.broadcast_event_message(event_id, message).await {
+				tide::log::error!("Failed to broadcast event viewer removal: {}", error);
+			}
+		}
 	}
 
 	/// Unsubscribes the provided connection from the provided event
 	pub async fn unsubscribe_from_event(
-		&self,
+		&mut self,
 		event_id: &str,
 		connection_id: &str,
 	) -> Result<(), SendError<ConnectionUpdate>> {
 		if let Some(event_subscription) = self.event_subscriptions.get(event_id) {
 			event_subscription.unsubscribe(connection_id).await?;
 		}
+		self.remove_event_viewer(event_id, connection_id).await;
+		self.remove_unused_event_subscriptions().await;
 		Ok(())
 	}
 
-	/// Sends the given message to all subscribed users for the given event
+	/// Stops tracking events that no longer have any subscribed connections. They'll be set up again if a connection
+	/// subscribes to them later.
+	async fn remove_unused_event_subscriptions(&mut self) {
+		let mut unused_event_ids: Vec<String> = Vec::new();
+		for (event_id, event_subscription) in self.event_subscriptions.iter() {
+			if !event_subscription.has_subscriptions().await {
+				unused_event_ids.push(event_id.clone());
+			}
+		}
+		for event_id in unused_event_ids.iter() {
+			if let Some(event_subscription) = self.event_subscriptions.remove(event_id) {
+				event_subscription.shutdown().await;
+			}
+		}
+	}
+
+	/// Ends all subscriptions to the provided event, letting each subscribed connection know that it was unsubscribed
+	pub async fn end_event_subscriptions(&mut self, event_id: &str) {
+		self.event_viewers.remove(event_id);
+		if let Some(event_subscription) = self.event_subscriptions.remove(event_id) {
+			event_subscription.shutdown().await;
+		}
+	}
+
+	/// Sends the given message to all subscribed users for the given event. Log entry changes are also sent to the
+	/// event's webhooks and cause the event's editor workloads to be recalculated if anyone is subscribed to them.
 	pub async fn broadcast_event_message(
 		&self,
 		event_id: &str,
 		message: SubscriptionData,
 	) -> Result<(), SendError<SubscriptionData>> {
+		if let Some(webhook_tx) = self.webhook_tx.as_ref() {
+			if let Some(payload) = WebhookPayload::from_event_message(&message) {
+				if let Err(error) = webhook_tx.try_send(payload) {
+					tide::log::error!("Failed to queue webhook payload: {}", error);
+				}
+			}
+		}
+		if let Some(editor_workload_tx) = self.editor_workload_tx.as_ref() {
+			if self.editor_workload_subscriptions.has_subscriptions_for(event_id) && changes_log_entry(&message) {
+				if let Err(error) = editor_workload_tx.try_send(event_id.to_string()) {
+					tide::log::error!("Failed to queue editor workload update: {}", error);
+				}
+			}
+		}
 		if let Some(event_subscription) = self.event_subscriptions.get(event_id) {
 			event_subscription.broadcast_message(message).await?;
 		}
 		Ok(())
 	}
 
+	/// Sends the given message to the subscribed users for the given event who have at least the given permission level
+	/// for the event. The other subscribed users are sent the alternate message instead.
+	pub async fn broadcast_event_message_by_permission(
+		&self,
+		event_id: &str,
+		minimum_permission: Permission,
+		message: SubscriptionData,
+		alternate_message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		if let Some(event_subscription) = self.event_subscriptions.get(event_id) {
+			event_subscription
+				.broadcast_message_by_event_permission(event_id, minimum_permission, message, alternate_message)
+				.await?;
+		}
+		Ok(())
+	}
+
 	/// Adds a subscription to its associated user
 	pub async fn subscribe_to_self_user(
 		&mut self,
@@ -174,7 +679,7 @@ impl SubscriptionManager {
 	/// Adds to the admin user list subscription
 	pub async fn add_admin_user_subscription(&self, connection_id: &str, update_channel: Sender<ConnectionUpdate>) {
 		self.admin_user_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -183,12 +688,22 @@ impl SubscriptionManager {
 		self.admin_user_subscriptions.unsubscribe(connection_id).await
 	}
 
-	/// Sends the given message to all subscribed connections for the admin user list
-	pub async fn broadcast_admin_user_message(
-		&self,
-		message: SubscriptionData,
-	) -> Result<(), SendError<SubscriptionData>> {
-		self.admin_user_subscriptions.broadcast_message(message).await
+	/// Records a change to a user and sends it to all subscribed connections for the admin user list
+	pub async fn broadcast_admin_user_message(&self, user: SelfUserData) -> Result<(), SendError<SubscriptionData>> {
+		let patch = self.admin_user_history.lock().unwrap().record(user);
+		self.admin_user_subscriptions
+			.broadcast_message(SubscriptionData::AdminUsersUpdate(patch))
+			.await
+	}
+
+	/// Gets the current version of the admin user list
+	pub fn admin_user_list_version(&self) -> ListVersion {
+		self.admin_user_history.lock().unwrap().version()
+	}
+
+	/// Gets the changes made to the admin user list since the given version, if they're all still kept
+	pub fn admin_user_changes_since(&self, version: &ListVersion) -> Option<Vec<ListPatch<SelfUserData>>> {
+		self.admin_user_history.lock().unwrap().changes_since(version)
 	}
 
 	/// Checks whether a connection is subscribed to admin users
@@ -199,7 +714,7 @@ impl SubscriptionManager {
 	/// Adds to the admin event list subscription
 	pub async fn add_admin_event_subscription(&self, connection_id: &str, update_channel: Sender<ConnectionUpdate>) {
 		self.admin_event_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -211,12 +726,25 @@ impl SubscriptionManager {
 		self.admin_event_subscriptions.unsubscribe(connection_id).await
 	}
 
-	/// Sends the given message to all subscribed connections for the admin event list
+	/// Records a change to the event list and sends it to all subscribed connections for the admin event list
 	pub async fn broadcast_admin_event_message(
 		&self,
-		message: SubscriptionData,
+		event_data: AdminEventData,
 	) -> Result<(), SendError<SubscriptionData>> {
-		self.admin_event_subscriptions.broadcast_message(message).await
+		let patch = self.admin_event_history.lock().unwrap().record(event_data);
+		self.admin_event_subscriptions
+			.broadcast_message(SubscriptionData::AdminEventsUpdate(patch))
+			.await
+	}
+
+	/// Gets the current version of the admin event list
+	pub fn admin_event_list_version(&self) -> ListVersion {
+		self.admin_event_history.lock().unwrap().version()
+	}
+
+	/// Gets the changes made to the admin event list since the given version, if they're all still kept
+	pub fn admin_event_changes_since(&self, version: &ListVersion) -> Option<Vec<ListPatch<AdminEventData>>> {
+		self.admin_event_history.lock().unwrap().changes_since(version)
 	}
 
 	/// Checks whether a connection is subscribed to admin events
@@ -231,7 +759,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_permission_group_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -269,7 +797,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_permission_group_user_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -300,6 +828,44 @@ impl SubscriptionManager {
 			.await
 	}
 
+	/// Adds to the admin user event permissions subscription
+	pub async fn add_admin_user_event_permissions_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_user_event_permission_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin user event permissions subscription
+	pub async fn remove_admin_user_event_permissions_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_user_event_permission_subscriptions
+			.unsubscribe(connection_id)
+			.await
+	}
+
+	/// Sends the given message to all subscribed connections for admin user event permissions
+	pub async fn broadcast_admin_user_event_permissions_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_user_event_permission_subscriptions
+			.broadcast_message(message)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin user event permissions
+	pub async fn is_subscribed_to_admin_user_event_permissions(&self, connection_id: &str) -> bool {
+		self.admin_user_event_permission_subscriptions
+			.is_subscribed(connection_id)
+			.await
+	}
+
 	/// Adds to the admin entry types subscription
 	pub async fn add_admin_entry_types_subscription(
 		&self,
@@ -307,7 +873,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_entry_type_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -339,7 +905,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_entry_type_event_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -373,7 +939,7 @@ impl SubscriptionManager {
 	/// Adds to the admin event editors subscription
 	pub async fn add_admin_editors_subscription(&self, connection_id: &str, update_channel: Sender<ConnectionUpdate>) {
 		self.admin_event_editor_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -385,17 +951,53 @@ impl SubscriptionManager {
 		self.admin_event_editor_subscriptions.unsubscribe(connection_id).await
 	}
 
-	/// Sends the given message to all subscribed connections for admin event editors
+	/// Sends the given message about the given event's editors to all subscribed connections for admin event editors,
+	/// including those subscribed only to that event's editors
 	pub async fn broadcast_admin_editors_message(
 		&self,
+		event_id: &str,
 		message: SubscriptionData,
 	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_event_editor_for_event_subscriptions
+			.broadcast_message(event_id, message.clone())
+			.await?;
 		self.admin_event_editor_subscriptions.broadcast_message(message).await
 	}
 
-	/// Checks whether a connection is subscribed to admin editors
-	pub async fn is_subscribed_to_admin_editors(&self, connection_id: &str) -> bool {
-		self.admin_event_editor_subscriptions.is_subscribed(connection_id).await
+	/// Adds to the admin editors subscription for a single event
+	pub async fn add_admin_editors_for_event_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_editor_for_event_subscriptions
+			.subscribe(
+				event_id,
+				connection_id,
+				self.client_sync_version(connection_id),
+				update_channel,
+			)
+			.await;
+	}
+
+	/// Removes from the admin editors subscription for a single event
+	pub async fn remove_admin_editors_for_event_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_editor_for_event_subscriptions
+			.unsubscribe(event_id, connection_id)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin editors for the given event, either directly or through the
+	/// subscription to all events
+	pub async fn is_subscribed_to_admin_editors_for_event(&self, event_id: &str, connection_id: &str) -> bool {
+		self.admin_event_editor_for_event_subscriptions
+			.is_subscribed(event_id, connection_id)
+			.await || self.admin_event_editor_subscriptions.is_subscribed(connection_id).await
 	}
 
 	/// Adds to the admin event log tabs subscription
@@ -405,7 +1007,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_event_log_tabs_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -417,17 +1019,69 @@ impl SubscriptionManager {
 		self.admin_event_log_tabs_subscriptions.unsubscribe(connection_id).await
 	}
 
-	/// Sends the given message to all subscribed connections for admin event log tabs
+	/// Records a change to the given event's tabs and sends it to all subscribed connections for admin event log tabs,
+	/// including those subscribed only to that event's tabs
 	pub async fn broadcast_admin_event_log_tabs_message(
 		&self,
-		message: SubscriptionData,
+		event_id: &str,
+		tabs_data: AdminEventLogTabsData,
 	) -> Result<(), SendError<SubscriptionData>> {
+		let patch = self.admin_event_log_tabs_history.lock().unwrap().record(tabs_data);
+		let message = SubscriptionData::AdminEventLogTabsUpdate(patch);
+		self.admin_event_log_tabs_for_event_subscriptions
+			.broadcast_message(event_id, message.clone())
+			.await?;
 		self.admin_event_log_tabs_subscriptions.broadcast_message(message).await
 	}
 
-	/// Checks whether a connection is subscribed to admin event log tabs
-	pub async fn is_subscribed_to_admin_event_log_tabs(&self, connection_id: &str) -> bool {
-		self.admin_event_log_tabs_subscriptions
+	/// Gets the current version of the admin event log tab list
+	pub fn admin_event_log_tabs_list_version(&self) -> ListVersion {
+		self.admin_event_log_tabs_history.lock().unwrap().version()
+	}
+
+	/// Gets the changes made to the admin event log tab list since the given version, if they're all still kept
+	pub fn admin_event_log_tabs_changes_since(
+		&self,
+		version: &ListVersion,
+	) -> Option<Vec<ListPatch<AdminEventLogTabsData>>> {
+		self.admin_event_log_tabs_history.lock().unwrap().changes_since(version)
+	}
+
+	/// Adds to the admin event log tabs subscription for a single event
+	pub async fn add_admin_event_log_tabs_for_event_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_log_tabs_for_event_subscriptions
+			.subscribe(
+				event_id,
+				connection_id,
+				self.client_sync_version(connection_id),
+				update_channel,
+			)
+			.await;
+	}
+
+	/// Removes from the admin event log tabs subscription for a single event
+	pub async fn remove_admin_event_log_tabs_for_event_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_log_tabs_for_event_subscriptions
+			.unsubscribe(event_id, connection_id)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin event log tabs for the given event, either directly or
+	/// through the subscription to all events
+	pub async fn is_subscribed_to_admin_event_log_tabs_for_event(&self, event_id: &str, connection_id: &str) -> bool {
+		self.admin_event_log_tabs_for_event_subscriptions
+			.is_subscribed(event_id, connection_id)
+			.await || self
+			.admin_event_log_tabs_subscriptions
 			.is_subscribed(connection_id)
 			.await
 	}
@@ -439,7 +1093,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_applications_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -471,7 +1125,7 @@ impl SubscriptionManager {
 		update_channel: Sender<ConnectionUpdate>,
 	) {
 		self.admin_info_pages_subscriptions
-			.subscribe(connection_id, update_channel)
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
 			.await;
 	}
 
@@ -496,6 +1150,310 @@ impl SubscriptionManager {
 		self.admin_info_pages_subscriptions.is_subscribed(connection_id).await
 	}
 
+	/// Adds to the admin event access requests subscription
+	pub async fn add_admin_event_access_requests_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_access_requests_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin event access requests subscription
+	pub async fn remove_admin_event_access_requests_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_access_requests_subscriptions
+			.unsubscribe(connection_id)
+			.await
+	}
+
+	/// Sends the given message to all subscribed connections for admin event access requests
+	pub async fn broadcast_admin_event_access_requests_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_event_access_requests_subscriptions
+			.broadcast_message(message)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin event access requests
+	pub async fn is_subscribed_to_admin_event_access_requests(&self, connection_id: &str) -> bool {
+		self.admin_event_access_requests_subscriptions
+			.is_subscribed(connection_id)
+			.await
+	}
+
+	/// Adds to the admin event webhooks subscription
+	pub async fn add_admin_event_webhooks_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_webhooks_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin event webhooks subscription
+	pub async fn remove_admin_event_webhooks_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_webhooks_subscriptions.unsubscribe(connection_id).await
+	}
+
+	/// Sends the given message to all subscribed connections for admin event webhooks
+	pub async fn broadcast_admin_event_webhooks_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_event_webhooks_subscriptions.broadcast_message(message).await
+	}
+
+	/// Checks whether a connection is subscribed to admin event webhooks
+	pub async fn is_subscribed_to_admin_event_webhooks(&self, connection_id: &str) -> bool {
+		self.admin_event_webhooks_subscriptions
+			.is_subscribed(connection_id)
+			.await
+	}
+
+	/// Adds to the admin event custom fields subscription
+	pub async fn add_admin_event_custom_fields_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_custom_fields_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin event custom fields subscription
+	pub async fn remove_admin_event_custom_fields_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_custom_fields_subscriptions
+			.unsubscribe(connection_id)
+			.await
+	}
+
+	/// Sends the given message to all subscribed connections for admin event custom fields
+	pub async fn broadcast_admin_event_custom_fields_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_event_custom_fields_subscriptions
+			.broadcast_message(message)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin event custom fields
+	pub async fn is_subscribed_to_admin_event_custom_fields(&self, connection_id: &str) -> bool {
+		self.admin_event_custom_fields_subscriptions
+			.is_subscribed(connection_id)
+			.await
+	}
+
+	/// Adds to the admin event share links subscription
+	pub async fn add_admin_event_share_links_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_event_share_links_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin event share links subscription
+	pub async fn remove_admin_event_share_links_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_event_share_links_subscriptions
+			.unsubscribe(connection_id)
+			.await
+	}
+
+	/// Sends the given message to all subscribed connections for admin event share links
+	pub async fn broadcast_admin_event_share_links_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_event_share_links_subscriptions
+			.broadcast_message(message)
+			.await
+	}
+
+	/// Checks whether a connection is subscribed to admin event share links
+	pub async fn is_subscribed_to_admin_event_share_links(&self, connection_id: &str) -> bool {
+		self.admin_event_share_links_subscriptions
+			.is_subscribed(connection_id)
+			.await
+	}
+
+	/// Adds to the admin rejected updates subscription
+	pub async fn add_admin_rejected_updates_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_rejected_updates_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin rejected updates subscription
+	pub async fn remove_admin_rejected_updates_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_rejected_updates_subscriptions
+			.unsubscribe(connection_id)
+			.await
+	}
+
+	/// Sends the given message to all subscribed connections for admin rejected updates
+	pub async fn broadcast_admin_rejected_updates_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_rejected_updates_subscriptions
+			.broadcast_message(message)
+			.await
+	}
+
+	/// Adds to the admin audit log subscription
+	pub async fn add_admin_audit_log_subscription(
+		&self,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.admin_audit_log_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin audit log subscription
+	pub async fn remove_admin_audit_log_subscription(
+		&self,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_audit_log_subscriptions.unsubscribe(connection_id).await
+	}
+
+	/// Sends the given message to all subscribed connections for the admin audit log
+	pub async fn broadcast_admin_audit_log_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_audit_log_subscriptions.broadcast_message(message).await
+	}
+
+	/// Adds to the admin background jobs subscription
+	pub async fn add_admin_jobs_subscription(&self, connection_id: &str, update_channel: Sender<ConnectionUpdate>) {
+		self.admin_jobs_subscriptions
+			.subscribe(connection_id, self.client_sync_version(connection_id), update_channel)
+			.await;
+	}
+
+	/// Removes from the admin background jobs subscription
+	pub async fn remove_admin_jobs_subscription(&self, connection_id: &str) -> Result<(), SendError<ConnectionUpdate>> {
+		self.admin_jobs_subscriptions.unsubscribe(connection_id).await
+	}
+
+	/// Sends the given message to all subscribed connections for admin background jobs
+	pub async fn broadcast_admin_jobs_message(
+		&self,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.admin_jobs_subscriptions.broadcast_message(message).await
+	}
+
+	/// Adds to the notification subscription for a user
+	pub async fn add_user_notifications_subscription(
+		&mut self,
+		user_id: &str,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.user_notification_subscriptions
+			.subscribe(
+				user_id,
+				connection_id,
+				self.client_sync_version(connection_id),
+				update_channel,
+			)
+			.await;
+	}
+
+	/// Removes from the notification subscription for a user
+	pub async fn remove_user_notifications_subscription(
+		&mut self,
+		user_id: &str,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.user_notification_subscriptions
+			.unsubscribe(user_id, connection_id)
+			.await
+	}
+
+	/// Sends the given message to all of a user's connections subscribed to their notifications
+	pub async fn broadcast_user_notifications_message(
+		&self,
+		user_id: &str,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.user_notification_subscriptions
+			.broadcast_message(user_id, message)
+			.await
+	}
+
+	/// Adds to the editor workload subscription for an event
+	pub async fn add_editor_workload_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+		update_channel: Sender<ConnectionUpdate>,
+	) {
+		self.editor_workload_subscriptions
+			.subscribe(
+				event_id,
+				connection_id,
+				self.client_sync_version(connection_id),
+				update_channel,
+			)
+			.await;
+	}
+
+	/// Removes from the editor workload subscription for an event
+	pub async fn remove_editor_workload_subscription(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		self.editor_workload_subscriptions
+			.unsubscribe(event_id, connection_id)
+			.await
+	}
+
+	/// Sends the given message to all connections subscribed to the editor workloads for an event
+	pub async fn broadcast_editor_workload_message(
+		&self,
+		event_id: &str,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		self.editor_workload_subscriptions
+			.broadcast_message(event_id, message)
+			.await
+	}
+
 	/// Unsubscribes a connection from all subscriptions
 	pub async fn unsubscribe_from_all(&mut self, connection_id: &str) -> Result<(), SendError<ConnectionUpdate>> {
 		let mut futures = Vec::with_capacity(self.event_subscriptions.len());
@@ -505,6 +1463,9 @@ impl SubscriptionManager {
 		for user_subscription in self.user_subscriptions.values_mut() {
 			user_subscription.remove(connection_id);
 		}
+		self.connections.remove(connection_id);
+		self.connection_sessions.remove(connection_id);
+		self.connection_sync_versions.remove(connection_id);
 		futures.push(self.admin_user_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_event_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_permission_group_subscriptions.unsubscribe(connection_id));
@@ -512,16 +1473,102 @@ impl SubscriptionManager {
 			self.admin_permission_group_user_subscriptions
 				.unsubscribe(connection_id),
 		);
+		futures.push(
+			self.admin_user_event_permission_subscriptions
+				.unsubscribe(connection_id),
+		);
 		futures.push(self.admin_entry_type_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_entry_type_event_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_event_editor_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_applications_subscriptions.unsubscribe(connection_id));
 		futures.push(self.admin_info_pages_subscriptions.unsubscribe(connection_id));
+		futures.push(
+			self.admin_event_access_requests_subscriptions
+				.unsubscribe(connection_id),
+		);
+		futures.push(self.admin_event_webhooks_subscriptions.unsubscribe(connection_id));
+		futures.push(self.admin_event_custom_fields_subscriptions.unsubscribe(connection_id));
+		futures.push(self.admin_event_share_links_subscriptions.unsubscribe(connection_id));
+		futures.push(self.admin_rejected_updates_subscriptions.unsubscribe(connection_id));
+		futures.push(self.admin_audit_log_subscriptions.unsubscribe(connection_id));
+		futures.push(self.admin_jobs_subscriptions.unsubscribe(connection_id));
 
 		let results = join_all(futures).await;
+		let viewed_event_ids: Vec<String> = self
+			.event_viewers
+			.iter()
+			.filter(|(_, event_viewers)| event_viewers.viewers_by_connection.contains_key(connection_id))
+			.map(|(event_id, _)| event_id.clone())
+			.collect();
+		for event_id in viewed_event_ids.iter() {
+			self.remove_event_viewer(event_id, connection_id).await;
+		}
+		self.remove_unused_event_subscriptions().await;
+		let editors_for_event_result = self
+			.admin_event_editor_for_event_subscriptions
+			.unsubscribe_from_all(connection_id)
+			.await;
+		let tabs_for_event_result = self
+			.admin_event_log_tabs_for_event_subscriptions
+			.unsubscribe_from_all(connection_id)
+			.await;
+		let notifications_result = self
+			.user_notification_subscriptions
+			.unsubscribe_from_all(connection_id)
+			.await;
+		let editor_workload_result = self
+			.editor_workload_subscriptions
+			.unsubscribe_from_all(connection_id)
+			.await;
 		for result in results {
 			result?;
 		}
+		editors_for_event_result?;
+		tabs_for_event_result?;
+		notifications_result?;
+		editor_workload_result?;
 		Ok(())
 	}
 }
+
+/// Checks whether a message sent to event subscribers changes a log entry
+fn changes_log_entry(message: &SubscriptionData) -> bool {
+	let SubscriptionData::EventUpdate(_, event_data) = message else {
+		return false;
+	};
+	matches!(
+		event_data.as_ref(),
+		EventSubscriptionData::UpdateLogEntry(_, _) | EventSubscriptionData::DeleteLogEntry(_)
+	)
+}
+
+/// The number of subscriptions of one type and the number of broadcast messages waiting to be sent to them
+pub struct SubscriptionStats {
+	pub subscription_type: &'static str,
+	pub subscriptions: usize,
+	pub queued_broadcasts: usize,
+}
+
+/// The users viewing an event
+struct EventViewers {
+	/// The event being viewed, used when telling viewers that the users viewing it changed
+	event: Event,
+	/// The user of each connection subscribed to the event, by connection ID
+	viewers_by_connection: HashMap<String, PublicUserData>,
+	/// The edit locks on the event's entries by entry ID
+	entry_locks: HashMap<String, HeldEntryLock>,
+}
+
+/// An entry edit lock along with the connection that holds it, so the lock can be released when the connection goes
+/// away
+struct HeldEntryLock {
+	connection_id: String,
+	lock: EntryEditLock,
+}
+
+impl EventViewers {
+	/// Checks whether the user with the given ID is viewing the event from any connection
+	fn is_viewing(&self, user_id: &str) -> bool {
+		self.viewers_by_connection.values().any(|user| user.id == user_id)
+	}
+}
@@ -0,0 +1,142 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::one_subscription::SingleSubscriptionManager;
+use crate::data_sync::connection::ConnectionUpdate;
+use async_std::channel::{SendError, Sender};
+use futures::future::join_all;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use stream_log_shared::messages::subscriptions::{SubscriptionData, SubscriptionType};
+
+/// Manages a kind of subscription that's made separately for each event
+pub struct EventScopedSubscriptionManager {
+	/// Creates the subscription type for an event ID
+	subscription_type: fn(String) -> SubscriptionType,
+	subscriptions: HashMap<String, SingleSubscriptionManager>,
+}
+
+impl EventScopedSubscriptionManager {
+	pub fn new(subscription_type: fn(String) -> SubscriptionType) -> Self {
+		Self {
+			subscription_type,
+			subscriptions: HashMap::new(),
+		}
+	}
+
+	pub async fn subscribe(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+		client_sync_version: u32,
+		channel: Sender<ConnectionUpdate>,
+	) {
+		match self.subscriptions.entry(event_id.to_string()) {
+			Entry::Occupied(event_subscription) => {
+				event_subscription
+					.get()
+					.subscribe(connection_id, client_sync_version, channel)
+					.await
+			}
+			Entry::Vacant(event_entry) => {
+				let event_subscription = SingleSubscriptionManager::new((self.subscription_type)(event_id.to_string()));
+				event_subscription
+					.subscribe(connection_id, client_sync_version, channel)
+					.await;
+				event_entry.insert(event_subscription);
+			}
+		}
+	}
+
+	pub async fn unsubscribe(
+		&mut self,
+		event_id: &str,
+		connection_id: &str,
+	) -> Result<(), SendError<ConnectionUpdate>> {
+		let result = match self.subscriptions.get(event_id) {
+			Some(event_subscription) => event_subscription.unsubscribe(connection_id).await,
+			None => Ok(()),
+		};
+		self.remove_unused_subscriptions().await;
+		result
+	}
+
+	/// Unsubscribes a connection from the subscriptions for all events
+	pub async fn unsubscribe_from_all(&mut self, connection_id: &str) -> Result<(), SendError<ConnectionUpdate>> {
+		let mut result = Ok(());
+		for event_subscription in self.subscriptions.values() {
+			let unsubscribe_result = event_subscription.unsubscribe(connection_id).await;
+			if unsubscribe_result.is_err() {
+				result = unsubscribe_result;
+			}
+		}
+		self.remove_unused_subscriptions().await;
+		result
+	}
+
+	pub async fn is_subscribed(&self, event_id: &str, connection_id: &str) -> bool {
+		match self.subscriptions.get(event_id) {
+			Some(event_subscription) => event_subscription.is_subscribed(connection_id).await,
+			None => false,
+		}
+	}
+
+	/// Checks whether any connections are subscribed for the given event
+	pub fn has_subscriptions_for(&self, event_id: &str) -> bool {
+		self.subscriptions.contains_key(event_id)
+	}
+
+	/// Gets the number of subscriptions across all events
+	pub async fn subscription_count(&self) -> usize {
+		let mut count = 0;
+		for event_subscription in self.subscriptions.values() {
+			count += event_subscription.subscription_count().await;
+		}
+		count
+	}
+
+	/// Gets the number of broadcast messages across all events that haven't yet been sent to subscribers
+	pub fn queued_broadcast_count(&self) -> usize {
+		self.subscriptions
+			.values()
+			.map(|event_subscription| event_subscription.queued_broadcast_count())
+			.sum()
+	}
+
+	pub async fn broadcast_message(
+		&self,
+		event_id: &str,
+		message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		match self.subscriptions.get(event_id) {
+			Some(event_subscription) => event_subscription.broadcast_message(message).await,
+			None => Ok(()),
+		}
+	}
+
+	pub async fn shutdown(mut self) {
+		let shutdown_futures = self
+			.subscriptions
+			.drain()
+			.map(|(_, event_subscription)| event_subscription.shutdown());
+		join_all(shutdown_futures).await;
+	}
+
+	/// Stops tracking events that no longer have any subscribed connections
+	async fn remove_unused_subscriptions(&mut self) {
+		let mut unused_event_ids: Vec<String> = Vec::new();
+		for (event_id, event_subscription) in self.subscriptions.iter() {
+			if !event_subscription.has_subscriptions().await {
+				unused_event_ids.push(event_id.clone());
+			}
+		}
+		for event_id in unused_event_ids.iter() {
+			if let Some(event_subscription) = self.subscriptions.remove(event_id) {
+				event_subscription.shutdown().await;
+			}
+		}
+	}
+}
@@ -0,0 +1,74 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use stream_log_shared::messages::list_sync::{ListPatch, ListVersion};
+
+/// The number of recent changes kept for each synced list. Clients that are further behind than this are sent the
+/// whole list instead.
+const KEPT_CHANGE_COUNT: usize = 1000;
+
+/// Tracks the version of a list that's kept in sync with clients along with its most recent changes, so that clients
+/// that already have most of the list can be sent only what changed
+pub struct ListHistory<C> {
+	history_id: String,
+	version: u64,
+	recent_changes: VecDeque<ListPatch<C>>,
+}
+
+impl<C: Clone> ListHistory<C> {
+	pub fn new() -> Self {
+		Self {
+			history_id: cuid2::create_id(),
+			version: 0,
+			recent_changes: VecDeque::new(),
+		}
+	}
+
+	/// Gets the current version of the list
+	pub fn version(&self) -> ListVersion {
+		ListVersion {
+			history_id: self.history_id.clone(),
+			number: self.version,
+		}
+	}
+
+	/// Records a change to the list. Returns the patch to send to subscribers.
+	pub fn record(&mut self, change: C) -> ListPatch<C> {
+		self.version += 1;
+		let patch = ListPatch {
+			version: self.version,
+			change,
+		};
+		if self.recent_changes.len() >= KEPT_CHANGE_COUNT {
+			self.recent_changes.pop_front();
+		}
+		self.recent_changes.push_back(patch.clone());
+		patch
+	}
+
+	/// Gets the changes made to the list after the given version, in order. If some of those changes are no longer
+	/// kept, or if the version isn't from this history, returns None.
+	pub fn changes_since(&self, version: &ListVersion) -> Option<Vec<ListPatch<C>>> {
+		if version.history_id != self.history_id || version.number > self.version {
+			return None;
+		}
+		if version.number == self.version {
+			return Some(Vec::new());
+		}
+		let oldest_kept_version = self.recent_changes.front()?.version;
+		if oldest_kept_version > version.number + 1 {
+			return None;
+		}
+		let changes = self
+			.recent_changes
+			.iter()
+			.filter(|patch| patch.version > version.number)
+			.cloned()
+			.collect();
+		Some(changes)
+	}
+}
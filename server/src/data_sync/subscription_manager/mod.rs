@@ -5,6 +5,8 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 mod all_subscriptions;
+mod event_scoped_subscriptions;
+mod list_history;
 mod one_subscription;
 
 pub use all_subscriptions::SubscriptionManager;
@@ -4,39 +4,76 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::data_sync::connection::ConnectionUpdate;
+use crate::data_sync::connection::{ConnectionUpdate, PermissionRestrictedData};
+use crate::models::Permission;
 use async_std::channel::{unbounded, SendError, Sender};
 use async_std::stream::StreamExt;
 use async_std::sync::{Arc, Mutex};
 use async_std::task::{spawn, JoinHandle};
 use std::collections::HashMap;
-use stream_log_shared::messages::subscriptions::{SubscriptionData, SubscriptionType};
+use stream_log_shared::client_sync_version_is_supported;
+use stream_log_shared::messages::subscriptions::{SubscriptionData, SubscriptionSequence, SubscriptionType};
 use stream_log_shared::messages::FromServerMessage;
 
 /// Manages subscriptions for a single set of subscription events
 pub struct SingleSubscriptionManager {
 	subscription_type: SubscriptionType,
-	pub thread_handle: JoinHandle<()>,
-	subscription_send_channel: Sender<SubscriptionData>,
+	thread_handle: JoinHandle<()>,
+	subscription_send_channel: Sender<BroadcastMessage>,
 	subscriptions: Arc<Mutex<HashMap<String, SingleSubscriptionData>>>,
 }
 
 impl SingleSubscriptionManager {
 	pub fn new(subscription_type: SubscriptionType) -> Self {
-		let (broadcast_tx, mut broadcast_rx) = unbounded::<SubscriptionData>();
+		let (broadcast_tx, mut broadcast_rx) = unbounded::<BroadcastMessage>();
 		let subscriptions: Arc<Mutex<HashMap<String, SingleSubscriptionData>>> = Arc::new(Mutex::new(HashMap::new()));
 		let thread_handle = spawn({
 			let subscriptions = Arc::clone(&subscriptions);
+			let subscription_type = subscription_type.clone();
 			async move {
 				while let Some(broadcast_msg) = broadcast_rx.next().await {
 					let mut subscriptions = subscriptions.lock().await;
 					let mut dead_connection_users: Vec<String> = Vec::new();
-					for (user_id, user_subscription) in subscriptions.iter() {
-						let message = FromServerMessage::SubscriptionMessage(Box::new(broadcast_msg.clone()));
-						let send_result = user_subscription
-							.channel
-							.send(ConnectionUpdate::SendData(Box::new(message)))
-							.await;
+					for (user_id, user_subscription) in subscriptions.iter_mut() {
+						// Clients the server doesn't support have to be reloaded before they can read anything they're
+						// sent, so they aren't sent broadcasts
+						if !client_sync_version_is_supported(user_subscription.client_sync_version) {
+							continue;
+						}
+						user_subscription.sequence_number += 1;
+						let sequence = SubscriptionSequence {
+							subscription_type: subscription_type.clone(),
+							number: user_subscription.sequence_number,
+						};
+						let update = match &broadcast_msg {
+							BroadcastMessage::All(message) => {
+								let message =
+									FromServerMessage::SubscriptionMessage(Box::new(message.clone()), Some(sequence));
+								ConnectionUpdate::SendData(Box::new(message))
+							}
+							BroadcastMessage::ByEventPermission {
+								event_id,
+								minimum_permission,
+								message,
+								alternate_message,
+							} => {
+								let message = FromServerMessage::SubscriptionMessage(
+									Box::new(message.clone()),
+									Some(sequence.clone()),
+								);
+								let alternate_message = FromServerMessage::SubscriptionMessage(
+									Box::new(alternate_message.clone()),
+									Some(sequence),
+								);
+								ConnectionUpdate::SendDataByEventPermission(Box::new(PermissionRestrictedData {
+									event_id: event_id.clone(),
+									minimum_permission: *minimum_permission,
+									data: Box::new(message),
+									alternate_data: Box::new(alternate_message),
+								}))
+							}
+						};
+						let send_result = user_subscription.channel.send(update).await;
 						if send_result.is_err() {
 							dead_connection_users.push(user_id.clone());
 						}
@@ -56,9 +93,13 @@ impl SingleSubscriptionManager {
 		}
 	}
 
-	pub async fn subscribe(&self, connection_id: &str, channel: Sender<ConnectionUpdate>) {
+	pub async fn subscribe(&self, connection_id: &str, client_sync_version: u32, channel: Sender<ConnectionUpdate>) {
 		let mut subscriptions = self.subscriptions.lock().await;
-		let subscription_data = SingleSubscriptionData { channel };
+		let subscription_data = SingleSubscriptionData {
+			channel,
+			client_sync_version,
+			sequence_number: 0,
+		};
 		subscriptions.insert(connection_id.to_owned(), subscription_data);
 	}
 
@@ -77,23 +118,86 @@ impl SingleSubscriptionManager {
 		self.subscriptions.lock().await.contains_key(connection_id)
 	}
 
+	pub async fn has_subscriptions(&self) -> bool {
+		!self.subscriptions.lock().await.is_empty()
+	}
+
+	pub async fn subscription_count(&self) -> usize {
+		self.subscriptions.lock().await.len()
+	}
+
+	/// Gets the number of broadcast messages that haven't yet been sent to subscribers
+	pub fn queued_broadcast_count(&self) -> usize {
+		self.subscription_send_channel.len()
+	}
+
 	pub async fn broadcast_message(&self, message: SubscriptionData) -> Result<(), SendError<SubscriptionData>> {
-		self.subscription_send_channel.send(message).await
+		self.subscription_send_channel
+			.send(BroadcastMessage::All(message))
+			.await
+			.map_err(|error| SendError(error.0.into_message()))
+	}
+
+	/// Broadcasts a message that only connections whose users have at least the given permission level for the event
+	/// receive. Other connections receive the alternate message instead.
+	pub async fn broadcast_message_by_event_permission(
+		&self,
+		event_id: &str,
+		minimum_permission: Permission,
+		message: SubscriptionData,
+		alternate_message: SubscriptionData,
+	) -> Result<(), SendError<SubscriptionData>> {
+		let broadcast_message = BroadcastMessage::ByEventPermission {
+			event_id: event_id.to_string(),
+			minimum_permission,
+			message,
+			alternate_message,
+		};
+		self.subscription_send_channel
+			.send(broadcast_message)
+			.await
+			.map_err(|error| SendError(error.0.into_message()))
 	}
 
-	pub async fn shutdown(self) -> JoinHandle<()> {
+	/// Ends the subscription for all subscribed connections. Broadcasts that are still queued are sent to them first.
+	pub async fn shutdown(self) {
+		self.subscription_send_channel.close();
+		self.thread_handle.await;
+
 		let mut subscriptions = self.subscriptions.lock().await;
-		let mut send_futures = Vec::new();
 		for (_, subscription_data) in subscriptions.drain() {
 			let message = ConnectionUpdate::SendData(Box::new(FromServerMessage::Unsubscribed(
 				self.subscription_type.clone(),
 			)));
-			send_futures.push(subscription_data.channel.send(message).await);
+			let _ = subscription_data.channel.send(message).await;
+		}
+	}
+}
+
+/// A message to send to the subscribed connections
+enum BroadcastMessage {
+	All(SubscriptionData),
+	ByEventPermission {
+		event_id: String,
+		minimum_permission: Permission,
+		message: SubscriptionData,
+		alternate_message: SubscriptionData,
+	},
+}
+
+impl BroadcastMessage {
+	fn into_message(self) -> SubscriptionData {
+		match self {
+			Self::All(message) => message,
+			Self::ByEventPermission { message, .. } => message,
 		}
-		self.thread_handle
 	}
 }
 
 struct SingleSubscriptionData {
 	channel: Sender<ConnectionUpdate>,
+	/// The sync version of the connection's client
+	client_sync_version: u32,
+	/// Sequence number of the last message broadcast to this connection for this subscription
+	sequence_number: u64,
 }
@@ -0,0 +1,261 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use super::user_notifications::send_user_notification;
+use crate::data_sync::user::UserDataUpdate;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{
+	Event as EventDb, EventAccessRequest as EventAccessRequestDb, Permission, PermissionEvent, User, UserPermission,
+};
+use crate::schema::{event_access_requests, events, permission_events, user_permissions, users};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEventAccessRequestData, AdminEventAccessRequestUpdate, AdminUserPermissionGroupData,
+	EventAccessRequest, UserPermissionGroupAssociation,
+};
+use stream_log_shared::messages::notifications::UserNotificationDetails;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_event_access_requests(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminEventAccessRequests,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let requests: Option<Vec<(EventAccessRequestDb, User, EventDb)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventAccessRequests,
+		"retrieving event access requests for admin subscription",
+		|db_connection| {
+			event_access_requests::table
+				.inner_join(users::table)
+				.inner_join(events::table)
+				.order(event_access_requests::requested_at.asc())
+				.load(db_connection)
+		},
+	)
+	.await?;
+	let Some(requests) = requests else {
+		return Ok(());
+	};
+	let requests: Vec<EventAccessRequest> = requests
+		.into_iter()
+		.map(|(request, user, event)| EventAccessRequest {
+			user: user.into(),
+			event: event.into(),
+			requested_at: request.requested_at,
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_event_access_requests_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminEventAccessRequests(requests),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_admin_event_access_requests_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: AdminEventAccessRequestUpdate,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_event_access_requests(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	match update_message {
+		AdminEventAccessRequestUpdate::Approve(request, group) => {
+			let group_event_permission: Option<PermissionEvent> = run_db_operation(
+				&db_connection_pool,
+				"checking permission group access for an event access request",
+				|db_connection| {
+					permission_events::table
+						.find((&group.id, &request.event.id))
+						.first(db_connection)
+						.optional()
+				},
+			)
+			.await?;
+			if group_event_permission.is_none() {
+				return Err(HandlerError::InvalidData(String::from(
+					"The selected permission group doesn't have access to the event.",
+				)));
+			}
+
+			let (added_to_group, event_permissions): (bool, Vec<Permission>) = run_db_operation(
+				&db_connection_pool,
+				"approving an event access request",
+				|db_connection| {
+					db_connection.transaction(|db_connection| {
+						let removed_requests =
+							diesel::delete(event_access_requests::table.find((&request.user.id, &request.event.id)))
+								.execute(db_connection)?;
+						if removed_requests == 0 {
+							return Err(diesel::result::Error::NotFound);
+						}
+
+						let user_permission = UserPermission {
+							user_id: request.user.id.clone(),
+							permission_group: group.id.clone(),
+						};
+						let added_permissions = diesel::insert_into(user_permissions::table)
+							.values(&user_permission)
+							.on_conflict_do_nothing()
+							.execute(db_connection)?;
+
+						let event_permissions: Vec<Permission> = permission_events::table
+							.inner_join(
+								user_permissions::table
+									.on(user_permissions::permission_group.eq(permission_events::permission_group)),
+							)
+							.filter(
+								permission_events::event
+									.eq(&request.event.id)
+									.and(user_permissions::user_id.eq(&request.user.id)),
+							)
+							.select(permission_events::level)
+							.load(db_connection)?;
+
+						Ok((added_permissions > 0, event_permissions))
+					})
+				},
+			)
+			.await?;
+
+			let highest_permission_level = Permission::highest(event_permissions);
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::AccessRequest,
+				format!(
+					"Approved the request from \"{}\" to access event \"{}\" with permission group \"{}\"",
+					request.user.username, request.event.name, group.name
+				),
+			)
+			.await;
+
+			if highest_permission_level.is_some() {
+				send_user_notification(
+					&db_connection_pool,
+					&subscription_manager,
+					&request.user.id,
+					&request.event,
+					UserNotificationDetails::AddedToEvent,
+				)
+				.await;
+			}
+
+			let mut subscription_manager = subscription_manager.lock().await;
+			let user_message = UserDataUpdate::EventPermissions(request.event.clone(), highest_permission_level);
+			subscription_manager
+				.send_message_to_user(&request.user.id, user_message)
+				.await;
+
+			if added_to_group {
+				let association = UserPermissionGroupAssociation {
+					user: request.user.clone(),
+					permission_group: group,
+				};
+				let admin_message = SubscriptionData::AdminUserPermissionGroupsUpdate(
+					AdminUserPermissionGroupData::AddUserToGroup(association),
+				);
+				let send_result = subscription_manager
+					.broadcast_admin_permission_group_users_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!(
+						"Failed to broadcast permission group user addition for an approved access request: {}",
+						error
+					);
+				}
+			}
+
+			let admin_message =
+				SubscriptionData::AdminEventAccessRequestsUpdate(AdminEventAccessRequestData::RemoveRequest(request));
+			let send_result = subscription_manager
+				.broadcast_admin_event_access_requests_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast approved event access request: {}", error);
+			}
+		}
+		AdminEventAccessRequestUpdate::Deny(request) => {
+			run_db_operation(
+				&db_connection_pool,
+				"denying an event access request",
+				|db_connection| {
+					diesel::delete(event_access_requests::table.find((&request.user.id, &request.event.id)))
+						.execute(db_connection)
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::AccessRequest,
+				format!(
+					"Denied the request from \"{}\" to access event \"{}\"",
+					request.user.username, request.event.name
+				),
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			let admin_message =
+				SubscriptionData::AdminEventAccessRequestsUpdate(AdminEventAccessRequestData::RemoveRequest(request));
+			let send_result = subscription_manager
+				.broadcast_admin_event_access_requests_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast denied event access request: {}", error);
+			}
+		}
+	}
+
+	Ok(())
+}
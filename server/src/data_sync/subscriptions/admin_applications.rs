@@ -4,10 +4,11 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
-use crate::models::Application as ApplicationDb;
-use crate::schema::applications;
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Application as ApplicationDb, ApplicationEvent};
+use crate::schema::{application_events, applications};
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
 use base64::engine::general_purpose::STANDARD_NO_PAD as base64_engine;
@@ -15,12 +16,13 @@ use base64::Engine;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use rand::random;
-use stream_log_shared::messages::admin::{AdminApplicationData, AdminApplicationUpdate, Application};
+use std::collections::HashMap;
+use stream_log_shared::messages::admin::{AdminActionType, AdminApplicationData, AdminApplicationUpdate, Application};
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_applications(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -40,34 +42,39 @@ pub async fn subscribe_to_admin_applications(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminApplications)
-				.await?;
-			return Ok(());
-		}
-	};
-	let applications: QueryResult<Vec<ApplicationDb>> = applications::table
-		.filter(applications::auth_key.is_not_null())
-		.load(&mut *db_connection);
-	let applications: Vec<Application> = match applications {
-		Ok(apps) => apps.into_iter().map(|app| app.into()).collect(),
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred loading applications for admin subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminApplications,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let applications: Option<(Vec<ApplicationDb>, Vec<ApplicationEvent>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminApplications,
+		"loading applications for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let applications: Vec<ApplicationDb> = applications::table
+					.filter(applications::auth_key.is_not_null())
+					.load(db_connection)?;
+				let application_events: Vec<ApplicationEvent> = application_events::table.load(db_connection)?;
+				Ok((applications, application_events))
+			})
+		},
+	)
+	.await?;
+	let Some((apps, app_events)) = applications else {
+		return Ok(());
 	};
+	let mut event_scopes: HashMap<String, Vec<String>> = HashMap::new();
+	for app_event in app_events {
+		event_scopes
+			.entry(app_event.application)
+			.or_default()
+			.push(app_event.event);
+	}
+	let applications: Vec<Application> = apps
+		.into_iter()
+		.map(|app| {
+			let event_scope = event_scopes.remove(&app.id).unwrap_or_default();
+			app.into_client_data(event_scope)
+		})
+		.collect();
 
 	let subscription_manager = subscription_manager.lock().await;
 	subscription_manager
@@ -91,9 +98,9 @@ pub async fn handle_admin_applications_message(
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminApplicationUpdate,
 	conn_update_tx: Sender<ConnectionUpdate>,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -101,7 +108,7 @@ pub async fn handle_admin_applications_message(
 		.is_subscribed_to_admin_applications(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
@@ -116,27 +123,31 @@ pub async fn handle_admin_applications_message(
 					read_log: application.read_log,
 					write_links: application.write_links,
 					creation_user: user.id.clone(),
+					write_processing_state: application.write_processing_state,
+					restrict_events: application.event_scope.is_some(),
+					write_entries: application.write_entries,
 				};
+				let db_application_events = application_events_for_scope(&application);
 
-				let insert_result: QueryResult<_> = {
-					let mut db_connection = match db_connection_pool.get() {
-						Ok(connection) => connection,
-						Err(error) => {
-							tide::log::error!(
-								"A database connection error occurred adding a new application: {}",
-								error
-							);
-							return;
-						}
-					};
-					diesel::insert_into(applications::table)
-						.values(db_application)
-						.execute(&mut *db_connection)
-				};
-				if let Err(error) = insert_result {
-					tide::log::error!("A database error occurred adding a new application: {}", error);
-					return;
-				}
+				run_db_operation(&db_connection_pool, "adding a new application", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						diesel::insert_into(applications::table)
+							.values(&db_application)
+							.execute(db_connection)?;
+						diesel::insert_into(application_events::table)
+							.values(&db_application_events)
+							.execute(db_connection)
+					})
+				})
+				.await?;
+				record_admin_action(
+					&db_connection_pool,
+					&subscription_manager,
+					user,
+					AdminActionType::Application,
+					format!("Created application \"{}\"", application.name),
+				)
+				.await;
 
 				let subscription_manager = subscription_manager.lock().await;
 				let message = SubscriptionData::AdminApplicationsUpdate(AdminApplicationData::UpdateApplication(
@@ -147,39 +158,48 @@ pub async fn handle_admin_applications_message(
 					tide::log::error!("Failed to send new application to admin subscription: {}", error);
 				}
 
-				let message =
-					FromServerMessage::SubscriptionMessage(Box::new(SubscriptionData::AdminApplicationsUpdate(
+				let message = FromServerMessage::SubscriptionMessage(
+					Box::new(SubscriptionData::AdminApplicationsUpdate(
 						AdminApplicationData::ShowApplicationAuthKey(application, auth_key),
-					)));
+					)),
+					None,
+				);
 				let send_result = conn_update_tx.send(ConnectionUpdate::SendData(Box::new(message))).await;
 				if let Err(error) = send_result {
 					tide::log::error!("Failed to send application auth key message: {}", error);
 				}
 			} else {
-				let update_result: QueryResult<_> = {
-					let mut db_connection = match db_connection_pool.get() {
-						Ok(connection) => connection,
-						Err(error) => {
-							tide::log::error!(
-								"A database connection error occurred updating an application: {}",
-								error
-							);
-							return;
-						}
-					};
-					diesel::update(applications::table)
-						.filter(applications::id.eq(&application.id))
-						.set((
-							applications::name.eq(&application.name),
-							applications::read_log.eq(application.read_log),
-							applications::write_links.eq(application.write_links),
-						))
-						.execute(&mut *db_connection)
-				};
-				if let Err(error) = update_result {
-					tide::log::error!("A database error occurred updating an application: {}", error);
-					return;
-				}
+				let db_application_events = application_events_for_scope(&application);
+				run_db_operation(&db_connection_pool, "updating an application", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						diesel::update(applications::table)
+							.filter(applications::id.eq(&application.id))
+							.set((
+								applications::name.eq(&application.name),
+								applications::read_log.eq(application.read_log),
+								applications::write_links.eq(application.write_links),
+								applications::write_processing_state.eq(application.write_processing_state),
+								applications::restrict_events.eq(application.event_scope.is_some()),
+								applications::write_entries.eq(application.write_entries),
+							))
+							.execute(db_connection)?;
+						diesel::delete(application_events::table)
+							.filter(application_events::application.eq(&application.id))
+							.execute(db_connection)?;
+						diesel::insert_into(application_events::table)
+							.values(&db_application_events)
+							.execute(db_connection)
+					})
+				})
+				.await?;
+				record_admin_action(
+					&db_connection_pool,
+					&subscription_manager,
+					user,
+					AdminActionType::Application,
+					format!("Updated application \"{}\"", application.name),
+				)
+				.await;
 
 				let subscription_manager = subscription_manager.lock().await;
 				let message =
@@ -192,57 +212,54 @@ pub async fn handle_admin_applications_message(
 		}
 		AdminApplicationUpdate::ResetAuthToken(application) => {
 			let new_auth_key = generate_application_auth_key();
-			let update_result = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred resetting an application auth key: {}",
-							error
-						);
-						return;
-					}
-				};
-				diesel::update(applications::table)
-					.filter(applications::id.eq(&application.id))
-					.set(applications::auth_key.eq(&new_auth_key))
-					.execute(&mut *db_connection)
-			};
-			if let Err(error) = update_result {
-				tide::log::error!("A database error occurred resetting an application auth key: {}", error);
-				return;
-			}
+			run_db_operation(
+				&db_connection_pool,
+				"resetting an application auth key",
+				|db_connection| {
+					diesel::update(applications::table)
+						.filter(applications::id.eq(&application.id))
+						.set(applications::auth_key.eq(&new_auth_key))
+						.execute(db_connection)
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Application,
+				format!("Reset the authorization key for application \"{}\"", application.name),
+			)
+			.await;
 
-			let message = FromServerMessage::SubscriptionMessage(Box::new(SubscriptionData::AdminApplicationsUpdate(
-				AdminApplicationData::ShowApplicationAuthKey(application, new_auth_key),
-			)));
+			let message = FromServerMessage::SubscriptionMessage(
+				Box::new(SubscriptionData::AdminApplicationsUpdate(
+					AdminApplicationData::ShowApplicationAuthKey(application, new_auth_key),
+				)),
+				None,
+			);
 			let send_result = conn_update_tx.send(ConnectionUpdate::SendData(Box::new(message))).await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send application auth key message: {}", error);
 			}
 		}
 		AdminApplicationUpdate::RevokeApplication(application) => {
-			let update_result = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred revoking an application: {}",
-							error
-						);
-						return;
-					}
-				};
+			run_db_operation(&db_connection_pool, "revoking an application", |db_connection| {
 				let null_auth_key: Option<String> = None;
 				diesel::update(applications::table)
 					.filter(applications::id.eq(&application.id))
 					.set(applications::auth_key.eq(null_auth_key))
-					.execute(&mut *db_connection)
-			};
-			if let Err(error) = update_result {
-				tide::log::error!("A database error occurred revoking an application: {}", error);
-				return;
-			}
+					.execute(db_connection)
+			})
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Application,
+				format!("Revoked application \"{}\"", application.name),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let message =
@@ -253,6 +270,21 @@ pub async fn handle_admin_applications_message(
 			}
 		}
 	}
+
+	Ok(())
+}
+
+/// Gets the database records associating an application with the events in its event scope
+fn application_events_for_scope(application: &Application) -> Vec<ApplicationEvent> {
+	application
+		.event_scope
+		.iter()
+		.flatten()
+		.map(|event_id| ApplicationEvent {
+			application: application.id.clone(),
+			event: event_id.clone(),
+		})
+		.collect()
 }
 
 /// Generates a new authorization key for an application.
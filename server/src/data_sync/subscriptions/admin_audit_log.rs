@@ -0,0 +1,145 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use crate::models::{AdminAuditLogEntry as AdminAuditLogEntryDb, User};
+use crate::schema::{admin_audit_log, users};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::{AdminActionType, AdminAuditLogData, AdminAuditLogEntry};
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// The number of most recent audit log entries sent to administrators when they subscribe
+const INITIAL_AUDIT_LOG_ENTRY_COUNT: i64 = 1000;
+
+pub async fn subscribe_to_admin_audit_log(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminAuditLog,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let audit_log_entries: Option<Vec<(AdminAuditLogEntryDb, User)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminAuditLog,
+		"retrieving the audit log for admin subscription",
+		|db_connection| {
+			admin_audit_log::table
+				.inner_join(users::table)
+				.order(admin_audit_log::performed_at.desc())
+				.limit(INITIAL_AUDIT_LOG_ENTRY_COUNT)
+				.load(db_connection)
+		},
+	)
+	.await?;
+	let Some(audit_log_entries) = audit_log_entries else {
+		return Ok(());
+	};
+	let audit_log_entries: Vec<AdminAuditLogEntry> = audit_log_entries
+		.into_iter()
+		.map(|(entry, actor)| AdminAuditLogEntry {
+			id: entry.id,
+			actor: actor.into(),
+			action_type: entry.action_type.into(),
+			description: entry.description,
+			performed_at: entry.performed_at,
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_audit_log_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminAuditLog(
+		audit_log_entries,
+	)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+/// Records a change an administrator made. The change is written to the server log and stored in the audit log.
+///
+/// The subscription manager is locked to send the new record to subscribed administrators, so it must not already be
+/// locked by the caller.
+pub async fn record_admin_action(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	actor: &SelfUserData,
+	action_type: AdminActionType,
+	description: String,
+) {
+	tide::log::info!(
+		"Administrative change by {} ({}): [{}] {}",
+		actor.username,
+		actor.id,
+		action_type.name(),
+		description
+	);
+
+	let audit_log_entry = AdminAuditLogEntry {
+		id: cuid2::create_id(),
+		actor: actor.clone().into(),
+		action_type,
+		description,
+		performed_at: Utc::now(),
+	};
+	let audit_log_entry_db = AdminAuditLogEntryDb {
+		id: audit_log_entry.id.clone(),
+		actor: audit_log_entry.actor.id.clone(),
+		action_type: audit_log_entry.action_type.into(),
+		description: audit_log_entry.description.clone(),
+		performed_at: audit_log_entry.performed_at,
+	};
+
+	let insert_result = run_db_operation(
+		db_connection_pool,
+		"recording an administrative change",
+		|db_connection| {
+			diesel::insert_into(admin_audit_log::table)
+				.values(&audit_log_entry_db)
+				.execute(db_connection)
+		},
+	)
+	.await;
+	if let Err(error) = insert_result {
+		tide::log::error!("{}", error);
+		return;
+	}
+
+	let message = SubscriptionData::AdminAuditLogUpdate(AdminAuditLogData::NewEntry(audit_log_entry));
+	let send_result = subscription_manager
+		.lock()
+		.await
+		.broadcast_admin_audit_log_message(message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to broadcast an audit log entry to administrators: {}", error);
+	}
+}
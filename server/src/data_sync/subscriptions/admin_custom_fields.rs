@@ -0,0 +1,278 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, EventCustomField as EventCustomFieldDb};
+use crate::schema::{event_custom_fields, event_log_custom_field_values, events};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEventCustomFieldData, AdminEventCustomFieldUpdate, EventCustomField,
+};
+use stream_log_shared::messages::custom_fields::CustomFieldType;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_event_custom_fields(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminEventCustomFields,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let query_result: Option<(Vec<EventDb>, Vec<EventCustomFieldDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventCustomFields,
+		"retrieving custom fields for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let fields: Vec<EventCustomFieldDb> = event_custom_fields::table
+					.order(event_custom_fields::name.asc())
+					.load(db_connection)?;
+				let events: Vec<EventDb> = events::table.load(db_connection)?;
+				Ok((events, fields))
+			})
+		},
+	)
+	.await?;
+	let Some((events, fields)) = query_result else {
+		return Ok(());
+	};
+
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
+		.collect();
+
+	let fields: Vec<EventCustomField> = fields
+		.into_iter()
+		.map(|field| EventCustomField {
+			event: events.get(&field.event).unwrap().clone(),
+			field: field.into(),
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_event_custom_fields_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminEventCustomFields(fields),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_admin_event_custom_fields_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: AdminEventCustomFieldUpdate,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_event_custom_fields(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	match update_message {
+		AdminEventCustomFieldUpdate::UpdateField(mut field_data) => {
+			field_data.field.name = field_data.field.name.trim().to_string();
+			if field_data.field.name.is_empty() {
+				return Err(HandlerError::InvalidData(String::from(
+					"Custom fields must have a name.",
+				)));
+			}
+			if let CustomFieldType::Select(options) = &mut field_data.field.field_type {
+				for option in options.iter_mut() {
+					*option = option.trim().to_string();
+				}
+				let mut seen_options: HashSet<String> = HashSet::new();
+				options.retain(|option| !option.is_empty() && seen_options.insert(option.clone()));
+				if options.is_empty() {
+					return Err(HandlerError::InvalidData(String::from(
+						"Select fields must have at least one option.",
+					)));
+				}
+			}
+
+			let is_new_field = field_data.field.id.is_empty();
+			if is_new_field {
+				field_data.field.id = cuid2::create_id();
+			}
+			let db_field = EventCustomFieldDb::from_sync_field(&field_data.field, field_data.event.id.clone());
+			let (field, event): (EventCustomFieldDb, EventDb) =
+				run_db_operation(&db_connection_pool, "updating a custom field", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let field: EventCustomFieldDb = if is_new_field {
+							diesel::insert_into(event_custom_fields::table)
+								.values(&db_field)
+								.get_result(db_connection)?
+						} else {
+							let current_field: EventCustomFieldDb =
+								event_custom_fields::table.find(&db_field.id).first(db_connection)?;
+							// Entries' values are only valid for the field's type, so the type can't change once the
+							// field exists. Select options can only be removed while no entry has chosen them.
+							if current_field.field_type != db_field.field_type {
+								return Err(diesel::result::Error::RollbackTransaction);
+							}
+							let removed_options: Vec<String> = current_field
+								.select_options
+								.into_iter()
+								.flatten()
+								.filter(|option| !db_field.select_options.contains(&Some(option.clone())))
+								.collect();
+							let removed_option_use_count: i64 = event_log_custom_field_values::table
+								.filter(
+									event_log_custom_field_values::field
+										.eq(&db_field.id)
+										.and(event_log_custom_field_values::value.eq_any(&removed_options)),
+								)
+								.count()
+								.get_result(db_connection)?;
+							if removed_option_use_count > 0 {
+								return Err(diesel::result::Error::RollbackTransaction);
+							}
+							diesel::update(event_custom_fields::table)
+								.filter(event_custom_fields::id.eq(&db_field.id))
+								.set((
+									event_custom_fields::name.eq(&db_field.name),
+									event_custom_fields::select_options.eq(&db_field.select_options),
+								))
+								.get_result(db_connection)?
+						};
+						let event: EventDb = events::table.find(&field.event).first(db_connection)?;
+						Ok((field, event))
+					})
+				})
+				.await?;
+			let event: Event = event.into();
+			let field = EventCustomField {
+				event: event.clone(),
+				field: field.into(),
+			};
+			let description = if is_new_field {
+				format!(
+					"Added custom field \"{}\" to event \"{}\"",
+					field.field.name, field.event.name
+				)
+			} else {
+				format!(
+					"Updated custom field \"{}\" for event \"{}\"",
+					field.field.name, field.event.name
+				)
+			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				description,
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			let event_message = SubscriptionData::EventUpdate(
+				event.clone(),
+				Box::new(EventSubscriptionData::UpdateCustomField(field.field.clone())),
+			);
+			let send_result = subscription_manager
+				.broadcast_event_message(&event.id, event_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to send event update for custom field: {}", error);
+			}
+			let admin_message =
+				SubscriptionData::AdminEventCustomFieldsUpdate(AdminEventCustomFieldData::UpdateField(field));
+			let send_result = subscription_manager
+				.broadcast_admin_event_custom_fields_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast admin update for custom field: {}", error);
+			}
+		}
+		AdminEventCustomFieldUpdate::DeleteField(field_data) => {
+			run_db_operation(&db_connection_pool, "deleting a custom field", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					diesel::delete(event_log_custom_field_values::table)
+						.filter(event_log_custom_field_values::field.eq(&field_data.field.id))
+						.execute(db_connection)?;
+					diesel::delete(event_custom_fields::table)
+						.filter(event_custom_fields::id.eq(&field_data.field.id))
+						.execute(db_connection)
+				})
+			})
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				format!(
+					"Deleted custom field \"{}\" from event \"{}\"",
+					field_data.field.name, field_data.event.name
+				),
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			let event_message = SubscriptionData::EventUpdate(
+				field_data.event.clone(),
+				Box::new(EventSubscriptionData::DeleteCustomField(field_data.field.clone())),
+			);
+			let send_result = subscription_manager
+				.broadcast_event_message(&field_data.event.id, event_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to send event update for custom field deletion: {}", error);
+			}
+			let admin_message =
+				SubscriptionData::AdminEventCustomFieldsUpdate(AdminEventCustomFieldData::DeleteField(field_data));
+			let send_result = subscription_manager
+				.broadcast_admin_event_custom_fields_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast admin update for custom field deletion: {}", error);
+			}
+		}
+	}
+
+	Ok(())
+}
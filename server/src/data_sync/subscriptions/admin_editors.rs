@@ -4,23 +4,27 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
-use crate::models::{Event as EventDb, EventEditor, User};
+use super::admin_audit_log::record_admin_action;
+use super::{load_subscription_data, user_is_event_supervisor};
+use crate::data_sync::user::load_event_user_ids_with_permission;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, EventEditor, Permission, User};
 use crate::schema::{event_editors, events, users};
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
-use stream_log_shared::messages::admin::{AdminEventEditorData, AdminEventEditorUpdate, EditorEventAssociation};
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEventEditorData, AdminEventEditorUpdate, EditorEventAssociation,
+};
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_editors(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -40,93 +44,40 @@ pub async fn subscribe_to_admin_editors(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminEventEditors)
-				.await?;
-			return Ok(());
-		}
-	};
-	let event_editors: QueryResult<Vec<EventEditor>> = event_editors::table.load(&mut *db_connection);
-	let event_editor_ids = match event_editors {
-		Ok(ids) => ids,
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred loading event editors for admin subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEventEditors,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let db_data: Option<(Vec<EventEditor>, Vec<User>, Vec<EventDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventEditors,
+		"loading event editors for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let event_editor_ids: Vec<EventEditor> = event_editors::table.load(db_connection)?;
+				let event_ids: Vec<String> = event_editor_ids
+					.iter()
+					.map(|event_editor| event_editor.event.clone())
+					.collect();
+				let editor_ids: Vec<String> = event_editor_ids
+					.iter()
+					.map(|event_editor| event_editor.editor.clone())
+					.collect();
+				let users: Vec<User> = users::table.filter(users::id.eq_any(&editor_ids)).load(db_connection)?;
+				let events: Vec<EventDb> = events::table
+					.filter(events::id.eq_any(&event_ids))
+					.load(db_connection)?;
+				Ok((event_editor_ids, users, events))
+			})
+		},
+	)
+	.await?;
+	let Some((event_editor_ids, users, events)) = db_data else {
+		return Ok(());
 	};
-	let event_ids: Vec<String> = event_editor_ids
-		.iter()
-		.map(|event_editor| event_editor.event.clone())
-		.collect();
-	let editor_ids: Vec<String> = event_editor_ids
-		.iter()
-		.map(|event_editor| event_editor.editor.clone())
+	let users: HashMap<String, PublicUserData> = users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
 		.collect();
 
-	let users: QueryResult<Vec<User>> = users::table
-		.filter(users::id.eq_any(&editor_ids))
-		.load(&mut *db_connection);
-	let events: QueryResult<Vec<EventDb>> = events::table
-		.filter(events::id.eq_any(&event_ids))
-		.load(&mut *db_connection);
-
-	let users = match users {
-		Ok(users) => {
-			let user_map: HashMap<String, PublicUserData> =
-				users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
-			user_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting users for admin editor subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEventEditors,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-	let events = match events {
-		Ok(events) => {
-			let event_map: HashMap<String, Event> = events
-				.into_iter()
-				.map(|event| (event.id.clone(), event.into()))
-				.collect();
-			event_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting events for admin editor subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEventEditors,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-
 	let mut event_editors: Vec<EditorEventAssociation> = Vec::with_capacity(event_editor_ids.len());
 	for event_editor in event_editor_ids.iter() {
 		let editor = users.get(&event_editor.editor).unwrap().clone();
@@ -149,50 +100,161 @@ pub async fn subscribe_to_admin_editors(
 	Ok(())
 }
 
+pub async fn subscribe_to_admin_editors_for_event(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	event_id: &str,
+) -> Result<(), HandleConnectionError> {
+	let subscription_type = SubscriptionType::AdminEventEditorsForEvent(event_id.to_string());
+	let is_allowed = if user.is_admin {
+		Some(true)
+	} else {
+		load_subscription_data(
+			&db_connection_pool,
+			&conn_update_tx,
+			subscription_type.clone(),
+			"checking event permissions for an event editors subscription",
+			|db_connection| user_is_event_supervisor(db_connection, &user.id, event_id),
+		)
+		.await?
+	};
+	match is_allowed {
+		Some(true) => (),
+		Some(false) => {
+			let message =
+				FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NotAllowed);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+			return Ok(());
+		}
+		None => return Ok(()),
+	}
+
+	let db_data: Option<(Option<EventDb>, Vec<User>, Vec<User>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		subscription_type.clone(),
+		"loading editors for an event editors subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let event: Option<EventDb> = events::table.find(event_id).first(db_connection).optional()?;
+				let editors: Vec<User> = users::table
+					.filter(
+						users::id.eq_any(
+							event_editors::table
+								.filter(event_editors::event.eq(event_id))
+								.select(event_editors::editor),
+						),
+					)
+					.load(db_connection)?;
+				let candidate_user_ids = load_event_user_ids_with_permission(
+					db_connection,
+					event_id,
+					&[Permission::Edit, Permission::Supervisor],
+				)?;
+				let candidate_users: Vec<User> = users::table
+					.filter(users::id.eq_any(&candidate_user_ids))
+					.load(db_connection)?;
+				Ok((event, editors, candidate_users))
+			})
+		},
+	)
+	.await?;
+	let Some((event, editors, candidate_users)) = db_data else {
+		return Ok(());
+	};
+	let Some(event) = event else {
+		let message = FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NoTarget);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	};
+	let event: Event = event.into();
+
+	let event_editors: Vec<EditorEventAssociation> = editors
+		.into_iter()
+		.map(|editor| EditorEventAssociation {
+			editor: editor.into(),
+			event: event.clone(),
+		})
+		.collect();
+	let candidate_users: Vec<PublicUserData> = candidate_users.into_iter().map(|user| user.into()).collect();
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_editors_for_event_subscription(event_id, connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminEventEditorsForEvent(event, event_editors, candidate_users),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
 pub async fn handle_admin_editors_message(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	connection_id: &str,
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminEventEditorUpdate,
-) {
+) -> Result<(), HandlerError> {
+	let event_id = match &update_message {
+		AdminEventEditorUpdate::AddEditor(editor_data) | AdminEventEditorUpdate::RemoveEditor(editor_data) => {
+			editor_data.event.id.clone()
+		}
+	};
 	if !user.is_admin {
-		return;
+		let is_supervisor = run_db_operation(
+			&db_connection_pool,
+			"checking event permissions for an editor change",
+			|db_connection| user_is_event_supervisor(db_connection, &user.id, &event_id),
+		)
+		.await?;
+		if !is_supervisor {
+			return Err(HandlerError::NotAllowed);
+		}
 	}
 	if !subscription_manager
 		.lock()
 		.await
-		.is_subscribed_to_admin_editors(connection_id)
+		.is_subscribed_to_admin_editors_for_event(&event_id, connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminEventEditorUpdate::AddEditor(editor_data) => {
-			{
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred adding an editor for an event: {}",
-							error
-						);
-						return;
-					}
-				};
-				let event_editor = EventEditor {
-					event: editor_data.event.id.clone(),
-					editor: editor_data.editor.id.clone(),
-				};
-				let db_result = diesel::insert_into(event_editors::table)
-					.values(event_editor)
-					.execute(&mut *db_connection);
-				if let Err(error) = db_result {
-					tide::log::error!("A database error occurred adding an editor for an event: {}", error);
-					return;
-				}
-			}
+			let event_editor = EventEditor {
+				event: editor_data.event.id.clone(),
+				editor: editor_data.editor.id.clone(),
+			};
+			run_db_operation(&db_connection_pool, "adding an editor for an event", |db_connection| {
+				diesel::insert_into(event_editors::table)
+					.values(&event_editor)
+					.execute(db_connection)
+			})
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Editor,
+				format!(
+					"Added \"{}\" as an editor for event \"{}\"",
+					editor_data.editor.username, editor_data.event.name
+				),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let event_message = SubscriptionData::EventUpdate(
@@ -208,36 +270,38 @@ pub async fn handle_admin_editors_message(
 
 			let admin_message = SubscriptionData::AdminEventEditorsUpdate(AdminEventEditorData::AddEditor(editor_data));
 			let send_result = subscription_manager
-				.broadcast_admin_editors_message(admin_message)
+				.broadcast_admin_editors_message(&event_id, admin_message)
 				.await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send editor update to admin subscription: {}", error);
 			}
 		}
 		AdminEventEditorUpdate::RemoveEditor(editor_data) => {
-			{
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred removing an editor from an event: {}",
-							error
-						);
-						return;
-					}
-				};
-				let db_result = diesel::delete(event_editors::table)
-					.filter(
-						event_editors::event
-							.eq(&editor_data.event.id)
-							.and(event_editors::editor.eq(&editor_data.editor.id)),
-					)
-					.execute(&mut *db_connection);
-				if let Err(error) = db_result {
-					tide::log::error!("A database error occurred removing an editor from an event: {}", error);
-					return;
-				}
-			}
+			run_db_operation(
+				&db_connection_pool,
+				"removing an editor from an event",
+				|db_connection| {
+					diesel::delete(event_editors::table)
+						.filter(
+							event_editors::event
+								.eq(&editor_data.event.id)
+								.and(event_editors::editor.eq(&editor_data.editor.id)),
+						)
+						.execute(db_connection)
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Editor,
+				format!(
+					"Removed \"{}\" as an editor for event \"{}\"",
+					editor_data.editor.username, editor_data.event.name
+				),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let event_message = SubscriptionData::EventUpdate(
@@ -254,11 +318,13 @@ pub async fn handle_admin_editors_message(
 			let admin_message =
 				SubscriptionData::AdminEventEditorsUpdate(AdminEventEditorData::RemoveEditor(editor_data));
 			let send_result = subscription_manager
-				.broadcast_admin_editors_message(admin_message)
+				.broadcast_admin_editors_message(&event_id, admin_message)
 				.await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send editor removal to admin subscription: {}", error);
 			}
 		}
 	}
+
+	Ok(())
 }
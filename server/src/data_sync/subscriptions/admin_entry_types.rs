@@ -4,8 +4,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
 use crate::models::{AvailableEntryType, EntryType as EntryTypeDb, Event as EventDb};
 use crate::schema::{available_entry_types_for_event, entry_types, events};
 use async_std::channel::Sender;
@@ -14,17 +15,17 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
 use stream_log_shared::messages::admin::{
-	AdminEntryTypeData, AdminEntryTypeEventData, AdminEntryTypeEventUpdate, AdminEntryTypeUpdate,
+	AdminActionType, AdminEntryTypeData, AdminEntryTypeEventData, AdminEntryTypeEventUpdate, AdminEntryTypeUpdate,
 	EntryTypeEventAssociation,
 };
-use stream_log_shared::messages::entry_types::EntryType;
+use stream_log_shared::messages::entry_types::{normalize_group_name, EntryType};
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_entry_types(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -44,33 +45,18 @@ pub async fn subscribe_to_admin_entry_types(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminEntryTypes)
-				.await?;
-			return Ok(());
-		}
-	};
-	let entry_types: QueryResult<Vec<EntryTypeDb>> = entry_types::table.load(&mut *db_connection);
-
-	let entry_types: Vec<EntryType> = match entry_types {
-		Ok(types) => types.into_iter().map(|entry_type| entry_type.into()).collect(),
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting admin entry type subscription data: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEntryTypes,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let entry_types: Option<Vec<EntryTypeDb>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEntryTypes,
+		"getting admin entry type subscription data",
+		|db_connection| entry_types::table.load(db_connection),
+	)
+	.await?;
+	let Some(entry_types) = entry_types else {
+		return Ok(());
 	};
+	let entry_types: Vec<EntryType> = entry_types.into_iter().map(|entry_type| entry_type.into()).collect();
 
 	let subscription_manager = subscription_manager.lock().await;
 	subscription_manager
@@ -92,9 +78,9 @@ pub async fn handle_admin_entry_type_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminEntryTypeUpdate,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -102,37 +88,46 @@ pub async fn handle_admin_entry_type_message(
 		.is_subscribed_to_admin_entry_types(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminEntryTypeUpdate::UpdateEntryType(mut entry_type) => {
-			let (update_result, event_data_result) = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!("A database connection error occurred updating an entry type: {}", error);
-						return;
-					}
+			if entry_type
+				.expected_duration_minutes
+				.is_some_and(|expected_duration| expected_duration <= 0)
+			{
+				return Err(HandlerError::InvalidData(String::from(
+					"The expected duration must be at least one minute.",
+				)));
+			}
+			entry_type.group_name = normalize_group_name(entry_type.group_name);
+
+			let is_new_entry_type = entry_type.id.is_empty();
+			if is_new_entry_type {
+				entry_type.id = cuid2::create_id();
+				let db_entry_type = EntryTypeDb {
+					id: entry_type.id.clone(),
+					name: entry_type.name.clone(),
+					description: entry_type.description.clone(),
+					color_red: entry_type.color.r.into(),
+					color_green: entry_type.color.g.into(),
+					color_blue: entry_type.color.b.into(),
+					require_end_time: entry_type.require_end_time,
+					expected_duration_minutes: entry_type.expected_duration_minutes,
+					group_name: entry_type.group_name.clone(),
 				};
-				let update_result = if entry_type.id.is_empty() {
-					entry_type.id = cuid2::create_id();
-					let db_entry_type = EntryTypeDb {
-						id: entry_type.id.clone(),
-						name: entry_type.name.clone(),
-						description: entry_type.description.clone(),
-						color_red: entry_type.color.r.into(),
-						color_green: entry_type.color.g.into(),
-						color_blue: entry_type.color.b.into(),
-						require_end_time: entry_type.require_end_time,
-					};
+				run_db_operation(&db_connection_pool, "adding an entry type", |db_connection| {
 					diesel::insert_into(entry_types::table)
-						.values(db_entry_type)
-						.execute(&mut *db_connection)
-				} else {
-					let red: i32 = entry_type.color.r.into();
-					let green: i32 = entry_type.color.g.into();
-					let blue: i32 = entry_type.color.b.into();
+						.values(&db_entry_type)
+						.execute(db_connection)
+				})
+				.await?;
+			} else {
+				let red: i32 = entry_type.color.r.into();
+				let green: i32 = entry_type.color.g.into();
+				let blue: i32 = entry_type.color.b.into();
+				run_db_operation(&db_connection_pool, "updating an entry type", |db_connection| {
 					diesel::update(entry_types::table)
 						.filter(entry_types::id.eq(&entry_type.id))
 						.set((
@@ -142,35 +137,40 @@ pub async fn handle_admin_entry_type_message(
 							entry_types::color_green.eq(green),
 							entry_types::color_blue.eq(blue),
 							entry_types::require_end_time.eq(entry_type.require_end_time),
+							entry_types::expected_duration_minutes.eq(entry_type.expected_duration_minutes),
+							entry_types::group_name.eq(&entry_type.group_name),
 						))
-						.execute(&mut *db_connection)
-				};
-
-				let event_ids: QueryResult<Vec<String>> = available_entry_types_for_event::table
-					.filter(available_entry_types_for_event::entry_type.eq(&entry_type.id))
-					.select(available_entry_types_for_event::event_id)
-					.load(&mut *db_connection);
-				match event_ids {
-					Ok(event_ids) => {
-						let events: QueryResult<Vec<EventDb>> = events::table
-							.filter(events::id.eq_any(&event_ids))
-							.load(&mut *db_connection);
-						(update_result, events)
-					}
-					Err(error) => (update_result, Err(error)),
-				}
-			};
-			if let Err(error) = update_result {
-				tide::log::error!("A database error occurred updating an entry type: {}", error);
-				return;
+						.execute(db_connection)
+				})
+				.await?;
 			}
-			let events = match event_data_result {
-				Ok(events) => events,
-				Err(error) => {
-					tide::log::error!("A database error occurred getting events for an entry type: {}", error);
-					return;
-				}
+
+			let events: Vec<EventDb> = run_db_operation(
+				&db_connection_pool,
+				"getting events for an entry type",
+				|db_connection| {
+					let event_ids: Vec<String> = available_entry_types_for_event::table
+						.filter(available_entry_types_for_event::entry_type.eq(&entry_type.id))
+						.select(available_entry_types_for_event::event_id)
+						.load(db_connection)?;
+					events::table.filter(events::id.eq_any(&event_ids)).load(db_connection)
+				},
+			)
+			.await?;
+
+			let description = if is_new_entry_type {
+				format!("Created entry type \"{}\"", entry_type.name)
+			} else {
+				format!("Updated entry type \"{}\"", entry_type.name)
 			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::EntryType,
+				description,
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let admin_message =
@@ -198,6 +198,8 @@ pub async fn handle_admin_entry_type_message(
 			}
 		}
 	}
+
+	Ok(())
 }
 
 pub async fn subscribe_to_admin_entry_types_events(
@@ -218,94 +220,40 @@ pub async fn subscribe_to_admin_entry_types_events(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(
-				error,
-				&conn_update_tx,
-				SubscriptionType::AdminEntryTypesEvents,
-			)
-			.await?;
-			return Ok(());
-		}
-	};
-	let entry_type_events: QueryResult<Vec<AvailableEntryType>> =
-		available_entry_types_for_event::table.load(&mut *db_connection);
-	let entry_type_events = match entry_type_events {
-		Ok(data) => data,
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred retrieving entry type and event associations for a subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEntryTypesEvents,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-
-	let event_ids: Vec<String> = entry_type_events.iter().map(|data| data.event_id.clone()).collect();
-	let entry_type_ids: Vec<String> = entry_type_events.iter().map(|data| data.entry_type.clone()).collect();
-
-	let events: QueryResult<Vec<EventDb>> = events::table
-		.filter(events::id.eq_any(&event_ids))
-		.load(&mut *db_connection);
-	let entry_types: QueryResult<Vec<EntryTypeDb>> = entry_types::table
-		.filter(entry_types::id.eq_any(&entry_type_ids))
-		.load(&mut *db_connection);
-
-	let events = match events {
-		Ok(events) => {
-			let events_map: HashMap<String, Event> = events
-				.into_iter()
-				.map(|event| (event.id.clone(), event.into()))
-				.collect();
-			events_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred retrieving event data for entry type associations: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEntryTypesEvents,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-	let entry_types = match entry_types {
-		Ok(entry_types) => {
-			let entry_types_map: HashMap<String, EntryType> = entry_types
-				.into_iter()
-				.map(|entry_type| (entry_type.id.clone(), entry_type.into()))
-				.collect();
-			entry_types_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred retrieving entry type data for event associations: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEntryTypesEvents,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let db_data: Option<(Vec<AvailableEntryType>, Vec<EventDb>, Vec<EntryTypeDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEntryTypesEvents,
+		"retrieving entry type and event associations for a subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let entry_type_events: Vec<AvailableEntryType> =
+					available_entry_types_for_event::table.load(db_connection)?;
+				let event_ids: Vec<String> = entry_type_events.iter().map(|data| data.event_id.clone()).collect();
+				let entry_type_ids: Vec<String> =
+					entry_type_events.iter().map(|data| data.entry_type.clone()).collect();
+				let events: Vec<EventDb> = events::table
+					.filter(events::id.eq_any(&event_ids))
+					.load(db_connection)?;
+				let entry_types: Vec<EntryTypeDb> = entry_types::table
+					.filter(entry_types::id.eq_any(&entry_type_ids))
+					.load(db_connection)?;
+				Ok((entry_type_events, events, entry_types))
+			})
+		},
+	)
+	.await?;
+	let Some((entry_type_events, events, entry_types)) = db_data else {
+		return Ok(());
 	};
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
+		.collect();
+	let entry_types: HashMap<String, EntryType> = entry_types
+		.into_iter()
+		.map(|entry_type| (entry_type.id.clone(), entry_type.into()))
+		.collect();
 
 	let mut entry_type_event_associations: Vec<EntryTypeEventAssociation> = Vec::with_capacity(entry_type_events.len());
 	for entry_type_event in entry_type_events.iter() {
@@ -335,9 +283,9 @@ pub async fn handle_admin_entry_type_event_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminEntryTypeEventUpdate,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -345,99 +293,182 @@ pub async fn handle_admin_entry_type_event_message(
 		.is_subscribed_to_admin_entry_types_events(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
-	let (admin_message, event_id, event_message) = match update_message {
+	let mut messages: Vec<(SubscriptionData, String, SubscriptionData)> = Vec::new();
+	match update_message {
 		AdminEntryTypeEventUpdate::AddTypeToEvent(association) => {
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!(
-						"A database connection error occurred adding event type + entry association: {}",
-						error
-					);
-					return;
-				}
-			};
 			let available_entry_type = AvailableEntryType {
 				entry_type: association.entry_type.id.clone(),
 				event_id: association.event.id.clone(),
 			};
-			let insert_result = diesel::insert_into(available_entry_types_for_event::table)
-				.values(available_entry_type)
-				.execute(&mut *db_connection);
-			if let Err(error) = insert_result {
-				tide::log::error!(
-					"A database error occurred adding event type + entry association: {}",
-					error
-				);
-				return;
-			}
+			run_db_operation(
+				&db_connection_pool,
+				"adding event type + entry association",
+				|db_connection| {
+					diesel::insert_into(available_entry_types_for_event::table)
+						.values(&available_entry_type)
+						.execute(db_connection)
+				},
+			)
+			.await?;
 
-			let event_id = association.event.id.clone();
-			let admin_message = SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::AddTypeToEvent(
-				association.clone(),
-			));
-			let event_message = SubscriptionData::EventUpdate(
-				association.event,
-				Box::new(EventSubscriptionData::AddEntryType(association.entry_type)),
-			);
-			(admin_message, event_id, event_message)
+			messages.push(added_association_messages(association));
 		}
 		AdminEntryTypeEventUpdate::RemoveTypeFromEvent(association) => {
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!(
-						"A database connection error occurred deleting event type + entry association: {}",
-						error
-					);
-					return;
-				}
-			};
-			let delete_result = diesel::delete(available_entry_types_for_event::table)
-				.filter(
-					available_entry_types_for_event::entry_type
-						.eq(&association.entry_type.id)
-						.and(available_entry_types_for_event::event_id.eq(&association.event.id)),
-				)
-				.execute(&mut *db_connection);
-			if let Err(error) = delete_result {
-				tide::log::error!(
-					"A database error occurred deleting event type + entry association: {}",
-					error
-				);
-				return;
-			}
+			run_db_operation(
+				&db_connection_pool,
+				"deleting event type + entry association",
+				|db_connection| {
+					diesel::delete(available_entry_types_for_event::table)
+						.filter(
+							available_entry_types_for_event::entry_type
+								.eq(&association.entry_type.id)
+								.and(available_entry_types_for_event::event_id.eq(&association.event.id)),
+						)
+						.execute(db_connection)
+				},
+			)
+			.await?;
+
+			messages.push(removed_association_messages(association));
+		}
+		AdminEntryTypeEventUpdate::UpdateAssociations(added_associations, removed_associations) => {
+			let available_entry_types: Vec<AvailableEntryType> = added_associations
+				.iter()
+				.map(|association| AvailableEntryType {
+					entry_type: association.entry_type.id.clone(),
+					event_id: association.event.id.clone(),
+				})
+				.collect();
+			let (inserted_entry_types, removed_flags): (Vec<AvailableEntryType>, Vec<bool>) = run_db_operation(
+				&db_connection_pool,
+				"updating event type + entry associations",
+				|db_connection| {
+					db_connection.transaction(|db_connection| {
+						let inserted_entry_types: Vec<AvailableEntryType> =
+							diesel::insert_into(available_entry_types_for_event::table)
+								.values(&available_entry_types)
+								.on_conflict_do_nothing()
+								.get_results(db_connection)?;
+						let mut removed_flags: Vec<bool> = Vec::with_capacity(removed_associations.len());
+						for association in removed_associations.iter() {
+							let removed_count = diesel::delete(available_entry_types_for_event::table)
+								.filter(
+									available_entry_types_for_event::entry_type
+										.eq(&association.entry_type.id)
+										.and(available_entry_types_for_event::event_id.eq(&association.event.id)),
+								)
+								.execute(db_connection)?;
+							removed_flags.push(removed_count > 0);
+						}
+						Ok((inserted_entry_types, removed_flags))
+					})
+				},
+			)
+			.await?;
 
-			let event_id = association.event.id.clone();
-			let admin_message = SubscriptionData::AdminEntryTypesEventsUpdate(
-				AdminEntryTypeEventData::RemoveTypeFromEvent(association.clone()),
+			// Only changes that actually happened are sent out so that subscribers don't see duplicate additions
+			messages.extend(
+				added_associations
+					.into_iter()
+					.filter(|association| {
+						inserted_entry_types.iter().any(|inserted| {
+							inserted.entry_type == association.entry_type.id
+								&& inserted.event_id == association.event.id
+						})
+					})
+					.map(added_association_messages),
 			);
-			let event_message = SubscriptionData::EventUpdate(
-				association.event,
-				Box::new(EventSubscriptionData::DeleteEntryType(association.entry_type)),
+			messages.extend(
+				removed_associations
+					.into_iter()
+					.zip(removed_flags)
+					.filter(|(_, removed)| *removed)
+					.map(|(association, _)| removed_association_messages(association)),
 			);
-			(admin_message, event_id, event_message)
 		}
-	};
+	}
+
+	for (admin_message, _, _) in messages.iter() {
+		if let Some(description) = association_change_description(admin_message) {
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::EntryType,
+				description,
+			)
+			.await;
+		}
+	}
 
 	let subscription_manager = subscription_manager.lock().await;
-	let send_result = subscription_manager
-		.broadcast_admin_entry_types_events_message(admin_message)
-		.await;
-	if let Err(error) = send_result {
-		tide::log::error!(
-			"Failed to broadcast entry type and event update to administrators: {}",
-			error
-		);
+	for (admin_message, event_id, event_message) in messages {
+		let send_result = subscription_manager
+			.broadcast_admin_entry_types_events_message(admin_message)
+			.await;
+		if let Err(error) = send_result {
+			tide::log::error!(
+				"Failed to broadcast entry type and event update to administrators: {}",
+				error
+			);
+		}
+
+		let send_result = subscription_manager
+			.broadcast_event_message(&event_id, event_message)
+			.await;
+		if let Err(error) = send_result {
+			tide::log::error!("Failed to broadcast entry type and event update to users: {}", error);
+		}
 	}
 
-	let send_result = subscription_manager
-		.broadcast_event_message(&event_id, event_message)
-		.await;
-	if let Err(error) = send_result {
-		tide::log::error!("Failed to broadcast entry type and event update to users: {}", error);
+	Ok(())
+}
+
+/// Describes the entry type/event association change in an admin message for the audit log
+fn association_change_description(admin_message: &SubscriptionData) -> Option<String> {
+	match admin_message {
+		SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::AddTypeToEvent(association)) => {
+			Some(format!(
+				"Added entry type \"{}\" to event \"{}\"",
+				association.entry_type.name, association.event.name
+			))
+		}
+		SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::RemoveTypeFromEvent(association)) => {
+			Some(format!(
+				"Removed entry type \"{}\" from event \"{}\"",
+				association.entry_type.name, association.event.name
+			))
+		}
+		_ => None,
 	}
 }
+
+/// Gets the admin message, event ID, and event message to broadcast for an added entry type/event association
+fn added_association_messages(association: EntryTypeEventAssociation) -> (SubscriptionData, String, SubscriptionData) {
+	let event_id = association.event.id.clone();
+	let admin_message =
+		SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::AddTypeToEvent(association.clone()));
+	let event_message = SubscriptionData::EventUpdate(
+		association.event,
+		Box::new(EventSubscriptionData::AddEntryType(association.entry_type)),
+	);
+	(admin_message, event_id, event_message)
+}
+
+/// Gets the admin message, event ID, and event message to broadcast for a removed entry type/event association
+fn removed_association_messages(
+	association: EntryTypeEventAssociation,
+) -> (SubscriptionData, String, SubscriptionData) {
+	let event_id = association.event.id.clone();
+	let admin_message = SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::RemoveTypeFromEvent(
+		association.clone(),
+	));
+	let event_message = SubscriptionData::EventUpdate(
+		association.event,
+		Box::new(EventSubscriptionData::DeleteEntryType(association.entry_type)),
+	);
+	(admin_message, event_id, event_message)
+}
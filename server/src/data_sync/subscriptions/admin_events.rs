@@ -4,22 +4,45 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
-use crate::models::Event as EventDb;
-use crate::schema::events;
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::user::UserDataUpdate;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::event_archive::{load_event_archive_data, purge_archived_event, write_event_archive, EventArchive};
+use crate::models::{
+	AvailableEntryType, EntryType as EntryTypeDb, Event as EventDb, EventLogEntry as EventLogEntryDb,
+	EventLogTab as EventLogTabDb, InfoPage as InfoPageDb, Permission, PermissionEvent,
+	PermissionGroup as PermissionGroupDb,
+};
+use crate::schema::{
+	available_entry_types_for_event, entry_types, event_log, event_log_tabs, events, info_pages, permission_events,
+	permission_groups, user_permissions,
+};
+use crate::storage::FileStorage;
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
+use chrono::TimeDelta;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
-use stream_log_shared::messages::admin::{AdminEventData, AdminEventUpdate};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEntryTypeData, AdminEntryTypeEventData, AdminEventData, AdminEventLogTabsData,
+	AdminEventUpdate, AdminInfoPageData, AdminPermissionGroupData, EntryTypeEventAssociation, EventTemplate,
+	EventTemplateInfoPage, EventTemplatePermissionGroup, EventTemplateTab, PermissionGroupEventAssociation,
+};
+use stream_log_shared::messages::entry_types::normalize_group_name;
+use stream_log_shared::messages::event_log::EventLogTab;
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::info_pages::InfoPage;
+use stream_log_shared::messages::list_sync::{ListSyncLoad, ListVersion};
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
+use stream_log_shared::messages::time_display::TimeDisplay;
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_events(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -27,6 +50,7 @@ pub async fn subscribe_to_admin_events(
 	connection_id: &str,
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	known_version: Option<ListVersion>,
 ) -> Result<(), HandleConnectionError> {
 	if !user.is_admin {
 		let message =
@@ -37,37 +61,44 @@ pub async fn subscribe_to_admin_events(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminEvents)
-				.await?;
-			return Ok(());
-		}
-	};
-	let events: QueryResult<Vec<EventDb>> = events::table.load(&mut *db_connection);
-	let events: Vec<Event> = match events {
-		Ok(events) => events.into_iter().map(|event| event.into()).collect(),
-		Err(error) => {
-			tide::log::error!("A database error occurred getting the admin events list: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEvents,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
+	if let Some(known_version) = known_version {
+		let subscription_manager = subscription_manager.lock().await;
+		if let Some(changes) = subscription_manager.admin_event_changes_since(&known_version) {
+			subscription_manager
+				.add_admin_event_subscription(connection_id, conn_update_tx.clone())
+				.await;
+			let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+				InitialSubscriptionLoadData::AdminEvents(ListSyncLoad::Patches(changes)),
+			));
 			conn_update_tx
 				.send(ConnectionUpdate::SendData(Box::new(message)))
 				.await?;
 			return Ok(());
 		}
+	}
+
+	let events: Option<Vec<EventDb>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEvents,
+		"getting the admin events list",
+		|db_connection| events::table.load(db_connection),
+	)
+	.await?;
+	let Some(events) = events else {
+		return Ok(());
 	};
+	let events: Vec<Event> = events.into_iter().map(|event| event.into()).collect();
 
 	let subscription_manager = subscription_manager.lock().await;
 	subscription_manager
 		.add_admin_event_subscription(connection_id, conn_update_tx.clone())
 		.await;
 
-	let message =
-		FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminEvents(events)));
+	let version = subscription_manager.admin_event_list_version();
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminEvents(
+		ListSyncLoad::Snapshot(version, events),
+	)));
 	conn_update_tx
 		.send(ConnectionUpdate::SendData(Box::new(message)))
 		.await?;
@@ -81,9 +112,11 @@ pub async fn handle_admin_event_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminEventUpdate,
-) {
+	conn_update_tx: Sender<ConnectionUpdate>,
+	archive_storage: Option<&FileStorage>,
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -91,32 +124,41 @@ pub async fn handle_admin_event_message(
 		.is_subscribed_to_admin_events(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminEventUpdate::UpdateEvent(mut event) => {
-			let db_result = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!("A database connection error occurred updating event data: {}", error);
-						return;
-					}
+			if event.end_time.is_some_and(|end_time| end_time <= event.start_time) {
+				return Err(HandlerError::InvalidData(String::from(
+					"An event's end time must be after its start time.",
+				)));
+			}
+			event.time_display.validate().map_err(HandlerError::InvalidData)?;
+
+			let is_new_event = event.id.is_empty();
+			if is_new_event {
+				event.id = cuid2::create_id();
+				let event_db = EventDb {
+					id: event.id.clone(),
+					name: event.name.clone(),
+					start_time: event.start_time,
+					editor_link_format: event.editor_link_format.clone(),
+					first_tab_name: event.first_tab_name.clone(),
+					description_max_length: event.description_max_length,
+					require_tag_deletion_approval: event.require_tag_deletion_approval,
+					end_time: event.end_time,
+					archived: event.archived,
+					time_display_zone: event.time_display.time_zone_name().map(String::from),
 				};
-				if event.id.is_empty() {
-					event.id = cuid2::create_id();
-					let event_db = EventDb {
-						id: event.id.clone(),
-						name: event.name.clone(),
-						start_time: event.start_time,
-						editor_link_format: event.editor_link_format.clone(),
-						first_tab_name: event.first_tab_name.clone(),
-					};
+				run_db_operation(&db_connection_pool, "adding an event", |db_connection| {
 					diesel::insert_into(events::table)
-						.values(event_db)
-						.execute(&mut *db_connection)
-				} else {
+						.values(&event_db)
+						.execute(db_connection)
+				})
+				.await?;
+			} else {
+				run_db_operation(&db_connection_pool, "updating event data", |db_connection| {
 					diesel::update(events::table)
 						.filter(events::id.eq(&event.id))
 						.set((
@@ -124,18 +166,34 @@ pub async fn handle_admin_event_message(
 							events::start_time.eq(event.start_time),
 							events::editor_link_format.eq(&event.editor_link_format),
 							events::first_tab_name.eq(&event.first_tab_name),
+							events::description_max_length.eq(event.description_max_length),
+							events::require_tag_deletion_approval.eq(event.require_tag_deletion_approval),
+							events::end_time.eq(event.end_time),
+							events::archived.eq(event.archived),
+							events::time_display_zone.eq(event.time_display.time_zone_name()),
 						))
-						.execute(&mut *db_connection)
-				}
-			};
-			if let Err(error) = db_result {
-				tide::log::error!("A database error occurred updating event data: {}", error);
-				return;
+						.execute(db_connection)
+				})
+				.await?;
 			}
+			let description = if is_new_event {
+				format!("Created event \"{}\"", event.name)
+			} else {
+				format!("Updated event \"{}\"", event.name)
+			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				description,
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
-			let admin_message = SubscriptionData::AdminEventsUpdate(AdminEventData::UpdateEvent(event.clone()));
-			let broadcast_result = subscription_manager.broadcast_admin_event_message(admin_message).await;
+			let broadcast_result = subscription_manager
+				.broadcast_admin_event_message(AdminEventData::UpdateEvent(event.clone()))
+				.await;
 			if let Err(error) = broadcast_result {
 				tide::log::error!("Failed to broadcast an admin event update: {}", error);
 			}
@@ -150,5 +208,593 @@ pub async fn handle_admin_event_message(
 				tide::log::error!("Failed to broadcast an event update: {}", error);
 			}
 		}
+		AdminEventUpdate::ExportTemplate(event) => {
+			let template: EventTemplate =
+				run_db_operation(&db_connection_pool, "exporting an event template", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let event_db: EventDb = events::table.find(&event.id).first(db_connection)?;
+						let event_entry_types: Vec<EntryTypeDb> = entry_types::table
+							.filter(
+								available_entry_types_for_event::table
+									.filter(
+										available_entry_types_for_event::event_id
+											.eq(&event.id)
+											.and(available_entry_types_for_event::entry_type.eq(entry_types::id)),
+									)
+									.count()
+									.single_value()
+									.gt(0),
+							)
+							.order(entry_types::name.asc())
+							.load(db_connection)?;
+						let tabs: Vec<EventLogTabDb> = event_log_tabs::table
+							.filter(event_log_tabs::event.eq(&event.id))
+							.order(event_log_tabs::start_time.asc())
+							.load(db_connection)?;
+						let group_permissions: Vec<(PermissionEvent, PermissionGroupDb)> = permission_events::table
+							.inner_join(permission_groups::table)
+							.filter(permission_events::event.eq(&event.id))
+							.order(permission_groups::name.asc())
+							.load(db_connection)?;
+						let pages: Vec<InfoPageDb> = info_pages::table
+							.filter(info_pages::event.eq(&event.id))
+							.order(info_pages::title.asc())
+							.load(db_connection)?;
+
+						let entry_types = event_entry_types
+							.into_iter()
+							.map(|entry_type| entry_type.into())
+							.collect();
+						let tabs = tabs
+							.into_iter()
+							.map(|tab| EventTemplateTab {
+								name: tab.name,
+								start_offset_seconds: (tab.start_time - event_db.start_time).num_seconds(),
+							})
+							.collect();
+						let permission_groups = group_permissions
+							.into_iter()
+							.map(|(permission_event, group)| EventTemplatePermissionGroup {
+								name: group.name,
+								permission: permission_event.level.into(),
+							})
+							.collect();
+						let info_pages = pages
+							.into_iter()
+							.map(|page| EventTemplateInfoPage {
+								title: page.title,
+								contents: page.contents,
+								minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+							})
+							.collect();
+
+						Ok(EventTemplate {
+							editor_link_format: event_db.editor_link_format,
+							first_tab_name: event_db.first_tab_name,
+							description_max_length: event_db.description_max_length,
+							require_tag_deletion_approval: event_db.require_tag_deletion_approval,
+							time_display: TimeDisplay::from_time_zone(event_db.time_display_zone),
+							entry_types,
+							tabs,
+							permission_groups,
+							info_pages,
+						})
+					})
+				})
+				.await?;
+
+			let message = FromServerMessage::SubscriptionMessage(
+				Box::new(SubscriptionData::AdminEventTemplate(event, template)),
+				None,
+			);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+		}
+		AdminEventUpdate::ImportTemplate(mut event, template) => {
+			event.id = cuid2::create_id();
+			event.editor_link_format = template.editor_link_format.clone();
+			event.first_tab_name = template.first_tab_name.clone();
+			event.description_max_length = template.description_max_length;
+			event.require_tag_deletion_approval = template.require_tag_deletion_approval;
+			event.time_display = template.time_display.clone();
+
+			if event.end_time.is_some_and(|end_time| end_time <= event.start_time) {
+				return Err(HandlerError::InvalidData(String::from(
+					"An event's end time must be after its start time.",
+				)));
+			}
+			event.time_display.validate().map_err(HandlerError::InvalidData)?;
+
+			// Offsets come from an uploaded file, so they're not guaranteed to be reasonable
+			let tab_offsets_valid = template.tabs.iter().all(|template_tab| {
+				TimeDelta::try_seconds(template_tab.start_offset_seconds)
+					.and_then(|offset| event.start_time.checked_add_signed(offset))
+					.is_some()
+			});
+			if !tab_offsets_valid {
+				return Err(HandlerError::InvalidData(String::from(
+					"The template contains a tab with an invalid start time.",
+				)));
+			}
+
+			let imported_data: ImportedTemplateData =
+				run_db_operation(&db_connection_pool, "importing an event template", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let event_db = EventDb {
+							id: event.id.clone(),
+							name: event.name.clone(),
+							start_time: event.start_time,
+							editor_link_format: event.editor_link_format.clone(),
+							first_tab_name: event.first_tab_name.clone(),
+							description_max_length: event.description_max_length,
+							require_tag_deletion_approval: event.require_tag_deletion_approval,
+							end_time: event.end_time,
+							archived: event.archived,
+							time_display_zone: event.time_display.time_zone_name().map(String::from),
+						};
+						diesel::insert_into(events::table)
+							.values(event_db)
+							.execute(db_connection)?;
+
+						let existing_entry_types: Vec<EntryTypeDb> = entry_types::table.load(db_connection)?;
+						let existing_entry_types_by_name: HashMap<String, EntryTypeDb> = existing_entry_types
+							.into_iter()
+							.map(|entry_type| (entry_type.name.clone(), entry_type))
+							.collect();
+						let mut added_entry_type_ids: HashSet<String> = HashSet::new();
+						let mut new_entry_types: Vec<EntryTypeDb> = Vec::new();
+						let mut event_entry_types: Vec<EntryTypeDb> = Vec::new();
+						for template_entry_type in template.entry_types.iter() {
+							let entry_type = match existing_entry_types_by_name.get(&template_entry_type.name) {
+								Some(entry_type) => entry_type.clone(),
+								None => {
+									let entry_type = EntryTypeDb {
+										id: cuid2::create_id(),
+										name: template_entry_type.name.clone(),
+										color_red: template_entry_type.color.r.into(),
+										color_green: template_entry_type.color.g.into(),
+										color_blue: template_entry_type.color.b.into(),
+										description: template_entry_type.description.clone(),
+										require_end_time: template_entry_type.require_end_time,
+										expected_duration_minutes: template_entry_type.expected_duration_minutes,
+										group_name: normalize_group_name(template_entry_type.group_name.clone()),
+									};
+									diesel::insert_into(entry_types::table)
+										.values(entry_type.clone())
+										.execute(db_connection)?;
+									new_entry_types.push(entry_type.clone());
+									entry_type
+								}
+							};
+							if added_entry_type_ids.insert(entry_type.id.clone()) {
+								event_entry_types.push(entry_type);
+							}
+						}
+						let available_entry_types: Vec<AvailableEntryType> = event_entry_types
+							.iter()
+							.map(|entry_type| AvailableEntryType {
+								entry_type: entry_type.id.clone(),
+								event_id: event.id.clone(),
+							})
+							.collect();
+						diesel::insert_into(available_entry_types_for_event::table)
+							.values(available_entry_types)
+							.execute(db_connection)?;
+
+						let existing_groups: Vec<PermissionGroupDb> = permission_groups::table.load(db_connection)?;
+						let mut groups_by_name: HashMap<String, String> = existing_groups
+							.into_iter()
+							.map(|group| (group.name, group.id))
+							.collect();
+						let mut new_groups: Vec<PermissionGroupDb> = Vec::new();
+						let mut group_permissions: Vec<PermissionEvent> = Vec::new();
+						for template_group in template.permission_groups.iter() {
+							let group_id = match groups_by_name.get(&template_group.name) {
+								Some(id) => id.clone(),
+								None => {
+									let group = PermissionGroupDb {
+										id: cuid2::create_id(),
+										name: template_group.name.clone(),
+									};
+									diesel::insert_into(permission_groups::table)
+										.values(&group)
+										.execute(db_connection)?;
+									groups_by_name.insert(group.name.clone(), group.id.clone());
+									let group_id = group.id.clone();
+									new_groups.push(group);
+									group_id
+								}
+							};
+							let permission_event = PermissionEvent {
+								permission_group: group_id,
+								event: event.id.clone(),
+								level: template_group.permission.into(),
+							};
+							diesel::insert_into(permission_events::table)
+								.values(&permission_event)
+								.on_conflict((permission_events::permission_group, permission_events::event))
+								.do_update()
+								.set(permission_events::level.eq(permission_event.level))
+								.execute(db_connection)?;
+							group_permissions
+								.retain(|permission| permission.permission_group != permission_event.permission_group);
+							group_permissions.push(permission_event);
+						}
+
+						let mut tabs: Vec<EventLogTabDb> = Vec::new();
+						for template_tab in template.tabs.iter() {
+							let Some(start_time) = TimeDelta::try_seconds(template_tab.start_offset_seconds)
+								.and_then(|offset| event.start_time.checked_add_signed(offset))
+							else {
+								return Err(diesel::result::Error::RollbackTransaction);
+							};
+							tabs.push(EventLogTabDb {
+								id: cuid2::create_id(),
+								event: event.id.clone(),
+								name: template_tab.name.clone(),
+								start_time,
+							});
+						}
+						let tabs: Vec<EventLogTabDb> = diesel::insert_into(event_log_tabs::table)
+							.values(tabs)
+							.get_results(db_connection)?;
+
+						let pages: Vec<InfoPageDb> = template
+							.info_pages
+							.iter()
+							.map(|page| InfoPageDb {
+								id: cuid2::create_id(),
+								event: event.id.clone(),
+								title: page.title.clone(),
+								contents: page.contents.clone(),
+								minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+							})
+							.collect();
+						let pages: Vec<InfoPageDb> = diesel::insert_into(info_pages::table)
+							.values(pages)
+							.get_results(db_connection)?;
+
+						let user_event_permissions: Vec<(String, Permission)> = user_permissions::table
+							.inner_join(
+								permission_events::table
+									.on(user_permissions::permission_group.eq(permission_events::permission_group)),
+							)
+							.filter(permission_events::event.eq(&event.id))
+							.select((user_permissions::user_id, permission_events::level))
+							.load(db_connection)?;
+						let mut user_levels: HashMap<String, Vec<Permission>> = HashMap::new();
+						for (user_id, level) in user_event_permissions {
+							user_levels.entry(user_id).or_default().push(level);
+						}
+						let user_permission_levels: HashMap<String, Permission> = user_levels
+							.into_iter()
+							.filter_map(|(user_id, levels)| Permission::highest(levels).map(|level| (user_id, level)))
+							.collect();
+
+						Ok(ImportedTemplateData {
+							new_entry_types,
+							event_entry_types,
+							new_groups,
+							group_permissions,
+							tabs,
+							info_pages: pages,
+							user_permission_levels,
+						})
+					})
+				})
+				.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				format!("Created event \"{}\" from a template", event.name),
+			)
+			.await;
+
+			let mut subscription_manager = subscription_manager.lock().await;
+			let send_result = subscription_manager
+				.broadcast_admin_event_message(AdminEventData::UpdateEvent(event.clone()))
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!(
+					"Failed to broadcast an admin event update for an imported event: {}",
+					error
+				);
+			}
+
+			for entry_type in imported_data.new_entry_types {
+				let admin_message =
+					SubscriptionData::AdminEntryTypesUpdate(AdminEntryTypeData::UpdateEntryType(entry_type.into()));
+				let send_result = subscription_manager
+					.broadcast_admin_entry_types_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast new entry type for an imported event: {}", error);
+				}
+			}
+			for entry_type in imported_data.event_entry_types {
+				let association = EntryTypeEventAssociation {
+					entry_type: entry_type.into(),
+					event: event.clone(),
+				};
+				let admin_message =
+					SubscriptionData::AdminEntryTypesEventsUpdate(AdminEntryTypeEventData::AddTypeToEvent(association));
+				let send_result = subscription_manager
+					.broadcast_admin_entry_types_events_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast entry type for an imported event: {}", error);
+				}
+			}
+
+			for group in imported_data.new_groups {
+				let admin_message =
+					SubscriptionData::AdminPermissionGroupsUpdate(AdminPermissionGroupData::UpdateGroup(group.into()));
+				let send_result = subscription_manager
+					.broadcast_admin_permission_groups_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!(
+						"Failed to broadcast new permission group for an imported event: {}",
+						error
+					);
+				}
+			}
+			for group_permission in imported_data.group_permissions {
+				let association: PermissionGroupEventAssociation = group_permission.into();
+				let admin_message = SubscriptionData::AdminPermissionGroupsUpdate(
+					AdminPermissionGroupData::SetEventPermissionForGroup(association),
+				);
+				let send_result = subscription_manager
+					.broadcast_admin_permission_groups_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast permission group for an imported event: {}", error);
+				}
+			}
+
+			for tab in imported_data.tabs {
+				let tab = EventLogTab {
+					id: tab.id,
+					name: tab.name,
+					start_time: tab.start_time,
+				};
+				let send_result = subscription_manager
+					.broadcast_admin_event_log_tabs_message(
+						&event.id,
+						AdminEventLogTabsData::AddTab(event.clone(), tab),
+					)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast event log tab for an imported event: {}", error);
+				}
+			}
+
+			for page in imported_data.info_pages {
+				let info_page = InfoPage {
+					id: page.id,
+					event: event.clone(),
+					title: page.title,
+					contents: page.contents,
+					minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+				};
+				let admin_message =
+					SubscriptionData::AdminInfoPagesUpdate(AdminInfoPageData::UpdateInfoPage(info_page));
+				let send_result = subscription_manager
+					.broadcast_admin_info_pages_message(admin_message)
+					.await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast info page for an imported event: {}", error);
+				}
+			}
+
+			for (user_id, permission) in imported_data.user_permission_levels {
+				let message = UserDataUpdate::EventPermissions(event.clone(), Some(permission));
+				subscription_manager.send_message_to_user(&user_id, message).await;
+			}
+		}
+		AdminEventUpdate::ArchiveAndPurge(event) => {
+			let Some(archive_storage) = archive_storage else {
+				return Err(HandlerError::InvalidData(String::from(
+					"Event archiving isn't set up on this server.",
+				)));
+			};
+
+			let archive_data = run_db_operation(&db_connection_pool, "exporting an event archive", |db_connection| {
+				db_connection
+					.build_transaction()
+					.repeatable_read()
+					.read_only()
+					.run(|db_connection| load_event_archive_data(db_connection, &event.id))
+			})
+			.await?;
+			let archive = EventArchive::new(archive_data);
+			let archive_file_name = write_event_archive(archive_storage, &archive)
+				.await
+				.map_err(|error| HandlerError::Storage("writing an event archive", error))?;
+
+			// Purging only goes ahead if the event's data is exactly what was archived. If anything changed while the
+			// archive was being written, the admin can archive again to get a copy that includes the changes.
+			let users_with_access =
+				run_db_operation(&db_connection_pool, "purging an archived event", |db_connection| {
+					db_connection
+						.build_transaction()
+						.serializable()
+						.run(|db_connection| purge_archived_event(db_connection, &archive.data))
+				})
+				.await?;
+			let Some(users_with_access) = users_with_access else {
+				return Err(HandlerError::InvalidData(String::from(
+					"The event changed while it was being archived, so it wasn't deleted. Try again to archive the changes.",
+				)));
+			};
+			tide::log::info!(
+				"Event {} was archived to {} and purged",
+				archive.data.event.id,
+				archive_file_name
+			);
+
+			let event: Event = archive.data.event.into();
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				format!("Archived and deleted event \"{}\" to {}", event.name, archive_file_name),
+			)
+			.await;
+
+			let mut subscription_manager = subscription_manager.lock().await;
+			subscription_manager.end_event_subscriptions(&event.id).await;
+
+			let send_result = subscription_manager
+				.broadcast_admin_event_message(AdminEventData::RemoveEvent(event.clone()))
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast an admin event removal: {}", error);
+			}
+
+			for user_id in users_with_access {
+				let message = UserDataUpdate::EventPermissions(event.clone(), None);
+				subscription_manager.send_message_to_user(&user_id, message).await;
+			}
+
+			let message = FromServerMessage::SubscriptionMessage(
+				Box::new(SubscriptionData::AdminEventArchived(event, archive_file_name)),
+				None,
+			);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+		}
+		AdminEventUpdate::RenumberEntries(event) => {
+			let (event, new_entry_numbers) =
+				run_db_operation(&db_connection_pool, "renumbering entries", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						// Lock the event so new entries can't be numbered while we renumber
+						let event: EventDb = events::table.find(&event.id).for_update().first(db_connection)?;
+						let entries: Vec<EventLogEntryDb> = event_log::table
+							.filter(event_log::event.eq(&event.id))
+							.load(db_connection)?;
+
+						let new_entry_numbers = renumbered_entries(&entries);
+						for (entry_id, entry_number) in new_entry_numbers.iter() {
+							diesel::update(event_log::table)
+								.filter(event_log::id.eq(entry_id))
+								.set(event_log::entry_number.eq(entry_number))
+								.execute(db_connection)?;
+						}
+
+						Ok((event, new_entry_numbers))
+					})
+				})
+				.await?;
+			let event: Event = event.into();
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Event,
+				format!(
+					"Renumbered entries in event \"{}\" ({} changed)",
+					event.name,
+					new_entry_numbers.len()
+				),
+			)
+			.await;
+
+			if !new_entry_numbers.is_empty() {
+				let subscription_manager = subscription_manager.lock().await;
+				let message = SubscriptionData::EventUpdate(
+					event.clone(),
+					Box::new(EventSubscriptionData::UpdateEntryNumbers(new_entry_numbers)),
+				);
+				let send_result = subscription_manager.broadcast_event_message(&event.id, message).await;
+				if let Err(error) = send_result {
+					tide::log::error!("Failed to broadcast renumbered entries: {}", error);
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Determines new numbers for the provided entries of an event, numbering them in the order they appear in the log with
+/// children following their parents. Deleted entries are numbered after all other entries. Entries with a video link
+/// keep their numbers, and other entries are numbered around them. Returns the entries whose numbers changed, mapped
+/// to their new numbers.
+fn renumbered_entries(entries: &[EventLogEntryDb]) -> HashMap<String, i32> {
+	let entry_ids: HashSet<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+	let mut children: HashMap<Option<&str>, Vec<&EventLogEntryDb>> = HashMap::new();
+	for entry in entries.iter() {
+		let parent = entry.parent.as_deref().filter(|parent| entry_ids.contains(parent));
+		children.entry(parent).or_default().push(entry);
+	}
+	for sibling_entries in children.values_mut() {
+		sibling_entries.sort_by(|a, b| {
+			a.start_time
+				.cmp(&b.start_time)
+				.then_with(|| match (a.manual_sort_key, b.manual_sort_key) {
+					(Some(a_key), Some(b_key)) => a_key.cmp(&b_key),
+					(Some(_), None) => Ordering::Less,
+					(None, Some(_)) => Ordering::Greater,
+					(None, None) => Ordering::Equal,
+				})
+				.then_with(|| a.created_at.cmp(&b.created_at))
+		});
+	}
+
+	let mut ordered_entries: Vec<(&EventLogEntryDb, bool)> = Vec::with_capacity(entries.len());
+	let mut entry_stack: Vec<(&EventLogEntryDb, bool)> = children
+		.get(&None)
+		.map(|root_entries| root_entries.iter().rev().map(|entry| (*entry, false)).collect())
+		.unwrap_or_default();
+	while let Some((entry, parent_deleted)) = entry_stack.pop() {
+		let deleted = parent_deleted || entry.deleted_by.is_some();
+		ordered_entries.push((entry, deleted));
+		if let Some(child_entries) = children.get(&Some(entry.id.as_str())) {
+			entry_stack.extend(child_entries.iter().rev().map(|child| (*child, deleted)));
+		}
+	}
+	ordered_entries.sort_by_key(|(_, deleted)| *deleted);
+
+	let kept_numbers: HashSet<i32> = entries
+		.iter()
+		.filter(|entry| entry.video_link.is_some())
+		.map(|entry| entry.entry_number)
+		.collect();
+	let mut new_entry_numbers = HashMap::new();
+	let mut next_number = 1;
+	for (entry, _) in ordered_entries {
+		if entry.video_link.is_some() {
+			continue;
+		}
+		while kept_numbers.contains(&next_number) {
+			next_number += 1;
+		}
+		if entry.entry_number != next_number {
+			new_entry_numbers.insert(entry.id.clone(), next_number);
+		}
+		next_number += 1;
 	}
+
+	new_entry_numbers
+}
+
+/// Data created while importing an event template, used to notify subscribers of the new data
+struct ImportedTemplateData {
+	/// Entry types that didn't exist before the import
+	new_entry_types: Vec<EntryTypeDb>,
+	/// All entry types made available for the new event
+	event_entry_types: Vec<EntryTypeDb>,
+	/// Permission groups that didn't exist before the import
+	new_groups: Vec<PermissionGroupDb>,
+	group_permissions: Vec<PermissionEvent>,
+	tabs: Vec<EventLogTabDb>,
+	info_pages: Vec<InfoPageDb>,
+	/// The highest permission level each user has for the new event
+	user_permission_levels: HashMap<String, Permission>,
 }
@@ -0,0 +1,47 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use crate::jobs::JobQueue;
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_jobs(
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	job_queue: &JobQueue,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message =
+			FromServerMessage::SubscriptionFailure(SubscriptionType::AdminJobs, SubscriptionFailureInfo::NotAllowed);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	// Job updates are sent while the subscription manager is locked, so holding the lock until the current jobs are
+	// read makes sure no update is missed in between.
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_jobs_subscription(connection_id, conn_update_tx.clone())
+		.await;
+	let jobs = job_queue.jobs().await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminJobs(jobs)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
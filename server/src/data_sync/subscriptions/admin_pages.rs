@@ -4,16 +4,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
-use crate::models::{Event as EventDb, InfoPage as InfoPageDb};
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, InfoPage as InfoPageDb, Permission};
 use crate::schema::{events, info_pages};
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
-use stream_log_shared::messages::admin::{AdminInfoPageData, AdminInfoPageUpdate};
+use stream_log_shared::messages::admin::{AdminActionType, AdminInfoPageData, AdminInfoPageUpdate};
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
 use stream_log_shared::messages::info_pages::InfoPage;
@@ -21,7 +22,7 @@ use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_info_pages(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -41,38 +42,22 @@ pub async fn subscribe_to_admin_info_pages(
 		return Ok(());
 	}
 
-	let query_result: QueryResult<(Vec<EventDb>, Vec<InfoPageDb>)> = {
-		let mut db_connection = match db_connection_pool.get() {
-			Ok(connection) => connection,
-			Err(error) => {
-				send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminInfoPages)
-					.await?;
-				return Ok(());
-			}
-		};
-		db_connection.transaction(|db_connection| {
-			let info_pages: Vec<InfoPageDb> = info_pages::table.load(db_connection)?;
-			let events: Vec<EventDb> = events::table.load(db_connection)?;
-			Ok((events, info_pages))
-		})
-	};
-
-	let (events, info_pages) = match query_result {
-		Ok(data) => data,
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred retrieving info pages for admin subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminInfoPages,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let query_result: Option<(Vec<EventDb>, Vec<InfoPageDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminInfoPages,
+		"retrieving info pages for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let info_pages: Vec<InfoPageDb> = info_pages::table.load(db_connection)?;
+				let events: Vec<EventDb> = events::table.load(db_connection)?;
+				Ok((events, info_pages))
+			})
+		},
+	)
+	.await?;
+	let Some((events, info_pages)) = query_result else {
+		return Ok(());
 	};
 
 	let events: HashMap<String, Event> = events
@@ -87,6 +72,7 @@ pub async fn subscribe_to_admin_info_pages(
 			event: events.get(&page.event).unwrap().clone(),
 			title: page.title,
 			contents: page.contents,
+			minimum_permission: page.minimum_permission.map(|permission| permission.into()),
 		})
 		.collect();
 
@@ -110,9 +96,9 @@ pub async fn handle_admin_info_pages_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminInfoPageUpdate,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 
 	if !subscription_manager
@@ -121,72 +107,99 @@ pub async fn handle_admin_info_pages_message(
 		.is_subscribed_to_admin_info_pages(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminInfoPageUpdate::UpdateInfoPage(info_page) => {
-			let info_page: InfoPage =
-				{
-					let mut db_connection = match db_connection_pool.get() {
-						Ok(connection) => connection,
-						Err(error) => {
-							tide::log::error!("A dtaabase connection occurred updating an info page: {}", error);
-							return;
-						}
-					};
-					let db_result: QueryResult<InfoPageDb> = if info_page.id.is_empty() {
-						let new_info_page = InfoPageDb {
-							id: cuid2::create_id(),
-							event: info_page.event.id,
-							title: info_page.title,
-							contents: info_page.contents,
+			let is_new_page = info_page.id.is_empty();
+			let new_page_id = cuid2::create_id();
+			let minimum_permission: Option<Permission> =
+				info_page.minimum_permission.map(|permission| permission.into());
+			let (page, event): (InfoPageDb, EventDb) =
+				run_db_operation(&db_connection_pool, "updating an info page", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let page: InfoPageDb = if is_new_page {
+							let new_info_page = InfoPageDb {
+								id: new_page_id.clone(),
+								event: info_page.event.id.clone(),
+								title: info_page.title.clone(),
+								contents: info_page.contents.clone(),
+								minimum_permission: info_page.minimum_permission.map(|permission| permission.into()),
+							};
+							diesel::insert_into(info_pages::table)
+								.values(new_info_page)
+								.get_result(db_connection)?
+						} else {
+							diesel::update(info_pages::table)
+								.filter(info_pages::id.eq(&info_page.id))
+								.set((
+									info_pages::title.eq(&info_page.title),
+									info_pages::contents.eq(&info_page.contents),
+									info_pages::minimum_permission.eq(minimum_permission),
+								))
+								.get_result(db_connection)?
 						};
-						diesel::insert_into(info_pages::table)
-							.values(new_info_page)
-							.get_result(&mut *db_connection)
-					} else {
-						diesel::update(info_pages::table)
-							.filter(info_pages::id.eq(&info_page.id))
-							.set((
-								info_pages::title.eq(info_page.title),
-								info_pages::contents.eq(info_page.contents),
-							))
-							.get_result(&mut *db_connection)
-					};
-
-					match db_result {
-						Ok(page) => {
-							let event: EventDb =
-								match events::table.find(&page.event).first(&mut *db_connection) {
-									Ok(event) => event,
-									Err(error) => {
-										tide::log::error!("Failed to get event associated with info page for admin message broadcast: {}", error);
-										return;
-									}
-								};
-							InfoPage {
-								id: page.id,
-								event: event.into(),
-								title: page.title,
-								contents: page.contents,
-							}
-						}
-						Err(error) => {
-							tide::log::error!("Failed to update info page for admin update: {}", error);
-							return;
-						}
-					}
-				};
+						let event: EventDb = events::table.find(&page.event).first(db_connection)?;
+						Ok((page, event))
+					})
+				})
+				.await?;
+			let info_page = InfoPage {
+				id: page.id,
+				event: event.into(),
+				title: page.title,
+				contents: page.contents,
+				minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+			};
+			let description = if is_new_page {
+				format!(
+					"Created info page \"{}\" for event \"{}\"",
+					info_page.title, info_page.event.name
+				)
+			} else {
+				format!(
+					"Updated info page \"{}\" for event \"{}\"",
+					info_page.title, info_page.event.name
+				)
+			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::InfoPage,
+				description,
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let event_message = SubscriptionData::EventUpdate(
 				info_page.event.clone(),
 				Box::new(EventSubscriptionData::UpdateInfoPage(info_page.clone())),
 			);
-			let send_result = subscription_manager
-				.broadcast_event_message(&info_page.event.id, event_message)
-				.await;
+			let send_result = match page.minimum_permission {
+				Some(minimum_permission) => {
+					// Users who can't see the page are told it was deleted so that it's removed for them if its
+					// visibility was just restricted. Only the ID is sent so nothing about the page is leaked to them.
+					let hidden_message = SubscriptionData::EventUpdate(
+						info_page.event.clone(),
+						Box::new(EventSubscriptionData::DeleteInfoPage(info_page.id.clone())),
+					);
+					subscription_manager
+						.broadcast_event_message_by_permission(
+							&info_page.event.id,
+							minimum_permission,
+							event_message,
+							hidden_message,
+						)
+						.await
+				}
+				None => {
+					subscription_manager
+						.broadcast_event_message(&info_page.event.id, event_message)
+						.await
+				}
+			};
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to broadcast event update for info page: {}", error);
 			}
@@ -200,14 +213,7 @@ pub async fn handle_admin_info_pages_message(
 			}
 		}
 		AdminInfoPageUpdate::DeleteInfoPage(info_page) => {
-			let delete_result: QueryResult<EventDb> = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!("A database connection error occurred deleting an info page: {}", error);
-						return;
-					}
-				};
+			let event: EventDb = run_db_operation(&db_connection_pool, "deleting an info page", |db_connection| {
 				db_connection.transaction(|db_connection| {
 					let page: InfoPageDb = diesel::delete(info_pages::table)
 						.filter(info_pages::id.eq(&info_page.id))
@@ -215,19 +221,25 @@ pub async fn handle_admin_info_pages_message(
 					let event: EventDb = events::table.find(&page.event).first(db_connection)?;
 					Ok(event)
 				})
-			};
-			let event: Event = match delete_result {
-				Ok(event) => event.into(),
-				Err(error) => {
-					tide::log::error!("An error occurred deleting an info page: {}", error);
-					return;
-				}
-			};
+			})
+			.await?;
+			let event: Event = event.into();
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::InfoPage,
+				format!(
+					"Deleted info page \"{}\" from event \"{}\"",
+					info_page.title, event.name
+				),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let event_message = SubscriptionData::EventUpdate(
 				event.clone(),
-				Box::new(EventSubscriptionData::DeleteInfoPage(info_page.clone())),
+				Box::new(EventSubscriptionData::DeleteInfoPage(info_page.id.clone())),
 			);
 			let send_result = subscription_manager
 				.broadcast_event_message(&event.id, event_message)
@@ -245,4 +257,6 @@ pub async fn handle_admin_info_pages_message(
 			}
 		}
 	}
+
+	Ok(())
 }
@@ -4,9 +4,11 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use super::user_notifications::send_user_notification;
 use crate::data_sync::user::UserDataUpdate;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
 use crate::models::{
 	Event as EventDb, Permission, PermissionEvent, PermissionGroup as PermissionGroupDb, User, UserPermission,
 };
@@ -17,15 +19,17 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
 use stream_log_shared::messages::admin::{
-	AdminPermissionGroupData, AdminPermissionGroupUpdate, AdminUserPermissionGroupData, AdminUserPermissionGroupUpdate,
-	PermissionGroup, PermissionGroupEventAssociation, UserPermissionGroupAssociation,
+	standard_event_group_name, AdminActionType, AdminPermissionGroupData, AdminPermissionGroupUpdate,
+	AdminUserPermissionGroupData, AdminUserPermissionGroupUpdate, PermissionGroup, PermissionGroupEventAssociation,
+	UserPermissionGroupAssociation, STANDARD_EVENT_GROUPS,
 };
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::notifications::UserNotificationDetails;
 use stream_log_shared::messages::subscriptions::{
 	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_permission_groups(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -45,52 +49,28 @@ pub async fn subscribe_to_admin_permission_groups(
 		return Ok(());
 	}
 
-	let (permission_groups, permission_group_events) = {
-		let mut db_connection = match db_connection_pool.get() {
-			Ok(connection) => connection,
-			Err(error) => {
-				send_lost_db_connection_subscription_response(
-					error,
-					&conn_update_tx,
-					SubscriptionType::AdminPermissionGroups,
-				)
-				.await?;
-				return Ok(());
-			}
-		};
-		let permission_groups: QueryResult<Vec<PermissionGroupDb>> = permission_groups::table.load(&mut *db_connection);
-		let permission_group_events: QueryResult<Vec<PermissionEvent>> =
-			permission_events::table.load(&mut *db_connection);
-		(permission_groups, permission_group_events)
-	};
-	let permission_groups: Vec<PermissionGroup> = match permission_groups {
-		Ok(permission_groups) => permission_groups.into_iter().map(|group| group.into()).collect(),
-		Err(error) => {
-			tide::log::error!("A database error occurred getting the permission groups for an admin permission groups subscription: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminPermissionGroups,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-	let permission_group_events: Vec<PermissionGroupEventAssociation> = match permission_group_events {
-		Ok(group_events) => group_events.into_iter().map(|association| association.into()).collect(),
-		Err(error) => {
-			tide::log::error!("A database error occurred getting the permission group and event associations for an admin permission groups subscription: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminPermissionGroups,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let db_data: Option<(Vec<PermissionGroupDb>, Vec<PermissionEvent>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminPermissionGroups,
+		"getting the permission groups for an admin permission groups subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let permission_groups: Vec<PermissionGroupDb> = permission_groups::table.load(db_connection)?;
+				let permission_group_events: Vec<PermissionEvent> = permission_events::table.load(db_connection)?;
+				Ok((permission_groups, permission_group_events))
+			})
+		},
+	)
+	.await?;
+	let Some((permission_groups, permission_group_events)) = db_data else {
+		return Ok(());
 	};
+	let permission_groups: Vec<PermissionGroup> = permission_groups.into_iter().map(|group| group.into()).collect();
+	let permission_group_events: Vec<PermissionGroupEventAssociation> = permission_group_events
+		.into_iter()
+		.map(|association| association.into())
+		.collect();
 
 	let subscription_manager = subscription_manager.lock().await;
 	subscription_manager
@@ -113,9 +93,9 @@ pub async fn handle_admin_permission_groups_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminPermissionGroupUpdate,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -123,46 +103,46 @@ pub async fn handle_admin_permission_groups_message(
 		.is_subscribed_to_admin_permission_groups(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminPermissionGroupUpdate::UpdateGroup(mut group) => {
-			{
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred adding a new permission group: {}",
-							error
-						);
-						return;
-					}
+			let is_new_group = group.id.is_empty();
+			if is_new_group {
+				group.id = cuid2::create_id();
+				let group_db = PermissionGroupDb {
+					id: group.id.clone(),
+					name: group.name.clone(),
 				};
-				if group.id.is_empty() {
-					group.id = cuid2::create_id();
-					let group_db = PermissionGroupDb {
-						id: group.id.clone(),
-						name: group.name.clone(),
-					};
-					let db_result = diesel::insert_into(permission_groups::table)
-						.values(group_db)
-						.execute(&mut *db_connection);
-					if let Err(error) = db_result {
-						tide::log::error!("A database error occurred adding a new permission group: {}", error);
-						return;
-					}
-				} else {
-					let db_result = diesel::update(permission_groups::table)
+				run_db_operation(&db_connection_pool, "adding a new permission group", |db_connection| {
+					diesel::insert_into(permission_groups::table)
+						.values(&group_db)
+						.execute(db_connection)
+				})
+				.await?;
+			} else {
+				run_db_operation(&db_connection_pool, "updating a permission group", |db_connection| {
+					diesel::update(permission_groups::table)
 						.filter(permission_groups::id.eq(&group.id))
 						.set(permission_groups::name.eq(&group.name))
-						.execute(&mut *db_connection);
-					if let Err(error) = db_result {
-						tide::log::error!("A database error occurred updating a permission group: {}", error);
-						return;
-					}
-				}
+						.execute(db_connection)
+				})
+				.await?;
 			}
+			let description = if is_new_group {
+				format!("Created permission group \"{}\"", group.name)
+			} else {
+				format!("Renamed permission group to \"{}\"", group.name)
+			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::PermissionGroup,
+				description,
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
 			let message = SubscriptionData::AdminPermissionGroupsUpdate(AdminPermissionGroupData::UpdateGroup(group));
@@ -174,32 +154,36 @@ pub async fn handle_admin_permission_groups_message(
 			}
 		}
 		AdminPermissionGroupUpdate::SetEventPermissionForGroup(event_group_association) => {
-			let (user_permissions, event) = {
+			let permission_event = PermissionEvent {
+				permission_group: event_group_association.group.clone(),
+				event: event_group_association.event.clone(),
+				level: event_group_association.permission.into(),
+			};
+			run_db_operation(
+				&db_connection_pool,
+				"setting permissions for an event in a permission group",
+				|db_connection| {
+					diesel::insert_into(permission_events::table)
+						.values(&permission_event)
+						.on_conflict((permission_events::permission_group, permission_events::event))
+						.do_update()
+						.set(permission_events::level.eq(permission_event.level))
+						.execute(db_connection)
+				},
+			)
+			.await?;
+
+			let (user_permissions, event, group_name) = {
 				let mut db_connection = match db_connection_pool.get() {
 					Ok(connection) => connection,
 					Err(error) => {
-						tide::log::error!("A database connection error occurred setting permissions for an event in a permission group: {}", error);
-						return;
+						tide::log::error!(
+							"A database connection error occurred getting users affected by a permission group update: {}",
+							error
+						);
+						return Ok(());
 					}
 				};
-				let permission_event = PermissionEvent {
-					permission_group: event_group_association.group.clone(),
-					event: event_group_association.event.clone(),
-					level: event_group_association.permission.into(),
-				};
-				let db_result = diesel::insert_into(permission_events::table)
-					.values(&permission_event)
-					.on_conflict((permission_events::permission_group, permission_events::event))
-					.do_update()
-					.set(permission_events::level.eq(permission_event.level))
-					.execute(&mut *db_connection);
-				if let Err(error) = db_result {
-					tide::log::error!(
-						"A database error occurred setting permissions for an event in a permission group: {}",
-						error
-					);
-					return;
-				}
 
 				// If this update lowered the event's permissions in this group, each user's other groups *might* have a higher permission level for the event.
 				let user_permissions: QueryResult<Vec<(String, Option<Permission>)>> = user_permissions::table
@@ -221,12 +205,28 @@ pub async fn handle_admin_permission_groups_message(
 							"A database error occurred getting the event associated with a permission group update: {}",
 							error
 						);
-						return;
+						return Ok(());
 					}
 				};
+				let group_name: QueryResult<String> = permission_groups::table
+					.find(&event_group_association.group)
+					.select(permission_groups::name)
+					.first(&mut *db_connection);
+				let group_name = group_name.unwrap_or_else(|_| event_group_association.group.clone());
 
-				(user_permissions, event)
+				(user_permissions, event, group_name)
 			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::PermissionGroup,
+				format!(
+					"Set {:?} permission for event \"{}\" in permission group \"{}\"",
+					event_group_association.permission, event.name, group_name
+				),
+			)
+			.await;
 
 			let mut subscription_manager = subscription_manager.lock().await;
 			let admin_message = SubscriptionData::AdminPermissionGroupsUpdate(
@@ -253,32 +253,19 @@ pub async fn handle_admin_permission_groups_message(
 			}
 		}
 		AdminPermissionGroupUpdate::RemoveEventFromGroup(group, event) => {
-			let user_permissions: QueryResult<Vec<(String, Option<Permission>)>> =
-				{
-					let mut db_connection =
-						match db_connection_pool.get() {
-							Ok(connection) => connection,
-							Err(error) => {
-								tide::log::error!("A database connection error occurred removing an event from a permission group: {}", error);
-								return;
-							}
-						};
-					let db_result = diesel::delete(permission_events::table)
+			let user_permissions: QueryResult<Vec<(String, Option<Permission>)>> = run_db_operation(
+				&db_connection_pool,
+				"removing an event from a permission group",
+				|db_connection| {
+					diesel::delete(permission_events::table)
 						.filter(
 							permission_events::permission_group
 								.eq(&group.id)
 								.and(permission_events::event.eq(&event.id)),
 						)
-						.execute(&mut *db_connection);
-					if let Err(error) = db_result {
-						tide::log::error!(
-							"A database error occurred removing an event from a permission group: {}",
-							error
-						);
-						return;
-					}
+						.execute(db_connection)?;
 
-					user_permissions::table
+					Ok(user_permissions::table
 						.filter(user_permissions::permission_group.eq(&event.id))
 						.left_outer_join(
 							permission_events::table
@@ -286,8 +273,21 @@ pub async fn handle_admin_permission_groups_message(
 						)
 						.filter(permission_events::event.eq(&event.id))
 						.select((user_permissions::user_id, permission_events::level.nullable()))
-						.load(&mut *db_connection)
-				};
+						.load(db_connection))
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::PermissionGroup,
+				format!(
+					"Removed event \"{}\" from permission group \"{}\"",
+					event.name, group.name
+				),
+			)
+			.await;
 
 			let mut subscription_manager = subscription_manager.lock().await;
 			let admin_message = SubscriptionData::AdminPermissionGroupsUpdate(
@@ -316,7 +316,134 @@ pub async fn handle_admin_permission_groups_message(
 				),
 			}
 		}
+		AdminPermissionGroupUpdate::DuplicateGroup(source_group, new_group_name) => {
+			if new_group_name.is_empty() {
+				return Err(HandlerError::InvalidData(String::from("The new group needs a name.")));
+			}
+			let new_group = PermissionGroupDb {
+				id: cuid2::create_id(),
+				name: new_group_name,
+			};
+			let new_group_events: Vec<PermissionEvent> =
+				run_db_operation(&db_connection_pool, "duplicating a permission group", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						diesel::insert_into(permission_groups::table)
+							.values(&new_group)
+							.execute(db_connection)?;
+
+						let source_group_events: Vec<PermissionEvent> = permission_events::table
+							.filter(permission_events::permission_group.eq(&source_group.id))
+							.load(db_connection)?;
+						let new_group_events: Vec<PermissionEvent> = source_group_events
+							.into_iter()
+							.map(|permission_event| PermissionEvent {
+								permission_group: new_group.id.clone(),
+								event: permission_event.event,
+								level: permission_event.level,
+							})
+							.collect();
+						diesel::insert_into(permission_events::table)
+							.values(&new_group_events)
+							.execute(db_connection)?;
+
+						Ok(new_group_events)
+					})
+				})
+				.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::PermissionGroup,
+				format!(
+					"Created permission group \"{}\" as a copy of \"{}\"",
+					new_group.name, source_group.name
+				),
+			)
+			.await;
+
+			// The new group doesn't have any users yet, so nobody's permissions change
+			let subscription_manager = subscription_manager.lock().await;
+			broadcast_new_group(&subscription_manager, new_group, new_group_events).await;
+		}
+		AdminPermissionGroupUpdate::CreateStandardEventGroups(event) => {
+			let new_groups: Vec<(PermissionGroupDb, PermissionEvent)> = STANDARD_EVENT_GROUPS
+				.iter()
+				.map(|(group_name, permission)| {
+					let group = PermissionGroupDb {
+						id: cuid2::create_id(),
+						name: standard_event_group_name(&event, group_name),
+					};
+					let permission_event = PermissionEvent {
+						permission_group: group.id.clone(),
+						event: event.id.clone(),
+						level: (*permission).into(),
+					};
+					(group, permission_event)
+				})
+				.collect();
+			run_db_operation(
+				&db_connection_pool,
+				"creating the standard permission groups for an event",
+				|db_connection| {
+					db_connection.transaction(|db_connection| {
+						for (group, permission_event) in new_groups.iter() {
+							diesel::insert_into(permission_groups::table)
+								.values(group)
+								.execute(db_connection)?;
+							diesel::insert_into(permission_events::table)
+								.values(permission_event)
+								.execute(db_connection)?;
+						}
+						Ok(())
+					})
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::PermissionGroup,
+				format!("Created the standard permission groups for event \"{}\"", event.name),
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			for (group, permission_event) in new_groups {
+				broadcast_new_group(&subscription_manager, group, vec![permission_event]).await;
+			}
+		}
 	};
+
+	Ok(())
+}
+
+/// Sends a newly created group and its event permissions to admin permission group subscribers
+async fn broadcast_new_group(
+	subscription_manager: &SubscriptionManager,
+	group: PermissionGroupDb,
+	group_events: Vec<PermissionEvent>,
+) {
+	let message = SubscriptionData::AdminPermissionGroupsUpdate(AdminPermissionGroupData::UpdateGroup(group.into()));
+	let send_result = subscription_manager
+		.broadcast_admin_permission_groups_message(message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send admin permission group update: {}", error);
+	}
+
+	for group_event in group_events {
+		let message = SubscriptionData::AdminPermissionGroupsUpdate(
+			AdminPermissionGroupData::SetEventPermissionForGroup(group_event.into()),
+		);
+		let send_result = subscription_manager
+			.broadcast_admin_permission_groups_message(message)
+			.await;
+		if let Err(error) = send_result {
+			tide::log::error!("Failed to send permission group events update admin message: {}", error);
+		}
+	}
 }
 
 pub async fn subscribe_to_admin_permission_groups_users(
@@ -337,96 +464,39 @@ pub async fn subscribe_to_admin_permission_groups_users(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(
-				error,
-				&conn_update_tx,
-				SubscriptionType::AdminPermissionGroupUsers,
-			)
-			.await?;
-			return Ok(());
-		}
-	};
-	let permission_group_users: QueryResult<Vec<UserPermission>> = user_permissions::table.load(&mut *db_connection);
-
-	let permission_group_users = match permission_group_users {
-		Ok(data) => data,
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting permission group user data for the admin subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminPermissionGroupUsers,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
+	let db_data: Option<(Vec<UserPermission>, Vec<User>, Vec<PermissionGroupDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminPermissionGroupUsers,
+		"getting permission group user data for the admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let permission_group_users: Vec<UserPermission> = user_permissions::table.load(db_connection)?;
+				let user_ids: Vec<String> = permission_group_users
+					.iter()
+					.map(|association| association.user_id.clone())
+					.collect();
+				let group_ids: Vec<String> = permission_group_users
+					.iter()
+					.map(|association| association.permission_group.clone())
+					.collect();
+				let users: Vec<User> = users::table.filter(users::id.eq_any(&user_ids)).load(db_connection)?;
+				let groups: Vec<PermissionGroupDb> = permission_groups::table
+					.filter(permission_groups::id.eq_any(&group_ids))
+					.load(db_connection)?;
+				Ok((permission_group_users, users, groups))
+			})
+		},
+	)
+	.await?;
+	let Some((permission_group_users, users, groups)) = db_data else {
+		return Ok(());
 	};
-	let user_ids: Vec<String> = permission_group_users
-		.iter()
-		.map(|association| association.user_id.clone())
+	let users: HashMap<String, PublicUserData> = users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
+	let permission_groups: HashMap<String, PermissionGroup> = groups
+		.into_iter()
+		.map(|group| (group.id.clone(), group.into()))
 		.collect();
-	let group_ids: Vec<String> = permission_group_users
-		.iter()
-		.map(|association| association.permission_group.clone())
-		.collect();
-	let users: QueryResult<Vec<User>> = users::table
-		.filter(users::id.eq_any(&user_ids))
-		.load(&mut *db_connection);
-	let groups: QueryResult<Vec<PermissionGroupDb>> = permission_groups::table
-		.filter(permission_groups::id.eq_any(&group_ids))
-		.load(&mut *db_connection);
-
-	let users = match users {
-		Ok(users) => {
-			let user_map: HashMap<String, PublicUserData> =
-				users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
-			user_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting users for permission group user admin data: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminPermissionGroupUsers,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-	let permission_groups = match groups {
-		Ok(groups) => {
-			let group_map: HashMap<String, PermissionGroup> = groups
-				.into_iter()
-				.map(|group| (group.id.clone(), group.into()))
-				.collect();
-			group_map
-		}
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting groups for permission group user admin data: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminPermissionGroupUsers,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
 
 	let mut permission_group_user_associations: Vec<UserPermissionGroupAssociation> =
 		Vec::with_capacity(permission_group_users.len());
@@ -460,9 +530,9 @@ pub async fn handle_admin_permission_group_users_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminUserPermissionGroupUpdate,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -470,103 +540,116 @@ pub async fn handle_admin_permission_group_users_message(
 		.is_subscribed_to_admin_permission_group_users(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
 		AdminUserPermissionGroupUpdate::AddUserToGroup(user_group_association) => {
-			let user_event_permissions = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred adding a user to a permission group: {}",
-							error
-						);
-						return;
-					}
-				};
-
-				let user_event_permissions: QueryResult<Vec<(Event, Option<Permission>)>> =
-					db_connection.transaction(|db_connection| {
-						let user_permission = UserPermission {
-							user_id: user_group_association.user.id.clone(),
-							permission_group: user_group_association.permission_group.id.clone(),
-						};
-						diesel::insert_into(user_permissions::table)
-							.values(user_permission)
-							.execute(&mut *db_connection)?;
-
-						let affected_event_permissions: Vec<PermissionEvent> = permission_events::table
-							.filter(permission_events::permission_group.eq(&user_group_association.permission_group.id))
-							.load(db_connection)?;
-						let affected_event_ids: Vec<String> = affected_event_permissions
-							.iter()
-							.map(|event_permission| event_permission.event.clone())
-							.collect();
-						let affected_events: Vec<EventDb> = events::table
-							.filter(events::id.eq_any(&affected_event_ids))
-							.load(db_connection)?;
-						let affected_events: Vec<Event> =
-							affected_events.into_iter().map(|event| event.into()).collect();
-
-						let all_user_event_permissions: Vec<PermissionEvent> = permission_events::table
-							.filter(
-								user_permissions::table
-									.filter(user_permissions::user_id.eq(&user_group_association.user.id).and(
-										user_permissions::permission_group.eq(permission_events::permission_group),
-									))
-									.count()
-									.single_value()
-									.gt(0),
-							)
-							.load(db_connection)?;
-						let mut user_event_permissions_by_event: HashMap<String, Vec<PermissionEvent>> = HashMap::new();
-						for user_event_permission in all_user_event_permissions {
-							user_event_permissions_by_event
-								.entry(user_event_permission.event.clone())
-								.or_default()
-								.push(user_event_permission);
-						}
+			let (user_event_permissions, previous_event_ids): (Vec<(Event, Option<Permission>)>, Vec<String>) =
+				run_db_operation(
+					&db_connection_pool,
+					"adding a user to a permission group",
+					|db_connection| {
+						db_connection.transaction(|db_connection| {
+							let previous_event_ids: Vec<String> = permission_events::table
+								.filter(
+									user_permissions::table
+										.filter(user_permissions::user_id.eq(&user_group_association.user.id).and(
+											user_permissions::permission_group.eq(permission_events::permission_group),
+										))
+										.count()
+										.single_value()
+										.gt(0),
+								)
+								.select(permission_events::event)
+								.distinct()
+								.load(db_connection)?;
+
+							let user_permission = UserPermission {
+								user_id: user_group_association.user.id.clone(),
+								permission_group: user_group_association.permission_group.id.clone(),
+							};
+							diesel::insert_into(user_permissions::table)
+								.values(&user_permission)
+								.execute(db_connection)?;
+
+							let affected_event_permissions: Vec<PermissionEvent> = permission_events::table
+								.filter(
+									permission_events::permission_group.eq(&user_group_association.permission_group.id),
+								)
+								.load(db_connection)?;
+							let affected_event_ids: Vec<String> = affected_event_permissions
+								.iter()
+								.map(|event_permission| event_permission.event.clone())
+								.collect();
+							let affected_events: Vec<EventDb> = events::table
+								.filter(events::id.eq_any(&affected_event_ids))
+								.load(db_connection)?;
+							let affected_events: Vec<Event> =
+								affected_events.into_iter().map(|event| event.into()).collect();
+
+							let all_user_event_permissions: Vec<PermissionEvent> = permission_events::table
+								.filter(
+									user_permissions::table
+										.filter(user_permissions::user_id.eq(&user_group_association.user.id).and(
+											user_permissions::permission_group.eq(permission_events::permission_group),
+										))
+										.count()
+										.single_value()
+										.gt(0),
+								)
+								.load(db_connection)?;
+							let mut user_event_permissions_by_event: HashMap<String, Vec<PermissionEvent>> =
+								HashMap::new();
+							for user_event_permission in all_user_event_permissions {
+								user_event_permissions_by_event
+									.entry(user_event_permission.event.clone())
+									.or_default()
+									.push(user_event_permission);
+							}
 
-						let mut user_event_permissions = Vec::new();
-						for event in affected_events {
-							match user_event_permissions_by_event.get(&event.id) {
-								Some(event_permissions) => {
-									let mut highest_permission_level: Option<Permission> = None;
-									for event_permission in event_permissions.iter() {
-										match (event_permission.level, highest_permission_level) {
-											(Permission::Supervisor, _) => {
-												highest_permission_level = Some(Permission::Supervisor);
-												break;
-											}
-											(Permission::Edit, Some(Permission::Supervisor)) => (),
-											(Permission::Edit, _) => highest_permission_level = Some(Permission::Edit),
-											(Permission::View, Some(Permission::Supervisor)) => (),
-											(Permission::View, Some(Permission::Edit)) => (),
-											(Permission::View, _) => highest_permission_level = Some(Permission::View),
-										}
+							let mut user_event_permissions = Vec::new();
+							for event in affected_events {
+								match user_event_permissions_by_event.get(&event.id) {
+									Some(event_permissions) => {
+										let highest_permission_level = Permission::highest(
+											event_permissions.iter().map(|event_permission| event_permission.level),
+										);
+										user_event_permissions.push((event, highest_permission_level));
 									}
-									user_event_permissions.push((event, highest_permission_level));
+									None => user_event_permissions.push((event, None)),
 								}
-								None => user_event_permissions.push((event, None)),
 							}
-						}
-
-						Ok(user_event_permissions)
-					});
 
-				match user_event_permissions {
-					Ok(data) => data,
-					Err(error) => {
-						tide::log::error!(
-							"A database error occurred adding a user to a permission group: {}",
-							error
-						);
-						return;
-					}
+							Ok((user_event_permissions, previous_event_ids))
+						})
+					},
+				)
+				.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::UserPermissionGroup,
+				format!(
+					"Added user \"{}\" to permission group \"{}\"",
+					user_group_association.user.username, user_group_association.permission_group.name
+				),
+			)
+			.await;
+
+			for (event, permission) in user_event_permissions.iter() {
+				if permission.is_some() && !previous_event_ids.contains(&event.id) {
+					send_user_notification(
+						&db_connection_pool,
+						&subscription_manager,
+						&user_group_association.user.id,
+						event,
+						UserNotificationDetails::AddedToEvent,
+					)
+					.await;
 				}
-			};
+			}
 
 			let mut subscription_manager = subscription_manager.lock().await;
 			for (event, permission) in user_event_permissions {
@@ -589,19 +672,10 @@ pub async fn handle_admin_permission_group_users_message(
 			}
 		}
 		AdminUserPermissionGroupUpdate::RemoveUserFromGroup(user_group_association) => {
-			let user_event_permissions = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred removing a user from a permission group: {}",
-							error
-						);
-						return;
-					}
-				};
-
-				let user_event_permissions: QueryResult<Vec<(Event, Option<Permission>)>> =
+			let user_event_permissions: Vec<(Event, Option<Permission>)> = run_db_operation(
+				&db_connection_pool,
+				"removing a user from a permission group",
+				|db_connection| {
 					db_connection.transaction(|db_connection| {
 						diesel::delete(user_permissions::table)
 							.filter(user_permissions::user_id.eq(&user_group_association.user.id).and(
@@ -645,19 +719,9 @@ pub async fn handle_admin_permission_group_users_message(
 						for event in affected_events {
 							match user_event_permissions_by_event.get(&event.id) {
 								Some(event_permissions) => {
-									let mut highest_permission_level: Option<Permission> = None;
-									for event_permission in event_permissions.iter() {
-										match (event_permission.level, highest_permission_level) {
-											(Permission::Supervisor, _) => {
-												highest_permission_level = Some(Permission::Supervisor)
-											}
-											(Permission::Edit, Some(Permission::Supervisor)) => (),
-											(Permission::Edit, _) => highest_permission_level = Some(Permission::Edit),
-											(Permission::View, Some(Permission::Supervisor)) => (),
-											(Permission::View, Some(Permission::Edit)) => (),
-											(Permission::View, _) => highest_permission_level = Some(Permission::View),
-										}
-									}
+									let highest_permission_level = Permission::highest(
+										event_permissions.iter().map(|event_permission| event_permission.level),
+									);
 									user_event_permissions.push((event, highest_permission_level));
 								}
 								None => user_event_permissions.push((event, None)),
@@ -665,19 +729,21 @@ pub async fn handle_admin_permission_group_users_message(
 						}
 
 						Ok(user_event_permissions)
-					});
-
-				match user_event_permissions {
-					Ok(data) => data,
-					Err(error) => {
-						tide::log::error!(
-							"A database error occurred removing a user from a permission group: {}",
-							error
-						);
-						return;
-					}
-				}
-			};
+					})
+				},
+			)
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::UserPermissionGroup,
+				format!(
+					"Removed user \"{}\" from permission group \"{}\"",
+					user_group_association.user.username, user_group_association.permission_group.name
+				),
+			)
+			.await;
 
 			let mut subscription_manager = subscription_manager.lock().await;
 			for (event, permission) in user_event_permissions {
@@ -700,4 +766,6 @@ pub async fn handle_admin_permission_group_users_message(
 			}
 		}
 	}
+
+	Ok(())
 }
@@ -0,0 +1,150 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use crate::models::{Event as EventDb, RejectedEventUpdate as RejectedEventUpdateDb, User};
+use crate::schema::{events, rejected_event_updates, users};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::{AdminRejectedUpdateData, RejectedEventUpdate};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// The number of most recent rejected updates sent to administrators when they subscribe
+const INITIAL_REJECTED_UPDATE_COUNT: i64 = 500;
+
+pub async fn subscribe_to_admin_rejected_updates(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminRejectedUpdates,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let rejected_updates: Option<Vec<(RejectedEventUpdateDb, EventDb, User)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminRejectedUpdates,
+		"retrieving rejected updates for admin subscription",
+		|db_connection| {
+			rejected_event_updates::table
+				.inner_join(events::table)
+				.inner_join(users::table)
+				.order(rejected_event_updates::rejected_at.desc())
+				.limit(INITIAL_REJECTED_UPDATE_COUNT)
+				.load(db_connection)
+		},
+	)
+	.await?;
+	let Some(rejected_updates) = rejected_updates else {
+		return Ok(());
+	};
+	let rejected_updates: Vec<RejectedEventUpdate> = rejected_updates
+		.into_iter()
+		.map(|(rejected_update, event, user)| RejectedEventUpdate {
+			id: rejected_update.id,
+			event: event.into(),
+			user: user.into(),
+			entry_id: rejected_update.log_entry,
+			update_kind: rejected_update.update_kind,
+			reason: rejected_update.reason,
+			rejected_at: rejected_update.rejected_at,
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_rejected_updates_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminRejectedUpdates(rejected_updates),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+/// Records that the server refused to make a change to an event. The rejection is written to the server log and
+/// stored for administrators to review.
+pub async fn record_rejected_event_update(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	event: &Event,
+	user: &SelfUserData,
+	entry_id: Option<String>,
+	update_kind: &str,
+	reason: String,
+) {
+	tide::log::warn!("Rejected an event update", {
+		event: event.id.as_str(),
+		user: user.id.as_str(),
+		entry: entry_id.as_deref().unwrap_or_default(),
+		update_kind: update_kind,
+		reason: reason.as_str(),
+	});
+
+	let rejected_update = RejectedEventUpdate {
+		id: cuid2::create_id(),
+		event: event.clone(),
+		user: user.clone().into(),
+		entry_id,
+		update_kind: update_kind.to_string(),
+		reason,
+		rejected_at: Utc::now(),
+	};
+	let rejected_update_db = RejectedEventUpdateDb {
+		id: rejected_update.id.clone(),
+		event: rejected_update.event.id.clone(),
+		user_id: rejected_update.user.id.clone(),
+		log_entry: rejected_update.entry_id.clone(),
+		update_kind: rejected_update.update_kind.clone(),
+		reason: rejected_update.reason.clone(),
+		rejected_at: rejected_update.rejected_at,
+	};
+
+	let insert_result = run_db_operation(db_connection_pool, "recording a rejected update", |db_connection| {
+		diesel::insert_into(rejected_event_updates::table)
+			.values(&rejected_update_db)
+			.execute(db_connection)
+	})
+	.await;
+	if let Err(error) = insert_result {
+		tide::log::error!("{}", error);
+		return;
+	}
+
+	let message =
+		SubscriptionData::AdminRejectedUpdatesUpdate(AdminRejectedUpdateData::NewRejectedUpdate(rejected_update));
+	let send_result = subscription_manager
+		.lock()
+		.await
+		.broadcast_admin_rejected_updates_message(message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to broadcast a rejected update to administrators: {}", error);
+	}
+}
@@ -0,0 +1,178 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, EventShareLink as EventShareLinkDb};
+use crate::schema::{event_share_links, events};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as base64_engine;
+use base64::Engine;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use rand::random;
+use std::collections::HashMap;
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEventShareLinkData, AdminEventShareLinkUpdate, EventShareLink,
+};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_event_share_links(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminEventShareLinks,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let query_result: Option<(Vec<EventDb>, Vec<EventShareLinkDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventShareLinks,
+		"retrieving share links for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let share_links: Vec<EventShareLinkDb> = event_share_links::table.load(db_connection)?;
+				let events: Vec<EventDb> = events::table.load(db_connection)?;
+				Ok((events, share_links))
+			})
+		},
+	)
+	.await?;
+	let Some((events, share_links)) = query_result else {
+		return Ok(());
+	};
+
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
+		.collect();
+
+	let share_links: Vec<EventShareLink> = share_links
+		.into_iter()
+		.map(|share_link| EventShareLink {
+			event: events.get(&share_link.event).unwrap().clone(),
+			token: share_link.token,
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_event_share_links_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminEventShareLinks(share_links),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_admin_event_share_links_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: AdminEventShareLinkUpdate,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_event_share_links(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	let (admin_message, description) = match update_message {
+		AdminEventShareLinkUpdate::CreateShareLink(event) => {
+			let share_link = EventShareLinkDb {
+				event: event.id.clone(),
+				token: generate_share_token(),
+			};
+			let event: EventDb = run_db_operation(&db_connection_pool, "creating a share link", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					let event: EventDb = events::table.find(&share_link.event).first(db_connection)?;
+					diesel::insert_into(event_share_links::table)
+						.values(&share_link)
+						.on_conflict(event_share_links::event)
+						.do_update()
+						.set(event_share_links::token.eq(&share_link.token))
+						.execute(db_connection)?;
+					Ok(event)
+				})
+			})
+			.await?;
+
+			let description = format!("Created a share link for event \"{}\"", event.name);
+			let share_link = AdminEventShareLinkData::UpdateShareLink(EventShareLink {
+				event: event.into(),
+				token: share_link.token,
+			});
+			(share_link, description)
+		}
+		AdminEventShareLinkUpdate::DeleteShareLink(event) => {
+			run_db_operation(&db_connection_pool, "deleting a share link", |db_connection| {
+				diesel::delete(event_share_links::table)
+					.filter(event_share_links::event.eq(&event.id))
+					.execute(db_connection)
+			})
+			.await?;
+
+			let description = format!("Deleted the share link for event \"{}\"", event.name);
+			(AdminEventShareLinkData::DeleteShareLink(event), description)
+		}
+	};
+	record_admin_action(
+		&db_connection_pool,
+		&subscription_manager,
+		user,
+		AdminActionType::ShareLink,
+		description,
+	)
+	.await;
+
+	let subscription_manager = subscription_manager.lock().await;
+	let send_result = subscription_manager
+		.broadcast_admin_event_share_links_message(SubscriptionData::AdminEventShareLinksUpdate(admin_message))
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to broadcast admin update for share link: {}", error);
+	}
+
+	Ok(())
+}
+
+/// Generates a token for a share link. Since the token is all that's needed to view the event log, it needs to be
+/// long and random enough that it can't be guessed.
+fn generate_share_token() -> String {
+	let random_data: [u8; 32] = random();
+	base64_engine.encode(random_data)
+}
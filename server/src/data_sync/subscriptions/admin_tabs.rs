@@ -4,8 +4,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError, SubscriptionManager};
+use super::admin_audit_log::record_admin_action;
+use super::{load_subscription_data, user_is_event_supervisor};
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
 use crate::models::{Event as EventDb, EventLogTab as EventLogTabDb};
 use crate::schema::{event_log_tabs, events};
 use async_std::channel::Sender;
@@ -13,15 +14,16 @@ use async_std::sync::{Arc, Mutex};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
-use stream_log_shared::messages::admin::{AdminEventLogTabsData, AdminEventLogTabsUpdate};
+use stream_log_shared::messages::admin::{AdminActionType, AdminEventLogTabsData, AdminEventLogTabsUpdate};
 use stream_log_shared::messages::event_log::EventLogTab;
 use stream_log_shared::messages::event_subscription::EventSubscriptionData;
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::list_sync::{ListSyncLoad, ListVersion};
 use stream_log_shared::messages::subscriptions::{
-	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+	InitialSubscriptionLoadData, SubscriptionFailureInfo, SubscriptionType,
 };
 use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_event_log_tabs(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -29,6 +31,7 @@ pub async fn subscribe_to_admin_event_log_tabs(
 	connection_id: &str,
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	known_version: Option<ListVersion>,
 ) -> Result<(), HandleConnectionError> {
 	if !user.is_admin {
 		let message = FromServerMessage::SubscriptionFailure(
@@ -41,35 +44,38 @@ pub async fn subscribe_to_admin_event_log_tabs(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminEventLogTabs)
-				.await?;
-			return Ok(());
-		}
-	};
-	let db_data: QueryResult<(Vec<EventLogTabDb>, Vec<EventDb>)> = db_connection.transaction(|db_connection| {
-		let tabs = event_log_tabs::table.load(db_connection)?;
-		let events = events::table.load(db_connection)?;
-		Ok((tabs, events))
-	});
-	let (tabs, events) = match db_data {
-		Ok(data) => data,
-		Err(error) => {
-			tide::log::error!(
-				"A database error occurred getting event log tabs for an admin subscription: {}",
-				error
-			);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminEventLogTabs,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
+	if let Some(known_version) = known_version {
+		let subscription_manager = subscription_manager.lock().await;
+		if let Some(changes) = subscription_manager.admin_event_log_tabs_changes_since(&known_version) {
+			subscription_manager
+				.add_admin_event_log_tabs_subscription(connection_id, conn_update_tx.clone())
+				.await;
+			let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+				InitialSubscriptionLoadData::AdminEventLogTabs(ListSyncLoad::Patches(changes)),
+			));
 			conn_update_tx
 				.send(ConnectionUpdate::SendData(Box::new(message)))
 				.await?;
 			return Ok(());
 		}
+	}
+
+	let db_data: Option<(Vec<EventLogTabDb>, Vec<EventDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventLogTabs,
+		"getting event log tabs for an admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let tabs = event_log_tabs::table.load(db_connection)?;
+				let events = events::table.load(db_connection)?;
+				Ok((tabs, events))
+			})
+		},
+	)
+	.await?;
+	let Some((tabs, events)) = db_data else {
+		return Ok(());
 	};
 
 	let events_by_id: HashMap<String, Event> = events
@@ -96,8 +102,94 @@ pub async fn subscribe_to_admin_event_log_tabs(
 		.add_admin_event_log_tabs_subscription(connection_id, conn_update_tx.clone())
 		.await;
 
-	let message =
-		FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminEventLogTabs(tabs)));
+	let version = subscription_manager.admin_event_log_tabs_list_version();
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminEventLogTabs(
+		ListSyncLoad::Snapshot(version, tabs),
+	)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+	Ok(())
+}
+
+pub async fn subscribe_to_admin_event_log_tabs_for_event(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	event_id: &str,
+) -> Result<(), HandleConnectionError> {
+	let subscription_type = SubscriptionType::AdminEventLogTabsForEvent(event_id.to_string());
+	let is_allowed = if user.is_admin {
+		Some(true)
+	} else {
+		load_subscription_data(
+			&db_connection_pool,
+			&conn_update_tx,
+			subscription_type.clone(),
+			"checking event permissions for an event log tabs subscription",
+			|db_connection| user_is_event_supervisor(db_connection, &user.id, event_id),
+		)
+		.await?
+	};
+	match is_allowed {
+		Some(true) => (),
+		Some(false) => {
+			let message =
+				FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NotAllowed);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+			return Ok(());
+		}
+		None => return Ok(()),
+	}
+
+	let db_data: Option<(Option<EventDb>, Vec<EventLogTabDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		subscription_type.clone(),
+		"getting event log tabs for an event log tabs subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let event: Option<EventDb> = events::table.find(event_id).first(db_connection).optional()?;
+				let tabs: Vec<EventLogTabDb> = event_log_tabs::table
+					.filter(event_log_tabs::event.eq(event_id))
+					.load(db_connection)?;
+				Ok((event, tabs))
+			})
+		},
+	)
+	.await?;
+	let Some((event, tabs)) = db_data else {
+		return Ok(());
+	};
+	let Some(event) = event else {
+		let message = FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NoTarget);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	};
+
+	let tabs: Vec<EventLogTab> = tabs
+		.into_iter()
+		.map(|tab| EventLogTab {
+			id: tab.id,
+			name: tab.name,
+			start_time: tab.start_time,
+		})
+		.collect();
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_event_log_tabs_for_event_subscription(event_id, connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminEventLogTabsForEvent(event.into(), tabs),
+	));
 	conn_update_tx
 		.send(ConnectionUpdate::SendData(Box::new(message)))
 		.await?;
@@ -110,17 +202,41 @@ pub async fn handle_admin_event_log_tabs_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_message: AdminEventLogTabsUpdate,
-) {
+) -> Result<(), HandlerError> {
+	let event_id = match &update_message {
+		AdminEventLogTabsUpdate::AddTab(event, _) => event.id.clone(),
+		AdminEventLogTabsUpdate::UpdateTab(tab) | AdminEventLogTabsUpdate::DeleteTab(tab) => {
+			run_db_operation(
+				&db_connection_pool,
+				"finding the event for an event log tab",
+				|db_connection| {
+					event_log_tabs::table
+						.find(&tab.id)
+						.select(event_log_tabs::event)
+						.first(db_connection)
+				},
+			)
+			.await?
+		}
+	};
 	if !user.is_admin {
-		return;
+		let is_supervisor = run_db_operation(
+			&db_connection_pool,
+			"checking event permissions for an event log tab change",
+			|db_connection| user_is_event_supervisor(db_connection, &user.id, &event_id),
+		)
+		.await?;
+		if !is_supervisor {
+			return Err(HandlerError::NotAllowed);
+		}
 	}
 	if !subscription_manager
 		.lock()
 		.await
-		.is_subscribed_to_admin_event_log_tabs(connection_id)
+		.is_subscribed_to_admin_event_log_tabs_for_event(&event_id, connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
 	match update_message {
@@ -132,27 +248,22 @@ pub async fn handle_admin_event_log_tabs_message(
 				name: tab.name.clone(),
 				start_time: tab.start_time,
 			};
-			let db_result: QueryResult<_> = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred adding an event log tab: {}",
-							error
-						);
-						return;
-					}
-				};
+			run_db_operation(&db_connection_pool, "adding an event log tab", |db_connection| {
 				diesel::insert_into(event_log_tabs::table)
-					.values(new_tab)
-					.execute(&mut *db_connection)
-			};
-			if let Err(error) = db_result {
-				tide::log::error!("A database error occurred adding an event log tab: {}", error);
-				return;
-			}
+					.values(&new_tab)
+					.execute(db_connection)
+			})
+			.await?;
 
 			tab.id = tab_id;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::EventLogTab,
+				format!("Added tab \"{}\" to event \"{}\"", tab.name, event.name),
+			)
+			.await;
 			let subscription_manager = subscription_manager.lock().await;
 			let event_message =
 				SubscriptionData::EventUpdate(event.clone(), Box::new(EventSubscriptionData::UpdateTab(tab.clone())));
@@ -162,26 +273,15 @@ pub async fn handle_admin_event_log_tabs_message(
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send event update for new event log tab: {}", error);
 			}
-			let admin_message = SubscriptionData::AdminEventLogTabsUpdate(AdminEventLogTabsData::AddTab(event, tab));
 			let send_result = subscription_manager
-				.broadcast_admin_event_log_tabs_message(admin_message)
+				.broadcast_admin_event_log_tabs_message(&event_id, AdminEventLogTabsData::AddTab(event, tab))
 				.await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send admin update for new event log tab: {}", error);
 			}
 		}
 		AdminEventLogTabsUpdate::UpdateTab(tab) => {
-			let db_result: QueryResult<EventDb> = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred updating an event log tab: {}",
-							error
-						);
-						return;
-					}
-				};
+			let event: EventDb = run_db_operation(&db_connection_pool, "updating an event log tab", |db_connection| {
 				db_connection.transaction(|db_connection| {
 					let db_tab: EventLogTabDb = diesel::update(event_log_tabs::table)
 						.filter(event_log_tabs::id.eq(&tab.id))
@@ -193,17 +293,19 @@ pub async fn handle_admin_event_log_tabs_message(
 					let event: EventDb = events::table.find(&db_tab.event).first(db_connection)?;
 					Ok(event)
 				})
-			};
-			let event: Event = match db_result {
-				Ok(event) => event.into(),
-				Err(error) => {
-					tide::log::error!("A database error occurred updating an event log tab: {}", error);
-					return;
-				}
-			};
+			})
+			.await?;
+			let event: Event = event.into();
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::EventLogTab,
+				format!("Updated tab \"{}\" in event \"{}\"", tab.name, event.name),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
-			let event_id = event.id.clone();
 			let event_message =
 				SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::UpdateTab(tab.clone())));
 			let send_result = subscription_manager
@@ -212,26 +314,15 @@ pub async fn handle_admin_event_log_tabs_message(
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send event update for event log section: {}", error);
 			}
-			let admin_message = SubscriptionData::AdminEventLogTabsUpdate(AdminEventLogTabsData::UpdateTab(tab));
 			let send_result = subscription_manager
-				.broadcast_admin_event_log_tabs_message(admin_message)
+				.broadcast_admin_event_log_tabs_message(&event_id, AdminEventLogTabsData::UpdateTab(tab))
 				.await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send admin update for event log tab: {}", error);
 			}
 		}
 		AdminEventLogTabsUpdate::DeleteTab(tab) => {
-			let db_result: QueryResult<EventDb> = {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!(
-							"A database connection error occurred deleting an event log tab: {}",
-							error
-						);
-						return;
-					}
-				};
+			let event: EventDb = run_db_operation(&db_connection_pool, "deleting an event log tab", |db_connection| {
 				db_connection.transaction(|db_connection| {
 					let db_section: EventLogTabDb = diesel::delete(event_log_tabs::table)
 						.filter(event_log_tabs::id.eq(&tab.id))
@@ -239,17 +330,19 @@ pub async fn handle_admin_event_log_tabs_message(
 					let event: EventDb = events::table.find(&db_section.event).first(db_connection)?;
 					Ok(event)
 				})
-			};
-			let event: Event = match db_result {
-				Ok(event) => event.into(),
-				Err(error) => {
-					tide::log::error!("A database error occurred deleting an event log tab: {}", error);
-					return;
-				}
-			};
+			})
+			.await?;
+			let event: Event = event.into();
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::EventLogTab,
+				format!("Deleted tab \"{}\" from event \"{}\"", tab.name, event.name),
+			)
+			.await;
 
 			let subscription_manager = subscription_manager.lock().await;
-			let event_id = event.id.clone();
 			let event_message =
 				SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::DeleteTab(tab.clone())));
 			let send_result = subscription_manager
@@ -258,13 +351,14 @@ pub async fn handle_admin_event_log_tabs_message(
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send event update for deleting event log tab: {}", error);
 			}
-			let admin_message = SubscriptionData::AdminEventLogTabsUpdate(AdminEventLogTabsData::DeleteTab(tab));
 			let send_result = subscription_manager
-				.broadcast_admin_event_log_tabs_message(admin_message)
+				.broadcast_admin_event_log_tabs_message(&event_id, AdminEventLogTabsData::DeleteTab(tab))
 				.await;
 			if let Err(error) = send_result {
 				tide::log::error!("Failed to send admin update for deleting event log tab: {}", error);
 			}
 		}
 	}
+
+	Ok(())
 }
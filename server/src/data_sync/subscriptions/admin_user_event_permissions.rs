@@ -0,0 +1,214 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use super::user_notifications::send_user_notification;
+use crate::data_sync::user::{load_event_permission, UserDataUpdate};
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, Permission, User, UserEventPermission as UserEventPermissionDb};
+use crate::schema::{events, user_event_permissions, users};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminUserEventPermissionData, AdminUserEventPermissionUpdate, UserEventPermission,
+};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::notifications::UserNotificationDetails;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_user_event_permissions(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminUserEventPermissions,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let permissions: Option<Vec<(UserEventPermissionDb, User, EventDb)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminUserEventPermissions,
+		"loading user event permissions for admin subscription",
+		|db_connection| {
+			user_event_permissions::table
+				.inner_join(users::table)
+				.inner_join(events::table)
+				.load(db_connection)
+		},
+	)
+	.await?;
+	let Some(permissions) = permissions else {
+		return Ok(());
+	};
+	let permissions: Vec<UserEventPermission> = permissions
+		.into_iter()
+		.map(|(permission, user, event)| UserEventPermission {
+			user: user.into(),
+			event: event.into(),
+			level: permission.level.into(),
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_user_event_permissions_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+		InitialSubscriptionLoadData::AdminUserEventPermissions(permissions),
+	));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_admin_user_event_permissions_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: AdminUserEventPermissionUpdate,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_user_event_permissions(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	let (permission_user, event_id) = match &update_message {
+		AdminUserEventPermissionUpdate::SetPermission(permission) => (&permission.user, &permission.event.id),
+		AdminUserEventPermissionUpdate::RemovePermission(permission_user, event) => (permission_user, &event.id),
+	};
+	let (permission_user, event, previous_level, new_level): (
+		PublicUserData,
+		Event,
+		Option<Permission>,
+		Option<Permission>,
+	) = run_db_operation(
+		&db_connection_pool,
+		"updating a user's direct event permission",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let permission_user: User = users::table.find(&permission_user.id).first(db_connection)?;
+				let event: EventDb = events::table.find(event_id).first(db_connection)?;
+				let previous_level = load_event_permission(db_connection, &permission_user.id, &event.id)?;
+
+				match &update_message {
+					AdminUserEventPermissionUpdate::SetPermission(permission) => {
+						let level: Permission = permission.level.into();
+						let db_permission = UserEventPermissionDb {
+							user_id: permission_user.id.clone(),
+							event: event.id.clone(),
+							level,
+						};
+						diesel::insert_into(user_event_permissions::table)
+							.values(&db_permission)
+							.on_conflict((user_event_permissions::user_id, user_event_permissions::event))
+							.do_update()
+							.set(user_event_permissions::level.eq(level))
+							.execute(db_connection)?;
+					}
+					AdminUserEventPermissionUpdate::RemovePermission(_, _) => {
+						diesel::delete(user_event_permissions::table.find((&permission_user.id, &event.id)))
+							.execute(db_connection)?;
+					}
+				}
+
+				let new_level = load_event_permission(db_connection, &permission_user.id, &event.id)?;
+				Ok((permission_user.into(), event.into(), previous_level, new_level))
+			})
+		},
+	)
+	.await?;
+
+	let (description, admin_message) = match update_message {
+		AdminUserEventPermissionUpdate::SetPermission(permission) => {
+			let description = format!(
+				"Granted {:?} permission for event \"{}\" directly to user \"{}\"",
+				permission.level, event.name, permission_user.username
+			);
+			let permission = UserEventPermission {
+				user: permission_user.clone(),
+				event: event.clone(),
+				level: permission.level,
+			};
+			(description, AdminUserEventPermissionData::SetPermission(permission))
+		}
+		AdminUserEventPermissionUpdate::RemovePermission(_, _) => {
+			let description = format!(
+				"Removed the permission for event \"{}\" granted directly to user \"{}\"",
+				event.name, permission_user.username
+			);
+			(
+				description,
+				AdminUserEventPermissionData::RemovePermission(permission_user.clone(), event.clone()),
+			)
+		}
+	};
+	record_admin_action(
+		&db_connection_pool,
+		&subscription_manager,
+		user,
+		AdminActionType::User,
+		description,
+	)
+	.await;
+
+	if previous_level.is_none() && new_level.is_some() {
+		send_user_notification(
+			&db_connection_pool,
+			&subscription_manager,
+			&permission_user.id,
+			&event,
+			UserNotificationDetails::AddedToEvent,
+		)
+		.await;
+	}
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let user_message = UserDataUpdate::EventPermissions(event, new_level);
+	subscription_manager
+		.send_message_to_user(&permission_user.id, user_message)
+		.await;
+
+	let message = SubscriptionData::AdminUserEventPermissionsUpdate(admin_message);
+	let send_result = subscription_manager
+		.broadcast_admin_user_event_permissions_message(message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!(
+			"Failed to broadcast user event permission update to admin subscription: {}",
+			error
+		);
+	}
+
+	Ok(())
+}
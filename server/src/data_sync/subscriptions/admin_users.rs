@@ -4,21 +4,26 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
 use crate::data_sync::connection::ConnectionUpdate;
 use crate::data_sync::UserDataUpdate;
-use crate::data_sync::{HandleConnectionError, SubscriptionManager};
-use crate::models::User;
-use crate::schema::users;
+use crate::data_sync::{run_db_operation, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{User, UserProfileAdminEdit, UsernameChange};
+use crate::schema::{user_profile_admin_edits, username_changes, users};
+use crate::session::{delete_user_sessions, SessionSelection};
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
+use chrono::Utc;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::admin::AdminActionType;
+use stream_log_shared::messages::list_sync::{ListSyncLoad, ListVersion};
 use stream_log_shared::messages::subscriptions::{
-	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+	InitialSubscriptionLoadData, SubscriptionFailureInfo, SubscriptionType,
 };
-use stream_log_shared::messages::user::SelfUserData;
-use stream_log_shared::messages::{DataError, FromServerMessage};
+use stream_log_shared::messages::user::{SelfUserData, UpdateUser};
+use stream_log_shared::messages::FromServerMessage;
 
 pub async fn subscribe_to_admin_users(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
@@ -26,6 +31,7 @@ pub async fn subscribe_to_admin_users(
 	connection_id: &str,
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	known_version: Option<ListVersion>,
 ) -> Result<(), HandleConnectionError> {
 	if !user.is_admin {
 		let message =
@@ -36,27 +42,32 @@ pub async fn subscribe_to_admin_users(
 		return Ok(());
 	}
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(error, &conn_update_tx, SubscriptionType::AdminUsers).await?;
-			return Ok(());
-		}
-	};
-	let all_users: QueryResult<Vec<User>> = users::table.load(&mut *db_connection);
-	let all_users = match all_users {
-		Ok(users) => users,
-		Err(error) => {
-			tide::log::error!("A database error occurred getting the user list: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::AdminUsers,
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
+	if let Some(known_version) = known_version {
+		let subscription_manager = subscription_manager.lock().await;
+		if let Some(changes) = subscription_manager.admin_user_changes_since(&known_version) {
+			subscription_manager
+				.add_admin_user_subscription(connection_id, conn_update_tx.clone())
+				.await;
+			let message = FromServerMessage::InitialSubscriptionLoad(Box::new(
+				InitialSubscriptionLoadData::AdminUsers(ListSyncLoad::Patches(changes)),
+			));
 			conn_update_tx
 				.send(ConnectionUpdate::SendData(Box::new(message)))
 				.await?;
 			return Ok(());
 		}
+	}
+
+	let all_users: Option<Vec<User>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminUsers,
+		"getting the user list",
+		|db_connection| users::table.load(db_connection),
+	)
+	.await?;
+	let Some(all_users) = all_users else {
+		return Ok(());
 	};
 
 	let subscription_manager = subscription_manager.lock().await;
@@ -65,8 +76,10 @@ pub async fn subscribe_to_admin_users(
 		.await;
 
 	let all_user_data: Vec<SelfUserData> = all_users.into_iter().map(|user| user.into()).collect();
-	let message =
-		FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminUsers(all_user_data)));
+	let version = subscription_manager.admin_user_list_version();
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminUsers(
+		ListSyncLoad::Snapshot(version, all_user_data),
+	)));
 	conn_update_tx
 		.send(ConnectionUpdate::SendData(Box::new(message)))
 		.await?;
@@ -80,9 +93,9 @@ pub async fn handle_admin_users_message(
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	modified_user: &SelfUserData,
-) {
+) -> Result<(), HandlerError> {
 	if !user.is_admin {
-		return;
+		return Err(HandlerError::NotAllowed);
 	}
 	if !subscription_manager
 		.lock()
@@ -90,44 +103,249 @@ pub async fn handle_admin_users_message(
 		.is_subscribed_to_admin_users(connection_id)
 		.await
 	{
-		return;
+		return Ok(());
 	}
 
-	let color_red: i32 = modified_user.color.r.into();
-	let color_green: i32 = modified_user.color.g.into();
-	let color_blue: i32 = modified_user.color.b.into();
-	{
-		let mut db_connection = match db_connection_pool.get() {
-			Ok(connection) => connection,
-			Err(error) => {
-				tide::log::error!("A database connection error occurred updating a user: {}", error);
-				return;
-			}
-		};
-		let db_result = diesel::update(users::table)
-			.filter(users::id.eq(&modified_user.id))
-			.set((
-				users::name.eq(&modified_user.username),
-				users::is_admin.eq(modified_user.is_admin),
-				users::color_red.eq(color_red),
-				users::color_green.eq(color_green),
-				users::color_blue.eq(color_blue),
-			))
-			.execute(&mut *db_connection);
-		if let Err(error) = db_result {
-			tide::log::error!("A database error occurred updating a user: {}", error);
-			return;
+	if modified_user.id == user.id && !modified_user.enabled {
+		return Err(HandlerError::InvalidData(String::from(
+			"Administrators can't deactivate their own accounts.",
+		)));
+	}
+
+	// Profile settings (like the user's color) are changed through handle_admin_user_profile_message so that those
+	// changes are recorded.
+	let (previous_user, updated_user, revoked_session_ids): (User, User, Vec<String>) =
+		run_db_operation(&db_connection_pool, "updating a user", |db_connection| {
+			db_connection.transaction(|db_connection| {
+				let previous_user: User = users::table.find(&modified_user.id).first(db_connection)?;
+				let updated_user: User = diesel::update(users::table)
+					.filter(users::id.eq(&modified_user.id))
+					.set((
+						users::name.eq(&modified_user.username),
+						users::is_admin.eq(modified_user.is_admin),
+						users::enabled.eq(modified_user.enabled),
+					))
+					.get_result(db_connection)?;
+
+				if previous_user.name != updated_user.name {
+					let username_change = UsernameChange {
+						id: cuid2::create_id(),
+						user_id: updated_user.id.clone(),
+						changed_by: user.id.clone(),
+						change_time: Utc::now(),
+						old_name: previous_user.name.clone(),
+						new_name: updated_user.name.clone(),
+					};
+					diesel::insert_into(username_changes::table)
+						.values(username_change)
+						.execute(db_connection)?;
+				}
+
+				// Deactivated users are signed out everywhere so that they lose access right away
+				let revoked_session_ids = if previous_user.enabled && !updated_user.enabled {
+					delete_user_sessions(db_connection, &updated_user.openid_user_id, SessionSelection::All)?
+				} else {
+					Vec::new()
+				};
+
+				Ok((previous_user, updated_user, revoked_session_ids))
+			})
+		})
+		.await?;
+
+	let mut changes: Vec<String> = Vec::new();
+	if previous_user.name != updated_user.name {
+		changes.push(format!("renamed from \"{}\"", previous_user.name));
+	}
+	if previous_user.is_admin != updated_user.is_admin {
+		if updated_user.is_admin {
+			changes.push(String::from("made an administrator"));
+		} else {
+			changes.push(String::from("removed as an administrator"));
+		}
+	}
+	if previous_user.enabled != updated_user.enabled {
+		if updated_user.enabled {
+			changes.push(String::from("reactivated"));
+		} else {
+			changes.push(format!("deactivated ({} sessions revoked)", revoked_session_ids.len()));
 		}
 	}
+	if !changes.is_empty() {
+		record_admin_action(
+			&db_connection_pool,
+			&subscription_manager,
+			user,
+			AdminActionType::User,
+			format!("Updated user \"{}\": {}", updated_user.name, changes.join(", ")),
+		)
+		.await;
+	}
+
+	let username_changed = previous_user.name != updated_user.name;
+	let updated_user: SelfUserData = updated_user.into();
 
 	let mut subscription_manager = subscription_manager.lock().await;
-	let admin_message = SubscriptionData::AdminUsersUpdate(modified_user.clone());
-	let send_result = subscription_manager.broadcast_admin_user_message(admin_message).await;
+	if username_changed {
+		subscription_manager
+			.update_public_user(&updated_user.clone().into())
+			.await;
+	}
+	if !revoked_session_ids.is_empty() {
+		subscription_manager
+			.close_session_connections(&revoked_session_ids)
+			.await;
+	}
+	let send_result = subscription_manager
+		.broadcast_admin_user_message(updated_user.clone())
+		.await;
 	if let Err(error) = send_result {
 		tide::log::error!("Failed to send admin message for user update: {}", error);
 	}
-	let user_message = UserDataUpdate::User(modified_user.clone());
+	let user_message = UserDataUpdate::User(updated_user);
 	subscription_manager
 		.send_message_to_user(&modified_user.id, user_message)
 		.await;
+
+	Ok(())
+}
+
+pub async fn handle_admin_user_profile_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	modified_user: &SelfUserData,
+	update_data: UpdateUser,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_users(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	let color_red: i32 = update_data.color.r.into();
+	let color_green: i32 = update_data.color.g.into();
+	let color_blue: i32 = update_data.color.b.into();
+	let updated_user: User = run_db_operation(&db_connection_pool, "updating a user profile", |db_connection| {
+		db_connection.transaction(|db_connection| {
+			let previous_user: User = users::table.find(&modified_user.id).first(db_connection)?;
+			let updated_user: User = diesel::update(users::table)
+				.filter(users::id.eq(&modified_user.id))
+				.set((
+					users::color_red.eq(color_red),
+					users::color_green.eq(color_green),
+					users::color_blue.eq(color_blue),
+					users::use_spell_check.eq(update_data.use_spell_check),
+				))
+				.get_result(db_connection)?;
+
+			let audit_record = UserProfileAdminEdit {
+				id: cuid2::create_id(),
+				edited_user: updated_user.id.clone(),
+				edit_user: user.id.clone(),
+				edit_time: Utc::now(),
+				old_color_red: previous_user.color_red,
+				old_color_green: previous_user.color_green,
+				old_color_blue: previous_user.color_blue,
+				old_use_spell_check: previous_user.use_spell_check,
+				new_color_red: updated_user.color_red,
+				new_color_green: updated_user.color_green,
+				new_color_blue: updated_user.color_blue,
+				new_use_spell_check: updated_user.use_spell_check,
+			};
+			diesel::insert_into(user_profile_admin_edits::table)
+				.values(audit_record)
+				.execute(db_connection)?;
+
+			Ok(updated_user)
+		})
+	})
+	.await?;
+	tide::log::info!(
+		"Administrator {} ({}) updated the profile of user {} ({})",
+		user.username,
+		user.id,
+		updated_user.name,
+		updated_user.id
+	);
+	record_admin_action(
+		&db_connection_pool,
+		&subscription_manager,
+		user,
+		AdminActionType::User,
+		format!("Updated the profile of user \"{}\"", updated_user.name),
+	)
+	.await;
+
+	let updated_user: SelfUserData = updated_user.into();
+	let mut subscription_manager = subscription_manager.lock().await;
+	let send_result = subscription_manager
+		.broadcast_admin_user_message(updated_user.clone())
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send admin message for user profile update: {}", error);
+	}
+	let user_id = updated_user.id.clone();
+	let user_message = UserDataUpdate::User(updated_user);
+	subscription_manager.send_message_to_user(&user_id, user_message).await;
+
+	Ok(())
+}
+
+/// Signs a user out of all of their sessions on an administrator's request
+pub async fn handle_admin_user_sessions_revoke_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	modified_user: &SelfUserData,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_users(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	let revoked_session_ids: Vec<String> =
+		run_db_operation(&db_connection_pool, "revoking a user's sessions", |db_connection| {
+			let openid_user_id: String = users::table
+				.find(&modified_user.id)
+				.select(users::openid_user_id)
+				.first(db_connection)?;
+			delete_user_sessions(db_connection, &openid_user_id, SessionSelection::All)
+		})
+		.await?;
+	record_admin_action(
+		&db_connection_pool,
+		&subscription_manager,
+		user,
+		AdminActionType::User,
+		format!(
+			"Signed user \"{}\" out of all sessions ({} revoked)",
+			modified_user.username,
+			revoked_session_ids.len()
+		),
+	)
+	.await;
+
+	subscription_manager
+		.lock()
+		.await
+		.close_session_connections(&revoked_session_ids)
+		.await;
+
+	Ok(())
 }
@@ -0,0 +1,204 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::admin_audit_log::record_admin_action;
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, EventWebhook as EventWebhookDb};
+use crate::schema::{event_webhooks, events};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use stream_log_shared::messages::admin::{
+	AdminActionType, AdminEventWebhookData, AdminEventWebhookUpdate, EventWebhook,
+};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn subscribe_to_admin_event_webhooks(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	if !user.is_admin {
+		let message = FromServerMessage::SubscriptionFailure(
+			SubscriptionType::AdminEventWebhooks,
+			SubscriptionFailureInfo::NotAllowed,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+		return Ok(());
+	}
+
+	let query_result: Option<(Vec<EventDb>, Vec<EventWebhookDb>)> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::AdminEventWebhooks,
+		"retrieving webhooks for admin subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let webhooks: Vec<EventWebhookDb> = event_webhooks::table.load(db_connection)?;
+				let events: Vec<EventDb> = events::table.load(db_connection)?;
+				Ok((events, webhooks))
+			})
+		},
+	)
+	.await?;
+	let Some((events, webhooks)) = query_result else {
+		return Ok(());
+	};
+
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
+		.collect();
+
+	let webhooks: Vec<EventWebhook> = webhooks
+		.into_iter()
+		.map(|webhook| EventWebhook {
+			id: webhook.id,
+			event: events.get(&webhook.event).unwrap().clone(),
+			url: webhook.url,
+		})
+		.collect();
+
+	let subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.add_admin_event_webhooks_subscription(connection_id, conn_update_tx.clone())
+		.await;
+
+	let message =
+		FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::AdminEventWebhooks(webhooks)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_admin_event_webhooks_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: AdminEventWebhookUpdate,
+) -> Result<(), HandlerError> {
+	if !user.is_admin {
+		return Err(HandlerError::NotAllowed);
+	}
+
+	if !subscription_manager
+		.lock()
+		.await
+		.is_subscribed_to_admin_event_webhooks(connection_id)
+		.await
+	{
+		return Ok(());
+	}
+
+	match update_message {
+		AdminEventWebhookUpdate::UpdateWebhook(webhook) => {
+			let url = webhook.url.trim();
+			if !url.starts_with("http://") && !url.starts_with("https://") {
+				return Err(HandlerError::InvalidData(String::from(
+					"Webhook URLs must start with http:// or https://",
+				)));
+			}
+
+			let is_new_webhook = webhook.id.is_empty();
+			let new_webhook_id = cuid2::create_id();
+			let (webhook, event): (EventWebhookDb, EventDb) =
+				run_db_operation(&db_connection_pool, "updating a webhook", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let webhook: EventWebhookDb = if is_new_webhook {
+							let new_webhook = EventWebhookDb {
+								id: new_webhook_id.clone(),
+								event: webhook.event.id.clone(),
+								url: url.to_string(),
+							};
+							diesel::insert_into(event_webhooks::table)
+								.values(new_webhook)
+								.get_result(db_connection)?
+						} else {
+							diesel::update(event_webhooks::table)
+								.filter(event_webhooks::id.eq(&webhook.id))
+								.set(event_webhooks::url.eq(url))
+								.get_result(db_connection)?
+						};
+						let event: EventDb = events::table.find(&webhook.event).first(db_connection)?;
+						Ok((webhook, event))
+					})
+				})
+				.await?;
+			let webhook = EventWebhook {
+				id: webhook.id,
+				event: event.into(),
+				url: webhook.url,
+			};
+			// Webhook URLs often contain secrets, so they're left out of the audit log.
+			let description = if is_new_webhook {
+				format!("Added a webhook to event \"{}\"", webhook.event.name)
+			} else {
+				format!("Updated a webhook for event \"{}\"", webhook.event.name)
+			};
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Webhook,
+				description,
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			let admin_message =
+				SubscriptionData::AdminEventWebhooksUpdate(AdminEventWebhookData::UpdateWebhook(webhook));
+			let send_result = subscription_manager
+				.broadcast_admin_event_webhooks_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast admin update for webhook: {}", error);
+			}
+		}
+		AdminEventWebhookUpdate::DeleteWebhook(webhook) => {
+			run_db_operation(&db_connection_pool, "deleting a webhook", |db_connection| {
+				diesel::delete(event_webhooks::table)
+					.filter(event_webhooks::id.eq(&webhook.id))
+					.execute(db_connection)
+			})
+			.await?;
+			record_admin_action(
+				&db_connection_pool,
+				&subscription_manager,
+				user,
+				AdminActionType::Webhook,
+				format!("Deleted a webhook from event \"{}\"", webhook.event.name),
+			)
+			.await;
+
+			let subscription_manager = subscription_manager.lock().await;
+			let admin_message =
+				SubscriptionData::AdminEventWebhooksUpdate(AdminEventWebhookData::DeleteWebhook(webhook));
+			let send_result = subscription_manager
+				.broadcast_admin_event_webhooks_message(admin_message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast admin update for webhook deletion: {}", error);
+			}
+		}
+	}
+
+	Ok(())
+}
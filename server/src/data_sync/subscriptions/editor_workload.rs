@@ -0,0 +1,209 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::{load_subscription_data, user_has_event_access};
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, User, VideoEditState, VideoProcessingState};
+use crate::schema::{event_log, events, users};
+use async_std::channel::{Receiver, Sender};
+use async_std::sync::{Arc, Mutex};
+use async_std::task;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use stream_log_shared::messages::editor_workload::EditorWorkload;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// How long to wait after an entry change before recalculating workloads. Changes often come in bursts, so other
+/// changes made in this time are handled by the same recalculation.
+const WORKLOAD_UPDATE_DELAY: Duration = Duration::from_secs(2);
+
+pub async fn subscribe_to_editor_workload(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	event_id: &str,
+) -> Result<(), HandleConnectionError> {
+	let subscription_type = SubscriptionType::EditorWorkload(event_id.to_string());
+	let is_allowed = if user.is_admin {
+		Some(true)
+	} else {
+		load_subscription_data(
+			&db_connection_pool,
+			&conn_update_tx,
+			subscription_type.clone(),
+			"checking event permissions for an editor workload subscription",
+			|db_connection| user_has_event_access(db_connection, &user.id, event_id),
+		)
+		.await?
+	};
+	match is_allowed {
+		Some(true) => (),
+		Some(false) => {
+			let message =
+				FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NotAllowed);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+			return Ok(());
+		}
+		None => return Ok(()),
+	}
+
+	let db_data: Option<Option<(EventDb, Vec<EditorWorkload>)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		subscription_type.clone(),
+		"getting editor workloads for an editor workload subscription",
+		|db_connection| {
+			db_connection.transaction(|db_connection| {
+				let event: Option<EventDb> = events::table.find(event_id).first(db_connection).optional()?;
+				match event {
+					Some(event) => {
+						let workloads = load_editor_workloads(db_connection, event_id)?;
+						Ok(Some((event, workloads)))
+					}
+					None => Ok(None),
+				}
+			})
+		},
+	)
+	.await?;
+	let (event, workloads) = match db_data {
+		Some(Some(data)) => data,
+		Some(None) => {
+			let message = FromServerMessage::SubscriptionFailure(subscription_type, SubscriptionFailureInfo::NoTarget);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+			return Ok(());
+		}
+		None => return Ok(()),
+	};
+
+	subscription_manager
+		.lock()
+		.await
+		.add_editor_workload_subscription(event_id, connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::EditorWorkload(
+		event.into(),
+		workloads,
+	)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+/// Counts the entries assigned to each editor of an event. Deleted entries aren't counted. Workloads are sorted by
+/// editor username.
+fn load_editor_workloads(db_connection: &mut PgConnection, event_id: &str) -> QueryResult<Vec<EditorWorkload>> {
+	let assigned_entries: Vec<(Option<String>, VideoEditState, VideoProcessingState)> = event_log::table
+		.filter(
+			event_log::event
+				.eq(event_id)
+				.and(event_log::deleted_by.is_null())
+				.and(event_log::editor.is_not_null()),
+		)
+		.select((
+			event_log::editor,
+			event_log::video_edit_state,
+			event_log::video_processing_state,
+		))
+		.load(db_connection)?;
+
+	let mut workloads_by_editor: HashMap<String, EditorWorkload> = HashMap::new();
+	let editor_ids: HashSet<String> = assigned_entries
+		.iter()
+		.filter_map(|(editor, _, _)| editor.clone())
+		.collect();
+	let editors: Vec<User> = users::table.filter(users::id.eq_any(&editor_ids)).load(db_connection)?;
+	for editor in editors {
+		let editor_id = editor.id.clone();
+		let workload = EditorWorkload {
+			editor: editor.into(),
+			total_entries: 0,
+			edit_state_counts: HashMap::new(),
+			processing_state_counts: HashMap::new(),
+		};
+		workloads_by_editor.insert(editor_id, workload);
+	}
+
+	for (editor_id, edit_state, processing_state) in assigned_entries {
+		let Some(workload) = editor_id.and_then(|id| workloads_by_editor.get_mut(&id)) else {
+			continue;
+		};
+		workload.total_entries += 1;
+		*workload.edit_state_counts.entry(edit_state.into()).or_default() += 1;
+		*workload
+			.processing_state_counts
+			.entry(processing_state.into())
+			.or_default() += 1;
+	}
+
+	let mut workloads: Vec<EditorWorkload> = workloads_by_editor.into_values().collect();
+	workloads.sort_by_key(|workload| workload.editor.username.to_lowercase());
+	Ok(workloads)
+}
+
+/// Recalculates editor workloads for events whose entries changed and sends them to the event's workload subscribers.
+/// Receives the IDs of events with changed entries.
+pub async fn run_editor_workload_updater(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	event_id_rx: Receiver<String>,
+) {
+	while let Ok(event_id) = event_id_rx.recv().await {
+		task::sleep(WORKLOAD_UPDATE_DELAY).await;
+
+		let mut event_ids: HashSet<String> = HashSet::new();
+		event_ids.insert(event_id);
+		while let Ok(event_id) = event_id_rx.try_recv() {
+			event_ids.insert(event_id);
+		}
+
+		for event_id in event_ids {
+			let db_data: Result<(EventDb, Vec<EditorWorkload>), HandlerError> =
+				run_db_operation(&db_connection_pool, "updating editor workloads", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let event: EventDb = events::table.find(&event_id).first(db_connection)?;
+						let workloads = load_editor_workloads(db_connection, &event_id)?;
+						Ok((event, workloads))
+					})
+				})
+				.await;
+			let (event, workloads) = match db_data {
+				Ok(data) => data,
+				Err(error) => {
+					tide::log::error!("Failed to update editor workloads for event {}: {}", event_id, error);
+					continue;
+				}
+			};
+
+			let event: Event = event.into();
+			let message = SubscriptionData::EditorWorkloadUpdate(event, workloads);
+			let send_result = subscription_manager
+				.lock()
+				.await
+				.broadcast_editor_workload_message(&event_id, message)
+				.await;
+			if let Err(error) = send_result {
+				tide::log::error!("Failed to broadcast editor workload update: {}", error);
+			}
+		}
+	}
+}
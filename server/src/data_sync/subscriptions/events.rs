@@ -4,41 +4,71 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::send_lost_db_connection_subscription_response;
+use super::admin_rejected_updates::record_rejected_event_update;
+use super::user_notifications::send_user_notification;
 use crate::data_sync::connection::ConnectionUpdate;
+use crate::data_sync::custom_fields::{
+	check_custom_field_values, custom_field_values_by_entry, entry_custom_field_values, event_custom_fields,
+	set_entry_custom_field_values,
+};
+use crate::data_sync::entry_history::revision_entry;
+use crate::data_sync::feedback_rate_limiter::FeedbackRateLimiter;
 use crate::data_sync::new_event_entries::{NewEventEntries, NEW_ENTRY_COUNT};
-use crate::data_sync::{HandleConnectionError, SubscriptionManager};
+use crate::data_sync::tab_entries::top_level_start_times;
+use crate::data_sync::user::load_event_permission;
+use crate::data_sync::{run_db_operation, HandleConnectionError, HandlerError, SubscriptionManager};
 use crate::models::{
-	AvailableEntryType, EditSource, EntryType as EntryTypeDb, Event as EventDb, EventLogEntry as EventLogEntryDb,
-	EventLogEntryChanges, EventLogHistoryEntry, EventLogHistoryTag, EventLogTab as EventLogTabDb, EventLogTag,
-	InfoPage as InfoPageDb, Permission, PermissionEvent, Tag as TagDb, User, VideoProcessingState,
+	AvailableEntryType, EditSource, EntryTemplate as EntryTemplateDb, EntryTemplateTag, EntryType as EntryTypeDb,
+	Event as EventDb, EventFeedback as EventFeedbackDb, EventLogEntry as EventLogEntryDb, EventLogEntryChanges,
+	EventLogHistoryEntry, EventLogHistoryTag, EventLogTab as EventLogTabDb, EventLogTag, InfoPage as InfoPageDb,
+	PendingTagAction as PendingTagActionDb, Permission, RecurringEntry as RecurringEntryDb, Tag as TagDb, User,
+	VideoEditState, VideoEditStateTagRule as VideoEditStateTagRuleDb, VideoProcessingState,
 };
+use crate::plugins::PluginRegistry;
+use crate::recurring_entries::{next_occurrence, truncate_to_minute};
 use crate::schema::{
-	available_entry_types_for_event, entry_types, event_editors, event_log, event_log_history, event_log_history_tags,
-	event_log_tabs, event_log_tags, events, info_pages, permission_events, tags, user_permissions, users,
+	available_entry_types_for_event, entry_template_tags, entry_templates, entry_types, event_editors, event_feedback,
+	event_log, event_log_history, event_log_history_tags, event_log_tabs, event_log_tags, events, info_pages,
+	pending_tag_actions, recurring_entries, tags, users, video_edit_state_tag_rules,
 };
 use async_std::channel::Sender;
 use async_std::sync::{Arc, Mutex};
 use chrono::prelude::*;
+use chrono::TimeDelta;
+use diesel::dsl::max;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::{HashMap, HashSet};
+use stream_log_shared::messages::broadcasts::{EventBroadcast, MAX_BROADCAST_LENGTH};
+use stream_log_shared::messages::custom_fields::CustomField;
+use stream_log_shared::messages::entry_templates::EntryTemplate;
 use stream_log_shared::messages::entry_types::EntryType;
 use stream_log_shared::messages::event_log::{EndTimeData, EventLogEntry, EventLogTab};
 use stream_log_shared::messages::event_subscription::{
-	EventSubscriptionData, EventSubscriptionUpdate, ModifiedEventLogEntryParts, NewTypingData, TypingData,
+	conflicting_entry_parts, ChildEntryHandling, EntryUpdateConflict, EventSubscriptionData, EventSubscriptionUpdate,
+	ModifiedEventLogEntryParts, NewTypingData, TypingData,
 };
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::feedback::{EventFeedback, MAX_FEEDBACK_LENGTH};
 use stream_log_shared::messages::info_pages::InfoPage;
+use stream_log_shared::messages::notifications::UserNotificationDetails;
 use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::recurring_entries::RecurringEntry;
 use stream_log_shared::messages::subscriptions::{
 	InitialEventSubscriptionLoadData, InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo,
 	SubscriptionType,
 };
-use stream_log_shared::messages::tags::{Tag, TagPlaylist};
+use stream_log_shared::messages::tags::{PendingTagAction, Tag, TagPlaylist, VideoEditStateTagRule};
+use stream_log_shared::messages::time_display::TimeDisplay;
 use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
 use stream_log_shared::messages::{DataError, FromServerMessage};
 
+/// The maximum number of log entries sent to the client in a single message when subscribing to an event
+const INITIAL_LOAD_ENTRY_CHUNK_SIZE: usize = 2000;
+/// The number of log entries to send when subscribing to an event before leaving the entries of earlier tabs to be
+/// requested later. Entries are sent a whole tab at a time, starting from the most recent tab, so this can be exceeded.
+const INITIAL_LOAD_TAB_ENTRY_COUNT: usize = 2000;
+
 pub struct SubscribeToEventArgs<'a> {
 	pub db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	pub conn_update_tx: Sender<ConnectionUpdate>,
@@ -48,6 +78,8 @@ pub struct SubscribeToEventArgs<'a> {
 	pub new_entries: Arc<Mutex<NewEventEntries>>,
 	pub event_id: &'a str,
 	pub event_permission_cache: &'a mut HashMap<Event, Option<Permission>>,
+	/// Whether the connection is impersonating the user, in which case the user isn't shown as viewing the event
+	pub impersonating: bool,
 }
 
 pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), HandleConnectionError> {
@@ -60,24 +92,17 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		new_entries,
 		event_id,
 		event_permission_cache,
+		impersonating,
 	} = args;
 
-	let mut db_connection = match db_connection_pool.get() {
-		Ok(connection) => connection,
-		Err(error) => {
-			send_lost_db_connection_subscription_response(
-				error,
-				&conn_update_tx,
-				SubscriptionType::EventLogData(event_id.to_string()),
-			)
-			.await?;
-			return Ok(());
-		}
-	};
-	let mut event: Vec<EventDb> = match events::table.filter(events::id.eq(event_id)).load(&mut *db_connection) {
+	let mut event: Vec<EventDb> = match run_db_operation(&db_connection_pool, "loading event", |db_connection| {
+		events::table.filter(events::id.eq(event_id)).load(db_connection)
+	})
+	.await
+	{
 		Ok(ev) => ev,
 		Err(error) => {
-			tide::log::error!("Database error loading event: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -103,53 +128,28 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		}
 	};
 
-	let event_permissions: Vec<PermissionEvent> = match permission_events::table
-		.filter(
-			permission_events::event.eq(event_id).and(
-				user_permissions::table
-					.filter(
-						user_permissions::permission_group
-							.eq(permission_events::permission_group)
-							.and(user_permissions::user_id.eq(&user.id)),
-					)
-					.count()
-					.single_value()
-					.gt(0),
-			),
-		)
-		.load(&mut *db_connection)
-	{
-		Ok(data) => data,
-		Err(error) => {
-			tide::log::error!("Database error retrieving event permissions: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::EventLogData(event_id.to_string()),
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			return Ok(());
-		}
-	};
-
-	let mut highest_permission_level: Option<Permission> = None;
-	for permission in event_permissions.iter() {
-		match (permission.level, highest_permission_level) {
-			(Permission::Supervisor, _) => {
-				highest_permission_level = Some(Permission::Supervisor);
-				break;
+	let highest_permission_level: Option<Permission> =
+		match run_db_operation(&db_connection_pool, "retrieving event permissions", |db_connection| {
+			load_event_permission(db_connection, &user.id, event_id)
+		})
+		.await
+		{
+			Ok(data) => data,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				return Ok(());
 			}
-			(Permission::Edit, Some(Permission::Supervisor)) => (),
-			(Permission::Edit, _) => highest_permission_level = Some(Permission::Edit),
-			(Permission::View, Some(Permission::Supervisor)) => (),
-			(Permission::View, Some(Permission::Edit)) => (),
-			(Permission::View, _) => highest_permission_level = Some(Permission::View),
-		}
-	}
+		};
 
 	let event_data: Event = event.clone().into();
-	event_permission_cache.insert(event_data, highest_permission_level);
+	event_permission_cache.insert(event_data.clone(), highest_permission_level);
 
 	let permission_level = match highest_permission_level {
 		Some(level) => level,
@@ -167,28 +167,37 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 
 	{
 		let mut subscriptions = subscription_manager.lock().await;
+		let user_data: PublicUserData = user.clone().into();
+		let viewer = if impersonating { None } else { Some(&user_data) };
 		subscriptions
-			.subscribe_to_event(event_id, connection_id, conn_update_tx.clone())
+			.subscribe_to_event(&event_data, viewer, connection_id, conn_update_tx.clone())
 			.await;
 	}
 
-	let entry_types: Vec<EntryTypeDb> = match entry_types::table
-		.filter(
-			available_entry_types_for_event::table
+	let entry_types: Vec<EntryTypeDb> = match run_db_operation(
+		&db_connection_pool,
+		"getting event types for an event",
+		|db_connection| {
+			entry_types::table
 				.filter(
-					available_entry_types_for_event::event_id
-						.eq(event_id)
-						.and(available_entry_types_for_event::entry_type.eq(entry_types::id)),
+					available_entry_types_for_event::table
+						.filter(
+							available_entry_types_for_event::event_id
+								.eq(event_id)
+								.and(available_entry_types_for_event::entry_type.eq(entry_types::id)),
+						)
+						.count()
+						.single_value()
+						.gt(0),
 				)
-				.count()
-				.single_value()
-				.gt(0),
-		)
-		.load(&mut *db_connection)
+				.load(db_connection)
+		},
+	)
+	.await
 	{
 		Ok(types) => types,
 		Err(error) => {
-			tide::log::error!("Database error getting event types for an event: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -205,13 +214,16 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		}
 	};
 
-	let tags: Vec<TagDb> = match tags::table
-		.filter(tags::deleted.eq(false).and(tags::for_event.eq(&event.id)))
-		.load(&mut *db_connection)
+	let tags: Vec<TagDb> = match run_db_operation(&db_connection_pool, "getting tags for an event", |db_connection| {
+		tags::table
+			.filter(tags::deleted.eq(false).and(tags::for_event.eq(&event.id)))
+			.load(db_connection)
+	})
+	.await
 	{
 		Ok(tags) => tags,
 		Err(error) => {
-			tide::log::error!("Database error getting tags for an event: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -228,67 +240,187 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		}
 	};
 
-	let log_tabs: Vec<EventLogTabDb> = match event_log_tabs::table
-		.filter(event_log_tabs::event.eq(event_id))
-		.order(event_log_tabs::start_time.asc())
-		.load(&mut *db_connection)
-	{
-		Ok(sections) => sections,
-		Err(error) => {
-			tide::log::error!("Database error getting event log sections: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::EventLogData(event_id.to_string()),
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			subscription_manager
-				.lock()
-				.await
-				.unsubscribe_from_event(event_id, connection_id)
-				.await?;
-			return Ok(());
-		}
-	};
+	let log_tabs: Vec<EventLogTabDb> =
+		match run_db_operation(&db_connection_pool, "getting event log sections", |db_connection| {
+			event_log_tabs::table
+				.filter(event_log_tabs::event.eq(event_id))
+				.order(event_log_tabs::start_time.asc())
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(sections) => sections,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
 
-	let log_entries: Vec<EventLogEntryDb> = match event_log::table
-		.filter(event_log::event.eq(event_id).and(event_log::deleted_by.is_null()))
-		.order((
-			event_log::start_time.asc(),
-			event_log::manual_sort_key.asc().nulls_last(),
-			event_log::created_at.asc(),
-		))
-		.load(&mut *db_connection)
-	{
-		Ok(entries) => entries,
-		Err(error) => {
-			tide::log::error!("Database error getting event log entries: {}", error);
-			let message = FromServerMessage::SubscriptionFailure(
-				SubscriptionType::EventLogData(event_id.to_string()),
-				SubscriptionFailureInfo::Error(DataError::DatabaseError),
-			);
-			conn_update_tx
-				.send(ConnectionUpdate::SendData(Box::new(message)))
-				.await?;
-			subscription_manager
-				.lock()
-				.await
-				.unsubscribe_from_event(event_id, connection_id)
-				.await?;
-			return Ok(());
+	let entry_templates: Vec<EntryTemplateDb> =
+		match run_db_operation(&db_connection_pool, "getting entry templates", |db_connection| {
+			entry_templates::table
+				.filter(entry_templates::event.eq(event_id))
+				.order(entry_templates::name.asc())
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(templates) => templates,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let entry_template_tags: Vec<EntryTemplateTag> =
+		match run_db_operation(&db_connection_pool, "getting entry template tags", |db_connection| {
+			entry_template_tags::table
+				.inner_join(entry_templates::table)
+				.filter(entry_templates::event.eq(event_id))
+				.select(entry_template_tags::all_columns)
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(template_tags) => template_tags,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let recurring_entries: Vec<RecurringEntryDb> =
+		match run_db_operation(&db_connection_pool, "getting recurring entries", |db_connection| {
+			recurring_entries::table
+				.filter(recurring_entries::event.eq(event_id))
+				.order(recurring_entries::first_time.asc())
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(recurring_entries) => recurring_entries,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let mut log_entries: Vec<EventLogEntryDb> =
+		match run_db_operation(&db_connection_pool, "getting event log entries", |db_connection| {
+			event_log::table
+				.filter(event_log::event.eq(event_id).and(event_log::deleted_by.is_null()))
+				.order((
+					event_log::start_time.asc(),
+					event_log::manual_sort_key.asc().nulls_last(),
+					event_log::created_at.asc(),
+				))
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(entries) => entries,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	// Loading and rendering every entry of a long event takes a while, so for events with many entries, we only send the
+	// most recent tabs and let the client request the rest.
+	let mut unloaded_tab_ids: Vec<String> = Vec::new();
+	if log_entries.len() > INITIAL_LOAD_TAB_ENTRY_COUNT {
+		let tab_start_times = top_level_start_times(&log_entries);
+		let loaded_tab_index = log_tabs.iter().rposition(|tab| {
+			let tab_entry_count = tab_start_times
+				.values()
+				.filter(|start_time| **start_time >= tab.start_time)
+				.count();
+			tab_entry_count >= INITIAL_LOAD_TAB_ENTRY_COUNT
+		});
+		if let Some(loaded_tab_index) = loaded_tab_index {
+			let loaded_from = log_tabs[loaded_tab_index].start_time;
+			log_entries.retain(|entry| tab_start_times[&entry.id] >= loaded_from);
+			unloaded_tab_ids.push(String::new());
+			unloaded_tab_ids.extend(log_tabs[..loaded_tab_index].iter().map(|tab| tab.id.clone()));
 		}
-	};
+	}
 
 	let log_entry_ids: Vec<String> = log_entries.iter().map(|entry| entry.id.clone()).collect();
 
-	let log_entry_tags: Vec<EventLogTag> = match event_log_tags::table
-		.filter(event_log_tags::log_entry.eq_any(&log_entry_ids))
-		.load(&mut *db_connection)
+	let log_entry_tags: Vec<EventLogTag> = match run_db_operation(
+		&db_connection_pool,
+		"retrieving tags for event log entries",
+		|db_connection| {
+			event_log_tags::table
+				.filter(event_log_tags::log_entry.eq_any(&log_entry_ids))
+				.load(db_connection)
+		},
+	)
+	.await
 	{
 		Ok(tags) => tags,
 		Err(error) => {
-			tide::log::error!("Database error retrieving tags for event log entries: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -329,12 +461,48 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 					name: tag.tag.clone(),
 					description: tag.description.clone(),
 					playlist,
+					valid_from: tag.valid_from,
+					valid_to: tag.valid_to,
 				}
 			}
 			None => {
+				// The tag was deleted or belongs to another event; the integrity check can clean these up
+				tide::log::warn!(
+					"Tag {} on log entry {} isn't an available tag for event {}; leaving it out",
+					log_entry_tag.tag,
+					log_entry_tag.log_entry,
+					event_id
+				);
+				continue;
+			}
+		};
+		tags_by_log_entry
+			.entry(log_entry_tag.log_entry.clone())
+			.or_default()
+			.push(tag);
+	}
+
+	let mut editor_user_ids: HashSet<String> = HashSet::new();
+	for log_entry in log_entries.iter() {
+		if let Some(user_id) = log_entry.editor.as_ref() {
+			editor_user_ids.insert(user_id.clone());
+		}
+	}
+	let event_editor_user_ids: Vec<String> =
+		match run_db_operation(&db_connection_pool, "retrieving editors for event", |db_connection| {
+			event_editors::table
+				.filter(event_editors::event.eq(event_id))
+				.select(event_editors::editor)
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(editors) => editors,
+			Err(error) => {
+				tide::log::error!("{}", error);
 				let message = FromServerMessage::SubscriptionFailure(
 					SubscriptionType::EventLogData(event_id.to_string()),
-					SubscriptionFailureInfo::Error(DataError::ServerError),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
 				);
 				conn_update_tx
 					.send(ConnectionUpdate::SendData(Box::new(message)))
@@ -347,26 +515,19 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 				return Ok(());
 			}
 		};
-		tags_by_log_entry
-			.entry(log_entry_tag.log_entry.clone())
-			.or_default()
-			.push(tag);
-	}
 
-	let mut editor_user_ids: Vec<String> = Vec::new();
-	for log_entry in log_entries.iter() {
-		if let Some(user_id) = log_entry.editor.as_ref() {
-			editor_user_ids.push(user_id.clone());
-		}
-	}
-	let editor_user_ids: Vec<String> = match event_editors::table
-		.filter(event_editors::event.eq(event_id))
-		.select(event_editors::editor)
-		.load(&mut *db_connection)
+	// Entries can still have editors who were since removed as editors for the event, so we load both
+	editor_user_ids.extend(event_editor_user_ids.iter().cloned());
+	let editors: Vec<User> = match run_db_operation(&db_connection_pool, "getting editor user data", |db_connection| {
+		users::table
+			.filter(users::id.eq_any(&editor_user_ids))
+			.load(db_connection)
+	})
+	.await
 	{
-		Ok(editors) => editors,
+		Ok(users) => users,
 		Err(error) => {
-			tide::log::error!("Database error retrieving editors for event: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -383,13 +544,32 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		}
 	};
 
-	let editors: Vec<User> = match users::table
-		.filter(users::id.eq_any(&editor_user_ids))
-		.load(&mut *db_connection)
+	let available_editors_list: Vec<PublicUserData> = editors
+		.iter()
+		.filter(|user| event_editor_user_ids.contains(&user.id))
+		.cloned()
+		.map(|user| user.into())
+		.collect();
+	let editors: HashMap<String, User> = editors.into_iter().map(|user| (user.id.clone(), user)).collect();
+
+	let creator_user_ids: HashSet<String> = log_entries
+		.iter()
+		.filter_map(|log_entry| log_entry.created_by.clone())
+		.collect();
+	let creators: Vec<User> = match run_db_operation(
+		&db_connection_pool,
+		"getting entry creator user data",
+		|db_connection| {
+			users::table
+				.filter(users::id.eq_any(&creator_user_ids))
+				.load(db_connection)
+		},
+	)
+	.await
 	{
 		Ok(users) => users,
 		Err(error) => {
-			tide::log::error!("Database error getting editor user data: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -405,17 +585,45 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 			return Ok(());
 		}
 	};
+	let creators: HashMap<String, PublicUserData> = creators
+		.into_iter()
+		.map(|user| (user.id.clone(), user.into()))
+		.collect();
 
-	let available_editors_list: Vec<PublicUserData> = editors.iter().cloned().map(|user| user.into()).collect();
-	let editors: HashMap<String, User> = editors.into_iter().map(|user| (user.id.clone(), user)).collect();
-
-	let info_pages: Vec<InfoPageDb> = match info_pages::table
-		.filter(info_pages::event.eq(&event.id))
-		.load(&mut *db_connection)
+	let custom_fields: Vec<CustomField> =
+		match run_db_operation(&db_connection_pool, "getting event custom fields", |db_connection| {
+			event_custom_fields(db_connection, event_id)
+		})
+		.await
+		{
+			Ok(fields) => fields,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+	let mut custom_field_values = match run_db_operation(
+		&db_connection_pool,
+		"getting entry custom field values",
+		|db_connection| custom_field_values_by_entry(db_connection, &log_entry_ids),
+	)
+	.await
 	{
-		Ok(pages) => pages,
+		Ok(values) => values,
 		Err(error) => {
-			tide::log::error!("Database error getting event info pages: {}", error);
+			tide::log::error!("{}", error);
 			let message = FromServerMessage::SubscriptionFailure(
 				SubscriptionType::EventLogData(event_id.to_string()),
 				SubscriptionFailureInfo::Error(DataError::DatabaseError),
@@ -432,41 +640,211 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		}
 	};
 
-	// Turn all the data we have into client-usable data
-	let event = Event {
-		id: event.id.clone(),
-		name: event.name.clone(),
-		start_time: event.start_time,
-		editor_link_format: event.editor_link_format,
-		first_tab_name: event.first_tab_name,
-	};
-	let permission_level: PermissionLevel = permission_level.into();
-	let entry_types: Vec<EntryType> = entry_types.into_iter().map(|et| et.into()).collect();
-	let tags: Vec<Tag> = tags.into_iter().map(|tag| tag.into()).collect();
-	let info_pages: Vec<InfoPage> = info_pages
-		.into_iter()
-		.map(|page| InfoPage {
-			id: page.id,
-			event: event.clone(),
-			title: page.title,
-			contents: page.contents,
+	let info_pages: Vec<InfoPageDb> =
+		match run_db_operation(&db_connection_pool, "getting event info pages", |db_connection| {
+			info_pages::table
+				.filter(info_pages::event.eq(&event.id))
+				.load(db_connection)
 		})
-		.collect();
-	let event_log_tabs: Vec<EventLogTab> = log_tabs
-		.into_iter()
-		.map(|section| EventLogTab {
-			id: section.id,
-			name: section.name,
-			start_time: section.start_time,
+		.await
+		{
+			Ok(pages) => pages,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let video_edit_state_rules: Vec<VideoEditStateTagRuleDb> =
+		match run_db_operation(&db_connection_pool, "getting video edit state rules", |db_connection| {
+			video_edit_state_tag_rules::table
+				.filter(video_edit_state_tag_rules::event.eq(&event.id))
+				.load(db_connection)
 		})
-		.collect();
-	let mut event_log_entries: Vec<EventLogEntry> = Vec::with_capacity(log_entries.len());
-	for log_entry in log_entries.iter() {
-		let end_time = log_entry.end_time_data();
-		let tags = tags_by_log_entry.remove(&log_entry.id).unwrap_or_default();
-		let editor: Option<PublicUserData> = match &log_entry.editor {
-			Some(editor) => match editors.get(editor) {
-				Some(editor) => Some(editor.clone().into()),
+		.await
+		{
+			Ok(rules) => rules,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let pending_tag_actions: Vec<(PendingTagActionDb, User)> =
+		match run_db_operation(&db_connection_pool, "getting pending tag actions", |db_connection| {
+			pending_tag_actions::table
+				.inner_join(users::table)
+				.filter(pending_tag_actions::event.eq(&event.id))
+				.order(pending_tag_actions::requested_at.asc())
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(actions) => actions,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		};
+
+	let feedback: Vec<EventFeedbackDb> = if permission_level == Permission::Supervisor {
+		match run_db_operation(&db_connection_pool, "getting event feedback", |db_connection| {
+			event_feedback::table
+				.filter(event_feedback::event.eq(&event.id))
+				.order(event_feedback::submitted_at.asc())
+				.load(db_connection)
+		})
+		.await
+		{
+			Ok(feedback) => feedback,
+			Err(error) => {
+				tide::log::error!("{}", error);
+				let message = FromServerMessage::SubscriptionFailure(
+					SubscriptionType::EventLogData(event_id.to_string()),
+					SubscriptionFailureInfo::Error(DataError::DatabaseError),
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
+				subscription_manager
+					.lock()
+					.await
+					.unsubscribe_from_event(event_id, connection_id)
+					.await?;
+				return Ok(());
+			}
+		}
+	} else {
+		Vec::new()
+	};
+
+	// Turn all the data we have into client-usable data
+	let video_edit_state_rules: Vec<VideoEditStateTagRule> = video_edit_state_rules
+		.into_iter()
+		.filter_map(|rule| {
+			tags_by_id.get(&rule.tag).map(|tag| VideoEditStateTagRule {
+				tag: (*tag).clone().into(),
+				video_edit_state: rule.video_edit_state.into(),
+			})
+		})
+		.collect();
+	let pending_tag_actions: Vec<PendingTagAction> = pending_tag_actions
+		.into_iter()
+		.filter_map(|(action, requested_by)| {
+			let tag: Tag = (*tags_by_id.get(&action.tag)?).clone().into();
+			let replacement_tag: Option<Tag> = match action.replacement_tag.as_ref() {
+				Some(replacement_tag_id) => Some((*tags_by_id.get(replacement_tag_id)?).clone().into()),
+				None => None,
+			};
+			Some(PendingTagAction {
+				id: action.id,
+				tag,
+				replacement_tag,
+				requested_by: requested_by.into(),
+				requested_at: action.requested_at,
+			})
+		})
+		.collect();
+	let mut entry_template_tags_by_template: HashMap<String, Vec<Tag>> = HashMap::new();
+	for template_tag in entry_template_tags {
+		if let Some(tag) = tags_by_id.get(&template_tag.tag) {
+			entry_template_tags_by_template
+				.entry(template_tag.template)
+				.or_default()
+				.push((*tag).clone().into());
+		}
+	}
+	let entry_templates: Vec<EntryTemplate> = entry_templates
+		.into_iter()
+		.map(|template| EntryTemplate {
+			tags: entry_template_tags_by_template.remove(&template.id).unwrap_or_default(),
+			id: template.id,
+			name: template.name,
+			entry_type: template.entry_type,
+			description: template.description,
+		})
+		.collect();
+	let recurring_entries: Vec<RecurringEntry> = recurring_entries
+		.into_iter()
+		.map(|recurring_entry| recurring_entry.into())
+		.collect();
+	let event = Event {
+		id: event.id.clone(),
+		name: event.name.clone(),
+		start_time: event.start_time,
+		end_time: event.end_time,
+		editor_link_format: event.editor_link_format,
+		first_tab_name: event.first_tab_name,
+		description_max_length: event.description_max_length,
+		require_tag_deletion_approval: event.require_tag_deletion_approval,
+		archived: event.archived,
+		time_display: TimeDisplay::from_time_zone(event.time_display_zone),
+	};
+	let feedback: Vec<EventFeedback> = feedback.into_iter().map(|feedback| feedback.into()).collect();
+	let permission_level: PermissionLevel = permission_level.into();
+	let entry_types: Vec<EntryType> = entry_types.into_iter().map(|et| et.into()).collect();
+	let tags: Vec<Tag> = tags.into_iter().map(|tag| tag.into()).collect();
+	let info_pages: Vec<InfoPage> = info_pages
+		.into_iter()
+		.map(|page| InfoPage {
+			id: page.id,
+			event: event.clone(),
+			title: page.title,
+			contents: page.contents,
+			minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+		})
+		.filter(|page| page.is_visible_to(permission_level))
+		.collect();
+	let event_log_tabs: Vec<EventLogTab> = log_tabs
+		.into_iter()
+		.map(|section| EventLogTab {
+			id: section.id,
+			name: section.name,
+			start_time: section.start_time,
+		})
+		.collect();
+	let mut event_log_entries: Vec<EventLogEntry> = Vec::with_capacity(log_entries.len());
+	for log_entry in log_entries.into_iter() {
+		let tags = tags_by_log_entry.remove(&log_entry.id).unwrap_or_default();
+		let editor: Option<PublicUserData> = match &log_entry.editor {
+			Some(editor) => match editors.get(editor) {
+				Some(editor) => Some(editor.clone().into()),
 				None => {
 					tide::log::error!(
 						"Editor {} found for log entry {} but not in users table (database constraint violation!)",
@@ -490,28 +868,12 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 			},
 			None => None,
 		};
-		let send_entry = EventLogEntry {
-			id: log_entry.id.clone(),
-			start_time: Some(log_entry.start_time),
-			end_time,
-			entry_type: log_entry.entry_type.clone(),
-			description: log_entry.description.clone(),
-			media_links: log_entry.media_links.iter().filter_map(|link| link.clone()).collect(),
-			submitter_or_winner: log_entry.submitter_or_winner.clone(),
-			tags,
-			notes: log_entry.notes.clone(),
-			editor,
-			video_link: log_entry.video_link.clone(),
-			parent: log_entry.parent.clone(),
-			created_at: log_entry.created_at,
-			manual_sort_key: log_entry.manual_sort_key,
-			video_processing_state: log_entry.video_processing_state.into(),
-			video_errors: log_entry.video_errors.clone(),
-			poster_moment: log_entry.poster_moment,
-			video_edit_state: log_entry.video_edit_state.into(),
-			missing_giveaway_information: log_entry.missing_giveaway_information,
-		};
-		event_log_entries.push(send_entry);
+		let created_by = log_entry
+			.created_by
+			.as_ref()
+			.and_then(|user_id| creators.get(user_id).cloned());
+		let field_values = custom_field_values.remove(&log_entry.id).unwrap_or_default();
+		event_log_entries.push(log_entry.into_sync_entry(tags, editor, created_by, field_values));
 	}
 
 	let new_entries = {
@@ -532,16 +894,40 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		new_event_entries_entry.clone()
 	};
 
+	// Sending every entry of a large event in one message makes for a huge message that the client has to receive and
+	// parse all at once, so we send the entries in chunks instead.
+	let pending_entry_count = event_log_entries.len().saturating_sub(INITIAL_LOAD_ENTRY_CHUNK_SIZE);
+	let mut event_log_entries = event_log_entries.into_iter();
+	let initial_entries: Vec<EventLogEntry> = event_log_entries.by_ref().take(INITIAL_LOAD_ENTRY_CHUNK_SIZE).collect();
+
+	let (viewers, entry_locks) = {
+		let subscription_manager = subscription_manager.lock().await;
+		(
+			subscription_manager.event_viewers(event_id),
+			subscription_manager.entry_locks(event_id),
+		)
+	};
+
 	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::Event(Box::new(
 		InitialEventSubscriptionLoadData {
-			event,
+			event: event.clone(),
 			permission: permission_level,
 			entry_types,
 			tags,
+			video_edit_state_rules,
+			pending_tag_actions,
+			feedback,
 			editors: available_editors_list,
+			viewers,
+			entry_locks,
 			info_pages,
 			tabs: event_log_tabs,
-			entries: event_log_entries,
+			entry_templates,
+			recurring_entries,
+			custom_fields,
+			entries: initial_entries,
+			pending_entry_count,
+			unloaded_tab_ids,
 			new_entries,
 		},
 	))));
@@ -549,6 +935,24 @@ pub async fn subscribe_to_event(args: SubscribeToEventArgs<'_>) -> Result<(), Ha
 		.send(ConnectionUpdate::SendData(Box::new(message)))
 		.await?;
 
+	loop {
+		let entries_chunk: Vec<EventLogEntry> =
+			event_log_entries.by_ref().take(INITIAL_LOAD_ENTRY_CHUNK_SIZE).collect();
+		if entries_chunk.is_empty() {
+			break;
+		}
+		let message = FromServerMessage::SubscriptionMessage(
+			Box::new(SubscriptionData::EventUpdate(
+				event.clone(),
+				Box::new(EventSubscriptionData::InitialLogEntryChunk(entries_chunk)),
+			)),
+			None,
+		);
+		conn_update_tx
+			.send(ConnectionUpdate::SendData(Box::new(message)))
+			.await?;
+	}
+
 	Ok(())
 }
 
@@ -556,25 +960,141 @@ pub async fn handle_event_update(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	new_entries: Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: &PluginRegistry,
+	event: &Event,
+	user: &SelfUserData,
+	connection_id: &str,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	event_permission_cache: &HashMap<Event, Option<Permission>>,
+	message: Box<EventSubscriptionUpdate>,
+) -> Result<(), HandlerError> {
+	let (update_kind, entry_id) = describe_event_update(&message);
+	// Rejected feedback isn't recorded, since the record would show who tried to send the feedback
+	let is_feedback = matches!(*message, EventSubscriptionUpdate::SubmitFeedback(_));
+	let result = apply_event_update(
+		db_connection_pool.clone(),
+		Arc::clone(&subscription_manager),
+		new_entries,
+		feedback_rate_limiter,
+		plugins,
+		event,
+		user,
+		connection_id,
+		conn_update_tx,
+		event_permission_cache,
+		message,
+	)
+	.await;
+
+	if let (Err(error), false) = (&result, is_feedback) {
+		if let Some(reason) = error.rejection_reason() {
+			record_rejected_event_update(
+				&db_connection_pool,
+				&subscription_manager,
+				event,
+				user,
+				entry_id,
+				update_kind,
+				reason,
+			)
+			.await;
+		}
+	}
+
+	result
+}
+
+/// Gets a short description of the kind of change an update makes, along with the ID of the log entry it changes, if
+/// any
+fn describe_event_update(message: &EventSubscriptionUpdate) -> (&'static str, Option<String>) {
+	match message {
+		EventSubscriptionUpdate::UpdateLogEntry(entry, _, _) => ("entry update", Some(entry.id.clone())),
+		EventSubscriptionUpdate::DeleteLogEntry(entry, _) => ("entry deletion", Some(entry.id.clone())),
+		EventSubscriptionUpdate::Typing(_) => ("typing", None),
+		EventSubscriptionUpdate::UpdateTag(_) => ("tag update", None),
+		EventSubscriptionUpdate::RemoveTag(_) => ("tag removal", None),
+		EventSubscriptionUpdate::ReplaceTag(_, _) => ("tag replacement", None),
+		EventSubscriptionUpdate::CopyTagsFromEvent(_) => ("tag copy", None),
+		EventSubscriptionUpdate::SetVideoEditStateRule(_, _) => ("video edit state rule change", None),
+		EventSubscriptionUpdate::ShiftEntryTimes(_) => ("entry time shift", None),
+		EventSubscriptionUpdate::ApprovePendingTagAction(_) => ("tag action approval", None),
+		EventSubscriptionUpdate::RejectPendingTagAction(_) => ("tag action rejection", None),
+		EventSubscriptionUpdate::SubmitFeedback(_) => ("feedback", None),
+		EventSubscriptionUpdate::DismissFeedback(_) => ("feedback dismissal", None),
+		EventSubscriptionUpdate::UpdateEntryTemplate(_) => ("entry template update", None),
+		EventSubscriptionUpdate::DeleteEntryTemplate(_) => ("entry template deletion", None),
+		EventSubscriptionUpdate::SendBroadcast(_) => ("broadcast", None),
+		EventSubscriptionUpdate::UpdateRecurringEntry(_) => ("recurring entry update", None),
+		EventSubscriptionUpdate::DeleteRecurringEntry(_) => ("recurring entry deletion", None),
+		EventSubscriptionUpdate::BeginEditingEntry(entry_id) => ("entry lock", Some(entry_id.clone())),
+		EventSubscriptionUpdate::EndEditingEntry(entry_id) => ("entry unlock", Some(entry_id.clone())),
+	}
+}
+
+async fn apply_event_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	new_entries: Arc<Mutex<NewEventEntries>>,
+	feedback_rate_limiter: Arc<Mutex<FeedbackRateLimiter>>,
+	plugins: &PluginRegistry,
 	event: &Event,
 	user: &SelfUserData,
+	connection_id: &str,
+	conn_update_tx: &Sender<ConnectionUpdate>,
 	event_permission_cache: &HashMap<Event, Option<Permission>>,
 	message: Box<EventSubscriptionUpdate>,
-) -> Result<(), HandleConnectionError> {
+) -> Result<(), HandlerError> {
 	let Some(permission_level) = event_permission_cache.get(event) else {
 		// If the user is interacting with the event, they should be subscribed. Subscribing adds the event to the
-		// permission cache, so we can safely abort if they don't have a cached value.
-		return Ok(());
+		// permission cache, so a missing cached value means they're acting on an event they haven't loaded.
+		return Err(HandlerError::NotAllowed);
 	};
 
 	if !permission_level.map(|level| level.can_edit()).unwrap_or_default() {
 		// The user doesn't have access to do this; they should either only view the data we send them or not interact
-		// with it at all. Therefore, we'll ignore their request in this case.
-		return Ok(());
+		// with it at all.
+		return Err(HandlerError::NotAllowed);
+	}
+
+	if event_is_archived(&db_connection_pool, event).await? {
+		return Err(HandlerError::InvalidData(String::from(
+			"The event is archived, so its log can't be changed.",
+		)));
 	}
 
 	let event_subscription_data = match *message {
-		EventSubscriptionUpdate::UpdateLogEntry(log_entry, modified_parts) => {
+		EventSubscriptionUpdate::UpdateLogEntry(mut log_entry, mut modified_parts, base_history_id) => {
+			if modified_parts.contains(&ModifiedEventLogEntryParts::Description) {
+				log_entry.description = log_entry.description.trim().to_string();
+
+				// The event data the client sent may be out of date, so we check against the current limit
+				let event_db: EventDb = run_db_operation(
+					&db_connection_pool,
+					"loading event description limit",
+					|db_connection| events::table.find(&event.id).first(db_connection),
+				)
+				.await?;
+				let current_event: Event = event_db.into();
+				if let Err(error) = current_event.check_description_length(&log_entry.description) {
+					return Err(HandlerError::InvalidData(error));
+				}
+			}
+			if modified_parts.contains(&ModifiedEventLogEntryParts::CustomFields) {
+				log_entry.custom_fields.retain(|_, value| !value.is_empty());
+
+				let custom_fields =
+					run_db_operation(&db_connection_pool, "loading event custom fields", |db_connection| {
+						event_custom_fields(db_connection, &event.id)
+					})
+					.await?;
+				if let Err(error) = check_custom_field_values(&custom_fields, &log_entry.custom_fields) {
+					return Err(HandlerError::InvalidData(error));
+				}
+			}
+
+			let mut update_conflict: Option<EntryUpdateConflict> = None;
+
 			let new_entry_subscription_data = {
 				let mut entry_messages: Vec<EventSubscriptionData> = Vec::new();
 				let mut new_entries = new_entries.lock().await;
@@ -618,6 +1138,9 @@ pub async fn handle_event_update(
 									new_entry.manual_sort_key = log_entry.manual_sort_key
 								}
 								ModifiedEventLogEntryParts::Parent => new_entry.parent = log_entry.parent.clone(),
+								ModifiedEventLogEntryParts::CustomFields => {
+									new_entry.custom_fields = log_entry.custom_fields.clone()
+								}
 							}
 						}
 
@@ -637,7 +1160,7 @@ pub async fn handle_event_update(
 
 							let create_time = Utc::now();
 
-							let db_entry = EventLogEntryDb {
+							let mut db_entry = EventLogEntryDb {
 								id: new_entry.id.clone(),
 								event: event.id.clone(),
 								start_time,
@@ -659,13 +1182,19 @@ pub async fn handle_event_update(
 								video_edit_state: new_entry.video_edit_state.into(),
 								missing_giveaway_information: new_entry.missing_giveaway_information,
 								end_time_incomplete,
+								created_by: Some(user.id.clone()),
+								dead_links: Vec::new(),
+								links_checked_at: None,
+								entry_number: 0,
+								latest_history_id: None,
 							};
 
-							let history_entry = EventLogHistoryEntry::new_from_event_log_entry(
+							let mut history_entry = EventLogHistoryEntry::new_from_event_log_entry(
 								&db_entry,
 								Utc::now(),
 								EditSource::User(user.id.clone()),
 							);
+							db_entry.latest_history_id = Some(history_entry.id.clone());
 
 							let saved_tags: HashMap<String, Tag> =
 								new_entry.tags.iter().map(|tag| (tag.id.clone(), tag.clone())).collect();
@@ -686,103 +1215,131 @@ pub async fn handle_event_update(
 								})
 								.collect();
 
-							let mut db_connection = match db_connection_pool.get() {
-								Ok(connection) => connection,
-								Err(error) => {
-									tide::log::error!("Database connection error adding an event log entry: {}", error);
-									return Ok(());
-								}
-							};
-							let insert_result: QueryResult<(EventLogEntryDb, Vec<TagDb>, Option<User>)> = db_connection
-								.transaction(|db_connection| {
-									if let Some(db_entry_type) = db_entry.entry_type.as_ref() {
-										let matching_entry_types: Vec<AvailableEntryType> =
-											available_entry_types_for_event::table
-												.filter(
-													available_entry_types_for_event::event_id.eq(&event.id).and(
-														available_entry_types_for_event::entry_type.eq(db_entry_type),
-													),
-												)
-												.limit(1)
-												.load(db_connection)?;
-										if matching_entry_types.is_empty() {
-											return Err(diesel::result::Error::RollbackTransaction);
+							let (entry, entry_tags, editor): (EventLogEntryDb, Vec<TagDb>, Option<User>) =
+								run_db_operation(&db_connection_pool, "adding an event log entry", |db_connection| {
+									db_connection.transaction(|db_connection| {
+										if let Some(db_entry_type) = db_entry.entry_type.as_ref() {
+											let matching_entry_types: Vec<AvailableEntryType> =
+												available_entry_types_for_event::table
+													.filter(
+														available_entry_types_for_event::event_id.eq(&event.id).and(
+															available_entry_types_for_event::entry_type
+																.eq(db_entry_type),
+														),
+													)
+													.limit(1)
+													.load(db_connection)?;
+											if matching_entry_types.is_empty() {
+												return Err(diesel::result::Error::RollbackTransaction);
+											}
 										}
-									}
-									let new_row: EventLogEntryDb = diesel::insert_into(event_log::table)
-										.values(db_entry)
-										.get_result(db_connection)?;
-									let new_row_tags: Vec<EventLogTag> = diesel::insert_into(event_log_tags::table)
-										.values(db_tags)
-										.get_results(db_connection)?;
-									let tag_ids: Vec<String> = new_row_tags.iter().map(|tag| tag.tag.clone()).collect();
-									let tags: Vec<TagDb> =
-										tags::table.filter(tags::id.eq_any(tag_ids)).load(db_connection)?;
-									let editor: Option<User> = match new_row.editor.as_ref() {
-										Some(editor) => Some(users::table.find(editor).first(db_connection)?),
-										None => None,
-									};
-									diesel::insert_into(event_log_history::table)
-										.values(history_entry)
-										.execute(db_connection)?;
-									diesel::insert_into(event_log_history_tags::table)
-										.values(history_tags)
-										.execute(db_connection)?;
-									Ok((new_row, tags, editor))
-								});
-							let new_log_entry = match insert_result {
-								Ok((entry, entry_tags, editor)) => {
-									let end_time = entry.end_time_data();
-									let tags: Vec<Tag> = entry_tags
-										.iter()
-										.map(|tag| {
-											let playlist =
-												if let (Some(id), Some(title), Some(shows_in_video_descriptions)) = (
-													tag.playlist.clone(),
-													tag.playlist_title.clone(),
-													tag.playlist_shows_in_video_descriptions,
-												) {
-													Some(TagPlaylist {
-														id,
-														title,
-														shows_in_video_descriptions,
-													})
-												} else {
-													None
-												};
-											Tag {
-												id: tag.id.clone(),
-												name: tag.tag.clone(),
-												description: tag.description.clone(),
-												playlist,
+										if db_entry.video_edit_state == VideoEditState::NoVideo {
+											let tag_ids: Vec<String> =
+												db_tags.iter().map(|tag| tag.tag.clone()).collect();
+											let rule =
+												video_edit_state_rule_for_tags(db_connection, &event.id, &tag_ids)?;
+											if let Some(rule) = rule {
+												db_entry.video_edit_state = rule.video_edit_state;
+												history_entry.video_edit_state = rule.video_edit_state;
+												history_entry.video_edit_state_rule_tag = Some(rule.tag);
 											}
-										})
-										.collect();
-									EventLogEntry {
-										id: entry.id,
-										start_time: Some(entry.start_time),
-										end_time,
-										entry_type: entry.entry_type,
-										description: entry.description,
-										media_links: entry.media_links.into_iter().flatten().collect(),
-										submitter_or_winner: entry.submitter_or_winner,
-										tags,
-										video_edit_state: entry.video_edit_state.into(),
-										notes: entry.notes,
-										editor: editor.map(|user| user.into()),
-										video_link: entry.video_link,
-										parent: entry.parent,
-										created_at: entry.created_at,
-										manual_sort_key: entry.manual_sort_key,
-										video_processing_state: entry.video_processing_state.into(),
-										video_errors: entry.video_errors,
-										poster_moment: entry.poster_moment,
-										missing_giveaway_information: entry.missing_giveaway_information,
-									}
-								}
-								Err(error) => {
-									tide::log::error!("Database error adding an event log entry: {}", error);
-									return Ok(());
+										}
+										// Lock the event while we pick the new entry's number so that entries created at the
+										// same time can't end up with the same number.
+										events::table
+											.find(&event.id)
+											.for_update()
+											.select(events::id)
+											.first::<String>(db_connection)?;
+										let highest_entry_number: Option<i32> = event_log::table
+											.filter(event_log::event.eq(&event.id))
+											.select(max(event_log::entry_number))
+											.first(db_connection)?;
+										db_entry.entry_number = highest_entry_number.unwrap_or(0) + 1;
+
+										let new_row: EventLogEntryDb = diesel::insert_into(event_log::table)
+											.values(&db_entry)
+											.get_result(db_connection)?;
+										let new_row_tags: Vec<EventLogTag> = diesel::insert_into(event_log_tags::table)
+											.values(&db_tags)
+											.get_results(db_connection)?;
+										let tag_ids: Vec<String> =
+											new_row_tags.iter().map(|tag| tag.tag.clone()).collect();
+										let tags: Vec<TagDb> =
+											tags::table.filter(tags::id.eq_any(tag_ids)).load(db_connection)?;
+										let editor: Option<User> = match new_row.editor.as_ref() {
+											Some(editor) => Some(users::table.find(editor).first(db_connection)?),
+											None => None,
+										};
+										diesel::insert_into(event_log_history::table)
+											.values(&history_entry)
+											.execute(db_connection)?;
+										diesel::insert_into(event_log_history_tags::table)
+											.values(&history_tags)
+											.execute(db_connection)?;
+										set_entry_custom_field_values(
+											db_connection,
+											&new_row.id,
+											&new_entry.custom_fields,
+										)?;
+										Ok((new_row, tags, editor))
+									})
+								})
+								.await?;
+							let new_log_entry = {
+								let end_time = entry.end_time_data();
+								let tags: Vec<Tag> = entry_tags
+									.iter()
+									.map(|tag| {
+										let playlist =
+											if let (Some(id), Some(title), Some(shows_in_video_descriptions)) = (
+												tag.playlist.clone(),
+												tag.playlist_title.clone(),
+												tag.playlist_shows_in_video_descriptions,
+											) {
+												Some(TagPlaylist {
+													id,
+													title,
+													shows_in_video_descriptions,
+												})
+											} else {
+												None
+											};
+										Tag {
+											id: tag.id.clone(),
+											name: tag.tag.clone(),
+											description: tag.description.clone(),
+											playlist,
+											valid_from: tag.valid_from,
+											valid_to: tag.valid_to,
+										}
+									})
+									.collect();
+								EventLogEntry {
+									id: entry.id,
+									start_time: Some(entry.start_time),
+									end_time,
+									entry_type: entry.entry_type,
+									description: entry.description,
+									media_links: entry.media_links.into_iter().flatten().collect(),
+									submitter_or_winner: entry.submitter_or_winner,
+									tags,
+									video_edit_state: entry.video_edit_state.into(),
+									notes: entry.notes,
+									editor: editor.map(|user| user.into()),
+									video_link: entry.video_link,
+									parent: entry.parent,
+									created_at: entry.created_at,
+									created_by: Some(user.clone().into()),
+									manual_sort_key: entry.manual_sort_key,
+									video_processing_state: entry.video_processing_state.into(),
+									video_errors: entry.video_errors,
+									poster_moment: entry.poster_moment,
+									missing_giveaway_information: entry.missing_giveaway_information,
+									dead_links: entry.dead_links.into_iter().flatten().collect(),
+									entry_number: Some(entry.entry_number),
+									custom_fields: new_entry.custom_fields.clone(),
+									history_id: entry.latest_history_id,
 								}
 							};
 
@@ -797,6 +1354,7 @@ pub async fn handle_event_update(
 								event_new_entries.push(new_entry);
 							}
 
+							plugins.entry_created(event, &new_log_entry, user);
 							entry_messages.push(EventSubscriptionData::UpdateLogEntry(
 								new_log_entry,
 								Some(user.clone().into()),
@@ -820,23 +1378,34 @@ pub async fn handle_event_update(
 				}
 			};
 
-			if let Some(subscription_data) = new_entry_subscription_data {
+			let entry_messages = if let Some(subscription_data) = new_entry_subscription_data {
 				// We handled everything with the new entry stuff
 				subscription_data
+			} else if modified_parts.is_empty() {
+				// There are no changes to save
+				Vec::new()
 			} else if log_entry.start_time.is_none() && modified_parts.contains(&ModifiedEventLogEntryParts::StartTime)
 			{
 				// Clearing the start time from an existing entry is not allowed, so we simply ignore this update
 				Vec::new()
+			} else if modified_parts.contains(&ModifiedEventLogEntryParts::Parent)
+				&& run_db_operation(
+					&db_connection_pool,
+					"checking a log entry's new parent",
+					|db_connection| {
+						parent_would_create_cycle(db_connection, &log_entry.id, log_entry.parent.as_deref())
+					},
+				)
+				.await?
+			{
+				// An entry can't be moved under one of its own children, so we ignore this update too
+				Vec::new()
 			} else {
-				let mut db_connection = match db_connection_pool.get() {
-					Ok(connection) => connection,
-					Err(error) => {
-						tide::log::error!("Database connection error updating a log entry: {}", error);
-						return Ok(());
-					}
-				};
-				let update_func = |db_connection: &mut PgConnection| {
+				let update_func = |db_connection: &mut PgConnection,
+				                   modified_parts: &[ModifiedEventLogEntryParts]|
+				 -> QueryResult<(EventLogEntryDb, Option<String>)> {
 					let mut changes = EventLogEntryChanges::default();
+					let mut added_tag_ids: Vec<String> = Vec::new();
 					for part in modified_parts.iter() {
 						match part {
 							ModifiedEventLogEntryParts::StartTime => {
@@ -864,12 +1433,26 @@ pub async fn handle_event_update(
 							}
 							ModifiedEventLogEntryParts::MediaLinks => {
 								changes.media_links =
-									Some(log_entry.media_links.iter().map(|link| Some(link.clone())).collect())
+									Some(log_entry.media_links.iter().map(|link| Some(link.clone())).collect());
+								// Changed links need to be checked again
+								changes.links_checked_at = Some(None);
 							}
 							ModifiedEventLogEntryParts::SubmitterOrWinner => {
 								changes.submitter_or_winner = Some(log_entry.submitter_or_winner.clone())
 							}
 							ModifiedEventLogEntryParts::Tags => {
+								let previous_tag_ids: HashSet<String> = event_log_tags::table
+									.filter(event_log_tags::log_entry.eq(&log_entry.id))
+									.select(event_log_tags::tag)
+									.load::<String>(db_connection)?
+									.into_iter()
+									.collect();
+								added_tag_ids = log_entry
+									.tags
+									.iter()
+									.filter(|tag| !previous_tag_ids.contains(&tag.id))
+									.map(|tag| tag.id.clone())
+									.collect();
 								let updated_tags: Vec<EventLogTag> = log_entry
 									.tags
 									.iter()
@@ -902,85 +1485,228 @@ pub async fn handle_event_update(
 								changes.manual_sort_key = Some(log_entry.manual_sort_key)
 							}
 							ModifiedEventLogEntryParts::Parent => changes.parent = Some(log_entry.parent.clone()),
+							ModifiedEventLogEntryParts::CustomFields => {
+								set_entry_custom_field_values(db_connection, &log_entry.id, &log_entry.custom_fields)?
+							}
+						}
+					}
+
+					// Video edit state rules only apply to newly-added tags and don't override states set by the user
+					let mut applied_rule_tag: Option<String> = None;
+					if changes.video_edit_state.is_none() && !added_tag_ids.is_empty() {
+						let current_video_edit_state: VideoEditState = event_log::table
+							.find(&log_entry.id)
+							.select(event_log::video_edit_state)
+							.first(db_connection)?;
+						if current_video_edit_state == VideoEditState::NoVideo {
+							let rule = video_edit_state_rule_for_tags(db_connection, &event.id, &added_tag_ids)?;
+							if let Some(rule) = rule {
+								changes.video_edit_state = Some(rule.video_edit_state);
+								applied_rule_tag = Some(rule.tag);
+							}
 						}
 					}
 
-					if changes.has_changes() {
+					let entry: EventLogEntryDb = if changes.has_changes() {
 						diesel::update(event_log::table)
 							.filter(event_log::id.eq(&log_entry.id))
 							.set(changes)
-							.get_result(db_connection)
+							.get_result(db_connection)?
 					} else {
-						event_log::table.find(&log_entry.id).first(db_connection)
-					}
+						event_log::table.find(&log_entry.id).first(db_connection)?
+					};
+					Ok((entry, applied_rule_tag))
 				};
-				let update_result = log_entry_change(&mut db_connection, update_func, user.id.clone());
+				// The entry is locked while checking for conflicts so no other change can be saved between the check and
+				// this update. Parts someone else changed since the version the user's changes were based on aren't saved,
+				// so the user can decide which value to keep. The rest of the update is saved as usual.
+				let (updated_entry, saved_parts, conflict) =
+					run_db_operation(&db_connection_pool, "updating a log entry", |db_connection| {
+						let current_entry: Option<EventLogEntryDb> = event_log::table
+							.filter(
+								event_log::id
+									.eq(&log_entry.id)
+									.and(event_log::event.eq(&event.id))
+									.and(event_log::deleted_by.is_null()),
+							)
+							.for_update()
+							.first(db_connection)
+							.optional()?;
+						let Some(current_entry) = current_entry else {
+							return Ok((None, Vec::new(), None));
+						};
+
+						let conflict = match base_history_id.as_ref() {
+							Some(base_history_id)
+								if current_entry.latest_history_id.as_ref() != Some(base_history_id) =>
+							{
+								load_update_conflict(
+									db_connection,
+									&log_entry,
+									&modified_parts,
+									base_history_id,
+									current_entry,
+								)?
+							}
+							_ => None,
+						};
+						let saved_parts: Vec<ModifiedEventLogEntryParts> = match conflict.as_ref() {
+							Some(conflict) => modified_parts
+								.iter()
+								.filter(|part| !conflict.conflicting_parts.contains(part))
+								.copied()
+								.collect(),
+							None => modified_parts.clone(),
+						};
+						if saved_parts.is_empty() {
+							return Ok((None, saved_parts, conflict));
+						}
 
-				let log_entry = match update_result {
-					Ok(entry) => entry,
-					Err(error) => {
-						tide::log::error!("Database error updating a log entry: {}", error);
-						return Ok(());
+						let entry = log_entry_change(
+							db_connection,
+							|db_connection| update_func(db_connection, &saved_parts),
+							user.id.clone(),
+						)?;
+						Ok((Some(entry), saved_parts, conflict))
+					})
+					.await?;
+				modified_parts = saved_parts;
+				update_conflict = conflict;
+
+				match updated_entry {
+					Some(log_entry) => {
+						plugins.entry_updated(event, &log_entry, &modified_parts, user);
+						vec![EventSubscriptionData::UpdateLogEntry(
+							log_entry,
+							Some(user.clone().into()),
+						)]
 					}
-				};
+					None => Vec::new(),
+				}
+			};
+
+			for message in entry_messages.iter() {
+				if let EventSubscriptionData::UpdateLogEntry(entry, Some(_)) = message {
+					send_entry_change_notifications(
+						&db_connection_pool,
+						&subscription_manager,
+						event,
+						entry,
+						&modified_parts,
+						user,
+					)
+					.await;
+				}
+			}
 
-				vec![EventSubscriptionData::UpdateLogEntry(
-					log_entry,
-					Some(user.clone().into()),
-				)]
+			if let Some(conflict) = update_conflict {
+				let message = FromServerMessage::SubscriptionMessage(
+					Box::new(SubscriptionData::EventUpdate(
+						event.clone(),
+						Box::new(EventSubscriptionData::LogEntryUpdateConflict(Box::new(conflict))),
+					)),
+					None,
+				);
+				conn_update_tx
+					.send(ConnectionUpdate::SendData(Box::new(message)))
+					.await?;
 			}
+
+			entry_messages
 		}
-		EventSubscriptionUpdate::DeleteLogEntry(deleted_log_entry) => {
-			// Deleting an entry requires supervisor permissions, so we'll ignore requests from non-supervisors.
+		EventSubscriptionUpdate::DeleteLogEntry(deleted_log_entry, child_handling) => {
+			// Deleting an entry requires supervisor permissions
 			if *permission_level != Some(Permission::Supervisor) {
-				return Ok(());
+				return Err(HandlerError::NotAllowed);
 			}
 
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!("Database connection error deleting an event log entry: {}", error);
-					return Ok(());
-				}
-			};
-			let delete_result: QueryResult<()> = db_connection.transaction(|db_connection| {
-				let deleted_entry: EventLogEntryDb = diesel::update(event_log::table)
-					.filter(
-						event_log::id
-							.eq(&deleted_log_entry.id)
-							.and(event_log::video_link.is_null()),
-					)
-					.set(event_log::deleted_by.eq(&user.id))
-					.get_result(db_connection)?;
-				let deleted_entry_tags: Vec<EventLogTag> = event_log_tags::table
-					.filter(event_log_tags::log_entry.eq(&deleted_entry.id))
-					.load(db_connection)?;
-				let history_entry = EventLogHistoryEntry::new_from_event_log_entry(
-					&deleted_entry,
-					Utc::now(),
-					EditSource::User(user.id.clone()),
-				);
-				let history_entry_tags: Vec<EventLogHistoryTag> = deleted_entry_tags
-					.into_iter()
-					.map(|tag| EventLogHistoryTag {
-						tag: tag.tag,
-						history_log_entry: history_entry.id.clone(),
+			let (deleted_children, promoted_children) =
+				run_db_operation(&db_connection_pool, "deleting an event log entry", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let deleted_entry: EventLogEntryDb = diesel::update(event_log::table)
+							.filter(
+								event_log::id
+									.eq(&deleted_log_entry.id)
+									.and(event_log::video_link.is_null()),
+							)
+							.set(event_log::deleted_by.eq(&user.id))
+							.get_result(db_connection)?;
+						let deleted_entry_tags = load_entry_tags(db_connection, &deleted_entry.id)?;
+						record_entry_deletion(db_connection, &deleted_entry, &deleted_entry_tags, &user.id)?;
+
+						let mut deleted_children: Vec<EventLogEntry> = Vec::new();
+						let mut promoted_children: Vec<EventLogEntry> = Vec::new();
+						match child_handling {
+							ChildEntryHandling::Delete => {
+								// Each pass deletes the next level of children, so this continues until we reach
+								// entries that have no children.
+								let mut parent_ids = vec![deleted_entry.id];
+								while !parent_ids.is_empty() {
+									let children: Vec<EventLogEntryDb> = diesel::update(event_log::table)
+										.filter(
+											event_log::parent
+												.eq_any(&parent_ids)
+												.and(event_log::deleted_by.is_null()),
+										)
+										.set(event_log::deleted_by.eq(&user.id))
+										.get_results(db_connection)?;
+									// Entries with videos can't be deleted, so we can't delete their parents'
+									// children either.
+									if children.iter().any(|child| child.video_link.is_some()) {
+										return Err(diesel::result::Error::RollbackTransaction);
+									}
+
+									parent_ids = children.iter().map(|child| child.id.clone()).collect();
+									for child in children {
+										let child_tags = load_entry_tags(db_connection, &child.id)?;
+										record_entry_deletion(db_connection, &child, &child_tags, &user.id)?;
+										deleted_children.push(event_log_entry_from_db(
+											db_connection,
+											child,
+											child_tags,
+										)?);
+									}
+								}
+							}
+							ChildEntryHandling::Promote => {
+								let child_ids: Vec<String> = event_log::table
+									.filter(
+										event_log::parent
+											.eq(&deleted_entry.id)
+											.and(event_log::deleted_by.is_null()),
+									)
+									.select(event_log::id)
+									.load(db_connection)?;
+								for child_id in child_ids {
+									let child = log_entry_change(
+										db_connection,
+										|db_connection| {
+											let child: EventLogEntryDb = diesel::update(event_log::table)
+												.filter(event_log::id.eq(&child_id))
+												.set(event_log::parent.eq(None::<String>))
+												.get_result(db_connection)?;
+											Ok((child, None))
+										},
+										user.id.clone(),
+									)?;
+									promoted_children.push(child);
+								}
+							}
+						}
+
+						Ok((deleted_children, promoted_children))
 					})
-					.collect();
-				diesel::insert_into(event_log_history::table)
-					.values(history_entry)
-					.execute(db_connection)?;
-				diesel::insert_into(event_log_history_tags::table)
-					.values(history_entry_tags)
-					.execute(db_connection)?;
-				Ok(())
-			});
-			if let Err(error) = delete_result {
-				tide::log::error!("Database error deleting an event log entry: {}", error);
-				return Ok(());
-			}
+				})
+				.await?;
 
-			vec![EventSubscriptionData::DeleteLogEntry(deleted_log_entry)]
+			let mut subscription_data = vec![EventSubscriptionData::DeleteLogEntry(deleted_log_entry)];
+			subscription_data.extend(deleted_children.into_iter().map(EventSubscriptionData::DeleteLogEntry));
+			subscription_data.extend(
+				promoted_children
+					.into_iter()
+					.map(|child| EventSubscriptionData::UpdateLogEntry(child, Some(user.clone().into()))),
+			);
+			subscription_data
 		}
 		EventSubscriptionUpdate::Typing(typing_data) => {
 			let user_data: PublicUserData = user.clone().into();
@@ -1007,6 +1733,8 @@ pub async fn handle_event_update(
 				NewTypingData::Notes(log_entry, notes_to_editor) => {
 					TypingData::Notes(log_entry, notes_to_editor, user_data)
 				}
+				NewTypingData::Tags(log_entry, tags) => TypingData::Tags(log_entry, tags, user_data),
+				NewTypingData::SortKey(log_entry, sort_key) => TypingData::SortKey(log_entry, sort_key, user_data),
 				NewTypingData::Clear(log_entry) => TypingData::Clear(log_entry, user_data),
 			};
 			vec![EventSubscriptionData::Typing(typing_data)]
@@ -1015,10 +1743,19 @@ pub async fn handle_event_update(
 			let new_tag = tag.id.is_empty();
 			if new_tag {
 				if tag.name.is_empty() || tag.name.contains(',') || tag.description.is_empty() {
-					return Ok(());
+					return Err(HandlerError::InvalidData(String::from(
+						"Tags must have a name without commas and a description.",
+					)));
 				}
 				tag.id = cuid2::create_id();
 			}
+			if let (Some(valid_from), Some(valid_to)) = (tag.valid_from, tag.valid_to) {
+				if valid_to < valid_from {
+					return Err(HandlerError::InvalidData(String::from(
+						"A tag's last valid day can't be before its first valid day.",
+					)));
+				}
+			}
 			let (playlist, playlist_title, playlist_shows_in_video_descriptions) =
 				if let Some(playlist) = tag.playlist.as_ref() {
 					(
@@ -1030,13 +1767,6 @@ pub async fn handle_event_update(
 					(None, None, None)
 				};
 
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!("Database connection error updating a tag: {}", error);
-					return Ok(());
-				}
-			};
 			let tag_db = TagDb {
 				id: tag.id.clone(),
 				tag: tag.name.clone(),
@@ -1046,239 +1776,654 @@ pub async fn handle_event_update(
 				playlist,
 				playlist_title,
 				playlist_shows_in_video_descriptions,
+				valid_from: tag.valid_from,
+				valid_to: tag.valid_to,
 			};
-			let db_result: QueryResult<bool> = db_connection.transaction(|db_connection| {
-				if new_tag {
-					diesel::insert_into(tags::table)
-						.values(&tag_db)
-						.execute(db_connection)?;
-					Ok(true)
-				} else {
-					let this_tag: TagDb = tags::table.find(&tag_db.id).first(db_connection)?;
-					if this_tag.for_event != event.id {
-						return Ok(false);
+			let tag_in_event = run_db_operation(&db_connection_pool, "updating a tag", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					if new_tag {
+						diesel::insert_into(tags::table)
+							.values(&tag_db)
+							.execute(db_connection)?;
+						Ok(true)
+					} else {
+						let this_tag: TagDb = tags::table.find(&tag_db.id).first(db_connection)?;
+						if this_tag.for_event != event.id {
+							return Ok(false);
+						}
+						diesel::update(tags::table)
+							.filter(tags::id.eq(&tag_db.id))
+							.set(&tag_db)
+							.execute(db_connection)?;
+						Ok(true)
 					}
-					diesel::update(tags::table)
-						.filter(tags::id.eq(&tag_db.id))
-						.set(&tag_db)
-						.execute(db_connection)?;
-					Ok(true)
-				}
-			});
-			match db_result {
-				Ok(true) => (),
-				Ok(false) => return Ok(()),
-				Err(error) => {
-					tide::log::error!("Database error updating a tag: {}", error);
-					return Ok(());
-				}
+				})
+			})
+			.await?;
+			if !tag_in_event {
+				return Err(tag_not_in_event_error());
+			}
+			if new_tag {
+				plugins.tag_created(event, &tag, user);
 			}
 
 			vec![EventSubscriptionData::UpdateTag(tag)]
 		}
 		EventSubscriptionUpdate::RemoveTag(tag) => {
 			if *permission_level != Some(Permission::Supervisor) {
-				return Ok(());
+				return Err(HandlerError::NotAllowed);
 			}
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!("Database connection error removing a tag: {}", error);
-					return Ok(());
-				}
-			};
-			let delete_result: QueryResult<bool> = db_connection.transaction(|db_connection| {
-				let this_tag: TagDb = tags::table.find(&tag.id).first(db_connection)?;
-				if this_tag.for_event != event.id {
-					return Ok(false);
-				}
-				diesel::update(tags::table)
-					.filter(tags::id.eq(&tag.id))
-					.set(tags::deleted.eq(true))
-					.execute(db_connection)?;
-				Ok(true)
-			});
-			match delete_result {
-				Ok(true) => (),
-				Ok(false) => return Ok(()),
-				Err(error) => {
-					tide::log::error!("Database error removing a tag: {}", error);
-					return Ok(());
-				}
+			if event_requires_tag_deletion_approval(&db_connection_pool, event).await? {
+				let pending_action = request_tag_action(&db_connection_pool, event, user, tag, None).await?;
+				vec![EventSubscriptionData::AddPendingTagAction(pending_action)]
+			} else {
+				let removal = run_db_operation(&db_connection_pool, "removing a tag", |db_connection| {
+					db_connection.transaction(|db_connection| remove_tag(db_connection, &event.id, &tag.id, None))
+				})
+				.await?;
+				let Some(removal) = removal else {
+					return Err(tag_not_in_event_error());
+				};
+				tag_removal_messages(tag, removal, user)
 			}
-
-			vec![EventSubscriptionData::RemoveTag(tag)]
 		}
 		EventSubscriptionUpdate::ReplaceTag(tag, replacement_tag) => {
 			if *permission_level != Some(Permission::Supervisor) {
-				return Ok(());
+				return Err(HandlerError::NotAllowed);
 			}
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!("Database connection error replacing a tag: {}", error);
-					return Ok(());
-				}
-			};
-			let replace_result: QueryResult<(bool, Vec<EventLogEntry>)> = db_connection.transaction(|db_connection| {
-				let original_tag: TagDb = tags::table.find(&tag.id).first(db_connection)?;
-				let replacement: TagDb = tags::table.find(&replacement_tag.id).first(db_connection)?;
-				if original_tag.for_event != event.id || replacement.for_event != event.id {
-					return Ok((false, Vec::new()));
-				}
-
-				let log_entry_tags: Vec<EventLogTag> = event_log_tags::table
-					.filter(event_log_tags::tag.eq(&tag.id))
-					.load(db_connection)?;
-				let entry_tags: Vec<EventLogTag> = log_entry_tags
-					.iter()
-					.map(|log_entry_tag| EventLogTag {
-						tag: replacement_tag.id.clone(),
-						log_entry: log_entry_tag.log_entry.clone(),
+			if event_requires_tag_deletion_approval(&db_connection_pool, event).await? {
+				let pending_action =
+					request_tag_action(&db_connection_pool, event, user, tag, Some(replacement_tag)).await?;
+				vec![EventSubscriptionData::AddPendingTagAction(pending_action)]
+			} else {
+				let removal = run_db_operation(&db_connection_pool, "replacing a tag", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						remove_tag(db_connection, &event.id, &tag.id, Some(&replacement_tag.id))
 					})
-					.collect();
-				diesel::insert_into(event_log_tags::table)
-					.values(&entry_tags)
-					.on_conflict_do_nothing()
-					.execute(db_connection)?;
-				diesel::delete(event_log_tags::table)
-					.filter(event_log_tags::tag.eq(&tag.id))
-					.execute(db_connection)?;
-				diesel::update(tags::table)
-					.filter(tags::id.eq(&tag.id))
-					.set(tags::deleted.eq(true))
-					.execute(db_connection)?;
-
-				let log_entry_ids: Vec<String> = log_entry_tags
-					.iter()
-					.map(|tag_entry| tag_entry.log_entry.clone())
-					.collect();
-				let affected_log_entries: Vec<EventLogEntryDb> = event_log::table
-					.filter(event_log::id.eq_any(log_entry_ids))
-					.load(db_connection)?;
-
-				let mut output_log_entries: Vec<EventLogEntry> = Vec::with_capacity(affected_log_entries.len());
-				for log_entry in affected_log_entries.iter() {
-					let end_time = log_entry.end_time_data();
-
-					let tag_ids: Vec<String> = entry_tags
+				})
+				.await?;
+				let Some(removal) = removal else {
+					return Err(tag_not_in_event_error());
+				};
+				tag_removal_messages(tag, removal, user)
+			}
+		}
+		EventSubscriptionUpdate::CopyTagsFromEvent(copy_from_event) => {
+			if !user.is_admin {
+				return Err(HandlerError::NotAllowed);
+			}
+
+			let added_tags: Vec<TagDb> = run_db_operation(&db_connection_pool, "copying event tags", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					let event_tags: Vec<TagDb> = tags::table
+						.filter(tags::for_event.eq(&copy_from_event.id).and(tags::deleted.eq(false)))
+						.load(db_connection)?;
+					let event_tag_names: Vec<String> = event_tags.iter().map(|tag| tag.tag.clone()).collect();
+					let overlapping_event_tag_names: Vec<String> = tags::table
+						.filter(
+							tags::for_event
+								.eq(&event.id)
+								.and(tags::tag.eq_any(&event_tag_names))
+								.and(tags::deleted.eq(false)),
+						)
+						.select(tags::tag)
+						.load(db_connection)?;
+					let overlapping_event_tag_names: HashSet<String> =
+						overlapping_event_tag_names.into_iter().collect();
+					let new_event_tags: Vec<TagDb> = event_tags
 						.iter()
-						.filter(|entry_tag| entry_tag.log_entry == log_entry.id)
-						.map(|entry_tag| entry_tag.tag.clone())
+						.filter(|tag| !overlapping_event_tag_names.contains(&tag.tag))
+						.map(|tag| TagDb {
+							id: cuid2::create_id(),
+							tag: tag.tag.clone(),
+							description: tag.description.clone(),
+							for_event: event.id.clone(),
+							deleted: false,
+							playlist: None,
+							playlist_title: None,
+							playlist_shows_in_video_descriptions: None,
+							// Valid dates are specific to the days of the event the tag is copied from
+							valid_from: None,
+							valid_to: None,
+						})
 						.collect();
-					let tags: Vec<TagDb> = tags::table.filter(tags::id.eq_any(tag_ids)).load(db_connection)?;
-					let tags: Vec<Tag> = tags.into_iter().map(|tag| tag.into()).collect();
-
-					let editor = match log_entry.editor.as_ref() {
-						Some(editor) => {
-							let editor: User = users::table.find(editor).first(db_connection)?;
-							let editor: PublicUserData = editor.into();
-							Some(editor)
-						}
-						None => None,
-					};
+					diesel::insert_into(tags::table)
+						.values(&new_event_tags)
+						.execute(db_connection)?;
 
-					let updated_entry = EventLogEntry {
-						id: log_entry.id.clone(),
-						start_time: Some(log_entry.start_time),
-						end_time,
-						entry_type: log_entry.entry_type.clone(),
-						description: log_entry.description.clone(),
-						media_links: log_entry.media_links.iter().filter_map(|link| link.clone()).collect(),
-						submitter_or_winner: log_entry.submitter_or_winner.clone(),
-						tags,
-						notes: log_entry.notes.clone(),
-						editor,
-						video_link: log_entry.video_link.clone(),
-						parent: log_entry.parent.clone(),
-						created_at: log_entry.created_at,
-						manual_sort_key: log_entry.manual_sort_key,
-						video_processing_state: log_entry.video_processing_state.into(),
-						video_errors: log_entry.video_errors.clone(),
-						poster_moment: log_entry.poster_moment,
-						video_edit_state: log_entry.video_edit_state.into(),
-						missing_giveaway_information: log_entry.missing_giveaway_information,
-					};
-					output_log_entries.push(updated_entry);
-				}
+					Ok(new_event_tags)
+				})
+			})
+			.await?;
+			let added_tags: Vec<Tag> = added_tags.into_iter().map(|tag| tag.into()).collect();
+			for tag in added_tags.iter() {
+				plugins.tag_created(event, tag, user);
+			}
 
-				Ok((true, output_log_entries))
-			});
-			let log_entries = match replace_result {
-				Ok((true, entries)) => entries,
-				Ok((false, _)) => return Ok(()),
-				Err(error) => {
-					tide::log::error!("Database error replacing a tag: {}", error);
-					return Ok(());
+			added_tags.into_iter().map(EventSubscriptionData::UpdateTag).collect()
+		}
+		EventSubscriptionUpdate::SetVideoEditStateRule(tag, video_edit_state) => {
+			if !user.is_admin {
+				return Err(HandlerError::NotAllowed);
+			}
+
+			let rule_tag: Option<TagDb> = run_db_operation(
+				&db_connection_pool,
+				"setting a video edit state rule",
+				|db_connection| {
+					db_connection.transaction(|db_connection| {
+						let this_tag: TagDb = tags::table.find(&tag.id).first(db_connection)?;
+						if this_tag.for_event != event.id || this_tag.deleted {
+							return Ok(None);
+						}
+						match video_edit_state {
+							Some(state) => {
+								let rule = VideoEditStateTagRuleDb {
+									tag: this_tag.id.clone(),
+									event: event.id.clone(),
+									video_edit_state: state.into(),
+								};
+								diesel::insert_into(video_edit_state_tag_rules::table)
+									.values(&rule)
+									.on_conflict(video_edit_state_tag_rules::tag)
+									.do_update()
+									.set(video_edit_state_tag_rules::video_edit_state.eq(rule.video_edit_state))
+									.execute(db_connection)?;
+							}
+							None => {
+								diesel::delete(video_edit_state_tag_rules::table)
+									.filter(video_edit_state_tag_rules::tag.eq(&this_tag.id))
+									.execute(db_connection)?;
+							}
+						}
+						Ok(Some(this_tag))
+					})
+				},
+			)
+			.await?;
+			let Some(tag) = rule_tag else {
+				return Err(tag_not_in_event_error());
+			};
+			let tag: Tag = tag.into();
+
+			match video_edit_state {
+				Some(video_edit_state) => {
+					vec![EventSubscriptionData::UpdateVideoEditStateRule(VideoEditStateTagRule {
+						tag,
+						video_edit_state,
+					})]
 				}
+				None => vec![EventSubscriptionData::DeleteVideoEditStateRule(tag)],
+			}
+		}
+		EventSubscriptionUpdate::ShiftEntryTimes(time_shift) => {
+			// Shifting entries changes many entries at once, so it requires supervisor permissions
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let Some(shift) = TimeDelta::try_seconds(time_shift.shift_seconds) else {
+				return Err(HandlerError::InvalidData(String::from("The time shift is too large.")));
 			};
-			let mut send_messages: Vec<EventSubscriptionData> = Vec::with_capacity(log_entries.len() + 1);
-			for log_entry in log_entries.into_iter() {
-				send_messages.push(EventSubscriptionData::UpdateLogEntry(
-					log_entry,
-					Some(user.clone().into()),
-				));
+			if shift.is_zero() {
+				return Ok(());
+			}
+			if let Some(range_end) = time_shift.range_end {
+				if range_end < time_shift.range_start {
+					return Err(HandlerError::InvalidData(String::from(
+						"The end of the range to shift is before the start.",
+					)));
+				}
 			}
-			send_messages.push(EventSubscriptionData::RemoveTag(tag));
 
-			send_messages
+			let shifted_entries = run_db_operation(&db_connection_pool, "shifting entry times", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					let mut entries_query = event_log::table
+						.filter(
+							event_log::event
+								.eq(&event.id)
+								.and(event_log::deleted_by.is_null())
+								.and(event_log::start_time.ge(time_shift.range_start)),
+						)
+						.into_boxed();
+					if let Some(range_end) = time_shift.range_end {
+						entries_query = entries_query.filter(event_log::start_time.le(range_end));
+					}
+					let entries: Vec<EventLogEntryDb> = entries_query.load(db_connection)?;
+
+					let mut shifted_entries: Vec<EventLogEntry> = Vec::with_capacity(entries.len());
+					for entry in entries {
+						let shifted_entry = log_entry_change(
+							db_connection,
+							|db_connection| {
+								let entry: EventLogEntryDb = diesel::update(event_log::table)
+									.filter(event_log::id.eq(&entry.id))
+									.set((
+										event_log::start_time.eq(entry.start_time + shift),
+										event_log::end_time.eq(entry.end_time.map(|end_time| end_time + shift)),
+									))
+									.get_result(db_connection)?;
+								Ok((entry, None))
+							},
+							user.id.clone(),
+						)?;
+						shifted_entries.push(shifted_entry);
+					}
+					Ok(shifted_entries)
+				})
+			})
+			.await?;
+
+			shifted_entries
+				.into_iter()
+				.map(|entry| EventSubscriptionData::UpdateLogEntry(entry, Some(user.clone().into())))
+				.collect()
 		}
-		EventSubscriptionUpdate::CopyTagsFromEvent(copy_from_event) => {
-			if !user.is_admin {
+		EventSubscriptionUpdate::ApprovePendingTagAction(action_id) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let approval_result: Result<(TagDb, Option<TagRemoval>), HandlerError> =
+				run_db_operation(&db_connection_pool, "approving a tag action", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						let action: Option<PendingTagActionDb> = pending_tag_actions::table
+							.find(&action_id)
+							.first(db_connection)
+							.optional()?;
+						let Some(action) = action else {
+							return Ok(Err(HandlerError::InvalidData(String::from(
+								"That tag change is no longer waiting for approval.",
+							))));
+						};
+						if action.event != event.id {
+							return Ok(Err(tag_not_in_event_error()));
+						}
+						if action.requested_by == user.id {
+							return Ok(Err(HandlerError::InvalidData(String::from(
+								"A different supervisor must approve this tag change.",
+							))));
+						}
+
+						let tag: TagDb = tags::table.find(&action.tag).first(db_connection)?;
+						let removal =
+							remove_tag(db_connection, &event.id, &action.tag, action.replacement_tag.as_deref())?;
+						Ok(Ok((tag, removal)))
+					})
+				})
+				.await?;
+			let (tag, removal) = approval_result?;
+			let Some(removal) = removal else {
+				return Err(tag_not_in_event_error());
+			};
+			tag_removal_messages(tag.into(), removal, user)
+		}
+		EventSubscriptionUpdate::RejectPendingTagAction(action_id) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let deleted_count = run_db_operation(&db_connection_pool, "rejecting a tag action", |db_connection| {
+				diesel::delete(pending_tag_actions::table)
+					.filter(
+						pending_tag_actions::id
+							.eq(&action_id)
+							.and(pending_tag_actions::event.eq(&event.id)),
+					)
+					.execute(db_connection)
+			})
+			.await?;
+			if deleted_count == 0 {
+				// Someone else already handled it
 				return Ok(());
 			}
+			vec![EventSubscriptionData::RemovePendingTagAction(action_id)]
+		}
+		EventSubscriptionUpdate::SubmitFeedback(message) => {
+			let message = message.trim().to_string();
+			if message.is_empty() {
+				return Err(HandlerError::InvalidData(String::from("Feedback can't be empty.")));
+			}
+			if message.chars().count() > MAX_FEEDBACK_LENGTH {
+				return Err(HandlerError::InvalidData(format!(
+					"Feedback can't be longer than {} characters.",
+					MAX_FEEDBACK_LENGTH
+				)));
+			}
+			let rate_limit_result = feedback_rate_limiter
+				.lock()
+				.await
+				.try_record_feedback(&user.id, &event.id);
+			if let Err(wait_time) = rate_limit_result {
+				return Err(HandlerError::InvalidData(format!(
+					"You can send more feedback in {} seconds.",
+					wait_time.num_seconds().max(1)
+				)));
+			}
 
-			let mut db_connection = match db_connection_pool.get() {
-				Ok(connection) => connection,
-				Err(error) => {
-					tide::log::error!("Database connection error copying event tags: {}", error);
-					return Ok(());
-				}
+			let feedback = EventFeedbackDb {
+				id: cuid2::create_id(),
+				event: event.id.clone(),
+				message,
+				submitted_at: Utc::now(),
 			};
-			let added_tags: QueryResult<Vec<TagDb>> = db_connection.transaction(|db_connection| {
-				let event_tags: Vec<TagDb> = tags::table
-					.filter(tags::for_event.eq(copy_from_event.id).and(tags::deleted.eq(false)))
-					.load(db_connection)?;
-				let event_tag_names: Vec<String> = event_tags.iter().map(|tag| tag.tag.clone()).collect();
-				let overlapping_event_tag_names: Vec<String> = tags::table
+			let feedback: EventFeedbackDb =
+				run_db_operation(&db_connection_pool, "adding event feedback", |db_connection| {
+					diesel::insert_into(event_feedback::table)
+						.values(&feedback)
+						.get_result(db_connection)
+				})
+				.await?;
+			let feedback: EventFeedback = feedback.into();
+
+			// Feedback is only shown to supervisors. Everyone else is sent a removal for it instead, which they can
+			// ignore, so that they don't get the message contents.
+			let supervisor_message = SubscriptionData::EventUpdate(
+				event.clone(),
+				Box::new(EventSubscriptionData::AddFeedback(feedback.clone())),
+			);
+			let hidden_message = SubscriptionData::EventUpdate(
+				event.clone(),
+				Box::new(EventSubscriptionData::RemoveFeedback(feedback.id)),
+			);
+			let broadcast_result = subscription_manager
+				.lock()
+				.await
+				.broadcast_event_message_by_permission(
+					&event.id,
+					Permission::Supervisor,
+					supervisor_message,
+					hidden_message,
+				)
+				.await;
+			if let Err(error) = broadcast_result {
+				tide::log::error!("Error occurred broadcasting event feedback: {}", error);
+			}
+			return Ok(());
+		}
+		EventSubscriptionUpdate::DismissFeedback(feedback_id) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let deleted_count = run_db_operation(&db_connection_pool, "dismissing event feedback", |db_connection| {
+				diesel::delete(event_feedback::table)
 					.filter(
-						tags::for_event
-							.eq(&event.id)
-							.and(tags::tag.eq_any(&event_tag_names))
-							.and(tags::deleted.eq(false)),
+						event_feedback::id
+							.eq(&feedback_id)
+							.and(event_feedback::event.eq(&event.id)),
 					)
-					.select(tags::tag)
-					.load(db_connection)?;
-				let overlapping_event_tag_names: HashSet<String> = overlapping_event_tag_names.into_iter().collect();
-				let new_event_tags: Vec<TagDb> = event_tags
-					.iter()
-					.filter(|tag| !overlapping_event_tag_names.contains(&tag.tag))
-					.map(|tag| TagDb {
-						id: cuid2::create_id(),
-						tag: tag.tag.clone(),
-						description: tag.description.clone(),
-						for_event: event.id.clone(),
-						deleted: false,
-						playlist: None,
-						playlist_title: None,
-						playlist_shows_in_video_descriptions: None,
+					.execute(db_connection)
+			})
+			.await?;
+			if deleted_count == 0 {
+				// Another supervisor already dismissed it
+				return Ok(());
+			}
+			vec![EventSubscriptionData::RemoveFeedback(feedback_id)]
+		}
+		EventSubscriptionUpdate::UpdateEntryTemplate(mut template) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			template.name = template.name.trim().to_string();
+			if template.name.is_empty() {
+				return Err(HandlerError::InvalidData(String::from(
+					"Entry templates must have a name.",
+				)));
+			}
+			let new_template = template.id.is_empty();
+			if new_template {
+				template.id = cuid2::create_id();
+			}
+
+			let template_db = EntryTemplateDb {
+				id: template.id.clone(),
+				event: event.id.clone(),
+				name: template.name.clone(),
+				entry_type: template.entry_type.clone(),
+				description: template.description.clone(),
+			};
+			let tag_ids: Vec<String> = template.tags.iter().map(|tag| tag.id.clone()).collect();
+			let template_tags: Option<Vec<TagDb>> =
+				run_db_operation(&db_connection_pool, "updating an entry template", |db_connection| {
+					db_connection.transaction(|db_connection| {
+						if let Some(entry_type) = template_db.entry_type.as_ref() {
+							let matching_entry_types: Vec<AvailableEntryType> = available_entry_types_for_event::table
+								.filter(
+									available_entry_types_for_event::event_id
+										.eq(&event.id)
+										.and(available_entry_types_for_event::entry_type.eq(entry_type)),
+								)
+								.limit(1)
+								.load(db_connection)?;
+							if matching_entry_types.is_empty() {
+								return Ok(None);
+							}
+						}
+						let template_tags: Vec<TagDb> = tags::table
+							.filter(
+								tags::id
+									.eq_any(&tag_ids)
+									.and(tags::for_event.eq(&event.id))
+									.and(tags::deleted.eq(false)),
+							)
+							.load(db_connection)?;
+						if template_tags.len() != tag_ids.len() {
+							return Ok(None);
+						}
+
+						if new_template {
+							diesel::insert_into(entry_templates::table)
+								.values(&template_db)
+								.execute(db_connection)?;
+						} else {
+							let updated_count = diesel::update(entry_templates::table)
+								.filter(
+									entry_templates::id
+										.eq(&template_db.id)
+										.and(entry_templates::event.eq(&event.id)),
+								)
+								.set(&template_db)
+								.execute(db_connection)?;
+							if updated_count == 0 {
+								return Ok(None);
+							}
+							diesel::delete(entry_template_tags::table)
+								.filter(entry_template_tags::template.eq(&template_db.id))
+								.execute(db_connection)?;
+						}
+						let db_template_tags: Vec<EntryTemplateTag> = template_tags
+							.iter()
+							.map(|tag| EntryTemplateTag {
+								template: template_db.id.clone(),
+								tag: tag.id.clone(),
+							})
+							.collect();
+						diesel::insert_into(entry_template_tags::table)
+							.values(db_template_tags)
+							.execute(db_connection)?;
+						Ok(Some(template_tags))
 					})
-					.collect();
-				diesel::insert_into(tags::table)
-					.values(&new_event_tags)
-					.execute(db_connection)?;
+				})
+				.await?;
+			let Some(template_tags) = template_tags else {
+				return Err(HandlerError::InvalidData(String::from(
+					"The entry template refers to data that isn't part of this event.",
+				)));
+			};
+			template.tags = template_tags.into_iter().map(|tag| tag.into()).collect();
 
-				Ok(new_event_tags)
-			});
-			let added_tags: Vec<Tag> = match added_tags {
-				Ok(tags) => tags.into_iter().map(|tag| tag.into()).collect(),
-				Err(error) => {
-					tide::log::error!("Database error copying event tags: {}", error);
-					return Ok(());
-				}
+			vec![EventSubscriptionData::UpdateEntryTemplate(template)]
+		}
+		EventSubscriptionUpdate::DeleteEntryTemplate(template) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let deleted_count = run_db_operation(&db_connection_pool, "deleting an entry template", |db_connection| {
+				diesel::delete(entry_templates::table)
+					.filter(
+						entry_templates::id
+							.eq(&template.id)
+							.and(entry_templates::event.eq(&event.id)),
+					)
+					.execute(db_connection)
+			})
+			.await?;
+			if deleted_count == 0 {
+				// Another supervisor already deleted it
+				return Ok(());
+			}
+			vec![EventSubscriptionData::DeleteEntryTemplate(template)]
+		}
+		EventSubscriptionUpdate::UpdateRecurringEntry(mut recurring_entry) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			recurring_entry.description = recurring_entry.description.trim().to_string();
+			if recurring_entry.description.is_empty() {
+				return Err(HandlerError::InvalidData(String::from(
+					"Recurring entries must have a description.",
+				)));
+			}
+			if let Err(error) = event.check_description_length(&recurring_entry.description) {
+				return Err(HandlerError::InvalidData(error));
+			}
+			if recurring_entry.interval_minutes < 1 {
+				return Err(HandlerError::InvalidData(String::from(
+					"Recurring entries must repeat at least a minute apart.",
+				)));
+			}
+			recurring_entry.first_time = truncate_to_minute(recurring_entry.first_time);
+			if recurring_entry.last_time < recurring_entry.first_time {
+				return Err(HandlerError::InvalidData(String::from(
+					"Recurring entries can't stop before their first entry.",
+				)));
+			}
+			let new_recurring_entry = recurring_entry.id.is_empty();
+			if new_recurring_entry {
+				recurring_entry.id = cuid2::create_id();
+			}
+
+			// Changing a recurring entry restarts its schedule from now so that entries aren't created for times that
+			// have already passed
+			let recurring_entry_db = RecurringEntryDb {
+				id: recurring_entry.id.clone(),
+				event: event.id.clone(),
+				entry_type: recurring_entry.entry_type.clone(),
+				description: recurring_entry.description.clone(),
+				interval_minutes: recurring_entry.interval_minutes,
+				first_time: recurring_entry.first_time,
+				last_time: recurring_entry.last_time,
+				next_time: next_occurrence(recurring_entry.first_time, recurring_entry.interval_minutes, Utc::now()),
+				created_by: user.id.clone(),
 			};
+			let saved = run_db_operation(&db_connection_pool, "updating a recurring entry", |db_connection| {
+				db_connection.transaction(|db_connection| {
+					if let Some(entry_type) = recurring_entry_db.entry_type.as_ref() {
+						let matching_entry_types: Vec<AvailableEntryType> = available_entry_types_for_event::table
+							.filter(
+								available_entry_types_for_event::event_id
+									.eq(&event.id)
+									.and(available_entry_types_for_event::entry_type.eq(entry_type)),
+							)
+							.limit(1)
+							.load(db_connection)?;
+						if matching_entry_types.is_empty() {
+							return Ok(false);
+						}
+					}
 
-			added_tags.into_iter().map(EventSubscriptionData::UpdateTag).collect()
+					if new_recurring_entry {
+						diesel::insert_into(recurring_entries::table)
+							.values(&recurring_entry_db)
+							.execute(db_connection)?;
+					} else {
+						let updated_count = diesel::update(recurring_entries::table)
+							.filter(
+								recurring_entries::id
+									.eq(&recurring_entry_db.id)
+									.and(recurring_entries::event.eq(&event.id)),
+							)
+							.set(&recurring_entry_db)
+							.execute(db_connection)?;
+						if updated_count == 0 {
+							return Ok(false);
+						}
+					}
+					Ok(true)
+				})
+			})
+			.await?;
+			if !saved {
+				return Err(HandlerError::InvalidData(String::from(
+					"The recurring entry refers to data that isn't part of this event.",
+				)));
+			}
+
+			vec![EventSubscriptionData::UpdateRecurringEntry(recurring_entry)]
+		}
+		EventSubscriptionUpdate::DeleteRecurringEntry(recurring_entry) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let deleted_count = run_db_operation(&db_connection_pool, "deleting a recurring entry", |db_connection| {
+				diesel::delete(recurring_entries::table)
+					.filter(
+						recurring_entries::id
+							.eq(&recurring_entry.id)
+							.and(recurring_entries::event.eq(&event.id)),
+					)
+					.execute(db_connection)
+			})
+			.await?;
+			if deleted_count == 0 {
+				// Another supervisor already deleted it
+				return Ok(());
+			}
+			vec![EventSubscriptionData::DeleteRecurringEntry(recurring_entry)]
+		}
+		EventSubscriptionUpdate::SendBroadcast(message) => {
+			if *permission_level != Some(Permission::Supervisor) {
+				return Err(HandlerError::NotAllowed);
+			}
+			let message = message.trim().to_string();
+			if message.is_empty() {
+				return Err(HandlerError::InvalidData(String::from("Broadcasts can't be empty.")));
+			}
+			if message.chars().count() > MAX_BROADCAST_LENGTH {
+				return Err(HandlerError::InvalidData(format!(
+					"Broadcasts can't be longer than {} characters.",
+					MAX_BROADCAST_LENGTH
+				)));
+			}
+
+			let broadcast = EventBroadcast {
+				id: cuid2::create_id(),
+				sender: user.clone().into(),
+				message,
+				sent_at: Utc::now(),
+			};
+			vec![EventSubscriptionData::Broadcast(broadcast)]
+		}
+		EventSubscriptionUpdate::BeginEditingEntry(entry_id) => {
+			let lock =
+				subscription_manager
+					.lock()
+					.await
+					.lock_entry(&event.id, &entry_id, &user.clone().into(), connection_id);
+			match lock {
+				Some(lock) => vec![EventSubscriptionData::EntryLocked(lock)],
+				// Someone else is already editing the entry, and everyone viewing the event already knows who
+				None => Vec::new(),
+			}
+		}
+		EventSubscriptionUpdate::EndEditingEntry(entry_id) => {
+			let unlocked = subscription_manager
+				.lock()
+				.await
+				.unlock_entry(&event.id, &entry_id, connection_id);
+			if unlocked {
+				vec![EventSubscriptionData::EntryUnlocked(entry_id)]
+			} else {
+				Vec::new()
+			}
 		}
 	};
 
@@ -1296,32 +2441,256 @@ pub async fn handle_event_update(
 	Ok(())
 }
 
+/// Gets the error to send when a tag update refers to a tag that isn't available in the event being updated
+fn tag_not_in_event_error() -> HandlerError {
+	HandlerError::InvalidData(String::from("The tag isn't available in this event."))
+}
+
+/// Checks whether the event is currently archived. The event data sent by the client may be out of date, so we load it
+/// from the database.
+async fn event_is_archived(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	event: &Event,
+) -> Result<bool, HandlerError> {
+	let archived: bool = run_db_operation(db_connection_pool, "loading event archived state", |db_connection| {
+		events::table
+			.find(&event.id)
+			.select(events::archived)
+			.first(db_connection)
+	})
+	.await?;
+	Ok(archived)
+}
+
+/// Checks the current event settings to see whether tag removals need a second supervisor's approval. The event data
+/// sent by the client may be out of date, so we load it from the database.
+async fn event_requires_tag_deletion_approval(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	event: &Event,
+) -> Result<bool, HandlerError> {
+	let event_db: EventDb = run_db_operation(db_connection_pool, "loading event tag settings", |db_connection| {
+		events::table.find(&event.id).first(db_connection)
+	})
+	.await?;
+	Ok(event_db.require_tag_deletion_approval)
+}
+
+/// Records a tag removal or replacement to be carried out once another supervisor approves it
+async fn request_tag_action(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	event: &Event,
+	user: &SelfUserData,
+	tag: Tag,
+	replacement_tag: Option<Tag>,
+) -> Result<PendingTagAction, HandlerError> {
+	let pending_action = PendingTagActionDb {
+		id: cuid2::create_id(),
+		event: event.id.clone(),
+		tag: tag.id.clone(),
+		replacement_tag: replacement_tag.as_ref().map(|tag| tag.id.clone()),
+		requested_by: user.id.clone(),
+		requested_at: Utc::now(),
+	};
+	let tags_in_event = run_db_operation(db_connection_pool, "requesting a tag action", |db_connection| {
+		db_connection.transaction(|db_connection| {
+			let tag_ids: Vec<String> = [Some(pending_action.tag.clone()), pending_action.replacement_tag.clone()]
+				.into_iter()
+				.flatten()
+				.collect();
+			let available_tag_count: i64 = tags::table
+				.filter(
+					tags::id
+						.eq_any(&tag_ids)
+						.and(tags::for_event.eq(&event.id))
+						.and(tags::deleted.eq(false)),
+				)
+				.count()
+				.get_result(db_connection)?;
+			if available_tag_count != tag_ids.len() as i64 {
+				return Ok(false);
+			}
+			diesel::insert_into(pending_tag_actions::table)
+				.values(&pending_action)
+				.execute(db_connection)?;
+			Ok(true)
+		})
+	})
+	.await?;
+	if !tags_in_event {
+		return Err(tag_not_in_event_error());
+	}
+
+	Ok(PendingTagAction {
+		id: pending_action.id,
+		tag,
+		replacement_tag,
+		requested_by: user.clone().into(),
+		requested_at: pending_action.requested_at,
+	})
+}
+
+/// The changes made by removing a tag
+struct TagRemoval {
+	/// Entries that got the replacement tag in place of the removed tag
+	updated_entries: Vec<EventLogEntry>,
+	/// IDs of pending tag actions that involved the removed tag and so were cleared
+	cleared_pending_actions: Vec<String>,
+}
+
+/// Removes a tag from an event. If a replacement tag is given, entries with the removed tag get the replacement tag
+/// instead. Returns None if either tag isn't available in the event.
+fn remove_tag(
+	db_connection: &mut PgConnection,
+	event_id: &str,
+	tag_id: &str,
+	replacement_tag_id: Option<&str>,
+) -> QueryResult<Option<TagRemoval>> {
+	let original_tag: TagDb = tags::table.find(tag_id).first(db_connection)?;
+	if original_tag.for_event != event_id {
+		return Ok(None);
+	}
+
+	let mut updated_entries: Vec<EventLogEntry> = Vec::new();
+	if let Some(replacement_tag_id) = replacement_tag_id {
+		let replacement: TagDb = tags::table.find(replacement_tag_id).first(db_connection)?;
+		if replacement.for_event != event_id {
+			return Ok(None);
+		}
+
+		let log_entry_tags: Vec<EventLogTag> = event_log_tags::table
+			.filter(event_log_tags::tag.eq(tag_id))
+			.load(db_connection)?;
+		let entry_tags: Vec<EventLogTag> = log_entry_tags
+			.iter()
+			.map(|log_entry_tag| EventLogTag {
+				tag: replacement_tag_id.to_string(),
+				log_entry: log_entry_tag.log_entry.clone(),
+			})
+			.collect();
+		diesel::insert_into(event_log_tags::table)
+			.values(&entry_tags)
+			.on_conflict_do_nothing()
+			.execute(db_connection)?;
+		diesel::delete(event_log_tags::table)
+			.filter(event_log_tags::tag.eq(tag_id))
+			.execute(db_connection)?;
+
+		let log_entry_ids: Vec<String> = log_entry_tags
+			.into_iter()
+			.map(|tag_entry| tag_entry.log_entry)
+			.collect();
+		let affected_log_entries: Vec<EventLogEntryDb> = event_log::table
+			.filter(event_log::id.eq_any(log_entry_ids))
+			.load(db_connection)?;
+		for log_entry in affected_log_entries {
+			let tags = load_entry_tags(db_connection, &log_entry.id)?;
+			updated_entries.push(event_log_entry_from_db(db_connection, log_entry, tags)?);
+		}
+	}
+
+	diesel::update(tags::table)
+		.filter(tags::id.eq(tag_id))
+		.set(tags::deleted.eq(true))
+		.execute(db_connection)?;
+	diesel::delete(video_edit_state_tag_rules::table)
+		.filter(video_edit_state_tag_rules::tag.eq(tag_id))
+		.execute(db_connection)?;
+	diesel::delete(entry_template_tags::table)
+		.filter(entry_template_tags::tag.eq(tag_id))
+		.execute(db_connection)?;
+	let cleared_pending_actions: Vec<String> = diesel::delete(pending_tag_actions::table)
+		.filter(
+			pending_tag_actions::tag
+				.eq(tag_id)
+				.or(pending_tag_actions::replacement_tag.eq(tag_id)),
+		)
+		.returning(pending_tag_actions::id)
+		.get_results(db_connection)?;
+
+	Ok(Some(TagRemoval {
+		updated_entries,
+		cleared_pending_actions,
+	}))
+}
+
+/// Gets the messages to send to subscribers for a tag removal
+fn tag_removal_messages(tag: Tag, removal: TagRemoval, user: &SelfUserData) -> Vec<EventSubscriptionData> {
+	let mut messages: Vec<EventSubscriptionData> =
+		Vec::with_capacity(removal.updated_entries.len() + removal.cleared_pending_actions.len() + 1);
+	for log_entry in removal.updated_entries {
+		messages.push(EventSubscriptionData::UpdateLogEntry(
+			log_entry,
+			Some(user.clone().into()),
+		));
+	}
+	messages.push(EventSubscriptionData::RemoveTag(tag));
+	for action_id in removal.cleared_pending_actions {
+		messages.push(EventSubscriptionData::RemovePendingTagAction(action_id));
+	}
+	messages
+}
+
+/// Gets the video edit state rule to apply to an entry given the specified newly-added tags, if any rule applies. When
+/// multiple tags have rules, the rule for the tag with the lowest ID is used so that the result is consistent.
+pub fn video_edit_state_rule_for_tags(
+	db_connection: &mut PgConnection,
+	event_id: &str,
+	tag_ids: &[String],
+) -> QueryResult<Option<VideoEditStateTagRuleDb>> {
+	if tag_ids.is_empty() {
+		return Ok(None);
+	}
+	video_edit_state_tag_rules::table
+		.filter(
+			video_edit_state_tag_rules::event
+				.eq(event_id)
+				.and(video_edit_state_tag_rules::tag.eq_any(tag_ids)),
+		)
+		.order(video_edit_state_tag_rules::tag.asc())
+		.first(db_connection)
+		.optional()
+}
+
+/// Checks whether giving an entry the specified parent would make the entry its own ancestor
+fn parent_would_create_cycle(
+	db_connection: &mut PgConnection,
+	entry_id: &str,
+	parent_id: Option<&str>,
+) -> QueryResult<bool> {
+	let mut checked_entry_ids: HashSet<String> = HashSet::new();
+	let mut next_parent_id = parent_id.map(String::from);
+	while let Some(parent_id) = next_parent_id {
+		if parent_id == entry_id {
+			return Ok(true);
+		}
+		if !checked_entry_ids.insert(parent_id.clone()) {
+			break;
+		}
+		next_parent_id = event_log::table
+			.find(&parent_id)
+			.select(event_log::parent)
+			.first::<Option<String>>(db_connection)
+			.optional()?
+			.flatten();
+	}
+	Ok(false)
+}
+
+/// Updates a log entry and records the change in the entry's history. The record update function returns the updated
+/// entry along with the ID of the tag whose video edit state rule was applied by the update, if any.
 fn log_entry_change(
 	db_connection: &mut PgConnection,
-	record_update: impl FnOnce(&mut PgConnection) -> QueryResult<EventLogEntryDb>,
+	record_update: impl FnOnce(&mut PgConnection) -> QueryResult<(EventLogEntryDb, Option<String>)>,
 	update_user_id: String,
 ) -> QueryResult<EventLogEntry> {
 	db_connection.transaction(|db_connection| {
-		let log_entry = record_update(db_connection)?;
+		let (mut log_entry, applied_rule_tag) = record_update(db_connection)?;
 
-		let end_time = log_entry.end_time_data();
-
-		let tags: Vec<TagDb> = tags::table
-			.filter(
-				event_log_tags::table
-					.filter(
-						event_log_tags::tag
-							.eq(tags::id)
-							.and(event_log_tags::log_entry.eq(&log_entry.id)),
-					)
-					.count()
-					.single_value()
-					.gt(0),
-			)
-			.load(db_connection)?;
+		let tags = load_entry_tags(db_connection, &log_entry.id)?;
 
-		let history_entry =
+		let mut history_entry =
 			EventLogHistoryEntry::new_from_event_log_entry(&log_entry, Utc::now(), EditSource::User(update_user_id));
+		history_entry.video_edit_state_rule_tag = applied_rule_tag;
 		let history_entry_tags: Vec<EventLogHistoryTag> = tags
 			.iter()
 			.map(|tag| EventLogHistoryTag {
@@ -1329,41 +2698,170 @@ fn log_entry_change(
 				history_log_entry: history_entry.id.clone(),
 			})
 			.collect();
+		let history_id = history_entry.id.clone();
 		diesel::insert_into(event_log_history::table)
 			.values(history_entry)
 			.execute(db_connection)?;
 		diesel::insert_into(event_log_history_tags::table)
 			.values(history_entry_tags)
 			.execute(db_connection)?;
+		diesel::update(event_log::table.find(&log_entry.id))
+			.set(event_log::latest_history_id.eq(&history_id))
+			.execute(db_connection)?;
+		log_entry.latest_history_id = Some(history_id);
 
-		let tags: Vec<Tag> = tags.into_iter().map(|tag| tag.into()).collect();
-		let editor: Option<User> = match log_entry.editor {
-			Some(user_id) => Some(users::table.find(user_id).first(db_connection)?),
-			None => None,
-		};
-		let editor = editor.map(|editor| editor.into());
-
-		let log_entry = EventLogEntry {
-			id: log_entry.id,
-			start_time: Some(log_entry.start_time),
-			end_time,
-			entry_type: log_entry.entry_type,
-			description: log_entry.description,
-			media_links: log_entry.media_links.into_iter().flatten().collect(),
-			submitter_or_winner: log_entry.submitter_or_winner,
-			tags,
-			notes: log_entry.notes,
-			editor,
-			video_link: log_entry.video_link,
-			parent: log_entry.parent,
-			created_at: log_entry.created_at,
-			manual_sort_key: log_entry.manual_sort_key,
-			video_processing_state: log_entry.video_processing_state.into(),
-			video_errors: log_entry.video_errors,
-			poster_moment: log_entry.poster_moment,
-			video_edit_state: log_entry.video_edit_state.into(),
-			missing_giveaway_information: log_entry.missing_giveaway_information,
-		};
-		Ok(log_entry)
+		event_log_entry_from_db(db_connection, log_entry, tags)
 	})
 }
+
+/// Checks a log entry update for parts someone else changed since the revision the update was based on. The current
+/// entry should be locked by the caller. Returns None if the base revision isn't known or nothing conflicts.
+fn load_update_conflict(
+	db_connection: &mut PgConnection,
+	log_entry: &EventLogEntry,
+	modified_parts: &[ModifiedEventLogEntryParts],
+	base_history_id: &str,
+	current_entry: EventLogEntryDb,
+) -> QueryResult<Option<EntryUpdateConflict>> {
+	let base_revision: Option<EventLogHistoryEntry> = event_log_history::table
+		.filter(
+			event_log_history::id
+				.eq(base_history_id)
+				.and(event_log_history::log_entry.eq(&current_entry.id)),
+		)
+		.first(db_connection)
+		.optional()?;
+	let Some(base_revision) = base_revision else {
+		return Ok(None);
+	};
+	let base_tags: Vec<TagDb> = event_log_history_tags::table
+		.inner_join(tags::table)
+		.filter(event_log_history_tags::history_log_entry.eq(&base_revision.id))
+		.select(tags::all_columns)
+		.load(db_connection)?;
+	let base_editor: Option<User> = match base_revision.editor.as_ref() {
+		Some(editor) => Some(users::table.find(editor).first(db_connection)?),
+		None => None,
+	};
+
+	let current_tags = load_entry_tags(db_connection, &current_entry.id)?;
+	let current_entry = event_log_entry_from_db(db_connection, current_entry, current_tags)?;
+	let mut base_entry = revision_entry(base_revision, base_tags, base_editor.map(|editor| editor.into()));
+	// History doesn't keep custom field values, so they can't be checked for conflicts
+	base_entry.custom_fields = current_entry.custom_fields.clone();
+
+	let conflicting_parts = conflicting_entry_parts(modified_parts, &base_entry, log_entry, &current_entry);
+	if conflicting_parts.is_empty() {
+		return Ok(None);
+	}
+	Ok(Some(EntryUpdateConflict {
+		entry: log_entry.clone(),
+		base_entry,
+		conflicting_parts,
+	}))
+}
+
+/// Records the deletion of a log entry in the entry's history
+fn record_entry_deletion(
+	db_connection: &mut PgConnection,
+	deleted_entry: &EventLogEntryDb,
+	deleted_entry_tags: &[TagDb],
+	deleting_user_id: &str,
+) -> QueryResult<()> {
+	let history_entry = EventLogHistoryEntry::new_from_event_log_entry(
+		deleted_entry,
+		Utc::now(),
+		EditSource::User(deleting_user_id.to_string()),
+	);
+	let history_entry_tags: Vec<EventLogHistoryTag> = deleted_entry_tags
+		.iter()
+		.map(|tag| EventLogHistoryTag {
+			tag: tag.id.clone(),
+			history_log_entry: history_entry.id.clone(),
+		})
+		.collect();
+	let history_id = history_entry.id.clone();
+	diesel::insert_into(event_log_history::table)
+		.values(history_entry)
+		.execute(db_connection)?;
+	diesel::insert_into(event_log_history_tags::table)
+		.values(history_entry_tags)
+		.execute(db_connection)?;
+	diesel::update(event_log::table.find(&deleted_entry.id))
+		.set(event_log::latest_history_id.eq(&history_id))
+		.execute(db_connection)?;
+	Ok(())
+}
+
+/// Loads the tags applied to a log entry
+fn load_entry_tags(db_connection: &mut PgConnection, log_entry_id: &str) -> QueryResult<Vec<TagDb>> {
+	tags::table
+		.filter(
+			event_log_tags::table
+				.filter(
+					event_log_tags::tag
+						.eq(tags::id)
+						.and(event_log_tags::log_entry.eq(log_entry_id)),
+				)
+				.count()
+				.single_value()
+				.gt(0),
+		)
+		.load(db_connection)
+}
+
+/// Notifies other users affected by a change a user made to a log entry. Users aren't notified of their own changes.
+///
+/// The subscription manager is locked to send notifications, so it must not already be locked by the caller.
+async fn send_entry_change_notifications(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	event: &Event,
+	log_entry: &EventLogEntry,
+	modified_parts: &[ModifiedEventLogEntryParts],
+	user: &SelfUserData,
+) {
+	if modified_parts.contains(&ModifiedEventLogEntryParts::Editor) {
+		if let Some(editor) = log_entry.editor.as_ref().filter(|editor| editor.id != user.id) {
+			let details = UserNotificationDetails::EditorAssigned {
+				entry_id: log_entry.id.clone(),
+				entry_description: log_entry.description.clone(),
+			};
+			send_user_notification(db_connection_pool, subscription_manager, &editor.id, event, details).await;
+		}
+	}
+
+	if modified_parts.contains(&ModifiedEventLogEntryParts::MissingGiveawayInfo)
+		&& log_entry.missing_giveaway_information
+	{
+		if let Some(creator) = log_entry.created_by.as_ref().filter(|creator| creator.id != user.id) {
+			let details = UserNotificationDetails::MissingGiveawayInformation {
+				entry_id: log_entry.id.clone(),
+				entry_description: log_entry.description.clone(),
+			};
+			send_user_notification(db_connection_pool, subscription_manager, &creator.id, event, details).await;
+		}
+	}
+}
+
+/// Converts a log entry from the database into the form sent to clients
+fn event_log_entry_from_db(
+	db_connection: &mut PgConnection,
+	log_entry: EventLogEntryDb,
+	tags: Vec<TagDb>,
+) -> QueryResult<EventLogEntry> {
+	let tags: Vec<Tag> = tags.into_iter().map(|tag| tag.into()).collect();
+	let editor: Option<User> = match log_entry.editor.as_ref() {
+		Some(user_id) => Some(users::table.find(user_id).first(db_connection)?),
+		None => None,
+	};
+	let editor = editor.map(|editor| editor.into());
+	let created_by: Option<User> = match log_entry.created_by.as_ref() {
+		Some(user_id) => Some(users::table.find(user_id).first(db_connection)?),
+		None => None,
+	};
+	let created_by = created_by.map(|creator| creator.into());
+	let custom_fields = entry_custom_field_values(db_connection, &log_entry.id)?;
+
+	Ok(log_entry.into_sync_entry(tags, editor, created_by, custom_fields))
+}
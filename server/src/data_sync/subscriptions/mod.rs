@@ -4,36 +4,69 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub mod admin_access_requests;
 pub mod admin_applications;
+pub mod admin_audit_log;
+pub mod admin_custom_fields;
 pub mod admin_editors;
 pub mod admin_entry_types;
 pub mod admin_events;
+pub mod admin_jobs;
 pub mod admin_pages;
 pub mod admin_permission_groups;
+pub mod admin_rejected_updates;
+pub mod admin_share_links;
 pub mod admin_tabs;
+pub mod admin_user_event_permissions;
 pub mod admin_users;
+pub mod admin_webhooks;
+pub mod editor_workload;
 pub mod events;
+pub mod user_notifications;
 
-use crate::data_sync::{ConnectionUpdate, HandleConnectionError};
-use crate::database::log_lost_db_connection;
+use crate::data_sync::user::load_event_permission;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError};
+use crate::models::Permission;
 use async_std::channel::Sender;
-use r2d2::Error as R2D2Error;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
 use stream_log_shared::messages::subscriptions::{SubscriptionFailureInfo, SubscriptionType};
 use stream_log_shared::messages::{DataError, FromServerMessage};
 
-/// Logs the database connection lost message to the web server log and sends the appropriate response to the client.
-async fn send_lost_db_connection_subscription_response(
-	error: R2D2Error,
+/// Runs a database operation to load data for a subscription, retrying temporary failures as [run_db_operation] does.
+/// If the data can't be loaded, the error is logged, the client is told the subscription failed, and `None` is
+/// returned.
+async fn load_subscription_data<T>(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
 	conn_update_tx: &Sender<ConnectionUpdate>,
 	subscription_type: SubscriptionType,
-) -> Result<(), HandleConnectionError> {
-	log_lost_db_connection(error);
-	let message = FromServerMessage::SubscriptionFailure(
-		subscription_type,
-		SubscriptionFailureInfo::Error(DataError::DatabaseError),
-	);
-	conn_update_tx
-		.send(ConnectionUpdate::SendData(Box::new(message)))
-		.await?;
-	Ok(())
+	action: &'static str,
+	operation: impl FnMut(&mut PgConnection) -> QueryResult<T>,
+) -> Result<Option<T>, HandleConnectionError> {
+	match run_db_operation(db_connection_pool, action, operation).await {
+		Ok(data) => Ok(Some(data)),
+		Err(error) => {
+			tide::log::error!("{}", error);
+			let message = FromServerMessage::SubscriptionFailure(
+				subscription_type,
+				SubscriptionFailureInfo::Error(DataError::DatabaseError),
+			);
+			conn_update_tx
+				.send(ConnectionUpdate::SendData(Box::new(message)))
+				.await?;
+			Ok(None)
+		}
+	}
+}
+
+/// Checks whether a user has any access to an event through any of their permission groups or a permission granted
+/// to them directly
+fn user_has_event_access(db_connection: &mut PgConnection, user_id: &str, event_id: &str) -> QueryResult<bool> {
+	Ok(load_event_permission(db_connection, user_id, event_id)?.is_some())
+}
+
+/// Checks whether a user has supervisor access to an event through any of their permission groups or a permission
+/// granted to them directly
+fn user_is_event_supervisor(db_connection: &mut PgConnection, user_id: &str, event_id: &str) -> QueryResult<bool> {
+	Ok(load_event_permission(db_connection, user_id, event_id)? == Some(Permission::Supervisor))
 }
@@ -0,0 +1,168 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::load_subscription_data;
+use crate::data_sync::{run_db_operation, ConnectionUpdate, HandleConnectionError, HandlerError, SubscriptionManager};
+use crate::models::{Event as EventDb, Notification};
+use crate::schema::{events, notifications};
+use async_std::channel::Sender;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::notifications::{
+	UserNotification, UserNotificationData, UserNotificationDetails, UserNotificationUpdate,
+};
+use stream_log_shared::messages::subscriptions::{
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionType,
+};
+use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+/// The number of most recent notifications sent to users when they subscribe
+const INITIAL_NOTIFICATION_COUNT: i64 = 100;
+
+pub async fn subscribe_to_user_notifications(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: Sender<ConnectionUpdate>,
+	connection_id: &str,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) -> Result<(), HandleConnectionError> {
+	let notifications: Option<Vec<(Notification, EventDb)>> = load_subscription_data(
+		&db_connection_pool,
+		&conn_update_tx,
+		SubscriptionType::UserNotifications,
+		"retrieving notifications for a user",
+		|db_connection| {
+			notifications::table
+				.inner_join(events::table)
+				.filter(notifications::user_id.eq(&user.id))
+				.order(notifications::created_at.desc())
+				.limit(INITIAL_NOTIFICATION_COUNT)
+				.load(db_connection)
+		},
+	)
+	.await?;
+	let Some(notifications) = notifications else {
+		return Ok(());
+	};
+	let notifications: Vec<UserNotification> = notifications
+		.into_iter()
+		.map(|(notification, event)| notification.into_user_notification(event.into()))
+		.collect();
+
+	subscription_manager
+		.lock()
+		.await
+		.add_user_notifications_subscription(&user.id, connection_id, conn_update_tx.clone())
+		.await;
+
+	let message = FromServerMessage::InitialSubscriptionLoad(Box::new(InitialSubscriptionLoadData::UserNotifications(
+		notifications,
+	)));
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+pub async fn handle_user_notifications_message(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update_message: UserNotificationUpdate,
+) -> Result<(), HandlerError> {
+	let read_ids: Vec<String> = match update_message {
+		UserNotificationUpdate::MarkRead(notification_ids) => {
+			run_db_operation(&db_connection_pool, "marking notifications read", |db_connection| {
+				diesel::update(notifications::table)
+					.filter(
+						notifications::id
+							.eq_any(&notification_ids)
+							.and(notifications::user_id.eq(&user.id))
+							.and(notifications::is_read.eq(false)),
+					)
+					.set(notifications::is_read.eq(true))
+					.returning(notifications::id)
+					.get_results(db_connection)
+			})
+			.await?
+		}
+		UserNotificationUpdate::MarkAllRead => {
+			run_db_operation(&db_connection_pool, "marking all notifications read", |db_connection| {
+				diesel::update(notifications::table)
+					.filter(
+						notifications::user_id
+							.eq(&user.id)
+							.and(notifications::is_read.eq(false)),
+					)
+					.set(notifications::is_read.eq(true))
+					.returning(notifications::id)
+					.get_results(db_connection)
+			})
+			.await?
+		}
+	};
+
+	if read_ids.is_empty() {
+		return Ok(());
+	}
+
+	// The user may have the site open in several places, so all of their connections are told what was read
+	let message = SubscriptionData::UserNotificationsUpdate(UserNotificationData::MarkedRead(read_ids));
+	let send_result = subscription_manager
+		.lock()
+		.await
+		.broadcast_user_notifications_message(&user.id, message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to broadcast read notifications: {}", error);
+	}
+
+	Ok(())
+}
+
+/// Stores a notification for a user and sends it to any of the user's connections subscribed to their notifications.
+/// Failures are logged rather than returned, since they shouldn't affect the change that caused the notification.
+///
+/// The subscription manager is locked to send the notification, so it must not already be locked by the caller.
+pub async fn send_user_notification(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	user_id: &str,
+	event: &Event,
+	details: UserNotificationDetails,
+) {
+	let notification = Notification::new(user_id.to_string(), event.id.clone(), &details);
+
+	let insert_result: Result<Notification, HandlerError> =
+		run_db_operation(db_connection_pool, "storing a notification", |db_connection| {
+			diesel::insert_into(notifications::table)
+				.values(&notification)
+				.get_result(db_connection)
+		})
+		.await;
+	let notification = match insert_result {
+		Ok(notification) => notification.into_user_notification(event.clone()),
+		Err(error) => {
+			tide::log::error!("Failed to notify user {}: {}", user_id, error);
+			return;
+		}
+	};
+
+	let message =
+		SubscriptionData::UserNotificationsUpdate(UserNotificationData::NewNotification(Box::new(notification)));
+	let send_result = subscription_manager
+		.lock()
+		.await
+		.broadcast_user_notifications_message(user_id, message)
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send a notification to user {}: {}", user_id, error);
+	}
+}
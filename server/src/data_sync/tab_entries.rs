@@ -0,0 +1,174 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::custom_fields::custom_field_values_by_entry;
+use super::{run_db_operation, HandlerError};
+use crate::models::{EventLogEntry as EventLogEntryDb, EventLogTab as EventLogTabDb, Permission, Tag as TagDb, User};
+use crate::schema::{event_log, event_log_tabs, event_log_tags, tags, users};
+use async_std::channel::Sender;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+use stream_log_shared::messages::tags::Tag;
+use stream_log_shared::messages::user::PublicUserData;
+use stream_log_shared::messages::FromServerMessage;
+
+pub async fn handle_tab_entries_request(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	event_permission_cache: &HashMap<Event, Option<Permission>>,
+	event_id: String,
+	tab_id: String,
+) -> Result<(), HandlerError> {
+	// Subscribing to the event adds it to the permission cache, so this also checks that the user loaded the event
+	let event = event_permission_cache
+		.iter()
+		.find(|(event, permission)| event.id == event_id && permission.is_some())
+		.map(|(event, _)| event.clone());
+	let Some(event) = event else {
+		return Err(HandlerError::NotAllowed);
+	};
+
+	let tabs: Vec<EventLogTabDb> = run_db_operation(&db_connection_pool, "loading event log tabs", |db_connection| {
+		event_log_tabs::table
+			.filter(event_log_tabs::event.eq(&event_id))
+			.order(event_log_tabs::start_time.asc())
+			.load(db_connection)
+	})
+	.await?;
+	let (range_start, range_end) = if tab_id.is_empty() {
+		(None, tabs.first().map(|tab| tab.start_time))
+	} else {
+		let Some(tab_index) = tabs.iter().position(|tab| tab.id == tab_id) else {
+			return Err(HandlerError::InvalidData(String::from("The tab doesn't exist.")));
+		};
+		(
+			Some(tabs[tab_index].start_time),
+			tabs.get(tab_index + 1).map(|tab| tab.start_time),
+		)
+	};
+
+	let entries: Vec<EventLogEntry> =
+		run_db_operation(&db_connection_pool, "loading the entries of a tab", |db_connection| {
+			db_connection.transaction(|db_connection| {
+				let log_entries: Vec<EventLogEntryDb> = event_log::table
+					.filter(event_log::event.eq(&event_id).and(event_log::deleted_by.is_null()))
+					.order((
+						event_log::start_time.asc(),
+						event_log::manual_sort_key.asc().nulls_last(),
+						event_log::created_at.asc(),
+					))
+					.load(db_connection)?;
+				let tab_start_times = top_level_start_times(&log_entries);
+				let log_entries: Vec<EventLogEntryDb> = log_entries
+					.into_iter()
+					.filter(|entry| {
+						let start_time = tab_start_times[&entry.id];
+						range_start.map(|range_start| start_time >= range_start).unwrap_or(true)
+							&& range_end.map(|range_end| start_time < range_end).unwrap_or(true)
+					})
+					.collect();
+
+				event_log_entries_from_db(db_connection, &event_id, log_entries)
+			})
+		})
+		.await?;
+
+	let message = FromServerMessage::SubscriptionMessage(
+		Box::new(SubscriptionData::EventUpdate(
+			event,
+			Box::new(EventSubscriptionData::TabEntries(tab_id, entries)),
+		)),
+		None,
+	);
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+
+	Ok(())
+}
+
+/// Gets the start time of the top-level entry each entry is under. Child entries are shown with their top-level entry,
+/// so this is the time that determines which tab an entry is in.
+pub fn top_level_start_times(entries: &[EventLogEntryDb]) -> HashMap<String, DateTime<Utc>> {
+	let entries_by_id: HashMap<&str, &EventLogEntryDb> =
+		entries.iter().map(|entry| (entry.id.as_str(), entry)).collect();
+	let mut start_times: HashMap<String, DateTime<Utc>> = HashMap::with_capacity(entries.len());
+	for entry in entries.iter() {
+		let mut top_level_entry = entry;
+		// Entries can't be nested more deeply than there are entries, so this limit only stops a parent loop
+		for _ in 0..entries.len() {
+			let parent = top_level_entry
+				.parent
+				.as_ref()
+				.and_then(|parent_id| entries_by_id.get(parent_id.as_str()));
+			match parent {
+				Some(parent) => top_level_entry = parent,
+				None => break,
+			}
+		}
+		start_times.insert(entry.id.clone(), top_level_entry.start_time);
+	}
+	start_times
+}
+
+/// Converts log entries from the database into the form sent to clients, loading their tags, custom field values, and
+/// users together
+pub fn event_log_entries_from_db(
+	db_connection: &mut PgConnection,
+	event_id: &str,
+	log_entries: Vec<EventLogEntryDb>,
+) -> QueryResult<Vec<EventLogEntry>> {
+	let log_entry_ids: Vec<String> = log_entries.iter().map(|entry| entry.id.clone()).collect();
+	let entry_tags: Vec<(String, TagDb)> = event_log_tags::table
+		.inner_join(tags::table)
+		.filter(
+			event_log_tags::log_entry
+				.eq_any(&log_entry_ids)
+				.and(tags::for_event.eq(event_id))
+				.and(tags::deleted.eq(false)),
+		)
+		.select((event_log_tags::log_entry, tags::all_columns))
+		.load(db_connection)?;
+	let mut tags_by_entry: HashMap<String, Vec<Tag>> = HashMap::new();
+	for (entry_id, tag) in entry_tags {
+		tags_by_entry.entry(entry_id).or_default().push(tag.into());
+	}
+	let mut custom_field_values = custom_field_values_by_entry(db_connection, &log_entry_ids)?;
+
+	let user_ids: Vec<String> = log_entries
+		.iter()
+		.flat_map(|entry| [entry.editor.clone(), entry.created_by.clone()])
+		.flatten()
+		.collect();
+	let users: Vec<User> = users::table.filter(users::id.eq_any(&user_ids)).load(db_connection)?;
+	let users_by_id: HashMap<String, PublicUserData> =
+		users.into_iter().map(|user| (user.id.clone(), user.into())).collect();
+
+	let entries = log_entries
+		.into_iter()
+		.map(|log_entry| {
+			let tags = tags_by_entry.remove(&log_entry.id).unwrap_or_default();
+			let editor = log_entry
+				.editor
+				.as_ref()
+				.and_then(|user_id| users_by_id.get(user_id).cloned());
+			let created_by = log_entry
+				.created_by
+				.as_ref()
+				.and_then(|user_id| users_by_id.get(user_id).cloned());
+			let custom_fields = custom_field_values.remove(&log_entry.id).unwrap_or_default();
+			log_entry.into_sync_entry(tags, editor, created_by, custom_fields)
+		})
+		.collect();
+	Ok(entries)
+}
@@ -4,12 +4,129 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::models::Permission;
+use crate::models::{Event as EventDb, Permission, PermissionEvent, UserEventPermission};
+use crate::schema::{events, permission_events, user_event_permissions, user_permissions};
+use diesel::prelude::*;
+use std::collections::HashMap;
 use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::hotkeys::HotkeyBinding;
 use stream_log_shared::messages::user::SelfUserData;
+use stream_log_shared::messages::view_preferences::FilterPreset;
 
 #[derive(Clone)]
 pub enum UserDataUpdate {
 	User(SelfUserData),
 	EventPermissions(Event, Option<Permission>),
+	Hotkeys(Vec<HotkeyBinding>),
+	FilterPresets(Vec<FilterPreset>),
+}
+
+/// Loads the events the user can access, either through their permission groups or through permissions granted to
+/// them directly, along with their permission level for each
+pub fn load_event_permissions(
+	db_connection: &mut PgConnection,
+	user_id: &str,
+) -> QueryResult<HashMap<Event, Option<Permission>>> {
+	let permission_events: Vec<PermissionEvent> = permission_events::table
+		.filter(
+			permission_events::permission_group.eq_any(
+				user_permissions::table
+					.filter(user_permissions::user_id.eq(user_id))
+					.select(user_permissions::permission_group),
+			),
+		)
+		.load(db_connection)?;
+	let direct_permissions: Vec<UserEventPermission> = user_event_permissions::table
+		.filter(user_event_permissions::user_id.eq(user_id))
+		.load(db_connection)?;
+
+	let mut event_levels: HashMap<String, Vec<Permission>> = HashMap::new();
+	for permission_event in permission_events {
+		event_levels
+			.entry(permission_event.event)
+			.or_default()
+			.push(permission_event.level);
+	}
+	for direct_permission in direct_permissions {
+		event_levels
+			.entry(direct_permission.event)
+			.or_default()
+			.push(direct_permission.level);
+	}
+
+	let event_ids: Vec<String> = event_levels.keys().cloned().collect();
+	let events: Vec<EventDb> = events::table
+		.filter(events::id.eq_any(&event_ids))
+		.load(db_connection)?;
+	let events: HashMap<String, Event> = events
+		.into_iter()
+		.map(|event| (event.id.clone(), event.into()))
+		.collect();
+
+	let mut available_events: HashMap<Event, Option<Permission>> = HashMap::new();
+	for (event_id, levels) in event_levels {
+		// We can expect the events we found to remain in the database, as nothing should remove them.
+		let event = events.get(&event_id).unwrap().clone();
+		available_events.insert(event, Permission::highest(levels));
+	}
+	Ok(available_events)
+}
+
+/// Loads the user's permission level for the event, combining their permission groups and any permission granted to
+/// them directly
+pub fn load_event_permission(
+	db_connection: &mut PgConnection,
+	user_id: &str,
+	event_id: &str,
+) -> QueryResult<Option<Permission>> {
+	let mut levels: Vec<Permission> = permission_events::table
+		.inner_join(
+			user_permissions::table.on(user_permissions::permission_group.eq(permission_events::permission_group)),
+		)
+		.filter(
+			permission_events::event
+				.eq(event_id)
+				.and(user_permissions::user_id.eq(user_id)),
+		)
+		.select(permission_events::level)
+		.load(db_connection)?;
+	let direct_level: Option<Permission> = user_event_permissions::table
+		.find((user_id, event_id))
+		.select(user_event_permissions::level)
+		.first(db_connection)
+		.optional()?;
+	levels.extend(direct_level);
+	Ok(Permission::highest(levels))
+}
+
+/// Loads the IDs of the users who have one of the given permission levels for the event, either through their
+/// permission groups or through permissions granted to them directly
+pub fn load_event_user_ids_with_permission(
+	db_connection: &mut PgConnection,
+	event_id: &str,
+	levels: &[Permission],
+) -> QueryResult<Vec<String>> {
+	let mut user_ids: Vec<String> = user_permissions::table
+		.inner_join(
+			permission_events::table.on(permission_events::permission_group.eq(user_permissions::permission_group)),
+		)
+		.filter(
+			permission_events::event
+				.eq(event_id)
+				.and(permission_events::level.eq_any(levels)),
+		)
+		.select(user_permissions::user_id)
+		.load(db_connection)?;
+	let direct_user_ids: Vec<String> = user_event_permissions::table
+		.filter(
+			user_event_permissions::event
+				.eq(event_id)
+				.and(user_event_permissions::level.eq_any(levels)),
+		)
+		.select(user_event_permissions::user_id)
+		.load(db_connection)?;
+	user_ids.extend(direct_user_ids);
+	user_ids.sort_unstable();
+	user_ids.dedup();
+	Ok(user_ids)
 }
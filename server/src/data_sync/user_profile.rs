@@ -5,35 +5,36 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::user::UserDataUpdate;
-use super::{HandleConnectionError, SubscriptionManager};
-use crate::schema::users;
+use super::{run_db_operation, HandlerError, SubscriptionManager};
+use crate::avatars::avatar_content_type;
+use crate::models::{User, UserAvatar, UserHotkey, UserViewPreference, UsernameChange};
+use crate::schema::{user_avatars, user_hotkeys, user_view_preferences, username_changes, users};
 use async_std::sync::{Arc, Mutex};
+use base64::engine::general_purpose::STANDARD as base64_engine;
+use base64::Engine;
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::dsl::max;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
-use stream_log_shared::messages::subscriptions::SubscriptionData;
-use stream_log_shared::messages::user::{SelfUserData, UpdateUser};
+use std::collections::HashSet;
+use stream_log_shared::messages::hotkeys::{effective_hotkeys, hotkey_conflicts, HotkeyBinding};
+use stream_log_shared::messages::notifications::NotificationPreferences;
+use stream_log_shared::messages::time_display::TimeDisplay;
+use stream_log_shared::messages::user::{SelfUserData, UpdateUser, AVATAR_SIZE_LIMIT, USERNAME_CHANGE_COOLDOWN_DAYS};
+use stream_log_shared::messages::user_register::USERNAME_LENGTH_LIMIT;
+use stream_log_shared::messages::view_preferences::{FilterPreset, FilterPresetUpdate, MAX_FILTER_PRESET_NAME_LENGTH};
 
 pub async fn handle_profile_update(
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	user: &SelfUserData,
 	subscription_manager: Arc<Mutex<SubscriptionManager>>,
 	update_data: UpdateUser,
-) -> Result<(), HandleConnectionError> {
+) -> Result<(), HandlerError> {
 	let red: i32 = update_data.color.r.into();
 	let green: i32 = update_data.color.g.into();
 	let blue: i32 = update_data.color.b.into();
 
-	let update_result = {
-		let mut db_connection = match db_connection_pool.get() {
-			Ok(connection) => connection,
-			Err(error) => {
-				tide::log::error!(
-					"A database connection error occurred updating a user profile: {}",
-					error
-				);
-				return Ok(());
-			}
-		};
+	run_db_operation(&db_connection_pool, "updating a user profile", |db_connection| {
 		diesel::update(users::table.filter(users::id.eq(&user.id)))
 			.set((
 				users::color_red.eq(red),
@@ -41,12 +42,9 @@ pub async fn handle_profile_update(
 				users::color_blue.eq(blue),
 				users::use_spell_check.eq(update_data.use_spell_check),
 			))
-			.execute(&mut *db_connection)
-	};
-	if let Err(error) = update_result {
-		tide::log::error!("Database error updating a user profile: {}", error);
-		return Err(HandleConnectionError::ConnectionClosed);
-	}
+			.execute(db_connection)
+	})
+	.await?;
 
 	let mut subscription_manager = subscription_manager.lock().await;
 	let mut new_user = user.clone();
@@ -56,11 +54,450 @@ pub async fn handle_profile_update(
 	let user_update = UserDataUpdate::User(new_user.clone());
 	subscription_manager.send_message_to_user(&user.id, user_update).await;
 
-	let admin_message = SubscriptionData::AdminUsersUpdate(new_user);
-	let send_result = subscription_manager.broadcast_admin_user_message(admin_message).await;
+	let send_result = subscription_manager.broadcast_admin_user_message(new_user).await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
+	}
+
+	Ok(())
+}
+
+pub async fn handle_username_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	new_username: String,
+) -> Result<(), HandlerError> {
+	let new_username = new_username.trim().to_string();
+	if new_username.is_empty() {
+		return Err(HandlerError::InvalidData(String::from("Usernames can't be empty.")));
+	}
+	if new_username.len() > USERNAME_LENGTH_LIMIT {
+		return Err(HandlerError::InvalidData(format!(
+			"Usernames can't be longer than {} characters.",
+			USERNAME_LENGTH_LIMIT
+		)));
+	}
+	if new_username == user.username {
+		return Ok(());
+	}
+
+	let (last_change_time, name_in_use): (Option<DateTime<Utc>>, bool) =
+		run_db_operation(&db_connection_pool, "checking a username change", |db_connection| {
+			// Changes made by administrators don't count toward the cooldown
+			let last_change_time: Option<DateTime<Utc>> = username_changes::table
+				.filter(
+					username_changes::user_id
+						.eq(&user.id)
+						.and(username_changes::changed_by.eq(&user.id)),
+				)
+				.select(max(username_changes::change_time))
+				.first(db_connection)?;
+			let name_in_use: bool =
+				diesel::select(diesel::dsl::exists(users::table.filter(users::name.eq(&new_username))))
+					.get_result(db_connection)?;
+			Ok((last_change_time, name_in_use))
+		})
+		.await?;
+	if let Some(last_change_time) = last_change_time {
+		let next_change_time = last_change_time + TimeDelta::days(USERNAME_CHANGE_COOLDOWN_DAYS);
+		if next_change_time > Utc::now() {
+			return Err(HandlerError::InvalidData(format!(
+				"You can't change your username again until {}.",
+				next_change_time.format("%Y-%m-%d %H:%M UTC")
+			)));
+		}
+	}
+	if name_in_use {
+		return Err(HandlerError::InvalidData(format!(
+			"The username \"{}\" is already taken.",
+			new_username
+		)));
+	}
+
+	let updated_user: User = run_db_operation(&db_connection_pool, "changing a username", |db_connection| {
+		db_connection.transaction(|db_connection| {
+			let updated_user: User = diesel::update(users::table.filter(users::id.eq(&user.id)))
+				.set(users::name.eq(&new_username))
+				.get_result(db_connection)?;
+			let username_change = UsernameChange {
+				id: cuid2::create_id(),
+				user_id: user.id.clone(),
+				changed_by: user.id.clone(),
+				change_time: Utc::now(),
+				old_name: user.username.clone(),
+				new_name: new_username.clone(),
+			};
+			diesel::insert_into(username_changes::table)
+				.values(username_change)
+				.execute(db_connection)?;
+			Ok(updated_user)
+		})
+	})
+	.await?;
+	let new_user: SelfUserData = updated_user.into();
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let user_update = UserDataUpdate::User(new_user.clone());
+	subscription_manager.send_message_to_user(&user.id, user_update).await;
+
+	let send_result = subscription_manager
+		.broadcast_admin_user_message(new_user.clone())
+		.await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
+	}
+
+	subscription_manager.update_public_user(&new_user.into()).await;
+
+	Ok(())
+}
+
+pub async fn handle_avatar_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	image: Option<String>,
+) -> Result<(), HandlerError> {
+	let avatar = match image {
+		Some(image) => {
+			let Ok(image) = base64_engine.decode(image) else {
+				return Err(HandlerError::InvalidData(String::from(
+					"The avatar image couldn't be read.",
+				)));
+			};
+			if image.len() > AVATAR_SIZE_LIMIT {
+				return Err(HandlerError::InvalidData(format!(
+					"Avatar images can't be larger than {} KiB.",
+					AVATAR_SIZE_LIMIT / 1024
+				)));
+			}
+			let Some(content_type) = avatar_content_type(&image) else {
+				return Err(HandlerError::InvalidData(String::from(
+					"Avatars must be PNG, JPEG, GIF, or WebP images.",
+				)));
+			};
+			Some(UserAvatar {
+				user_id: user.id.clone(),
+				content_type: content_type.to_string(),
+				image,
+			})
+		}
+		None => None,
+	};
+
+	let updated_user: User = run_db_operation(&db_connection_pool, "updating a user avatar", |db_connection| {
+		db_connection.transaction(|db_connection| {
+			let avatar_updated_at = match avatar.as_ref() {
+				Some(avatar) => {
+					diesel::insert_into(user_avatars::table)
+						.values(avatar)
+						.on_conflict(user_avatars::user_id)
+						.do_update()
+						.set((
+							user_avatars::content_type.eq(&avatar.content_type),
+							user_avatars::image.eq(&avatar.image),
+						))
+						.execute(db_connection)?;
+					Some(Utc::now())
+				}
+				None => {
+					diesel::delete(user_avatars::table.filter(user_avatars::user_id.eq(&user.id)))
+						.execute(db_connection)?;
+					None
+				}
+			};
+			diesel::update(users::table.filter(users::id.eq(&user.id)))
+				.set(users::avatar_updated_at.eq(avatar_updated_at))
+				.get_result(db_connection)
+		})
+	})
+	.await?;
+	let new_user: SelfUserData = updated_user.into();
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let user_update = UserDataUpdate::User(new_user.clone());
+	subscription_manager.send_message_to_user(&user.id, user_update).await;
+
+	let send_result = subscription_manager
+		.broadcast_admin_user_message(new_user.clone())
+		.await;
 	if let Err(error) = send_result {
 		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
 	}
 
+	subscription_manager.update_public_user(&new_user.into()).await;
+
+	Ok(())
+}
+
+pub async fn handle_guided_tour_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	completed: bool,
+) -> Result<(), HandlerError> {
+	run_db_operation(&db_connection_pool, "updating guided tour state", |db_connection| {
+		diesel::update(users::table.filter(users::id.eq(&user.id)))
+			.set(users::completed_guided_tour.eq(completed))
+			.execute(db_connection)
+	})
+	.await?;
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let mut new_user = user.clone();
+	new_user.completed_guided_tour = completed;
+
+	let user_update = UserDataUpdate::User(new_user.clone());
+	subscription_manager.send_message_to_user(&user.id, user_update).await;
+
+	let send_result = subscription_manager.broadcast_admin_user_message(new_user).await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
+	}
+
+	Ok(())
+}
+
+pub async fn handle_notification_preferences_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	preferences: NotificationPreferences,
+) -> Result<(), HandlerError> {
+	run_db_operation(
+		&db_connection_pool,
+		"updating notification preferences",
+		|db_connection| {
+			diesel::update(users::table.filter(users::id.eq(&user.id)))
+				.set((
+					users::editor_assigned_sound.eq(preferences.editor_assigned_sound),
+					users::editor_assigned_desktop.eq(preferences.editor_assigned_desktop),
+					users::typing_in_edited_entry_sound.eq(preferences.typing_in_edited_entry_sound),
+					users::typing_in_edited_entry_desktop.eq(preferences.typing_in_edited_entry_desktop),
+					users::other_notifications_sound.eq(preferences.other_notifications_sound),
+					users::other_notifications_desktop.eq(preferences.other_notifications_desktop),
+				))
+				.execute(db_connection)
+		},
+	)
+	.await?;
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let mut new_user = user.clone();
+	new_user.notification_preferences = preferences;
+
+	let user_update = UserDataUpdate::User(new_user.clone());
+	subscription_manager.send_message_to_user(&user.id, user_update).await;
+
+	let send_result = subscription_manager.broadcast_admin_user_message(new_user).await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
+	}
+
+	Ok(())
+}
+
+pub async fn handle_time_display_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	time_display: Option<TimeDisplay>,
+) -> Result<(), HandlerError> {
+	if let Some(time_display) = time_display.as_ref() {
+		time_display.validate().map_err(HandlerError::InvalidData)?;
+	}
+
+	let time_display_zone = time_display
+		.as_ref()
+		.and_then(|time_display| time_display.time_zone_name());
+	run_db_operation(&db_connection_pool, "updating time display", |db_connection| {
+		diesel::update(users::table.filter(users::id.eq(&user.id)))
+			.set((
+				users::use_event_time_display.eq(time_display.is_none()),
+				users::time_display_zone.eq(time_display_zone),
+			))
+			.execute(db_connection)
+	})
+	.await?;
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	let mut new_user = user.clone();
+	new_user.time_display = time_display;
+
+	let user_update = UserDataUpdate::User(new_user.clone());
+	subscription_manager.send_message_to_user(&user.id, user_update).await;
+
+	let send_result = subscription_manager.broadcast_admin_user_message(new_user).await;
+	if let Err(error) = send_result {
+		tide::log::error!("Failed to send user update to admin subscriptions: {}", error);
+	}
+
+	Ok(())
+}
+
+pub async fn handle_hotkeys_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	hotkeys: Vec<HotkeyBinding>,
+) -> Result<(), HandlerError> {
+	let mut seen_actions = HashSet::new();
+	for binding in hotkeys.iter() {
+		if !seen_actions.insert(binding.action) {
+			return Err(HandlerError::InvalidData(format!(
+				"More than one hotkey was given for \"{}\".",
+				binding.action.description()
+			)));
+		}
+		if !binding.hotkey.is_usable() {
+			return Err(HandlerError::InvalidData(format!(
+				"The hotkey for \"{}\" must use Alt or Ctrl.",
+				binding.action.description()
+			)));
+		}
+	}
+	if let Some(conflict) = hotkey_conflicts(&effective_hotkeys(&hotkeys)).first() {
+		let actions: Vec<&str> = conflict.iter().map(|action| action.description()).collect();
+		return Err(HandlerError::InvalidData(format!(
+			"The same hotkey is used for more than one action: {}",
+			actions.join(", ")
+		)));
+	}
+
+	let user_hotkey_records: Vec<UserHotkey> = hotkeys
+		.iter()
+		.map(|binding| UserHotkey::from_binding(user.id.clone(), binding.clone()))
+		.collect();
+	run_db_operation(&db_connection_pool, "updating user hotkeys", |db_connection| {
+		db_connection.transaction(|db_connection| {
+			diesel::delete(user_hotkeys::table)
+				.filter(user_hotkeys::user_id.eq(&user.id))
+				.execute(db_connection)?;
+			diesel::insert_into(user_hotkeys::table)
+				.values(&user_hotkey_records)
+				.execute(db_connection)
+		})
+	})
+	.await?;
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.send_message_to_user(&user.id, UserDataUpdate::Hotkeys(hotkeys))
+		.await;
+
+	Ok(())
+}
+
+pub async fn handle_filter_preset_update(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	user: &SelfUserData,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	update: FilterPresetUpdate,
+) -> Result<(), HandlerError> {
+	match update {
+		FilterPresetUpdate::Save(mut preset) => {
+			preset.name = preset.name.trim().to_string();
+			if preset.name.is_empty() {
+				return Err(HandlerError::InvalidData(String::from(
+					"Filter presets must have a name.",
+				)));
+			}
+			if preset.name.len() > MAX_FILTER_PRESET_NAME_LENGTH {
+				return Err(HandlerError::InvalidData(format!(
+					"Filter preset names can't be longer than {} characters.",
+					MAX_FILTER_PRESET_NAME_LENGTH
+				)));
+			}
+
+			let name_in_use: bool =
+				run_db_operation(&db_connection_pool, "checking filter preset names", |db_connection| {
+					diesel::select(diesel::dsl::exists(
+						user_view_preferences::table.filter(
+							user_view_preferences::user_id
+								.eq(&user.id)
+								.and(user_view_preferences::name.eq(&preset.name))
+								.and(user_view_preferences::id.ne(&preset.id)),
+						),
+					))
+					.get_result(db_connection)
+				})
+				.await?;
+			if name_in_use {
+				return Err(HandlerError::InvalidData(format!(
+					"You already have a filter preset named \"{}\".",
+					preset.name
+				)));
+			}
+
+			if preset.id.is_empty() {
+				preset.id = cuid2::create_id();
+				let preset_record = UserViewPreference::from_preset(user.id.clone(), preset);
+				run_db_operation(&db_connection_pool, "adding a filter preset", |db_connection| {
+					diesel::insert_into(user_view_preferences::table)
+						.values(&preset_record)
+						.execute(db_connection)
+				})
+				.await?;
+			} else {
+				let preset_record = UserViewPreference::from_preset(user.id.clone(), preset);
+				let updated_count =
+					run_db_operation(&db_connection_pool, "updating a filter preset", |db_connection| {
+						diesel::update(user_view_preferences::table)
+							.filter(
+								user_view_preferences::id
+									.eq(&preset_record.id)
+									.and(user_view_preferences::user_id.eq(&user.id)),
+							)
+							.set((
+								user_view_preferences::name.eq(&preset_record.name),
+								user_view_preferences::video_edit_states.eq(&preset_record.video_edit_states),
+								user_view_preferences::video_processing_states
+									.eq(&preset_record.video_processing_states),
+								user_view_preferences::created_by_me.eq(preset_record.created_by_me),
+								user_view_preferences::assigned_to_me.eq(preset_record.assigned_to_me),
+								user_view_preferences::over_expected_duration.eq(preset_record.over_expected_duration),
+								user_view_preferences::missing_giveaway_information
+									.eq(preset_record.missing_giveaway_information),
+							))
+							.execute(db_connection)
+					})
+					.await?;
+				if updated_count == 0 {
+					return Err(HandlerError::InvalidData(String::from(
+						"The filter preset to update doesn't exist.",
+					)));
+				}
+			}
+		}
+		FilterPresetUpdate::Delete(preset_id) => {
+			run_db_operation(&db_connection_pool, "deleting a filter preset", |db_connection| {
+				diesel::delete(user_view_preferences::table)
+					.filter(
+						user_view_preferences::id
+							.eq(&preset_id)
+							.and(user_view_preferences::user_id.eq(&user.id)),
+					)
+					.execute(db_connection)
+			})
+			.await?;
+		}
+	}
+
+	let presets: Vec<UserViewPreference> =
+		run_db_operation(&db_connection_pool, "loading filter presets", |db_connection| {
+			user_view_preferences::table
+				.filter(user_view_preferences::user_id.eq(&user.id))
+				.order(user_view_preferences::name.asc())
+				.load(db_connection)
+		})
+		.await?;
+	let presets: Vec<FilterPreset> = presets.into_iter().map(|preset| preset.into()).collect();
+
+	let mut subscription_manager = subscription_manager.lock().await;
+	subscription_manager
+		.send_message_to_user(&user.id, UserDataUpdate::FilterPresets(presets))
+		.await;
+
 	Ok(())
 }
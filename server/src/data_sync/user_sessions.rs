@@ -0,0 +1,86 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::connection::ConnectionUpdate;
+use super::{run_db_operation, HandlerError, SubscriptionManager};
+use crate::models::Session as SessionDb;
+use crate::session::{delete_user_sessions, load_active_sessions, SessionSelection};
+use async_std::channel::Sender;
+use async_std::sync::Mutex;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use stream_log_shared::messages::user::UserSession;
+use stream_log_shared::messages::FromServerMessage;
+
+/// Sends the user's sessions that haven't expired to the connection
+pub async fn handle_sessions_request(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	openid_user_id: &str,
+	current_session_id: &str,
+) -> Result<(), HandlerError> {
+	let sessions: Vec<SessionDb> = run_db_operation(db_connection_pool, "loading a user's sessions", |db_connection| {
+		load_active_sessions(db_connection, openid_user_id)
+	})
+	.await?;
+	let connected_session_ids = subscription_manager.lock().await.connected_session_ids();
+
+	let sessions: Vec<UserSession> = sessions
+		.into_iter()
+		.map(|session| UserSession {
+			is_connected: connected_session_ids.contains(&session.id),
+			is_current: session.id == current_session_id,
+			id: session.id,
+			user_agent: session.user_agent,
+			created_at: session.created_at,
+			last_active_at: session.last_active_at,
+		})
+		.collect();
+	let message = FromServerMessage::UserSessions(sessions);
+	conn_update_tx
+		.send(ConnectionUpdate::SendData(Box::new(message)))
+		.await?;
+	Ok(())
+}
+
+/// Signs the user out of some of their sessions, closing any connections opened with them. Unless the connection's own
+/// session was revoked, the user's remaining sessions are sent to the connection.
+pub async fn handle_session_revoke(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	conn_update_tx: &Sender<ConnectionUpdate>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	openid_user_id: &str,
+	current_session_id: &str,
+	selection: SessionSelection<'_>,
+) -> Result<(), HandlerError> {
+	let revoked_session_ids = run_db_operation(db_connection_pool, "revoking a user's sessions", |db_connection| {
+		delete_user_sessions(db_connection, openid_user_id, selection)
+	})
+	.await?;
+	tide::log::info!("Revoked {} sessions", revoked_session_ids.len());
+
+	subscription_manager
+		.lock()
+		.await
+		.close_session_connections(&revoked_session_ids)
+		.await;
+
+	if revoked_session_ids
+		.iter()
+		.any(|session_id| session_id == current_session_id)
+	{
+		return Ok(());
+	}
+	handle_sessions_request(
+		db_connection_pool,
+		conn_update_tx,
+		subscription_manager,
+		openid_user_id,
+		current_session_id,
+	)
+	.await
+}
@@ -66,6 +66,17 @@ pub fn run_embedded_migrations(
 	}
 }
 
+/// Checks whether the database has migrations that haven't been applied, without applying them
+pub fn has_pending_migrations(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<bool, MigrationError> {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => return Err(MigrationError(Box::new(error))),
+	};
+	db_connection.has_pending_migration(MIGRATIONS).map_err(MigrationError)
+}
+
 pub fn log_lost_db_connection(error: R2D2Error) {
 	tide::log::error!("Database connection lost: {}", error);
 }
@@ -0,0 +1,379 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::models::{
+	AvailableEntryType, EntryType, Event, EventCustomField, EventEditor, EventLogCustomFieldValue, EventLogEntry,
+	EventLogHistoryEntry, EventLogHistoryTag, EventLogTab, EventLogTag, InfoPage, PermissionEvent, PermissionGroup,
+	Tag, UserEventPermission, VideoEditStateTagRule,
+};
+use crate::schema::{
+	application_events, applications, available_entry_types_for_event, entry_template_tags, entry_templates,
+	entry_types, event_access_requests, event_custom_fields, event_editors, event_feedback, event_log,
+	event_log_custom_field_values, event_log_history, event_log_history_tags, event_log_tabs, event_log_tags,
+	event_share_links, event_webhooks, events, info_pages, notifications, pending_tag_actions, permission_events,
+	permission_groups, recurring_entries, rejected_event_updates, tags, user_event_permissions, user_permissions,
+	users, video_edit_state_tag_rules,
+};
+use crate::storage::FileStorage;
+use async_std::io;
+use chrono::prelude::*;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The version of the archive file format. This should be increased whenever the structure of the archive changes so
+/// that anything reading archives can tell which structure to expect.
+const ARCHIVE_FORMAT_VERSION: u32 = 3;
+
+/// A complete copy of an event's data, written to a file before the event is purged from the database
+#[derive(Deserialize, Serialize)]
+pub struct EventArchive {
+	pub format_version: u32,
+	pub archived_at: DateTime<Utc>,
+	pub data: EventArchiveData,
+}
+
+impl EventArchive {
+	pub fn new(data: EventArchiveData) -> Self {
+		Self {
+			format_version: ARCHIVE_FORMAT_VERSION,
+			archived_at: Utc::now(),
+			data,
+		}
+	}
+}
+
+/// All of the database rows belonging to an event, along with the rows from other tables they refer to
+#[derive(Deserialize, PartialEq, Serialize)]
+pub struct EventArchiveData {
+	pub event: Event,
+	pub tabs: Vec<EventLogTab>,
+	pub info_pages: Vec<InfoPage>,
+	pub tags: Vec<Tag>,
+	pub video_edit_state_tag_rules: Vec<VideoEditStateTagRule>,
+	pub available_entry_types: Vec<AvailableEntryType>,
+	/// Entry types that are available for the event or used in any revision of its entries
+	pub entry_types: Vec<EntryType>,
+	pub group_permissions: Vec<PermissionEvent>,
+	pub permission_groups: Vec<PermissionGroup>,
+	/// Permissions granted to users for the event directly. Added in format version 3.
+	#[serde(default)]
+	pub user_permissions: Vec<UserEventPermission>,
+	pub editors: Vec<EventEditor>,
+	/// Added in format version 2
+	#[serde(default)]
+	pub custom_fields: Vec<EventCustomField>,
+	pub entries: Vec<EventLogEntry>,
+	pub entry_tags: Vec<EventLogTag>,
+	/// Added in format version 2
+	#[serde(default)]
+	pub entry_custom_field_values: Vec<EventLogCustomFieldValue>,
+	pub entry_history: Vec<EventLogHistoryEntry>,
+	pub entry_history_tags: Vec<EventLogHistoryTag>,
+	/// Users referred to anywhere in the event's data
+	pub users: Vec<ArchivedUser>,
+	/// Applications that edited any of the event's entries
+	pub applications: Vec<ArchivedApplication>,
+}
+
+/// Identifying information for a user referred to by archived data
+#[derive(Deserialize, PartialEq, Queryable, Serialize)]
+pub struct ArchivedUser {
+	pub id: String,
+	pub name: String,
+}
+
+/// Identifying information for an application referred to by archived data
+#[derive(Deserialize, PartialEq, Queryable, Serialize)]
+pub struct ArchivedApplication {
+	pub id: String,
+	pub name: String,
+}
+
+/// Loads all of the data for the given event. To get a consistent copy of the data, this should be run in a
+/// transaction.
+pub fn load_event_archive_data(db_connection: &mut PgConnection, event_id: &str) -> QueryResult<EventArchiveData> {
+	let event: Event = events::table.find(event_id).first(db_connection)?;
+	let tabs: Vec<EventLogTab> = event_log_tabs::table
+		.filter(event_log_tabs::event.eq(event_id))
+		.order(event_log_tabs::id.asc())
+		.load(db_connection)?;
+	let info_pages: Vec<InfoPage> = info_pages::table
+		.filter(info_pages::event.eq(event_id))
+		.order(info_pages::id.asc())
+		.load(db_connection)?;
+	let tags: Vec<Tag> = tags::table
+		.filter(tags::for_event.eq(event_id))
+		.order(tags::id.asc())
+		.load(db_connection)?;
+	let video_edit_state_tag_rules: Vec<VideoEditStateTagRule> = video_edit_state_tag_rules::table
+		.filter(video_edit_state_tag_rules::event.eq(event_id))
+		.order(video_edit_state_tag_rules::tag.asc())
+		.load(db_connection)?;
+	let available_entry_types: Vec<AvailableEntryType> = available_entry_types_for_event::table
+		.filter(available_entry_types_for_event::event_id.eq(event_id))
+		.order(available_entry_types_for_event::entry_type.asc())
+		.load(db_connection)?;
+	let group_permissions: Vec<PermissionEvent> = permission_events::table
+		.filter(permission_events::event.eq(event_id))
+		.order(permission_events::permission_group.asc())
+		.load(db_connection)?;
+	let user_permissions: Vec<UserEventPermission> = user_event_permissions::table
+		.filter(user_event_permissions::event.eq(event_id))
+		.order(user_event_permissions::user_id.asc())
+		.load(db_connection)?;
+	let editors: Vec<EventEditor> = event_editors::table
+		.filter(event_editors::event.eq(event_id))
+		.order(event_editors::editor.asc())
+		.load(db_connection)?;
+	let custom_fields: Vec<EventCustomField> = event_custom_fields::table
+		.filter(event_custom_fields::event.eq(event_id))
+		.order(event_custom_fields::id.asc())
+		.load(db_connection)?;
+
+	let entries: Vec<EventLogEntry> = event_log::table
+		.filter(event_log::event.eq(event_id))
+		.order(event_log::id.asc())
+		.load(db_connection)?;
+	let entry_ids: Vec<&String> = entries.iter().map(|entry| &entry.id).collect();
+	let entry_tags: Vec<EventLogTag> = event_log_tags::table
+		.filter(event_log_tags::log_entry.eq_any(&entry_ids))
+		.order((event_log_tags::log_entry.asc(), event_log_tags::tag.asc()))
+		.load(db_connection)?;
+	let entry_custom_field_values: Vec<EventLogCustomFieldValue> = event_log_custom_field_values::table
+		.filter(event_log_custom_field_values::log_entry.eq_any(&entry_ids))
+		.order((
+			event_log_custom_field_values::log_entry.asc(),
+			event_log_custom_field_values::field.asc(),
+		))
+		.load(db_connection)?;
+	let entry_history: Vec<EventLogHistoryEntry> = event_log_history::table
+		.filter(event_log_history::log_entry.eq_any(&entry_ids))
+		.order(event_log_history::id.asc())
+		.load(db_connection)?;
+	let history_ids: Vec<&String> = entry_history.iter().map(|history_entry| &history_entry.id).collect();
+	let entry_history_tags: Vec<EventLogHistoryTag> = event_log_history_tags::table
+		.filter(event_log_history_tags::history_log_entry.eq_any(&history_ids))
+		.order((
+			event_log_history_tags::history_log_entry.asc(),
+			event_log_history_tags::tag.asc(),
+		))
+		.load(db_connection)?;
+
+	let mut entry_type_ids: BTreeSet<&String> = available_entry_types
+		.iter()
+		.map(|available_type| &available_type.entry_type)
+		.collect();
+	entry_type_ids.extend(entries.iter().filter_map(|entry| entry.entry_type.as_ref()));
+	entry_type_ids.extend(
+		entry_history
+			.iter()
+			.filter_map(|history_entry| history_entry.entry_type.as_ref()),
+	);
+	let entry_types: Vec<EntryType> = entry_types::table
+		.filter(entry_types::id.eq_any(&entry_type_ids))
+		.order(entry_types::id.asc())
+		.load(db_connection)?;
+
+	let permission_groups: Vec<PermissionGroup> = permission_groups::table
+		.filter(
+			permission_groups::id.eq_any(
+				group_permissions
+					.iter()
+					.map(|permission| &permission.permission_group)
+					.collect::<Vec<_>>(),
+			),
+		)
+		.order(permission_groups::id.asc())
+		.load(db_connection)?;
+
+	let mut user_ids: BTreeSet<&String> = editors.iter().map(|editor| &editor.editor).collect();
+	user_ids.extend(user_permissions.iter().map(|permission| &permission.user_id));
+	for entry in entries.iter() {
+		user_ids.extend(
+			[&entry.editor, &entry.deleted_by, &entry.created_by]
+				.into_iter()
+				.filter_map(|user_id| user_id.as_ref()),
+		);
+	}
+	for history_entry in entry_history.iter() {
+		user_ids.extend(
+			[
+				&history_entry.edit_user,
+				&history_entry.editor,
+				&history_entry.deleted_by,
+			]
+			.into_iter()
+			.filter_map(|user_id| user_id.as_ref()),
+		);
+	}
+	let users: Vec<ArchivedUser> = users::table
+		.filter(users::id.eq_any(&user_ids))
+		.select((users::id, users::name))
+		.order(users::id.asc())
+		.load(db_connection)?;
+
+	let application_ids: BTreeSet<&String> = entry_history
+		.iter()
+		.filter_map(|history_entry| history_entry.edit_application.as_ref())
+		.collect();
+	let applications: Vec<ArchivedApplication> = applications::table
+		.filter(applications::id.eq_any(&application_ids))
+		.select((applications::id, applications::name))
+		.order(applications::id.asc())
+		.load(db_connection)?;
+
+	Ok(EventArchiveData {
+		event,
+		tabs,
+		info_pages,
+		tags,
+		video_edit_state_tag_rules,
+		available_entry_types,
+		entry_types,
+		group_permissions,
+		permission_groups,
+		user_permissions,
+		editors,
+		custom_fields,
+		entries,
+		entry_tags,
+		entry_custom_field_values,
+		entry_history,
+		entry_history_tags,
+		users,
+		applications,
+	})
+}
+
+/// Writes the archive to a new stored file. The file is only given its final name once all of the data has been
+/// written. Returns the name of the file.
+pub async fn write_event_archive(storage: &FileStorage, archive: &EventArchive) -> io::Result<String> {
+	let file_name = format!(
+		"{}-{}.json",
+		archive.data.event.id,
+		archive.archived_at.format("%Y%m%dT%H%M%SZ")
+	);
+
+	let contents = serde_json::to_vec_pretty(archive)?;
+	storage
+		.write_new_file(&file_name, "application/json", &contents)
+		.await?;
+
+	verify_event_archive(storage, &file_name, archive).await?;
+
+	Ok(file_name)
+}
+
+/// Reads back a written archive file and checks that it contains exactly the archived data
+async fn verify_event_archive(storage: &FileStorage, file_name: &str, archive: &EventArchive) -> io::Result<()> {
+	let contents = storage.read_file(file_name).await?;
+	let written_archive: EventArchive = serde_json::from_slice(&contents)?;
+	if written_archive.format_version != archive.format_version
+		|| written_archive.archived_at != archive.archived_at
+		|| written_archive.data != archive.data
+	{
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("The archive file {} doesn't match the archived data", file_name),
+		));
+	}
+	Ok(())
+}
+
+/// Deletes all of the event's data from the database, provided that it hasn't changed since the archive was made. This
+/// should be run in a transaction that prevents changes to the event's data from happening between the check and the
+/// deletion.
+///
+/// If the event's data was deleted, returns the IDs of all users who had access to the event. If the event's data
+/// changed since it was archived, returns None without deleting anything.
+pub fn purge_archived_event(
+	db_connection: &mut PgConnection,
+	archive: &EventArchiveData,
+) -> QueryResult<Option<Vec<String>>> {
+	let event_id = archive.event.id.as_str();
+	let current_data = load_event_archive_data(db_connection, event_id)?;
+	if current_data != *archive {
+		return Ok(None);
+	}
+
+	let group_ids: Vec<&String> = archive
+		.group_permissions
+		.iter()
+		.map(|permission| &permission.permission_group)
+		.collect();
+	let mut users_with_access: BTreeSet<String> = user_permissions::table
+		.filter(user_permissions::permission_group.eq_any(&group_ids))
+		.select(user_permissions::user_id)
+		.distinct()
+		.load::<String>(db_connection)?
+		.into_iter()
+		.collect();
+	users_with_access.extend(
+		archive
+			.user_permissions
+			.iter()
+			.map(|permission| permission.user_id.clone()),
+	);
+
+	let entry_ids: Vec<&String> = archive.entries.iter().map(|entry| &entry.id).collect();
+	let history_ids: Vec<&String> = archive
+		.entry_history
+		.iter()
+		.map(|history_entry| &history_entry.id)
+		.collect();
+
+	diesel::delete(
+		event_log_history_tags::table.filter(event_log_history_tags::history_log_entry.eq_any(&history_ids)),
+	)
+	.execute(db_connection)?;
+	diesel::delete(event_log_history::table.filter(event_log_history::log_entry.eq_any(&entry_ids)))
+		.execute(db_connection)?;
+	diesel::delete(event_log_tags::table.filter(event_log_tags::log_entry.eq_any(&entry_ids)))
+		.execute(db_connection)?;
+	diesel::delete(
+		event_log_custom_field_values::table.filter(event_log_custom_field_values::log_entry.eq_any(&entry_ids)),
+	)
+	.execute(db_connection)?;
+	diesel::delete(notifications::table.filter(notifications::log_entry.eq_any(&entry_ids))).execute(db_connection)?;
+	diesel::delete(notifications::table.filter(notifications::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(event_log::table.filter(event_log::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(event_custom_fields::table.filter(event_custom_fields::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(video_edit_state_tag_rules::table.filter(video_edit_state_tag_rules::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(pending_tag_actions::table.filter(pending_tag_actions::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(event_feedback::table.filter(event_feedback::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(recurring_entries::table.filter(recurring_entries::event.eq(event_id))).execute(db_connection)?;
+	let template_ids = entry_templates::table
+		.filter(entry_templates::event.eq(event_id))
+		.select(entry_templates::id);
+	diesel::delete(entry_template_tags::table.filter(entry_template_tags::template.eq_any(template_ids)))
+		.execute(db_connection)?;
+	diesel::delete(entry_templates::table.filter(entry_templates::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(tags::table.filter(tags::for_event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(event_log_tabs::table.filter(event_log_tabs::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(info_pages::table.filter(info_pages::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(permission_events::table.filter(permission_events::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(user_event_permissions::table.filter(user_event_permissions::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(event_editors::table.filter(event_editors::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(
+		available_entry_types_for_event::table.filter(available_entry_types_for_event::event_id.eq(event_id)),
+	)
+	.execute(db_connection)?;
+	diesel::delete(event_access_requests::table.filter(event_access_requests::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(application_events::table.filter(application_events::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(event_webhooks::table.filter(event_webhooks::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(event_share_links::table.filter(event_share_links::event.eq(event_id))).execute(db_connection)?;
+	diesel::delete(rejected_event_updates::table.filter(rejected_event_updates::event.eq(event_id)))
+		.execute(db_connection)?;
+	diesel::delete(events::table.find(event_id)).execute(db_connection)?;
+
+	Ok(Some(users_with_access.into_iter().collect()))
+}
@@ -0,0 +1,207 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::models::AvailableEntryType;
+use crate::schema::{available_entry_types_for_event, event_editors, event_log, event_log_tags, tags};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A problem with the stored data that the rest of the server doesn't expect to happen. The database schema doesn't
+/// prevent any of these, but they can cause loading an event to fail.
+pub enum IntegrityProblem {
+	/// An entry has a tag that's been deleted or that belongs to a different event
+	EntryTagNotInEvent { entry_id: String, tag_id: String },
+	/// An entry's editor isn't an editor for the entry's event
+	EditorNotInEvent { entry_id: String, editor_id: String },
+	/// An entry has an entry type that isn't available for the entry's event
+	EntryTypeNotAvailable {
+		entry_id: String,
+		event_id: String,
+		entry_type_id: String,
+	},
+	/// An entry's parent has been deleted or belongs to a different event
+	MissingParent { entry_id: String, parent_id: String },
+}
+
+impl fmt::Display for IntegrityProblem {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::EntryTagNotInEvent { entry_id, tag_id } => write!(
+				f,
+				"Entry {} has tag {}, which is deleted or not a tag for the entry's event",
+				entry_id, tag_id
+			),
+			Self::EditorNotInEvent { entry_id, editor_id } => write!(
+				f,
+				"Entry {} has editor {}, who isn't an editor for the entry's event",
+				entry_id, editor_id
+			),
+			Self::EntryTypeNotAvailable {
+				entry_id,
+				event_id,
+				entry_type_id,
+			} => write!(
+				f,
+				"Entry {} has entry type {}, which isn't available for event {}",
+				entry_id, entry_type_id, event_id
+			),
+			Self::MissingParent { entry_id, parent_id } => write!(
+				f,
+				"Entry {} has parent {}, which is deleted or not in the entry's event",
+				entry_id, parent_id
+			),
+		}
+	}
+}
+
+impl IntegrityProblem {
+	/// Describes how [fix_integrity_problems] fixes the problem
+	pub fn fix_description(&self) -> &'static str {
+		match self {
+			Self::EntryTagNotInEvent { .. } => "removing the tag from the entry",
+			Self::EditorNotInEvent { .. } => "removing the editor from the entry",
+			Self::EntryTypeNotAvailable { .. } => "making the entry type available for the event",
+			Self::MissingParent { .. } => "removing the entry's parent",
+		}
+	}
+}
+
+/// Checks all entries that haven't been deleted for problems with the data they reference
+pub fn check_integrity(db_connection: &mut PgConnection) -> QueryResult<Vec<IntegrityProblem>> {
+	let mut problems: Vec<IntegrityProblem> = Vec::new();
+
+	let entries: Vec<(String, String, Option<String>, Option<String>, Option<String>, bool)> = event_log::table
+		.select((
+			event_log::id,
+			event_log::event,
+			event_log::entry_type,
+			event_log::editor,
+			event_log::parent,
+			event_log::deleted_by.is_not_null(),
+		))
+		.load(db_connection)?;
+	let entry_events: HashMap<&str, (&str, bool)> = entries
+		.iter()
+		.map(|(id, event, _, _, _, deleted)| (id.as_str(), (event.as_str(), *deleted)))
+		.collect();
+
+	let event_editors: HashSet<(String, String)> = event_editors::table
+		.select((event_editors::event, event_editors::editor))
+		.load::<(String, String)>(db_connection)?
+		.into_iter()
+		.collect();
+	let available_entry_types: HashSet<(String, String)> = available_entry_types_for_event::table
+		.select((
+			available_entry_types_for_event::event_id,
+			available_entry_types_for_event::entry_type,
+		))
+		.load::<(String, String)>(db_connection)?
+		.into_iter()
+		.collect();
+
+	for (entry_id, event_id, entry_type, editor, parent, deleted) in entries.iter() {
+		if *deleted {
+			continue;
+		}
+
+		if let Some(editor_id) = editor {
+			if !event_editors.contains(&(event_id.clone(), editor_id.clone())) {
+				problems.push(IntegrityProblem::EditorNotInEvent {
+					entry_id: entry_id.clone(),
+					editor_id: editor_id.clone(),
+				});
+			}
+		}
+
+		if let Some(entry_type_id) = entry_type {
+			if !available_entry_types.contains(&(event_id.clone(), entry_type_id.clone())) {
+				problems.push(IntegrityProblem::EntryTypeNotAvailable {
+					entry_id: entry_id.clone(),
+					event_id: event_id.clone(),
+					entry_type_id: entry_type_id.clone(),
+				});
+			}
+		}
+
+		if let Some(parent_id) = parent {
+			let parent_is_valid = match entry_events.get(parent_id.as_str()) {
+				Some((parent_event_id, parent_deleted)) => *parent_event_id == event_id.as_str() && !*parent_deleted,
+				None => false,
+			};
+			if !parent_is_valid {
+				problems.push(IntegrityProblem::MissingParent {
+					entry_id: entry_id.clone(),
+					parent_id: parent_id.clone(),
+				});
+			}
+		}
+	}
+
+	let entry_tags: Vec<(String, String, String, bool)> = event_log_tags::table
+		.inner_join(tags::table)
+		.select((event_log_tags::log_entry, tags::id, tags::for_event, tags::deleted))
+		.load(db_connection)?;
+	for (entry_id, tag_id, tag_event_id, tag_deleted) in entry_tags {
+		let Some((entry_event_id, entry_deleted)) = entry_events.get(entry_id.as_str()) else {
+			continue;
+		};
+		if *entry_deleted {
+			continue;
+		}
+		if tag_deleted || *entry_event_id != tag_event_id.as_str() {
+			problems.push(IntegrityProblem::EntryTagNotInEvent { entry_id, tag_id });
+		}
+	}
+
+	Ok(problems)
+}
+
+/// Fixes the given problems, changing as little data as possible to do so
+pub fn fix_integrity_problems(db_connection: &mut PgConnection, problems: &[IntegrityProblem]) -> QueryResult<()> {
+	db_connection.transaction(|db_connection| {
+		for problem in problems.iter() {
+			match problem {
+				IntegrityProblem::EntryTagNotInEvent { entry_id, tag_id } => {
+					diesel::delete(event_log_tags::table)
+						.filter(
+							event_log_tags::log_entry
+								.eq(entry_id)
+								.and(event_log_tags::tag.eq(tag_id)),
+						)
+						.execute(db_connection)?;
+				}
+				IntegrityProblem::EditorNotInEvent { entry_id, .. } => {
+					diesel::update(event_log::table)
+						.filter(event_log::id.eq(entry_id))
+						.set(event_log::editor.eq(None::<String>))
+						.execute(db_connection)?;
+				}
+				IntegrityProblem::EntryTypeNotAvailable {
+					event_id,
+					entry_type_id,
+					..
+				} => {
+					let available_entry_type = AvailableEntryType {
+						entry_type: entry_type_id.clone(),
+						event_id: event_id.clone(),
+					};
+					diesel::insert_into(available_entry_types_for_event::table)
+						.values(available_entry_type)
+						.on_conflict_do_nothing()
+						.execute(db_connection)?;
+				}
+				IntegrityProblem::MissingParent { entry_id, .. } => {
+					diesel::update(event_log::table)
+						.filter(event_log::id.eq(entry_id))
+						.set(event_log::parent.eq(None::<String>))
+						.execute(db_connection)?;
+				}
+			}
+		}
+		Ok(())
+	})
+}
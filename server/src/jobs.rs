@@ -0,0 +1,419 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::data_sync::{run_db_operation, SubscriptionManager};
+use crate::models::{QueuedJob as QueuedJobDb, RecurringEntry as RecurringEntryDb};
+use crate::recurring_entries::create_recurring_entry;
+use crate::schema::{queued_jobs, recurring_entries};
+use crate::webhooks::{build_webhook_client, deliver_webhook};
+use async_std::channel::{unbounded, Receiver, Sender};
+use async_std::sync::{Arc, Mutex};
+use async_std::task::{self, sleep};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use isahc::HttpClient;
+use miette::IntoDiagnostic;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use stream_log_shared::messages::admin::{AdminJobData, Job, JobStatus};
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+
+/// The number of jobs that can be run at the same time
+const WORKER_COUNT: usize = 4;
+
+/// The number of times a job is attempted before it's given up on
+const MAX_ATTEMPTS: u32 = 5;
+
+/// How long to wait before trying a failed job again. The wait doubles with each further failure.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// The number of finished jobs to keep so that administrators can see what happened recently
+const FINISHED_JOB_HISTORY: usize = 200;
+
+/// Work to be done in the background
+pub enum JobKind {
+	/// Posts a webhook payload to one of an event's webhook URLs
+	WebhookDelivery { url: String, body: Arc<Vec<u8>> },
+	/// Creates the log entry for an occurrence of a recurring entry that has come due
+	RecurringEntry {
+		recurring_entry: RecurringEntryDb,
+		occurrence_time: DateTime<Utc>,
+	},
+}
+
+impl JobKind {
+	fn description(&self) -> String {
+		match self {
+			Self::WebhookDelivery { url, .. } => format!("Deliver webhook to {}", url),
+			Self::RecurringEntry {
+				recurring_entry,
+				occurrence_time,
+			} => {
+				format!(
+					"Create recurring entry \"{}\" for {}",
+					recurring_entry.description,
+					occurrence_time.format("%Y-%m-%d %H:%M UTC")
+				)
+			}
+		}
+	}
+
+	/// Identifies work that should only be queued once. While a job with a key hasn't finished, other jobs with the
+	/// same key aren't queued.
+	fn unique_key(&self) -> Option<String> {
+		match self {
+			Self::WebhookDelivery { .. } => None,
+			Self::RecurringEntry {
+				recurring_entry,
+				occurrence_time,
+			} => Some(format!(
+				"recurring_entry:{}:{}",
+				recurring_entry.id,
+				occurrence_time.timestamp()
+			)),
+		}
+	}
+
+	/// Does the job's work. If it fails, a description of the problem is returned.
+	async fn run(&self, context: &JobContext) -> Result<(), String> {
+		match self {
+			Self::WebhookDelivery { url, body } => deliver_webhook(&context.webhook_client, url, body).await,
+			Self::RecurringEntry {
+				recurring_entry,
+				occurrence_time,
+			} => {
+				create_recurring_entry(
+					&context.db_connection_pool,
+					&context.subscription_manager,
+					&recurring_entry.id,
+					*occurrence_time,
+				)
+				.await
+			}
+		}
+	}
+
+	/// Gets the database record that keeps a job of this kind until it finishes
+	fn to_stored(&self, id: &str, attempts: u32, queued_at: DateTime<Utc>) -> QueuedJobDb {
+		let mut stored_job = QueuedJobDb {
+			id: id.to_string(),
+			webhook_url: None,
+			webhook_body: None,
+			recurring_entry: None,
+			occurrence_time: None,
+			attempts: attempts as i32,
+			queued_at,
+		};
+		match self {
+			Self::WebhookDelivery { url, body } => {
+				stored_job.webhook_url = Some(url.clone());
+				stored_job.webhook_body = Some(body.to_vec());
+			}
+			Self::RecurringEntry {
+				recurring_entry,
+				occurrence_time,
+			} => {
+				stored_job.recurring_entry = Some(recurring_entry.id.clone());
+				stored_job.occurrence_time = Some(*occurrence_time);
+			}
+		}
+		stored_job
+	}
+
+	/// Gets the work a stored job does. Returns None if the stored job doesn't describe any work, such as if its
+	/// recurring entry has since been deleted.
+	fn from_stored(stored_job: QueuedJobDb, recurring_entry: Option<RecurringEntryDb>) -> Option<Self> {
+		if let (Some(url), Some(body)) = (stored_job.webhook_url, stored_job.webhook_body) {
+			return Some(Self::WebhookDelivery {
+				url,
+				body: Arc::new(body),
+			});
+		}
+		Some(Self::RecurringEntry {
+			recurring_entry: recurring_entry?,
+			occurrence_time: stored_job.occurrence_time?,
+		})
+	}
+}
+
+/// What jobs need to do their work
+struct JobContext {
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	webhook_client: HttpClient,
+}
+
+struct QueuedJob {
+	id: String,
+	kind: JobKind,
+	attempts: u32,
+}
+
+#[derive(Default)]
+struct JobList {
+	/// Jobs that haven't finished and recently finished jobs by ID
+	jobs: HashMap<String, Job>,
+	/// The IDs of finished jobs, oldest first
+	finished_job_ids: VecDeque<String>,
+	/// The unique keys of jobs that haven't finished
+	pending_keys: HashSet<String>,
+}
+
+/// Runs work in the background so that it doesn't hold up the connections that cause it. Jobs that fail are retried
+/// with increasing delays, and administrators are kept up to date with the status of each job. Jobs are stored in the
+/// database until they finish, so jobs that were waiting when the server stopped are run once it starts again.
+pub struct JobQueue {
+	job_tx: Sender<QueuedJob>,
+	job_list: Mutex<JobList>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+}
+
+impl JobQueue {
+	/// Sets up the job queue, queues the jobs that hadn't finished when the server last stopped, and starts the workers
+	/// that run its jobs
+	pub fn start(
+		db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+		subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	) -> miette::Result<Arc<Self>> {
+		let webhook_client = build_webhook_client().into_diagnostic()?;
+		let context = Arc::new(JobContext {
+			db_connection_pool: db_connection_pool.clone(),
+			subscription_manager: Arc::clone(&subscription_manager),
+			webhook_client,
+		});
+
+		let stored_jobs: Vec<(QueuedJobDb, Option<RecurringEntryDb>)> = {
+			let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+			queued_jobs::table
+				.left_join(recurring_entries::table)
+				.order(queued_jobs::queued_at)
+				.load(&mut *db_connection)
+				.into_diagnostic()?
+		};
+
+		let (job_tx, job_rx) = unbounded();
+		let mut job_list = JobList::default();
+		let now = Utc::now();
+		for (stored_job, recurring_entry) in stored_jobs {
+			let id = stored_job.id.clone();
+			let attempts = stored_job.attempts as u32;
+			let queued_at = stored_job.queued_at;
+			let Some(kind) = JobKind::from_stored(stored_job, recurring_entry) else {
+				continue;
+			};
+			if let Some(key) = kind.unique_key() {
+				job_list.pending_keys.insert(key);
+			}
+			let job = Job {
+				id: id.clone(),
+				description: kind.description(),
+				status: JobStatus::Queued,
+				attempts,
+				last_error: None,
+				queued_at,
+				updated_at: now,
+			};
+			job_list.jobs.insert(id.clone(), job);
+			if let Err(error) = job_tx.try_send(QueuedJob { id, kind, attempts }) {
+				tide::log::error!("Failed to queue a stored job: {}", error);
+			}
+		}
+
+		let job_queue = Arc::new(Self {
+			job_tx,
+			job_list: Mutex::new(job_list),
+			db_connection_pool,
+			subscription_manager,
+		});
+		for _ in 0..WORKER_COUNT {
+			task::spawn(run_job_worker(
+				Arc::clone(&job_queue),
+				Arc::clone(&context),
+				job_rx.clone(),
+			));
+		}
+
+		Ok(job_queue)
+	}
+
+	/// Adds a job to the end of the queue. If the job has a unique key and a job with the same key hasn't finished yet,
+	/// the job isn't added.
+	pub async fn enqueue(&self, kind: JobKind) {
+		let now = Utc::now();
+		let job = Job {
+			id: cuid2::create_id(),
+			description: kind.description(),
+			status: JobStatus::Queued,
+			attempts: 0,
+			last_error: None,
+			queued_at: now,
+			updated_at: now,
+		};
+		{
+			let mut job_list = self.job_list.lock().await;
+			if let Some(key) = kind.unique_key() {
+				if !job_list.pending_keys.insert(key) {
+					return;
+				}
+			}
+			job_list.jobs.insert(job.id.clone(), job.clone());
+		}
+
+		let stored_job = kind.to_stored(&job.id, 0, now);
+		let store_result = run_db_operation(&self.db_connection_pool, "storing a queued job", |db_connection| {
+			diesel::insert_into(queued_jobs::table)
+				.values(&stored_job)
+				.execute(db_connection)
+		})
+		.await;
+		if let Err(error) = store_result {
+			tide::log::error!("{}; it won't be run if the server restarts before it finishes", error);
+		}
+
+		let queued_job = QueuedJob {
+			id: job.id.clone(),
+			kind,
+			attempts: 0,
+		};
+		// Administrators need to hear about the job before a worker can pick it up and report that it's running
+		self.broadcast_updates(vec![AdminJobData::UpdateJob(job)]).await;
+		if let Err(error) = self.job_tx.send(queued_job).await {
+			tide::log::error!("Failed to queue a job: {}", error);
+		}
+	}
+
+	/// Gets all unfinished jobs and recently finished jobs, most recently queued first
+	pub async fn jobs(&self) -> Vec<Job> {
+		let job_list = self.job_list.lock().await;
+		let mut jobs: Vec<Job> = job_list.jobs.values().cloned().collect();
+		jobs.sort_by(|a, b| b.queued_at.cmp(&a.queued_at));
+		jobs
+	}
+
+	/// Updates the status of a job and sends the change to administrators
+	async fn update_job(&self, queued_job: &QueuedJob, update: impl FnOnce(&mut Job)) {
+		let mut updates = Vec::new();
+		let is_finished = {
+			let mut job_list = self.job_list.lock().await;
+			let Some(job) = job_list.jobs.get_mut(&queued_job.id) else {
+				return;
+			};
+			update(job);
+			job.updated_at = Utc::now();
+			let job = job.clone();
+
+			if job.status.is_finished() {
+				if let Some(key) = queued_job.kind.unique_key() {
+					job_list.pending_keys.remove(&key);
+				}
+				job_list.finished_job_ids.push_back(job.id.clone());
+				let excess_job_count = job_list.finished_job_ids.len().saturating_sub(FINISHED_JOB_HISTORY);
+				let removed_job_ids: Vec<String> = job_list.finished_job_ids.drain(..excess_job_count).collect();
+				for job_id in removed_job_ids {
+					job_list.jobs.remove(&job_id);
+					updates.push(AdminJobData::RemoveJob(job_id));
+				}
+			}
+			let is_finished = job.status.is_finished();
+			updates.insert(0, AdminJobData::UpdateJob(job));
+			is_finished
+		};
+		self.update_stored_job(queued_job, is_finished).await;
+		self.broadcast_updates(updates).await;
+	}
+
+	/// Keeps the stored copy of a job up to date, removing it once the job has finished
+	async fn update_stored_job(&self, queued_job: &QueuedJob, is_finished: bool) {
+		let job_id = queued_job.id.as_str();
+		let attempts = queued_job.attempts as i32;
+		let store_result = run_db_operation(&self.db_connection_pool, "updating a stored job", |db_connection| {
+			let stored_job = queued_jobs::table.find(job_id);
+			if is_finished {
+				diesel::delete(stored_job).execute(db_connection)
+			} else {
+				diesel::update(stored_job)
+					.set(queued_jobs::attempts.eq(attempts))
+					.execute(db_connection)
+			}
+		})
+		.await;
+		if let Err(error) = store_result {
+			tide::log::error!("{}", error);
+		}
+	}
+
+	async fn broadcast_updates(&self, updates: Vec<AdminJobData>) {
+		let subscription_manager = self.subscription_manager.lock().await;
+		for update in updates {
+			let message = SubscriptionData::AdminJobsUpdate(update);
+			if let Err(error) = subscription_manager.broadcast_admin_jobs_message(message).await {
+				tide::log::error!("Failed to broadcast a job update to administrators: {}", error);
+			}
+		}
+	}
+}
+
+/// Runs jobs from the queue one at a time
+async fn run_job_worker(job_queue: Arc<JobQueue>, context: Arc<JobContext>, job_rx: Receiver<QueuedJob>) {
+	while let Ok(mut queued_job) = job_rx.recv().await {
+		queued_job.attempts += 1;
+		let attempts = queued_job.attempts;
+		job_queue
+			.update_job(&queued_job, |job| {
+				job.status = JobStatus::Running;
+				job.attempts = attempts;
+			})
+			.await;
+
+		let error = match queued_job.kind.run(&context).await {
+			Ok(()) => {
+				job_queue
+					.update_job(&queued_job, |job| job.status = JobStatus::Succeeded)
+					.await;
+				continue;
+			}
+			Err(error) => error,
+		};
+
+		let description = queued_job.kind.description();
+		if attempts >= MAX_ATTEMPTS {
+			tide::log::error!("Job \"{}\" failed after {} attempts: {}", description, attempts, error);
+			job_queue
+				.update_job(&queued_job, |job| {
+					job.status = JobStatus::Failed;
+					job.last_error = Some(error);
+				})
+				.await;
+			continue;
+		}
+
+		let retry_delay = INITIAL_RETRY_DELAY * 2u32.pow(attempts - 1);
+		tide::log::warn!(
+			"Job \"{}\" failed; trying again in {} seconds: {}",
+			description,
+			retry_delay.as_secs(),
+			error
+		);
+		let retry_time = Utc::now() + TimeDelta::seconds(retry_delay.as_secs() as i64);
+		job_queue
+			.update_job(&queued_job, |job| {
+				job.status = JobStatus::WaitingToRetry(retry_time);
+				job.last_error = Some(error);
+			})
+			.await;
+
+		// The job goes to the back of the queue once the delay is up, leaving the worker free for other jobs until then
+		let job_tx = job_queue.job_tx.clone();
+		task::spawn(async move {
+			sleep(retry_delay).await;
+			if let Err(error) = job_tx.send(queued_job).await {
+				tide::log::error!("Failed to queue a job to be tried again: {}", error);
+			}
+		});
+	}
+}
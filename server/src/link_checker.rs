@@ -0,0 +1,283 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::data_sync::custom_fields::entry_custom_field_values;
+use crate::data_sync::SubscriptionManager;
+use crate::database::log_lost_db_connection;
+use crate::models::{Event as EventDb, EventLogEntry as EventLogEntryDb, Tag as TagDb, User};
+use crate::schema::{event_log, event_log_tags, events, tags, users};
+use async_std::sync::{Arc, Mutex};
+use async_std::task::sleep;
+use chrono::{TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use isahc::config::{Configurable, RedirectPolicy};
+use isahc::error::ErrorKind;
+use isahc::http::StatusCode;
+use isahc::{AsyncBody, HttpClient, Request, Response};
+use std::time::Duration;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+
+/// How long to wait between looking for entries with links that are due to be checked
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The maximum number of entries loaded from the database to be checked at once
+const CHECK_BATCH_SIZE: i64 = 100;
+
+/// The maximum number of redirects followed when checking a link
+const MAX_REDIRECTS: u32 = 10;
+
+/// Timeout used for link checks when none is configured
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The result of checking a single link
+enum LinkStatus {
+	/// The link responded successfully (or with an error that doesn't mean the resource is gone)
+	Alive,
+	/// The link points to something that doesn't exist
+	Dead,
+	/// We couldn't tell whether the link works (for example, the request timed out)
+	Unknown,
+}
+
+/// Periodically checks the video and media links of all log entries, recording which links are dead. Entries are
+/// checked again once `check_interval` has passed since they were last checked, or as soon as possible after their
+/// links change. When the set of dead links on an entry changes, the updated entry is sent to everyone subscribed to
+/// its event.
+pub async fn run_link_checker(
+	check_interval: Duration,
+	request_timeout: Option<Duration>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+) {
+	let http_client = HttpClient::builder()
+		.timeout(request_timeout.unwrap_or(DEFAULT_TIMEOUT))
+		.redirect_policy(RedirectPolicy::Limit(MAX_REDIRECTS))
+		.build();
+	let http_client = match http_client {
+		Ok(client) => client,
+		Err(error) => {
+			tide::log::error!("Failed to set up HTTP client for the link checker: {}", error);
+			return;
+		}
+	};
+	let check_interval = match TimeDelta::from_std(check_interval) {
+		Ok(interval) => interval,
+		Err(error) => {
+			tide::log::error!("Invalid link checker interval: {}", error);
+			return;
+		}
+	};
+
+	loop {
+		loop {
+			let entries = match load_entries_to_check(&db_connection_pool, check_interval) {
+				Some(entries) => entries,
+				None => break,
+			};
+			let checked_everything = entries.len() < CHECK_BATCH_SIZE as usize;
+			for entry in entries {
+				check_entry_links(&http_client, &db_connection_pool, &subscription_manager, entry).await;
+			}
+			if checked_everything {
+				break;
+			}
+		}
+
+		sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// Loads a batch of entries that are due for a link check. Returns None if the entries couldn't be loaded.
+fn load_entries_to_check(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	check_interval: TimeDelta,
+) -> Option<Vec<EventLogEntryDb>> {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => {
+			log_lost_db_connection(error);
+			return None;
+		}
+	};
+
+	let checked_before = Utc::now() - check_interval;
+	let no_links: Vec<Option<String>> = Vec::new();
+	let entries: QueryResult<Vec<EventLogEntryDb>> = event_log::table
+		.filter(
+			event_log::deleted_by.is_null().and(
+				event_log::links_checked_at
+					.is_null()
+					.or(event_log::links_checked_at.lt(checked_before)),
+			),
+		)
+		.filter(
+			event_log::video_link
+				.is_not_null()
+				.or(event_log::media_links.ne(&no_links))
+				.or(event_log::dead_links.ne(&no_links)),
+		)
+		.order(event_log::links_checked_at.asc().nulls_first())
+		.limit(CHECK_BATCH_SIZE)
+		.load(&mut *db_connection);
+
+	match entries {
+		Ok(entries) => Some(entries),
+		Err(error) => {
+			tide::log::error!("Database error loading entries for link checks: {}", error);
+			None
+		}
+	}
+}
+
+/// Checks all links on an entry and records the results
+async fn check_entry_links(
+	http_client: &HttpClient,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	entry: EventLogEntryDb,
+) {
+	let links: Vec<&String> = entry
+		.video_link
+		.iter()
+		.chain(entry.media_links.iter().flatten())
+		.collect();
+
+	let mut dead_links: Vec<Option<String>> = Vec::new();
+	for link in links {
+		let link_is_dead = match check_link(http_client, link).await {
+			LinkStatus::Alive => false,
+			LinkStatus::Dead => true,
+			// If we can't tell right now, keep whatever we found last time
+			LinkStatus::Unknown => entry.dead_links.iter().flatten().any(|dead_link| dead_link == link),
+		};
+		if link_is_dead && !dead_links.iter().flatten().any(|dead_link| dead_link == link) {
+			dead_links.push(Some(link.clone()));
+		}
+	}
+
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => {
+			log_lost_db_connection(error);
+			return;
+		}
+	};
+
+	let update_result: QueryResult<Option<(Event, EventLogEntry)>> = db_connection.transaction(|db_connection| {
+		// The links may have been changed while we were checking them. In that case, the entry will be checked again
+		// later, so we don't record anything now.
+		let updated_entry: Option<EventLogEntryDb> = diesel::update(event_log::table)
+			.filter(
+				event_log::id
+					.eq(&entry.id)
+					.and(event_log::video_link.is_not_distinct_from(&entry.video_link))
+					.and(event_log::media_links.eq(&entry.media_links)),
+			)
+			.set((
+				event_log::dead_links.eq(&dead_links),
+				event_log::links_checked_at.eq(Utc::now()),
+			))
+			.get_result(db_connection)
+			.optional()?;
+
+		let Some(updated_entry) = updated_entry else {
+			return Ok(None);
+		};
+		if updated_entry.dead_links == entry.dead_links || updated_entry.deleted_by.is_some() {
+			return Ok(None);
+		}
+
+		let entry_tags: Vec<TagDb> = tags::table
+			.filter(
+				tags::id.eq_any(
+					event_log_tags::table
+						.filter(event_log_tags::log_entry.eq(&updated_entry.id))
+						.select(event_log_tags::tag),
+				),
+			)
+			.load(db_connection)?;
+		let editor: Option<User> = if let Some(editor) = updated_entry.editor.as_ref() {
+			Some(users::table.find(editor).first(db_connection)?)
+		} else {
+			None
+		};
+		let created_by: Option<User> = if let Some(creator) = updated_entry.created_by.as_ref() {
+			Some(users::table.find(creator).first(db_connection)?)
+		} else {
+			None
+		};
+
+		let event: EventDb = events::table.find(&updated_entry.event).first(db_connection)?;
+		let event: Event = event.into();
+
+		let tags = entry_tags.into_iter().map(|tag| tag.into()).collect();
+		let editor = editor.map(|editor| editor.into());
+		let created_by = created_by.map(|creator| creator.into());
+		let custom_fields = entry_custom_field_values(db_connection, &updated_entry.id)?;
+		let entry = updated_entry.into_sync_entry(tags, editor, created_by, custom_fields);
+		Ok(Some((event, entry)))
+	});
+
+	drop(db_connection);
+
+	match update_result {
+		Ok(Some((event, entry))) => {
+			let subscription_manager = subscription_manager.lock().await;
+			let event_id = event.id.clone();
+			let message =
+				SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::UpdateLogEntry(entry, None)));
+			if let Err(error) = subscription_manager.broadcast_event_message(&event_id, message).await {
+				tide::log::error!("Failed to broadcast event log update after link check: {}", error);
+			}
+		}
+		Ok(None) => (),
+		Err(error) => tide::log::error!("Database error recording link check results: {}", error),
+	}
+}
+
+/// Checks whether a single link is still reachable
+async fn check_link(http_client: &HttpClient, link: &str) -> LinkStatus {
+	// Media links can be anything users type in; only web links can be checked.
+	if !link.starts_with("http://") && !link.starts_with("https://") {
+		return LinkStatus::Unknown;
+	}
+
+	let request = match Request::head(link).body(()) {
+		Ok(request) => request,
+		Err(_) => return LinkStatus::Dead,
+	};
+	let response = http_client.send_async(request).await;
+	let response = match response {
+		// Some servers don't support HEAD requests, so we fall back to GET for those. We only look at the response
+		// status, so the body is never read.
+		Ok(response)
+			if response.status() == StatusCode::METHOD_NOT_ALLOWED
+				|| response.status() == StatusCode::NOT_IMPLEMENTED =>
+		{
+			http_client.get_async(link).await
+		}
+		response => response,
+	};
+
+	link_status_from_response(response)
+}
+
+fn link_status_from_response(response: Result<Response<AsyncBody>, isahc::Error>) -> LinkStatus {
+	match response {
+		Ok(response) => match response.status() {
+			StatusCode::NOT_FOUND | StatusCode::GONE => LinkStatus::Dead,
+			_ => LinkStatus::Alive,
+		},
+		Err(error) => match error.kind() {
+			ErrorKind::ConnectionFailed | ErrorKind::NameResolution | ErrorKind::InvalidRequest => LinkStatus::Dead,
+			_ => LinkStatus::Unknown,
+		},
+	}
+}
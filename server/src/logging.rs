@@ -0,0 +1,277 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::{LogFormat, LogLevel, LoggingConfig};
+use async_std::task_local;
+use chrono::{SecondsFormat, Utc};
+use log::kv::{Error as KvError, Key, Value, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+use miette::IntoDiagnostic;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::cell::RefCell;
+use std::io::Write;
+use std::time::Instant;
+use tide::{Middleware, Next, Request};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+task_local! {
+	static LOG_CONTEXT: RefCell<LogContext> = RefCell::new(LogContext::default());
+}
+
+/// Identifies what the current task is working on. This is included with everything logged by the task, so log lines
+/// can be traced back to the connection or request (and user) they came from.
+#[derive(Default)]
+struct LogContext {
+	connection_id: Option<String>,
+	request_id: Option<String>,
+	/// The ID the OpenID Connect provider gave the user. This is known before the user registers and for requests that
+	/// don't otherwise load the user.
+	openid_user_id: Option<String>,
+	user_id: Option<String>,
+	/// The number (within the connection) and type of the WebSocket message being handled
+	message: Option<(u64, &'static str)>,
+	messages_received: u64,
+}
+
+impl LogContext {
+	fn fields(&self) -> Vec<(&'static str, String)> {
+		let mut fields = Vec::new();
+		if let Some(connection_id) = self.connection_id.as_ref() {
+			fields.push(("connection_id", connection_id.clone()));
+		}
+		if let Some(request_id) = self.request_id.as_ref() {
+			fields.push(("request_id", request_id.clone()));
+		}
+		if let Some(openid_user_id) = self.openid_user_id.as_ref() {
+			fields.push(("openid_user_id", openid_user_id.clone()));
+		}
+		if let Some(user_id) = self.user_id.as_ref() {
+			fields.push(("user_id", user_id.clone()));
+		}
+		if let Some((message_number, message_type)) = self.message {
+			fields.push(("message_number", message_number.to_string()));
+			fields.push(("message_type", message_type.to_string()));
+		}
+		fields
+	}
+}
+
+/// Updates the current task's log context. Outside of a task (for example, in a database pool thread), there's no
+/// context to update, so nothing is done.
+fn update_log_context(update: impl FnOnce(&mut LogContext)) {
+	let _ = LOG_CONTEXT.try_with(|context| update(&mut context.borrow_mut()));
+}
+
+/// Tags everything the current task logs with the given WebSocket connection
+pub fn set_log_connection(connection_id: &str, openid_user_id: &str) {
+	update_log_context(|context| {
+		context.connection_id = Some(connection_id.to_string());
+		context.openid_user_id = Some(openid_user_id.to_string());
+	});
+}
+
+/// Tags everything the current task logs with the given user. This is updated as the user of a connection changes
+/// (for example, when a new user registers).
+pub fn set_log_user(user_id: Option<&str>) {
+	update_log_context(|context| context.user_id = user_id.map(|id| id.to_string()));
+}
+
+/// Tags everything the current task logs with the next WebSocket message number for the connection and the type of
+/// the message until the returned scope is dropped
+pub fn start_log_message(message_type: &'static str) -> LogMessageScope {
+	update_log_context(|context| {
+		context.messages_received += 1;
+		context.message = Some((context.messages_received, message_type));
+	});
+	LogMessageScope
+}
+
+/// Stops tagging log lines with a WebSocket message once the message has been handled
+pub struct LogMessageScope;
+
+impl Drop for LogMessageScope {
+	fn drop(&mut self) {
+		update_log_context(|context| context.message = None);
+	}
+}
+
+fn current_log_context_fields() -> Vec<(&'static str, String)> {
+	LOG_CONTEXT
+		.try_with(|context| context.borrow().fields())
+		.unwrap_or_default()
+}
+
+/// Collects the key-value pairs attached to a log record
+#[derive(Default)]
+struct RecordFields {
+	fields: Vec<(String, JsonValue)>,
+}
+
+impl<'kvs> VisitSource<'kvs> for RecordFields {
+	fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+		let value = if let Some(value) = value.to_bool() {
+			JsonValue::from(value)
+		} else if let Some(value) = value.to_u64() {
+			JsonValue::from(value)
+		} else if let Some(value) = value.to_i64() {
+			JsonValue::from(value)
+		} else if let Some(value) = value.to_f64() {
+			JsonValue::from(value)
+		} else {
+			JsonValue::from(value.to_string())
+		};
+		self.fields.push((key.as_str().to_string(), value));
+		Ok(())
+	}
+}
+
+struct Logger {
+	format: LogFormat,
+	level: LevelFilter,
+}
+
+fn format_text_line(record: &Record, context_fields: Vec<(&'static str, String)>, fields: RecordFields) -> String {
+	let mut line = format!(
+		"{} {:<5} {}: {}",
+		Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+		record.level(),
+		record.target(),
+		record.args()
+	);
+	for (key, value) in context_fields {
+		push_text_field(&mut line, key, &value);
+	}
+	for (key, value) in fields.fields {
+		match value {
+			JsonValue::String(value) => push_text_field(&mut line, &key, &value),
+			value => push_text_field(&mut line, &key, &value.to_string()),
+		}
+	}
+	line
+}
+
+fn push_text_field(line: &mut String, key: &str, value: &str) {
+	if value.is_empty() || value.contains(char::is_whitespace) {
+		line.push_str(&format!(" {}={:?}", key, value));
+	} else {
+		line.push_str(&format!(" {}={}", key, value));
+	}
+}
+
+fn format_json_line(record: &Record, context_fields: Vec<(&'static str, String)>, fields: RecordFields) -> String {
+	let mut line = JsonMap::new();
+	line.insert(
+		String::from("time"),
+		JsonValue::from(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+	);
+	line.insert(String::from("level"), JsonValue::from(record.level().as_str()));
+	line.insert(String::from("target"), JsonValue::from(record.target()));
+	line.insert(String::from("message"), JsonValue::from(record.args().to_string()));
+	for (key, value) in context_fields {
+		line.insert(key.to_string(), JsonValue::from(value));
+	}
+	for (key, value) in fields.fields {
+		line.insert(key, value);
+	}
+	JsonValue::Object(line).to_string()
+}
+
+impl Log for Logger {
+	fn enabled(&self, metadata: &Metadata) -> bool {
+		metadata.level() <= self.level
+	}
+
+	fn log(&self, record: &Record) {
+		if !self.enabled(record.metadata()) {
+			return;
+		}
+		// Tide logs every request itself, but it can't include the request ID. Those lines are replaced by the ones
+		// logged by RequestLogMiddleware.
+		if record.target().starts_with("tide::log::middleware") {
+			return;
+		}
+
+		let mut fields = RecordFields::default();
+		let _ = record.key_values().visit(&mut fields);
+		let context_fields = current_log_context_fields();
+		let line = match self.format {
+			LogFormat::Text => format_text_line(record, context_fields, fields),
+			LogFormat::Json => format_json_line(record, context_fields, fields),
+		};
+		let _ = writeln!(std::io::stdout().lock(), "{}", line);
+	}
+
+	fn flush(&self) {
+		let _ = std::io::stdout().flush();
+	}
+}
+
+/// Sets up logging for the server using the logging configuration
+pub fn start_logging(config: Option<&LoggingConfig>) -> miette::Result<()> {
+	let format = config.and_then(|config| config.format).unwrap_or_default();
+	let level = match config.and_then(|config| config.level).unwrap_or_default() {
+		LogLevel::Error => LevelFilter::Error,
+		LogLevel::Warn => LevelFilter::Warn,
+		LogLevel::Info => LevelFilter::Info,
+		LogLevel::Debug => LevelFilter::Debug,
+		LogLevel::Trace => LevelFilter::Trace,
+	};
+	log::set_boxed_logger(Box::new(Logger { format, level })).into_diagnostic()?;
+	log::set_max_level(level);
+	Ok(())
+}
+
+/// Tags everything logged while handling a request with a request ID, and logs each request once it's handled
+pub struct RequestLogMiddleware;
+
+#[tide::utils::async_trait]
+impl Middleware<()> for RequestLogMiddleware {
+	async fn handle(&self, request: Request<()>, next: Next<'_, ()>) -> tide::Result {
+		let request_id = cuid2::create_id();
+		let openid_user_id = request.user_id();
+		update_log_context(|context| {
+			context.request_id = Some(request_id);
+			context.openid_user_id = openid_user_id;
+		});
+
+		let method = request.method().to_string();
+		let path = request.url().path().to_string();
+		let start_time = Instant::now();
+		let response = next.run(request).await;
+		let status: u16 = response.status().into();
+		let duration_ms = start_time.elapsed().as_millis() as u64;
+
+		match response.error() {
+			Some(error) if response.status().is_server_error() => tide::log::error!("Request failed", {
+				method: method,
+				path: path,
+				status: status,
+				duration_ms: duration_ms,
+				error: error.to_string(),
+			}),
+			Some(error) => tide::log::warn!("Request rejected", {
+				method: method,
+				path: path,
+				status: status,
+				duration_ms: duration_ms,
+				error: error.to_string(),
+			}),
+			None => tide::log::info!("Request handled", {
+				method: method,
+				path: path,
+				status: status,
+				duration_ms: duration_ms,
+			}),
+		}
+
+		// A connection can be used for more requests, which shouldn't be tagged with this one's ID
+		update_log_context(|context| {
+			context.request_id = None;
+			context.openid_user_id = None;
+		});
+		Ok(response)
+	}
+}
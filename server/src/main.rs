@@ -4,12 +4,19 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use async_std::channel;
 use async_std::fs;
 use async_std::sync::{Arc, Mutex};
+use async_std::task;
 use clap::Parser;
-use miette::IntoDiagnostic;
+use futures::future::{select, Either};
+use futures::StreamExt;
+use miette::{miette, IntoDiagnostic};
+use signal_hook::consts::signal::{SIGINT, SIGTERM};
+use signal_hook_async_std::Signals;
+use std::time::Duration;
 use tide::http::cookies::SameSite;
-use tide::sessions::SessionMiddleware;
+use tide::sessions::{MemoryStore, SessionMiddleware};
 use tide::{Body, Server};
 use tide_openidconnect::{
 	ClientId, ClientSecret, IssuerUrl, OpenIdConnectMiddleware, OpenIdConnectRouteExt, RedirectUrl,
@@ -21,23 +28,59 @@ mod api;
 mod args;
 use args::CliArgs;
 
+mod avatars;
+use avatars::serve_avatar;
+
 mod config;
 use config::parse_config;
 
 mod data_sync;
 use data_sync::connection::handle_connection;
+use data_sync::feedback_rate_limiter::FeedbackRateLimiter;
 use data_sync::new_event_entries::NewEventEntries;
-use data_sync::SubscriptionManager;
+use data_sync::{run_editor_workload_updater, SubscriptionManager};
 
 mod database;
-use database::{connect_db, run_embedded_migrations};
+use database::{connect_db, has_pending_migrations, run_embedded_migrations};
+
+mod event_archive;
+
+mod integrity;
+use integrity::{check_integrity, fix_integrity_problems};
+
+mod jobs;
+use jobs::JobQueue;
+
+mod metrics;
+use metrics::{serve_metrics, Metrics};
+
+mod read_only;
+use read_only::ReadOnlyMiddleware;
+
+mod recurring_entries;
+use recurring_entries::run_recurring_entry_creator;
+
+mod logging;
+use logging::{start_logging, RequestLogMiddleware};
+
+mod link_checker;
+use link_checker::run_link_checker;
+
+mod webhooks;
+use webhooks::run_webhook_sender;
 
 mod session;
-use session::DatabaseSessionStore;
+use session::{DatabaseSessionStore, SessionUserMiddleware};
+
+mod storage;
+use storage::{serve_stored_file, FileStorage};
 
 mod websocket_msg;
 
 mod models;
+mod plugins;
+use plugins::PluginRegistry;
+
 mod schema;
 
 fn establish_alternate_route(app: &mut Server<()>, path: &str) -> miette::Result<()> {
@@ -54,25 +97,108 @@ async fn main() -> miette::Result<()> {
 	let config = Arc::new(parse_config(&args.config).await?);
 
 	let db_connection_pool = connect_db(&config)?;
-	run_embedded_migrations(&db_connection_pool)?;
+	if args.read_only {
+		if args.migrations_only || args.fix_integrity {
+			return Err(miette!(
+				"Migrations and integrity fixes change the database, so they can't be run in read-only mode"
+			));
+		}
+		// A read replica can't be migrated, so its schema has to already match what this version of the server expects
+		if has_pending_migrations(&db_connection_pool)? {
+			return Err(miette!(
+				"The database has migrations that haven't been applied; run the server against the primary database first to apply them"
+			));
+		}
+	} else {
+		run_embedded_migrations(&db_connection_pool)?;
+	}
 
 	if args.migrations_only {
 		return Ok(());
 	}
 
-	tide::log::start();
+	start_logging(config.logging.as_ref())?;
+
+	if args.read_only {
+		tide::log::info!("Running in read-only mode; all changes will be rejected");
+	}
 
-	let subscription_manager = Arc::new(Mutex::new(SubscriptionManager::new()));
+	if args.check_integrity || args.fix_integrity {
+		let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+		let problems = check_integrity(&mut db_connection).into_diagnostic()?;
+		if problems.is_empty() {
+			tide::log::info!("Integrity check found no problems");
+		}
+		for problem in problems.iter() {
+			if args.fix_integrity {
+				tide::log::warn!("{}; fixing by {}", problem, problem.fix_description());
+			} else {
+				tide::log::warn!("{}", problem);
+			}
+		}
+		if args.fix_integrity && !problems.is_empty() {
+			fix_integrity_problems(&mut db_connection, &problems).into_diagnostic()?;
+			tide::log::info!("Fixed {} integrity problems", problems.len());
+		}
+	}
+
+	let file_storage = FileStorage::from_config(&config)?.map(Arc::new);
+
+	let (webhook_tx, webhook_rx) = channel::unbounded();
+	let (editor_workload_tx, editor_workload_rx) = channel::unbounded();
+
+	let mut subscription_manager = SubscriptionManager::new();
+	subscription_manager.set_webhook_sender(webhook_tx);
+	subscription_manager.set_editor_workload_sender(editor_workload_tx);
+	let subscription_manager = Arc::new(Mutex::new(subscription_manager));
+	let job_queue = JobQueue::start(db_connection_pool.clone(), Arc::clone(&subscription_manager))?;
+	task::spawn(run_webhook_sender(
+		db_connection_pool.clone(),
+		webhook_rx,
+		Arc::clone(&job_queue),
+	));
+	task::spawn(run_editor_workload_updater(
+		db_connection_pool.clone(),
+		Arc::clone(&subscription_manager),
+		editor_workload_rx,
+	));
 	let new_entries = Arc::new(Mutex::new(NewEventEntries::default()));
+	let feedback_rate_limiter = Arc::new(Mutex::new(FeedbackRateLimiter::default()));
+	let plugins = Arc::new(PluginRegistry::new());
+	let metrics = Arc::new(Metrics::default());
+
+	// The link checker records the results of its checks, so it can't run against a read-only database
+	if let Some(link_checker_config) = config.link_checker.as_ref().filter(|_| !args.read_only) {
+		let check_interval = Duration::from_secs(link_checker_config.interval_minutes * 60);
+		let request_timeout = link_checker_config.timeout_seconds.map(Duration::from_secs);
+		task::spawn(run_link_checker(
+			check_interval,
+			request_timeout,
+			db_connection_pool.clone(),
+			Arc::clone(&subscription_manager),
+		));
+	}
+
+	// Recurring entries are added to the database, so they can't be created in read-only mode
+	if !args.read_only {
+		task::spawn(run_recurring_entry_creator(
+			db_connection_pool.clone(),
+			Arc::clone(&job_queue),
+		));
+	}
 
 	let mut app = tide::new();
 
-	let session_middleware = {
-		let session_secret = fs::read(&config.session_secret_key_file).await.into_diagnostic()?;
-		SessionMiddleware::new(DatabaseSessionStore::new(db_connection_pool.clone()), &session_secret)
-			.with_same_site_policy(SameSite::Lax)
-	};
-	app.with(session_middleware);
+	let session_secret = fs::read(&config.session_secret_key_file).await.into_diagnostic()?;
+	// Sessions can't be saved to a read-only database, so they're kept in memory instead
+	if args.read_only {
+		app.with(SessionMiddleware::new(MemoryStore::new(), &session_secret).with_same_site_policy(SameSite::Lax));
+	} else {
+		app.with(
+			SessionMiddleware::new(DatabaseSessionStore::new(db_connection_pool.clone()), &session_secret)
+				.with_same_site_policy(SameSite::Lax),
+		);
+	}
 
 	let openid_config = tide_openidconnect::Config {
 		issuer_url: IssuerUrl::new(config.openid.endpoint.clone()).into_diagnostic()?,
@@ -82,16 +208,41 @@ async fn main() -> miette::Result<()> {
 		idp_logout_url: Some(config.openid.logout_url.clone()),
 	};
 	app.with(OpenIdConnectMiddleware::new(&openid_config).await);
+	app.with(SessionUserMiddleware::new(db_connection_pool.clone()));
+	app.with(RequestLogMiddleware);
+
+	if args.read_only {
+		app.with(ReadOnlyMiddleware);
+	}
 
-	api::add_routes(&mut app, db_connection_pool.clone(), Arc::clone(&subscription_manager))?;
+	api::add_routes(
+		&mut app,
+		db_connection_pool.clone(),
+		Arc::clone(&subscription_manager),
+		Arc::clone(&metrics),
+	)?;
 
 	app.at("/ws").authenticated().get(WebSocket::new({
 		let subscription_manager = Arc::clone(&subscription_manager);
 		let new_entries = Arc::clone(&new_entries);
+		let feedback_rate_limiter = Arc::clone(&feedback_rate_limiter);
+		let plugins = Arc::clone(&plugins);
+		let config = Arc::clone(&config);
+		let file_storage = file_storage.clone();
+		let metrics = Arc::clone(&metrics);
+		let job_queue = Arc::clone(&job_queue);
+		let db_connection_pool = db_connection_pool.clone();
+		let read_only = args.read_only;
 		move |request, stream| {
 			let db_connection_pool = db_connection_pool.clone();
 			let subscription_manager = Arc::clone(&subscription_manager);
 			let new_entries = Arc::clone(&new_entries);
+			let feedback_rate_limiter = Arc::clone(&feedback_rate_limiter);
+			let plugins = Arc::clone(&plugins);
+			let config = Arc::clone(&config);
+			let file_storage = file_storage.clone();
+			let metrics = Arc::clone(&metrics);
+			let job_queue = Arc::clone(&job_queue);
 			async move {
 				handle_connection(
 					db_connection_pool.clone(),
@@ -99,15 +250,57 @@ async fn main() -> miette::Result<()> {
 					stream,
 					subscription_manager,
 					new_entries,
+					feedback_rate_limiter,
+					plugins,
+					config,
+					file_storage,
+					metrics,
+					job_queue,
+					read_only,
 				)
 				.await
 			}
 		}
 	}));
 
+	{
+		let db_connection_pool = db_connection_pool.clone();
+		app.at("/avatars/:user_id")
+			.authenticated()
+			.get(move |request| serve_avatar(request, db_connection_pool.clone()));
+	}
+
+	if let Some(file_storage) = file_storage.as_ref() {
+		let file_storage = Arc::clone(file_storage);
+		let db_connection_pool = db_connection_pool.clone();
+		app.at("/files/:name")
+			.authenticated()
+			.get(move |request| serve_stored_file(request, Arc::clone(&file_storage), db_connection_pool.clone()));
+	}
+
+	if let Some(metrics_config) = config.metrics.as_ref() {
+		let auth_token = metrics_config.auth_token.clone().map(Arc::new);
+		let subscription_manager = Arc::clone(&subscription_manager);
+		let db_connection_pool = db_connection_pool.clone();
+		app.at("/metrics").get(move |request| {
+			serve_metrics(
+				request,
+				Arc::clone(&metrics),
+				Arc::clone(&subscription_manager),
+				db_connection_pool.clone(),
+				auth_token.clone(),
+			)
+		});
+	}
+
 	if let Some(favicon_file_path) = config.favicon_file.as_ref() {
 		app.at("/favicon.ico").serve_file(favicon_file_path).into_diagnostic()?;
 	}
+	if let Some(client_config_file_path) = config.client_config_file.as_ref() {
+		app.at("/config.json")
+			.serve_file(client_config_file_path)
+			.into_diagnostic()?;
+	}
 
 	app.at("/")
 		.authenticated()
@@ -120,20 +313,44 @@ async fn main() -> miette::Result<()> {
 	establish_alternate_route(&mut app, "/log/:id")?;
 	establish_alternate_route(&mut app, "/log/:id/tags")?;
 	establish_alternate_route(&mut app, "/log/:id/entry_types")?;
+	establish_alternate_route(&mut app, "/log/:id/dead_links")?;
+	establish_alternate_route(&mut app, "/log/:id/notes")?;
+	establish_alternate_route(&mut app, "/log/:id/time_shift")?;
+	establish_alternate_route(&mut app, "/log/:id/editors")?;
+	establish_alternate_route(&mut app, "/log/:id/editors_dashboard")?;
+	establish_alternate_route(&mut app, "/log/:id/stats")?;
+	establish_alternate_route(&mut app, "/log/:id/tabs")?;
+	establish_alternate_route(&mut app, "/log/:id/feedback")?;
+	establish_alternate_route(&mut app, "/log/:id/templates")?;
+	establish_alternate_route(&mut app, "/log/:id/recurring")?;
+	establish_alternate_route(&mut app, "/log/:id/import")?;
 	establish_alternate_route(&mut app, "/log/:event_id/page/:page_id")?;
 	establish_alternate_route(&mut app, "/admin/events")?;
 	establish_alternate_route(&mut app, "/admin/users")?;
 	establish_alternate_route(&mut app, "/admin/groups")?;
 	establish_alternate_route(&mut app, "/admin/assign_groups")?;
+	establish_alternate_route(&mut app, "/admin/user_event_permissions")?;
 	establish_alternate_route(&mut app, "/admin/event_types")?;
 	establish_alternate_route(&mut app, "/admin/assign_event_types")?;
+	establish_alternate_route(&mut app, "/admin/entry_type_matrix")?;
 	establish_alternate_route(&mut app, "/admin/editors")?;
 	establish_alternate_route(&mut app, "/admin/tags")?;
 	establish_alternate_route(&mut app, "/admin/applications")?;
 	establish_alternate_route(&mut app, "/admin/info_pages")?;
+	establish_alternate_route(&mut app, "/admin/access_requests")?;
+	establish_alternate_route(&mut app, "/admin/webhooks")?;
+	establish_alternate_route(&mut app, "/admin/custom_fields")?;
+	establish_alternate_route(&mut app, "/admin/audit")?;
+	establish_alternate_route(&mut app, "/admin/jobs")?;
 	establish_alternate_route(&mut app, "/user_profile")?;
 
-	app.listen(&config.listen.addr).await.into_diagnostic()?;
+	// When the server is told to stop, it stops accepting connections but lets the open ones finish up before exiting
+	let mut shutdown_signals = Signals::new([SIGTERM, SIGINT]).into_diagnostic()?;
+	let listen_future = Box::pin(app.listen(&config.listen.addr));
+	match select(listen_future, shutdown_signals.next()).await {
+		Either::Left((listen_result, _)) => listen_result.into_diagnostic()?,
+		Either::Right(_) => tide::log::info!("Received a signal to shut down"),
+	}
 
 	tide::log::info!("Initiating shutdown");
 
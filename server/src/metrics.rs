@@ -0,0 +1,277 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::data_sync::SubscriptionManager;
+use async_std::sync::{Arc, Mutex};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex as SyncMutex;
+use std::time::{Duration, Instant};
+use tide::{Middleware, Next, Request, Response, StatusCode};
+
+/// The upper bounds, in seconds, of the buckets into which handling times are counted
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Default)]
+struct LatencyHistogram {
+	/// The number of observations at or below each bound in [LATENCY_BUCKETS]
+	bucket_counts: [u64; LATENCY_BUCKETS.len()],
+	count: u64,
+	sum_seconds: f64,
+}
+
+impl LatencyHistogram {
+	fn record(&mut self, duration: Duration) {
+		let seconds = duration.as_secs_f64();
+		for (bucket_bound, bucket_count) in LATENCY_BUCKETS.iter().zip(self.bucket_counts.iter_mut()) {
+			if seconds <= *bucket_bound {
+				*bucket_count += 1;
+			}
+		}
+		self.count += 1;
+		self.sum_seconds += seconds;
+	}
+
+	/// Writes the histogram's samples to the output. The labels are written inside the braces of each sample, so they
+	/// should be formatted like `name="value"`.
+	fn write_samples(&self, output: &mut String, metric_name: &str, labels: &str) {
+		for (bucket_bound, bucket_count) in LATENCY_BUCKETS.iter().zip(self.bucket_counts.iter()) {
+			output.push_str(&format!(
+				"{}_bucket{{{},le=\"{}\"}} {}\n",
+				metric_name, labels, bucket_bound, bucket_count
+			));
+		}
+		output.push_str(&format!(
+			"{}_bucket{{{},le=\"+Inf\"}} {}\n",
+			metric_name, labels, self.count
+		));
+		output.push_str(&format!("{}_sum{{{}}} {}\n", metric_name, labels, self.sum_seconds));
+		output.push_str(&format!("{}_count{{{}}} {}\n", metric_name, labels, self.count));
+	}
+}
+
+/// Measurements of the running server, which are reported in the Prometheus text format by the metrics endpoint
+#[derive(Default)]
+pub struct Metrics {
+	open_connections: AtomicU64,
+	total_connections: AtomicU64,
+	/// Handling times of WebSocket messages by message type
+	message_handling: SyncMutex<HashMap<&'static str, LatencyHistogram>>,
+	/// Handling times of API requests by route and response status
+	api_requests: SyncMutex<HashMap<(&'static str, u16), LatencyHistogram>>,
+}
+
+impl Metrics {
+	pub fn connection_opened(&self) {
+		self.open_connections.fetch_add(1, Ordering::Relaxed);
+		self.total_connections.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub fn connection_closed(&self) {
+		self.open_connections.fetch_sub(1, Ordering::Relaxed);
+	}
+
+	pub fn record_message_handling(&self, message_type: &'static str, duration: Duration) {
+		let mut message_handling = self.message_handling.lock().unwrap();
+		message_handling.entry(message_type).or_default().record(duration);
+	}
+
+	pub fn record_api_request(&self, route: &'static str, status: StatusCode, duration: Duration) {
+		let mut api_requests = self.api_requests.lock().unwrap();
+		api_requests.entry((route, status.into())).or_default().record(duration);
+	}
+
+	/// Formats all the metrics in the Prometheus text exposition format
+	async fn render(
+		&self,
+		subscription_manager: &Mutex<SubscriptionManager>,
+		db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	) -> String {
+		let mut output = String::new();
+
+		write_metric_description(
+			&mut output,
+			"stream_log_websocket_connections",
+			"gauge",
+			"The number of open WebSocket connections.",
+		);
+		output.push_str(&format!(
+			"stream_log_websocket_connections {}\n",
+			self.open_connections.load(Ordering::Relaxed)
+		));
+		write_metric_description(
+			&mut output,
+			"stream_log_websocket_connections_total",
+			"counter",
+			"The number of WebSocket connections opened since the server started.",
+		);
+		output.push_str(&format!(
+			"stream_log_websocket_connections_total {}\n",
+			self.total_connections.load(Ordering::Relaxed)
+		));
+
+		let (subscription_stats, background_queue_stats) = {
+			let subscription_manager = subscription_manager.lock().await;
+			(
+				subscription_manager.subscription_stats().await,
+				subscription_manager.background_queue_stats(),
+			)
+		};
+		write_metric_description(
+			&mut output,
+			"stream_log_subscriptions",
+			"gauge",
+			"The number of active subscriptions of each type.",
+		);
+		for stats in subscription_stats.iter() {
+			output.push_str(&format!(
+				"stream_log_subscriptions{{type=\"{}\"}} {}\n",
+				stats.subscription_type, stats.subscriptions
+			));
+		}
+		write_metric_description(
+			&mut output,
+			"stream_log_broadcast_queue_depth",
+			"gauge",
+			"The number of messages waiting to be broadcast to the subscribers of each type of subscription.",
+		);
+		for stats in subscription_stats.iter() {
+			output.push_str(&format!(
+				"stream_log_broadcast_queue_depth{{type=\"{}\"}} {}\n",
+				stats.subscription_type, stats.queued_broadcasts
+			));
+		}
+		write_metric_description(
+			&mut output,
+			"stream_log_background_queue_depth",
+			"gauge",
+			"The number of items waiting to be processed by each background task.",
+		);
+		for (queue, depth) in background_queue_stats {
+			output.push_str(&format!(
+				"stream_log_background_queue_depth{{queue=\"{}\"}} {}\n",
+				queue, depth
+			));
+		}
+
+		let pool_state = db_connection_pool.state();
+		write_metric_description(
+			&mut output,
+			"stream_log_db_pool_connections",
+			"gauge",
+			"The number of database connections in the pool by whether they're in use.",
+		);
+		output.push_str(&format!(
+			"stream_log_db_pool_connections{{state=\"in_use\"}} {}\n",
+			pool_state.connections - pool_state.idle_connections
+		));
+		output.push_str(&format!(
+			"stream_log_db_pool_connections{{state=\"idle\"}} {}\n",
+			pool_state.idle_connections
+		));
+		write_metric_description(
+			&mut output,
+			"stream_log_db_pool_max_connections",
+			"gauge",
+			"The maximum number of connections the database pool can hold.",
+		);
+		output.push_str(&format!(
+			"stream_log_db_pool_max_connections {}\n",
+			db_connection_pool.max_size()
+		));
+
+		write_metric_description(
+			&mut output,
+			"stream_log_message_handling_seconds",
+			"histogram",
+			"How long WebSocket messages took to handle by message type.",
+		);
+		{
+			let message_handling = self.message_handling.lock().unwrap();
+			let mut message_handling: Vec<_> = message_handling.iter().collect();
+			message_handling.sort_by_key(|(message_type, _)| **message_type);
+			for (message_type, histogram) in message_handling {
+				let labels = format!("message_type=\"{}\"", message_type);
+				histogram.write_samples(&mut output, "stream_log_message_handling_seconds", &labels);
+			}
+		}
+
+		write_metric_description(
+			&mut output,
+			"stream_log_api_request_seconds",
+			"histogram",
+			"How long API requests took to handle by route and response status.",
+		);
+		{
+			let api_requests = self.api_requests.lock().unwrap();
+			let mut api_requests: Vec<_> = api_requests.iter().collect();
+			api_requests.sort_by_key(|(request_type, _)| **request_type);
+			for ((route, status), histogram) in api_requests {
+				let labels = format!("route=\"{}\",status=\"{}\"", route, status);
+				histogram.write_samples(&mut output, "stream_log_api_request_seconds", &labels);
+			}
+		}
+
+		output
+	}
+}
+
+/// Writes the lines describing a metric, which come before its samples
+fn write_metric_description(output: &mut String, metric_name: &str, metric_type: &str, help: &str) {
+	output.push_str(&format!("# HELP {} {}\n", metric_name, help));
+	output.push_str(&format!("# TYPE {} {}\n", metric_name, metric_type));
+}
+
+/// Records how long requests to a route take to handle
+pub struct RequestMetricsMiddleware {
+	metrics: Arc<Metrics>,
+	route: &'static str,
+}
+
+impl RequestMetricsMiddleware {
+	pub fn new(metrics: Arc<Metrics>, route: &'static str) -> Self {
+		Self { metrics, route }
+	}
+}
+
+#[tide::utils::async_trait]
+impl Middleware<()> for RequestMetricsMiddleware {
+	async fn handle(&self, request: Request<()>, next: Next<'_, ()>) -> tide::Result {
+		let start_time = Instant::now();
+		let response = next.run(request).await;
+		self.metrics
+			.record_api_request(self.route, response.status(), start_time.elapsed());
+		Ok(response)
+	}
+}
+
+/// Serves the server's metrics. If an auth token is configured, requests must pass it as a bearer token.
+pub async fn serve_metrics(
+	request: Request<()>,
+	metrics: Arc<Metrics>,
+	subscription_manager: Arc<Mutex<SubscriptionManager>>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	auth_token: Option<Arc<String>>,
+) -> tide::Result {
+	if let Some(auth_token) = auth_token {
+		let expected_header = format!("Bearer {}", auth_token);
+		let authorized = request
+			.header("Authorization")
+			.is_some_and(|header| header.last().as_str() == expected_header);
+		if !authorized {
+			return Ok(Response::new(StatusCode::Unauthorized));
+		}
+	}
+
+	let body = metrics.render(&subscription_manager, &db_connection_pool).await;
+	let response = Response::builder(StatusCode::Ok)
+		.content_type("text/plain; version=0.0.4; charset=utf-8")
+		.body(body)
+		.build();
+	Ok(response)
+}
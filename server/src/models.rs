@@ -5,33 +5,50 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::schema::{
-	applications, available_entry_types_for_event, entry_types, event_editors, event_log, event_log_history,
-	event_log_history_tags, event_log_tabs, event_log_tags, events, info_pages, permission_events, permission_groups,
-	sessions, tags, user_permissions, users,
+	admin_audit_log, application_events, applications, available_entry_types_for_event, entry_template_tags,
+	entry_templates, entry_types, event_access_requests, event_custom_fields, event_editors, event_feedback, event_log,
+	event_log_custom_field_values, event_log_history, event_log_history_tags, event_log_tabs, event_log_tags,
+	event_share_links, event_webhooks, events, info_pages, notifications, pending_tag_actions, permission_events,
+	permission_groups, queued_jobs, recurring_entries, recurring_entry_occurrences, rejected_event_updates, sessions,
+	tags, user_event_permissions, user_hotkeys, user_permissions, user_profile_admin_edits, user_view_preferences,
+	username_changes, users, video_edit_state_tag_rules,
 };
 use chrono::prelude::*;
 use diesel::{AsChangeset, Insertable, Queryable};
 use diesel_derive_enum::DbEnum;
 use rgb::RGB8;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use stream_log_shared::messages::admin::{
-	Application as ApplicationWs, PermissionGroup as PermissionGroupWs, PermissionGroupEventAssociation,
+	AdminActionType as AdminActionTypeWs, Application as ApplicationWs, PermissionGroup as PermissionGroupWs,
+	PermissionGroupEventAssociation,
 };
+use stream_log_shared::messages::custom_fields::{CustomField as CustomFieldWs, CustomFieldType as CustomFieldTypeWs};
 use stream_log_shared::messages::entry_types::EntryType as EntryTypeWs;
 use stream_log_shared::messages::event_log::{
-	EndTimeData, VideoEditState as VideoEditStateWs, VideoProcessingState as VideoProcessingStateWs,
+	EndTimeData, EventLogEntry as EventLogEntryWs, VideoEditState as VideoEditStateWs,
+	VideoProcessingState as VideoProcessingStateWs,
 };
 use stream_log_shared::messages::events::Event as EventWs;
+use stream_log_shared::messages::feedback::EventFeedback as EventFeedbackWs;
+use stream_log_shared::messages::hotkeys::{Hotkey, HotkeyAction as HotkeyActionWs, HotkeyBinding};
 use stream_log_shared::messages::info_pages::InfoPage as InfoPageWs;
+use stream_log_shared::messages::notifications::{NotificationPreferences, UserNotification, UserNotificationDetails};
 use stream_log_shared::messages::permissions::PermissionLevel;
+use stream_log_shared::messages::recurring_entries::RecurringEntry as RecurringEntryWs;
 use stream_log_shared::messages::tags::{Tag as TagWs, TagPlaylist};
+use stream_log_shared::messages::time_display::TimeDisplay;
 use stream_log_shared::messages::user::{PublicUserData, SelfUserData};
+use stream_log_shared::messages::view_preferences::{EventLogFilters, FilterPreset};
 
-/// Permissions a user can have for an event, as stored in the database.
-#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+/// Permissions a user can have for an event, as stored in the database. Levels are ordered from least to most access.
+#[derive(Clone, Copy, DbEnum, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 #[ExistingTypePath = "crate::schema::sql_types::Permission"]
 pub enum Permission {
 	/// Allows viewing the event data
 	View,
+	/// Allows viewing all of the event data, including the video editor's view, without editing anything
+	Auditor,
 	/// Allows viewing and editing the event data
 	Edit,
 	/// Allows viewing and editing the event data and performing supervisor-specific actions
@@ -43,12 +60,19 @@ impl Permission {
 	pub fn can_edit(&self) -> bool {
 		matches!(self, Self::Supervisor | Self::Edit)
 	}
+
+	/// Gets the highest of the given permission levels. A user with several permission levels for an event (for
+	/// example, through multiple permission groups) gets the highest of them.
+	pub fn highest(levels: impl IntoIterator<Item = Self>) -> Option<Self> {
+		levels.into_iter().max()
+	}
 }
 
 impl From<PermissionLevel> for Permission {
 	fn from(level: PermissionLevel) -> Self {
 		match level {
 			PermissionLevel::View => Self::View,
+			PermissionLevel::Auditor => Self::Auditor,
 			PermissionLevel::Edit => Self::Edit,
 			PermissionLevel::Supervisor => Self::Supervisor,
 		}
@@ -59,6 +83,7 @@ impl From<Permission> for PermissionLevel {
 	fn from(permission: Permission) -> Self {
 		match permission {
 			Permission::View => Self::View,
+			Permission::Auditor => Self::Auditor,
 			Permission::Edit => Self::Edit,
 			Permission::Supervisor => Self::Supervisor,
 		}
@@ -66,7 +91,7 @@ impl From<Permission> for PermissionLevel {
 }
 
 /// Edit state for a video, as stored in the database
-#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, DbEnum, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[ExistingTypePath = "crate::schema::sql_types::VideoEditState"]
 pub enum VideoEditState {
 	/// State indicating that no video should be made
@@ -100,7 +125,7 @@ impl From<VideoEditState> for VideoEditStateWs {
 /// Processing state of a video, as stored in the database.
 /// The processing state is updated by external systems via the API indicating a video's progress in being processed and
 /// uploaded.
-#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, DbEnum, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[ExistingTypePath = "crate::schema::sql_types::VideoProcessingState"]
 pub enum VideoProcessingState {
 	Unedited,
@@ -169,6 +194,29 @@ pub struct User {
 	/// Whether the user wants their entries to be spell-checked. If true, Stream Log will hint to the browser that
 	/// spell-checking should occur in certain fields
 	pub use_spell_check: bool,
+	/// Whether the user has finished or dismissed the guided tour of the event log
+	pub completed_guided_tour: bool,
+	/// Whether the user can log in. Deactivated users are kept so that their past work is still attributed to them.
+	pub enabled: bool,
+	/// When the user last changed their uploaded avatar, or None if they haven't uploaded one
+	pub avatar_updated_at: Option<DateTime<Utc>>,
+	/// Whether to play a sound when the user is made the editor of an entry
+	pub editor_assigned_sound: bool,
+	/// Whether to show a desktop notification when the user is made the editor of an entry
+	pub editor_assigned_desktop: bool,
+	/// Whether to play a sound when someone else types in an entry for which the user is the editor
+	pub typing_in_edited_entry_sound: bool,
+	/// Whether to show a desktop notification when someone else types in an entry for which the user is the editor
+	pub typing_in_edited_entry_desktop: bool,
+	/// Whether to play a sound for the user's other notifications
+	pub other_notifications_sound: bool,
+	/// Whether to show a desktop notification for the user's other notifications
+	pub other_notifications_desktop: bool,
+	/// Whether the user sees times in event logs the way each event shows them by default
+	pub use_event_time_display: bool,
+	/// The time zone in which the user sees times in event logs as wall-clock times, or None to see event-relative
+	/// times. Only used if the user doesn't use each event's default.
+	pub time_display_zone: Option<String>,
 }
 
 impl User {
@@ -180,6 +228,27 @@ impl User {
 		let blue: u8 = self.color_blue.try_into().unwrap();
 		RGB8::new(red, green, blue)
 	}
+
+	/// Collects the user's individual notification preference values
+	pub fn notification_preferences(&self) -> NotificationPreferences {
+		NotificationPreferences {
+			editor_assigned_sound: self.editor_assigned_sound,
+			editor_assigned_desktop: self.editor_assigned_desktop,
+			typing_in_edited_entry_sound: self.typing_in_edited_entry_sound,
+			typing_in_edited_entry_desktop: self.typing_in_edited_entry_desktop,
+			other_notifications_sound: self.other_notifications_sound,
+			other_notifications_desktop: self.other_notifications_desktop,
+		}
+	}
+
+	/// Gets how the user has chosen to see times in event logs, or None if they use each event's default
+	pub fn time_display(&self) -> Option<TimeDisplay> {
+		if self.use_event_time_display {
+			None
+		} else {
+			Some(TimeDisplay::from_time_zone(self.time_display_zone.clone()))
+		}
+	}
 }
 
 impl From<User> for PublicUserData {
@@ -192,7 +261,14 @@ impl From<User> for PublicUserData {
 		let b: u8 = value.color_blue.try_into().unwrap();
 		let color = RGB8::new(r, g, b);
 
-		Self { id, username, color }
+		let avatar_updated_at = value.avatar_updated_at;
+
+		Self {
+			id,
+			username,
+			color,
+			avatar_updated_at,
+		}
 	}
 }
 
@@ -208,6 +284,11 @@ impl From<User> for SelfUserData {
 		let color = RGB8::new(r, g, b);
 
 		let use_spell_check = value.use_spell_check;
+		let completed_guided_tour = value.completed_guided_tour;
+		let enabled = value.enabled;
+		let avatar_updated_at = value.avatar_updated_at;
+		let notification_preferences = value.notification_preferences();
+		let time_display = value.time_display();
 
 		Self {
 			id,
@@ -215,12 +296,217 @@ impl From<User> for SelfUserData {
 			is_admin,
 			color,
 			use_spell_check,
+			completed_guided_tour,
+			enabled,
+			avatar_updated_at,
+			notification_preferences,
+			time_display,
+		}
+	}
+}
+
+/// An action that can be performed with a hotkey, as stored in the database
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::HotkeyAction"]
+pub enum HotkeyAction {
+	StartTimeNow,
+	EndTimeNow,
+	ToggleMissingGiveawayInfo,
+}
+
+impl From<HotkeyActionWs> for HotkeyAction {
+	fn from(value: HotkeyActionWs) -> Self {
+		match value {
+			HotkeyActionWs::StartTimeNow => Self::StartTimeNow,
+			HotkeyActionWs::EndTimeNow => Self::EndTimeNow,
+			HotkeyActionWs::ToggleMissingGiveawayInfo => Self::ToggleMissingGiveawayInfo,
+		}
+	}
+}
+
+impl From<HotkeyAction> for HotkeyActionWs {
+	fn from(value: HotkeyAction) -> Self {
+		match value {
+			HotkeyAction::StartTimeNow => Self::StartTimeNow,
+			HotkeyAction::EndTimeNow => Self::EndTimeNow,
+			HotkeyAction::ToggleMissingGiveawayInfo => Self::ToggleMissingGiveawayInfo,
 		}
 	}
 }
 
+/// A hotkey a user chose for an action
+#[derive(Insertable, Queryable)]
+pub struct UserHotkey {
+	/// ID of the user who chose the hotkey
+	pub user_id: String,
+	/// The action performed by the hotkey
+	pub action: HotkeyAction,
+	/// The physical key pressed for the hotkey, as given by the browser
+	pub key_code: String,
+	/// Whether Alt is held for the hotkey
+	pub alt: bool,
+	/// Whether Ctrl is held for the hotkey
+	pub ctrl: bool,
+	/// Whether Shift is held for the hotkey
+	pub shift: bool,
+}
+
+impl UserHotkey {
+	pub fn from_binding(user_id: String, binding: HotkeyBinding) -> Self {
+		Self {
+			user_id,
+			action: binding.action.into(),
+			key_code: binding.hotkey.code,
+			alt: binding.hotkey.alt,
+			ctrl: binding.hotkey.ctrl,
+			shift: binding.hotkey.shift,
+		}
+	}
+}
+
+impl From<UserHotkey> for HotkeyBinding {
+	fn from(value: UserHotkey) -> Self {
+		Self {
+			action: value.action.into(),
+			hotkey: Hotkey {
+				code: value.key_code,
+				alt: value.alt,
+				ctrl: value.ctrl,
+				shift: value.shift,
+			},
+		}
+	}
+}
+
+/// A named set of event log filters saved by a user
+#[derive(Insertable, Queryable)]
+pub struct UserViewPreference {
+	/// ID of the preset
+	pub id: String,
+	/// ID of the user who saved the preset
+	pub user_id: String,
+	/// The name the user gave the preset
+	pub name: String,
+	pub video_edit_states: Vec<Option<VideoEditState>>,
+	pub video_processing_states: Vec<Option<VideoProcessingState>>,
+	pub created_by_me: bool,
+	pub assigned_to_me: bool,
+	pub over_expected_duration: bool,
+	pub missing_giveaway_information: bool,
+}
+
+impl UserViewPreference {
+	pub fn from_preset(user_id: String, preset: FilterPreset) -> Self {
+		let filters = preset.filters;
+		Self {
+			id: preset.id,
+			user_id,
+			name: preset.name,
+			video_edit_states: filters
+				.video_edit_states
+				.into_iter()
+				.map(|state| Some(state.into()))
+				.collect(),
+			video_processing_states: filters
+				.video_processing_states
+				.into_iter()
+				.map(|state| Some(state.into()))
+				.collect(),
+			created_by_me: filters.created_by_me,
+			assigned_to_me: filters.assigned_to_me,
+			over_expected_duration: filters.over_expected_duration,
+			missing_giveaway_information: filters.missing_giveaway_information,
+		}
+	}
+}
+
+impl From<UserViewPreference> for FilterPreset {
+	fn from(value: UserViewPreference) -> Self {
+		let filters = EventLogFilters {
+			video_edit_states: value
+				.video_edit_states
+				.into_iter()
+				.flatten()
+				.map(|state| state.into())
+				.collect(),
+			video_processing_states: value
+				.video_processing_states
+				.into_iter()
+				.flatten()
+				.map(|state| state.into())
+				.collect(),
+			created_by_me: value.created_by_me,
+			assigned_to_me: value.assigned_to_me,
+			over_expected_duration: value.over_expected_duration,
+			missing_giveaway_information: value.missing_giveaway_information,
+		};
+		Self {
+			id: value.id,
+			name: value.name,
+			filters,
+		}
+	}
+}
+
+/// A record of an administrator changing another user's profile settings
+#[derive(Insertable, Queryable)]
+pub struct UserProfileAdminEdit {
+	/// ID of the record
+	pub id: String,
+	/// ID of the user whose profile was changed
+	pub edited_user: String,
+	/// ID of the administrator who made the change
+	pub edit_user: String,
+	/// When the change was made
+	pub edit_time: DateTime<Utc>,
+	/// The red color value for the user's color before the change
+	pub old_color_red: i32,
+	/// The green color value for the user's color before the change
+	pub old_color_green: i32,
+	/// The blue color value for the user's color before the change
+	pub old_color_blue: i32,
+	/// The user's spell check setting before the change
+	pub old_use_spell_check: bool,
+	/// The red color value for the user's color after the change
+	pub new_color_red: i32,
+	/// The green color value for the user's color after the change
+	pub new_color_green: i32,
+	/// The blue color value for the user's color after the change
+	pub new_color_blue: i32,
+	/// The user's spell check setting after the change
+	pub new_use_spell_check: bool,
+}
+
+/// A record of a user's username being changed
+#[derive(Insertable, Queryable)]
+pub struct UsernameChange {
+	/// ID of the record
+	pub id: String,
+	/// ID of the user whose username was changed
+	pub user_id: String,
+	/// ID of the user who made the change. This is the user themselves unless an administrator renamed them.
+	pub changed_by: String,
+	/// When the change was made
+	pub change_time: DateTime<Utc>,
+	/// The username before the change
+	pub old_name: String,
+	/// The username after the change
+	pub new_name: String,
+}
+
+/// An avatar image uploaded by a user
+#[derive(Insertable, Queryable)]
+pub struct UserAvatar {
+	/// ID of the user whose avatar this is
+	pub user_id: String,
+	/// The MIME type of the image
+	pub content_type: String,
+	/// The image data
+	pub image: Vec<u8>,
+}
+
 /// Database information about an event
-#[derive(Clone, Insertable, Queryable)]
+#[derive(Clone, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct Event {
 	/// Event's ID
 	pub id: String,
@@ -232,6 +518,20 @@ pub struct Event {
 	pub editor_link_format: String,
 	/// The name of the first tab to show in the UI for log entries that occur before the first configured tab
 	pub first_tab_name: String,
+	/// The maximum number of characters allowed in entry descriptions for the event
+	pub description_max_length: Option<i32>,
+	/// Whether removing and replacing tags requires approval from a second supervisor
+	#[serde(default)]
+	pub require_tag_deletion_approval: bool,
+	/// When the event is scheduled to end
+	#[serde(default)]
+	pub end_time: Option<DateTime<Utc>>,
+	/// Whether the event is archived, which prevents changes to its log
+	#[serde(default)]
+	pub archived: bool,
+	/// The time zone in which the event's log shows wall-clock times by default, or None to show event-relative times
+	#[serde(default)]
+	pub time_display_zone: Option<String>,
 }
 
 impl From<Event> for EventWs {
@@ -240,14 +540,19 @@ impl From<Event> for EventWs {
 			id: event.id,
 			name: event.name,
 			start_time: event.start_time,
+			end_time: event.end_time,
 			editor_link_format: event.editor_link_format,
 			first_tab_name: event.first_tab_name,
+			description_max_length: event.description_max_length,
+			require_tag_deletion_approval: event.require_tag_deletion_approval,
+			archived: event.archived,
+			time_display: TimeDisplay::from_time_zone(event.time_display_zone),
 		}
 	}
 }
 
 /// Database information about a permission group, which links users to events for which they have permission
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct PermissionGroup {
 	/// Permission group's ID
 	pub id: String,
@@ -264,7 +569,7 @@ impl From<PermissionGroup> for PermissionGroupWs {
 }
 
 /// Linkage between an event and a permission group
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct PermissionEvent {
 	/// The ID of the permission group that has permissions for the event
 	pub permission_group: String,
@@ -274,6 +579,17 @@ pub struct PermissionEvent {
 	pub level: Permission,
 }
 
+/// A permission level granted to a user for an event directly, rather than through a permission group
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+pub struct UserEventPermission {
+	/// The ID of the user to whom the permission is granted
+	pub user_id: String,
+	/// The ID of the event for which the permission is granted
+	pub event: String,
+	/// The permission to grant
+	pub level: Permission,
+}
+
 impl From<PermissionEvent> for PermissionGroupEventAssociation {
 	fn from(value: PermissionEvent) -> Self {
 		let group = value.permission_group;
@@ -296,8 +612,19 @@ pub struct UserPermission {
 	pub permission_group: String,
 }
 
+/// A user's pending request for access to an event
+#[derive(Insertable, Queryable)]
+pub struct EventAccessRequest {
+	/// ID of the user requesting access
+	pub user_id: String,
+	/// ID of the event to which the user is requesting access
+	pub event: String,
+	/// When the user made the request
+	pub requested_at: DateTime<Utc>,
+}
+
 /// Database information on an event log entry type
-#[derive(Clone, Insertable, Queryable)]
+#[derive(Clone, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct EntryType {
 	/// ID of the entry type
 	pub id: String,
@@ -314,6 +641,12 @@ pub struct EntryType {
 	/// Whether log entries with this type must have an end time specified
 	/// If true, the end time may be not entered yet but may not be "has no end time"
 	pub require_end_time: bool,
+	/// How long entries of this type are expected to last, if there's an expectation
+	#[serde(default)]
+	pub expected_duration_minutes: Option<i32>,
+	/// Group the entry type is listed under, if any
+	#[serde(default)]
+	pub group_name: Option<String>,
 }
 
 impl EntryType {
@@ -334,18 +667,22 @@ impl From<EntryType> for EntryTypeWs {
 		let name = value.name;
 		let description = value.description;
 		let require_end_time = value.require_end_time;
+		let expected_duration_minutes = value.expected_duration_minutes;
+		let group_name = value.group_name;
 		Self {
 			id,
 			name,
 			description,
 			color,
 			require_end_time,
+			expected_duration_minutes,
+			group_name,
 		}
 	}
 }
 
 /// Database linkage for an entry type being available for an event
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 #[diesel(table_name = available_entry_types_for_event)]
 pub struct AvailableEntryType {
 	/// Entry type being made available to the event
@@ -355,7 +692,7 @@ pub struct AvailableEntryType {
 }
 
 /// Database information on a tag
-#[derive(AsChangeset, Clone, Insertable, Queryable)]
+#[derive(AsChangeset, Clone, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct Tag {
 	/// ID of the tag
 	pub id: String,
@@ -376,6 +713,12 @@ pub struct Tag {
 	/// Whether the playlist automatically populated from this tag should appear in video descriptions.
 	/// If any playlist fields are populated, all must be populated.
 	pub playlist_shows_in_video_descriptions: Option<bool>,
+	/// The first day on which the tag is offered for new entries
+	#[serde(default)]
+	pub valid_from: Option<NaiveDate>,
+	/// The last day on which the tag is offered for new entries
+	#[serde(default)]
+	pub valid_to: Option<NaiveDate>,
 }
 
 impl From<Tag> for TagWs {
@@ -401,12 +744,14 @@ impl From<Tag> for TagWs {
 			name,
 			description,
 			playlist,
+			valid_from: value.valid_from,
+			valid_to: value.valid_to,
 		}
 	}
 }
 
 /// Database information on an event log entry
-#[derive(Clone, Insertable, Queryable)]
+#[derive(Clone, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 #[diesel(table_name = event_log)]
 pub struct EventLogEntry {
 	/// ID of the entry
@@ -454,6 +799,24 @@ pub struct EventLogEntry {
 	pub video_processing_state: VideoProcessingState,
 	/// ID of the entry type
 	pub entry_type: Option<String>,
+	/// ID of the user who created the entry, if created by a user
+	pub created_by: Option<String>,
+	/// Links (video link or media links) that failed the last dead link check. All values in the Vec should have
+	/// values.
+	#[serde(default)]
+	pub dead_links: Vec<Option<String>>,
+	/// When the entry's links were last checked for dead links. None if they haven't been checked since they last
+	/// changed.
+	#[serde(default)]
+	pub links_checked_at: Option<DateTime<Utc>>,
+	/// The entry's number in its event. Numbers are assigned by the server when the entry is created and only change
+	/// when a supervisor renumbers the event's entries.
+	#[serde(default)]
+	pub entry_number: i32,
+	/// ID of the entry's latest revision in its history. Kept up to date whenever a revision is recorded, so that
+	/// clients can say which version of the entry their changes were based on.
+	#[serde(default)]
+	pub latest_history_id: Option<String>,
 }
 
 impl EventLogEntry {
@@ -465,10 +828,48 @@ impl EventLogEntry {
 			(None, false) => EndTimeData::NoTime,
 		}
 	}
+
+	/// Converts the entry to the form sent to clients. Everything that sends entries to clients uses this, so that they
+	/// all map the stored data the same way.
+	pub fn into_sync_entry(
+		self,
+		tags: Vec<TagWs>,
+		editor: Option<PublicUserData>,
+		created_by: Option<PublicUserData>,
+		custom_fields: HashMap<String, String>,
+	) -> EventLogEntryWs {
+		let end_time = self.end_time_data();
+		EventLogEntryWs {
+			id: self.id,
+			start_time: Some(self.start_time),
+			end_time,
+			entry_type: self.entry_type,
+			description: self.description,
+			media_links: self.media_links.into_iter().flatten().collect(),
+			submitter_or_winner: self.submitter_or_winner,
+			tags,
+			notes: self.notes,
+			editor,
+			video_link: self.video_link,
+			parent: self.parent,
+			created_at: self.created_at,
+			created_by,
+			manual_sort_key: self.manual_sort_key,
+			video_processing_state: self.video_processing_state.into(),
+			video_errors: self.video_errors,
+			poster_moment: self.poster_moment,
+			video_edit_state: self.video_edit_state.into(),
+			missing_giveaway_information: self.missing_giveaway_information,
+			dead_links: self.dead_links.into_iter().flatten().collect(),
+			entry_number: Some(self.entry_number),
+			custom_fields,
+			history_id: self.latest_history_id,
+		}
+	}
 }
 
 /// A tag entered on an event log entry
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct EventLogTag {
 	/// ID of the tag
 	pub tag: String,
@@ -494,6 +895,7 @@ pub struct EventLogEntryChanges {
 	pub missing_giveaway_information: Option<bool>,
 	pub media_links: Option<Vec<Option<String>>>,
 	pub end_time_incomplete: Option<bool>,
+	pub links_checked_at: Option<Option<DateTime<Utc>>>,
 }
 
 impl EventLogEntryChanges {
@@ -516,7 +918,7 @@ impl EventLogEntryChanges {
 }
 
 /// A video editor for an event
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct EventEditor {
 	/// ID of an event for which this represents a video editor
 	pub event: String,
@@ -525,7 +927,7 @@ pub struct EventEditor {
 }
 
 /// A tab in the log of an event
-#[derive(Clone, Insertable, Queryable)]
+#[derive(Clone, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct EventLogTab {
 	/// ID of the tab
 	pub id: String,
@@ -554,21 +956,42 @@ pub struct Application {
 	pub write_links: bool,
 	/// ID of the user who created the application
 	pub creation_user: String,
+	/// Whether the application can set video processing states and errors
+	pub write_processing_state: bool,
+	/// Whether the application can access only the events associated with it in [ApplicationEvent] records
+	pub restrict_events: bool,
+	/// Whether the application can edit log entries
+	pub write_entries: bool,
 }
 
-impl From<Application> for ApplicationWs {
-	fn from(value: Application) -> Self {
-		Self {
-			id: value.id,
-			name: value.name,
-			read_log: value.read_log,
-			write_links: value.write_links,
+impl Application {
+	/// Converts the application to data for the client. The event scope should be the list of events the application
+	/// is associated with.
+	pub fn into_client_data(self, event_scope: Vec<String>) -> ApplicationWs {
+		let event_scope = if self.restrict_events { Some(event_scope) } else { None };
+		ApplicationWs {
+			id: self.id,
+			name: self.name,
+			read_log: self.read_log,
+			write_links: self.write_links,
+			write_processing_state: self.write_processing_state,
+			write_entries: self.write_entries,
+			event_scope,
 		}
 	}
 }
 
-/// Database information on a historical revision of an event log entry
+/// Associates an application with an event it can access
 #[derive(Insertable, Queryable)]
+pub struct ApplicationEvent {
+	/// ID of the application
+	pub application: String,
+	/// ID of the event
+	pub event: String,
+}
+
+/// Database information on a historical revision of an event log entry
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 #[diesel(table_name = event_log_history)]
 pub struct EventLogHistoryEntry {
 	/// ID of the history entry
@@ -600,6 +1023,8 @@ pub struct EventLogHistoryEntry {
 	pub end_time_incomplete: bool,
 	pub video_processing_state: VideoProcessingState,
 	pub entry_type: Option<String>,
+	/// The ID of the tag whose video edit state rule set the video edit state in this revision, if any
+	pub video_edit_state_rule_tag: Option<String>,
 }
 
 /// The source of an edit
@@ -643,12 +1068,13 @@ impl EventLogHistoryEntry {
 			video_edit_state: entry.video_edit_state,
 			missing_giveaway_information: entry.missing_giveaway_information,
 			end_time_incomplete: entry.end_time_incomplete,
+			video_edit_state_rule_tag: None,
 		}
 	}
 }
 
 /// A tag associated with an event log entry history entry
-#[derive(Insertable, Queryable)]
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct EventLogHistoryTag {
 	/// ID of the tag
 	pub tag: String,
@@ -656,8 +1082,36 @@ pub struct EventLogHistoryTag {
 	pub history_log_entry: String,
 }
 
-/// An info page for event-related information
+/// A rule automatically setting the video edit state of entries to which a tag is added
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+pub struct VideoEditStateTagRule {
+	/// ID of the tag that triggers the rule
+	pub tag: String,
+	/// ID of the event to which the tag belongs
+	pub event: String,
+	/// The video edit state to set for entries getting the tag
+	pub video_edit_state: VideoEditState,
+}
+
+/// A tag removal or replacement waiting for approval by a second supervisor
 #[derive(Insertable, Queryable)]
+pub struct PendingTagAction {
+	/// ID of the pending action
+	pub id: String,
+	/// ID of the event to which the tag belongs
+	pub event: String,
+	/// ID of the tag to remove
+	pub tag: String,
+	/// ID of the tag replacing the removed tag on entries, if the tag is being replaced
+	pub replacement_tag: Option<String>,
+	/// ID of the user who requested the change
+	pub requested_by: String,
+	/// When the change was requested
+	pub requested_at: DateTime<Utc>,
+}
+
+/// An info page for event-related information
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
 pub struct InfoPage {
 	/// ID of the page
 	pub id: String,
@@ -667,6 +1121,10 @@ pub struct InfoPage {
 	pub title: String,
 	/// Contents of the page, with Markdown formatting
 	pub contents: String,
+	/// The lowest permission level for the event needed to see the page, or None if everyone with access to the event
+	/// can see it
+	#[serde(default)]
+	pub minimum_permission: Option<Permission>,
 }
 
 impl From<InfoPageWs> for InfoPage {
@@ -676,10 +1134,236 @@ impl From<InfoPageWs> for InfoPage {
 			event: page.event.id,
 			title: page.title,
 			contents: page.contents,
+			minimum_permission: page.minimum_permission.map(|permission| permission.into()),
+		}
+	}
+}
+
+/// A URL to which log entry changes for an event are sent
+#[derive(Insertable, Queryable)]
+pub struct EventWebhook {
+	/// ID of the webhook
+	pub id: String,
+	/// ID of the event for which changes are sent
+	pub event: String,
+	/// The URL to which changes are posted
+	pub url: String,
+}
+
+/// A record of a change to an event that the server refused to make
+#[derive(Insertable, Queryable)]
+pub struct RejectedEventUpdate {
+	/// ID of the record
+	pub id: String,
+	/// ID of the event the change was for
+	pub event: String,
+	/// ID of the user who requested the change
+	pub user_id: String,
+	/// ID of the log entry the change was for, if it was for an entry
+	pub log_entry: Option<String>,
+	/// A short description of the kind of change
+	pub update_kind: String,
+	/// Why the change was refused
+	pub reason: String,
+	/// When the change was refused
+	pub rejected_at: DateTime<Utc>,
+}
+
+/// The part of the site configuration an administrative action changed, as stored in the database
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::AdminActionType"]
+pub enum AdminActionType {
+	Event,
+	EntryType,
+	PermissionGroup,
+	UserPermissionGroup,
+	User,
+	Editor,
+	EventLogTab,
+	Application,
+	InfoPage,
+	AccessRequest,
+	Webhook,
+	ShareLink,
+}
+
+impl From<AdminActionTypeWs> for AdminActionType {
+	fn from(value: AdminActionTypeWs) -> Self {
+		match value {
+			AdminActionTypeWs::Event => Self::Event,
+			AdminActionTypeWs::EntryType => Self::EntryType,
+			AdminActionTypeWs::PermissionGroup => Self::PermissionGroup,
+			AdminActionTypeWs::UserPermissionGroup => Self::UserPermissionGroup,
+			AdminActionTypeWs::User => Self::User,
+			AdminActionTypeWs::Editor => Self::Editor,
+			AdminActionTypeWs::EventLogTab => Self::EventLogTab,
+			AdminActionTypeWs::Application => Self::Application,
+			AdminActionTypeWs::InfoPage => Self::InfoPage,
+			AdminActionTypeWs::AccessRequest => Self::AccessRequest,
+			AdminActionTypeWs::Webhook => Self::Webhook,
+			AdminActionTypeWs::ShareLink => Self::ShareLink,
+		}
+	}
+}
+
+impl From<AdminActionType> for AdminActionTypeWs {
+	fn from(value: AdminActionType) -> Self {
+		match value {
+			AdminActionType::Event => Self::Event,
+			AdminActionType::EntryType => Self::EntryType,
+			AdminActionType::PermissionGroup => Self::PermissionGroup,
+			AdminActionType::UserPermissionGroup => Self::UserPermissionGroup,
+			AdminActionType::User => Self::User,
+			AdminActionType::Editor => Self::Editor,
+			AdminActionType::EventLogTab => Self::EventLogTab,
+			AdminActionType::Application => Self::Application,
+			AdminActionType::InfoPage => Self::InfoPage,
+			AdminActionType::AccessRequest => Self::AccessRequest,
+			AdminActionType::Webhook => Self::Webhook,
+			AdminActionType::ShareLink => Self::ShareLink,
+		}
+	}
+}
+
+/// A record of a change an administrator made
+#[derive(Insertable, Queryable)]
+#[diesel(table_name = admin_audit_log)]
+pub struct AdminAuditLogEntry {
+	/// ID of the record
+	pub id: String,
+	/// ID of the administrator who made the change
+	pub actor: String,
+	/// The part of the configuration that was changed
+	pub action_type: AdminActionType,
+	/// A description of the change
+	pub description: String,
+	/// When the change was made
+	pub performed_at: DateTime<Utc>,
+}
+
+/// Anonymous feedback sent for an event
+#[derive(Insertable, Queryable)]
+#[diesel(table_name = event_feedback)]
+pub struct EventFeedback {
+	/// ID of the feedback
+	pub id: String,
+	/// ID of the event the feedback is for
+	pub event: String,
+	/// The feedback message
+	pub message: String,
+	/// When the feedback was sent
+	pub submitted_at: DateTime<Utc>,
+}
+
+impl From<EventFeedback> for EventFeedbackWs {
+	fn from(value: EventFeedback) -> Self {
+		Self {
+			id: value.id,
+			message: value.message,
+			submitted_at: value.submitted_at,
+		}
+	}
+}
+
+/// A template for quickly filling in a kind of entry that comes up often in an event
+#[derive(AsChangeset, Insertable, Queryable)]
+pub struct EntryTemplate {
+	/// ID of the template
+	pub id: String,
+	/// ID of the event the template is used in
+	pub event: String,
+	/// The name of the template shown when choosing one
+	pub name: String,
+	/// ID of the entry type the template fills in, if any
+	pub entry_type: Option<String>,
+	/// The description the template fills in
+	pub description: String,
+}
+
+/// A tag filled in by an entry template
+#[derive(Insertable, Queryable)]
+pub struct EntryTemplateTag {
+	/// ID of the template
+	pub template: String,
+	/// ID of the tag
+	pub tag: String,
+}
+
+/// An entry added to an event's log automatically at a regular interval
+#[derive(AsChangeset, Insertable, Queryable)]
+pub struct RecurringEntry {
+	/// ID of the recurring entry
+	pub id: String,
+	/// ID of the event the entries are added to
+	pub event: String,
+	/// ID of the entry type given to the created entries, if any
+	pub entry_type: Option<String>,
+	/// The description given to the created entries
+	pub description: String,
+	/// The number of minutes between created entries
+	pub interval_minutes: i32,
+	/// The start time of the first created entry
+	pub first_time: DateTime<Utc>,
+	/// The latest time at which an entry can be created
+	pub last_time: DateTime<Utc>,
+	/// The start time of the next entry to be created
+	pub next_time: DateTime<Utc>,
+	/// ID of the user who last set up the recurring entry, who is recorded as the creator of the created entries
+	pub created_by: String,
+}
+
+/// Records that the log entry for an occurrence of a recurring entry was created, so that it's only created once
+#[derive(Insertable, Queryable)]
+#[diesel(table_name = recurring_entry_occurrences)]
+pub struct RecurringEntryOccurrence {
+	/// ID of the recurring entry
+	pub recurring_entry: String,
+	/// The start time of the created entry
+	pub occurrence_time: DateTime<Utc>,
+}
+
+/// A background job that hasn't finished yet, stored so that it's picked up again if the server restarts. Exactly one
+/// of the sets of job data is present.
+#[derive(Insertable, Queryable)]
+pub struct QueuedJob {
+	/// ID of the job
+	pub id: String,
+	/// For webhook deliveries, the URL to deliver to
+	pub webhook_url: Option<String>,
+	/// For webhook deliveries, the payload to deliver
+	pub webhook_body: Option<Vec<u8>>,
+	/// For recurring entries, ID of the recurring entry
+	pub recurring_entry: Option<String>,
+	/// For recurring entries, the start time of the entry to create
+	pub occurrence_time: Option<DateTime<Utc>>,
+	/// The number of times the job has been started
+	pub attempts: i32,
+	/// When the job was first queued
+	pub queued_at: DateTime<Utc>,
+}
+
+impl From<RecurringEntry> for RecurringEntryWs {
+	fn from(value: RecurringEntry) -> Self {
+		Self {
+			id: value.id,
+			entry_type: value.entry_type,
+			description: value.description,
+			interval_minutes: value.interval_minutes,
+			first_time: value.first_time,
+			last_time: value.last_time,
 		}
 	}
 }
 
+/// A token allowing the event log to be viewed publicly
+#[derive(Insertable, Queryable)]
+pub struct EventShareLink {
+	/// ID of the event that can be viewed
+	pub event: String,
+	/// The token used in the public view URL
+	pub token: String,
+}
+
 /// A user session
 #[derive(Insertable, Queryable)]
 pub struct Session {
@@ -687,4 +1371,198 @@ pub struct Session {
 	pub id: String,
 	/// Session data
 	pub data: String,
+	/// The OpenID Connect user ID of the user who most recently connected using the session
+	pub openid_user_id: Option<String>,
+	/// The User-Agent header of the browser that most recently connected using the session
+	pub user_agent: String,
+	pub created_at: DateTime<Utc>,
+	pub last_active_at: DateTime<Utc>,
+}
+
+/// The kind of a user notification, as stored in the database
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::NotificationType"]
+pub enum NotificationType {
+	VideoProcessingStateChanged,
+	EditorAssigned,
+	AddedToEvent,
+	MissingGiveawayInformation,
+}
+
+/// A notice to a user about something relevant to them
+#[derive(Insertable, Queryable)]
+pub struct Notification {
+	/// ID of the notification
+	pub id: String,
+	/// ID of the user the notification is for
+	pub user_id: String,
+	/// ID of the event the notification is about
+	pub event: String,
+	/// The kind of notification
+	pub notification_type: NotificationType,
+	/// ID of the log entry the notification is about, if any
+	pub log_entry: Option<String>,
+	/// The description of the log entry at the time the notification was sent
+	pub entry_description: String,
+	/// For video processing state notifications, the new state
+	pub video_processing_state: Option<VideoProcessingState>,
+	/// When the notification was sent
+	pub created_at: DateTime<Utc>,
+	/// Whether the user has marked the notification as read
+	pub is_read: bool,
+}
+
+impl Notification {
+	/// Creates a new unread notification with the given details
+	pub fn new(user_id: String, event: String, details: &UserNotificationDetails) -> Self {
+		let (notification_type, log_entry, entry_description, video_processing_state) = match details {
+			UserNotificationDetails::VideoProcessingStateChanged {
+				entry_id,
+				entry_description,
+				state,
+			} => (
+				NotificationType::VideoProcessingStateChanged,
+				Some(entry_id.clone()),
+				entry_description.clone(),
+				Some((*state).into()),
+			),
+			UserNotificationDetails::EditorAssigned {
+				entry_id,
+				entry_description,
+			} => (
+				NotificationType::EditorAssigned,
+				Some(entry_id.clone()),
+				entry_description.clone(),
+				None,
+			),
+			UserNotificationDetails::AddedToEvent => (NotificationType::AddedToEvent, None, String::new(), None),
+			UserNotificationDetails::MissingGiveawayInformation {
+				entry_id,
+				entry_description,
+			} => (
+				NotificationType::MissingGiveawayInformation,
+				Some(entry_id.clone()),
+				entry_description.clone(),
+				None,
+			),
+		};
+		Self {
+			id: cuid2::create_id(),
+			user_id,
+			event,
+			notification_type,
+			log_entry,
+			entry_description,
+			video_processing_state,
+			created_at: Utc::now(),
+			is_read: false,
+		}
+	}
+
+	/// Converts the notification to the form sent to clients
+	pub fn into_user_notification(self, event: EventWs) -> UserNotification {
+		let entry_id = self.log_entry.unwrap_or_default();
+		let entry_description = self.entry_description;
+		let details = match self.notification_type {
+			NotificationType::VideoProcessingStateChanged => UserNotificationDetails::VideoProcessingStateChanged {
+				entry_id,
+				entry_description,
+				state: self
+					.video_processing_state
+					.map(|state| state.into())
+					.unwrap_or_default(),
+			},
+			NotificationType::EditorAssigned => UserNotificationDetails::EditorAssigned {
+				entry_id,
+				entry_description,
+			},
+			NotificationType::AddedToEvent => UserNotificationDetails::AddedToEvent,
+			NotificationType::MissingGiveawayInformation => UserNotificationDetails::MissingGiveawayInformation {
+				entry_id,
+				entry_description,
+			},
+		};
+		UserNotification {
+			id: self.id,
+			time: self.created_at,
+			event,
+			details,
+			read: self.is_read,
+		}
+	}
+}
+
+/// The kind of value a custom field holds, as stored in the database
+#[derive(Clone, Copy, DbEnum, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[ExistingTypePath = "crate::schema::sql_types::CustomFieldType"]
+pub enum CustomFieldType {
+	Text,
+	Number,
+	Checkbox,
+	Select,
+}
+
+/// An extra field added to the entries of an event
+#[derive(AsChangeset, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+pub struct EventCustomField {
+	/// ID of the field
+	pub id: String,
+	/// ID of the event whose entries have the field
+	pub event: String,
+	/// The name of the field shown to users
+	pub name: String,
+	/// The kind of value the field holds
+	pub field_type: CustomFieldType,
+	/// The options that can be chosen for select fields. Empty for other field types. All values in the Vec should
+	/// have values.
+	pub select_options: Vec<Option<String>>,
+}
+
+impl EventCustomField {
+	/// Creates a database field from the data sent by clients
+	pub fn from_sync_field(field: &CustomFieldWs, event_id: String) -> Self {
+		let (field_type, select_options) = match &field.field_type {
+			CustomFieldTypeWs::Text => (CustomFieldType::Text, Vec::new()),
+			CustomFieldTypeWs::Number => (CustomFieldType::Number, Vec::new()),
+			CustomFieldTypeWs::Checkbox => (CustomFieldType::Checkbox, Vec::new()),
+			CustomFieldTypeWs::Select(options) => (
+				CustomFieldType::Select,
+				options.iter().map(|option| Some(option.clone())).collect(),
+			),
+		};
+		Self {
+			id: field.id.clone(),
+			event: event_id,
+			name: field.name.clone(),
+			field_type,
+			select_options,
+		}
+	}
+}
+
+impl From<EventCustomField> for CustomFieldWs {
+	fn from(field: EventCustomField) -> Self {
+		let field_type = match field.field_type {
+			CustomFieldType::Text => CustomFieldTypeWs::Text,
+			CustomFieldType::Number => CustomFieldTypeWs::Number,
+			CustomFieldType::Checkbox => CustomFieldTypeWs::Checkbox,
+			CustomFieldType::Select => CustomFieldTypeWs::Select(field.select_options.into_iter().flatten().collect()),
+		};
+		Self {
+			id: field.id,
+			name: field.name,
+			field_type,
+		}
+	}
+}
+
+/// The value of a custom field for a log entry
+#[derive(Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+pub struct EventLogCustomFieldValue {
+	/// ID of the log entry
+	pub log_entry: String,
+	/// ID of the custom field
+	pub field: String,
+	/// The entered value
+	pub value: String,
 }
@@ -0,0 +1,51 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::ServerPlugin;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::ModifiedEventLogEntryParts;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::tags::Tag;
+use stream_log_shared::messages::user::SelfUserData;
+
+/// Writes a line to the server log for each change. Mostly useful as a starting point for writing other plugins.
+pub struct EntryLoggerPlugin;
+
+impl ServerPlugin for EntryLoggerPlugin {
+	fn name(&self) -> &str {
+		"Entry Logger"
+	}
+
+	fn entry_created(&self, event: &Event, entry: &EventLogEntry, user: &SelfUserData) {
+		tide::log::info!(
+			"[{}] {} created entry {}: {}",
+			event.name,
+			user.username,
+			entry.id,
+			entry.description
+		);
+	}
+
+	fn entry_updated(
+		&self,
+		event: &Event,
+		entry: &EventLogEntry,
+		modified_parts: &[ModifiedEventLogEntryParts],
+		user: &SelfUserData,
+	) {
+		tide::log::info!(
+			"[{}] {} updated entry {} ({} changed parts)",
+			event.name,
+			user.username,
+			entry.id,
+			modified_parts.len()
+		);
+	}
+
+	fn tag_created(&self, event: &Event, tag: &Tag, user: &SelfUserData) {
+		tide::log::info!("[{}] {} created tag {}", event.name, user.username, tag.name);
+	}
+}
@@ -0,0 +1,89 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Plugins are compiled into the server to add site-specific behavior when log data changes. Each plugin lives in its
+//! own module behind a Cargo feature and is added to the registry in [`PluginRegistry::new`] when that feature is
+//! enabled.
+
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::ModifiedEventLogEntryParts;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::tags::Tag;
+use stream_log_shared::messages::user::SelfUserData;
+
+#[cfg(feature = "entry-logger-plugin")]
+mod entry_logger;
+
+/// A server extension notified when log data changes. Hooks are called after the change has been saved to the
+/// database and before it's sent to subscribed clients. Hooks run on the task handling the user's connection, so
+/// anything slow (such as network requests) should be moved to a separate task.
+pub trait ServerPlugin: Send + Sync {
+	/// The name used to refer to the plugin in log messages
+	fn name(&self) -> &str;
+
+	/// Called when a user adds a new entry to an event log
+	fn entry_created(&self, _event: &Event, _entry: &EventLogEntry, _user: &SelfUserData) {}
+
+	/// Called when a user changes an existing event log entry. `modified_parts` lists the parts of the entry the user
+	/// changed.
+	fn entry_updated(
+		&self,
+		_event: &Event,
+		_entry: &EventLogEntry,
+		_modified_parts: &[ModifiedEventLogEntryParts],
+		_user: &SelfUserData,
+	) {
+	}
+
+	/// Called when a tag is added to an event, either created by a user or copied from another event
+	fn tag_created(&self, _event: &Event, _tag: &Tag, _user: &SelfUserData) {}
+}
+
+/// The set of plugins compiled into the server
+pub struct PluginRegistry {
+	plugins: Vec<Box<dyn ServerPlugin>>,
+}
+
+impl PluginRegistry {
+	/// Creates the registry containing all plugins enabled for this build
+	pub fn new() -> Self {
+		#[allow(unused_mut)]
+		let mut plugins: Vec<Box<dyn ServerPlugin>> = Vec::new();
+
+		#[cfg(feature = "entry-logger-plugin")]
+		plugins.push(Box::new(entry_logger::EntryLoggerPlugin));
+
+		for plugin in plugins.iter() {
+			tide::log::info!("Loaded plugin: {}", plugin.name());
+		}
+
+		Self { plugins }
+	}
+
+	pub fn entry_created(&self, event: &Event, entry: &EventLogEntry, user: &SelfUserData) {
+		for plugin in self.plugins.iter() {
+			plugin.entry_created(event, entry, user);
+		}
+	}
+
+	pub fn entry_updated(
+		&self,
+		event: &Event,
+		entry: &EventLogEntry,
+		modified_parts: &[ModifiedEventLogEntryParts],
+		user: &SelfUserData,
+	) {
+		for plugin in self.plugins.iter() {
+			plugin.entry_updated(event, entry, modified_parts, user);
+		}
+	}
+
+	pub fn tag_created(&self, event: &Event, tag: &Tag, user: &SelfUserData) {
+		for plugin in self.plugins.iter() {
+			plugin.tag_created(event, tag, user);
+		}
+	}
+}
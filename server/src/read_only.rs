@@ -0,0 +1,25 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use tide::http::Method;
+use tide::{Middleware, Next, Request, Response, StatusCode};
+
+/// Rejects all HTTP requests that could change data while the server is running in read-only mode
+pub struct ReadOnlyMiddleware;
+
+#[tide::utils::async_trait]
+impl Middleware<()> for ReadOnlyMiddleware {
+	async fn handle(&self, request: Request<()>, next: Next<'_, ()>) -> tide::Result {
+		match request.method() {
+			Method::Get | Method::Head | Method::Options => Ok(next.run(request).await),
+			_ => {
+				let mut response = Response::new(StatusCode::ServiceUnavailable);
+				response.set_body("This server is read-only, so changes can't be made here.");
+				Ok(response)
+			}
+		}
+	}
+}
@@ -0,0 +1,258 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::data_sync::{run_db_operation, SubscriptionManager};
+use crate::database::log_lost_db_connection;
+use crate::jobs::{JobKind, JobQueue};
+use crate::models::{
+	EditSource, Event as EventDb, EventLogEntry as EventLogEntryDb, EventLogHistoryEntry,
+	RecurringEntry as RecurringEntryDb, RecurringEntryOccurrence, User, VideoEditState, VideoProcessingState,
+};
+use crate::schema::{
+	available_entry_types_for_event, event_log, event_log_history, events, recurring_entries,
+	recurring_entry_occurrences, users,
+};
+use async_std::sync::{Arc, Mutex};
+use async_std::task::sleep;
+use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::HashMap;
+use std::time::Duration;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::events::Event;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+
+/// How long to wait between looking for recurring entries that are due to be created
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Gets the first time a recurring entry occurs at or after the given time
+pub fn next_occurrence(first_time: DateTime<Utc>, interval_minutes: i32, after: DateTime<Utc>) -> DateTime<Utc> {
+	if first_time >= after {
+		return first_time;
+	}
+	let interval_minutes = i64::from(interval_minutes);
+	let elapsed_minutes = (after - first_time).num_minutes();
+	let mut occurrence = first_time + TimeDelta::minutes(elapsed_minutes / interval_minutes * interval_minutes);
+	if occurrence < after {
+		occurrence += TimeDelta::minutes(interval_minutes);
+	}
+	occurrence
+}
+
+/// Gets the latest time a recurring entry occurs at or before the given time. Occurrences missed while the server
+/// wasn't running aren't made up, since a burst of old entries wouldn't be useful to anyone; only the most recent one
+/// is created.
+fn latest_occurrence(recurring_entry: &RecurringEntryDb, now: DateTime<Utc>) -> DateTime<Utc> {
+	let occurrence = next_occurrence(recurring_entry.next_time, recurring_entry.interval_minutes, now);
+	if occurrence > now {
+		occurrence - TimeDelta::minutes(recurring_entry.interval_minutes.into())
+	} else {
+		occurrence
+	}
+}
+
+/// Rounds a time down to the minute, which is the granularity with which entry times are stored
+pub fn truncate_to_minute(time: DateTime<Utc>) -> DateTime<Utc> {
+	time.with_second(0)
+		.and_then(|time| time.with_nanosecond(0))
+		.unwrap_or(time)
+}
+
+/// Periodically looks for recurring entries that have come due and queues jobs to add their log entries
+pub async fn run_recurring_entry_creator(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	job_queue: Arc<JobQueue>,
+) {
+	loop {
+		queue_due_entries(&db_connection_pool, &job_queue).await;
+		sleep(POLL_INTERVAL).await;
+	}
+}
+
+async fn queue_due_entries(db_connection_pool: &Pool<ConnectionManager<PgConnection>>, job_queue: &JobQueue) {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => {
+			log_lost_db_connection(error);
+			return;
+		}
+	};
+
+	let now = Utc::now();
+	let due_entries: QueryResult<Vec<RecurringEntryDb>> = recurring_entries::table
+		.filter(
+			recurring_entries::next_time
+				.le(now)
+				.and(recurring_entries::next_time.le(recurring_entries::last_time)),
+		)
+		.load(&mut *db_connection);
+	let due_entries = match due_entries {
+		Ok(entries) => entries,
+		Err(error) => {
+			tide::log::error!("Database error loading due recurring entries: {}", error);
+			return;
+		}
+	};
+
+	for recurring_entry in due_entries {
+		let occurrence_time = latest_occurrence(&recurring_entry, now);
+		job_queue
+			.enqueue(JobKind::RecurringEntry {
+				recurring_entry,
+				occurrence_time,
+			})
+			.await;
+	}
+}
+
+/// Adds the log entry for an occurrence of a recurring entry that has come due, sending the created entry to everyone
+/// subscribed to its event. Returns a description of the problem if the entry couldn't be created. Trying again is
+/// safe, since each occurrence is recorded when its entry is created and an entry isn't created for it again.
+pub async fn create_recurring_entry(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	subscription_manager: &Mutex<SubscriptionManager>,
+	recurring_entry_id: &str,
+	occurrence_time: DateTime<Utc>,
+) -> Result<(), String> {
+	let now = Utc::now();
+	let create_result = run_db_operation(db_connection_pool, "creating a recurring log entry", |db_connection| {
+		db_connection
+			.transaction(|db_connection| create_log_entry(db_connection, recurring_entry_id, occurrence_time, now))
+	})
+	.await;
+	let (event, entry) = match create_result {
+		Ok(Some(created)) => created,
+		Ok(None) => return Ok(()),
+		Err(error) => return Err(error.to_string()),
+	};
+
+	let subscription_manager = subscription_manager.lock().await;
+	let event_id = event.id.clone();
+	let message = SubscriptionData::EventUpdate(event, Box::new(EventSubscriptionData::UpdateLogEntry(entry, None)));
+	if let Err(error) = subscription_manager.broadcast_event_message(&event_id, message).await {
+		tide::log::error!("Failed to broadcast a recurring log entry: {}", error);
+	}
+	Ok(())
+}
+
+/// Creates the log entry for an occurrence of a recurring entry and moves the recurring entry on to its next
+/// occurrence. No entry is created for archived events, or if the entry for the occurrence was already created.
+fn create_log_entry(
+	db_connection: &mut PgConnection,
+	recurring_entry_id: &str,
+	occurrence_time: DateTime<Utc>,
+	now: DateTime<Utc>,
+) -> QueryResult<Option<(Event, EventLogEntry)>> {
+	// The recurring entry may have been deleted since the job was queued, in which case there's nothing to do
+	let Some(recurring_entry): Option<RecurringEntryDb> = recurring_entries::table
+		.find(recurring_entry_id)
+		.for_update()
+		.first(db_connection)
+		.optional()?
+	else {
+		return Ok(None);
+	};
+	// A supervisor may have rescheduled the recurring entry since the job was queued, in which case the new schedule
+	// is handled on the next poll
+	if recurring_entry.next_time > occurrence_time {
+		return Ok(None);
+	}
+	let occurrence = RecurringEntryOccurrence {
+		recurring_entry: recurring_entry.id.clone(),
+		occurrence_time,
+	};
+	let inserted_count = diesel::insert_into(recurring_entry_occurrences::table)
+		.values(&occurrence)
+		.on_conflict_do_nothing()
+		.execute(db_connection)?;
+	if inserted_count == 0 {
+		return Ok(None);
+	}
+
+	// Lock the event while we pick the new entry's number so that entries created at the same time can't end up with
+	// the same number.
+	let event: EventDb = events::table
+		.find(&recurring_entry.event)
+		.for_update()
+		.first(db_connection)?;
+
+	let interval = TimeDelta::minutes(recurring_entry.interval_minutes.into());
+	let next_time = next_occurrence(occurrence_time + interval, recurring_entry.interval_minutes, now);
+	diesel::update(recurring_entries::table.find(&recurring_entry.id))
+		.set(recurring_entries::next_time.eq(next_time))
+		.execute(db_connection)?;
+	if event.archived || occurrence_time > recurring_entry.last_time {
+		return Ok(None);
+	}
+
+	// The entry type may have been removed from the event since the recurring entry was set up
+	let entry_type = match recurring_entry.entry_type.as_ref() {
+		Some(entry_type) => available_entry_types_for_event::table
+			.filter(
+				available_entry_types_for_event::event_id
+					.eq(&event.id)
+					.and(available_entry_types_for_event::entry_type.eq(entry_type)),
+			)
+			.select(available_entry_types_for_event::entry_type)
+			.first::<String>(db_connection)
+			.optional()?,
+		None => None,
+	};
+
+	let highest_entry_number: Option<i32> = event_log::table
+		.filter(event_log::event.eq(&event.id))
+		.select(max(event_log::entry_number))
+		.first(db_connection)?;
+	let mut db_entry = EventLogEntryDb {
+		id: cuid2::create_id(),
+		event: event.id.clone(),
+		start_time: occurrence_time,
+		end_time: None,
+		entry_type,
+		description: recurring_entry.description.clone(),
+		media_links: Vec::new(),
+		submitter_or_winner: String::new(),
+		notes: String::new(),
+		editor: None,
+		video_link: None,
+		parent: None,
+		deleted_by: None,
+		created_at: now,
+		manual_sort_key: None,
+		video_processing_state: VideoProcessingState::default(),
+		video_errors: String::new(),
+		poster_moment: false,
+		video_edit_state: VideoEditState::NoVideo,
+		missing_giveaway_information: false,
+		end_time_incomplete: true,
+		created_by: Some(recurring_entry.created_by.clone()),
+		dead_links: Vec::new(),
+		links_checked_at: None,
+		entry_number: highest_entry_number.unwrap_or(0) + 1,
+		latest_history_id: None,
+	};
+	let history_entry = EventLogHistoryEntry::new_from_event_log_entry(
+		&db_entry,
+		now,
+		EditSource::User(recurring_entry.created_by.clone()),
+	);
+	db_entry.latest_history_id = Some(history_entry.id.clone());
+
+	let new_row: EventLogEntryDb = diesel::insert_into(event_log::table)
+		.values(&db_entry)
+		.get_result(db_connection)?;
+	diesel::insert_into(event_log_history::table)
+		.values(&history_entry)
+		.execute(db_connection)?;
+	let created_by: User = users::table.find(&recurring_entry.created_by).first(db_connection)?;
+
+	let event: Event = event.into();
+	let entry = new_row.into_sync_entry(Vec::new(), None, Some(created_by.into()), HashMap::new());
+	Ok(Some((event, entry)))
+}
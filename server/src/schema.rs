@@ -1,6 +1,22 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+	#[derive(diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "admin_action_type"))]
+	pub struct AdminActionType;
+
+	#[derive(diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "custom_field_type"))]
+	pub struct CustomFieldType;
+
+	#[derive(diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "hotkey_action"))]
+	pub struct HotkeyAction;
+
+	#[derive(diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "notification_type"))]
+	pub struct NotificationType;
+
 	#[derive(diesel::sql_types::SqlType)]
 	#[diesel(postgres_type(name = "permission"))]
 	pub struct Permission;
@@ -14,6 +30,26 @@ pub mod sql_types {
 	pub struct VideoProcessingState;
 }
 
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::AdminActionType;
+
+	admin_audit_log (id) {
+		id -> Text,
+		actor -> Text,
+		action_type -> AdminActionType,
+		description -> Text,
+		performed_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	application_events (application, event) {
+		application -> Text,
+		event -> Text,
+	}
+}
+
 diesel::table! {
 	applications (id) {
 		id -> Text,
@@ -22,6 +58,9 @@ diesel::table! {
 		read_log -> Bool,
 		write_links -> Bool,
 		creation_user -> Text,
+		write_processing_state -> Bool,
+		restrict_events -> Bool,
+		write_entries -> Bool,
 	}
 }
 
@@ -32,6 +71,23 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	entry_template_tags (template, tag) {
+		template -> Text,
+		tag -> Text,
+	}
+}
+
+diesel::table! {
+	entry_templates (id) {
+		id -> Text,
+		event -> Text,
+		name -> Text,
+		entry_type -> Nullable<Text>,
+		description -> Text,
+	}
+}
+
 diesel::table! {
 	entry_types (id) {
 		id -> Text,
@@ -41,6 +97,29 @@ diesel::table! {
 		color_blue -> Int4,
 		description -> Text,
 		require_end_time -> Bool,
+		expected_duration_minutes -> Nullable<Int4>,
+		group_name -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	event_access_requests (user_id, event) {
+		user_id -> Text,
+		event -> Text,
+		requested_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::CustomFieldType;
+
+	event_custom_fields (id) {
+		id -> Text,
+		event -> Text,
+		name -> Text,
+		field_type -> CustomFieldType,
+		select_options -> Array<Nullable<Text>>,
 	}
 }
 
@@ -51,6 +130,15 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	event_feedback (id) {
+		id -> Text,
+		event -> Text,
+		message -> Text,
+		submitted_at -> Timestamptz,
+	}
+}
+
 diesel::table! {
 	use diesel::sql_types::*;
 	use super::sql_types::VideoEditState;
@@ -78,6 +166,19 @@ diesel::table! {
 		end_time_incomplete -> Bool,
 		video_processing_state -> VideoProcessingState,
 		entry_type -> Nullable<Text>,
+		created_by -> Nullable<Text>,
+		dead_links -> Array<Nullable<Text>>,
+		links_checked_at -> Nullable<Timestamptz>,
+		entry_number -> Int4,
+		latest_history_id -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	event_log_custom_field_values (log_entry, field) {
+		log_entry -> Text,
+		field -> Text,
+		value -> Text,
 	}
 }
 
@@ -111,6 +212,7 @@ diesel::table! {
 		end_time_incomplete -> Bool,
 		video_processing_state -> VideoProcessingState,
 		entry_type -> Nullable<Text>,
+		video_edit_state_rule_tag -> Nullable<Text>,
 	}
 }
 
@@ -137,6 +239,21 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	event_share_links (event) {
+		event -> Text,
+		token -> Text,
+	}
+}
+
+diesel::table! {
+	event_webhooks (id) {
+		id -> Text,
+		event -> Text,
+		url -> Text,
+	}
+}
+
 diesel::table! {
 	events (id) {
 		id -> Text,
@@ -144,15 +261,53 @@ diesel::table! {
 		start_time -> Timestamptz,
 		editor_link_format -> Text,
 		first_tab_name -> Text,
+		description_max_length -> Nullable<Int4>,
+		require_tag_deletion_approval -> Bool,
+		end_time -> Nullable<Timestamptz>,
+		archived -> Bool,
+		time_display_zone -> Nullable<Text>,
 	}
 }
 
 diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::Permission;
+
 	info_pages (id) {
 		id -> Text,
 		event -> Text,
 		title -> Text,
 		contents -> Text,
+		minimum_permission -> Nullable<Permission>,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::NotificationType;
+	use super::sql_types::VideoProcessingState;
+
+	notifications (id) {
+		id -> Text,
+		user_id -> Text,
+		event -> Text,
+		notification_type -> NotificationType,
+		log_entry -> Nullable<Text>,
+		entry_description -> Text,
+		video_processing_state -> Nullable<VideoProcessingState>,
+		created_at -> Timestamptz,
+		is_read -> Bool,
+	}
+}
+
+diesel::table! {
+	pending_tag_actions (id) {
+		id -> Text,
+		event -> Text,
+		tag -> Text,
+		replacement_tag -> Nullable<Text>,
+		requested_by -> Text,
+		requested_at -> Timestamptz,
 	}
 }
 
@@ -174,10 +329,59 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	queued_jobs (id) {
+		id -> Text,
+		webhook_url -> Nullable<Text>,
+		webhook_body -> Nullable<Bytea>,
+		recurring_entry -> Nullable<Text>,
+		occurrence_time -> Nullable<Timestamptz>,
+		attempts -> Int4,
+		queued_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	recurring_entries (id) {
+		id -> Text,
+		event -> Text,
+		entry_type -> Nullable<Text>,
+		description -> Text,
+		interval_minutes -> Int4,
+		first_time -> Timestamptz,
+		last_time -> Timestamptz,
+		next_time -> Timestamptz,
+		created_by -> Text,
+	}
+}
+
+diesel::table! {
+	recurring_entry_occurrences (recurring_entry, occurrence_time) {
+		recurring_entry -> Text,
+		occurrence_time -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	rejected_event_updates (id) {
+		id -> Text,
+		event -> Text,
+		user_id -> Text,
+		log_entry -> Nullable<Text>,
+		update_kind -> Text,
+		reason -> Text,
+		rejected_at -> Timestamptz,
+	}
+}
+
 diesel::table! {
 	sessions (id) {
 		id -> Text,
 		data -> Text,
+		openid_user_id -> Nullable<Text>,
+		user_agent -> Text,
+		created_at -> Timestamptz,
+		last_active_at -> Timestamptz,
 	}
 }
 
@@ -191,6 +395,41 @@ diesel::table! {
 		playlist -> Nullable<Text>,
 		playlist_title -> Nullable<Text>,
 		playlist_shows_in_video_descriptions -> Nullable<Bool>,
+		valid_from -> Nullable<Date>,
+		valid_to -> Nullable<Date>,
+	}
+}
+
+diesel::table! {
+	user_avatars (user_id) {
+		user_id -> Text,
+		content_type -> Text,
+		image -> Bytea,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::HotkeyAction;
+
+	user_hotkeys (user_id, action) {
+		user_id -> Text,
+		action -> HotkeyAction,
+		key_code -> Text,
+		alt -> Bool,
+		ctrl -> Bool,
+		shift -> Bool,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::Permission;
+
+	user_event_permissions (user_id, event) {
+		user_id -> Text,
+		event -> Text,
+		level -> Permission,
 	}
 }
 
@@ -201,6 +440,52 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	user_profile_admin_edits (id) {
+		id -> Text,
+		edited_user -> Text,
+		edit_user -> Text,
+		edit_time -> Timestamptz,
+		old_color_red -> Int4,
+		old_color_green -> Int4,
+		old_color_blue -> Int4,
+		old_use_spell_check -> Bool,
+		new_color_red -> Int4,
+		new_color_green -> Int4,
+		new_color_blue -> Int4,
+		new_use_spell_check -> Bool,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::VideoEditState;
+	use super::sql_types::VideoProcessingState;
+
+	user_view_preferences (id) {
+		id -> Text,
+		user_id -> Text,
+		name -> Text,
+		video_edit_states -> Array<Nullable<VideoEditState>>,
+		video_processing_states -> Array<Nullable<VideoProcessingState>>,
+		created_by_me -> Bool,
+		assigned_to_me -> Bool,
+		over_expected_duration -> Bool,
+		missing_giveaway_information -> Bool,
+	}
+}
+
+diesel::table! {
+	username_changes (id) {
+		id -> Text,
+		user_id -> Text,
+		changed_by -> Text,
+		change_time -> Timestamptz,
+		old_name -> Text,
+		new_name -> Text,
+	}
+}
+
 diesel::table! {
 	users (id) {
 		id -> Text,
@@ -211,46 +496,126 @@ diesel::table! {
 		color_green -> Int4,
 		color_blue -> Int4,
 		use_spell_check -> Bool,
+		completed_guided_tour -> Bool,
+		enabled -> Bool,
+		avatar_updated_at -> Nullable<Timestamptz>,
+		editor_assigned_sound -> Bool,
+		editor_assigned_desktop -> Bool,
+		typing_in_edited_entry_sound -> Bool,
+		typing_in_edited_entry_desktop -> Bool,
+		other_notifications_sound -> Bool,
+		other_notifications_desktop -> Bool,
+		use_event_time_display -> Bool,
+		time_display_zone -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::VideoEditState;
+
+	video_edit_state_tag_rules (tag) {
+		tag -> Text,
+		event -> Text,
+		video_edit_state -> VideoEditState,
 	}
 }
 
+diesel::joinable!(admin_audit_log -> users (actor));
 diesel::joinable!(applications -> users (creation_user));
 diesel::joinable!(available_entry_types_for_event -> entry_types (entry_type));
+diesel::joinable!(application_events -> applications (application));
+diesel::joinable!(application_events -> events (event));
 diesel::joinable!(available_entry_types_for_event -> events (event_id));
+diesel::joinable!(entry_template_tags -> entry_templates (template));
+diesel::joinable!(entry_template_tags -> tags (tag));
+diesel::joinable!(entry_templates -> entry_types (entry_type));
+diesel::joinable!(entry_templates -> events (event));
+diesel::joinable!(event_access_requests -> events (event));
+diesel::joinable!(event_access_requests -> users (user_id));
+diesel::joinable!(event_custom_fields -> events (event));
 diesel::joinable!(event_editors -> events (event));
 diesel::joinable!(event_editors -> users (editor));
+diesel::joinable!(event_feedback -> events (event));
 diesel::joinable!(event_log -> entry_types (entry_type));
 diesel::joinable!(event_log -> events (event));
+diesel::joinable!(event_log_custom_field_values -> event_custom_fields (field));
+diesel::joinable!(event_log_custom_field_values -> event_log (log_entry));
 diesel::joinable!(event_log_history -> applications (edit_application));
 diesel::joinable!(event_log_history -> entry_types (entry_type));
+diesel::joinable!(event_log_history -> tags (video_edit_state_rule_tag));
 diesel::joinable!(event_log_history_tags -> event_log_history (history_log_entry));
 diesel::joinable!(event_log_history_tags -> tags (tag));
 diesel::joinable!(event_log_tabs -> events (event));
 diesel::joinable!(event_log_tags -> event_log (log_entry));
 diesel::joinable!(event_log_tags -> tags (tag));
+diesel::joinable!(event_share_links -> events (event));
+diesel::joinable!(event_webhooks -> events (event));
 diesel::joinable!(info_pages -> events (event));
+diesel::joinable!(notifications -> event_log (log_entry));
+diesel::joinable!(notifications -> events (event));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(pending_tag_actions -> events (event));
+diesel::joinable!(pending_tag_actions -> users (requested_by));
 diesel::joinable!(permission_events -> events (event));
 diesel::joinable!(permission_events -> permission_groups (permission_group));
+diesel::joinable!(queued_jobs -> recurring_entries (recurring_entry));
+diesel::joinable!(recurring_entries -> entry_types (entry_type));
+diesel::joinable!(recurring_entries -> events (event));
+diesel::joinable!(recurring_entries -> users (created_by));
+diesel::joinable!(recurring_entry_occurrences -> recurring_entries (recurring_entry));
+diesel::joinable!(rejected_event_updates -> events (event));
+diesel::joinable!(rejected_event_updates -> users (user_id));
 diesel::joinable!(tags -> events (for_event));
+diesel::joinable!(user_event_permissions -> events (event));
+diesel::joinable!(user_avatars -> users (user_id));
+diesel::joinable!(user_event_permissions -> users (user_id));
+diesel::joinable!(user_hotkeys -> users (user_id));
 diesel::joinable!(user_permissions -> permission_groups (permission_group));
 diesel::joinable!(user_permissions -> users (user_id));
+diesel::joinable!(user_view_preferences -> users (user_id));
+diesel::joinable!(video_edit_state_tag_rules -> events (event));
+diesel::joinable!(video_edit_state_tag_rules -> tags (tag));
 
 diesel::allow_tables_to_appear_in_same_query!(
+	admin_audit_log,
+	application_events,
 	applications,
 	available_entry_types_for_event,
+	entry_template_tags,
+	entry_templates,
 	entry_types,
+	event_access_requests,
+	event_custom_fields,
 	event_editors,
+	event_feedback,
 	event_log,
+	event_log_custom_field_values,
 	event_log_history,
 	event_log_history_tags,
 	event_log_tabs,
 	event_log_tags,
+	event_share_links,
+	event_webhooks,
 	events,
 	info_pages,
+	notifications,
+	pending_tag_actions,
 	permission_events,
 	permission_groups,
+	queued_jobs,
+	recurring_entries,
+	recurring_entry_occurrences,
+	rejected_event_updates,
 	sessions,
 	tags,
+	user_avatars,
+	user_event_permissions,
+	user_hotkeys,
 	user_permissions,
+	user_profile_admin_edits,
+	user_view_preferences,
+	username_changes,
 	users,
+	video_edit_state_tag_rules,
 );
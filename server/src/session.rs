@@ -4,12 +4,31 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::database::log_lost_db_connection;
 use crate::models::Session as SessionDb;
-use crate::schema::sessions;
+use crate::schema::{sessions, users};
+use async_std::task::spawn_blocking;
+use chrono::Utc;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use r2d2::Error as R2D2Error;
 use tide::sessions::{Session, SessionStore};
 use tide::utils::async_trait;
+use tide::{Middleware, Next, Request, Response, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+/// Key under which the OpenID user ID of the session's user is kept in the session data
+const OPENID_USER_ID_SESSION_KEY: &str = "stream_log.openid_user_id";
+
+/// Paths of the pages, WebSocket connection and API, which act for the signed-in user. Other than the root page, which
+/// is matched exactly, these match every path starting with them.
+const USER_PATH_PREFIXES: [&str; 6] = ["/ws", "/api/", "/log/", "/admin/", "/register", "/user_profile"];
+
+/// Checks whether a request path is for a part of the site that acts for the signed-in user. Static files, avatars and
+/// stored files don't.
+fn is_user_path(path: &str) -> bool {
+	path == "/" || USER_PATH_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
 
 #[derive(Clone)]
 pub struct DatabaseSessionStore {
@@ -48,16 +67,32 @@ impl SessionStore for DatabaseSessionStore {
 
 	async fn store_session(&self, session: Session) -> anyhow::Result<Option<String>> {
 		let mut db_connection = self.db_connection_pool.get()?;
+		let now = Utc::now();
+		// Only the data and user are updated for existing sessions; the rest is recorded as the session is used. The user
+		// is recorded as soon as the session has one so that signing the user out finds the session.
 		let session_row: SessionDb = SessionDb {
 			id: session.id().to_string(),
 			data: serde_json::to_string(&session)?,
+			openid_user_id: session.get(OPENID_USER_ID_SESSION_KEY),
+			user_agent: String::new(),
+			created_at: now,
+			last_active_at: now,
 		};
-		diesel::insert_into(sessions::table)
+		let upsert = diesel::insert_into(sessions::table)
 			.values(&session_row)
 			.on_conflict(sessions::id)
-			.do_update()
-			.set(sessions::data.eq(&session_row.data))
-			.execute(&mut *db_connection)?;
+			.do_update();
+		match session_row.openid_user_id.as_ref() {
+			Some(openid_user_id) => upsert
+				.set((
+					sessions::data.eq(&session_row.data),
+					sessions::openid_user_id.eq(openid_user_id),
+				))
+				.execute(&mut *db_connection)?,
+			None => upsert
+				.set(sessions::data.eq(&session_row.data))
+				.execute(&mut *db_connection)?,
+		};
 		Ok(session.into_cookie_value())
 	}
 
@@ -75,3 +110,144 @@ impl SessionStore for DatabaseSessionStore {
 		Ok(())
 	}
 }
+
+/// Checks signed-in requests for pages, the WebSocket connection, and the API against the user's account. Deactivated
+/// users are signed out, and the user is recorded in the session data so the session store can associate the session
+/// with its user from the start.
+///
+/// The OpenID login callback redirects straight back to the site, so this also stops deactivated users from completing
+/// a login.
+pub struct SessionUserMiddleware {
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+}
+
+impl SessionUserMiddleware {
+	pub fn new(db_connection_pool: Pool<ConnectionManager<PgConnection>>) -> Self {
+		Self { db_connection_pool }
+	}
+}
+
+#[async_trait]
+impl Middleware<()> for SessionUserMiddleware {
+	async fn handle(&self, mut request: Request<()>, next: Next<'_, ()>) -> tide::Result {
+		let Some(openid_user_id) = request.user_id() else {
+			return Ok(next.run(request).await);
+		};
+		if !is_user_path(request.url().path()) {
+			return Ok(next.run(request).await);
+		}
+
+		// Database queries block, so the check runs away from the threads handling requests
+		let enabled = spawn_blocking({
+			let db_connection_pool = self.db_connection_pool.clone();
+			let openid_user_id = openid_user_id.clone();
+			move || -> Result<QueryResult<Option<bool>>, R2D2Error> {
+				let mut db_connection = db_connection_pool.get()?;
+				Ok(users::table
+					.filter(users::openid_user_id.eq(&openid_user_id))
+					.select(users::enabled)
+					.first(&mut *db_connection)
+					.optional())
+			}
+		})
+		.await;
+		let enabled = match enabled {
+			Ok(enabled) => enabled,
+			Err(error) => {
+				log_lost_db_connection(error);
+				return Ok(Response::new(StatusCode::InternalServerError));
+			}
+		};
+		match enabled {
+			// Users who haven't registered yet have no account to check
+			Ok(Some(true)) | Ok(None) => (),
+			Ok(Some(false)) => {
+				tide::log::info!("Signing out a deactivated user");
+				request.session_mut().destroy();
+				// The websocket connection tells the client why it can't connect
+				if request.url().path() == "/ws" {
+					return Ok(next.run(request).await);
+				}
+				let mut response = Response::new(StatusCode::Forbidden);
+				response.set_body("This account has been deactivated.");
+				return Ok(response);
+			}
+			Err(error) => {
+				tide::log::error!("Database error checking whether a user is enabled: {}", error);
+				return Ok(Response::new(StatusCode::InternalServerError));
+			}
+		}
+
+		let recorded_user_id: Option<String> = request.session().get(OPENID_USER_ID_SESSION_KEY);
+		if recorded_user_id.as_ref() != Some(&openid_user_id) {
+			request
+				.session_mut()
+				.insert(OPENID_USER_ID_SESSION_KEY, &openid_user_id)?;
+		}
+		Ok(next.run(request).await)
+	}
+}
+
+/// Records that a session was used to connect. The user the session belongs to and the browser using it are updated
+/// along with the time.
+pub fn record_session_activity(
+	db_connection: &mut PgConnection,
+	session_id: &str,
+	openid_user_id: &str,
+	user_agent: &str,
+) -> QueryResult<()> {
+	diesel::update(sessions::table)
+		.filter(sessions::id.eq(session_id))
+		.set((
+			sessions::openid_user_id.eq(openid_user_id),
+			sessions::user_agent.eq(user_agent),
+			sessions::last_active_at.eq(Utc::now()),
+		))
+		.execute(db_connection)?;
+	Ok(())
+}
+
+/// Loads the sessions of a user that haven't expired, most recently active first
+pub fn load_active_sessions(db_connection: &mut PgConnection, openid_user_id: &str) -> QueryResult<Vec<SessionDb>> {
+	let sessions: Vec<SessionDb> = sessions::table
+		.filter(sessions::openid_user_id.eq(openid_user_id))
+		.order(sessions::last_active_at.desc())
+		.load(db_connection)?;
+	let sessions = sessions
+		.into_iter()
+		.filter(|session| {
+			serde_json::from_str::<Session>(&session.data).is_ok_and(|session_data| !session_data.is_expired())
+		})
+		.collect();
+	Ok(sessions)
+}
+
+/// Which of a user's sessions to delete
+#[derive(Clone, Copy)]
+pub enum SessionSelection<'a> {
+	All,
+	/// Only the session with the given ID
+	Only(&'a str),
+	/// Every session except the one with the given ID
+	AllExcept(&'a str),
+}
+
+/// Deletes sessions of a user, signing the user out of them. Returns the IDs of the deleted sessions.
+pub fn delete_user_sessions(
+	db_connection: &mut PgConnection,
+	openid_user_id: &str,
+	selection: SessionSelection,
+) -> QueryResult<Vec<String>> {
+	let user_sessions = sessions::table.filter(sessions::openid_user_id.eq(openid_user_id));
+	match selection {
+		SessionSelection::All => diesel::delete(user_sessions)
+			.returning(sessions::id)
+			.get_results(db_connection),
+		SessionSelection::Only(session_id) => diesel::delete(user_sessions.filter(sessions::id.eq(session_id)))
+			.returning(sessions::id)
+			.get_results(db_connection),
+		SessionSelection::AllExcept(session_id) => diesel::delete(user_sessions.filter(sessions::id.ne(session_id)))
+			.returning(sessions::id)
+			.get_results(db_connection),
+	}
+}
@@ -0,0 +1,349 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::{ConfigDocument, ObjectStorageConfig};
+use crate::database::log_lost_db_connection;
+use crate::schema::users;
+use async_std::fs::{self, OpenOptions};
+use async_std::io::{self, WriteExt};
+use async_std::path::PathBuf;
+use async_std::sync::Arc;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use hmac::{Hmac, Mac};
+use isahc::http::{Method, StatusCode as HttpStatusCode, Uri};
+use isahc::{AsyncBody, AsyncReadResponseExt, HttpClient, Request as HttpRequest, Response as HttpResponse};
+use miette::{miette, IntoDiagnostic};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tide::http::mime;
+use tide::{Redirect, Request, Response, StatusCode};
+use tide_openidconnect::OpenIdConnectRequestExt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long presigned URLs stay valid when no expiry is configured
+const DEFAULT_PRESIGNED_URL_EXPIRY: Duration = Duration::from_secs(15 * 60);
+
+/// The longest expiry allowed for a presigned URL by S3
+const MAX_PRESIGNED_URL_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Timeout for requests made by the server to object storage
+const OBJECT_STORAGE_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where files written by Stream Log (such as event archives) are kept
+pub enum FileStorage {
+	/// Files are kept in a directory on the server
+	Directory(PathBuf),
+	/// Files are kept in a bucket in S3-compatible object storage
+	Object(ObjectStorage),
+}
+
+impl FileStorage {
+	/// Sets up file storage as configured. Object storage is used if it's configured; otherwise, the archive directory
+	/// is used. If neither is configured, returns None.
+	pub fn from_config(config: &ConfigDocument) -> miette::Result<Option<Self>> {
+		if let Some(object_storage_config) = config.object_storage.as_ref() {
+			let object_storage = ObjectStorage::new(object_storage_config)?;
+			return Ok(Some(Self::Object(object_storage)));
+		}
+		let storage = config
+			.archive_directory
+			.as_ref()
+			.map(|directory| Self::Directory(PathBuf::from(directory)));
+		Ok(storage)
+	}
+
+	/// Checks whether a file with the given name is stored
+	pub async fn exists(&self, name: &str) -> io::Result<bool> {
+		check_file_name(name)?;
+		match self {
+			Self::Directory(directory) => Ok(directory.join(name).exists().await),
+			Self::Object(object_storage) => {
+				let response = object_storage.send(Method::HEAD, name, Vec::new(), None).await?;
+				match response.status() {
+					HttpStatusCode::NOT_FOUND => Ok(false),
+					status if status.is_success() => Ok(true),
+					status => Err(object_storage_status_error(status)),
+				}
+			}
+		}
+	}
+
+	/// Stores a new file. The file must not already exist. The file is only visible under its name once it's been
+	/// completely written.
+	pub async fn write_new_file(&self, name: &str, content_type: &str, contents: &[u8]) -> io::Result<()> {
+		check_file_name(name)?;
+		if self.exists(name).await? {
+			return Err(io::Error::new(
+				io::ErrorKind::AlreadyExists,
+				format!("A stored file named {} already exists", name),
+			));
+		}
+
+		match self {
+			Self::Directory(directory) => {
+				let file_path = directory.join(name);
+				let partial_path = directory.join(format!("{}.partial", name));
+
+				let mut file = OpenOptions::new()
+					.write(true)
+					.create_new(true)
+					.open(&partial_path)
+					.await?;
+				file.write_all(contents).await?;
+				file.sync_all().await?;
+				drop(file);
+				fs::rename(&partial_path, &file_path).await
+			}
+			Self::Object(object_storage) => {
+				// S3 only makes an object visible once the upload of its full contents succeeds.
+				let response = object_storage
+					.send(Method::PUT, name, contents.to_vec(), Some(content_type))
+					.await?;
+				if response.status().is_success() {
+					Ok(())
+				} else {
+					Err(object_storage_status_error(response.status()))
+				}
+			}
+		}
+	}
+
+	/// Reads the full contents of a stored file
+	pub async fn read_file(&self, name: &str) -> io::Result<Vec<u8>> {
+		check_file_name(name)?;
+		match self {
+			Self::Directory(directory) => fs::read(directory.join(name)).await,
+			Self::Object(object_storage) => {
+				let mut response = object_storage.send(Method::GET, name, Vec::new(), None).await?;
+				match response.status() {
+					HttpStatusCode::NOT_FOUND => Err(io::Error::new(
+						io::ErrorKind::NotFound,
+						format!("No stored file named {} exists", name),
+					)),
+					status if status.is_success() => response.bytes().await,
+					status => Err(object_storage_status_error(status)),
+				}
+			}
+		}
+	}
+}
+
+/// Connection information for an S3-compatible object storage bucket. Objects are addressed with path-style URLs
+/// (`endpoint/bucket/object`), which all S3-compatible services support.
+pub struct ObjectStorage {
+	http_client: HttpClient,
+	endpoint: String,
+	host: String,
+	region: String,
+	bucket: String,
+	access_key_id: String,
+	secret_access_key: String,
+	url_expiry: Duration,
+}
+
+impl ObjectStorage {
+	fn new(config: &ObjectStorageConfig) -> miette::Result<Self> {
+		let endpoint = config.endpoint.trim_end_matches('/').to_string();
+		let endpoint_uri: Uri = endpoint.parse().into_diagnostic()?;
+		let Some(host) = endpoint_uri.authority() else {
+			return Err(miette!("The object storage endpoint must be a full URL"));
+		};
+		if !endpoint_uri.path().is_empty() && endpoint_uri.path() != "/" {
+			return Err(miette!("The object storage endpoint must not include a path"));
+		}
+		let host = host.to_string();
+
+		let url_expiry = match config.presigned_url_expiry_minutes {
+			Some(minutes) => Duration::from_secs(minutes * 60),
+			None => DEFAULT_PRESIGNED_URL_EXPIRY,
+		};
+		if url_expiry.is_zero() || url_expiry > MAX_PRESIGNED_URL_EXPIRY {
+			return Err(miette!("The presigned URL expiry must be between 1 minute and 7 days"));
+		}
+
+		let http_client = HttpClient::builder()
+			.timeout(OBJECT_STORAGE_REQUEST_TIMEOUT)
+			.build()
+			.into_diagnostic()?;
+
+		Ok(Self {
+			http_client,
+			endpoint,
+			host,
+			region: config.region.clone(),
+			bucket: config.bucket.clone(),
+			access_key_id: config.access_key_id.clone(),
+			secret_access_key: config.secret_access_key.clone(),
+			url_expiry,
+		})
+	}
+
+	/// Generates a presigned URL for retrieving a stored file. Anyone with the URL can download the file until the URL
+	/// expires, so the server doesn't need to pass file contents through itself.
+	pub fn download_url(&self, name: &str) -> String {
+		self.presigned_url(&Method::GET, name, self.url_expiry, Utc::now())
+	}
+
+	/// Sends a request for the named object using a presigned URL
+	async fn send(
+		&self,
+		method: Method,
+		name: &str,
+		body: Vec<u8>,
+		content_type: Option<&str>,
+	) -> io::Result<HttpResponse<AsyncBody>> {
+		let url = self.presigned_url(&method, name, DEFAULT_PRESIGNED_URL_EXPIRY, Utc::now());
+		let mut request = HttpRequest::builder().method(method).uri(url);
+		if let Some(content_type) = content_type {
+			request = request.header("Content-Type", content_type);
+		}
+		let request = request.body(body).map_err(io::Error::other)?;
+		self.http_client.send_async(request).await.map_err(io::Error::other)
+	}
+
+	/// Generates a URL for the given request to the named object, signed using AWS Signature Version 4 with the
+	/// signature in the query string. Only the host header is signed, and the payload isn't signed, so the URL can be
+	/// used by any HTTP client.
+	fn presigned_url(&self, method: &Method, name: &str, expires_in: Duration, now: DateTime<Utc>) -> String {
+		let date = now.format("%Y%m%d").to_string();
+		let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+		let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+		let credential = format!("{}/{}", self.access_key_id, scope);
+		let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(name));
+
+		// Query parameters must be sorted by name in the canonical request; these are listed in that order.
+		let query = format!(
+			"X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential={}&X-Amz-Date={}&X-Amz-Expires={}&X-Amz-SignedHeaders=host",
+			uri_encode(&credential),
+			timestamp,
+			expires_in.as_secs()
+		);
+		let canonical_request = format!(
+			"{}\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD",
+			method.as_str(),
+			path,
+			query,
+			self.host
+		);
+		let string_to_sign = format!(
+			"AWS4-HMAC-SHA256\n{}\n{}\n{}",
+			timestamp,
+			scope,
+			hex_string(&Sha256::digest(canonical_request.as_bytes()))
+		);
+
+		let signing_key = format!("AWS4{}", self.secret_access_key);
+		let signing_key = hmac_sha256(signing_key.as_bytes(), &date);
+		let signing_key = hmac_sha256(&signing_key, &self.region);
+		let signing_key = hmac_sha256(&signing_key, "s3");
+		let signing_key = hmac_sha256(&signing_key, "aws4_request");
+		let signature = hex_string(&hmac_sha256(&signing_key, &string_to_sign));
+
+		format!("{}{}?{}&X-Amz-Signature={}", self.endpoint, path, query, signature)
+	}
+}
+
+/// Serves a stored file to an administrator. Files in object storage are served by redirecting to a presigned URL.
+/// Since event archives are currently the only stored files, only administrators can retrieve stored files.
+pub async fn serve_stored_file(
+	request: Request<()>,
+	storage: Arc<FileStorage>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> tide::Result {
+	let Some(openid_user_id) = request.user_id() else {
+		return Ok(Response::new(StatusCode::Unauthorized));
+	};
+
+	let is_admin: QueryResult<Option<bool>> = match db_connection_pool.get() {
+		Ok(mut db_connection) => users::table
+			.filter(users::openid_user_id.eq(&openid_user_id))
+			.select(users::is_admin)
+			.first(&mut *db_connection)
+			.optional(),
+		Err(error) => {
+			log_lost_db_connection(error);
+			return Ok(Response::new(StatusCode::InternalServerError));
+		}
+	};
+	match is_admin {
+		Ok(Some(true)) => (),
+		Ok(_) => return Ok(Response::new(StatusCode::Forbidden)),
+		Err(error) => {
+			tide::log::error!("Database error checking access to a stored file: {}", error);
+			return Ok(Response::new(StatusCode::InternalServerError));
+		}
+	}
+
+	let name = request.param("name")?;
+	if check_file_name(name).is_err() {
+		return Ok(Response::new(StatusCode::NotFound));
+	}
+
+	match storage.as_ref() {
+		FileStorage::Object(object_storage) => Ok(Redirect::temporary(object_storage.download_url(name)).into()),
+		FileStorage::Directory(_) => match storage.read_file(name).await {
+			Ok(contents) => {
+				let content_type = if name.ends_with(".json") {
+					mime::JSON
+				} else {
+					mime::BYTE_STREAM
+				};
+				let response = Response::builder(StatusCode::Ok)
+					.body(contents)
+					.content_type(content_type)
+					.header("Content-Disposition", format!("attachment; filename=\"{}\"", name))
+					.build();
+				Ok(response)
+			}
+			Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Response::new(StatusCode::NotFound)),
+			Err(error) => {
+				tide::log::error!("Failed to read stored file {}: {}", name, error);
+				Ok(Response::new(StatusCode::InternalServerError))
+			}
+		},
+	}
+}
+
+/// Stored files are addressed only by a name, so names that could refer to other locations aren't allowed
+fn check_file_name(name: &str) -> io::Result<()> {
+	if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("{} isn't a valid stored file name", name),
+		));
+	}
+	Ok(())
+}
+
+fn object_storage_status_error(status: HttpStatusCode) -> io::Error {
+	io::Error::other(format!("Object storage responded with {}", status))
+}
+
+/// Encodes a URL component as required for AWS signatures
+fn uri_encode(value: &str) -> String {
+	let mut encoded = String::with_capacity(value.len());
+	for byte in value.bytes() {
+		if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+			encoded.push(byte as char);
+		} else {
+			encoded.push_str(&format!("%{:02X}", byte));
+		}
+	}
+	encoded
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+	let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+	mac.update(data.as_bytes());
+	mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
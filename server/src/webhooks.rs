@@ -0,0 +1,142 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::database::log_lost_db_connection;
+use crate::jobs::{JobKind, JobQueue};
+use crate::schema::event_webhooks;
+use async_std::channel::Receiver;
+use async_std::sync::Arc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use isahc::config::{Configurable, RedirectPolicy};
+use isahc::{HttpClient, Request};
+use serde::Serialize;
+use std::time::Duration;
+use stream_log_shared::messages::event_log::EventLogEntry;
+use stream_log_shared::messages::event_subscription::EventSubscriptionData;
+use stream_log_shared::messages::subscriptions::SubscriptionData;
+
+/// How long to wait for a webhook endpoint to respond before giving up on it
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The kind of change to a log entry reported to a webhook
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookChange {
+	/// The entry was created or changed. New entries are sent to event subscribers the same way as updated entries,
+	/// so webhooks can't tell the two apart either.
+	Updated,
+	Deleted,
+}
+
+/// The JSON body posted to each webhook URL for an event when one of its log entries changes
+#[derive(Serialize)]
+pub struct WebhookPayload {
+	pub event_id: String,
+	pub event_name: String,
+	pub change: WebhookChange,
+	pub entry: EventLogEntry,
+}
+
+impl WebhookPayload {
+	/// Gets the payload to send for a message broadcast to event subscribers, or None if the message isn't a log
+	/// entry change
+	pub fn from_event_message(message: &SubscriptionData) -> Option<Self> {
+		let SubscriptionData::EventUpdate(event, event_data) = message else {
+			return None;
+		};
+		let (change, entry) = match event_data.as_ref() {
+			EventSubscriptionData::UpdateLogEntry(entry, _) => (WebhookChange::Updated, entry),
+			EventSubscriptionData::DeleteLogEntry(entry) => (WebhookChange::Deleted, entry),
+			_ => return None,
+		};
+		Some(Self {
+			event_id: event.id.clone(),
+			event_name: event.name.clone(),
+			change,
+			entry: entry.clone(),
+		})
+	}
+}
+
+/// Sets up the HTTP client used to deliver webhooks
+pub fn build_webhook_client() -> Result<HttpClient, isahc::Error> {
+	HttpClient::builder()
+		.timeout(REQUEST_TIMEOUT)
+		.redirect_policy(RedirectPolicy::None)
+		.build()
+}
+
+/// Queues a delivery job for each payload received to each of the webhook URLs configured for its event. Since failed
+/// deliveries are retried later, endpoints may see changes out of the order in which they happened.
+pub async fn run_webhook_sender(
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	payload_rx: Receiver<WebhookPayload>,
+	job_queue: Arc<JobQueue>,
+) {
+	while let Ok(payload) = payload_rx.recv().await {
+		let Some(urls) = load_webhook_urls(&db_connection_pool, &payload.event_id) else {
+			continue;
+		};
+		if urls.is_empty() {
+			continue;
+		}
+
+		let body = match serde_json::to_vec(&payload) {
+			Ok(body) => Arc::new(body),
+			Err(error) => {
+				tide::log::error!("Failed to serialize webhook payload: {}", error);
+				continue;
+			}
+		};
+
+		for url in urls {
+			let body = Arc::clone(&body);
+			job_queue.enqueue(JobKind::WebhookDelivery { url, body }).await;
+		}
+	}
+}
+
+/// Posts a webhook payload to a URL. If it isn't delivered successfully, a description of the problem is returned.
+pub async fn deliver_webhook(http_client: &HttpClient, url: &str, body: &[u8]) -> Result<(), String> {
+	let request = Request::post(url)
+		.header("Content-Type", "application/json")
+		.body(body.to_vec())
+		.map_err(|error| format!("Invalid webhook URL: {}", error))?;
+	match http_client.send_async(request).await {
+		Ok(response) if !response.status().is_success() => {
+			Err(format!("The endpoint responded with status {}", response.status()))
+		}
+		Ok(_) => Ok(()),
+		Err(error) => Err(format!("Failed to send the request: {}", error)),
+	}
+}
+
+/// Loads the webhook URLs for an event. Returns None if they couldn't be loaded.
+fn load_webhook_urls(
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+	event_id: &str,
+) -> Option<Vec<String>> {
+	let mut db_connection = match db_connection_pool.get() {
+		Ok(connection) => connection,
+		Err(error) => {
+			log_lost_db_connection(error);
+			return None;
+		}
+	};
+
+	let urls: QueryResult<Vec<String>> = event_webhooks::table
+		.filter(event_webhooks::event.eq(event_id))
+		.select(event_webhooks::url)
+		.load(&mut *db_connection);
+	match urls {
+		Ok(urls) => Some(urls),
+		Err(error) => {
+			tide::log::error!("Database error loading webhooks for event {}: {}", event_id, error);
+			None
+		}
+	}
+}
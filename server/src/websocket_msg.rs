@@ -5,8 +5,16 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use async_std::stream::StreamExt;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::io::Write;
 use tide_websockets::{Message, WebSocketConnection};
 
+/// Messages at least this many bytes long are compressed before they're sent. Smaller messages are sent as text, since
+/// compressing them saves little and costs time on both ends.
+const COMPRESSED_MESSAGE_SIZE: usize = 16 * 1024;
+
 pub enum WebSocketRecvError {
 	StreamExhausted,
 	WebSocketError(tide_websockets::Error),
@@ -55,3 +63,19 @@ pub async fn recv_msg(stream: &mut WebSocketConnection) -> Result<String, WebSoc
 		}
 	}
 }
+
+/// Sends a single message on the connection. Large messages are sent as binary messages containing the
+/// DEFLATE-compressed JSON (as announced to the client in the initial message); all others are sent as JSON text.
+pub async fn send_msg(stream: &WebSocketConnection, data: &impl Serialize) -> tide::Result<()> {
+	let message = serde_json::to_string(data)?;
+	if message.len() < COMPRESSED_MESSAGE_SIZE {
+		stream.send_string(message).await?;
+		return Ok(());
+	}
+
+	let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+	encoder.write_all(message.as_bytes())?;
+	let compressed_message = encoder.finish()?;
+	stream.send_bytes(compressed_message).await?;
+	Ok(())
+}
@@ -6,4 +6,14 @@
 
 pub mod messages;
 
-pub const SYNC_VERSION: u32 = 0;
+pub const SYNC_VERSION: u32 = 67;
+
+/// The oldest client [SYNC_VERSION] the server still serves. Clients send their [SYNC_VERSION] when connecting. The
+/// server sends every client the same messages, so this should be raised to the current [SYNC_VERSION] whenever a
+/// change to the messages would break older clients.
+pub const MINIMUM_CLIENT_SYNC_VERSION: u32 = 67;
+
+/// Checks whether the server can still serve a client with the given [SYNC_VERSION]
+pub fn client_sync_version_is_supported(client_sync_version: u32) -> bool {
+	(MINIMUM_CLIENT_SYNC_VERSION..=SYNC_VERSION).contains(&client_sync_version)
+}
@@ -4,24 +4,82 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::custom_fields::CustomField;
 use super::entry_types::EntryType;
 use super::event_log::EventLogTab;
 use super::events::Event;
 use super::info_pages::InfoPage;
 use super::permissions::PermissionLevel;
+use super::time_display::TimeDisplay;
 use super::user::PublicUserData;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// An update to an event from the admin events page
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum AdminEventUpdate {
 	UpdateEvent(Event),
+	/// Requests a template of the event's configuration
+	ExportTemplate(Event),
+	/// Creates a new event from the provided event data and configuration template
+	ImportTemplate(Event, EventTemplate),
+	/// Writes a complete archive of the event's data to the server's archive location and, once the archive is
+	/// verified, deletes the event and all of its data
+	ArchiveAndPurge(Event),
+	/// Renumbers the event's entries to match the order they appear in the log. Entries that have a video link keep
+	/// their numbers, since videos are named for them.
+	RenumberEntries(Event),
 }
 
-/// Data for a server-processed change for the admin events page
+/// A server-processed change to the list of events on the admin events page
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum AdminEventData {
 	UpdateEvent(Event),
+	/// The event was deleted
+	RemoveEvent(Event),
+}
+
+/// A reusable copy of an event's configuration, which can be used to set up new events
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventTemplate {
+	pub editor_link_format: String,
+	pub first_tab_name: String,
+	#[serde(default)]
+	pub description_max_length: Option<i32>,
+	#[serde(default)]
+	pub require_tag_deletion_approval: bool,
+	#[serde(default)]
+	pub time_display: TimeDisplay,
+	/// Entry types available for the event. When imported, these are matched to existing entry types by name.
+	pub entry_types: Vec<EntryType>,
+	pub tabs: Vec<EventTemplateTab>,
+	/// Permission groups with access to the event. When imported, these are matched to existing groups by name.
+	pub permission_groups: Vec<EventTemplatePermissionGroup>,
+	pub info_pages: Vec<EventTemplateInfoPage>,
+}
+
+/// An event log tab in an event template
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventTemplateTab {
+	pub name: String,
+	/// Number of seconds after the start of the event that the tab starts
+	pub start_offset_seconds: i64,
+}
+
+/// A permission group's access to an event in an event template
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventTemplatePermissionGroup {
+	pub name: String,
+	pub permission: PermissionLevel,
+}
+
+/// An info page in an event template
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventTemplateInfoPage {
+	pub title: String,
+	pub contents: String,
+	#[serde(default)]
+	pub minimum_permission: Option<PermissionLevel>,
 }
 
 /// An update to an entry type from the admin entry types page
@@ -55,6 +113,23 @@ pub enum AdminPermissionGroupUpdate {
 	UpdateGroup(PermissionGroup),
 	SetEventPermissionForGroup(PermissionGroupEventAssociation),
 	RemoveEventFromGroup(PermissionGroup, Event),
+	/// Creates a new group with the given name that has the same event permissions as the given group
+	DuplicateGroup(PermissionGroup, String),
+	/// Creates a group for the event for each of the [STANDARD_EVENT_GROUPS]
+	CreateStandardEventGroups(Event),
+}
+
+/// The set of groups that can be created for a new event all at once. Each group is named for the event followed by
+/// the name here and has the associated permission level for only that event.
+pub const STANDARD_EVENT_GROUPS: [(&str, PermissionLevel); 3] = [
+	("Supervisors", PermissionLevel::Supervisor),
+	("Editors", PermissionLevel::Edit),
+	("Viewers", PermissionLevel::View),
+];
+
+/// Gets the name of a group in the [STANDARD_EVENT_GROUPS] set for an event
+pub fn standard_event_group_name(event: &Event, group_name: &str) -> String {
+	format!("{} {}", event.name, group_name)
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -100,10 +175,34 @@ pub enum AdminUserPermissionGroupData {
 	RemoveUserFromGroup(UserPermissionGroupAssociation),
 }
 
+/// A permission level granted to a user for an event directly, rather than through a permission group. Users get the
+/// highest of this and the levels from their permission groups.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserEventPermission {
+	pub user: PublicUserData,
+	pub event: Event,
+	pub level: PermissionLevel,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminUserEventPermissionUpdate {
+	/// Grants the permission, replacing any permission already granted directly to the user for the event
+	SetPermission(UserEventPermission),
+	RemovePermission(PublicUserData, Event),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminUserEventPermissionData {
+	SetPermission(UserEventPermission),
+	RemovePermission(PublicUserData, Event),
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum AdminEntryTypeEventUpdate {
 	AddTypeToEvent(EntryTypeEventAssociation),
 	RemoveTypeFromEvent(EntryTypeEventAssociation),
+	/// Adds the first list of associations and removes the second list all at once
+	UpdateAssociations(Vec<EntryTypeEventAssociation>, Vec<EntryTypeEventAssociation>),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -138,6 +237,10 @@ pub struct Application {
 	pub name: String,
 	pub read_log: bool,
 	pub write_links: bool,
+	pub write_processing_state: bool,
+	pub write_entries: bool,
+	/// IDs of the events the application can access, or None if it can access all events
+	pub event_scope: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -165,3 +268,220 @@ pub enum AdminInfoPageUpdate {
 	UpdateInfoPage(InfoPage),
 	DeleteInfoPage(InfoPage),
 }
+
+/// A URL to which the server sends log entry changes for an event
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventWebhook {
+	pub id: String,
+	pub event: Event,
+	pub url: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventWebhookData {
+	UpdateWebhook(EventWebhook),
+	DeleteWebhook(EventWebhook),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventWebhookUpdate {
+	UpdateWebhook(EventWebhook),
+	DeleteWebhook(EventWebhook),
+}
+
+/// A custom entry field along with the event it belongs to
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventCustomField {
+	pub event: Event,
+	pub field: CustomField,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventCustomFieldData {
+	UpdateField(EventCustomField),
+	DeleteField(EventCustomField),
+}
+
+/// A change to an event's custom fields. Fields with an empty ID are created.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventCustomFieldUpdate {
+	UpdateField(EventCustomField),
+	/// Deletes the field along with its values in all of the event's entries
+	DeleteField(EventCustomField),
+}
+
+/// A token that allows anyone with the link to view an event's log without logging in
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventShareLink {
+	pub event: Event,
+	pub token: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventShareLinkData {
+	UpdateShareLink(EventShareLink),
+	/// The event's share link was removed
+	DeleteShareLink(Event),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventShareLinkUpdate {
+	/// Generates a new share link for the event, replacing any existing one
+	CreateShareLink(Event),
+	DeleteShareLink(Event),
+}
+
+/// A user's pending request for access to an event
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventAccessRequest {
+	pub user: PublicUserData,
+	pub event: Event,
+	pub requested_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventAccessRequestData {
+	NewRequest(EventAccessRequest),
+	/// The request was approved or denied, so it's no longer pending
+	RemoveRequest(EventAccessRequest),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminEventAccessRequestUpdate {
+	/// Approves the request by adding the user to the given permission group
+	Approve(EventAccessRequest, PermissionGroup),
+	Deny(EventAccessRequest),
+}
+
+/// A change to an event that a user requested but the server refused to make
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RejectedEventUpdate {
+	pub id: String,
+	pub event: Event,
+	pub user: PublicUserData,
+	/// ID of the log entry the change was for, if it was for an entry
+	pub entry_id: Option<String>,
+	/// A short description of the kind of change that was requested
+	pub update_kind: String,
+	/// Why the change wasn't made
+	pub reason: String,
+	pub rejected_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminRejectedUpdateData {
+	NewRejectedUpdate(RejectedEventUpdate),
+}
+
+/// The part of the site configuration an administrative action changed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum AdminActionType {
+	Event,
+	EntryType,
+	PermissionGroup,
+	UserPermissionGroup,
+	User,
+	Editor,
+	EventLogTab,
+	Application,
+	InfoPage,
+	AccessRequest,
+	Webhook,
+	ShareLink,
+}
+
+impl AdminActionType {
+	/// Gets all action types in the order they should be shown to users
+	pub fn all() -> [Self; 12] {
+		[
+			Self::Event,
+			Self::EntryType,
+			Self::PermissionGroup,
+			Self::UserPermissionGroup,
+			Self::User,
+			Self::Editor,
+			Self::EventLogTab,
+			Self::Application,
+			Self::InfoPage,
+			Self::AccessRequest,
+			Self::Webhook,
+			Self::ShareLink,
+		]
+	}
+
+	/// Gets the name of the action type shown to users
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Event => "Events",
+			Self::EntryType => "Entry types",
+			Self::PermissionGroup => "Permission groups",
+			Self::UserPermissionGroup => "Group membership",
+			Self::User => "Users",
+			Self::Editor => "Editors",
+			Self::EventLogTab => "Tabs",
+			Self::Application => "Applications",
+			Self::InfoPage => "Info pages",
+			Self::AccessRequest => "Access requests",
+			Self::Webhook => "Webhooks",
+			Self::ShareLink => "Share links",
+		}
+	}
+}
+
+/// A change an administrator made to the site's configuration
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AdminAuditLogEntry {
+	pub id: String,
+	/// The administrator who made the change
+	pub actor: PublicUserData,
+	pub action_type: AdminActionType,
+	/// A description of what was changed
+	pub description: String,
+	pub performed_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminAuditLogData {
+	NewEntry(AdminAuditLogEntry),
+}
+
+/// Where a background job is in being run
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum JobStatus {
+	Queued,
+	Running,
+	/// The job failed and will be tried again at the given time
+	WaitingToRetry(DateTime<Utc>),
+	Succeeded,
+	/// The job failed and won't be tried again
+	Failed,
+}
+
+impl JobStatus {
+	/// Whether the job is done being run, whether or not it succeeded
+	pub fn is_finished(&self) -> bool {
+		matches!(self, Self::Succeeded | Self::Failed)
+	}
+}
+
+/// A piece of work the server is doing in the background
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Job {
+	pub id: String,
+	/// A short description of the work the job does
+	pub description: String,
+	pub status: JobStatus,
+	/// The number of times the job has been started
+	pub attempts: u32,
+	/// The error from the job's most recent failed attempt
+	pub last_error: Option<String>,
+	pub queued_at: DateTime<Utc>,
+	pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AdminJobData {
+	UpdateJob(Job),
+	/// A finished job was dropped from the server's job history. Contains the ID of the job.
+	RemoveJob(String),
+}
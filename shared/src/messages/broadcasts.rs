@@ -0,0 +1,22 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::user::PublicUserData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The longest broadcast message that can be sent, in characters
+pub const MAX_BROADCAST_LENGTH: usize = 300;
+
+/// A one-off notice sent by a supervisor to everyone currently viewing an event. Broadcasts aren't stored, so users who
+/// aren't viewing the event when one is sent never see it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventBroadcast {
+	pub id: String,
+	pub sender: PublicUserData,
+	pub message: String,
+	pub sent_at: DateTime<Utc>,
+}
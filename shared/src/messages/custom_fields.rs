@@ -0,0 +1,71 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+/// An extra field added to the entries of a single event, for event-specific information that doesn't fit in the
+/// standard entry fields. Custom fields are managed by administrators.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CustomField {
+	pub id: String,
+	pub name: String,
+	pub field_type: CustomFieldType,
+}
+
+/// The kind of value a custom field holds. Values are always stored as strings.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CustomFieldType {
+	Text,
+	/// A number, which may have a fractional part
+	Number,
+	/// A yes/no value, stored as "true" when checked
+	Checkbox,
+	/// A choice from the given list of options
+	Select(Vec<String>),
+}
+
+impl CustomFieldType {
+	/// Gets the name of the field type shown to users
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Text => "Text",
+			Self::Number => "Number",
+			Self::Checkbox => "Checkbox",
+			Self::Select(_) => "Select",
+		}
+	}
+}
+
+impl CustomField {
+	/// Checks whether the given value can be stored in the field. Empty values are always allowed and mean that the
+	/// field isn't filled in for the entry.
+	pub fn check_value(&self, value: &str) -> Result<(), String> {
+		if value.is_empty() {
+			return Ok(());
+		}
+		match &self.field_type {
+			CustomFieldType::Text => Ok(()),
+			CustomFieldType::Number => match value.parse::<f64>() {
+				Ok(number) if number.is_finite() => Ok(()),
+				_ => Err(format!("{} must be a number", self.name)),
+			},
+			CustomFieldType::Checkbox => {
+				if value == "true" {
+					Ok(())
+				} else {
+					Err(format!("{} must be checked or unchecked", self.name))
+				}
+			}
+			CustomFieldType::Select(options) => {
+				if options.iter().any(|option| option == value) {
+					Ok(())
+				} else {
+					Err(format!("{} must be one of its options", self.name))
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,31 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log::{VideoEditState, VideoProcessingState};
+use super::user::PublicUserData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How many entries in an event are assigned to an editor, broken down by the state of their videos
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EditorWorkload {
+	pub editor: PublicUserData,
+	pub total_entries: u32,
+	/// Numbers of assigned entries in each video edit state. States with no entries may be missing.
+	pub edit_state_counts: HashMap<VideoEditState, u32>,
+	/// Numbers of assigned entries in each video processing state. States with no entries may be missing.
+	pub processing_state_counts: HashMap<VideoProcessingState, u32>,
+}
+
+impl EditorWorkload {
+	pub fn edit_state_count(&self, state: VideoEditState) -> u32 {
+		self.edit_state_counts.get(&state).copied().unwrap_or_default()
+	}
+
+	pub fn processing_state_count(&self, state: VideoProcessingState) -> u32 {
+		self.processing_state_counts.get(&state).copied().unwrap_or_default()
+	}
+}
@@ -0,0 +1,20 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::tags::Tag;
+use serde::{Deserialize, Serialize};
+
+/// A set of values that can be filled into the entry edit form to quickly log a kind of entry that comes up often in an
+/// event. Templates are managed by the event's supervisors.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntryTemplate {
+	pub id: String,
+	pub name: String,
+	/// The ID of the entry type filled in by the template, if it sets one
+	pub entry_type: Option<String>,
+	pub description: String,
+	pub tags: Vec<Tag>,
+}
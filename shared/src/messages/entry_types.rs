@@ -14,4 +14,18 @@ pub struct EntryType {
 	pub description: String,
 	pub color: RGB8,
 	pub require_end_time: bool,
+	/// How long entries of this type are expected to last. Entries that run longer are flagged in the log.
+	#[serde(default)]
+	pub expected_duration_minutes: Option<i32>,
+	/// The group the entry type is listed under when choosing an entry type, if it's in one
+	#[serde(default)]
+	pub group_name: Option<String>,
+}
+
+/// Cleans up a group name entered for an entry type. Group names that are empty after trimming mean the entry type
+/// isn't in a group.
+pub fn normalize_group_name(group_name: Option<String>) -> Option<String> {
+	group_name
+		.map(|name| name.trim().to_string())
+		.filter(|name| !name.is_empty())
 }
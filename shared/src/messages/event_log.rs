@@ -8,23 +8,19 @@ use super::tags::Tag;
 use super::user::PublicUserData;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub enum EndTimeData {
 	/// Indicates an entered time with the specified accompanying value
 	Time(DateTime<Utc>),
 	/// Indicates that a time has not yet been entered but will be
+	#[default]
 	NotEntered,
 	/// Indicates that no time is to be entered
 	NoTime,
 }
 
-impl Default for EndTimeData {
-	fn default() -> Self {
-		Self::NotEntered
-	}
-}
-
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct EventLogEntry {
 	pub id: String,
@@ -40,12 +36,22 @@ pub struct EventLogEntry {
 	pub video_link: Option<String>,
 	pub parent: Option<String>,
 	pub created_at: DateTime<Utc>,
+	pub created_by: Option<PublicUserData>,
 	pub manual_sort_key: Option<i32>,
 	pub video_processing_state: VideoProcessingState,
 	pub video_errors: String,
 	pub poster_moment: bool,
 	pub video_edit_state: VideoEditState,
 	pub missing_giveaway_information: bool,
+	pub dead_links: Vec<String>,
+	pub entry_number: Option<i32>,
+	/// Values of the event's custom fields, keyed by field ID. Fields that aren't filled in for the entry are left
+	/// out.
+	pub custom_fields: HashMap<String, String>,
+	/// ID of the entry's latest revision in its history, which identifies the version of the entry changes are based
+	/// on. None for entries that haven't been saved yet.
+	#[serde(default)]
+	pub history_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -55,8 +61,9 @@ pub struct EventLogTab {
 	pub start_time: DateTime<Utc>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum VideoProcessingState {
+	#[default]
 	Unedited,
 	Edited,
 	Claimed,
@@ -82,12 +89,6 @@ impl VideoProcessingState {
 	}
 }
 
-impl Default for VideoProcessingState {
-	fn default() -> Self {
-		Self::Unedited
-	}
-}
-
 impl std::fmt::Display for VideoProcessingState {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let str_value = match self {
@@ -104,8 +105,9 @@ impl std::fmt::Display for VideoProcessingState {
 	}
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum VideoEditState {
+	#[default]
 	NoVideo,
 	MarkedForEditing,
 	DoneEditing,
@@ -117,8 +119,147 @@ impl VideoEditState {
 	}
 }
 
-impl Default for VideoEditState {
-	fn default() -> Self {
-		Self::NoVideo
+/// The source of a change to a log entry
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EntryChangeSource {
+	User(PublicUserData),
+	/// The change was made by an application; contains the application's name
+	Application(String),
+	/// The user or application that made the change no longer exists
+	Unknown,
+}
+
+/// A revision of a log entry from the entry's edit history
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogEntryRevision {
+	pub edit_time: DateTime<Utc>,
+	pub changed_by: EntryChangeSource,
+	/// The entry data as of this revision
+	pub entry: EventLogEntry,
+	/// Whether the entry was deleted in this revision
+	pub deleted: bool,
+}
+
+/// The stored data of a log entry, reduced to what every way of delivering entries to clients should agree on. Users,
+/// entry types, and tags are referred to by ID, and tags are sorted, so that summaries built from differently-shaped
+/// representations of the same entry compare equal.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogEntrySummary {
+	pub id: String,
+	pub entry_number: Option<i32>,
+	pub start_time: Option<DateTime<Utc>>,
+	pub end_time: EndTimeData,
+	pub entry_type: Option<String>,
+	pub description: String,
+	pub media_links: Vec<String>,
+	pub submitter_or_winner: String,
+	pub tags: Vec<String>,
+	pub notes: String,
+	pub editor: Option<String>,
+	pub created_by: Option<String>,
+	pub video_link: Option<String>,
+	pub parent: Option<String>,
+	pub manual_sort_key: Option<i32>,
+	pub video_edit_state: VideoEditState,
+	pub video_processing_state: VideoProcessingState,
+	pub video_errors: String,
+	pub poster_moment: bool,
+	pub missing_giveaway_information: bool,
+}
+
+impl EventLogEntrySummary {
+	/// Gets the names of the fields that differ between two summaries
+	pub fn differing_fields(&self, other: &Self) -> Vec<&'static str> {
+		let mut fields = Vec::new();
+		if self.id != other.id {
+			fields.push("id");
+		}
+		if self.entry_number != other.entry_number {
+			fields.push("entry_number");
+		}
+		if self.start_time != other.start_time {
+			fields.push("start_time");
+		}
+		if self.end_time != other.end_time {
+			fields.push("end_time");
+		}
+		if self.entry_type != other.entry_type {
+			fields.push("entry_type");
+		}
+		if self.description != other.description {
+			fields.push("description");
+		}
+		if self.media_links != other.media_links {
+			fields.push("media_links");
+		}
+		if self.submitter_or_winner != other.submitter_or_winner {
+			fields.push("submitter_or_winner");
+		}
+		if self.tags != other.tags {
+			fields.push("tags");
+		}
+		if self.notes != other.notes {
+			fields.push("notes");
+		}
+		if self.editor != other.editor {
+			fields.push("editor");
+		}
+		if self.created_by != other.created_by {
+			fields.push("created_by");
+		}
+		if self.video_link != other.video_link {
+			fields.push("video_link");
+		}
+		if self.parent != other.parent {
+			fields.push("parent");
+		}
+		if self.manual_sort_key != other.manual_sort_key {
+			fields.push("manual_sort_key");
+		}
+		if self.video_edit_state != other.video_edit_state {
+			fields.push("video_edit_state");
+		}
+		if self.video_processing_state != other.video_processing_state {
+			fields.push("video_processing_state");
+		}
+		if self.video_errors != other.video_errors {
+			fields.push("video_errors");
+		}
+		if self.poster_moment != other.poster_moment {
+			fields.push("poster_moment");
+		}
+		if self.missing_giveaway_information != other.missing_giveaway_information {
+			fields.push("missing_giveaway_information");
+		}
+		fields
+	}
+}
+
+impl From<&EventLogEntry> for EventLogEntrySummary {
+	fn from(entry: &EventLogEntry) -> Self {
+		let mut tags: Vec<String> = entry.tags.iter().map(|tag| tag.id.clone()).collect();
+		tags.sort();
+		Self {
+			id: entry.id.clone(),
+			entry_number: entry.entry_number,
+			start_time: entry.start_time,
+			end_time: entry.end_time,
+			entry_type: entry.entry_type.clone(),
+			description: entry.description.clone(),
+			media_links: entry.media_links.clone(),
+			submitter_or_winner: entry.submitter_or_winner.clone(),
+			tags,
+			notes: entry.notes.clone(),
+			editor: entry.editor.as_ref().map(|editor| editor.id.clone()),
+			created_by: entry.created_by.as_ref().map(|creator| creator.id.clone()),
+			video_link: entry.video_link.clone(),
+			parent: entry.parent.clone(),
+			manual_sort_key: entry.manual_sort_key,
+			video_edit_state: entry.video_edit_state,
+			video_processing_state: entry.video_processing_state,
+			video_errors: entry.video_errors.clone(),
+			poster_moment: entry.poster_moment,
+			missing_giveaway_information: entry.missing_giveaway_information,
+		}
 	}
 }
@@ -0,0 +1,28 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+/// The outcome of importing event log entries from a CSV file. The import is all-or-nothing: if any row has an error,
+/// no entries are added.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogImportResult {
+	/// The ID of the event into which entries were imported
+	pub event_id: String,
+	/// The number of entries added to the event log
+	pub imported_count: usize,
+	/// Problems found in the file, in the order of the rows in which they were found
+	pub row_errors: Vec<EventLogImportRowError>,
+}
+
+/// A problem with one row of an imported file
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogImportRowError {
+	/// The row of the file containing the problem, counting the header row as row 1
+	pub row: usize,
+	/// A description of the problem
+	pub error: String,
+}
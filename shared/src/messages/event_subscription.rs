@@ -4,13 +4,24 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::broadcasts::EventBroadcast;
+use super::custom_fields::CustomField;
+use super::entry_templates::EntryTemplate;
 use super::entry_types::EntryType;
-use super::event_log::{EventLogEntry, EventLogTab};
+use super::event_log::{EventLogEntry, EventLogTab, VideoEditState};
 use super::events::Event;
+use super::feedback::EventFeedback;
 use super::info_pages::InfoPage;
-use super::tags::Tag;
+use super::recurring_entries::RecurringEntry;
+use super::tags::{PendingTagAction, Tag, VideoEditStateTagRule};
 use super::user::PublicUserData;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How long an entry stays locked for editing without being renewed. Clients renew locks on entries they're still
+/// editing well before this, so a lock only expires when the client holding it stops responding.
+pub const ENTRY_EDIT_LOCK_DURATION_SECONDS: i64 = 300;
 
 /// Event subscription data sent by the server to subscribed clients with information about what changes were made.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -25,11 +36,71 @@ pub enum EventSubscriptionData {
 	AddEditor(PublicUserData),
 	RemoveEditor(PublicUserData),
 	UpdateInfoPage(InfoPage),
-	DeleteInfoPage(InfoPage),
+	/// Removes the info page with the given ID
+	DeleteInfoPage(String),
 	UpdateTab(EventLogTab),
 	DeleteTab(EventLogTab),
 	UpdateTag(Tag),
 	RemoveTag(Tag),
+	UpdateVideoEditStateRule(VideoEditStateTagRule),
+	DeleteVideoEditStateRule(Tag),
+	/// Entries were renumbered. Maps the IDs of entries whose numbers changed to their new numbers.
+	UpdateEntryNumbers(HashMap<String, i32>),
+	/// More of the existing entries for an event with too many entries to send in the initial subscription load. Only
+	/// sent to the connection that subscribed. Chunks are sent in entry order.
+	InitialLogEntryChunk(Vec<EventLogEntry>),
+	/// The entries of a tab that weren't sent in the initial subscription load, along with the tab ID (which is empty
+	/// for the event's first tab). Only sent to the connection that requested them.
+	TabEntries(String, Vec<EventLogEntry>),
+	/// A supervisor requested a tag change that needs approval
+	AddPendingTagAction(PendingTagAction),
+	/// The pending tag action with the given ID was approved, rejected, or is no longer relevant
+	RemovePendingTagAction(String),
+	/// Feedback was sent for the event. Only sent to supervisors.
+	AddFeedback(EventFeedback),
+	/// The feedback with the given ID was dismissed
+	RemoveFeedback(String),
+	/// A user started viewing the event
+	AddViewer(PublicUserData),
+	/// The user with the given ID is no longer viewing the event
+	RemoveViewer(String),
+	UpdateEntryTemplate(EntryTemplate),
+	DeleteEntryTemplate(EntryTemplate),
+	/// A supervisor sent a message to everyone viewing the event
+	Broadcast(EventBroadcast),
+	UpdateCustomField(CustomField),
+	DeleteCustomField(CustomField),
+	UpdateRecurringEntry(RecurringEntry),
+	DeleteRecurringEntry(RecurringEntry),
+	/// A user started (or is still) editing an entry
+	EntryLocked(EntryEditLock),
+	/// The entry with the given ID is no longer being edited
+	EntryUnlocked(String),
+	/// Some of the changes in an entry update weren't saved because someone else changed the same parts of the entry
+	/// first. The rest of the update was saved. Only sent to the connection that sent the update.
+	LogEntryUpdateConflict(Box<EntryUpdateConflict>),
+}
+
+/// The parts of an entry update that conflicted with changes someone else made
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntryUpdateConflict {
+	/// The entry as the user's update would have left it
+	pub entry: EventLogEntry,
+	/// The version of the entry the update was based on
+	pub base_entry: EventLogEntry,
+	/// The parts of the update that weren't saved
+	pub conflicting_parts: Vec<ModifiedEventLogEntryParts>,
+}
+
+/// An advisory lock held by a user editing an entry. Locks don't prevent changes to the entry; they let other users
+/// know that someone else is working on it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntryEditLock {
+	pub entry_id: String,
+	/// The user editing the entry
+	pub user: PublicUserData,
+	/// When the lock expires if it isn't renewed
+	pub expires_at: DateTime<Utc>,
 }
 
 /// Typing data sent by the server as part of event subscription data with information on what updates to make to typing
@@ -44,19 +115,69 @@ pub enum TypingData {
 	MediaLinks(EventLogEntry, String, PublicUserData),
 	SubmitterWinner(EventLogEntry, String, PublicUserData),
 	Notes(EventLogEntry, String, PublicUserData),
+	Tags(EventLogEntry, String, PublicUserData),
+	SortKey(EventLogEntry, String, PublicUserData),
 	Clear(EventLogEntry, PublicUserData),
 }
 
 /// Event subscription update sent by the client to the server.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum EventSubscriptionUpdate {
-	UpdateLogEntry(EventLogEntry, Vec<ModifiedEventLogEntryParts>),
-	DeleteLogEntry(EventLogEntry),
+	/// Updates the given parts of an entry. If the history ID of the version of the entry the changes were based on is
+	/// included, parts that someone else has changed since then aren't saved and are reported back as a conflict
+	/// instead.
+	UpdateLogEntry(EventLogEntry, Vec<ModifiedEventLogEntryParts>, Option<String>),
+	DeleteLogEntry(EventLogEntry, ChildEntryHandling),
 	Typing(NewTypingData),
 	UpdateTag(Tag),
 	RemoveTag(Tag),
 	ReplaceTag(Tag, Tag),
 	CopyTagsFromEvent(Event),
+	/// Sets or clears (with None) the video edit state rule for a tag
+	SetVideoEditStateRule(Tag, Option<VideoEditState>),
+	/// Moves the start and end times of a range of entries
+	ShiftEntryTimes(EntryTimeShift),
+	/// Approves and carries out the pending tag action with the given ID
+	ApprovePendingTagAction(String),
+	/// Rejects the pending tag action with the given ID without making the change
+	RejectPendingTagAction(String),
+	/// Sends anonymous feedback with the given message to the event's supervisors
+	SubmitFeedback(String),
+	/// Removes the feedback with the given ID from the supervisors' feedback list
+	DismissFeedback(String),
+	/// Creates or updates an entry template. Templates with an empty ID are created.
+	UpdateEntryTemplate(EntryTemplate),
+	DeleteEntryTemplate(EntryTemplate),
+	/// Sends a message to everyone currently viewing the event. Only supervisors can send broadcasts.
+	SendBroadcast(String),
+	/// Creates or updates a recurring entry. Recurring entries with an empty ID are created.
+	UpdateRecurringEntry(RecurringEntry),
+	DeleteRecurringEntry(RecurringEntry),
+	/// Locks the entry with the given ID for editing by the user, or renews the user's lock on it
+	BeginEditingEntry(String),
+	/// Releases the user's lock on the entry with the given ID
+	EndEditingEntry(String),
+}
+
+/// Describes a change to the times of all entries in a range
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntryTimeShift {
+	/// Entries starting at or after this time are moved
+	pub range_start: DateTime<Utc>,
+	/// If set, only entries starting at or before this time are moved
+	pub range_end: Option<DateTime<Utc>>,
+	/// How far the entries are moved. Negative values move entries earlier.
+	pub shift_seconds: i64,
+}
+
+/// What to do with the child entries of an entry that's being deleted
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ChildEntryHandling {
+	/// Delete all entries under the deleted entry, including children of children
+	Delete,
+	/// Move the direct children of the deleted entry to the top level
+	#[default]
+	Promote,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -69,9 +190,31 @@ pub enum NewTypingData {
 	MediaLinks(EventLogEntry, String),
 	SubmitterWinner(EventLogEntry, String),
 	Notes(EventLogEntry, String),
+	/// Tag names currently entered, joined with commas
+	Tags(EventLogEntry, String),
+	SortKey(EventLogEntry, String),
 	Clear(EventLogEntry),
 }
 
+impl NewTypingData {
+	/// Gets the entry being typed in
+	pub fn log_entry(&self) -> &EventLogEntry {
+		match self {
+			Self::Parent(log_entry, _)
+			| Self::StartTime(log_entry, _)
+			| Self::EndTime(log_entry, _)
+			| Self::EntryType(log_entry, _)
+			| Self::Description(log_entry, _)
+			| Self::MediaLinks(log_entry, _)
+			| Self::SubmitterWinner(log_entry, _)
+			| Self::Notes(log_entry, _)
+			| Self::Tags(log_entry, _)
+			| Self::SortKey(log_entry, _)
+			| Self::Clear(log_entry) => log_entry,
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum ModifiedEventLogEntryParts {
 	StartTime,
@@ -88,4 +231,75 @@ pub enum ModifiedEventLogEntryParts {
 	MissingGiveawayInfo,
 	SortKey,
 	Parent,
+	CustomFields,
+}
+
+impl ModifiedEventLogEntryParts {
+	/// Gets all parts of an entry in the order they're shown in the edit form
+	pub fn all() -> [Self; 15] {
+		[
+			Self::StartTime,
+			Self::EndTime,
+			Self::EntryType,
+			Self::Description,
+			Self::MediaLinks,
+			Self::SubmitterOrWinner,
+			Self::Tags,
+			Self::VideoEditState,
+			Self::PosterMoment,
+			Self::Notes,
+			Self::Editor,
+			Self::MissingGiveawayInfo,
+			Self::SortKey,
+			Self::Parent,
+			Self::CustomFields,
+		]
+	}
+}
+
+/// Checks whether the given part of two entries is the same
+pub fn entry_parts_match(part: ModifiedEventLogEntryParts, entry: &EventLogEntry, other: &EventLogEntry) -> bool {
+	match part {
+		ModifiedEventLogEntryParts::StartTime => entry.start_time == other.start_time,
+		ModifiedEventLogEntryParts::EndTime => entry.end_time == other.end_time,
+		ModifiedEventLogEntryParts::EntryType => entry.entry_type == other.entry_type,
+		ModifiedEventLogEntryParts::Description => entry.description == other.description,
+		ModifiedEventLogEntryParts::MediaLinks => entry.media_links == other.media_links,
+		ModifiedEventLogEntryParts::SubmitterOrWinner => entry.submitter_or_winner == other.submitter_or_winner,
+		ModifiedEventLogEntryParts::Tags => {
+			let tag_ids: HashSet<&str> = entry.tags.iter().map(|tag| tag.id.as_str()).collect();
+			let other_tag_ids: HashSet<&str> = other.tags.iter().map(|tag| tag.id.as_str()).collect();
+			tag_ids == other_tag_ids
+		}
+		ModifiedEventLogEntryParts::VideoEditState => entry.video_edit_state == other.video_edit_state,
+		ModifiedEventLogEntryParts::PosterMoment => entry.poster_moment == other.poster_moment,
+		ModifiedEventLogEntryParts::Notes => entry.notes == other.notes,
+		ModifiedEventLogEntryParts::Editor => {
+			entry.editor.as_ref().map(|editor| &editor.id) == other.editor.as_ref().map(|editor| &editor.id)
+		}
+		ModifiedEventLogEntryParts::MissingGiveawayInfo => {
+			entry.missing_giveaway_information == other.missing_giveaway_information
+		}
+		ModifiedEventLogEntryParts::SortKey => entry.manual_sort_key == other.manual_sort_key,
+		ModifiedEventLogEntryParts::Parent => entry.parent == other.parent,
+		ModifiedEventLogEntryParts::CustomFields => entry.custom_fields == other.custom_fields,
+	}
+}
+
+/// Gets the parts of an edit that conflict with other changes to the entry. A part conflicts if someone else changed it
+/// since the version the edit was based on to something other than what the edit sets it to.
+pub fn conflicting_entry_parts(
+	modified_parts: &[ModifiedEventLogEntryParts],
+	base_entry: &EventLogEntry,
+	edited_entry: &EventLogEntry,
+	current_entry: &EventLogEntry,
+) -> Vec<ModifiedEventLogEntryParts> {
+	modified_parts
+		.iter()
+		.filter(|part| {
+			!entry_parts_match(**part, base_entry, current_entry)
+				&& !entry_parts_match(**part, edited_entry, current_entry)
+		})
+		.copied()
+		.collect()
 }
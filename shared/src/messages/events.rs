@@ -4,14 +4,99 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::time_display::TimeDisplay;
 use chrono::prelude::*;
+use chrono::TimeDelta;
 use serde::{Deserialize, Serialize};
 
+/// How long an event without a scheduled end time is considered live after it starts
+const UNSCHEDULED_EVENT_LIVE_DAYS: i64 = 7;
+
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Event {
 	pub id: String,
 	pub name: String,
 	pub start_time: DateTime<Utc>,
+	/// When the event is scheduled to end, if known
+	pub end_time: Option<DateTime<Utc>>,
 	pub editor_link_format: String,
 	pub first_tab_name: String,
+	/// The maximum number of characters allowed in an entry description, if descriptions are limited for the event
+	pub description_max_length: Option<i32>,
+	/// Whether removing and replacing tags in the event requires approval from a second supervisor
+	pub require_tag_deletion_approval: bool,
+	/// Whether the event is archived, which prevents any changes to its log
+	pub archived: bool,
+	/// How times in the event's log are shown to users who haven't chosen how they want them shown
+	pub time_display: TimeDisplay,
+}
+
+/// Where an event is in its schedule
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EventStatus {
+	/// The event is happening now
+	Live,
+	/// The event hasn't started yet
+	Upcoming,
+	/// The event is over
+	Archived,
+}
+
+impl EventStatus {
+	/// Gets all statuses in the order events are grouped by them
+	pub fn all() -> [Self; 3] {
+		[Self::Live, Self::Upcoming, Self::Archived]
+	}
+
+	/// Gets the name of the status shown to users
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Live => "Live",
+			Self::Upcoming => "Upcoming",
+			Self::Archived => "Past",
+		}
+	}
+}
+
+impl Event {
+	/// Gets the status of the event at the given time. Archived events are always over, and events without a scheduled
+	/// end time are considered over a fixed period after they start.
+	pub fn status(&self, now: DateTime<Utc>) -> EventStatus {
+		if self.archived {
+			return EventStatus::Archived;
+		}
+		if now < self.start_time {
+			return EventStatus::Upcoming;
+		}
+		let end_time = self
+			.end_time
+			.unwrap_or_else(|| self.start_time + TimeDelta::days(UNSCHEDULED_EVENT_LIVE_DAYS));
+		if now < end_time {
+			EventStatus::Live
+		} else {
+			EventStatus::Archived
+		}
+	}
+
+	/// Checks a description against the event's description length limit. Leading and trailing whitespace is trimmed
+	/// before counting, since it's trimmed from descriptions when they're saved. Returns a message describing the
+	/// problem if the description is too long.
+	pub fn check_description_length(&self, description: &str) -> Result<(), String> {
+		let Some(max_length) = self.description_max_length else {
+			return Ok(());
+		};
+		let length = description_length(description);
+		if length > max_length as usize {
+			return Err(format!(
+				"Descriptions may be at most {} characters after trimming leading and trailing whitespace (this one is {}).",
+				max_length, length
+			));
+		}
+		Ok(())
+	}
+}
+
+/// Gets the length of a description as counted for description length limits
+pub fn description_length(description: &str) -> usize {
+	description.trim().chars().count()
 }
@@ -0,0 +1,23 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The longest feedback message that can be sent, in characters
+pub const MAX_FEEDBACK_LENGTH: usize = 500;
+
+/// How long a user must wait after sending feedback for an event before sending more feedback for that event
+pub const FEEDBACK_INTERVAL_SECONDS: i64 = 60;
+
+/// A short message about an event sent by someone working on it. Feedback is anonymous: who sent it isn't recorded.
+/// Only supervisors for the event can see the feedback.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventFeedback {
+	pub id: String,
+	pub message: String,
+	pub submitted_at: DateTime<Utc>,
+}
@@ -0,0 +1,135 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Actions that can be performed with a hotkey
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum HotkeyAction {
+	/// Sets the start time of the entry being edited to the current time
+	StartTimeNow,
+	/// Sets the end time of the entry being edited to the current time
+	EndTimeNow,
+	/// Toggles whether the entry being edited is missing giveaway information
+	ToggleMissingGiveawayInfo,
+}
+
+impl HotkeyAction {
+	/// Gets all hotkey actions in the order they should be shown to users
+	pub fn all() -> [Self; 3] {
+		[Self::StartTimeNow, Self::EndTimeNow, Self::ToggleMissingGiveawayInfo]
+	}
+
+	/// Gets the description of the action shown to users
+	pub fn description(&self) -> &'static str {
+		match self {
+			Self::StartTimeNow => "Set start time to now",
+			Self::EndTimeNow => "Set end time to now",
+			Self::ToggleMissingGiveawayInfo => "Toggle missing giveaway information",
+		}
+	}
+
+	/// Gets the hotkey used for the action when the user hasn't chosen one
+	pub fn default_hotkey(&self) -> Hotkey {
+		let code = match self {
+			Self::StartTimeNow => "KeyS",
+			Self::EndTimeNow => "KeyE",
+			Self::ToggleMissingGiveawayInfo => "KeyI",
+		};
+		Hotkey {
+			code: String::from(code),
+			alt: true,
+			ctrl: false,
+			shift: false,
+		}
+	}
+}
+
+/// A key combination. Keys are identified by their physical position on the keyboard (the `code` of a browser keyboard
+/// event) rather than the character they produce, since the character depends on the keyboard layout and can change
+/// when modifier keys are held.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Hotkey {
+	pub code: String,
+	pub alt: bool,
+	pub ctrl: bool,
+	pub shift: bool,
+}
+
+impl Hotkey {
+	/// Checks whether a key press matches the hotkey. Key presses with the meta key held never match, as the meta key
+	/// is generally reserved for the operating system.
+	pub fn matches(&self, code: &str, alt: bool, ctrl: bool, shift: bool, meta: bool) -> bool {
+		!meta && self.code == code && self.alt == alt && self.ctrl == ctrl && self.shift == shift
+	}
+
+	/// Checks whether the hotkey can be used. Hotkeys need Alt or Ctrl so that they don't interfere with typing.
+	pub fn is_usable(&self) -> bool {
+		!self.code.is_empty() && (self.alt || self.ctrl)
+	}
+}
+
+impl fmt::Display for Hotkey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.ctrl {
+			write!(f, "Ctrl+")?;
+		}
+		if self.alt {
+			write!(f, "Alt+")?;
+		}
+		if self.shift {
+			write!(f, "Shift+")?;
+		}
+		let key = self
+			.code
+			.strip_prefix("Key")
+			.or_else(|| self.code.strip_prefix("Digit"))
+			.unwrap_or(&self.code);
+		write!(f, "{}", key)
+	}
+}
+
+/// A hotkey the user chose for an action
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HotkeyBinding {
+	pub action: HotkeyAction,
+	pub hotkey: Hotkey,
+}
+
+/// Gets the hotkey for every action, using the user's chosen hotkeys where they've set them and the default hotkey
+/// otherwise
+pub fn effective_hotkeys(bindings: &[HotkeyBinding]) -> HashMap<HotkeyAction, Hotkey> {
+	let mut hotkeys: HashMap<HotkeyAction, Hotkey> = HotkeyAction::all()
+		.into_iter()
+		.map(|action| (action, action.default_hotkey()))
+		.collect();
+	for binding in bindings.iter() {
+		hotkeys.insert(binding.action, binding.hotkey.clone());
+	}
+	hotkeys
+}
+
+/// Finds groups of actions that are assigned the same hotkey. Each group is sorted, and the groups are sorted by their
+/// first action.
+pub fn hotkey_conflicts(hotkeys: &HashMap<HotkeyAction, Hotkey>) -> Vec<Vec<HotkeyAction>> {
+	let mut actions_by_hotkey: HashMap<&Hotkey, Vec<HotkeyAction>> = HashMap::new();
+	for (action, hotkey) in hotkeys.iter() {
+		actions_by_hotkey.entry(hotkey).or_default().push(*action);
+	}
+
+	let mut conflicts: Vec<Vec<HotkeyAction>> = actions_by_hotkey
+		.into_values()
+		.filter(|actions| actions.len() > 1)
+		.map(|mut actions| {
+			actions.sort();
+			actions
+		})
+		.collect();
+	conflicts.sort();
+	conflicts
+}
@@ -5,6 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::events::Event;
+use super::permissions::PermissionLevel;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -13,4 +14,17 @@ pub struct InfoPage {
 	pub event: Event,
 	pub title: String,
 	pub contents: String,
+	/// The lowest permission level for the event that a user needs to see the page. If not set, everyone who can see
+	/// the event can see the page.
+	pub minimum_permission: Option<PermissionLevel>,
+}
+
+impl InfoPage {
+	/// Checks whether a user with the given permission level for the page's event can see the page
+	pub fn is_visible_to(&self, permission: PermissionLevel) -> bool {
+		match self.minimum_permission {
+			Some(minimum_permission) => permission.is_at_least(minimum_permission),
+			None => true,
+		}
+	}
 }
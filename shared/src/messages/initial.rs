@@ -5,29 +5,72 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::events::Event;
+use super::hotkeys::HotkeyBinding;
 use super::user::SelfUserData;
-use crate::SYNC_VERSION;
+use super::view_preferences::FilterPreset;
+use crate::{MINIMUM_CLIENT_SYNC_VERSION, SYNC_VERSION};
 use serde::{Deserialize, Serialize};
 
+/// The query parameter of the WebSocket URL with which clients send their [SYNC_VERSION] to the server
+pub const CLIENT_SYNC_VERSION_PARAMETER: &str = "sync_version";
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct InitialMessage {
 	pub sync_version: u32,
+	/// The oldest client sync version the server can still communicate with
+	pub minimum_client_sync_version: u32,
 	pub user_data: UserDataLoad,
+	/// The compression the server uses for large messages sent after this one. Compressed messages are sent as
+	/// binary messages; all other messages (including this one) are sent as JSON text.
+	pub compression: Option<MessageCompression>,
 }
 
 impl InitialMessage {
 	pub fn new(user_data: UserDataLoad) -> Self {
 		Self {
 			sync_version: SYNC_VERSION,
+			minimum_client_sync_version: MINIMUM_CLIENT_SYNC_VERSION,
 			user_data,
+			compression: Some(MessageCompression::Deflate),
+		}
+	}
+
+	/// Determines whether a client built with this crate's [SYNC_VERSION] can keep communicating with the server that
+	/// sent this message
+	pub fn client_compatibility(&self) -> SyncCompatibility {
+		if self.sync_version == SYNC_VERSION {
+			SyncCompatibility::Current
+		} else if self.minimum_client_sync_version <= SYNC_VERSION && SYNC_VERSION < self.sync_version {
+			SyncCompatibility::UpdateAvailable
+		} else {
+			SyncCompatibility::UpdateRequired
 		}
 	}
 }
 
+/// How well the client's sync version matches the server's
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SyncCompatibility {
+	#[default]
+	Current,
+	/// The server is newer than the client but still supports it
+	UpdateAvailable,
+	/// The client can't communicate with the server and needs to be reloaded
+	UpdateRequired,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MessageCompression {
+	/// The message is JSON compressed with raw DEFLATE
+	Deflate,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub enum UserDataLoad {
-	User(SelfUserData, Vec<Event>),
+	User(SelfUserData, Vec<Event>, Vec<HotkeyBinding>, Vec<FilterPreset>),
 	NewUser,
 	MissingId,
+	/// The user's account was deactivated by an administrator
+	Deactivated,
 	Error,
 }
@@ -0,0 +1,33 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+/// A point in the history of a list the server keeps clients in sync with. Version numbers are only meaningful along
+/// with the history ID, which changes whenever the server starts.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ListVersion {
+	pub history_id: String,
+	pub number: u64,
+}
+
+/// A change to a synced list. Each change to a list has a version number one higher than the change before it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ListPatch<C> {
+	/// The version number of the list after this change
+	pub version: u64,
+	pub change: C,
+}
+
+/// The data sent when a client subscribes to a synced list
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ListSyncLoad<T, C> {
+	/// The whole list as of the given version
+	Snapshot(ListVersion, Vec<T>),
+	/// The changes made since the version the client already has, in order. This is sent instead of a snapshot when
+	/// the client subscribes with a version that the server still has all the following changes for.
+	Patches(Vec<ListPatch<C>>),
+}
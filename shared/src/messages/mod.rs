@@ -8,23 +8,45 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 pub mod admin;
+pub mod broadcasts;
+pub mod custom_fields;
+pub mod editor_workload;
+pub mod entry_templates;
 pub mod entry_types;
 pub mod event_log;
+pub mod event_log_import;
 pub mod event_subscription;
 pub mod events;
+pub mod feedback;
+pub mod hotkeys;
 pub mod info_pages;
 pub mod initial;
+pub mod list_sync;
+pub mod notifications;
 pub mod permissions;
+pub mod recurring_entries;
 pub mod subscriptions;
 pub mod tags;
+pub mod time_display;
 pub mod user;
 pub mod user_register;
+pub mod view_preferences;
 
+use event_log::EventLogEntryRevision;
+use event_log_import::EventLogImportResult;
+use event_subscription::EventSubscriptionUpdate;
+use events::Event;
+use hotkeys::HotkeyBinding;
+use list_sync::ListVersion;
+use notifications::NotificationPreferences;
 use subscriptions::{
-	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionTargetUpdate, SubscriptionType,
+	InitialSubscriptionLoadData, SubscriptionData, SubscriptionFailureInfo, SubscriptionSequence,
+	SubscriptionTargetUpdate, SubscriptionType,
 };
-use user::UpdateUser;
+use time_display::TimeDisplay;
+use user::{PublicUserData, UpdateUser, UserSession, UserSubscriptionUpdate};
 use user_register::{RegistrationResponse, UserRegistration};
+use view_preferences::FilterPresetUpdate;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum DataError {
@@ -41,20 +63,167 @@ impl fmt::Display for DataError {
 	}
 }
 
+/// Describes why the server couldn't complete an update requested by the client
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RequestFailure {
+	/// The update failed for a reason that may be temporary, so sending it again later may succeed
+	Temporary,
+	/// The user isn't allowed to make the update
+	NotAllowed,
+	/// The update contained data the server can't accept; contains a description of the problem
+	InvalidData(String),
+	/// The server failed to process the update
+	ServerError,
+	/// The server is running in read-only mode, so it doesn't accept any changes
+	ReadOnly,
+	/// The client sent messages too quickly, so some of them were dropped
+	RateLimited,
+	/// The connection is impersonating another user, so it can't make changes
+	Impersonating,
+}
+
+impl fmt::Display for RequestFailure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Temporary => write!(f, "The server couldn't save your change right now. Please try again."),
+			Self::NotAllowed => write!(f, "You don't have permission to make that change."),
+			Self::InvalidData(description) => write!(f, "The change couldn't be saved: {}", description),
+			Self::ServerError => write!(f, "The server failed to save your change."),
+			Self::ReadOnly => write!(f, "This server is read-only, so changes can't be made here."),
+			Self::RateLimited => write!(
+				f,
+				"You're sending changes too quickly, so some of them weren't saved. Please wait a moment and try again."
+			),
+			Self::Impersonating => write!(
+				f,
+				"You're viewing the site as another user, so changes can't be made. Reload the page to return to your own account."
+			),
+		}
+	}
+}
+
 #[derive(Clone, Deserialize, Serialize)]
 pub enum FromClientMessage {
 	StartSubscription(SubscriptionType),
+	/// Starts a subscription to a synced list that the client already has a copy of as of the given version. If the
+	/// server still has the changes made since then, it sends only those changes rather than the whole list.
+	ResumeSubscription(SubscriptionType, ListVersion),
 	EndSubscription(SubscriptionType),
 	SubscriptionMessage(Box<SubscriptionTargetUpdate>),
 	RegistrationRequest(UserRegistration),
 	UpdateProfile(UpdateUser),
+	/// Changes the user's username. Users can only change their username once every
+	/// [USERNAME_CHANGE_COOLDOWN_DAYS](user::USERNAME_CHANGE_COOLDOWN_DAYS) days.
+	UpdateUsername(String),
+	/// Sets the user's uploaded avatar to the base64-encoded image (PNG, JPEG, GIF, or WebP, no larger than
+	/// [AVATAR_SIZE_LIMIT](user::AVATAR_SIZE_LIMIT) bytes), or removes it if None
+	UpdateAvatar(Option<String>),
+	/// Replaces the user's notification and sound preferences
+	UpdateNotificationPreferences(NotificationPreferences),
+	/// Sets how the user sees times in event logs, or None to use each event's default
+	UpdateTimeDisplay(Option<TimeDisplay>),
+	/// Replaces the user's chosen hotkeys. Actions not included use their default hotkeys.
+	UpdateHotkeys(Vec<HotkeyBinding>),
+	/// Adds, changes, or removes one of the user's saved event log filter presets
+	UpdateFilterPreset(FilterPresetUpdate),
+	/// Requests access to the event with the given ID
+	RequestEventAccess(String),
+	/// Sent periodically to check that the connection is still working. The server responds with a keepalive message.
+	KeepAlive,
+	/// Requests the edit history of the log entry with the given ID
+	EventLogEntryHistory(String),
+	/// Requests the entries of an event log tab that weren't sent when subscribing to the event. Contains the event ID
+	/// and the tab ID, which is empty for the event's first tab.
+	RequestTabEntries(String, String),
+	/// Sets whether the user has completed the guided tour. Users who haven't are shown the tour when they open an
+	/// event log.
+	SetGuidedTourCompleted(bool),
+	/// Imports log entries into the event with the given ID. Contains the event ID and the contents of a CSV file with
+	/// the same columns as the event log export.
+	ImportEventLog(String, String),
+	/// Requests the user's sessions that haven't expired
+	RequestSessions,
+	/// Signs the user out of the session with the given ID
+	RevokeSession(String),
+	/// Signs the user out of every session other than the one they're connected with
+	RevokeOtherSessions,
+	/// Switches the connection to see the site as the user with the given ID would. Only administrators can do this.
+	/// Nothing can be changed for the rest of the connection.
+	StartImpersonation(String),
+}
+
+impl FromClientMessage {
+	/// Whether handling the message can change stored data. Servers in read-only mode reject these messages.
+	pub fn makes_changes(&self) -> bool {
+		match self {
+			// Typing notifications are only relayed to other users, not stored
+			Self::SubscriptionMessage(update) => !matches!(
+				update.as_ref(),
+				SubscriptionTargetUpdate::EventUpdate(_, event_update)
+					if matches!(event_update.as_ref(), EventSubscriptionUpdate::Typing(_))
+			),
+			Self::UpdateProfile(_)
+			| Self::UpdateUsername(_)
+			| Self::UpdateAvatar(_)
+			| Self::UpdateNotificationPreferences(_)
+			| Self::UpdateTimeDisplay(_)
+			| Self::UpdateHotkeys(_)
+			| Self::UpdateFilterPreset(_)
+			| Self::RequestEventAccess(_)
+			| Self::SetGuidedTourCompleted(_)
+			| Self::ImportEventLog(_, _)
+			| Self::RevokeSession(_)
+			| Self::RevokeOtherSessions
+			// Each impersonation is recorded in the audit log
+			| Self::StartImpersonation(_) => true,
+			Self::RegistrationRequest(registration) => matches!(registration, UserRegistration::Finalize(_)),
+			Self::StartSubscription(_)
+			| Self::ResumeSubscription(_, _)
+			| Self::EndSubscription(_)
+			| Self::KeepAlive
+			| Self::EventLogEntryHistory(_)
+			| Self::RequestTabEntries(_, _)
+			| Self::RequestSessions => false,
+		}
+	}
 }
 
 #[derive(Deserialize, Serialize)]
 pub enum FromServerMessage {
 	InitialSubscriptionLoad(Box<InitialSubscriptionLoadData>),
-	SubscriptionMessage(Box<SubscriptionData>),
+	/// Data for a subscription. Messages broadcast to all subscribers include a sequence number; messages sent only to
+	/// one connection do not.
+	SubscriptionMessage(Box<SubscriptionData>, Option<SubscriptionSequence>),
 	Unsubscribed(SubscriptionType),
 	SubscriptionFailure(SubscriptionType, SubscriptionFailureInfo),
+	/// Sent to the requesting client when an update it sent couldn't be completed
+	RequestFailure(RequestFailure),
+	/// Confirms that a request for access to the event with the given ID was recorded
+	EventAccessRequested(String),
 	RegistrationResponse(RegistrationResponse),
+	/// Response to a keepalive message from the client
+	KeepAlive,
+	/// The edit history of the log entry with the given ID, oldest revision first. Sent only to the user who requested it.
+	EventLogEntryHistory(String, Vec<EventLogEntryRevision>),
+	/// The outcome of an event log import. Sent only to the user who sent the file.
+	EventLogImportResult(EventLogImportResult),
+	/// The user's sessions that haven't expired, most recently active first. Sent only to the connection that
+	/// requested them or changed them.
+	UserSessions(Vec<UserSession>),
+	/// Sent to a connection when the session it was opened with is revoked. The connection is closed once everything
+	/// still queued for it has been sent.
+	SessionRevoked,
+	/// Confirms that the connection now sees the site as the contained user. Replaces the user data and available
+	/// events the client had.
+	ImpersonationStarted(UserSubscriptionUpdate),
+	/// Sent to every connection when a user's public data (like their username) changes, so that the copies of it the
+	/// client has can be updated
+	PublicUserUpdate(PublicUserData),
+	/// Sent to every connection when the server is shutting down (usually to restart). The connection is closed once
+	/// everything still queued for it has been sent.
+	ServerShuttingDown,
+	/// Sent to a connection when the user's permission level for the contained event changes. What the user can see in
+	/// the event (like restricted info pages) depends on their permission level, so clients subscribed to the event
+	/// should load it again.
+	EventPermissionChanged(Event),
 }
@@ -0,0 +1,110 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log::VideoProcessingState;
+use super::events::Event;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A notice sent to a user about something relevant to them that happened elsewhere
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UserNotification {
+	pub id: String,
+	pub time: DateTime<Utc>,
+	pub event: Event,
+	pub details: UserNotificationDetails,
+	/// Whether the user has marked the notification as read
+	pub read: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum UserNotificationDetails {
+	/// The video processing state changed for an entry for which the user is the editor
+	VideoProcessingStateChanged {
+		entry_id: String,
+		entry_description: String,
+		state: VideoProcessingState,
+	},
+	/// The user was made the editor of an entry
+	EditorAssigned {
+		entry_id: String,
+		entry_description: String,
+	},
+	/// The user was given access to the event
+	AddedToEvent,
+	/// An entry the user created was marked as missing giveaway information
+	MissingGiveawayInformation {
+		entry_id: String,
+		entry_description: String,
+	},
+}
+
+impl std::fmt::Display for UserNotification {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.details {
+			UserNotificationDetails::VideoProcessingStateChanged {
+				entry_description,
+				state,
+				..
+			} => write!(
+				f,
+				"{}: The video for \"{}\" is now {}",
+				self.event.name, entry_description, state
+			),
+			UserNotificationDetails::EditorAssigned { entry_description, .. } => write!(
+				f,
+				"{}: You were made the editor for \"{}\"",
+				self.event.name, entry_description
+			),
+			UserNotificationDetails::AddedToEvent => write!(f, "You now have access to {}", self.event.name),
+			UserNotificationDetails::MissingGiveawayInformation { entry_description, .. } => write!(
+				f,
+				"{}: Your entry \"{}\" is missing giveaway information",
+				self.event.name, entry_description
+			),
+		}
+	}
+}
+
+/// How a user wants to be alerted to things that happen while they have Stream Log open. Sounds are played and desktop
+/// notifications are shown by the browser, so they only work while the user has a page open.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NotificationPreferences {
+	/// Play a sound when the user is made the editor of an entry
+	pub editor_assigned_sound: bool,
+	/// Show a desktop notification when the user is made the editor of an entry
+	pub editor_assigned_desktop: bool,
+	/// Play a sound when someone else starts typing in an entry for which the user is the editor
+	pub typing_in_edited_entry_sound: bool,
+	/// Show a desktop notification when someone else starts typing in an entry for which the user is the editor
+	pub typing_in_edited_entry_desktop: bool,
+	/// Play a sound for any other notification the user receives
+	pub other_notifications_sound: bool,
+	/// Show a desktop notification for any other notification the user receives
+	pub other_notifications_desktop: bool,
+}
+
+impl NotificationPreferences {
+	/// Whether any of the preferences need desktop notifications to be allowed by the browser
+	pub fn uses_desktop_notifications(&self) -> bool {
+		self.editor_assigned_desktop || self.typing_in_edited_entry_desktop || self.other_notifications_desktop
+	}
+}
+
+/// Data sent to a user's notification subscription
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum UserNotificationData {
+	NewNotification(Box<UserNotification>),
+	/// The notifications with the given IDs were marked as read
+	MarkedRead(Vec<String>),
+}
+
+/// Changes a user can make to their notifications
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum UserNotificationUpdate {
+	MarkRead(Vec<String>),
+	MarkAllRead,
+}
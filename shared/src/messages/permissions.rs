@@ -6,10 +6,11 @@
 
 use serde::{Deserialize, Serialize};
 
-/// Permission level available for sending over the socket
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+/// Permission level available for sending over the socket. Levels are ordered from least to most access.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum PermissionLevel {
 	View,
+	Auditor,
 	Edit,
 	Supervisor,
 }
@@ -18,4 +19,15 @@ impl PermissionLevel {
 	pub fn can_edit(&self) -> bool {
 		matches!(self, Self::Supervisor | Self::Edit)
 	}
+
+	/// Checks whether the permission level allows seeing the video editor's view of the event without being a video
+	/// editor for the event
+	pub fn can_see_editor_view(&self) -> bool {
+		matches!(self, Self::Supervisor | Self::Auditor)
+	}
+
+	/// Checks whether this permission level is the same as or higher than the given level
+	pub fn is_at_least(&self, level: PermissionLevel) -> bool {
+		*self >= level
+	}
 }
@@ -0,0 +1,25 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An entry that's added to an event's log automatically at a regular interval, for segments that come up on a schedule.
+/// Recurring entries are managed by the event's supervisors.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecurringEntry {
+	pub id: String,
+	/// The ID of the entry type given to the created entries, if they have one
+	pub entry_type: Option<String>,
+	/// The description given to the created entries
+	pub description: String,
+	/// How often an entry is created
+	pub interval_minutes: i32,
+	/// The start time of the first created entry
+	pub first_time: DateTime<Utc>,
+	/// No entries are created to start after this time
+	pub last_time: DateTime<Utc>,
+}
@@ -5,21 +5,35 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::messages::admin::{
-	AdminApplicationData, AdminApplicationUpdate, AdminEntryTypeData, AdminEntryTypeEventData,
-	AdminEntryTypeEventUpdate, AdminEntryTypeUpdate, AdminEventData, AdminEventEditorData, AdminEventEditorUpdate,
-	AdminEventLogTabsData, AdminEventLogTabsUpdate, AdminEventUpdate, AdminInfoPageData, AdminInfoPageUpdate,
-	AdminPermissionGroupData, AdminPermissionGroupUpdate, AdminUserPermissionGroupData, AdminUserPermissionGroupUpdate,
-	Application, EditorEventAssociation, EntryTypeEventAssociation, PermissionGroup, PermissionGroupEventAssociation,
+	AdminApplicationData, AdminApplicationUpdate, AdminAuditLogData, AdminAuditLogEntry, AdminEntryTypeData,
+	AdminEntryTypeEventData, AdminEntryTypeEventUpdate, AdminEntryTypeUpdate, AdminEventAccessRequestData,
+	AdminEventAccessRequestUpdate, AdminEventCustomFieldData, AdminEventCustomFieldUpdate, AdminEventData,
+	AdminEventEditorData, AdminEventEditorUpdate, AdminEventLogTabsData, AdminEventLogTabsUpdate,
+	AdminEventShareLinkData, AdminEventShareLinkUpdate, AdminEventUpdate, AdminEventWebhookData,
+	AdminEventWebhookUpdate, AdminInfoPageData, AdminInfoPageUpdate, AdminJobData, AdminPermissionGroupData,
+	AdminPermissionGroupUpdate, AdminRejectedUpdateData, AdminUserEventPermissionData, AdminUserEventPermissionUpdate,
+	AdminUserPermissionGroupData, AdminUserPermissionGroupUpdate, Application, EditorEventAssociation,
+	EntryTypeEventAssociation, EventAccessRequest, EventCustomField, EventShareLink, EventTemplate, EventWebhook, Job,
+	PermissionGroup, PermissionGroupEventAssociation, RejectedEventUpdate, UserEventPermission,
 	UserPermissionGroupAssociation,
 };
+use crate::messages::custom_fields::CustomField;
+use crate::messages::editor_workload::EditorWorkload;
+use crate::messages::entry_templates::EntryTemplate;
 use crate::messages::entry_types::EntryType;
 use crate::messages::event_log::{EventLogEntry, EventLogTab};
-use crate::messages::event_subscription::{EventSubscriptionData, EventSubscriptionUpdate};
+use crate::messages::event_subscription::{EntryEditLock, EventSubscriptionData, EventSubscriptionUpdate};
 use crate::messages::events::Event;
+use crate::messages::feedback::EventFeedback;
+use crate::messages::hotkeys::HotkeyBinding;
 use crate::messages::info_pages::InfoPage;
+use crate::messages::list_sync::{ListPatch, ListSyncLoad};
+use crate::messages::notifications::{UserNotification, UserNotificationData, UserNotificationUpdate};
 use crate::messages::permissions::PermissionLevel;
-use crate::messages::tags::Tag;
-use crate::messages::user::{PublicUserData, SelfUserData, UserSubscriptionUpdate};
+use crate::messages::recurring_entries::RecurringEntry;
+use crate::messages::tags::{PendingTagAction, Tag, VideoEditStateTagRule};
+use crate::messages::user::{PublicUserData, SelfUserData, UpdateUser, UserSubscriptionUpdate};
+use crate::messages::view_preferences::FilterPreset;
 use crate::messages::DataError;
 use serde::{Deserialize, Serialize};
 
@@ -36,18 +50,45 @@ pub enum SubscriptionType {
 	AdminPermissionGroups,
 	/// A subscription to relationships between permission groups and users.
 	AdminPermissionGroupUsers,
+	/// A subscription to the permissions granted to users for events directly rather than through permission groups.
+	AdminUserEventPermissions,
 	/// A subscription to all entry types.
 	AdminEntryTypes,
 	/// A subscription to relationships between entry types and events.
 	AdminEntryTypesEvents,
 	/// A subscription to relationships between users (as video editors) and events.
 	AdminEventEditors,
+	/// A subscription to the video editors of a single event. An event ID is provided with this variant. Unlike the
+	/// other admin subscriptions, this is also available to the event's supervisors.
+	AdminEventEditorsForEvent(String),
 	/// A subscription to event log tabs.
 	AdminEventLogTabs,
+	/// A subscription to the event log tabs of a single event. An event ID is provided with this variant. Unlike the
+	/// other admin subscriptions, this is also available to the event's supervisors.
+	AdminEventLogTabsForEvent(String),
 	/// A subscription to all applications.
 	AdminApplications,
 	/// A subscription to all info pages.
 	AdminInfoPages,
+	/// A subscription to pending requests for event access.
+	AdminEventAccessRequests,
+	/// A subscription to the webhooks configured for events.
+	AdminEventWebhooks,
+	/// A subscription to the public share links for events.
+	AdminEventShareLinks,
+	/// A subscription to the custom entry fields of all events.
+	AdminEventCustomFields,
+	/// A subscription to event changes the server refused to make.
+	AdminRejectedUpdates,
+	/// A subscription to the record of changes administrators made.
+	AdminAuditLog,
+	/// A subscription to the status of the server's background jobs.
+	AdminJobs,
+	/// A subscription to the logged-in user's notifications.
+	UserNotifications,
+	/// A subscription to the number of entries assigned to each editor of an event. An event ID is provided with this
+	/// variant.
+	EditorWorkload(String),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -60,14 +101,38 @@ pub struct InitialEventSubscriptionLoadData {
 	pub entry_types: Vec<EntryType>,
 	/// The tags that can be used for the event
 	pub tags: Vec<Tag>,
+	/// The rules for automatically setting the video edit state based on tags
+	pub video_edit_state_rules: Vec<VideoEditStateTagRule>,
+	/// Tag changes waiting for approval by a second supervisor
+	pub pending_tag_actions: Vec<PendingTagAction>,
+	/// Feedback sent for the event that hasn't been dismissed. Only supervisors receive feedback.
+	pub feedback: Vec<EventFeedback>,
 	/// The list of users that can be entered as editors
 	pub editors: Vec<PublicUserData>,
+	/// The users currently viewing the event, including the user subscribing
+	pub viewers: Vec<PublicUserData>,
+	/// The entries currently being edited and who's editing them
+	pub entry_locks: Vec<EntryEditLock>,
 	/// The list of info pages that can be read for this event
 	pub info_pages: Vec<InfoPage>,
 	/// The event log tabs
 	pub tabs: Vec<EventLogTab>,
-	/// The event log entries that have already been created
+	/// The templates that can be used to fill in entries
+	pub entry_templates: Vec<EntryTemplate>,
+	/// The entries added to the log automatically on a schedule
+	pub recurring_entries: Vec<RecurringEntry>,
+	/// The extra fields the event's entries have, sorted by name
+	pub custom_fields: Vec<CustomField>,
+	/// The event log entries that have already been created. For events with many entries, this only contains the
+	/// first entries, and the rest are sent afterward in [EventSubscriptionData::InitialLogEntryChunk] messages.
+	/// Entries in the tabs listed in `unloaded_tab_ids` aren't included.
 	pub entries: Vec<EventLogEntry>,
+	/// The number of entries that will be sent in later chunks
+	pub pending_entry_count: usize,
+	/// The IDs of tabs whose entries aren't sent with the subscription. Events with many entries only have the entries
+	/// in their most recent tabs sent, and the entries of earlier tabs can be requested as they're needed. The event's
+	/// first tab is represented by an empty ID.
+	pub unloaded_tab_ids: Vec<String>,
 	/// Placeholder data for new entries that haven't yet been created
 	pub new_entries: Vec<EventLogEntry>,
 }
@@ -85,16 +150,32 @@ pub enum InitialSubscriptionLoadData {
 	/// - The event log section headers
 	/// - The event log entries that have already been created
 	Event(Box<InitialEventSubscriptionLoadData>),
-	AdminUsers(Vec<SelfUserData>),
-	AdminEvents(Vec<Event>),
+	AdminUsers(ListSyncLoad<SelfUserData, SelfUserData>),
+	AdminEvents(ListSyncLoad<Event, AdminEventData>),
 	AdminPermissionGroups(Vec<PermissionGroup>, Vec<PermissionGroupEventAssociation>),
 	AdminPermissionGroupUsers(Vec<UserPermissionGroupAssociation>),
+	AdminUserEventPermissions(Vec<UserEventPermission>),
 	AdminEntryTypes(Vec<EntryType>),
 	AdminEntryTypesEvents(Vec<EntryTypeEventAssociation>),
 	AdminEventEditors(Vec<EditorEventAssociation>),
-	AdminEventLogTabs(Vec<(Event, EventLogTab)>),
+	/// Data for subscribing to the editors of a single event. Includes the event's editors and the users who have
+	/// access to the event (and so can be made editors).
+	AdminEventEditorsForEvent(Event, Vec<EditorEventAssociation>, Vec<PublicUserData>),
+	AdminEventLogTabs(ListSyncLoad<(Event, EventLogTab), AdminEventLogTabsData>),
+	AdminEventLogTabsForEvent(Event, Vec<EventLogTab>),
 	AdminApplications(Vec<Application>),
 	AdminInfoPages(Vec<InfoPage>),
+	AdminEventAccessRequests(Vec<EventAccessRequest>),
+	AdminEventWebhooks(Vec<EventWebhook>),
+	AdminEventShareLinks(Vec<EventShareLink>),
+	AdminEventCustomFields(Vec<EventCustomField>),
+	AdminRejectedUpdates(Vec<RejectedEventUpdate>),
+	AdminAuditLog(Vec<AdminAuditLogEntry>),
+	/// The jobs the server is running or waiting to run along with recently finished jobs
+	AdminJobs(Vec<Job>),
+	/// The user's most recent notifications, most recent first
+	UserNotifications(Vec<UserNotification>),
+	EditorWorkload(Event, Vec<EditorWorkload>),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -102,16 +183,47 @@ pub enum SubscriptionData {
 	EventUpdate(Event, Box<EventSubscriptionData>),
 	/// Indicates an update to data related to the logged-in user.
 	UserUpdate(UserSubscriptionUpdate),
-	AdminEventsUpdate(AdminEventData),
+	/// Indicates that the logged-in user changed their hotkeys. Contains all of the user's chosen hotkeys.
+	UserHotkeysUpdate(Vec<HotkeyBinding>),
+	/// Indicates that the logged-in user changed their saved filter presets. Contains all of the user's presets.
+	UserFilterPresetsUpdate(Vec<FilterPreset>),
+	UserNotificationsUpdate(UserNotificationData),
+	AdminEventsUpdate(ListPatch<AdminEventData>),
+	/// A configuration template for the event. Sent only to the admin who requested it.
+	AdminEventTemplate(Event, EventTemplate),
+	/// The event was archived and purged. Contains the name of the archive file. Sent only to the admin who requested
+	/// it.
+	AdminEventArchived(Event, String),
 	AdminEntryTypesUpdate(AdminEntryTypeData),
 	AdminEntryTypesEventsUpdate(AdminEntryTypeEventData),
 	AdminPermissionGroupsUpdate(AdminPermissionGroupData),
-	AdminUsersUpdate(SelfUserData),
+	AdminUsersUpdate(ListPatch<SelfUserData>),
 	AdminEventEditorsUpdate(AdminEventEditorData),
 	AdminUserPermissionGroupsUpdate(AdminUserPermissionGroupData),
-	AdminEventLogTabsUpdate(AdminEventLogTabsData),
+	AdminUserEventPermissionsUpdate(AdminUserEventPermissionData),
+	/// A change to an event's tabs. Connections subscribed to the tabs of only that event receive the same patches, so
+	/// the version numbers they see skip the changes to other events' tabs.
+	AdminEventLogTabsUpdate(ListPatch<AdminEventLogTabsData>),
 	AdminApplicationsUpdate(AdminApplicationData),
 	AdminInfoPagesUpdate(AdminInfoPageData),
+	AdminEventAccessRequestsUpdate(AdminEventAccessRequestData),
+	AdminEventWebhooksUpdate(AdminEventWebhookData),
+	AdminEventShareLinksUpdate(AdminEventShareLinkData),
+	AdminEventCustomFieldsUpdate(AdminEventCustomFieldData),
+	AdminRejectedUpdatesUpdate(AdminRejectedUpdateData),
+	AdminAuditLogUpdate(AdminAuditLogData),
+	AdminJobsUpdate(AdminJobData),
+	/// The editor workloads for an event changed. Contains the full set of workloads for the event.
+	EditorWorkloadUpdate(Event, Vec<EditorWorkload>),
+}
+
+/// The position of a broadcast message within the stream of messages for a subscription
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SubscriptionSequence {
+	pub subscription_type: SubscriptionType,
+	/// The first message after the initial subscription load is numbered 1, and each following message for the
+	/// subscription is numbered one higher than the last. A gap indicates that messages were missed.
+	pub number: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -130,9 +242,19 @@ pub enum SubscriptionTargetUpdate {
 	AdminEntryTypesEventsUpdate(AdminEntryTypeEventUpdate),
 	AdminPermissionGroupsUpdate(AdminPermissionGroupUpdate),
 	AdminUserUpdate(SelfUserData),
+	/// Changes the profile settings of the given user on their behalf
+	AdminUserProfileUpdate(SelfUserData, UpdateUser),
+	/// Signs the given user out of all of their sessions
+	AdminUserSessionsRevoke(SelfUserData),
 	AdminEventEditorsUpdate(AdminEventEditorUpdate),
 	AdminUserPermissionGroupsUpdate(AdminUserPermissionGroupUpdate),
+	AdminUserEventPermissionsUpdate(AdminUserEventPermissionUpdate),
 	AdminEventLogTabsUpdate(AdminEventLogTabsUpdate),
 	AdminApplicationsUpdate(AdminApplicationUpdate),
 	AdminInfoPagesUpdate(AdminInfoPageUpdate),
+	AdminEventAccessRequestsUpdate(AdminEventAccessRequestUpdate),
+	AdminEventWebhooksUpdate(AdminEventWebhookUpdate),
+	AdminEventShareLinksUpdate(AdminEventShareLinkUpdate),
+	AdminEventCustomFieldsUpdate(AdminEventCustomFieldUpdate),
+	UserNotificationsUpdate(UserNotificationUpdate),
 }
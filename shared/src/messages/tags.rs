@@ -4,6 +4,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::event_log::VideoEditState;
+use super::user::PublicUserData;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -12,6 +15,25 @@ pub struct Tag {
 	pub name: String,
 	pub description: String,
 	pub playlist: Option<TagPlaylist>,
+	/// The first day (in UTC) on which the tag is offered for new entries, for tags that are only relevant on some days
+	#[serde(default)]
+	pub valid_from: Option<NaiveDate>,
+	/// The last day (in UTC) on which the tag is offered for new entries
+	#[serde(default)]
+	pub valid_to: Option<NaiveDate>,
+}
+
+impl Tag {
+	/// Whether the tag is offered for new entries on the given day. Tags outside their valid dates stay on the entries
+	/// that already have them.
+	pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+		self.valid_from.is_none_or(|valid_from| valid_from <= date) && !self.is_expired_on(date)
+	}
+
+	/// Whether the tag's valid dates have passed as of the given day
+	pub fn is_expired_on(&self, date: NaiveDate) -> bool {
+		self.valid_to.is_some_and(|valid_to| valid_to < date)
+	}
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -20,3 +42,26 @@ pub struct TagPlaylist {
 	pub title: String,
 	pub shows_in_video_descriptions: bool,
 }
+
+/// A rule setting the video edit state of entries to which a particular tag is added
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VideoEditStateTagRule {
+	/// The tag that triggers the rule
+	pub tag: Tag,
+	/// The video edit state entries get when the tag is added
+	pub video_edit_state: VideoEditState,
+}
+
+/// A tag removal or replacement waiting for approval by another supervisor. Events can require destructive tag changes
+/// to be confirmed by a second supervisor before they're made.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PendingTagAction {
+	pub id: String,
+	/// The tag to be removed
+	pub tag: Tag,
+	/// If set, the tag is replaced with this tag on all entries when it's removed
+	pub replacement_tag: Option<Tag>,
+	/// The supervisor who requested the change. The same supervisor can't approve it.
+	pub requested_by: PublicUserData,
+	pub requested_at: DateTime<Utc>,
+}
@@ -0,0 +1,161 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// The format in which wall-clock times are shown. Times entered in this format are also accepted.
+const WALL_CLOCK_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// How times in an event's log are shown and entered
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum TimeDisplay {
+	/// Times are shown as hours and minutes since the start of the event
+	#[default]
+	EventRelative,
+	/// Times are shown as the date and time of day in the time zone with the given IANA name (such as
+	/// "America/New_York")
+	WallClock(String),
+}
+
+impl TimeDisplay {
+	/// Gets the time display for a stored time zone name. No time zone means times are shown relative to the event.
+	pub fn from_time_zone(time_zone: Option<String>) -> Self {
+		match time_zone {
+			Some(time_zone) => Self::WallClock(time_zone),
+			None => Self::EventRelative,
+		}
+	}
+
+	/// Gets the name of the time zone in which times are shown, if times are shown as wall-clock times
+	pub fn time_zone_name(&self) -> Option<&str> {
+		match self {
+			Self::EventRelative => None,
+			Self::WallClock(time_zone) => Some(time_zone),
+		}
+	}
+
+	/// Checks that times can be shown this way. If they can't, returns a message suitable for display to the user.
+	pub fn validate(&self) -> Result<(), String> {
+		match self {
+			Self::EventRelative => Ok(()),
+			Self::WallClock(time_zone) => parse_time_zone(time_zone).map(|_| ()),
+		}
+	}
+
+	/// Formats a time in an event's log for display
+	pub fn format_time(&self, time: DateTime<Utc>, event_start: DateTime<Utc>) -> String {
+		match self {
+			Self::EventRelative => format_duration(&(time - event_start)),
+			Self::WallClock(time_zone) => match parse_time_zone(time_zone) {
+				Ok(time_zone) => time.with_timezone(&time_zone).format(WALL_CLOCK_FORMAT).to_string(),
+				Err(_) => time.format(WALL_CLOCK_FORMAT).to_string(),
+			},
+		}
+	}
+
+	/// Parses a time entered by the user for an event's log. Wall-clock times can be entered either with their date or
+	/// as only a time of day, in which case the closest such time to `reference_time` is used. If parsing fails,
+	/// returns a string suitable for display to the user who entered the value.
+	pub fn parse_time(
+		&self,
+		value: &str,
+		event_start: DateTime<Utc>,
+		reference_time: DateTime<Utc>,
+	) -> Result<DateTime<Utc>, String> {
+		let time_zone = match self {
+			Self::EventRelative => return get_duration_from_formatted(value).map(|duration| event_start + duration),
+			Self::WallClock(time_zone) => parse_time_zone(time_zone)?,
+		};
+		let value = value.trim();
+
+		let date_time = NaiveDateTime::parse_from_str(value, WALL_CLOCK_FORMAT)
+			.or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"));
+		if let Ok(date_time) = date_time {
+			return match time_zone.from_local_datetime(&date_time).earliest() {
+				Some(time) => Ok(time.with_timezone(&Utc)),
+				None => Err(format!("That time doesn't exist in {}", time_zone.name())),
+			};
+		}
+
+		let Ok(time_of_day) = NaiveTime::parse_from_str(value, "%H:%M") else {
+			return Err(String::from("Invalid format; use YYYY-MM-DD hh:mm or hh:mm"));
+		};
+		let reference_date = reference_time.with_timezone(&time_zone).date_naive();
+		[
+			reference_date - Duration::days(1),
+			reference_date,
+			reference_date + Duration::days(1),
+		]
+		.into_iter()
+		.filter_map(|date| time_zone.from_local_datetime(&date.and_time(time_of_day)).earliest())
+		.map(|time| time.with_timezone(&Utc))
+		.min_by_key(|time| (*time - reference_time).num_seconds().abs())
+		.ok_or_else(|| format!("That time doesn't exist in {}", time_zone.name()))
+	}
+}
+
+/// Gets the names of all time zones in which times can be shown
+pub fn time_zone_names() -> impl Iterator<Item = &'static str> {
+	chrono_tz::TZ_VARIANTS.iter().map(|time_zone| time_zone.name())
+}
+
+fn parse_time_zone(time_zone: &str) -> Result<Tz, String> {
+	time_zone
+		.parse()
+		.map_err(|_| format!("\"{}\" isn't a known time zone", time_zone))
+}
+
+/// Formats a [`Duration`] object as hours:minutes
+pub fn format_duration(duration: &Duration) -> String {
+	let mut hours = duration.num_hours();
+	let mut minutes = duration.num_minutes() % 60;
+	let sign = if hours < 0 || minutes < 0 {
+		hours = hours.abs();
+		minutes = minutes.abs();
+		"-"
+	} else {
+		""
+	};
+	format!("{}{}:{:02}", sign, hours, minutes)
+}
+
+/// Parses a string formatted as hhh:mm into a [`Duration`] object. If parsing fails,
+/// returns a string suitable for display to the user who entered the value.
+pub fn get_duration_from_formatted(formatted_duration: &str) -> Result<Duration, String> {
+	let Some((hours, minutes)) = formatted_duration.split_once(':') else {
+		return Err(String::from("Invalid format"));
+	};
+
+	let is_negative = match hours.chars().next() {
+		Some(c) => c == '-',
+		None => false,
+	};
+	let hours: i64 = match hours.parse() {
+		Ok(hours) => hours,
+		Err(error) => return Err(format!("Couldn't parse hours: {}", error)),
+	};
+
+	let mut minutes: i64 = match minutes.parse() {
+		Ok(mins) => mins,
+		Err(error) => return Err(format!("Couldn't parse minutes: {}", error)),
+	};
+
+	if is_negative {
+		if hours > 0 {
+			return Err(format!(
+				"Hour parsing went wrong: detected negative duration but parsed hours as {}",
+				hours
+			));
+		}
+
+		minutes = -minutes;
+	}
+
+	let duration_minutes = hours * 60 + minutes;
+	Ok(Duration::minutes(duration_minutes))
+}
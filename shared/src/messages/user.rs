@@ -5,6 +5,9 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::events::Event;
+use super::notifications::NotificationPreferences;
+use super::time_display::TimeDisplay;
+use chrono::{DateTime, Utc};
 use rgb::RGB8;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +17,9 @@ pub struct PublicUserData {
 	pub id: String,
 	pub username: String,
 	pub color: RGB8,
+	/// When the user last changed their uploaded avatar, or None if they haven't uploaded one. Users without an uploaded
+	/// avatar are shown with an identicon generated from their ID.
+	pub avatar_updated_at: Option<DateTime<Utc>>,
 }
 
 /// User data sent to the user represented by the data, including all the settings for the user.
@@ -24,6 +30,23 @@ pub struct SelfUserData {
 	pub color: RGB8,
 	pub is_admin: bool,
 	pub use_spell_check: bool,
+	/// Whether the user has finished or dismissed the guided tour of the event log
+	pub completed_guided_tour: bool,
+	/// Whether the user is allowed to log in
+	pub enabled: bool,
+	/// When the user last changed their uploaded avatar, or None if they haven't uploaded one
+	pub avatar_updated_at: Option<DateTime<Utc>>,
+	/// How the user wants to be alerted to notifications and activity
+	pub notification_preferences: NotificationPreferences,
+	/// How the user wants times in event logs shown, or None to use each event's default
+	pub time_display: Option<TimeDisplay>,
+}
+
+impl SelfUserData {
+	/// Gets how times in the given event's log should be shown to the user
+	pub fn time_display_for_event(&self, event: &Event) -> TimeDisplay {
+		self.time_display.clone().unwrap_or_else(|| event.time_display.clone())
+	}
 }
 
 impl From<SelfUserData> for PublicUserData {
@@ -32,10 +55,17 @@ impl From<SelfUserData> for PublicUserData {
 			id: value.id,
 			username: value.username,
 			color: value.color,
+			avatar_updated_at: value.avatar_updated_at,
 		}
 	}
 }
 
+/// The number of days a user has to wait after changing their username before they can change it again
+pub const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+
+/// The largest avatar image, in bytes, that users can upload
+pub const AVATAR_SIZE_LIMIT: usize = 256 * 1024;
+
 /// Update information sent when a user updates their profile settings.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct UpdateUser {
@@ -50,3 +80,17 @@ pub struct UserSubscriptionUpdate {
 	pub user: SelfUserData,
 	pub available_events: Vec<Event>,
 }
+
+/// A login session of the user
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UserSession {
+	pub id: String,
+	/// The User-Agent header of the browser that most recently connected using the session
+	pub user_agent: String,
+	pub created_at: DateTime<Utc>,
+	pub last_active_at: DateTime<Utc>,
+	/// Whether the session has a connection open to the server
+	pub is_connected: bool,
+	/// Whether this is the session the user is connected with
+	pub is_current: bool,
+}
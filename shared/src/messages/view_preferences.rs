@@ -0,0 +1,49 @@
+// © 2022-2024 Jacob Riddle (ElementalAlchemist)
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::event_log::{VideoEditState, VideoProcessingState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// The maximum length of a filter preset name
+pub const MAX_FILTER_PRESET_NAME_LENGTH: usize = 100;
+
+/// Filters limiting which entries are shown in the event log
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogFilters {
+	/// The video edit states of entries to show. If empty, entries are shown regardless of video edit state.
+	pub video_edit_states: HashSet<VideoEditState>,
+	/// The video processing states of entries to show. If empty, entries are shown regardless of video processing
+	/// state.
+	pub video_processing_states: HashSet<VideoProcessingState>,
+	/// Whether to show only entries created by the viewing user
+	pub created_by_me: bool,
+	/// Whether to show only entries assigned to the viewing user to edit
+	pub assigned_to_me: bool,
+	/// Whether to show only entries that ran longer than expected for their entry type
+	pub over_expected_duration: bool,
+	/// Whether to show only entries marked as missing giveaway information
+	pub missing_giveaway_information: bool,
+}
+
+/// A named set of event log filters saved by a user
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FilterPreset {
+	/// The ID of the preset. When saving a new preset, this is empty.
+	pub id: String,
+	pub name: String,
+	pub filters: EventLogFilters,
+}
+
+/// A change to the current user's filter presets
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum FilterPresetUpdate {
+	/// Saves a preset. If the preset has no ID, it's added as a new preset; otherwise, the preset with that ID is
+	/// replaced.
+	Save(FilterPreset),
+	/// Deletes the preset with the given ID
+	Delete(String),
+}